@@ -8,8 +8,14 @@ use chrono::{DateTime, Utc};
 pub const MAX_LISTEN_PAYLOAD_SIZE: u64 = 10240000;
 
 /// Maximum overall listen size in bytes, to prevent egregious spamming.
-/// - <https://listenbrainz.readthedocs.io/en/latest/users/api/core.html#listenbrainz.webserver.views.api_tools.MAX_LISTEN_PAYLOAD_SIZE>
-pub const MAX_LISTEN_SIZE: u64 = 10240000;
+/// - <https://listenbrainz.readthedocs.io/en/latest/users/api/core.html#listenbrainz.webserver.views.api_tools.MAX_LISTEN_SIZE>
+pub const MAX_LISTEN_SIZE: u64 = 10240;
+
+/// The most bytes that the name of an artist, track, or release is cut down to.
+///
+/// The names don't have limits of their own, but this keeps the three of them together within [`MAX_LISTEN_SIZE`],
+/// with room left over for the rest of the listen.
+pub const MAX_NAME_SIZE: u16 = 2048;
 
 /// The max permitted value of duration field.
 /// It is currently set to 24 days.
@@ -77,24 +77,17 @@ pub mod token_validity {
 
 
 
-
-struct InternalSharedListenPack<'a> {
-    track: submit_listens::BasicTrackMetadata<'a>,
-    timestamp: Option<chrono::DateTime<chrono::Utc>>,
-    extra: Option<submit_listens::additional_info::AdditionalInfo<'a>>
-}
-
-
 // TODO: add authorization type-state like lastfm
 // TODO: ratelimit middleware?
 pub struct Client<PS: AsRef<str>> {
     net: reqwest::Client,
+    retry: http_client::Retry,
     program: musicbrainz::request_client::ProgramInfo<PS>,
     token: Option<UserToken>,
 }
 impl<PS: AsRef<str>> Client<PS> {
-    fn mk_net(program: &musicbrainz::request_client::ProgramInfo<PS>, token: Option<&UserToken>) -> reqwest::Client {
-        let mut client = reqwest::ClientBuilder::new()
+    fn mk_net(net: reqwest::ClientBuilder, program: &musicbrainz::request_client::ProgramInfo<PS>, token: Option<&UserToken>) -> reqwest::Client {
+        let mut client = net
             .pool_max_idle_per_host(0)
             .https_only(true)
             .user_agent(program.to_user_agent());
@@ -118,14 +111,22 @@ impl<PS: AsRef<str>> Client<PS> {
         &self.program
     }
 
-    pub fn new(program: musicbrainz::request_client::ProgramInfo<PS>, token: Option<UserToken>) -> Self {
+    /// The network client is built from the given builder, with the program's user agent and the token's authorization added on.
+    pub fn new(net: reqwest::ClientBuilder, program: musicbrainz::request_client::ProgramInfo<PS>, token: Option<UserToken>) -> Self {
         Self {
-            net: Self::mk_net(&program, token.as_ref()),
+            net: Self::mk_net(net, &program, token.as_ref()),
+            retry: http_client::Retry::default(),
             program,
             token
         }
     }
 
+    /// Retry requests which fail transiently in this way, rather than [the default](http_client::Retry::default).
+    pub fn with_retry(mut self, retry: http_client::Retry) -> Self {
+        self.retry = retry;
+        self
+    }
+
     async fn submit_listen_payloads(&self, variant: submit_listens::ListenType, payloads: &[submit_listens::ListeningPayload<'_>]) -> Result<(reqwest::StatusCode, String), reqwest::Error> {
         let body = submit_listens::RawBody {
             listen_type: variant,
@@ -134,7 +135,7 @@ impl<PS: AsRef<str>> Client<PS> {
 
         // TODO: Make use of the defined payload limits in the constants file.
         
-        let response = self.net.post(format!("{API_ROOT}/submit-listens")).body(body).send().await?;
+        let response = http_client::send(self.net.post(format!("{API_ROOT}/submit-listens")).body(body), &self.retry).await?;
         Ok((response.status(), response.text().await?))
     }
 
@@ -163,14 +164,28 @@ impl<PS: AsRef<str>> Client<PS> {
         }
 
 
-        let (code, body) = self.submit_listen_payloads(submit_listens::ListenType::Single, &[submit_listens::ListeningPayload {
-            listened_at: Some(time.timestamp() as u32),
-            metadata: submit_listens::ListeningPayloadTrackMetadata {
-                basic: track,
-                additional_info: extra.map(|info| info.into_raw())
-            }
-        }]).await?;
+        let (code, body) = self.submit_listen_payloads(submit_listens::ListenType::Single, &[submit_listens::Listen { track, time, extra }.into_payload()]).await?;
+        Self::listen_submission_result(code, body)
+    }
+
+    /// Submits listens which happened a while ago all at once, in as few requests as possible.
+    ///
+    /// If any of them is too historic, none are submitted.
+    /// A failing request stops those after it from being made, but the listens of the requests before it will have been submitted.
+    pub async fn import_listens(&self, listens: Vec<submit_listens::Listen<'_>>) -> Result<(), submit_listens::ListenSubmissionError> {
+        if listens.iter().any(|listen| listen.time < super::constants::LISTEN_MINIMUM_DATE) {
+            return Err(error::ListenDateTooHistoric)?;
+        }
+
+        let payloads = listens.into_iter().map(submit_listens::Listen::into_payload).collect::<Vec<_>>();
+        for chunk in payloads.chunks(super::constants::MAX_LISTENS_PER_REQUEST as usize) {
+            let (code, body) = self.submit_listen_payloads(submit_listens::ListenType::Import, chunk).await?;
+            Self::listen_submission_result(code, body)?;
+        }
+        Ok(())
+    }
 
+    fn listen_submission_result(code: reqwest::StatusCode, body: String) -> Result<(), submit_listens::ListenSubmissionError> {
         use reqwest::StatusCode;
         use submit_listens::ListenSubmissionError;
         match code {
@@ -51,6 +51,25 @@ impl RawBody<'_> {
 }
 
 
+/// A track which was listened to, starting at some time.
+#[derive(Debug)]
+pub struct Listen<'a> {
+    pub track: BasicTrackMetadata<'a>,
+    pub time: chrono::DateTime<chrono::Utc>,
+    pub extra: Option<additional_info::AdditionalInfo<'a>>,
+}
+impl<'a> Listen<'a> {
+    pub(crate) fn into_payload(self) -> ListeningPayload<'a> {
+        ListeningPayload {
+            listened_at: Some(self.time.timestamp() as u32),
+            metadata: ListeningPayloadTrackMetadata {
+                basic: self.track,
+                additional_info: self.extra.map(|info| info.into_raw())
+            }
+        }
+    }
+}
+
 #[derive(serde::Serialize, Debug)]
 pub(crate) struct ListeningPayload<'a> {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -62,12 +81,13 @@ pub(crate) struct ListeningPayload<'a> {
 pub mod additional_info {
     use musicbrainz::{*, request_client::ProgramInfo};
 
+    #[derive(Debug)]
     pub struct MediaPlayer<'a> {
         pub name: &'a str,
         pub version: Option<&'a str>
     }
 
-    
+    #[derive(Debug)]
     pub enum MusicService<'a> {
         Domain(&'a str),
         DomainIndeterminate { name: &'a str }
@@ -83,7 +103,7 @@ pub mod additional_info {
         pub works: Option<Vec<Id<entities::Work>>>
     }
     
-    #[derive(Default)]
+    #[derive(Debug, Default)]
     pub struct AdditionalInfo<'a> {
         pub ids: BrainzIds,
         pub track_number: Option<u32>,
@@ -106,7 +126,7 @@ pub mod additional_info {
                 artist_mbids: self.ids.artists.map(|vec| vec.into_iter().map(Id::contextless).collect()),
                 tracknumber: self.track_number.map(|n| n.to_string()),
                 isrc: self.isrc,
-                tags: self.tags.iter().map(|tag| unimplemented!()).collect(),
+                tags: (!self.tags.is_empty()).then(|| self.tags.into_iter().map(Tag::into_inner).collect()),
                 media_player: self.media_player.as_ref().map(|player| player.name),
                 media_player_version: self.media_player.as_ref().and_then(|player| player.version),
                 submission_client: self.submission_client.as_ref().map(|player| player.name.as_ref()),
@@ -133,7 +153,7 @@ pub mod additional_info {
         #[serde(skip_serializing_if = "Option::is_none")] pub work_mbids: Option<Vec<HyphenatedUuidString>>,
         #[serde(skip_serializing_if = "Option::is_none")] pub tracknumber: Option<String>,
         #[serde(skip_serializing_if = "Option::is_none")] pub isrc: Option<&'a str>,
-        #[serde(skip_serializing_if = "Option::is_none")] pub tags: Option<Vec<&'a str>>,
+        #[serde(skip_serializing_if = "Option::is_none")] pub tags: Option<Vec<maybe_owned_string::MaybeOwnedString<'a>>>,
         #[serde(skip_serializing_if = "Option::is_none")] pub media_player: Option<&'a str>,
         #[serde(skip_serializing_if = "Option::is_none")] pub media_player_version: Option<&'a str>,
         #[serde(skip_serializing_if = "Option::is_none")] pub submission_client: Option<&'a str>,
@@ -146,6 +166,140 @@ pub mod additional_info {
     }
 }
 
+/// Why a [`ListenBuilder`] couldn't build a listen which ListenBrainz would accept.
+#[derive(Debug, thiserror::Error)]
+pub enum InvalidListenError {
+    /// Contains the name of the missing field.
+    #[error("missing {0}")]
+    Missing(&'static str),
+    /// Contains the name of the field, which ListenBrainz would reject for containing a null character.
+    #[error("{0} contains a null character")]
+    NullCharacter(&'static str),
+    #[error("duration of {0:?} exceeds the maximum")]
+    DurationTooLong(core::time::Duration),
+    #[error(transparent)]
+    HistoricDate(#[from] super::error::ListenDateTooHistoric),
+}
+
+/// Assembles the metadata of a track for submission, checking it against what ListenBrainz accepts.
+///
+/// Names are trimmed of surrounding whitespace and cut to the [maximum size](crate::constants::MAX_NAME_SIZE),
+/// tags are cut to the [maximum length](crate::constants::MAX_TAG_SIZE)
+/// and [count](crate::constants::MAX_TAGS_PER_LISTEN), and the duration is rounded down to whole milliseconds.
+/// - <https://listenbrainz.readthedocs.io/en/latest/users/json.html>
+#[derive(Debug, Default)]
+pub struct ListenBuilder<'a> {
+    artist: Option<&'a str>,
+    track: Option<&'a str>,
+    release: Option<&'a str>,
+    extra: additional_info::AdditionalInfo<'a>,
+}
+impl<'a> ListenBuilder<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use the given additional information, replacing any tags or duration that were set beforehand.
+    pub fn with_extra(mut self, extra: additional_info::AdditionalInfo<'a>) -> Self {
+        self.extra = extra;
+        self
+    }
+
+    /// The name of the artist. Required.
+    pub fn artist(mut self, artist: impl Into<Option<&'a str>>) -> Self {
+        self.artist = artist.into();
+        self
+    }
+
+    /// The name of the track. Required.
+    pub fn track(mut self, track: impl Into<Option<&'a str>>) -> Self {
+        self.track = track.into();
+        self
+    }
+
+    /// The name of the release (i.e. the album) that the track is from.
+    pub fn release(mut self, release: impl Into<Option<&'a str>>) -> Self {
+        self.release = release.into();
+        self
+    }
+
+    pub fn duration(mut self, duration: impl Into<Option<core::time::Duration>>) -> Self {
+        self.extra.duration = duration.into();
+        self
+    }
+
+    /// Add a tag; ignored if the listen already has as many as it can.
+    pub fn tag(mut self, tag: impl Into<maybe_owned_string::MaybeOwnedString<'a>>) -> Self {
+        use maybe_owned_string::MaybeOwnedString;
+        use crate::constants::{MAX_TAGS_PER_LISTEN, MAX_TAG_SIZE};
+        if self.extra.tags.len() >= MAX_TAGS_PER_LISTEN.into() { return self }
+        let tag = match tag.into() {
+            MaybeOwnedString::Borrowed(tag) => MaybeOwnedString::Borrowed(truncate(tag.trim(), MAX_TAG_SIZE.into())),
+            tag => {
+                let trimmed = truncate(tag.trim(), MAX_TAG_SIZE.into());
+                if trimmed.len() == tag.len() { tag } else { trimmed.to_owned().into() }
+            }
+        };
+        if !tag.is_empty() {
+            self.extra.tags.push(musicbrainz::Tag::new(tag));
+        }
+        self
+    }
+
+    /// Validates the metadata, as is for a track which is [playing now](ListenType::PlayingNow).
+    pub fn build(mut self) -> Result<(BasicTrackMetadata<'a>, additional_info::AdditionalInfo<'a>), InvalidListenError> {
+        fn name(value: &str) -> &str {
+            truncate_bytes(value.trim(), crate::constants::MAX_NAME_SIZE.into())
+        }
+        fn required<'a>(value: Option<&'a str>, field: &'static str) -> Result<&'a str, InvalidListenError> {
+            value.map(name).filter(|value| !value.is_empty()).ok_or(InvalidListenError::Missing(field))
+        }
+        fn without_null<'a>(value: &'a str, field: &'static str) -> Result<&'a str, InvalidListenError> {
+            if value.contains('\0') { Err(InvalidListenError::NullCharacter(field)) } else { Ok(value) }
+        }
+
+        let artist = without_null(required(self.artist, "artist name")?, "artist name")?;
+        let track = without_null(required(self.track, "track name")?, "track name")?;
+        let release = self.release.map(name).filter(|release| !release.is_empty())
+            .map(|release| without_null(release, "release name")).transpose()?;
+        for tag in &self.extra.tags {
+            without_null(tag.as_str(), "tag")?;
+        }
+
+        if let Some(duration) = self.extra.duration {
+            if duration > crate::constants::MAX_DURATION_LIMIT {
+                return Err(InvalidListenError::DurationTooLong(duration))
+            }
+            let milliseconds = duration.as_millis() as u64;
+            // a duration of zero is rejected; it's better off being left unknown
+            self.extra.duration = (milliseconds != 0).then(|| core::time::Duration::from_millis(milliseconds));
+        }
+
+        Ok((BasicTrackMetadata { artist, track, release }, self.extra))
+    }
+
+    /// Validates the metadata and the time for a listen which has happened.
+    pub fn build_listen(self, time: chrono::DateTime<chrono::Utc>) -> Result<Listen<'a>, InvalidListenError> {
+        if time < crate::constants::LISTEN_MINIMUM_DATE {
+            return Err(super::error::ListenDateTooHistoric)?
+        }
+        let (track, extra) = self.build()?;
+        Ok(Listen { track, time, extra: Some(extra) })
+    }
+}
+
+/// Returns at most the first `max` characters of the string.
+fn truncate(str: &str, max: usize) -> &str {
+    str.char_indices().nth(max).map_or(str, |(index, _)| &str[..index])
+}
+
+/// Returns as much of the start of the string as fits within `max` bytes, without splitting a character.
+fn truncate_bytes(str: &str, max: usize) -> &str {
+    if str.len() <= max { return str }
+    let end = (0..=max).rev().find(|&index| str.is_char_boundary(index)).unwrap_or_default();
+    &str[..end]
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum ListenSubmissionError {
     #[error(transparent)]
@@ -172,3 +326,68 @@ pub enum CurrentlyPlayingSubmissionError {
     Other(reqwest::StatusCode, String)
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{MAX_NAME_SIZE, MAX_TAGS_PER_LISTEN, MAX_TAG_SIZE};
+
+    fn listen<'a>() -> ListenBuilder<'a> {
+        ListenBuilder::new().artist("Artist").track("Track")
+    }
+
+    #[test]
+    fn required_fields() {
+        assert!(matches!(ListenBuilder::new().track("Track").build(), Err(InvalidListenError::Missing("artist name"))));
+        assert!(matches!(ListenBuilder::new().artist("Artist").track("  ").build(), Err(InvalidListenError::Missing("track name"))));
+        assert!(matches!(listen().release("Release\0").build(), Err(InvalidListenError::NullCharacter("release name"))));
+        assert!(matches!(listen().tag("tag\0").build(), Err(InvalidListenError::NullCharacter("tag"))));
+
+        let (track, _) = listen().artist("  Artist ").release(" ").build().unwrap();
+        assert_eq!((track.artist, track.track, track.release), ("Artist", "Track", None));
+    }
+
+    #[test]
+    fn names_are_truncated_on_char_boundaries() {
+        let size = usize::from(MAX_NAME_SIZE);
+        // Three bytes each, so the limit falls in the middle of one.
+        let long = "あ".repeat(size);
+        let (track, _) = listen().artist(long.as_str()).track(long.as_str()).release(long.as_str()).build().unwrap();
+        for name in [track.artist, track.track, track.release.unwrap()] {
+            assert_eq!(name.len(), size - size % 3);
+            assert!(name.chars().all(|char| char == 'あ'));
+        }
+
+        let exact = "a".repeat(size);
+        let (track, _) = listen().track(exact.as_str()).build().unwrap();
+        assert_eq!(track.track, exact);
+    }
+
+    #[test]
+    fn tags() {
+        let long = "ü".repeat(usize::from(MAX_TAG_SIZE) + 1);
+        let (_, extra) = listen().tag(long.as_str()).tag(" ").tag(" rock ".to_owned()).build().unwrap();
+        let tags = extra.tags.iter().map(|tag| tag.as_str()).collect::<Vec<_>>();
+        assert_eq!(tags, [&long[..long.len() - 'ü'.len_utf8()], "rock"]);
+
+        let builder = (0..=MAX_TAGS_PER_LISTEN).fold(listen(), |builder, index| builder.tag(index.to_string()));
+        assert_eq!(builder.build().unwrap().1.tags.len(), usize::from(MAX_TAGS_PER_LISTEN));
+    }
+
+    #[test]
+    fn durations() {
+        use core::time::Duration;
+        let (_, extra) = listen().duration(Duration::from_micros(1_500_999)).build().unwrap();
+        assert_eq!(extra.duration, Some(Duration::from_millis(1_500)));
+        let (_, extra) = listen().duration(Duration::from_micros(999)).build().unwrap();
+        assert_eq!(extra.duration, None, "zero milliseconds should be left unknown");
+        let too_long = crate::constants::MAX_DURATION_LIMIT + Duration::from_secs(1);
+        assert!(matches!(listen().duration(too_long).build(), Err(InvalidListenError::DurationTooLong(duration)) if duration == too_long));
+    }
+
+    #[test]
+    fn listen_dates() {
+        let historic = crate::constants::LISTEN_MINIMUM_DATE - chrono::TimeDelta::seconds(1);
+        assert!(matches!(listen().build_listen(historic), Err(InvalidListenError::HistoricDate(_))));
+        assert!(listen().build_listen(crate::constants::LISTEN_MINIMUM_DATE).is_ok());
+    }
+}
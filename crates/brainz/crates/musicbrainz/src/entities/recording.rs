@@ -6,6 +6,8 @@ use crate::id::{IdPossessor, IdSubject};
 pub struct Recording {
     pub id: crate::Id<Self>,
     pub title: String,
+    /// In milliseconds.
+    pub length: Option<u32>,
     pub artist_credit: super::artist::credit::List,
 }
 impl IdPossessor for Recording {
@@ -3,8 +3,15 @@
 use shared::HyphenatedUuidString;
 
 #[repr(transparent)]
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
 pub struct Id<T: IdPossessor>(HyphenatedUuidString, core::marker::PhantomData<T>);
+// Not derived, since that'd require the entity itself to be `Clone`.
+impl<T: IdPossessor> Clone for Id<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T: IdPossessor> Copy for Id<T> {}
 impl<T: IdPossessor> Id<T> {
     pub const fn as_str(&self) -> &str {
         self.0.as_str()
@@ -4,6 +4,17 @@ pub use id::Id;
 
 pub mod request_client;
 
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, Debug)]
 pub struct Tag<'a>(maybe_owned_string::MaybeOwnedString<'a>);
+impl<'a> Tag<'a> {
+    pub fn new(name: impl Into<maybe_owned_string::MaybeOwnedString<'a>>) -> Self {
+        Self(name.into())
+    }
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+    pub fn into_inner(self) -> maybe_owned_string::MaybeOwnedString<'a> {
+        self.0
+    }
+}
 
@@ -27,3 +27,127 @@ impl<S: AsRef<str>> ProgramInfo<S> {
         out
     }
 }
+
+/// What a response said about its version of the resource, with which it can be requested again conditionally;
+/// if it hasn't changed since, the server responds with an empty `304 Not Modified` instead of sending it all over again.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Validators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+impl Validators {
+    pub fn from_headers(headers: &reqwest::header::HeaderMap) -> Self {
+        let get = |name| headers.get(name).and_then(|value| value.to_str().ok()).map(ToOwned::to_owned);
+        Self {
+            etag: get(reqwest::header::ETAG),
+            last_modified: get(reqwest::header::LAST_MODIFIED),
+        }
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+
+    /// Makes the request conditional upon the resource having changed since these were given.
+    pub fn apply(&self, mut request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        if let Some(etag) = &self.etag {
+            request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &self.last_modified {
+            request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+        }
+        request
+    }
+}
+
+/// A response from a [`ConditionalCache`].
+#[derive(Debug, Clone)]
+pub struct CachedResponse {
+    pub status: reqwest::StatusCode,
+    pub body: std::sync::Arc<str>,
+    /// Whether the server said the stored body was still current, rather than sending it anew.
+    pub revalidated: bool,
+}
+
+/// Successful response bodies, along with their [validators](Validators), by the URL they were requested from.
+///
+/// Requests sent through it for a URL that's been stored are made conditional, so the body only has to be sent again if it changed.
+/// Responses without any validators aren't stored, since they couldn't be revalidated anyhow.
+#[derive(Debug)]
+pub struct ConditionalCache {
+    entries: std::sync::Mutex<std::collections::HashMap<String, (Validators, std::sync::Arc<str>)>>,
+    /// The number of responses held before it's cleared out.
+    capacity: usize,
+}
+impl ConditionalCache {
+    pub fn new(capacity: usize) -> Self {
+        Self { entries: std::sync::Mutex::default(), capacity }
+    }
+
+    fn lookup(&self, url: &str) -> Option<(Validators, std::sync::Arc<str>)> {
+        self.entries.lock().expect("poisoned").get(url).cloned()
+    }
+
+    fn store(&self, url: String, validators: Validators, body: std::sync::Arc<str>) {
+        let mut entries = self.entries.lock().expect("poisoned");
+        if entries.len() >= self.capacity && !entries.contains_key(&url) { entries.clear() }
+        entries.insert(url, (validators, body));
+    }
+
+    /// Sends the request (retrying it if it fails transiently), conditionally upon it having changed if it's been stored.
+    pub async fn send(&self, request: reqwest::RequestBuilder, retry: &http_client::Retry) -> reqwest::Result<CachedResponse> {
+        let (client, request) = request.build_split();
+        let request = request?;
+        let url = request.url().to_string();
+        let stored = self.lookup(&url);
+
+        let mut request = reqwest::RequestBuilder::from_parts(client, request);
+        if let Some((validators, _)) = &stored {
+            request = validators.apply(request);
+        }
+
+        let response = http_client::send(request, retry).await?;
+        if let (reqwest::StatusCode::NOT_MODIFIED, Some((_, body))) = (response.status(), stored) {
+            return Ok(CachedResponse { status: reqwest::StatusCode::OK, body, revalidated: true })
+        }
+
+        let status = response.status();
+        let validators = Validators::from_headers(response.headers());
+        let body: std::sync::Arc<str> = response.text().await?.into();
+        if status.is_success() && !validators.is_empty() {
+            self.store(url, validators, body.clone());
+        }
+        Ok(CachedResponse { status, body, revalidated: false })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validators() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        assert!(Validators::from_headers(&headers).is_empty());
+        headers.insert(reqwest::header::ETAG, "\"abc\"".parse().unwrap());
+        headers.insert(reqwest::header::LAST_MODIFIED, "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap());
+        let validators = Validators::from_headers(&headers);
+        assert_eq!(validators.etag.as_deref(), Some("\"abc\""));
+
+        let request = validators.apply(reqwest::Client::new().get("https://musicbrainz.org/ws/2/")).build().unwrap();
+        assert_eq!(request.headers()[reqwest::header::IF_NONE_MATCH], "\"abc\"");
+        assert_eq!(request.headers()[reqwest::header::IF_MODIFIED_SINCE], "Wed, 21 Oct 2015 07:28:00 GMT");
+    }
+
+    #[test]
+    fn eviction() {
+        let cache = ConditionalCache::new(2);
+        let validators = Validators { etag: Some("\"1\"".to_owned()), last_modified: None };
+        cache.store("a".to_owned(), validators.clone(), "a".into());
+        cache.store("b".to_owned(), validators.clone(), "b".into());
+        cache.store("b".to_owned(), validators.clone(), "b".into());
+        assert!(cache.lookup("a").is_some(), "replacing an entry shouldn't clear the others out");
+        cache.store("c".to_owned(), validators, "c".into());
+        assert!(cache.lookup("a").is_none() && cache.lookup("c").is_some());
+    }
+}
@@ -0,0 +1,165 @@
+//! A shared configuration for the HTTP clients used across the workspace, so that every API crate
+//! makes its requests with the same user agent, timeouts, proxy, and retry behavior.
+//!
+//! API crates accept a [`reqwest::Client`] (or a [`reqwest::ClientBuilder`], if they need to add something of their own)
+//! instead of constructing one, and the application builds it from [`Options`].
+use core::time::Duration;
+
+/// How the client should be constructed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Options {
+    /// The `User-Agent` header sent with every request, unless a request overrides it.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub user_agent: Option<String>,
+    /// How long a request can take altogether, in seconds.
+    #[cfg_attr(feature = "serde", serde(with = "seconds"))]
+    pub timeout: Duration,
+    /// How long establishing a connection can take, in seconds.
+    #[cfg_attr(feature = "serde", serde(with = "seconds"))]
+    pub connect_timeout: Duration,
+    /// The URL of a proxy to send every request through.
+    ///
+    /// If not given, the proxy is taken from the environment (e.x. `HTTPS_PROXY`) or the system, if any.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub proxy: Option<String>,
+    pub retry: Retry,
+}
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            user_agent: None,
+            timeout: Duration::from_secs(30),
+            connect_timeout: Duration::from_secs(10),
+            proxy: None,
+            retry: Retry::default(),
+        }
+    }
+}
+impl Options {
+    /// A builder with the options applied, which can be further customized before building.
+    pub fn builder(&self) -> reqwest::Result<reqwest::ClientBuilder> {
+        let mut builder = reqwest::Client::builder()
+            .timeout(self.timeout)
+            .connect_timeout(self.connect_timeout);
+        if let Some(user_agent) = &self.user_agent {
+            builder = builder.user_agent(user_agent);
+        }
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+        }
+        Ok(builder)
+    }
+
+    pub fn build(&self) -> reqwest::Result<reqwest::Client> {
+        self.builder()?.build()
+    }
+}
+
+/// How requests which fail transiently (timeouts, connection failures, rate limiting, or an unavailable server) are retried.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Retry {
+    /// How many times a request is retried before giving up.
+    pub attempts: u8,
+    /// How long to wait before the first retry, in seconds; each retry after waits twice as long as the last.
+    ///
+    /// A server asking to be retried after some time (with `Retry-After`) is waited on for that long instead.
+    #[cfg_attr(feature = "serde", serde(with = "seconds"))]
+    pub backoff: Duration,
+}
+impl Default for Retry {
+    fn default() -> Self {
+        Self {
+            attempts: 2,
+            backoff: Duration::from_millis(500),
+        }
+    }
+}
+impl Retry {
+    /// The longest that'll be waited before retrying, regardless of what the server asks for.
+    pub const MAX_DELAY: Duration = Duration::from_secs(30);
+
+    /// Never retry.
+    pub const fn none() -> Self {
+        Self { attempts: 0, backoff: Duration::ZERO }
+    }
+
+    fn delay(&self, retry: u8, requested: Option<Duration>) -> Duration {
+        requested
+            .unwrap_or_else(|| self.backoff.saturating_mul(1 << retry.min(16)))
+            .min(Self::MAX_DELAY)
+    }
+}
+
+const fn is_retryable_status(status: reqwest::StatusCode) -> bool {
+    matches!(status.as_u16(), 429 | 502 | 503 | 504)
+}
+
+fn is_retryable_error(error: &reqwest::Error) -> bool {
+    error.is_timeout() || error.is_connect()
+}
+
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    response.headers().get(reqwest::header::RETRY_AFTER)?
+        .to_str().ok()?
+        .trim().parse().ok()
+        .map(Duration::from_secs)
+}
+
+/// Sends the request, retrying it if it fails transiently.
+///
+/// Requests with a streaming body can't be cloned to be sent again, and so are only ever sent once.
+pub async fn send(request: reqwest::RequestBuilder, retry: &Retry) -> reqwest::Result<reqwest::Response> {
+    let mut retries = 0;
+    loop {
+        let Some(attempt) = request.try_clone() else { return request.send().await };
+        let result = attempt.send().await;
+        let retryable = match &result {
+            Ok(response) => is_retryable_status(response.status()),
+            Err(error) => is_retryable_error(error),
+        };
+        if !retryable || retries >= retry.attempts {
+            return result
+        }
+        tokio::time::sleep(retry.delay(retries, result.as_ref().ok().and_then(retry_after))).await;
+        retries += 1;
+    }
+}
+
+#[cfg(feature = "serde")]
+mod seconds {
+    use core::time::Duration;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(duration.as_secs_f64())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        Duration::try_from_secs_f64(f64::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delay() {
+        let retry = Retry { attempts: 3, backoff: Duration::from_millis(500) };
+        assert_eq!(retry.delay(0, None), Duration::from_millis(500));
+        assert_eq!(retry.delay(2, None), Duration::from_secs(2));
+        assert_eq!(retry.delay(0, Some(Duration::from_secs(5))), Duration::from_secs(5));
+        assert_eq!(retry.delay(200, None), Retry::MAX_DELAY);
+        assert_eq!(retry.delay(0, Some(Duration::from_secs(3600))), Retry::MAX_DELAY);
+    }
+
+    #[test]
+    fn building() {
+        assert!(Options::default().build().is_ok());
+        assert!(Options { proxy: Some("http://localhost:8080".to_owned()), ..Default::default() }.build().is_ok());
+    }
+}
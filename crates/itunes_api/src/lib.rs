@@ -101,7 +101,7 @@ fn deserialize_results<T>(response: &str) -> Result<Vec<T>, serde_json::Error> w
     Ok(results)
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Artist {
     #[serde(rename = "artistName")]
@@ -118,7 +118,7 @@ pub struct Artist {
     pub link: String,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Track {
     #[serde(rename = "artistViewUrl")]
@@ -139,8 +139,15 @@ pub struct Track {
     #[serde(rename = "collectionCensoredName")]
     pub collection_name_censored: String,
     pub collection_name: String,
+
+    #[serde(rename = "trackTimeMillis")]
+    pub duration_millis: Option<u64>,
 }
 impl Track {
+    pub fn duration(&self) -> Option<core::time::Duration> {
+        self.duration_millis.map(core::time::Duration::from_millis)
+    }
+
     pub fn artwork_mzstatic(&self) -> Result<
         mzstatic::image::MzStaticImage<'_>,
         mzstatic::image::ParseError<'_>,
@@ -149,6 +156,211 @@ impl Track {
     }
 }
 
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MusicVideo {
+    #[serde(rename = "artistViewUrl")]
+    pub artist_apple_music_url: Option<String>,
+    pub artist_name: String,
+
+    #[serde(rename = "trackCensoredName")]
+    pub name_censored: String,
+    #[serde(rename = "trackName")]
+    pub name: String,
+
+    #[serde(rename = "artworkUrl100")]
+    pub artwork_preview_url: String,
+
+    #[serde(rename = "trackViewUrl")]
+    pub apple_music_url: String,
+
+    /// Left out for music videos which were released on their own.
+    pub collection_name: Option<String>,
+
+    #[serde(rename = "trackTimeMillis")]
+    pub duration_millis: Option<u64>,
+}
+
+/// A podcast as a whole, rather than any one of its episodes.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Podcast {
+    pub artist_name: String,
+
+    #[serde(rename = "collectionCensoredName")]
+    pub name_censored: String,
+    #[serde(rename = "collectionName")]
+    pub name: String,
+
+    #[serde(rename = "artworkUrl100")]
+    pub artwork_preview_url: String,
+
+    #[serde(rename = "collectionViewUrl")]
+    pub apple_podcasts_url: String,
+
+    pub feed_url: Option<String>,
+    #[serde(rename = "primaryGenreName")]
+    pub genre: Option<String>,
+    #[serde(rename = "trackCount")]
+    pub episode_count: Option<u32>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Audiobook {
+    #[serde(rename = "artistViewUrl")]
+    pub artist_apple_books_url: Option<String>,
+    /// The author, usually; sometimes followed by the narrator.
+    pub artist_name: String,
+
+    #[serde(rename = "collectionCensoredName")]
+    pub name_censored: String,
+    #[serde(rename = "collectionName")]
+    pub name: String,
+
+    #[serde(rename = "artworkUrl100")]
+    pub artwork_preview_url: String,
+
+    #[serde(rename = "collectionViewUrl")]
+    pub apple_books_url: String,
+}
+
+/// The kind of thing to search for, as given under `entity`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Entity {
+    #[default]
+    Song,
+    MusicVideo,
+    Podcast,
+    Audiobook,
+}
+impl Entity {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Song => "song",
+            Self::MusicVideo => "musicVideo",
+            Self::Podcast => "podcast",
+            Self::Audiobook => "audiobook",
+        }
+    }
+
+    /// The kind of media the entity is of, which is searched within.
+    const fn media(self) -> &'static str {
+        match self {
+            Self::Song => "music",
+            Self::MusicVideo => "musicVideo",
+            Self::Podcast => "podcast",
+            Self::Audiobook => "audiobook",
+        }
+    }
+}
+
+/// A result of searching for (or looking up) a certain [`Entity`].
+pub trait Searchable: for<'de> Deserialize<'de> {
+    const ENTITY: Entity;
+}
+impl Searchable for Track { const ENTITY: Entity = Entity::Song; }
+impl Searchable for MusicVideo { const ENTITY: Entity = Entity::MusicVideo; }
+impl Searchable for Podcast { const ENTITY: Entity = Entity::Podcast; }
+impl Searchable for Audiobook { const ENTITY: Entity = Entity::Audiobook; }
+
+/// A result of any of the [`Searchable`] kinds, for when which is searched for isn't known ahead of time.
+#[derive(Debug, Clone)]
+pub enum SearchResult {
+    Song(Track),
+    MusicVideo(MusicVideo),
+    Podcast(Podcast),
+    Audiobook(Audiobook),
+}
+impl SearchResult {
+    pub const fn entity(&self) -> Entity {
+        match self {
+            Self::Song(_) => Entity::Song,
+            Self::MusicVideo(_) => Entity::MusicVideo,
+            Self::Podcast(_) => Entity::Podcast,
+            Self::Audiobook(_) => Entity::Audiobook,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Song(track) => &track.name,
+            Self::MusicVideo(video) => &video.name,
+            Self::Podcast(podcast) => &podcast.name,
+            Self::Audiobook(book) => &book.name,
+        }
+    }
+
+    pub fn name_censored(&self) -> &str {
+        match self {
+            Self::Song(track) => &track.name_censored,
+            Self::MusicVideo(video) => &video.name_censored,
+            Self::Podcast(podcast) => &podcast.name_censored,
+            Self::Audiobook(book) => &book.name_censored,
+        }
+    }
+
+    pub fn artist_name(&self) -> &str {
+        match self {
+            Self::Song(track) => &track.artist_name,
+            Self::MusicVideo(video) => &video.artist_name,
+            Self::Podcast(podcast) => &podcast.artist_name,
+            Self::Audiobook(book) => &book.artist_name,
+        }
+    }
+
+    /// The album it's on; podcasts and audiobooks are themselves the collection, so they don't have one.
+    pub fn collection_name(&self) -> Option<&str> {
+        match self {
+            Self::Song(track) => Some(&track.collection_name),
+            Self::MusicVideo(video) => video.collection_name.as_deref(),
+            Self::Podcast(_) | Self::Audiobook(_) => None,
+        }
+    }
+
+    pub fn duration(&self) -> Option<core::time::Duration> {
+        match self {
+            Self::Song(track) => track.duration(),
+            Self::MusicVideo(video) => video.duration_millis.map(core::time::Duration::from_millis),
+            Self::Podcast(_) | Self::Audiobook(_) => None,
+        }
+    }
+
+    /// Where it can be found on the web; for podcasts and audiobooks, this is Apple Podcasts and Apple Books respectively.
+    pub fn url(&self) -> &str {
+        match self {
+            Self::Song(track) => &track.apple_music_url,
+            Self::MusicVideo(video) => &video.apple_music_url,
+            Self::Podcast(podcast) => &podcast.apple_podcasts_url,
+            Self::Audiobook(book) => &book.apple_books_url,
+        }
+    }
+
+    pub fn artwork_mzstatic(&self) -> Result<
+        mzstatic::image::MzStaticImage<'_>,
+        mzstatic::image::ParseError<'_>,
+    > {
+        mzstatic::image::MzStaticImage::parse(match self {
+            Self::Song(track) => &track.artwork_preview_url,
+            Self::MusicVideo(video) => &video.artwork_preview_url,
+            Self::Podcast(podcast) => &podcast.artwork_preview_url,
+            Self::Audiobook(book) => &book.artwork_preview_url,
+        })
+    }
+}
+impl From<Track> for SearchResult {
+    fn from(value: Track) -> Self { Self::Song(value) }
+}
+impl From<MusicVideo> for SearchResult {
+    fn from(value: MusicVideo) -> Self { Self::MusicVideo(value) }
+}
+impl From<Podcast> for SearchResult {
+    fn from(value: Podcast) -> Self { Self::Podcast(value) }
+}
+impl From<Audiobook> for SearchResult {
+    fn from(value: Audiobook) -> Self { Self::Audiobook(value) }
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("HTTP error: {0}")]
@@ -159,35 +371,66 @@ pub enum Error {
 
 pub struct Client {
     reqwest: reqwest::Client,
+    retry: http_client::Retry,
 }
 impl Client {
     pub fn new(reqwest_client: reqwest::Client) -> Self {
         Self {
-            reqwest: reqwest_client
+            reqwest: reqwest_client,
+            retry: http_client::Retry::default(),
         }
-    } 
+    }
+
+    /// Retry requests which fail transiently in this way, rather than [the default](http_client::Retry::default).
+    pub fn with_retry(mut self, retry: http_client::Retry) -> Self {
+        self.retry = retry;
+        self
+    }
 
-    async fn lookup<T>(&self, id: u32, entity: &str) -> Result<Option<T>, Error> where T: for<'de> Deserialize<'de> {
+    async fn lookup<T>(&self, id: u64, entity: &str) -> Result<Option<T>, Error> where T: for<'de> Deserialize<'de> {
         let url = format!("{ITUNES_API_BASE_URL}/lookup?id={id}&entity={entity}");
-        let response = self.reqwest.get(&url).send().await?;
+        let response = http_client::send(self.reqwest.get(&url), &self.retry).await?;
         let json = response.text().await?;
         Ok(deserialize_results::<T>(&json)?.into_iter().next())
     }
 
     pub async fn lookup_artist(&self, id: u32) -> Result<Option<Artist>, Error> {
-        self.lookup(id, "musicArtist").await
+        self.lookup(id.into(), "musicArtist").await
     }
 
-    pub async fn search_songs(&self, query: &str, limit: usize) -> Result<Vec<Track>, Error> {
+    /// Looks up a song, music video, podcast, or audiobook by its ID.
+    pub async fn lookup_entity<T: Searchable>(&self, id: u64) -> Result<Option<T>, Error> {
+        self.lookup(id, T::ENTITY.as_str()).await
+    }
+
+    pub async fn search<T: Searchable>(&self, query: &str, limit: usize) -> Result<Vec<T>, Error> {
         let mut url = reqwest::Url::parse(format!("{ITUNES_API_BASE_URL}/search").as_str()).unwrap();
         url.query_pairs_mut()
             .append_pair("term", query)
-            .append_pair("entity", "song")
+            .append_pair("media", T::ENTITY.media())
+            .append_pair("entity", T::ENTITY.as_str())
             .append_pair("limit", &limit.to_string());
 
-        let res = self.reqwest.get(url).send().await?;
+        let res = http_client::send(self.reqwest.get(url), &self.retry).await?;
         let text = res.text().await.map_err(|_| Error::DeserializationFailed(serde_json::Error::custom("could not decode response")))?;
-        Ok(deserialize_results::<Track>(&text)?)
+        Ok(deserialize_results::<T>(&text)?)
+    }
+
+    /// Like [`Self::search`], but for whichever kind of entity is given.
+    pub async fn search_entity(&self, entity: Entity, query: &str, limit: usize) -> Result<Vec<SearchResult>, Error> {
+        async fn search<T: Searchable + Into<SearchResult>>(client: &Client, query: &str, limit: usize) -> Result<Vec<SearchResult>, Error> {
+            Ok(client.search::<T>(query, limit).await?.into_iter().map(Into::into).collect())
+        }
+        match entity {
+            Entity::Song => search::<Track>(self, query, limit).await,
+            Entity::MusicVideo => search::<MusicVideo>(self, query, limit).await,
+            Entity::Podcast => search::<Podcast>(self, query, limit).await,
+            Entity::Audiobook => search::<Audiobook>(self, query, limit).await,
+        }
+    }
+
+    pub async fn search_songs(&self, query: &str, limit: usize) -> Result<Vec<Track>, Error> {
+        self.search(query, limit).await
     }
 }
 
@@ -196,6 +439,29 @@ impl Client {
 mod tests {
     use super::*;
 
+    #[test]
+    fn entities() {
+        let podcast = r#"{"resultCount":1,"results":[{"wrapperType":"track","kind":"podcast","collectionId":1200361736,"trackId":1200361736,
+            "artistName":"The New York Times","collectionName":"The Daily","trackName":"The Daily","collectionCensoredName":"The Daily",
+            "trackCensoredName":"The Daily","collectionViewUrl":"https://podcasts.apple.com/us/podcast/the-daily/id1200361736?uo=4",
+            "feedUrl":"https://feeds.simplecast.com/54nAGcIl","artworkUrl100":"https://is1-ssl.mzstatic.com/image/thumb/Podcasts221/v4/a1/b2/c3/a1b2c3d4-e5f6-7890-abcd-ef1234567890/mza_1234567890123456789.jpg/100x100bb.jpg",
+            "trackCount":2000,"primaryGenreName":"Daily News"}]}"#;
+        let podcast = deserialize_results::<Podcast>(podcast).unwrap().remove(0);
+        assert_eq!(podcast.episode_count, Some(2000));
+        let podcast = SearchResult::from(podcast);
+        assert_eq!((podcast.entity(), podcast.name(), podcast.collection_name()), (Entity::Podcast, "The Daily", None));
+        assert!(podcast.artwork_mzstatic().is_ok());
+
+        let video = r#"{"resultCount":1,"results":[{"wrapperType":"track","kind":"music-video","artistId":909253,"trackId":1445112870,
+            "artistName":"Jack Johnson","trackName":"Upside Down","trackCensoredName":"Upside Down",
+            "artistViewUrl":"https://music.apple.com/us/artist/jack-johnson/909253?uo=4","trackViewUrl":"https://music.apple.com/us/music-video/upside-down/1445112870?uo=4",
+            "artworkUrl100":"https://is1-ssl.mzstatic.com/image/thumb/Video118/v4/ab/cd/ef/abcdef01-2345-6789-abcd-ef0123456789/source/100x100bb.jpg",
+            "trackTimeMillis":212000,"primaryGenreName":"Rock"}]}"#;
+        let video = SearchResult::from(deserialize_results::<MusicVideo>(video).unwrap().remove(0));
+        assert_eq!(video.collection_name(), None, "released on its own");
+        assert_eq!(video.duration(), Some(core::time::Duration::from_secs(212)));
+    }
+
     #[tokio::test]
     #[ignore = "requires network connection"]
     async fn test_lookup_artist() {
@@ -17,6 +17,7 @@ pub(crate) const API_URL: &str = "https://ws.audioscrobbler.com/2.0/";
 pub struct Client<A: auth::state::AuthorizationStatus> {
     pub identity: auth::ClientIdentity,
     pub net: reqwest::Client, // exposed for re-use if dev'd like to only have one
+    retry: http_client::Retry,
     session_key: Option<auth::SessionKey>,
     _authorized: core::marker::PhantomData<A>
 }
@@ -24,11 +25,19 @@ impl<A: auth::state::AuthorizationStatus> Client<A> {
     pub const fn is_authorized(&self) -> bool {
         self.session_key.is_some()
     }
+
+    /// Retry requests which fail transiently in this way, rather than [the default](http_client::Retry::default).
+    pub fn with_retry(mut self, retry: http_client::Retry) -> Self {
+        self.retry = retry;
+        self
+    }
 }
 impl Client<auth::state::Unauthorized> {
-    pub fn new(identity: auth::ClientIdentity) -> Client<auth::state::Unauthorized> {
+    /// The user agent of the identity is sent with every request, regardless of how the network client was built.
+    pub fn new(identity: auth::ClientIdentity, net: reqwest::Client) -> Client<auth::state::Unauthorized> {
         Client::<auth::state::Unauthorized> {
-            net: reqwest::Client::builder().user_agent(&identity.user_agent).build().expect("cannot construct reqwest client"),
+            net,
+            retry: http_client::Retry::default(),
             identity,
             session_key: None,
             _authorized: core::marker::PhantomData
@@ -38,6 +47,7 @@ impl Client<auth::state::Unauthorized> {
     pub fn into_authorized(self, session_key: auth::SessionKey) -> Client<auth::state::Authorized> {
         Client::<auth::state::Authorized> {
             net: self.net,
+            retry: self.retry,
             identity: self.identity,
             session_key: Some(session_key),
             _authorized: core::marker::PhantomData,
@@ -45,9 +55,12 @@ impl Client<auth::state::Unauthorized> {
     }
 }
 impl<'a> Client<auth::state::Authorized> {
-    pub fn authorized(identity: auth::ClientIdentity, session_key: auth::SessionKey) -> Self {
+    pub const MAX_TAGS_PER_REQUEST: usize = 10;
+
+    pub fn authorized(identity: auth::ClientIdentity, session_key: auth::SessionKey, net: reqwest::Client) -> Self {
         Self {
-            net: reqwest::Client::builder().user_agent(&identity.user_agent).build().expect("cannot construct reqwest client"),
+            net,
+            retry: http_client::Retry::default(),
             identity,
             session_key: Some(session_key),
             _authorized: core::marker::PhantomData,
@@ -67,9 +80,8 @@ impl<'a> Client<auth::state::Authorized> {
         let request = self.net.request(request.method, crate::API_URL)
             .header("Content-Length", "0")
             .header("User-Agent", &self.identity.user_agent)
-            .query(&request.parameters)
-            .build()?;
-        self.net.execute(request).await
+            .query(&request.parameters);
+        http_client::send(request, &self.retry).await
     }
 
 
@@ -98,6 +110,36 @@ impl<'a> Client<auth::state::Authorized> {
         
         Ok(response)
     }
+
+    /// Tags a track on behalf of the user. At most [ten](Self::MAX_TAGS_PER_REQUEST) tags can be added at once.
+    /// - <https://www.last.fm/api/show/track.addTags>
+    pub async fn add_tags(&self, artist: &str, track: &str, tags: &[&str]) -> Result<()> {
+        debug_assert!(tags.len() <= Self::MAX_TAGS_PER_REQUEST, "too many tags for a single request");
+
+        let mut parameters = parameters::Map::from_collection(std::collections::HashMap::with_capacity(3));
+        parameters.add("artist".to_owned(), MaybeOwnedString::Borrowed(artist));
+        parameters.add("track".to_owned(), MaybeOwnedString::Borrowed(track));
+        parameters.add("tags".to_owned(), MaybeOwnedString::Owned(tags.join(",")));
+
+        let response = self.dispatch_authorized(ApiRequest {
+            endpoint: "track.addTags",
+            method: reqwest::Method::POST,
+            parameters,
+        }).await?;
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Response {
+            Fail { #[serde(rename = "error")] code: u8, message: String },
+            Ok {}
+        }
+
+        let response = response.text().await?;
+        match serde_json::from_str(&response)? {
+            Response::Ok {} => Ok(()),
+            Response::Fail { code, .. } => Err(Error::from(code))
+        }
+    }
 }
 
 struct ApiRequest<'a> {
@@ -57,6 +57,8 @@ impl<'a> From<&'a scrobble::HeardTrackInfo<'a>> for Map<'a> {
         if let Some(mbid) = &track.mbid { map.insert("mbid".to_owned(), MaybeOwnedString::Borrowed(mbid.as_str())); }
         if let Some(album_artist) = track.album_artist { map.insert("albumArtist".to_owned(), MaybeOwnedString::Borrowed(album_artist)); }
         if let Some(duration) = track.duration_in_seconds { map.insert("duration".to_owned(), MaybeOwnedString::Owned(duration.to_string())); }
+        if let Some(track_number) = track.track_number { map.insert("trackNumber".to_owned(), MaybeOwnedString::Owned(track_number.to_string())); }
+        if let Some(context) = track.context { map.insert("context".to_owned(), MaybeOwnedString::Borrowed(context)); }
         Self(map)
     }
 }
@@ -74,8 +76,66 @@ impl<'a> From<&'a [scrobble::Scrobble<'a>]> for Map<'a> {
             if let Some(mbid) = &scrobble.info.mbid { map.insert(format!("mbid[{i}]"), MaybeOwnedString::Borrowed(mbid.as_str())); }
             if let Some(album_artist) = scrobble.info.album_artist { map.insert(format!("albumArtist[{i}]"), MaybeOwnedString::Borrowed(album_artist)); }
             if let Some(duration) = scrobble.info.duration_in_seconds { map.insert(format!("duration[{i}]"), MaybeOwnedString::Owned(duration.to_string())); }
+            if let Some(track_number) = scrobble.info.track_number { map.insert(format!("trackNumber[{i}]"), MaybeOwnedString::Owned(track_number.to_string())); }
+            if let Some(context) = scrobble.info.context { map.insert(format!("context[{i}]"), MaybeOwnedString::Borrowed(context)); }
+            if let Some(stream_id) = scrobble.stream_id { map.insert(format!("streamId[{i}]"), MaybeOwnedString::Borrowed(stream_id)); }
         } 
         Self(map)
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compilation_scrobble() {
+        let scrobbles = [scrobble::Scrobble {
+            info: scrobble::HeardTrackInfo {
+                artist: "Lumpy",
+                track: "Snorkel",
+                album: Some("Now That's What I Call Snorkeling"),
+                album_artist: Some("Various Artists"),
+                ..Default::default()
+            },
+            timestamp: chrono::DateTime::UNIX_EPOCH,
+            chosen_by_user: None,
+            stream_id: None,
+        }];
+        let map = Map::from(scrobbles.as_slice());
+        assert_eq!(map.0.get("artist[0]").map(AsRef::as_ref), Some("Lumpy"));
+        assert_eq!(map.0.get("albumArtist[0]").map(AsRef::as_ref), Some("Various Artists"));
+        assert!(!map.0.contains_key("chosenByUser[0]"));
+
+        let map = Map::from(&scrobbles[0].info);
+        assert_eq!(map.0.get("artist").map(AsRef::as_ref), Some("Lumpy"));
+        assert_eq!(map.0.get("albumArtist").map(AsRef::as_ref), Some("Various Artists"));
+    }
+
+    #[test]
+    fn optional_scrobble_parameters() {
+        let mbid = serde_json::from_str(r#""0f3fd4e2-f7b5-4d5c-9e5b-f1e2a0f7e2b1""#).unwrap();
+        let scrobbles = [scrobble::HeardTrackInfo {
+            artist: "Lumpy",
+            track: "Snorkel",
+            track_number: Some(3),
+            mbid: Some(mbid),
+            context: Some("radio"),
+            ..Default::default()
+        }.promote_to_scrobble(scrobble::ScrobbleEnrichmentParameters {
+            timestamp: chrono::DateTime::UNIX_EPOCH,
+            chosen_by_user: Some(false),
+            stream_id: Some("1234"),
+        })];
+        let map = Map::from(scrobbles.as_slice());
+        assert_eq!(map.0.get("chosenByUser[0]").map(AsRef::as_ref), Some("0"));
+        assert_eq!(map.0.get("streamId[0]").map(AsRef::as_ref), Some("1234"));
+        assert_eq!(map.0.get("mbid[0]").map(AsRef::as_ref), Some("0f3fd4e2-f7b5-4d5c-9e5b-f1e2a0f7e2b1"));
+        assert_eq!(map.0.get("trackNumber[0]").map(AsRef::as_ref), Some("3"));
+        assert_eq!(map.0.get("context[0]").map(AsRef::as_ref), Some("radio"));
+
+        let map = Map::from(&scrobbles[0].info);
+        assert_eq!(map.0.get("trackNumber").map(AsRef::as_ref), Some("3"));
+        assert!(!map.0.contains_key("streamId"));
+    }
+}
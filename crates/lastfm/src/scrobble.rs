@@ -3,7 +3,7 @@
 /// It is the shared parameters of the following endpoints:
 /// - <https://www.last.fm/api/show/track.scrobble#Params>
 /// - <https://www.last.fm/api/show/track.updateNowPlaying#Params>
-#[derive(Default)] // Should only be used for `{ artist: Foo, track: Bar, ..Default::default() }`, not generating an entire default.
+#[derive(Debug, Default)] // Should only be used for `{ artist: Foo, track: Bar, ..Default::default() }`, not generating an entire default.
 pub struct HeardTrackInfo<'a> {
     /// The artist name.
     pub artist: &'a str,
@@ -20,24 +20,31 @@ pub struct HeardTrackInfo<'a> {
     /// The album artist, if it differs from the track artist
     pub album_artist: Option<&'a str>,
 
-    /// The MusicBrainz Track ID.
+    /// The MusicBrainz ID of the recording.
+    ///
+    /// Last.fm calls this the "track" ID, but what it identifies is what MusicBrainz calls a recording.
     // TODO: Gate this type definition behind a feature, making it a `&'a str` otherwise.
-    pub mbid: Option<brainz::music::Id<brainz::music::entities::Track>>,
+    pub mbid: Option<brainz::music::Id<brainz::music::entities::Recording>>,
 
     /// The duration of the track in seconds.
     pub duration_in_seconds: Option<u32>,
+
+    /// Sub-client version; not public, only enabled for certain API keys.
+    pub context: Option<&'a str>,
 }
 impl<'a> HeardTrackInfo<'a> {
-    pub fn promote_to_scrobble(self, parameters: ScrobbleEnrichmentParameters) -> Scrobble<'a> where Self: 'a {
+    pub fn promote_to_scrobble(self, parameters: ScrobbleEnrichmentParameters<'a>) -> Scrobble<'a> where Self: 'a {
         Scrobble {
             info: self,
             timestamp: parameters.timestamp,
-            chosen_by_user: parameters.chosen_by_user
+            chosen_by_user: parameters.chosen_by_user,
+            stream_id: parameters.stream_id,
         }
     }
 }
 
 /// <https://www.last.fm/api/show/track.scrobble#Params>
+#[derive(Debug)]
 pub struct Scrobble<'a> {
     /// The track that was played.
     pub info: HeardTrackInfo<'a>,
@@ -50,10 +57,13 @@ pub struct Scrobble<'a> {
     /// If `false`, the song was chosen by someone else, such as a radio station or recommendation service.
     /// If there is any ambiguity or doubt, then don't send this value. Defaults to `true`.
     pub chosen_by_user: Option<bool>,
+
+    /// The stream ID given to the track by Last.fm, if it was played from a Last.fm radio stream.
+    pub stream_id: Option<&'a str>,
 }
 
 
-pub struct ScrobbleEnrichmentParameters {
+pub struct ScrobbleEnrichmentParameters<'a> {
     /// The time the track started playing.
     // TODO: Just use a `u64`?
     pub timestamp: chrono::DateTime<chrono::Utc>,
@@ -62,6 +72,9 @@ pub struct ScrobbleEnrichmentParameters {
     /// If `false`, the song was chosen by someone else, such as a radio station or recommendation service.
     /// Defaults to `true`.
     pub chosen_by_user: Option<bool>,
+
+    /// The stream ID given to the track by Last.fm, if it was played from a Last.fm radio stream.
+    pub stream_id: Option<&'a str>,
 }
 
 pub use response::ScrobbleError;
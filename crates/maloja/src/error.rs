@@ -0,0 +1,69 @@
+use serde::Deserialize;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The URL of the server couldn't be parsed, or isn't one that can be requested.
+    #[error("invalid server url: {0}")]
+    InvalidUrl(String),
+    /// The API key is missing, or isn't one the server knows of.
+    #[error("authentication failed: {0}")]
+    Unauthorized(String),
+    /// The server refused the request, such as for it missing data which it requires.
+    #[error("request rejected ({kind}): {description}")]
+    Rejected { kind: String, description: String },
+    /// The server responded unsuccessfully in some other way.
+    #[error("unexpected response ({status}): {body}")]
+    Unexpected { status: reqwest::StatusCode, body: String },
+    /// An error occurred while sending the request.
+    #[error("network error: {0}")]
+    Network(#[from] reqwest::Error),
+    /// An error occurred while (de)serializing a request or response.
+    #[error("deserialization error: {0}")]
+    Deserialization(#[from] serde_json::Error),
+}
+impl Error {
+    /// Interprets an unsuccessful response, which the server describes with a body like:
+    /// ```json
+    /// { "status": "failure", "error": { "type": "authentication_fail", "desc": "Invalid or missing authentication" } }
+    /// ```
+    pub(crate) fn from_response(status: reqwest::StatusCode, body: String) -> Self {
+        #[derive(Deserialize)]
+        struct Body {
+            error: Details,
+        }
+        #[derive(Deserialize)]
+        struct Details {
+            #[serde(rename = "type")]
+            kind: String,
+            #[serde(default)]
+            desc: String,
+        }
+
+        let details = serde_json::from_str::<Body>(&body).ok().map(|body| body.error);
+        match (status.as_u16(), details) {
+            (401 | 403, details) => Self::Unauthorized(details.map_or(body, |details| details.desc)),
+            (429, _) => Self::Unexpected { status, body },
+            (400..=499, Some(Details { kind, desc })) => Self::Rejected { kind, description: desc },
+            _ => Self::Unexpected { status, body },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_response() {
+        let body = r#"{"status": "failure", "error": {"type": "authentication_fail", "desc": "Invalid or missing authentication"}}"#;
+        let error = Error::from_response(reqwest::StatusCode::FORBIDDEN, body.to_owned());
+        assert!(matches!(error, Error::Unauthorized(reason) if reason == "Invalid or missing authentication"));
+
+        let body = r#"{"status": "failure", "error": {"type": "missing_scrobble_data", "desc": "Scrobble is missing required data: title"}}"#;
+        let error = Error::from_response(reqwest::StatusCode::BAD_REQUEST, body.to_owned());
+        assert!(matches!(error, Error::Rejected { kind, .. } if kind == "missing_scrobble_data"));
+
+        let error = Error::from_response(reqwest::StatusCode::BAD_GATEWAY, "<html>".to_owned());
+        assert!(matches!(error, Error::Unexpected { status: reqwest::StatusCode::BAD_GATEWAY, .. }));
+    }
+}
@@ -0,0 +1,102 @@
+//! A client for the native API of [Maloja](https://github.com/krateng/maloja), a self-hosted scrobble server.
+//!
+//! - <https://github.com/krateng/maloja/blob/master/API.md>
+use serde::{Deserialize, Serialize};
+
+pub mod error;
+pub mod scrobble;
+
+pub use error::Error;
+pub type Result<T> = ::core::result::Result<T, Error>;
+
+/// Where the native API is, relative to the root of the server.
+const API_PATH: &str = "apis/mlj_1/";
+
+/// A key to the API of a server, created through its admin panel.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ApiKey(String);
+impl ApiKey {
+    pub const fn new(key: String) -> Self {
+        Self(key)
+    }
+}
+impl AsRef<str> for ApiKey {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+impl core::fmt::Debug for ApiKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("ApiKey(..)")
+    }
+}
+
+pub struct Client {
+    pub net: reqwest::Client, // exposed for re-use if dev'd like to only have one
+    api: reqwest::Url,
+    key: ApiKey,
+    retry: http_client::Retry,
+}
+impl Client {
+    /// The `root` is wherever the server is hosted, such as `https://maloja.example.com`.
+    pub fn new(root: &str, key: ApiKey, net: reqwest::Client) -> Result<Self> {
+        // Without the trailing slash, the last segment of the path would be replaced rather than appended to.
+        let root = if root.ends_with('/') { reqwest::Url::parse(root) } else { reqwest::Url::parse(&format!("{root}/")) };
+        let api = root.and_then(|root| root.join(API_PATH)).map_err(|error| Error::InvalidUrl(error.to_string()))?;
+        if !matches!(api.scheme(), "http" | "https") {
+            return Err(Error::InvalidUrl(format!("unsupported scheme {:?}", api.scheme())))
+        }
+        Ok(Self { net, api, key, retry: http_client::Retry::default() })
+    }
+
+    /// Retry requests which fail transiently in this way, rather than [the default](http_client::Retry::default).
+    pub fn with_retry(mut self, retry: http_client::Retry) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Sends the payload as JSON, with the API key included alongside its fields.
+    async fn post<T: Serialize>(&self, endpoint: &str, payload: &T) -> Result<String> {
+        #[derive(Serialize)]
+        struct Authorized<'a, T> {
+            key: &'a str,
+            #[serde(flatten)]
+            payload: &'a T,
+        }
+
+        let body = serde_json::to_string(&Authorized { key: self.key.as_ref(), payload })?;
+        let url = self.api.join(endpoint).expect("endpoints are valid paths");
+        let request = self.net.post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body);
+        let response = http_client::send(request, &self.retry).await?;
+
+        let status = response.status();
+        let body = response.text().await?;
+        if status.is_success() { Ok(body) } else { Err(Error::from_response(status, body)) }
+    }
+
+    /// - <https://github.com/krateng/maloja/blob/master/API.md#scrobbling>
+    pub async fn scrobble(&self, scrobble: &scrobble::Scrobble<'_>) -> Result<scrobble::Acknowledgement> {
+        let response = self.post("newscrobble", scrobble).await?;
+        Ok(serde_json::from_str(&response)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn api_url() {
+        let key = || ApiKey::new("key".to_owned());
+        let client = Client::new("https://example.com/maloja", key(), reqwest::Client::new()).unwrap();
+        assert_eq!(client.api.join("newscrobble").unwrap().as_str(), "https://example.com/maloja/apis/mlj_1/newscrobble");
+        let client = Client::new("http://localhost:42010/", key(), reqwest::Client::new()).unwrap();
+        assert_eq!(client.api.as_str(), "http://localhost:42010/apis/mlj_1/");
+
+        assert!(matches!(Client::new("localhost:42010", key(), reqwest::Client::new()), Err(Error::InvalidUrl(_))));
+        assert_eq!(format!("{:?}", key()), "ApiKey(..)");
+    }
+}
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+/// A track that was listened to.
+///
+/// - <https://github.com/krateng/maloja/blob/master/API.md#scrobbling>
+#[derive(Debug, Serialize)]
+pub struct Scrobble<'a> {
+    /// The artists of the track.
+    ///
+    /// Unless told otherwise, the server splits up combined credits (like `A & B` or `A feat. B`) itself,
+    /// so an artist string as-is from a music player is fine.
+    pub artists: Vec<&'a str>,
+
+    /// The track name.
+    pub title: &'a str,
+
+    /// The album name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub album: Option<&'a str>,
+
+    /// The artists of the album, if they differ from those of the track.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub albumartists: Vec<&'a str>,
+
+    /// How long the track was listened to for, in seconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<u32>,
+
+    /// The duration of the track in seconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub length: Option<u32>,
+
+    /// The time the track was listened to.
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub time: chrono::DateTime<chrono::Utc>,
+}
+
+/// What the server said upon accepting a scrobble.
+#[derive(Debug, Deserialize)]
+pub struct Acknowledgement {
+    /// A description of what was scrobbled, such as `Scrobbled Song by Artist`.
+    #[serde(default)]
+    pub desc: Option<String>,
+    /// Anything odd about the scrobble which the server noticed, but which didn't stop it from being accepted.
+    #[serde(default)]
+    pub warnings: Vec<Warning>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Warning {
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub desc: Option<String>,
+}
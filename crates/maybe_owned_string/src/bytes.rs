@@ -0,0 +1,314 @@
+use alloc::vec::Vec;
+
+/// A value that is either a reference to a byte slice or an owned byte vector.
+#[derive(Debug)]
+pub enum MaybeOwnedBytes<'a> {
+    /// A borrowed byte slice.
+    Borrowed(&'a [u8]),
+    /// An owned byte vector.
+    Owned(Vec<u8>)
+}
+impl<'a> From<&'a MaybeOwnedBytes<'a>> for &'a [u8] {
+    fn from(value: &'a MaybeOwnedBytes<'a>) -> Self {
+        match value {
+            MaybeOwnedBytes::Borrowed(borrowed) => borrowed,
+            MaybeOwnedBytes::Owned(owned) => owned
+        }
+    }
+}
+impl<'a> From<&MaybeOwnedBytes<'a>> for Vec<u8> {
+    fn from(value: &MaybeOwnedBytes<'a>) -> Self {
+        match value {
+            MaybeOwnedBytes::Borrowed(borrowed) => borrowed.to_vec(),
+            MaybeOwnedBytes::Owned(owned) => owned.clone()
+        }
+    }
+}
+impl<'a> From<&'a [u8]> for MaybeOwnedBytes<'a> {
+    fn from(value: &'a [u8]) -> Self {
+        Self::Borrowed(value)
+    }
+}
+impl<'a, const N: usize> From<&'a [u8; N]> for MaybeOwnedBytes<'a> {
+    fn from(value: &'a [u8; N]) -> Self {
+        Self::Borrowed(value)
+    }
+}
+impl From<Vec<u8>> for MaybeOwnedBytes<'_> {
+    fn from(value: Vec<u8>) -> Self {
+        Self::Owned(value)
+    }
+}
+impl<'a> From<&'a Vec<u8>> for MaybeOwnedBytes<'a> {
+    fn from(value: &'a Vec<u8>) -> Self {
+        Self::Borrowed(value.as_slice())
+    }
+}
+impl AsRef<[u8]> for MaybeOwnedBytes<'_> {
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            Self::Borrowed(borrowed) => borrowed,
+            Self::Owned(owned) => owned
+        }
+    }
+}
+impl core::ops::Deref for MaybeOwnedBytes<'_> {
+    type Target = [u8];
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Borrowed(borrowed) => borrowed,
+            Self::Owned(owned) => owned
+        }
+    }
+}
+impl Default for MaybeOwnedBytes<'_> {
+    fn default() -> Self {
+        Self::Borrowed(&[])
+    }
+}
+impl Clone for MaybeOwnedBytes<'_> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Borrowed(borrowed) => Self::Borrowed(borrowed),
+            Self::Owned(owned) => Self::Owned(owned.clone())
+        }
+    }
+    fn clone_from(&mut self, source: &Self) {
+        match self {
+            Self::Borrowed(borrowed) => match source {
+                Self::Borrowed(rhs) => *borrowed = rhs,
+                Self::Owned(rhs) => *self = Self::Owned(rhs.clone())
+            },
+            Self::Owned(owned) => match source {
+                Self::Borrowed(rhs) => alloc::borrow::ToOwned::clone_into(*rhs, owned),
+                Self::Owned(rhs) => owned.clone_from(rhs)
+            }
+        }
+    }
+}
+impl PartialOrd for MaybeOwnedBytes<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for MaybeOwnedBytes<'_> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        let lhs = AsRef::<[u8]>::as_ref(self);
+        let rhs = AsRef::<[u8]>::as_ref(other);
+        lhs.cmp(rhs)
+    }
+}
+impl PartialEq for MaybeOwnedBytes<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        let lhs = AsRef::<[u8]>::as_ref(self);
+        let rhs = AsRef::<[u8]>::as_ref(other);
+        lhs.eq(rhs)
+    }
+}
+impl PartialEq<[u8]> for MaybeOwnedBytes<'_> {
+    fn eq(&self, other: &[u8]) -> bool {
+        let lhs = AsRef::<[u8]>::as_ref(self);
+        lhs.eq(other)
+    }
+}
+impl PartialEq<&[u8]> for MaybeOwnedBytes<'_> {
+    fn eq(&self, other: &&[u8]) -> bool {
+        let lhs = AsRef::<[u8]>::as_ref(self);
+        lhs.eq(*other)
+    }
+}
+impl PartialEq<Vec<u8>> for MaybeOwnedBytes<'_> {
+    fn eq(&self, other: &Vec<u8>) -> bool {
+        let lhs = AsRef::<[u8]>::as_ref(self);
+        lhs.eq(other.as_slice())
+    }
+}
+impl PartialEq<MaybeOwnedBytes<'_>> for dyn AsRef<[u8]> {
+    fn eq(&self, other: &MaybeOwnedBytes<'_>) -> bool {
+        let rhs = AsRef::<[u8]>::as_ref(other);
+        let lhs = self.as_ref();
+        lhs.eq(rhs)
+    }
+}
+impl Eq for MaybeOwnedBytes<'_> {}
+impl core::hash::Hash for MaybeOwnedBytes<'_> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        AsRef::<[u8]>::as_ref(self).hash(state);
+    }
+}
+impl<'a> MaybeOwnedBytes<'a> {
+    /// Whether this is a byte slice reference.
+    pub const fn is_borrowed(&self) -> bool {
+        matches!(self, Self::Borrowed(_))
+    }
+
+    /// Whether an owned byte vector is held.
+    pub const fn is_owned(&self) -> bool {
+        matches!(self, Self::Owned(_))
+    }
+
+    /// Returns the held inner owned byte vector, if these bytes are owned.
+    pub fn into_inner_owned(self) -> Option<Vec<u8>> {
+        if let Self::Owned(owned) = self {
+            Some(owned)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the held bytes as an owned vector, cloning them if they're borrowed.
+    pub fn into_vec(self) -> Vec<u8> {
+        match self {
+            Self::Owned(owned) => owned,
+            Self::Borrowed(borrowed) => borrowed.to_vec()
+        }
+    }
+
+    /// Converts into a [`MaybeOwnedBytes`] which doesn't borrow anything, cloning the bytes if they're borrowed.
+    pub fn into_owned(self) -> MaybeOwnedBytes<'static> {
+        MaybeOwnedBytes::Owned(self.into_vec())
+    }
+
+    /// Returns a mutable reference to the owned byte vector, cloning the bytes first if they're borrowed.
+    pub fn to_mut(&mut self) -> &mut Vec<u8> {
+        if let Self::Borrowed(borrowed) = *self {
+            *self = Self::Owned(borrowed.to_vec());
+        }
+        match self {
+            Self::Owned(owned) => owned,
+            Self::Borrowed(_) => unreachable!("bytes were just made owned")
+        }
+    }
+
+    /// Create a new borrowed [`MaybeOwnedBytes`] which points to this byte slice.
+    pub fn borrowed(&'a self) -> MaybeOwnedBytes<'a> {
+        Self::Borrowed(self.as_ref())
+    }
+}
+impl MaybeOwnedBytes<'static> {
+    /// Create a borrowed [`MaybeOwnedBytes`] from a static byte slice, usable in constant contexts.
+    pub const fn borrowed_static(bytes: &'static [u8]) -> Self {
+        Self::Borrowed(bytes)
+    }
+}
+
+#[cfg(feature = "std")] use std::borrow::Cow;
+#[cfg(feature = "std")] impl<'a> From<Cow<'a, [u8]>> for MaybeOwnedBytes<'a> {
+    fn from(value: Cow<'a, [u8]>) -> Self {
+        match value {
+            Cow::Borrowed(borrowed) => Self::Borrowed(borrowed),
+            Cow::Owned(owned) => Self::Owned(owned),
+        }
+    }
+}
+#[cfg(feature = "std")] impl<'a> From<MaybeOwnedBytes<'a>> for Cow<'a, [u8]> {
+    fn from(value: MaybeOwnedBytes<'a>) -> Self {
+        match value {
+            MaybeOwnedBytes::Borrowed(borrowed) => Self::Borrowed(borrowed),
+            MaybeOwnedBytes::Owned(owned) => Self::Owned(owned)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'a, 'de: 'a> serde::de::Deserialize<'de> for MaybeOwnedBytes<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: serde::de::Deserializer<'de>, {
+        use serde::de::Error;
+        struct Visitor;
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = MaybeOwnedBytes<'de>;
+
+            fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+                formatter.write_str("a byte array")
+            }
+
+            fn visit_borrowed_bytes<E>(self, value: &'de [u8]) -> Result<Self::Value, E> where E: Error {
+                Ok(MaybeOwnedBytes::Borrowed(value))
+            }
+            fn visit_byte_buf<E>(self, value: Vec<u8>) -> Result<Self::Value, E> where E: Error {
+                Ok(MaybeOwnedBytes::Owned(value))
+            }
+            fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E> where E: Error {
+                Ok(MaybeOwnedBytes::Owned(value.to_vec()))
+            }
+            // For self-describing formats without a native byte type, which serialize bytes as a sequence of integers.
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error> where A: serde::de::SeqAccess<'de> {
+                let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+                while let Some(byte) = seq.next_element()? {
+                    bytes.push(byte);
+                }
+                Ok(MaybeOwnedBytes::Owned(bytes))
+            }
+        }
+
+        deserializer.deserialize_bytes(Visitor)
+    }
+}
+#[cfg(feature = "serde")]
+impl serde::Serialize for MaybeOwnedBytes<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn construction() {
+        let bytes = [1, 2, 3];
+        let borrowed = MaybeOwnedBytes::from(&bytes);
+        assert!(borrowed.is_borrowed());
+        assert_eq!(borrowed, &bytes[..]);
+
+        let owned = MaybeOwnedBytes::from(bytes.to_vec());
+        assert!(owned.is_owned());
+        assert_eq!(owned, borrowed);
+        assert_eq!(owned.into_inner_owned(), Some(vec![1, 2, 3]));
+        assert_eq!(borrowed.into_inner_owned(), None);
+
+        assert!(MaybeOwnedBytes::from(Cow::Borrowed(&bytes[..])).is_borrowed());
+        assert!(MaybeOwnedBytes::from(Cow::<[u8]>::Owned(bytes.to_vec())).is_owned());
+    }
+
+    #[test]
+    fn into_owned() {
+        let owned: MaybeOwnedBytes<'static> = {
+            let bytes = vec![1, 2, 3];
+            MaybeOwnedBytes::from(&bytes).into_owned()
+        };
+        assert!(owned.is_owned());
+        assert_eq!(owned, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn to_mut_clones_borrowed() {
+        let bytes = [1, 2, 3];
+        let mut maybe = MaybeOwnedBytes::from(&bytes);
+        maybe.to_mut().push(4);
+        assert!(maybe.is_owned());
+        assert_eq!(maybe, vec![1, 2, 3, 4]);
+        assert_eq!(bytes, [1, 2, 3]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let bytes = MaybeOwnedBytes::from(&[0, 127, 255]);
+        let json = serde_json::to_string(&bytes).unwrap();
+        assert_eq!(json, "[0,127,255]");
+        let decoded: MaybeOwnedBytes = serde_json::from_str(&json).unwrap();
+        assert!(decoded.is_owned());
+        assert_eq!(decoded, bytes);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_borrows_when_possible() {
+        let json = String::from("\"abc\"");
+        let decoded: MaybeOwnedBytes = serde_json::from_str(&json).unwrap();
+        assert!(decoded.is_borrowed());
+        assert_eq!(decoded, &b"abc"[..]);
+    }
+}
@@ -3,19 +3,31 @@
 extern crate alloc;
 use alloc::string::{String, ToString};
 
+mod bytes;
+pub use bytes::MaybeOwnedBytes;
+
 /// A value that is either a reference to a string slice or an owned string.
+///
+/// Other variants may be added by features, so matching on it needs a wildcard arm.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum MaybeOwnedString<'a> {
     /// A borrowed string.
     Borrowed(&'a str),
     /// An owned string.
-    Owned(String)
+    Owned(String),
+    /// An owned string which is kept inline rather than allocated for if it's short enough.
+    /// Made when a borrowed string is [made owned](Self::into_owned) or deserialized without being borrowed.
+    #[cfg(feature = "compact_str")]
+    Compact(compact_str::CompactString),
 }
 impl<'a> From<&'a MaybeOwnedString<'a>> for &'a str {
     fn from(value: &'a MaybeOwnedString<'a>) -> Self {
         match value {
             MaybeOwnedString::Borrowed(borrowed) => borrowed,
-            MaybeOwnedString::Owned(owned) => owned
+            MaybeOwnedString::Owned(owned) => owned,
+            #[cfg(feature = "compact_str")]
+            MaybeOwnedString::Compact(compact) => compact,
         }
     }
 }
@@ -23,7 +35,9 @@ impl<'a> From<&MaybeOwnedString<'a>> for String {
     fn from(value: &MaybeOwnedString<'a>) -> Self {
         match value {
             MaybeOwnedString::Borrowed(borrowed) => borrowed.to_string(),
-            MaybeOwnedString::Owned(owned) => owned.clone()
+            MaybeOwnedString::Owned(owned) => owned.clone(),
+            #[cfg(feature = "compact_str")]
+            MaybeOwnedString::Compact(compact) => compact.to_string(),
         }
     }
 }
@@ -37,6 +51,12 @@ impl From<String> for MaybeOwnedString<'_> {
         Self::Owned(value)
     }
 }
+#[cfg(feature = "compact_str")]
+impl From<compact_str::CompactString> for MaybeOwnedString<'_> {
+    fn from(value: compact_str::CompactString) -> Self {
+        Self::Compact(value)
+    }
+}
 impl<'a> From<&'a String> for MaybeOwnedString<'a> {
     fn from(value: &'a String) -> Self {
         Self::Borrowed(value.as_str())
@@ -46,7 +66,9 @@ impl AsRef<str> for MaybeOwnedString<'_> {
     fn as_ref(&self) -> &str {
         match self {
             Self::Borrowed(borrowed) => borrowed,
-            Self::Owned(owned) => owned
+            Self::Owned(owned) => owned,
+            #[cfg(feature = "compact_str")]
+            Self::Compact(compact) => compact,
         }
     }
 }
@@ -55,7 +77,9 @@ impl core::ops::Deref for MaybeOwnedString<'_> {
     fn deref(&self) -> &Self::Target {
         match self {
             Self::Borrowed(borrowed) => borrowed,
-            Self::Owned(owned) => owned
+            Self::Owned(owned) => owned,
+            #[cfg(feature = "compact_str")]
+            Self::Compact(compact) => compact,
         }
     }
 }
@@ -73,19 +97,25 @@ impl Clone for MaybeOwnedString<'_> {
     fn clone(&self) -> Self {
         match self {
             Self::Borrowed(borrowed) => Self::Borrowed(borrowed),
-            Self::Owned(owned) => Self::Owned(owned.clone())
+            Self::Owned(owned) => Self::Owned(owned.clone()),
+            #[cfg(feature = "compact_str")]
+            Self::Compact(compact) => Self::Compact(compact.clone()),
         }
     }
     fn clone_from(&mut self, source: &Self) {
-        match self {
-            Self::Borrowed(borrowed) => match source {
-                Self::Borrowed(rhs) => *borrowed = rhs,
-                Self::Owned(rhs) => *self = Self::Owned(rhs.to_string())
+        match (&mut *self, source) {
+            (Self::Borrowed(borrowed), Self::Borrowed(rhs)) => *borrowed = rhs,
+            (Self::Owned(owned), Self::Owned(rhs)) => owned.clone_from(rhs),
+            (Self::Owned(owned), rhs) => {
+                owned.clear();
+                owned.push_str(rhs);
             },
-            Self::Owned(owned) => match source {
-                Self::Borrowed(rhs) => *owned = rhs.to_string(),
-                Self::Owned(rhs) => alloc::borrow::ToOwned::clone_into(rhs, owned)
-            }
+            #[cfg(feature = "compact_str")]
+            (Self::Compact(compact), rhs) => {
+                compact.clear();
+                compact.push_str(rhs);
+            },
+            (_, rhs) => *self = rhs.clone(),
         }
     }
 }
@@ -136,10 +166,7 @@ impl PartialEq<MaybeOwnedString<'_>> for dyn AsRef<str> {
 impl Eq for MaybeOwnedString<'_> {}
 impl core::hash::Hash for MaybeOwnedString<'_> {
     fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
-        state.write(match self {
-            Self::Borrowed(borrowed) => borrowed.as_bytes(),
-            Self::Owned(owned) => owned.as_bytes()
-        })
+        state.write(self.as_bytes())
     }
 }
 impl<'a> MaybeOwnedString<'a> {
@@ -150,22 +177,54 @@ impl<'a> MaybeOwnedString<'a> {
 
     /// Whether an owned string is held.
     pub const fn is_owned(&self) -> bool {
-        matches!(self, Self::Owned(_))
+        !self.is_borrowed()
     }
 
     /// Returns the held inner owned string, if this string is owned.
     pub fn into_inner_owned(self) -> Option<String> {
-        if let Self::Owned(owned) = self {
-            Some(owned)
-        } else {
-            None
+        match self {
+            Self::Borrowed(_) => None,
+            owned => Some(owned.into_string()),
+        }
+    }
+
+    /// Returns the held string as an owned string, cloning it if it's borrowed.
+    pub fn into_string(self) -> String {
+        match self {
+            Self::Owned(owned) => owned,
+            Self::Borrowed(borrowed) => borrowed.to_string(),
+            #[cfg(feature = "compact_str")]
+            Self::Compact(compact) => compact.into_string(),
+        }
+    }
+
+    /// Makes an owned copy of the string slice; kept inline rather than allocated for if it's short enough
+    /// and the `compact_str` feature is enabled.
+    fn owned_from(str: &str) -> MaybeOwnedString<'static> {
+        #[cfg(feature = "compact_str")]
+        return MaybeOwnedString::Compact(str.into());
+        #[cfg(not(feature = "compact_str"))]
+        return MaybeOwnedString::Owned(str.to_string());
+    }
+
+    /// Converts into a [`MaybeOwnedString`] which doesn't borrow anything, cloning the string if it's borrowed.
+    pub fn into_owned(self) -> MaybeOwnedString<'static> {
+        match self {
+            Self::Owned(owned) => MaybeOwnedString::Owned(owned),
+            Self::Borrowed(borrowed) => Self::owned_from(borrowed),
+            #[cfg(feature = "compact_str")]
+            Self::Compact(compact) => MaybeOwnedString::Compact(compact),
         }
     }
 
-    pub fn into_owned(self) -> String {
+    /// Returns a mutable reference to the owned string, cloning it first if it isn't held as a [`String`].
+    pub fn to_mut(&mut self) -> &mut String {
+        if !matches!(self, Self::Owned(_)) {
+            *self = Self::Owned(core::mem::take(self).into_string());
+        }
         match self {
             Self::Owned(owned) => owned,
-            Self::Borrowed(borrowed) => borrowed.to_string()
+            _ => unreachable!("string was just made owned")
         }
     }
 
@@ -174,6 +233,12 @@ impl<'a> MaybeOwnedString<'a> {
         Self::Borrowed(self.as_ref())
     }
 }
+impl MaybeOwnedString<'static> {
+    /// Create a borrowed [`MaybeOwnedString`] from a static string slice, usable in constant contexts.
+    pub const fn borrowed_static(str: &'static str) -> Self {
+        Self::Borrowed(str)
+    }
+}
 
 #[cfg(feature = "std")] use std::borrow::Cow;
 #[cfg(feature = "std")] impl<'a> From<Cow<'a, str>> for MaybeOwnedString<'a> {
@@ -188,7 +253,7 @@ impl<'a> MaybeOwnedString<'a> {
     fn from(value: MaybeOwnedString<'a>) -> Self {
         match value {
             MaybeOwnedString::Borrowed(borrowed) => Self::Borrowed(borrowed),
-            MaybeOwnedString::Owned(owned) => Self::Owned(owned)
+            owned => Self::Owned(owned.into_string()),
         }
     }
 }
@@ -212,7 +277,7 @@ impl<'a, 'de: 'a> serde::de::Deserialize<'de> for MaybeOwnedString<'a> {
                 Ok(MaybeOwnedString::Owned(value))
             }
             fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> where E: Error {
-                Ok(MaybeOwnedString::Owned(value.to_string()))
+                Ok(MaybeOwnedString::owned_from(value))
             }
         }
 
@@ -274,7 +339,7 @@ impl<'de> serde::de::Deserialize<'de> for MaybeOwnedStringDeserializeToOwned<'_>
                 Ok(MaybeOwnedStringDeserializeToOwned(MaybeOwnedString::Owned(value)))
             }
             fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> where E: Error {
-                Ok(MaybeOwnedStringDeserializeToOwned(MaybeOwnedString::Owned(value.to_string())))
+                Ok(MaybeOwnedStringDeserializeToOwned(MaybeOwnedString::owned_from(value)))
             }
         }
 
@@ -295,3 +360,80 @@ impl serde::Serialize for MaybeOwnedStringDeserializeToOwned<'_> {
 
 
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn into_owned_stops_borrowing() {
+        let owned: MaybeOwnedString<'static> = {
+            let string = String::from("borrowed");
+            let borrowed = MaybeOwnedString::from(&string);
+            assert!(borrowed.is_borrowed());
+            borrowed.into_owned()
+        };
+        assert!(owned.is_owned());
+        assert_eq!(owned, "borrowed");
+    }
+
+    #[test]
+    fn into_string() {
+        assert_eq!(MaybeOwnedString::from("borrowed").into_string(), "borrowed");
+        assert_eq!(MaybeOwnedString::from(String::from("owned")).into_string(), "owned");
+    }
+
+    #[test]
+    fn to_mut_clones_borrowed() {
+        let string = String::from("borrowed");
+        let mut maybe = MaybeOwnedString::from(&string);
+        maybe.to_mut().push_str(", then owned");
+        assert!(maybe.is_owned());
+        assert_eq!(maybe, "borrowed, then owned");
+        assert_eq!(string, "borrowed");
+
+        let mut owned = MaybeOwnedString::from(String::from("owned"));
+        owned.to_mut().push('!');
+        assert_eq!(owned.into_inner_owned().as_deref(), Some("owned!"));
+    }
+
+    #[test]
+    fn clone_from_reuses() {
+        let mut owned = MaybeOwnedString::from(String::from("a much longer string than the one replacing it"));
+        owned.clone_from(&MaybeOwnedString::from("short"));
+        assert!(owned.is_owned());
+        assert_eq!(owned, "short");
+        owned.clone_from(&MaybeOwnedString::from(String::from("owned")));
+        assert_eq!(owned, "owned");
+    }
+
+    #[cfg(feature = "compact_str")]
+    #[test]
+    fn short_strings_are_inline() {
+        let MaybeOwnedString::Compact(short) = MaybeOwnedString::from("short").into_owned() else { panic!("should be compact") };
+        assert!(!short.is_heap_allocated());
+        let MaybeOwnedString::Compact(long) = MaybeOwnedString::from("a string too long to be kept inline").into_owned() else { panic!("should be compact") };
+        assert!(long.is_heap_allocated());
+
+        let mut compact = MaybeOwnedString::from(short);
+        assert!(compact.is_owned());
+        compact.to_mut().push('!');
+        assert!(matches!(&compact, MaybeOwnedString::Owned(owned) if owned == "short!"));
+        assert_eq!(MaybeOwnedString::from(String::from("owned")).into_inner_owned().as_deref(), Some("owned"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let json = String::from("\"plain\"");
+        let borrowed: MaybeOwnedString = serde_json::from_str(&json).unwrap();
+        assert!(borrowed.is_borrowed());
+        let escaped: MaybeOwnedString = serde_json::from_str("\"\\\"quoted\\\"\"").unwrap();
+        assert!(escaped.is_owned());
+        assert_eq!(escaped, "\"quoted\"");
+        assert_eq!(serde_json::to_string(&borrowed).unwrap(), json);
+
+        let owned: MaybeOwnedStringDeserializeToOwned = serde_json::from_str(&json).unwrap();
+        assert!(owned.0.is_owned());
+    }
+}
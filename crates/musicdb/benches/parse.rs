@@ -0,0 +1,70 @@
+use std::{hint::black_box, io::Cursor};
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use musicdb::{chunk::ReadableChunk, fixture::{Fixture, Size}, MusicDB, MusicDbReader, TrackMap};
+use unaligned_u16::{endian::LittleEndian, utf16::Utf16Str};
+
+const SIZES: [Size; 3] = [Size::SMALL, Size::MEDIUM, Size::LARGE];
+
+fn full(c: &mut Criterion) {
+    let mut group = c.benchmark_group("full");
+    for size in SIZES {
+        let fixture = Fixture::build(size);
+        group.throughput(Throughput::Bytes(fixture.data.len() as u64));
+        group.bench_function(size.name, |b| b.iter_batched(
+            || fixture.data.clone().into_boxed_slice(),
+            |data| MusicDB::from_decoded(data, "Library.musicdb").expect("fixture should parse"),
+            BatchSize::LargeInput
+        ));
+    }
+}
+
+fn tracks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tracks");
+    for size in SIZES {
+        let fixture = Fixture::build(size);
+        group.throughput(Throughput::Elements(size.tracks as u64));
+        group.bench_function(size.name, |b| b.iter(|| {
+            let mut cursor = Cursor::new(fixture.data.as_slice());
+            cursor.set_position(fixture.tracks);
+            TrackMap::read(black_box(&mut cursor)).expect("fixture should parse")
+        }));
+    }
+}
+
+fn lazy(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lazy");
+    for size in SIZES {
+        let fixture = Fixture::build(size);
+        group.throughput(Throughput::Elements(size.tracks as u64));
+        group.bench_function(size.name, |b| b.iter(|| {
+            let reader = MusicDbReader::new(black_box(fixture.data.as_slice())).expect("fixture should parse");
+            for track in reader.tracks().expect("fixture should parse") {
+                black_box(track.expect("fixture should parse"));
+            }
+        }));
+    }
+}
+
+fn strings(c: &mut Criterion) {
+    let fixture = Fixture::build(Size::MEDIUM);
+    let bytes = fixture.strings.iter().map(Vec::len).sum::<usize>() as u64;
+    let mut group = c.benchmark_group("strings");
+    group.throughput(Throughput::Bytes(bytes));
+    group.bench_function("validate", |b| b.iter(|| {
+        for string in &fixture.strings {
+            black_box(Utf16Str::<LittleEndian>::new(string.as_slice()).expect("fixture strings should be valid"));
+        }
+    }));
+    let strings = fixture.strings.iter()
+        .map(|string| Utf16Str::<LittleEndian>::new(string.as_slice()).expect("fixture strings should be valid"))
+        .collect::<Vec<_>>();
+    group.bench_function("to_string", |b| b.iter(|| {
+        for string in &strings {
+            black_box(string.to_string());
+        }
+    }));
+}
+
+criterion_group!(benches, full, tracks, lazy, strings);
+criterion_main!(benches);
@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use musicdb::MusicDB;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = MusicDB::from_decoded(data.into(), "Library.musicdb");
+});
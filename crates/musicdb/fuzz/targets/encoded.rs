@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use musicdb::encoded::{decode_in_place, StreamingDecoder};
+
+fuzz_target!(|data: &[u8]| {
+    let _ = decode_in_place(&mut data.to_vec());
+    if let Ok(mut decoder) = StreamingDecoder::new(data) {
+        let _ = std::io::copy(&mut decoder, &mut std::io::sink());
+    }
+});
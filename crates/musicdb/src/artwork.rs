@@ -0,0 +1,304 @@
+//! Local artwork cache resolution.
+//!
+//! Tracks and collections reference their artwork through an opaque token (stored as `cloud-artwork-token` within their plist bomas).
+//! Apple Music keeps a local copy of the artwork it has downloaded under the `Artwork/Modified` directory of the library bundle,
+//! keyed by that token, which allows for high-quality artwork to be loaded without touching the network.
+//!
+//! Files that were added to the library themselves usually have their artwork [embedded](read_embedded) within them instead.
+use std::{collections::HashMap, path::{Path, PathBuf}, sync::OnceLock};
+use maybe_owned_string::MaybeOwnedString;
+
+/// A token referencing a piece of artwork, as stored within the library.
+///
+/// The token is of the form `{pool}/{asset path}` (e.x. `Music126/v4/2b/5a/9c/.../cover.jpg`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ArtworkToken<'a>(MaybeOwnedString<'a>);
+impl<'a> ArtworkToken<'a> {
+    pub fn new(token: impl Into<MaybeOwnedString<'a>>) -> Self {
+        Self(token.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.0.as_ref()
+    }
+
+    /// The key used for this artwork within the [`ArtworkCache`]; the token with all path separators flattened.
+    pub fn cache_key(&self) -> String {
+        self.as_str().trim_matches('/').replace('/', "_")
+    }
+
+    /// Returns the remote form of this artwork, if the token can be interpreted as one.
+    pub fn to_mzstatic(&self) -> Option<mzstatic::image::MzStaticImage<'_>> {
+        mzstatic::image::MzStaticImage::with_pool_and_token(MaybeOwnedString::Borrowed(self.as_str())).ok()
+    }
+
+    pub fn into_inner(self) -> MaybeOwnedString<'a> {
+        self.0
+    }
+}
+impl AsRef<str> for ArtworkToken<'_> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+impl core::fmt::Display for ArtworkToken<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The on-disk cache of artwork that Apple Music has downloaded.
+///
+/// What's in the directory is listed once, upon the first [lookup](Self::resolve); artwork cached since then won't be found
+/// unless the cache is [refreshed](Self::refresh).
+#[derive(Debug, Clone)]
+pub struct ArtworkCache {
+    path: PathBuf,
+    /// The cached files, by their stem (which is the [key](ArtworkToken::cache_key) of their token).
+    index: OnceLock<HashMap<String, PathBuf>>,
+}
+impl PartialEq for ArtworkCache {
+    fn eq(&self, other: &Self) -> bool {
+        self.path == other.path
+    }
+}
+impl Eq for ArtworkCache {}
+impl ArtworkCache {
+    /// The path of the cache directory relative to the library bundle (the directory containing `Library.musicdb`).
+    pub const RELATIVE_PATH: &'static str = "Artwork/Modified";
+
+    /// Creates a cache reader for the given cache directory.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into(), index: OnceLock::new() }
+    }
+
+    /// Creates a cache reader for the cache belonging to the `.musicdb` file at the given path.
+    pub fn for_library(musicdb_path: impl AsRef<Path>) -> Self {
+        let bundle = musicdb_path.as_ref().parent().unwrap_or(Path::new(""));
+        Self::new(bundle.join(Self::RELATIVE_PATH))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Forgets what's in the directory, so that it's listed again upon the next lookup.
+    pub fn refresh(&mut self) {
+        self.index = OnceLock::new();
+    }
+
+    fn index(&self) -> std::io::Result<&HashMap<String, PathBuf>> {
+        if let Some(index) = self.index.get() {
+            return Ok(index)
+        }
+        let mut index = HashMap::new();
+        for entry in std::fs::read_dir(&self.path)? {
+            let path = entry?.path();
+            if let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) {
+                index.insert(stem.to_owned(), path);
+            }
+        }
+        Ok(self.index.get_or_init(|| index))
+    }
+
+    /// Returns the path of the cached file for the given token, if it has been cached.
+    ///
+    /// The extension of the cached file varies (JPEG, PNG, HEIC, ...), so files are looked up by their stem.
+    pub fn resolve(&self, token: &ArtworkToken<'_>) -> std::io::Result<Option<PathBuf>> {
+        Ok(self.index()?.get(&token.cache_key()).cloned())
+    }
+}
+
+/// Artwork found on this machine, either in the [cache](ArtworkCache) or [embedded](read_embedded) within a track's file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LocalArtwork {
+    /// The path of the cached file.
+    Cached(PathBuf),
+    /// The image data, as it was stored within the file.
+    Embedded(Vec<u8>),
+}
+impl LocalArtwork {
+    pub fn into_bytes(self) -> std::io::Result<Vec<u8>> {
+        match self {
+            Self::Cached(path) => std::fs::read(path),
+            Self::Embedded(data) => Ok(data),
+        }
+    }
+}
+
+/// Reads the artwork embedded within the audio file at the given path, if it has any.
+///
+/// Only MPEG-4 (`.m4a`, `.mp4`, ...) files and those with ID3v2 tags (`.mp3`, `.aiff`, ...) are understood;
+/// the artwork of other files is never found.
+pub fn read_embedded(path: impl AsRef<Path>) -> std::io::Result<Option<Vec<u8>>> {
+    let data = std::fs::read(path)?;
+    let artwork = if data.starts_with(b"ID3") {
+        embedded_in_id3(&data)
+    } else if data.get(4..8) == Some(b"ftyp") {
+        embedded_in_mp4(&data)
+    } else {
+        None
+    };
+    Ok(artwork.map(<[u8]>::to_vec))
+}
+
+/// Finds the first child atom of the given type within the contents of an MPEG-4 atom.
+fn mp4_atom<'a>(mut data: &'a [u8], kind: &[u8; 4]) -> Option<&'a [u8]> {
+    while data.len() >= 8 {
+        let size = u32::from_be_bytes(data[0..4].try_into().unwrap());
+        let (header, size) = match size {
+            0 => (8, data.len()),
+            1 => (16, usize::try_from(u64::from_be_bytes(data.get(8..16)?.try_into().unwrap())).ok()?),
+            size => (8, size as usize),
+        };
+        if size < header || size > data.len() { return None }
+        if &data[4..8] == kind {
+            return Some(&data[header..size])
+        }
+        data = &data[size..];
+    }
+    None
+}
+
+/// Finds the cover art within the metadata of an MPEG-4 file (`moov/udta/meta/ilst/covr/data`).
+fn embedded_in_mp4(data: &[u8]) -> Option<&[u8]> {
+    let metadata = mp4_atom(mp4_atom(data, b"moov")?, b"udta")?;
+    let metadata = mp4_atom(metadata, b"meta")?.get(4..)?; // version & flags
+    let cover = mp4_atom(mp4_atom(metadata, b"ilst")?, b"covr")?;
+    mp4_atom(cover, b"data")?.get(8..) // type indicator & locale
+}
+
+/// Finds the picture within an ID3v2.3 or ID3v2.4 tag, preferring the front cover if there's more than one.
+fn embedded_in_id3(data: &[u8]) -> Option<&[u8]> {
+    fn syncsafe(bytes: &[u8]) -> usize {
+        bytes.iter().fold(0, |value, byte| (value << 7) | usize::from(byte & 0x7F))
+    }
+
+    let header = data.get(..10)?;
+    let version = header[3];
+    if !matches!(version, 3 | 4) { return None }
+    let flags = header[5];
+    let end = (10 + syncsafe(&header[6..10])).min(data.len());
+    let mut frames = data.get(10..end)?;
+    if flags & 0x40 != 0 { // extended header
+        let size = if version == 4 { syncsafe(frames.get(..4)?) } else { 4 + u32::from_be_bytes(frames.get(..4)?.try_into().unwrap()) as usize };
+        frames = frames.get(size..)?;
+    }
+
+    let mut found = None;
+    while let Some(header) = frames.get(..10) {
+        if header[0] == 0 { break } // padding
+        let size = if version == 4 { syncsafe(&header[4..8]) } else { u32::from_be_bytes(header[4..8].try_into().unwrap()) as usize };
+        let frame = frames.get(10..10 + size)?;
+        frames = &frames[10 + size..];
+        if &header[..4] != b"APIC" { continue }
+
+        // encoding, null-terminated MIME type, picture type, description terminated according to the encoding, data
+        let (&encoding, rest) = frame.split_first()?;
+        let rest = &rest[rest.iter().position(|&byte| byte == 0)? + 1..];
+        let (&picture_type, rest) = rest.split_first()?;
+        let picture = match encoding {
+            1 | 2 => rest.chunks_exact(2).position(|pair| pair == [0, 0]).map(|index| &rest[index * 2 + 2..]),
+            _ => rest.iter().position(|&byte| byte == 0).map(|index| &rest[index + 1..]),
+        }?;
+        const FRONT_COVER: u8 = 3;
+        if picture_type == FRONT_COVER { return Some(picture) }
+        found.get_or_insert(picture);
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_key() {
+        let token = ArtworkToken::new("Music126/v4/2b/5a/9c/2b5a9c4e/cover.jpg");
+        assert_eq!(token.cache_key(), "Music126_v4_2b_5a_9c_2b5a9c4e_cover.jpg");
+    }
+
+    fn mp4_atom_bytes(kind: &[u8; 4], contents: &[u8]) -> Vec<u8> {
+        let mut atom = (8 + contents.len() as u32).to_be_bytes().to_vec();
+        atom.extend(kind);
+        atom.extend(contents);
+        atom
+    }
+
+    #[test]
+    fn embedded_mp4() {
+        let image = b"\xFF\xD8\xFFimage";
+        let data = [&[0, 0, 0, 1][..], &[0; 4], image].concat();
+        let covr = mp4_atom_bytes(b"covr", &mp4_atom_bytes(b"data", &data));
+        let ilst = mp4_atom_bytes(b"ilst", &[mp4_atom_bytes(b"\xA9nam", b"title"), covr].concat());
+        let meta = mp4_atom_bytes(b"meta", &[&[0; 4][..], &mp4_atom_bytes(b"hdlr", &[0; 8]), &ilst].concat());
+        let moov = mp4_atom_bytes(b"moov", &[mp4_atom_bytes(b"mvhd", &[0; 4]), mp4_atom_bytes(b"udta", &meta)].concat());
+        let file = [mp4_atom_bytes(b"ftyp", b"M4A "), moov, mp4_atom_bytes(b"mdat", &[0; 16])].concat();
+        assert_eq!(embedded_in_mp4(&file), Some(&image[..]));
+
+        let truncated = &file[..file.len() - 40];
+        assert_eq!(embedded_in_mp4(truncated), None);
+    }
+
+    #[test]
+    fn embedded_id3() {
+        fn frame(id: &[u8; 4], contents: &[u8]) -> Vec<u8> {
+            [&id[..], &(contents.len() as u32).to_be_bytes(), &[0, 0], contents].concat()
+        }
+        let back = frame(b"APIC", &[&[0][..], b"image/png\0", &[4], b"back\0", b"back image"].concat());
+        let front = frame(b"APIC", &[&[1][..], b"image/jpeg\0", &[3], &[0xFF, 0xFE, b'a', 0, 0, 0], b"front image"].concat());
+        let frames = [frame(b"TIT2", b"\0title"), back, front, vec![0; 16]].concat();
+        let size = frames.len();
+        let syncsafe = [(size >> 21) as u8 & 0x7F, (size >> 14) as u8 & 0x7F, (size >> 7) as u8 & 0x7F, size as u8 & 0x7F];
+        let tag = [&b"ID3\x03\x00\x00"[..], &syncsafe, &frames, b"audio"].concat();
+        assert_eq!(embedded_in_id3(&tag), Some(&b"front image"[..]));
+    }
+
+    #[test]
+    fn cache_path() {
+        let cache = ArtworkCache::for_library("/Music/Music Library.musiclibrary/Library.musicdb");
+        assert_eq!(cache.path(), Path::new("/Music/Music Library.musiclibrary/Artwork/Modified"));
+    }
+
+    #[test]
+    fn resolve() {
+        let bundle = std::env::temp_dir().join(format!("musicdb-artwork-{}", std::process::id()));
+        let directory = bundle.join(ArtworkCache::RELATIVE_PATH);
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("Music126_v4_2b_5a_9c_2b5a9c4e_cover.jpg.png"), b"").unwrap();
+
+        let mut cache = ArtworkCache::for_library(bundle.join("Library.musicdb"));
+        let token = ArtworkToken::new("Music126/v4/2b/5a/9c/2b5a9c4e/cover.jpg");
+        assert_eq!(cache.resolve(&token).unwrap(), Some(directory.join("Music126_v4_2b_5a_9c_2b5a9c4e_cover.jpg.png")));
+
+        let later = ArtworkToken::new("Music116/v4/aa/bb/cc/aabbcc/later.jpg");
+        std::fs::write(directory.join("Music116_v4_aa_bb_cc_aabbcc_later.jpg.heic"), b"").unwrap();
+        assert_eq!(cache.resolve(&later).unwrap(), None, "the directory should only be listed once");
+        cache.refresh();
+        assert!(cache.resolve(&later).unwrap().is_some());
+
+        std::fs::remove_dir_all(&bundle).unwrap();
+    }
+
+    /// Checks the layout of the cache against that of real libraries, each of which should be a `.musiclibrary` bundle
+    /// (containing both `Library.musicdb` and `Artwork/Modified`) within the samples directory.
+    #[test]
+    #[ignore = "needs populated samples directory"]
+    fn resolve_in_samples() {
+        let samples = Path::new(env!("CARGO_MANIFEST_DIR")).join("samples");
+        let mut checked = 0;
+        for entry in std::fs::read_dir(samples).expect("fs error") {
+            let library = entry.expect("fs error").path().join("Library.musicdb");
+            if !library.exists() { continue }
+            let musicdb = crate::MusicDB::read_path(&library).expect("failed to read sample");
+            let cache = musicdb.artwork_cache();
+            assert!(cache.path().is_dir(), "{} should exist", cache.path().display());
+
+            let tokens = musicdb.tracks().values().filter_map(|track| track.artwork_token.as_ref()).collect::<Vec<_>>();
+            let resolved = tokens.iter().filter(|token| cache.resolve(token).unwrap().is_some()).count();
+            assert!(tokens.is_empty() || resolved > 0, "none of the {} artwork tokens of {} were found in its cache", tokens.len(), library.display());
+            checked += 1;
+        }
+        assert!(checked > 0, "no sample libraries were found");
+    }
+}
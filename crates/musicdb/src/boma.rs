@@ -11,7 +11,7 @@ use crate::chunks::CollectionMember;
 use crate::version::AppleMusicVersion;
 use crate::{cloud, setup_eaters, CursorReadingExtensions};
 
-use super::{convert_timestamp};
+use crate::units::{timestamp, duration_from_millis};
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct UnknownBomaError(pub u32);
@@ -121,22 +121,31 @@ impl<'a> ReadableChunk<'a> for Boma<'a> {
     type ReadError = std::io::Error;
 
     fn read(cursor: &mut Cursor<&'a [u8]>) -> Result<Self, Self::ReadError> where Self: Sized {
-        <Self as ReadableChunk>::read_signature(cursor);
+        let offset = cursor.position();
+        <Self as ReadableChunk>::read_signature(cursor)?;
         cursor.advance(4)?;
         let length = cursor.read_u32::<LittleEndian>()?;
         let subtype = cursor.read_u32::<LittleEndian>()?;
         let subtype =  BomaSubtype::try_from(subtype);
-        Ok(match subtype {
+        let boma = match subtype {
             Ok(subtype) => match subtype {
                 BomaSubtype::TrackNumerics => Self::TrackNumerics(TrackNumerics::read_content(cursor, length)?),
                 BomaSubtype::TrackPlayStatistics => Self::TrackPlayStatistics(TrackPlayStatistics::read_content(cursor, length)?),
                 BomaSubtype::CollectionItemMember => Self::CollectionMember(CollectionMember::read_content(cursor)?),
-                BomaSubtype::Utf16(variant) => Self::Utf16(BomaUtf16::read_variant_content(cursor, variant).unwrap_or_else(|error| panic!("bad boma content on {variant:?}: {error:?}"))),
+                BomaSubtype::Utf16(variant) => Self::Utf16(BomaUtf16::read_variant_content(cursor, variant).map_err(|error| match error {
+                    BomaUtf16Error::Io(error) => error,
+                    BomaUtf16Error::InvalidUtf16(error, _) => crate::chunk::invalid_data(format!("bad boma content on {variant:?}: {error}")),
+                })?),
                 BomaSubtype::Utf8Xml(variant) => Self::Utf8Xml(BomaUtf8::read_variant_content(cursor, length, variant)?),
                 BomaSubtype::Book(variant) => Self::Book(BomaBook::read_variant_content(cursor, length, variant)?)
             },
             Err(UnknownBomaError(subtype)) => Self::Unknown(UnknownBoma::read_variant_content(cursor, length, subtype)?)
-        })
+        };
+        // Content is skipped past using the length, so a short one can seek back to before the boma, having it read again.
+        if cursor.position() < offset + 16 {
+            return Err(crate::chunk::invalid_data(format!("boma @0x{offset:X} claims a length of {length}, which doesn't cover its own header")))
+        }
+        Ok(boma)
     }
 }
 impl Boma<'_> {
@@ -160,8 +169,7 @@ pub struct TrackNumerics<'a> {
     pub bitrate: Option<crate::units::KilobitsPerSecond>,
     pub date_added: Option<chrono::DateTime<chrono::Utc>>,
     pub date_modified: Option<chrono::DateTime<chrono::Utc>>,
-    /// Duration of the track, in milliseconds.
-    pub duration_ms: u32,
+    pub duration: core::time::Duration,
 
     pub cloud_catalog_album_id: Option<crate::id::cloud::Catalog<crate::chunks::Album<'a>>>,
     pub cloud_catalog_artist_id: Option<crate::id::cloud::Catalog<crate::chunks::Artist<'a>>>,
@@ -177,11 +185,11 @@ impl TrackNumerics<'_> {
         cursor.seek(SeekFrom::Current(108 - (12 + 4)))?;
         let bitrate = cursor.read_u32::<LittleEndian>()?;
         let bitrate = if bitrate == 0 { None } else { Some(crate::units::KilobitsPerSecond(bitrate)) };
-        let date_added = convert_timestamp(cursor.read_u32::<LittleEndian>()?);
+        let date_added = timestamp(cursor.read_u32::<LittleEndian>()?);
         cursor.seek(SeekFrom::Current(148 - (112 + 4)))?;
-        let date_modified = convert_timestamp(cursor.read_u32::<LittleEndian>()?);
+        let date_modified = timestamp(cursor.read_u32::<LittleEndian>()?);
         cursor.seek(SeekFrom::Current(176 - (148 + 4)))?;
-        let duration_ms = cursor.read_u32::<LittleEndian>()?; // milliseconds
+        let duration = duration_from_millis(cursor.read_u32::<LittleEndian>()?);
         let cloud_catalog_album_id = cursor.read_u32::<LittleEndian>()?;
         let cloud_catalog_album_id = core::num::NonZeroU32::new(cloud_catalog_album_id);
         let cloud_catalog_album_id = cloud_catalog_album_id.map(|id| unsafe { crate::id::cloud::Catalog::new_unchecked(id) });
@@ -204,7 +212,7 @@ impl TrackNumerics<'_> {
             bitrate,
             date_added,
             date_modified,
-            duration_ms,
+            duration,
             bytes,
 
             cloud_catalog_album_id,
@@ -213,11 +221,6 @@ impl TrackNumerics<'_> {
         })
     }
 
-    /// The duration of the track as a [`core::time::Duration`].
-    pub fn duration(&self) -> core::time::Duration {
-        core::time::Duration::from_millis(self.duration_ms as u64)
-    }
-
     /// A link to the track through Apple Music, if it can be made.
     // TODO: Store regions? Names in the URL?
     pub fn apple_music_url(&self) -> Option<String> {
@@ -248,7 +251,7 @@ impl TrackPlayStatistics {
     pub fn read_content(cursor: &mut Cursor<&[u8]>, length: u32) -> Result<Self, std::io::Error> {
         cursor.seek(SeekFrom::Current(4))?; // padding
         cursor.seek(SeekFrom::Current(8))?; // skip repeat of track ID
-        let last = convert_timestamp(cursor.read_u32::<LittleEndian>()?);
+        let last = timestamp(cursor.read_u32::<LittleEndian>()?);
         let times = cursor.read_u32::<LittleEndian>()?;
         cursor.seek(SeekFrom::Current(length as i64 - 36))?;
         Ok(Self { last, times })
@@ -265,7 +268,7 @@ pub struct UnknownBoma<'a> {
 }
 impl<'a> UnknownBoma<'a> {
     pub fn read_variant_content(cursor: &mut Cursor<&'a [u8]>, length: u32, subtype: u32) -> Result<Self, std::io::Error> {
-        let bytes = cursor.read_slice((length as usize) - 16)?;
+        let bytes = cursor.read_slice((length as usize).checked_sub(16).ok_or_else(|| crate::chunk::invalid_data("boma is shorter than its header"))?)?;
         Ok(Self { subtype, bytes })
     }
 
@@ -409,11 +412,12 @@ impl<'a> BomaUtf8<'a> {
         // awesome.
         if variant == BomaUtf8Variant::TrackLocalFilePathUrl {
             cursor.advance(16)?;
-            length -= 16;
+            length = length.saturating_sub(16);
         }
 
-        let slice = cursor.read_slice((length as usize) - 20)?;
-        Ok(Self(unsafe { str::from_utf8_unchecked(slice) }, variant))
+        let length = (length as usize).checked_sub(20).ok_or_else(|| crate::chunk::invalid_data("boma is shorter than its header"))?;
+        let slice = cursor.read_slice(length)?;
+        Ok(Self(str::from_utf8(slice).map_err(crate::chunk::invalid_data)?, variant))
     }
 }
 
@@ -461,7 +465,9 @@ pub enum BookValue<'a> {
 pub struct BomaBook<'a>(Vec<BookValue<'a>>, BookVariant);
 impl<'a> BomaBook<'a> {
     pub(crate) fn read_variant_content(cursor: &mut Cursor<&'a [u8]>, length: u32, variant: BookVariant) -> Result<Self, std::io::Error> {
-        assert_eq!(cursor.read_slice_exact::<4>()?, b"\0\0\0\0", "expected null padding");
+        if cursor.read_slice_exact::<4>()? != b"\0\0\0\0" {
+            return Err(crate::chunk::invalid_data("expected null padding"))
+        }
         let signature = cursor.read_signature()?;
 
         if signature != *b"book" {
@@ -473,7 +479,6 @@ impl<'a> BomaBook<'a> {
             return Ok(Self(vec![], variant))
         }
 
-        assert_eq!(&signature, b"book");
         let mut values = vec![];
         let destination = cursor.position() - 24 + length as u64;
         cursor.advance(48)?;
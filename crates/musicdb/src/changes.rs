@@ -0,0 +1,117 @@
+//! Finding what changed about the tracks of a library between two reads of it.
+use std::collections::HashMap;
+use std::hash::Hasher as _;
+
+use crate::{MusicDB, PersistentId, Track};
+
+/// A track which was added, removed, or modified.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ChangedTrack {
+    pub id: PersistentId<Track<'static>>,
+    pub name: Option<String>,
+}
+
+/// The tracks which changed between a [`TrackSnapshot`] and a later read of the library.
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct TrackChanges {
+    pub added: Vec<ChangedTrack>,
+    pub removed: Vec<ChangedTrack>,
+    /// Tracks whose parsed contents differ in any way, which includes their play statistics.
+    pub modified: Vec<ChangedTrack>,
+}
+impl TrackChanges {
+    pub const fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// Enough about every track of a library to tell which of them changed, without keeping the library itself around.
+#[derive(Debug, Default)]
+pub struct TrackSnapshot(HashMap<u64, (u64, Option<String>)>);
+impl TrackSnapshot {
+    pub fn take(db: &MusicDB) -> Self {
+        Self(db.tracks().iter().map(|(id, track)| {
+            (id.get_raw(), (fingerprint(track), track.name.map(ToString::to_string)))
+        }).collect())
+    }
+
+    /// Returns the changes of the given library relative to this snapshot.
+    pub fn compare(&self, db: &MusicDB) -> TrackChanges {
+        let mut changes = TrackChanges::default();
+        let tracks = db.tracks();
+
+        for (id, track) in tracks.iter() {
+            let changed = || ChangedTrack { id: PersistentId::new(id.get_raw()), name: track.name.map(ToString::to_string) };
+            match self.0.get(&id.get_raw()) {
+                None => changes.added.push(changed()),
+                Some((fingerprint_then, _)) if *fingerprint_then != fingerprint(track) => changes.modified.push(changed()),
+                Some(_) => {}
+            }
+        }
+
+        for (id, (_, name)) in &self.0 {
+            if !tracks.contains_key(&PersistentId::new(*id)) {
+                changes.removed.push(ChangedTrack { id: PersistentId::new(*id), name: name.clone() });
+            }
+        }
+
+        // the maps are unordered, so this is just to keep the output stable
+        for list in [&mut changes.added, &mut changes.removed, &mut changes.modified] {
+            list.sort_unstable_by_key(|track| track.id.get_raw());
+        }
+        changes
+    }
+}
+
+/// Hashes the [`Debug`] representation of a track, since that covers everything parsed out of it.
+fn fingerprint(track: &Track<'_>) -> u64 {
+    struct Writer(std::hash::DefaultHasher);
+    impl core::fmt::Write for Writer {
+        fn write_str(&mut self, s: &str) -> core::fmt::Result {
+            self.0.write(s.as_bytes());
+            Ok(())
+        }
+    }
+
+    let mut writer = Writer(std::hash::DefaultHasher::new());
+    core::fmt::write(&mut writer, format_args!("{track:?}")).expect("hashing can't fail");
+    writer.0.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::{Fixture, Size};
+
+    fn read(data: Vec<u8>) -> MusicDB {
+        MusicDB::from_decoded(data.into_boxed_slice(), "Library.musicdb").expect("fixture should parse")
+    }
+
+    #[test]
+    fn changes() {
+        let small = read(Fixture::build(Size::SMALL).data);
+        let snapshot = TrackSnapshot::take(&small);
+        assert!(snapshot.compare(&small).is_empty());
+
+        let larger = read(Fixture::build(Size { tracks: Size::SMALL.tracks + 2, ..Size::SMALL }).data);
+        let changes = snapshot.compare(&larger);
+        assert_eq!(changes.added.len(), 2);
+        assert_eq!(changes.added[0].name.as_deref(), Some("Track Number 100 (Remastered)"));
+        assert!(changes.removed.is_empty() && changes.modified.is_empty());
+
+        let changes = TrackSnapshot::take(&larger).compare(&small);
+        assert_eq!(changes.removed, [
+            ChangedTrack { id: PersistentId::new(0x3000_0000_0000_0064), name: Some("Track Number 100 (Remastered)".to_owned()) },
+            ChangedTrack { id: PersistentId::new(0x3000_0000_0000_0065), name: Some("Track Number 101 (Remastered)".to_owned()) },
+        ]);
+
+        // rename a track, from "Track" to "Truck"
+        let mut data = Fixture::build(Size::SMALL).data;
+        let title = "Track Number 5 (Remastered)".encode_utf16().flat_map(u16::to_le_bytes).collect::<Vec<_>>();
+        let position = data.windows(title.len()).position(|window| window == title).expect("title should be present");
+        data[position + 4] = b'u';
+        let changes = snapshot.compare(&read(data));
+        assert_eq!(changes.modified, [ChangedTrack { id: PersistentId::new(0x3000_0000_0000_0005), name: Some("Truck Number 5 (Remastered)".to_owned()) }]);
+        assert!(changes.added.is_empty() && changes.removed.is_empty());
+    }
+}
@@ -13,12 +13,20 @@ pub trait Chunk {
     }
 }
 
+/// An error for data which can be read, but doesn't make sense.
+pub(crate) fn invalid_data(message: impl Into<Box<dyn core::error::Error + Send + Sync>>) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
 #[allow(unused)]
 pub(crate) trait CursorReadingExtensions<'a>: Seek + Read + 'a {
     fn get_position(&self) -> u64;
     fn get_slice(&self) -> &'a [u8];
     fn get_slice_ahead(&self) -> &'a [u8] {
-       &self.get_slice()[self.get_position() as usize..]
+        // The position can be past the end after seeking; there's just nothing ahead then.
+        usize::try_from(self.get_position()).ok()
+            .and_then(|position| self.get_slice().get(position..))
+            .unwrap_or_default()
     }
     fn get_positioned_ptr(&self) -> *const u8 {
         self.get_slice_ahead().as_ptr()
@@ -282,16 +290,13 @@ pub trait ReadableChunk<'a>: Chunk {
         Self::read(cursor).map(Some)
     }
 
-    /// Reads the signature and asserts that it matches the expected signature.
-    /// Returns the original position of the cursor, or an error if a read error occurs.
-    /// Panics if the signature does not match.
+    /// Reads the signature and checks that it matches the expected signature.
+    /// Returns an [`InvalidData`](std::io::ErrorKind::InvalidData) error if it doesn't.
     fn read_signature(cursor: &mut Cursor<&'a [u8]>) -> Result<(), Self::ReadError> where Self: Sized {
         let signature = cursor.peek_signature()?;
-        assert!(signature == Self::SIGNATURE, "invalid header @0x{:X} ({}), expected {:?} got {signature:?}",
-            cursor.position(),
-            cursor.position(),
-            Self::SIGNATURE,
-        );
+        if signature != Self::SIGNATURE {
+            return Err(invalid_data(format!("invalid header @0x{:X}, expected {:?} got {signature:?}", cursor.position(), Self::SIGNATURE)).into())
+        }
 
         cursor.advance(Signature::LENGTH as i64)?;
         Ok(())
@@ -329,6 +334,10 @@ impl<'a, T: SizedFirstReadableChunk<'a>> ReadableChunk<'a> for T {
         let offset = cursor.position();
         Self::read_signature(cursor)?;
         let length = cursor.read_u32::<LittleEndian>()?;
+        // Any shorter, and skipping to the end would go back to (or before) the start, reading the same chunk again.
+        if length < 8 {
+            return Err(invalid_data(format!("chunk @0x{offset:X} claims a length of {length}, which doesn't cover its own header")).into())
+        }
         Self::read_sized_content(cursor, offset, length)
     }
     fn skip_extras(cursor: &mut Cursor<&'a [u8]>) {
@@ -39,7 +39,11 @@ impl<'a> SizedFirstReadableChunk<'a> for Album<'a> {
                 Boma::Utf16(BomaUtf16(value, BomaUtf16Variant::IamaAlbumCloudId)) => {
                     cloud_library_id = Some(unsafe { id::cloud::Library::new_unchecked(value) });
                 },
-                _ => panic!("unknown") // fixme good error handling
+                #[allow(unused, reason = "used in feature-gated logging")]
+                boma => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!("unexpected boma {:?} on album {persistent_id:?}", boma.get_subtype());
+                }
             }
         }
         Ok(Self {
@@ -51,6 +55,27 @@ impl<'a> SizedFirstReadableChunk<'a> for Album<'a> {
         })
     }
 }
+impl<'a> Album<'a> {
+    /// Returns the artwork token of the album, taken from the first of its tracks which has one.
+    ///
+    /// This goes through every track; to get the tokens of many albums, use [`Self::artwork_tokens_on`] instead.
+    pub fn get_artwork_token_on(&self, tracks: impl Into<&'a TrackMap<'a>>) -> Option<&'a artwork::ArtworkToken<'a>> {
+        Into::<&'a TrackMap<'a>>::into(tracks).values()
+            .filter(|track| track.album_id == self.persistent_id)
+            .find_map(|track| track.artwork_token.as_ref())
+    }
+
+    /// Returns the artwork token of every album which has one, each taken from the first of its tracks which has one.
+    pub fn artwork_tokens_on(tracks: impl Into<&'a TrackMap<'a>>) -> std::collections::HashMap<<Self as id::persistent::Possessor>::Id, &'a artwork::ArtworkToken<'a>> {
+        let mut tokens = std::collections::HashMap::new();
+        for track in Into::<&'a TrackMap<'a>>::into(tracks).values() {
+            if let Some(token) = track.artwork_token.as_ref() {
+                tokens.entry(track.album_id).or_insert(token);
+            }
+        }
+        tokens
+    }
+}
 impl<'a> id::persistent::Possessor for Album<'a> {
     type Id = PersistentId<Album<'a>>;
     #[allow(private_interfaces)]
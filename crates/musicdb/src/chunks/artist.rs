@@ -48,19 +48,24 @@ impl<'a> SizedFirstReadableChunk<'a> for Artist<'a> {
                 Boma::Utf16(BomaUtf16(value, BomaUtf16Variant::ArtistsArtistCloudLibraryId)) => {
                     cloud_library_id = Some(unsafe { id::cloud::Library::new_unchecked(value) })
                 },
-                Boma::Utf8Xml(BomaUtf8(mut value, BomaUtf8Variant::PlistArtworkURL)) => {
+                Boma::Utf8Xml(BomaUtf8(value, BomaUtf8Variant::PlistArtworkURL)) => {
                     // very rigid and robust code
-                    value = &value["<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n<plist version=\"1.0\">\n<dict>\n".len()..];
-                    if value.starts_with("\t<key>artwork-url</key>\n\t<string>") {
-                        value = &value["\t<key>artwork-url</key>\n\t<string>".len()..];
-                        value = &value[..value.len() - "</string>\n</dict>\n</plist>\n".len()];
+                    let value = value
+                        .strip_prefix("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n<plist version=\"1.0\">\n<dict>\n")
+                        .and_then(|value| value.strip_prefix("\t<key>artwork-url</key>\n\t<string>"))
+                        .and_then(|value| value.strip_suffix("</string>\n</dict>\n</plist>\n"));
+                    if let Some(value) = value {
                         let parsed = mzstatic::image::MzStaticImage::parse(value);
                         #[cfg(feature = "tracing")]
                         let parsed = parsed.inspect_err(|error| tracing::error!(?error, %value, "bad artwork URL"));
                         artwork_url = parsed.ok();
                     }
                 },
-                _ => unimplemented!()
+                #[allow(unused, reason = "used in feature-gated logging")]
+                boma => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!("unexpected boma {:?} on artist {persistent_id:?}", boma.get_subtype());
+                }
             };
         }
         
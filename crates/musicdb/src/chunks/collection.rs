@@ -1,6 +1,6 @@
 use maybe_owned_string::MaybeOwnedString;
 
-use crate::{boma::*, chunk::*, convert_timestamp, id, setup_eaters, PersistentId, Utf16Str};
+use crate::{artwork::ArtworkToken, boma::*, chunk::*, id, setup_eaters, units::timestamp, PersistentId, Utf16Str};
 use super::{derive_list, track::Track};
 
 
@@ -18,6 +18,7 @@ pub enum CollectionReadError<'a> {
 pub struct CollectionInfo<'a> {
     pub owner: Option<(Option<u32>, MaybeOwnedString<'a>)>, // no ID for (own?) user playlists
     pub description: Option<MaybeOwnedString<'a>>,
+    pub artwork_token: Option<ArtworkToken<'a>>,
 }
 impl<'a> TryFrom<&'a str> for CollectionInfo<'a> {
     type Error = plist::serde::Error<'a>;
@@ -52,15 +53,18 @@ impl<'a> TryFrom<&'a str> for CollectionInfo<'a> {
         }
 
 
-        let mut deserializer = plist::serde::Deserializer::parse(value)?.expect("a value should be present");
+        use serde::de::Error as _;
+        let mut deserializer = plist::serde::Deserializer::parse(value)?.ok_or_else(|| Self::Error::custom("playlist info is empty"))?;
         let raw = Raw::deserialize(&mut deserializer)?;
 
+        let owner_id = match raw.owner_id {
+            Some(id) => Some(id.as_ref().parse().map_err(Self::Error::custom)?),
+            None => None,
+        };
         Ok(CollectionInfo {
             description: raw.description,
-            owner: raw.owner_name.map(|name| (
-                raw.owner_id.map(|v| v.as_ref().parse().unwrap()),
-                name,
-            )),
+            artwork_token: raw.cloud_artwork_token.map(ArtworkToken::new),
+            owner: raw.owner_name.map(|name| (owner_id, name)),
         })
     }
 }
@@ -102,13 +106,49 @@ define_preset_collection_kinds! {
     Downloaded = 65,
 }
 
+/// What a collection is, as far as it's told apart within the library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollectionKind {
+    /// A playlist whose tracks are picked out by hand.
+    Playlist,
+    /// A folder of other collections, holding the tracks of everything within it.
+    ///
+    /// Nothing about a folder itself has been found to mark it as one, so it's only known to be one by
+    /// [another collection being within it](Collection::parent); an empty folder is read as a playlist.
+    Folder,
+    /// A playlist whose tracks are picked out by a set of rules.
+    Smart,
+    /// The playlist Genius made.
+    Genius,
+    /// A collection the application keeps for itself, such as "Music" or "Purchased".
+    System(PresetCollectionKind),
+}
+impl CollectionKind {
+    fn new(preset: Option<PresetCollectionKind>, smart: bool) -> Self {
+        match preset {
+            Some(PresetCollectionKind::Genius) => Self::Genius,
+            Some(preset) => Self::System(preset),
+            None if smart => Self::Smart,
+            None => Self::Playlist,
+        }
+    }
+
+    /// Whether the collection was made by the user (or Genius, on their behalf), as opposed to being kept by the application.
+    pub const fn is_user_made(self) -> bool {
+        !matches!(self, Self::System(_))
+    }
+}
+
 #[derive(Debug)]
 pub struct Collection<'a> {
     pub name: &'a Utf16Str,
     pub info: Option<CollectionInfo<'a>>, // not present on collection w/ name "Hidden Cloud PlaylistOnly Tracks"
     pub tracks: Vec<CollectionMember<'a>>,
     pub persistent_id: <Self as id::persistent::Possessor>::Id,
+    /// The folder which the collection is in, if it's in one.
+    pub parent: Option<<Self as id::persistent::Possessor>::Id>,
     pub preset_kind: Option<PresetCollectionKind>,
+    pub kind: CollectionKind,
     pub creation_date: Option<chrono::DateTime<chrono::Utc>>,
     pub modification_date: Option<chrono::DateTime<chrono::Utc>>,
 }
@@ -125,18 +165,22 @@ impl<'a> SizedFirstReadableChunk<'a> for Collection<'a> {
         let boma_count = u32!()?;
         let track_count = u32!()?;
         skip!(18 - (12 + 4))?;
-        let creation_date = convert_timestamp(u32!()?);
+        let creation_date = timestamp(u32!()?);
         skip!(26 - (18 + 4))?;
         let persistent_id = id!(Collection)?;
-        skip!(75 - (26 + 8))?;
+        skip!(46 - (26 + 8))?;
+        let parent = Some(id!(Collection)?).filter(|parent| parent.get_raw() != 0);
+        skip!(75 - (46 + 8))?;
         let preset_kind = PresetCollectionKind::from_u8(u8!()?);
         skip!(134 - (75 + 1))?;
-        let modification_date = convert_timestamp(u32!()?);
+        let modification_date = timestamp(u32!()?);
 
         skip_to_end!()?;
-        let mut tracks = Vec::with_capacity(track_count as usize);
+        // Each member takes up far more than a byte, so this can't be trusted beyond what's left to read.
+        let mut tracks = Vec::with_capacity((track_count as usize).min(cursor.get_slice_ahead().len()));
         let mut name = None;
         let mut info = None::<CollectionInfo<'a>>;
+        let mut smart = false;
 
         for boma in cursor.reading_chunks::<Boma>(boma_count as usize) {
             match boma? {
@@ -145,7 +189,8 @@ impl<'a> SizedFirstReadableChunk<'a> for Collection<'a> {
                 Boma::CollectionMember(member) => tracks.push(member),
                 _boma => {
                     match _boma.get_subtype() {
-                        Err(UnknownBomaError(201 | 202)) => continue, // TODO: figure out what these are
+                        // Only smart playlists have these; presumably they hold its rules, like the same subtypes do in the older `.itl` format.
+                        Err(UnknownBomaError(Self::SMART_INFO_BOMA_SUBTYPE | Self::SMART_CRITERIA_BOMA_SUBTYPE)) => smart = true,
                         #[allow(unused, reason = "used in feature-gated logging")]
                         subtype => {
                             #[cfg(feature = "tracing")]
@@ -157,10 +202,15 @@ impl<'a> SizedFirstReadableChunk<'a> for Collection<'a> {
         }
         let name = name.ok_or(CollectionReadError::LackingBoma(BomaUtf16Variant::PlaylistName.into()))?;
 
-        Ok(Self { name, info, tracks, persistent_id, preset_kind, creation_date, modification_date })
+        let kind = CollectionKind::new(preset_kind, smart);
+
+        Ok(Self { name, info, tracks, persistent_id, parent, preset_kind, kind, creation_date, modification_date })
     }
 }
 impl<'a> Collection<'a> {
+    const SMART_INFO_BOMA_SUBTYPE: u32 = 201;
+    const SMART_CRITERIA_BOMA_SUBTYPE: u32 = 202;
+
     pub fn get_tracks_on<'b: 'a>(&'b self, tracks: &'a crate::TrackMap<'a>) -> impl Iterator<Item = Option<&'a Track<'a>>> + 'b {
         self.tracks.iter().map(move |member| tracks.get(&member.track_persistent_id))
     }
@@ -194,7 +244,10 @@ impl CollectionMember<'_> {
         use byteorder::LittleEndian as LE;
         use std::io::Seek as _;
         cursor.advance(4)?;
-        assert_eq!(&cursor.read_signature()?, b"ipfa");
+        let signature = cursor.read_signature()?;
+        if signature != *b"ipfa" {
+            return Err(crate::chunk::invalid_data(format!("expected collection member signature, got {signature:?}")))
+        }
         let length = cursor.read_u32::<LE>()?;
         cursor.advance(12)?;
         let track_persistent_id = cursor.read_u64::<LE>()?.into();
@@ -204,3 +257,85 @@ impl CollectionMember<'_> {
 }
 
 derive_list!(pub CollectionList, Collection<'a>, *b"lPma");
+impl CollectionList<'_> {
+    /// Marks those which other collections are within as [folders](CollectionKind::Folder), which can't be told from a collection alone.
+    pub(crate) fn classify_folders(&mut self) {
+        let parents = self.0.iter().filter_map(|collection| collection.parent).collect::<std::collections::HashSet<_>>();
+        for collection in &mut self.0 {
+            if parents.contains(&collection.persistent_id) && collection.kind.is_user_made() {
+                collection.kind = CollectionKind::Folder;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kinds() {
+        assert_eq!(CollectionKind::new(None, false), CollectionKind::Playlist);
+        assert_eq!(CollectionKind::new(None, true), CollectionKind::Smart);
+        assert_eq!(CollectionKind::new(Some(PresetCollectionKind::Genius), false), CollectionKind::Genius);
+        // the likes of "Purchased" are smart playlists too, but kept by the application
+        assert_eq!(CollectionKind::new(Some(PresetCollectionKind::Purchased), true), CollectionKind::System(PresetCollectionKind::Purchased));
+        assert!(!CollectionKind::System(PresetCollectionKind::Music).is_user_made());
+        assert!(CollectionKind::Genius.is_user_made());
+    }
+
+    #[test]
+    fn folders() {
+        use crate::fixture::{Fixture, FOLDER};
+        let db = crate::MusicDB::from_decoded(Fixture::folder().data.into_boxed_slice(), "Library.musicdb").expect("fixture should parse");
+        let kinds = db.collections().iter().map(|collection| (collection.name.to_string(), collection.kind, collection.parent.map(|parent| parent.get_raw()))).collect::<Vec<_>>();
+        assert_eq!(kinds, [
+            ("Seasons".to_owned(), CollectionKind::Folder, None),
+            ("Summer".to_owned(), CollectionKind::Playlist, Some(FOLDER)),
+            ("Winter".to_owned(), CollectionKind::Playlist, Some(FOLDER)),
+            // nothing's in it, so it can't be told apart from a folder which is empty
+            ("Nothing Yet".to_owned(), CollectionKind::Playlist, None),
+        ]);
+        assert!(CollectionKind::Folder.is_user_made());
+    }
+
+    /// See [`crate::units::tests::against_exported_samples`].
+    #[test]
+    #[ignore = "needs populated samples directory"]
+    fn kinds_against_exported_samples() {
+        use xml::{arena::DefaultArena, plist::PlistValue, span::Span, Document};
+
+        let samples = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("samples");
+        let mut compared = 0;
+        for entry in std::fs::read_dir(samples).expect("fs error") {
+            let folder = entry.expect("fs error").path();
+            let (library, export) = (folder.join("Library.musicdb"), folder.join("Library.xml"));
+            if !library.exists() || !export.exists() { continue }
+
+            let musicdb = crate::MusicDB::read_path(&library).expect("failed to read sample");
+            let export = std::fs::read_to_string(&export).expect("fs error");
+            let mut arena = DefaultArena::default();
+            let document = Document::parse(&Span::new_root(&export), &mut arena).expect("failed to parse export");
+            let export = PlistValue::from_root(document.root(&arena), &arena).expect("failed to interpret export");
+            let playlists = export.as_dictionary().and_then(|export| export.get("Playlists")).and_then(PlistValue::as_array).expect("export should list playlists");
+
+            for exported in playlists {
+                let exported = exported.as_dictionary().expect("exported playlist should be a dictionary");
+                let id = exported.get("Playlist Persistent ID").and_then(PlistValue::as_str).expect("exported playlist should have a persistent ID");
+                let Some(collection) = musicdb.collections().iter().find(|collection| collection.persistent_id == PersistentId::from_hex(id).expect("bad persistent ID")) else { continue };
+                let parent = exported.get("Parent Persistent ID").and_then(PlistValue::as_str).map(|id| PersistentId::from_hex(id).expect("bad persistent ID"));
+                assert_eq!(collection.parent, parent, "parent of {id}");
+                let expected = match exported.get("Distinguished Kind").and_then(PlistValue::as_integer) {
+                    Some(kind) => CollectionKind::new(u8::try_from(kind).ok().and_then(PresetCollectionKind::from_u8), false),
+                    // those which are empty can't be told apart from playlists
+                    None if exported.get("Folder").is_some() => if collection.kind == CollectionKind::Playlist && collection.tracks.is_empty() { continue } else { CollectionKind::Folder },
+                    None if exported.get("Smart Info").is_some() => CollectionKind::Smart,
+                    None => CollectionKind::Playlist,
+                };
+                assert_eq!(collection.kind, expected, "kind of {id}");
+                compared += 1;
+            }
+        }
+        assert!(compared > 0, "no collections of exported sample libraries were found");
+    }
+}
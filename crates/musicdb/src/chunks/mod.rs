@@ -19,8 +19,9 @@ use crate::{id, setup_eaters, chunk::*};
 pub struct SectionBoundary<T>  {
     // r0x0..3 ; b"hsma"
     // boundary_length: u32, // r0x4..7
-    // section_length: u32, // r0x8..12
-    _subtype: T, // r0x12..15
+    /// The length of the section, including this boundary.
+    pub(crate) section_length: u32, // r0x8..12
+    pub(crate) subtype: T, // r0x12..15
     // ; ...zeros, len-12
 }
 impl<T> Chunk for SectionBoundary<T> {
@@ -31,13 +32,26 @@ impl<T: From<u32>> SizedFirstReadableChunk<'_> for SectionBoundary<T> {
 
     fn read_sized_content(cursor: &mut std::io::Cursor<&'_ [u8]>, offset: u64, length: u32) -> Result<Self, Self::ReadError> {
         setup_eaters!(cursor, offset, length);
-        skip!(4)?; // len of section
+        let section_length = u32!()?;
         let subtype = T::from(u32!()?);
         skip_to_end!()?;
-        Ok(Self { _subtype: subtype })
+        Ok(Self { section_length, subtype })
     }
 }
 
+/// The kind of section that a [`SectionBoundary`] precedes.
+#[derive(strum_macros::FromRepr, Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum SectionKind {
+    Tracks = 1,
+    Collections = 2,
+    Header = 3,
+    Albums = 4,
+    Artists = 5,
+    LibraryMaster = 6,
+    Accounts = 15,
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum ListReadError<T: core::fmt::Debug> {
     #[error("bad item: {0}")]
@@ -53,6 +67,11 @@ impl<T: core::fmt::Debug> From<std::io::Error> for ListReadError<T> {
 
 
 pub struct List<'a, T>(pub Vec<T>, PhantomData<&'a ()>);
+impl<T> Default for List<'_, T> {
+    fn default() -> Self {
+        Self(Vec::new(), PhantomData)
+    }
+}
 #[allow(private_bounds)]
 impl<'a, T: ReadableChunk<'a>> List<'a, T> {
     pub(crate) fn read_contents(cursor: &mut std::io::Cursor<&'a [u8]>, _: u64, length: u32) -> Result<Self, ListReadError<<T as ReadableChunk<'a>>::ReadError>> {
@@ -62,7 +81,8 @@ impl<'a, T: ReadableChunk<'a>> List<'a, T> {
         cursor.advance(length as i64 - 12).map_err(ListReadError::BadListHeader)?;
 
         
-        let mut items = Vec::with_capacity(item_count);
+        // The count can't be trusted beyond what's left to read, as every item takes up far more than a byte.
+        let mut items = Vec::with_capacity(item_count.min(cursor.get_slice_ahead().len()));
         for item in cursor.reading_chunks::<T>(item_count) {
             items.push(item.map_err(ListReadError::BadItem)?);
         }
@@ -98,12 +118,17 @@ impl<'a, T: ReadableChunk<'a>> IntoIterator for &List<'a, T> where Self: 'a {
 use std::collections::HashMap;
 
 pub struct Map<'a, T: id::persistent::Possessor>(pub HashMap<T::Id, T>, PhantomData<&'a ()>);
+impl<T: id::persistent::Possessor> Default for Map<'_, T> {
+    fn default() -> Self {
+        Self(HashMap::new(), PhantomData)
+    }
+}
 impl<'a, T: ReadableChunk<'a> + id::persistent::Possessor> Map<'a, T> {
     pub(crate) fn read_contents(cursor: &mut std::io::Cursor<&'a [u8]>, offset: u64, length: u32) -> Result<Self, ListReadError<<T as ReadableChunk<'a>>::ReadError>> where <T as id::persistent::Possessor>::Id: core::fmt::Debug {
         setup_eaters!(cursor, offset, length);
         let item_count = u32!().map_err(ListReadError::BadListHeader)? as usize;
         skip_to_end!().map_err(ListReadError::BadListHeader)?;
-        let mut items = HashMap::<T::Id, T>::with_capacity(item_count);
+        let mut items = HashMap::<T::Id, T>::with_capacity(item_count.min(cursor.get_slice_ahead().len()));
         for item in cursor.reading_chunks::<T>(item_count) {
             let item = item.map_err(ListReadError::BadItem)?;
             items.insert(item.get_persistent_id(), item);
@@ -1,20 +1,25 @@
 use maybe_owned_string::MaybeOwnedString;
 use mzstatic::image::MzStaticImage;
 
-use crate::{boma::*, chunk::*, id, setup_eaters, PersistentId, Utf16Str};
+use crate::{artwork::ArtworkToken, boma::*, chunk::*, id, setup_eaters, PersistentId, Utf16Str};
 use super::{album::Album, artist::Artist, derive_map, AlbumMap, ArtistMap};
 
 
 #[derive(thiserror::Error, Debug)]
-pub enum TrackReadError {
+pub enum TrackReadError<'a> {
     #[error("io error: {0}")]
     Io(#[from] std::io::Error),
     #[error("missing required boma: {0:?}")]
     LackingBoma(BomaSubtype),
     // #[error("invalid utf-16 string: {0}")]
     // InvalidUtf16(unaligned_u16::utf16::error::InvalidUtf16)
-    // #[cfg_attr(feature = "serde", error("plist deserialization error: {0}"))]
-    // #[cfg(feature = "serde")] Deserialization(#[from] plist::Error),
+    #[error("plist deserialization error: {0}")]
+    Deserialization(Box<plist::serde::Error<'a>>),
+}
+impl<'a> From<plist::serde::Error<'a>> for TrackReadError<'a> {
+    fn from(error: plist::serde::Error<'a>) -> Self {
+        Self::Deserialization(Box::new(error))
+    }
 }
 
 // TODO: find play count >:-[
@@ -37,12 +42,15 @@ pub struct Track<'a> {
     pub sort_order_composer: Option<&'a Utf16Str>,
 
     pub artwork: Option<MzStaticImage<'a>>,
+    /// A reference to the artwork, which can be resolved against the local [`ArtworkCache`](crate::artwork::ArtworkCache).
+    pub artwork_token: Option<ArtworkToken<'a>>,
 
 
     pub numerics: TrackNumerics<'a>,
     pub played: TrackPlayStatistics,
     pub composer: Option<&'a Utf16Str>,
     pub kind: Option<&'a Utf16Str>,
+    /// The copyright notice (e.x. "℗ 2020 Some Label"), which is usually only present on tracks from Apple Music.
     pub copyright: Option<&'a Utf16Str>,
     pub comment: Option<&'a Utf16Str>,
 
@@ -50,8 +58,14 @@ pub struct Track<'a> {
     pub purchaser_email: Option<&'a Utf16Str>,
     pub purchaser_name: Option<&'a Utf16Str>,
     pub grouping: Option<&'a Utf16Str>,
+    /// The name of the (classical) work the track is a movement of; shown instead of its name when "Use Work & Movement" is checked.
     pub classical_work_name: Option<&'a Utf16Str>,
+    /// The name of the movement the track is of its [work](Self::classical_work_name).
     pub classical_movement_title: Option<&'a Utf16Str>,
+    /// The lyrics of the track, as Apple Music has them.
+    ///
+    /// Lyrics which were set locally haven't been found within the library; they seem to only be written to the tags of the file itself.
+    pub lyrics: Option<MaybeOwnedString<'a>>,
     pub fairplay_info: Option<&'a Utf16Str>,
     // appears on downloading for offline, maybe purchasing? no examples to test
     pub local_file_path: Option<&'a Utf16Str>,
@@ -60,7 +74,7 @@ impl Chunk for Track<'_> {
     const SIGNATURE: Signature = Signature::new(*b"itma");
 }
 impl<'a> SizedFirstReadableChunk<'a> for Track<'a> {
-    type ReadError = TrackReadError;
+    type ReadError = TrackReadError<'a>;
 
     fn read_sized_content(cursor: &mut std::io::Cursor<&'a [u8]>, offset: u64, length: u32) -> Result<Self, Self::ReadError> {
         setup_eaters!(cursor, offset, length);
@@ -95,8 +109,10 @@ impl<'a> SizedFirstReadableChunk<'a> for Track<'a> {
         let mut grouping = None;
         let mut classical_work_name = None;
         let mut classical_movement_title = None;
+        let mut lyrics = None;
         let mut fairplay_info = None;
         let mut artwork = None;
+        let mut artwork_token = None;
         let mut local_file_path = None;
         let mut cloud_id = None;
 
@@ -104,7 +120,7 @@ impl<'a> SizedFirstReadableChunk<'a> for Track<'a> {
             ($boma: expr, [$(($variant: ident, $variable: ident)$(,)?)*], $fallback: expr) => {
                 match $boma {
                     $(Boma::Utf16(BomaUtf16(value, BomaUtf16Variant::$variant)) => { $variable = Some(value) }),*
-                    boma => $fallback(boma)
+                    boma => $fallback(boma)?
                 }
             }
         }
@@ -153,10 +169,12 @@ impl<'a> SizedFirstReadableChunk<'a> for Track<'a> {
                         }
 
 
-                        let mut deserializer = plist::serde::Deserializer::parse(value).unwrap().expect("a value should be present");
-                        let raw = Raw::deserialize(&mut deserializer).unwrap(); // TODO: Handle
-                    
-                        artwork = raw.cloud_artwork_token.and_then(|v| MzStaticImage::with_pool_and_token(v).ok())
+                        let Some(mut deserializer) = plist::serde::Deserializer::parse(value)? else { return Ok(()) };
+                        let raw = Raw::deserialize(&mut deserializer)?;
+
+                        artwork = raw.cloud_artwork_token.clone().and_then(|v| MzStaticImage::with_pool_and_token(v).ok());
+                        artwork_token = raw.cloud_artwork_token.map(ArtworkToken::new);
+                        lyrics = raw.cloud_lyrics.filter(|lyrics| !lyrics.trim().is_empty());
                     }
                     Boma::Utf8Xml(BomaUtf8(value, BomaUtf8Variant::PlistCloudDownloadInformation)) => {
                         use serde::Deserialize as _;
@@ -170,8 +188,8 @@ impl<'a> SizedFirstReadableChunk<'a> for Track<'a> {
                         }
 
 
-                        let mut deserializer = plist::serde::Deserializer::parse(value).unwrap().expect("a value should be present");
-                        let raw = Raw::deserialize(&mut deserializer).unwrap(); // TODO: Handle
+                        let Some(mut deserializer) = plist::serde::Deserializer::parse(value)? else { return Ok(()) };
+                        let raw = Raw::deserialize(&mut deserializer)?;
                         cloud_id = raw.cloud_universal_library_id.and_then(|v| unsafe { id::cloud::Library::new_unchecked(v) }.into());
                     } 
                     Boma::Utf8Xml(BomaUtf8(_, BomaUtf8Variant::TrackLocalFilePathUrl)) => {}, // TODO
@@ -185,12 +203,14 @@ impl<'a> SizedFirstReadableChunk<'a> for Track<'a> {
                         }
                     }
                 }
+                Ok::<_, TrackReadError<'a>>(())
             });
         }
 
 
         Ok(Self {
             artwork,
+            artwork_token,
             name,
             cloud_id,
             album_id,
@@ -216,6 +236,7 @@ impl<'a> SizedFirstReadableChunk<'a> for Track<'a> {
             grouping,
             classical_movement_title,
             classical_work_name,
+            lyrics,
             fairplay_info,
             local_file_path
         })
@@ -245,7 +266,37 @@ impl<'a> Track<'a> {
     pub fn get_album_on(&'a self, albums: impl Into<&'a AlbumMap<'a>> + 'a) -> Option<&'a Album<'a>> {
         Into::<&'a AlbumMap<'a>>::into(albums).get(&self.album_id)
     }
+    /// Returns the path to the locally cached artwork of this track, if it has any.
+    pub fn get_cached_artwork_path(&self, cache: &crate::artwork::ArtworkCache) -> Option<std::path::PathBuf> {
+        cache.resolve(self.artwork_token.as_ref()?).ok().flatten()
+    }
+    /// Returns the artwork embedded within the file of this track, if it has a file on this machine and that has any.
+    pub fn get_embedded_artwork(&self) -> Option<Vec<u8>> {
+        let path = self.local_file_path?.to_string();
+        crate::artwork::read_embedded(&path)
+            .inspect_err(|_error| {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(?_error, %path, "failed to read embedded artwork");
+            })
+            .ok().flatten()
+    }
+    /// Returns the artwork of this track that's on this machine, preferring the [cached](Self::get_cached_artwork_path) copy
+    /// over that [embedded](Self::get_embedded_artwork) within its file.
+    pub fn get_local_artwork(&self, cache: &crate::artwork::ArtworkCache) -> Option<crate::artwork::LocalArtwork> {
+        self.get_cached_artwork_path(cache).map(crate::artwork::LocalArtwork::Cached)
+            .or_else(|| self.get_embedded_artwork().map(crate::artwork::LocalArtwork::Embedded))
+    }
 }
 
 derive_map!(pub TrackMap, Track<'a>, *b"ltma");
 
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn lyrics() {
+        use crate::fixture::{Fixture, COLLABORATION_TRACK};
+        let db = crate::MusicDB::from_decoded(Fixture::collaboration().data.into_boxed_slice(), "Library.musicdb").expect("fixture should parse");
+        let track = db.tracks().get(&crate::PersistentId::new(COLLABORATION_TRACK)).expect("track should be in the library");
+        assert_eq!(track.lyrics.as_deref(), Some("Down where it's wetter\nIt's better, take it from me"));
+    }
+}
@@ -85,6 +85,16 @@ impl Destination {
     }
 }
 
+/// How the changes found by [`Command::Watch`] are written.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ChangeFormat {
+    /// One line per changed track.
+    #[default]
+    Log,
+    /// One JSON object per reload.
+    Json,
+}
+
 #[derive(Subcommand)]
 pub enum Command {
     /// Export a decrypted (but not yet parsed) `.musicdb` file.
@@ -116,6 +126,33 @@ pub enum Command {
         ids: Option<Vec<String>>,
     },
 
+    /// Write the artwork of a track or album that's on this machine, taken from Apple Music's cache or else embedded within a file.
+    Artwork {
+        /// The path to the `Library.musicdb` file to look within. Defaults to the one of the current user.
+        #[arg(short, long, value_name = "PATH")]
+        path: Option<PathBuf>,
+
+        /// The persistent-ID of the track or album. Base-10 or base-16 (case-insensitive).
+        #[arg(short, long, value_name = "ID")]
+        id: String,
+
+        /// The destination path ('-' for stdout) to write to.
+        /// Must be explicitly provided, unless being piped (in which case stdout is chosen).
+        #[arg(short, long, value_name = "TARGET", alias = "out", required = !*IS_PIPING_OUTPUT)]
+        output: Option<Destination>,
+    },
+
+    /// Watch a `.musicdb` file, re-reading it whenever it changes and printing the tracks which were added, removed, or modified.
+    Watch {
+        /// The path to the `Library.musicdb` file to watch. Defaults to the one of the current user.
+        #[arg(short, long, value_name = "PATH")]
+        path: Option<PathBuf>,
+
+        /// How to print the changes.
+        #[arg(short, long, value_enum, default_value_t)]
+        format: ChangeFormat,
+    },
+
     /// Print the compression ratio(s) of the `.musicdb` file(s), recursively searching directories.
     #[cfg(debug_assertions)]
     #[clap(alias = "ratio")]
@@ -132,11 +169,12 @@ impl Command {
 
         match self {
             Command::Decrypt { path, output } => {
-                let decoded = MusicDB::decode(path.unwrap_or_else(MusicDB::default_path)).expect("failed to extract raw data");
+                let file = std::fs::File::open(path.unwrap_or_else(MusicDB::default_path)).expect("failed to open file");
+                let mut decoder = crate::encoded::StreamingDecoder::new(std::io::BufReader::new(file)).expect("failed to read file header");
                 let is_stdout = output.as_ref() == Some(&Destination::Stdout);
                 let mut writer = std::io::BufWriter::new(output.unwrap_or_default().into_writer());
 
-                if let Err(error) = writer.write_all(&decoded) {
+                if let Err(error) = std::io::copy(&mut decoder, &mut writer).and_then(|_| writer.flush()) {
                     eprintln!("Write error: {error:?}");
                 } else if !is_stdout {
                     println!("Done!");
@@ -186,7 +224,86 @@ impl Command {
                     println!("Done!");
                 }
             }
-        
+
+            Command::Artwork { path, id, output } => {
+                let musicdb = MusicDB::read_path(path.unwrap_or_else(MusicDB::default_path)).expect("failed to read musicdb");
+                let raw = parse_ambiguous_id(&id).expect("bad id");
+                let tracks = musicdb.tracks();
+
+                // an album's artwork is that of the first of its tracks to have any
+                let candidates: Vec<_> = if let Some(track) = tracks.get(&crate::PersistentId::new(raw)) {
+                    vec![track]
+                } else if musicdb.albums().contains_key(&crate::PersistentId::new(raw)) {
+                    tracks.values().filter(|track| track.album_id.get_raw() == raw).collect()
+                } else {
+                    eprintln!("No track or album has the ID {id}.");
+                    std::process::exit(1)
+                };
+
+                let cache = musicdb.artwork_cache();
+                let Some(artwork) = candidates.iter().find_map(|track| track.get_local_artwork(&cache)) else {
+                    eprintln!("No artwork of {id} could be found on this machine.");
+                    std::process::exit(1)
+                };
+
+                let is_stdout = output.as_ref() == Some(&Destination::Stdout);
+                let mut writer = std::io::BufWriter::new(output.unwrap_or_default().into_writer());
+                if let Err(error) = artwork.into_bytes().and_then(|bytes| writer.write_all(&bytes)).and_then(|()| writer.flush()) {
+                    eprintln!("Write error: {error:?}");
+                } else if !is_stdout {
+                    println!("Done!");
+                }
+            }
+
+            Command::Watch { path, format } => {
+                use crate::changes::TrackSnapshot;
+                use notify::Watcher as _;
+
+                let mut musicdb = MusicDB::read_path(path.unwrap_or_else(MusicDB::default_path)).expect("failed to read musicdb");
+                let mut snapshot = TrackSnapshot::take(&musicdb);
+
+                // The file is replaced rather than written to, so it's the directory that has to be watched.
+                let (tx, rx) = std::sync::mpsc::channel();
+                let mut watcher = notify::recommended_watcher(tx).expect("failed to create file watcher");
+                let directory = musicdb.path().parent().expect("musicdb path should have a parent directory").to_path_buf();
+                watcher.watch(&directory, notify::RecursiveMode::NonRecursive).expect("failed to watch musicdb directory");
+                eprintln!("Watching {} for changes...", musicdb.path().display());
+
+                let mut stdout = std::io::stdout().lock();
+                while let Ok(event) = rx.recv() {
+                    if let Err(error) = event {
+                        eprintln!("Watch error: {error:?}");
+                        continue
+                    }
+                    // A save comes as a burst of events; wait for it to settle.
+                    while rx.recv_timeout(core::time::Duration::from_millis(500)).is_ok() {}
+
+                    match musicdb.reload_if_changed() {
+                        Ok(false) => continue,
+                        Ok(true) => {}
+                        Err(error) => {
+                            eprintln!("Failed to re-read musicdb: {error}");
+                            continue
+                        }
+                    }
+
+                    let changes = snapshot.compare(&musicdb);
+                    snapshot = TrackSnapshot::take(&musicdb);
+                    if changes.is_empty() { continue }
+
+                    let written = match format {
+                        ChangeFormat::Json => writeln!(stdout, "{}", serde_json::to_string(&changes).expect("changes should serialize")),
+                        ChangeFormat::Log => [("+", &changes.added), ("-", &changes.removed), ("~", &changes.modified)].into_iter()
+                            .flat_map(|(symbol, tracks)| tracks.iter().map(move |track| (symbol, track)))
+                            .try_for_each(|(symbol, track)| writeln!(stdout, "{symbol} {} {}", track.id, track.name.as_deref().unwrap_or("<untitled>"))),
+                    };
+                    if let Err(error) = written.and_then(|()| stdout.flush()) {
+                        eprintln!("Write error: {error:?}");
+                        break
+                    }
+                }
+            }
+
             #[cfg(debug_assertions)]
             Command::Ratios { paths } => {
                 use crate::MusicDB;
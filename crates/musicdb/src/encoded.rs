@@ -12,6 +12,11 @@
 //!  1. It is compressed following the DEFLATE algorithm.
 //!  2. It is encrypted using AES-128 in ECB mode.
 //! 
+//! ## Streaming
+//!
+//! [`decode_in_place`] requires the entire file to be held in memory. For larger files, [`StreamingDecoder`] can instead be wrapped around
+//! any [`Read`] implementor to decrypt and decompress the data as it's consumed, using a constant amount of memory.
+//!
 //! ### Partial Encryption
 //! 
 //! There are two situations in which unencrypted (though still compressed) data may be appended at the end of the encrypted data:
@@ -50,7 +55,9 @@ pub fn decode_in_place<'a>(data: &'a mut [u8]) -> Result<(Vec<u8>, PackedFileInf
     });
 
     let info = PackedFileInfo::read(&mut cursor).map_err(DecodeError::Io)?;
-    let data = &mut data.get_mut()[info.header_size as usize..];
+    let data = data.get_mut().get_mut(info.header_size as usize..).ok_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "file is smaller than its own header")
+    })?;
     let split_at = (info.max_encrypted_byte_count as usize).min(data.len() & !0x0F);
 
     Ok((decode_split_encryption(data, split_at)?, info))
@@ -83,6 +90,117 @@ fn decompress(source: impl Read, compressed_size: usize) -> Result<Vec<u8>, std:
 }
 
 
+/// A [`Read`] adapter which decrypts and decompresses an encoded file as it is read.
+///
+/// ```no_run
+/// # use musicdb::encoded::StreamingDecoder;
+/// let file = std::fs::File::open(musicdb::MusicDB::default_path())?;
+/// let mut decoder = StreamingDecoder::new(std::io::BufReader::new(file))?;
+/// std::io::copy(&mut decoder, &mut std::io::stdout())?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct StreamingDecoder<R: Read> {
+    header: Box<[u8]>,
+    inner: flate2::read::ZlibDecoder<StreamingDecryptor<R>>,
+}
+impl<R: Read> StreamingDecoder<R> {
+    /// Reads the file header from the reader, preparing to decode the rest of its content.
+    pub fn new(mut reader: R) -> Result<Self, std::io::Error> {
+        use byteorder::{LittleEndian, ReadBytesExt};
+
+        let mut prelude = [0; Signature::LENGTH + 4];
+        reader.read_exact(&mut prelude)?;
+        let header_size = (&prelude[Signature::LENGTH..]).read_u32::<LittleEndian>()? as usize;
+        if header_size < prelude.len() {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "header is smaller than its own prelude"))
+        }
+
+        // Read through `take` rather than into a buffer of the claimed size, so a bogus size can't have a huge buffer allocated.
+        let mut header = prelude.to_vec();
+        (&mut reader).take((header_size - prelude.len()) as u64).read_to_end(&mut header)?;
+        if header.len() != header_size {
+            return Err(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "file is smaller than its own header"))
+        }
+        let header = header.into_boxed_slice();
+
+        let info = PackedFileInfo::read(&mut std::io::Cursor::new(&header[..]))?;
+        let decryptor = StreamingDecryptor::new(reader, info.max_encrypted_byte_count as usize);
+
+        Ok(Self { header, inner: flate2::read::ZlibDecoder::new(decryptor) })
+    }
+
+    /// Returns the information from the header of the file.
+    pub fn info(&self) -> PackedFileInfo<'_> {
+        PackedFileInfo::read(&mut std::io::Cursor::new(&self.header[..])).expect("header was already read once")
+    }
+}
+impl<R: Read> Read for StreamingDecoder<R> {
+    fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+        self.inner.read(buffer)
+    }
+}
+
+const BLOCK_SIZE: usize = 16;
+
+/// Decrypts data block-by-block as it's read, until the encryption threshold is met or there isn't a full block left.
+struct StreamingDecryptor<R: Read> {
+    inner: R,
+    cipher: aes::Aes128,
+    encrypted_remaining: usize,
+    block: [u8; BLOCK_SIZE],
+    block_length: usize,
+    block_index: usize,
+}
+impl<R: Read> StreamingDecryptor<R> {
+    fn new(inner: R, encrypted_byte_count: usize) -> Self {
+        use aes::cipher::KeyInit;
+        Self {
+            inner,
+            cipher: aes::Aes128::new(KEY.into()),
+            encrypted_remaining: encrypted_byte_count,
+            block: [0; BLOCK_SIZE],
+            block_length: 0,
+            block_index: 0,
+        }
+    }
+
+    fn fill_block(&mut self) -> std::io::Result<()> {
+        use aes::cipher::BlockDecrypt;
+
+        self.block_index = 0;
+        self.block_length = 0;
+        while self.block_length < BLOCK_SIZE {
+            match self.inner.read(&mut self.block[self.block_length..]) {
+                Ok(0) => break,
+                Ok(read) => self.block_length += read,
+                Err(error) if error.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            }
+        }
+
+        if self.block_length == BLOCK_SIZE && self.encrypted_remaining >= BLOCK_SIZE {
+            self.cipher.decrypt_block((&mut self.block).into());
+            self.encrypted_remaining -= BLOCK_SIZE;
+        } else {
+            // Anything after a partial block or the threshold is stored unencrypted.
+            self.encrypted_remaining = 0;
+        }
+
+        Ok(())
+    }
+}
+impl<R: Read> Read for StreamingDecryptor<R> {
+    fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+        if self.block_index == self.block_length {
+            self.fill_block()?;
+        }
+        let read = buffer.len().min(self.block_length - self.block_index);
+        buffer[..read].copy_from_slice(&self.block[self.block_index..][..read]);
+        self.block_index += read;
+        Ok(read)
+    }
+}
+
 /// Read from two slices, one after the other, without allocating.
 struct ReadableDualJoined<'a> {
     second: &'a [u8],
@@ -179,5 +297,64 @@ mod tests {
         let mut bytes = Vec::new();
         file.read_to_end(&mut bytes).expect("failed to read file");
         let _ = decode_in_place(&mut bytes[..]).expect("failed to decode file");
-    }    
+    }
+
+    /// Builds an encoded file out of the given content, encrypting up to `max_encrypted` bytes of the compressed data.
+    fn encode(content: &[u8], max_encrypted: u32) -> Vec<u8> {
+        use aes::cipher::{BlockEncrypt, KeyInit};
+        use byteorder::{LittleEndian, WriteBytesExt};
+        use std::io::Write;
+
+        let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(content).unwrap();
+        let mut compressed = encoder.finish().unwrap();
+
+        let cipher = aes::Aes128::new(KEY.into());
+        let encrypted = (max_encrypted as usize).min(compressed.len() & !0x0F);
+        for block in compressed[..encrypted].chunks_exact_mut(16) {
+            cipher.encrypt_block(block.into());
+        }
+
+        const HEADER_SIZE: u32 = 96;
+        let mut file = Vec::new();
+        file.extend_from_slice(b"hfma");
+        file.write_u32::<LittleEndian>(HEADER_SIZE).unwrap();
+        file.write_u32::<LittleEndian>(HEADER_SIZE + compressed.len() as u32).unwrap();
+        file.write_u16::<LittleEndian>(1).unwrap(); // format major
+        file.write_u16::<LittleEndian>(0).unwrap(); // format minor
+        let mut app_version = [0; 0x20];
+        app_version[..7].copy_from_slice(b"1.5.0.1");
+        file.extend_from_slice(&app_version);
+        file.extend_from_slice(&[0; 8 + 4 + 4 + 4 + 16]);
+        file.write_u32::<LittleEndian>(max_encrypted).unwrap();
+        file.resize(HEADER_SIZE as usize, 0);
+        file.extend_from_slice(&compressed);
+        file
+    }
+
+    #[test]
+    fn streaming_matches_in_place() {
+        let content = (0..20_000u32).flat_map(|n| (n * 7919 % 251).to_le_bytes()).collect::<Vec<_>>();
+        for max_encrypted in [0, 16, 1024, !0x0F] {
+            let mut file = encode(&content, max_encrypted);
+
+            let mut streamed = Vec::new();
+            let mut decoder = StreamingDecoder::new(&file[..]).expect("failed to read header");
+            assert_eq!(decoder.info().app_version.to_str(), Ok("1.5.0.1"));
+            decoder.read_to_end(&mut streamed).expect("failed to stream");
+
+            let (in_place, _) = decode_in_place(&mut file).expect("failed to decode");
+            assert_eq!(streamed, in_place);
+            assert_eq!(streamed, content);
+        }
+    }
+
+    #[test]
+    fn truncated_headers() {
+        let mut file = encode(b"content", 0);
+        file[4..8].copy_from_slice(&u32::MAX.to_le_bytes()); // header size
+        file.truncate(96);
+        assert!(decode_in_place(&mut file.clone()).is_err());
+        assert!(StreamingDecoder::new(&file[..]).is_err());
+    }
 }
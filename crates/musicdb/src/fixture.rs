@@ -0,0 +1,352 @@
+//! Builds synthetic (decoded) libraries, so that there's something to test and benchmark against without needing a real one.
+//!
+//! Only what the parser actually reads is filled in; everything else is left zeroed.
+
+/// How many of each item a synthetic library has.
+#[derive(Debug, Clone, Copy)]
+pub struct Size {
+    pub name: &'static str,
+    pub tracks: usize,
+}
+impl Size {
+    pub const SMALL: Self = Self { name: "small", tracks: 100 };
+    pub const MEDIUM: Self = Self { name: "medium", tracks: 2_500 };
+    pub const LARGE: Self = Self { name: "large", tracks: 25_000 };
+
+    const TRACKS_PER_ALBUM: usize = 12;
+    const ALBUMS_PER_ARTIST: usize = 3;
+
+    fn albums(self) -> usize {
+        self.tracks.div_ceil(Self::TRACKS_PER_ALBUM)
+    }
+    fn artists(self) -> usize {
+        self.albums().div_ceil(Self::ALBUMS_PER_ARTIST)
+    }
+}
+
+/// A synthetic library, as it'd be once decoded.
+pub struct Fixture {
+    pub data: Vec<u8>,
+    /// The position of the track list, just after its section boundary.
+    pub tracks: u64,
+    /// Every string in the library, encoded as it is within it.
+    pub strings: Vec<Vec<u8>>,
+}
+
+const SECTION_TRACKS: u32 = 1;
+const SECTION_COLLECTIONS: u32 = 2;
+const SECTION_HEADER: u32 = 3;
+const SECTION_ALBUMS: u32 = 4;
+const SECTION_ARTISTS: u32 = 5;
+const SECTION_LIBRARY_MASTER: u32 = 6;
+
+const BOMA_TRACK_NUMERICS: u32 = 0x1;
+const BOMA_TRACK_PLAY_STATISTICS: u32 = 0x17;
+const BOMA_TRACK_TITLE: u32 = 0x2;
+const BOMA_ALBUM: u32 = 0x3;
+const BOMA_ARTIST: u32 = 0x4;
+const BOMA_GENRE: u32 = 0x5;
+const BOMA_ALBUM_ARTIST: u32 = 0x1B;
+const BOMA_IAMA_ALBUM: u32 = 0x12C;
+const BOMA_IAMA_ALBUM_ARTIST: u32 = 0x12D;
+const BOMA_ARTIST_NAME: u32 = 400;
+const BOMA_PLIST_TRACK_CLOUD_INFORMATION: u32 = 0x36;
+const BOMA_PLAYLIST_NAME: u32 = 200;
+const BOMA_COLLECTION_MEMBER: u32 = 206;
+
+const TRACK_NUMERICS_LENGTH: usize = 328;
+const TRACK_PLAY_STATISTICS_LENGTH: usize = 36;
+
+const ALBUM_ID_BASE: u64 = 0x1000_0000_0000_0000;
+const ARTIST_ID_BASE: u64 = 0x2000_0000_0000_0000;
+const TRACK_ID_BASE: u64 = 0x3000_0000_0000_0000;
+const COLLECTION_ID_BASE: u64 = 0x4000_0000_0000_0000;
+
+#[derive(Default)]
+struct Writer {
+    data: Vec<u8>,
+    strings: Vec<Vec<u8>>,
+}
+impl Writer {
+    fn u16(&mut self, value: u16) { self.data.extend(value.to_le_bytes()) }
+    fn u32(&mut self, value: u32) { self.data.extend(value.to_le_bytes()) }
+    fn u64(&mut self, value: u64) { self.data.extend(value.to_le_bytes()) }
+    fn zeros(&mut self, count: usize) { self.data.resize(self.data.len() + count, 0) }
+
+    fn put_u32(&mut self, at: usize, value: u32) {
+        self.data[at..at + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    /// Writes a chunk header with a placeholder length, returning where the chunk starts so the length can be [filled in](Self::end_chunk).
+    fn start_chunk(&mut self, signature: &[u8; 4]) -> usize {
+        let start = self.data.len();
+        self.data.extend(signature);
+        self.u32(0);
+        start
+    }
+    fn end_chunk(&mut self, start: usize) {
+        self.put_u32(start + 4, (self.data.len() - start) as u32);
+    }
+
+    /// Writes a section boundary, returning where it starts so the section length can be [filled in](Self::end_section).
+    fn start_section(&mut self, kind: u32) -> usize {
+        let start = self.data.len();
+        self.data.extend(b"hsma");
+        self.u32(16);
+        self.u32(0);
+        self.u32(kind);
+        start
+    }
+    fn end_section(&mut self, start: usize) {
+        self.put_u32(start + 8, (self.data.len() - start) as u32);
+    }
+
+    fn list(&mut self, signature: &[u8; 4], count: usize) {
+        let start = self.start_chunk(signature);
+        self.u32(count as u32);
+        self.end_chunk(start);
+    }
+
+    /// Starts an item (a track, album, et cetera) with the given boma count and persistent ID, leaving the rest of its header to the caller.
+    fn start_item(&mut self, signature: &[u8; 4], boma_count: u32, persistent_id: u64) -> usize {
+        let start = self.start_chunk(signature);
+        self.u32(0); // appendage byte length
+        self.u32(boma_count);
+        self.u64(persistent_id);
+        start
+    }
+
+    fn boma(&mut self, subtype: u32, length: usize) {
+        self.data.extend(b"boma");
+        self.u32(20);
+        self.u32(length as u32);
+        self.u32(subtype);
+    }
+
+    fn string(&mut self, subtype: u32, value: &str) {
+        let encoded: Vec<u8> = value.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        self.boma(subtype, 36 + encoded.len());
+        self.zeros(8);
+        self.u32(encoded.len() as u32);
+        self.zeros(8);
+        self.data.extend(&encoded);
+        self.strings.push(encoded);
+    }
+
+    fn plist(&mut self, subtype: u32, entries: &[(&str, &str)]) {
+        let mut xml = String::from(r#"<?xml version="1.0" encoding="UTF-8"?><plist version="1.0"><dict>"#);
+        for (key, value) in entries {
+            xml += &format!("<key>{key}</key><string>{value}</string>");
+        }
+        xml += "</dict></plist>";
+        self.boma(subtype, 20 + xml.len());
+        self.zeros(4);
+        self.data.extend(xml.as_bytes());
+    }
+
+    fn track_numerics(&mut self, duration_ms: u32, date_added: u32, cloud_artist_id: u32) {
+        let start = self.data.len();
+        self.boma(BOMA_TRACK_NUMERICS, TRACK_NUMERICS_LENGTH);
+        self.zeros(108 - 16);
+        self.u32(256); // bitrate
+        self.u32(date_added);
+        self.zeros(148 - 116);
+        self.u32(date_added); // date modified
+        self.zeros(176 - 152);
+        self.u32(duration_ms);
+        self.zeros(188 - 180);
+        self.u32(cloud_artist_id);
+        self.zeros(TRACK_NUMERICS_LENGTH - (self.data.len() - start));
+    }
+
+    fn artist(&mut self, persistent_id: u64, cloud_id: u32, name: &str) {
+        let start = self.start_item(b"iAma", 1, persistent_id);
+        self.zeros(28);
+        self.u32(cloud_id);
+        self.end_chunk(start);
+        self.string(BOMA_ARTIST_NAME, name);
+    }
+
+    fn header(&mut self) {
+        let file = self.start_section(SECTION_HEADER);
+        let header = self.start_chunk(b"hfma");
+        self.zeros(4);
+        self.u16(1); // format version
+        self.u16(21);
+        let app_version = b"1.4.5.7";
+        self.data.extend(app_version);
+        self.zeros(0x20 - app_version.len());
+        self.end_chunk(header);
+        self.end_section(file);
+
+        let section = self.start_section(SECTION_LIBRARY_MASTER);
+        self.list(b"plma", 0);
+        self.end_section(section);
+    }
+
+    fn collections(&mut self) {
+        let section = self.start_section(SECTION_COLLECTIONS);
+        self.list(b"lPma", 0);
+        self.end_section(section);
+    }
+
+    /// A playlist of the given tracks, within the folder of the given persistent ID (or zero, for none).
+    fn collection(&mut self, persistent_id: u64, parent: u64, name: &str, tracks: &[u64]) {
+        let start = self.start_chunk(b"lpma");
+        self.u32(0); // appendage byte length
+        self.u32(1 + tracks.len() as u32);
+        self.u32(tracks.len() as u32);
+        self.zeros(30 - 20);
+        self.u64(persistent_id);
+        self.zeros(50 - 38);
+        self.u64(parent);
+        self.zeros(142 - 58);
+        self.end_chunk(start);
+        self.string(BOMA_PLAYLIST_NAME, name);
+        for &track in tracks {
+            self.boma(BOMA_COLLECTION_MEMBER, 48);
+            self.zeros(4);
+            self.data.extend(b"ipfa");
+            self.u32(28);
+            self.zeros(12);
+            self.u64(track);
+        }
+    }
+
+    fn track_play_statistics(&mut self, persistent_id: u64, times: u32) {
+        self.boma(BOMA_TRACK_PLAY_STATISTICS, TRACK_PLAY_STATISTICS_LENGTH);
+        self.zeros(4);
+        self.u64(persistent_id);
+        self.u32(0); // last played
+        self.u32(times);
+    }
+}
+
+impl Fixture {
+    pub fn build(size: Size) -> Self {
+        let mut writer = Writer::default();
+        writer.header();
+
+        let section = writer.start_section(SECTION_ALBUMS);
+        writer.list(b"lama", size.albums());
+        for album in 0..size.albums() {
+            let start = writer.start_item(b"iama", 2, ALBUM_ID_BASE + album as u64);
+            writer.end_chunk(start);
+            writer.string(BOMA_IAMA_ALBUM, &album_name(album));
+            writer.string(BOMA_IAMA_ALBUM_ARTIST, &artist_name(album / Size::ALBUMS_PER_ARTIST));
+        }
+        writer.end_section(section);
+
+        let section = writer.start_section(SECTION_ARTISTS);
+        writer.list(b"lAma", size.artists());
+        for artist in 0..size.artists() {
+            writer.artist(ARTIST_ID_BASE + artist as u64, 0, &artist_name(artist));
+        }
+        writer.end_section(section);
+
+        let section = writer.start_section(SECTION_TRACKS);
+        let tracks = writer.data.len() as u64;
+        writer.list(b"ltma", size.tracks);
+        for track in 0..size.tracks {
+            let album = track / Size::TRACKS_PER_ALBUM;
+            let artist = album / Size::ALBUMS_PER_ARTIST;
+            let persistent_id = TRACK_ID_BASE + track as u64;
+            let start = writer.start_item(b"itma", 7, persistent_id);
+            writer.zeros(148);
+            writer.u64(ALBUM_ID_BASE + album as u64);
+            writer.u64(ARTIST_ID_BASE + artist as u64);
+            writer.end_chunk(start);
+            writer.track_numerics(150_000 + (track as u32 % 120) * 1_000, 3_600_000_000 + track as u32, 0);
+            writer.track_play_statistics(persistent_id, track as u32 % 50);
+            writer.string(BOMA_TRACK_TITLE, &format!("Track Number {track} (Remastered)"));
+            writer.string(BOMA_ALBUM, &album_name(album));
+            writer.string(BOMA_ARTIST, &artist_name(artist));
+            writer.string(BOMA_ALBUM_ARTIST, &artist_name(artist));
+            writer.string(BOMA_GENRE, ["Pop", "Électronique", "ヒップホップ", "Jazz"][track % 4]);
+        }
+        writer.end_section(section);
+
+        writer.collections();
+
+        Self { data: writer.data, tracks, strings: writer.strings }
+    }
+
+    /// A library of a [single track](COLLABORATION_TRACK) credited to two artists together, where one of them is also
+    /// in the library on their own. Apple Music gives both artists the same cloud catalog ID, that of the one on their own.
+    pub fn collaboration() -> Self {
+        const CLOUD_ARTIST_ID: u32 = 1_234_567;
+        let mut writer = Writer::default();
+        writer.header();
+
+        let section = writer.start_section(SECTION_ALBUMS);
+        writer.list(b"lama", 1);
+        let start = writer.start_item(b"iama", 2, ALBUM_ID_BASE);
+        writer.end_chunk(start);
+        writer.string(BOMA_IAMA_ALBUM, "Now That's What I Call Snorkeling");
+        writer.string(BOMA_IAMA_ALBUM_ARTIST, "Various Artists");
+        writer.end_section(section);
+
+        let section = writer.start_section(SECTION_ARTISTS);
+        writer.list(b"lAma", 2);
+        writer.artist(ARTIST_ID_BASE, CLOUD_ARTIST_ID, "Lumpy & The Snorkelers");
+        writer.artist(ARTIST_ID_BASE + 1, CLOUD_ARTIST_ID, "Lumpy");
+        writer.end_section(section);
+
+        let section = writer.start_section(SECTION_TRACKS);
+        let tracks = writer.data.len() as u64;
+        writer.list(b"ltma", 1);
+        let start = writer.start_item(b"itma", 7, COLLABORATION_TRACK);
+        writer.zeros(148);
+        writer.u64(ALBUM_ID_BASE);
+        writer.u64(ARTIST_ID_BASE);
+        writer.end_chunk(start);
+        writer.track_numerics(200_000, 3_600_000_000, CLOUD_ARTIST_ID);
+        writer.track_play_statistics(COLLABORATION_TRACK, 1);
+        writer.plist(BOMA_PLIST_TRACK_CLOUD_INFORMATION, &[("cloud-lyrics", "Down where it's wetter\nIt's better, take it from me")]);
+        writer.string(BOMA_TRACK_TITLE, "Snorkel");
+        writer.string(BOMA_ALBUM, "Now That's What I Call Snorkeling");
+        writer.string(BOMA_ARTIST, "Lumpy & The Snorkelers");
+        writer.string(BOMA_ALBUM_ARTIST, "Various Artists");
+        writer.end_section(section);
+
+        writer.collections();
+        Self { data: writer.data, tracks, strings: writer.strings }
+    }
+}
+
+/// The persistent ID of the track of the [collaboration](Fixture::collaboration) library.
+pub const COLLABORATION_TRACK: u64 = TRACK_ID_BASE;
+
+impl Fixture {
+    /// A library of nothing but [a folder](FOLDER) holding two playlists, alongside an empty playlist outside of it.
+    pub fn folder() -> Self {
+        let mut writer = Writer::default();
+        writer.header();
+        for (kind, signature) in [(SECTION_ALBUMS, b"lama"), (SECTION_ARTISTS, b"lAma"), (SECTION_TRACKS, b"ltma")] {
+            let section = writer.start_section(kind);
+            writer.list(signature, 0);
+            writer.end_section(section);
+        }
+
+        let section = writer.start_section(SECTION_COLLECTIONS);
+        writer.list(b"lPma", 4);
+        writer.collection(FOLDER, 0, "Seasons", &[TRACK_ID_BASE, TRACK_ID_BASE + 1]);
+        writer.collection(FOLDER + 1, FOLDER, "Summer", &[TRACK_ID_BASE]);
+        writer.collection(FOLDER + 2, FOLDER, "Winter", &[TRACK_ID_BASE + 1]);
+        writer.collection(FOLDER + 3, 0, "Nothing Yet", &[]);
+        writer.end_section(section);
+
+        Self { data: writer.data, tracks: 0, strings: writer.strings }
+    }
+}
+
+/// The persistent ID of the folder of the [folder](Fixture::folder) library; those within it follow on from it.
+pub const FOLDER: u64 = COLLECTION_ID_BASE;
+
+fn album_name(album: usize) -> String {
+    format!("Album {album}: Songs From Somewhere 🎶")
+}
+
+fn artist_name(artist: usize) -> String {
+    format!("The Artist Formerly Known As #{artist}")
+}
@@ -17,6 +17,16 @@ pub mod persistent {
             Ok(Id::new(u64::from_str_radix(value, 16)?))       
         }
 
+        /// Parses the ID from its decimal representation.
+        pub fn from_decimal(value: &str) -> Result<Self, core::num::ParseIntError> {
+            Ok(Id::new(value.parse()?))
+        }
+
+        /// Formats the ID the way the application does: as sixteen uppercase hexadecimal digits (e.x. `0A1B2C3D4E5F6071`).
+        pub fn to_hex(&self) -> String {
+            format!("{:016X}", self.0)
+        }
+
         pub fn to_hex_upper(&self) -> String {
             format!("{:X}", self.0)
         }
@@ -61,12 +71,68 @@ pub mod persistent {
             Ok(Id::new(u64::from_str_radix(value, 16)?))
         }
     }
+    /// Accepts either of the representations an ID tends to be found in:
+    /// - hexadecimal, if it's prefixed with `0x`, or is [sixteen digits long](Id::to_hex) and has any of `A` to `F` among them,
+    /// - decimal, otherwise.
+    ///
+    /// Hexadecimal without a prefix or any letters can't be told apart from decimal, and so is read as the latter.
+    impl<T> core::str::FromStr for Id<T> {
+        type Err = core::num::ParseIntError;
+        fn from_str(value: &str) -> Result<Self, Self::Err> {
+            if let Some(hex) = value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+                Self::from_hex(hex)
+            } else if value.len() == HEX_LENGTH && value.bytes().any(|byte| matches!(byte, b'A'..=b'F' | b'a'..=b'f')) {
+                Self::from_hex(value)
+            } else {
+                Self::from_decimal(value)
+            }
+        }
+    }
+    /// Formats as [hexadecimal](Id::to_hex).
+    impl<T> core::fmt::Display for Id<T> {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.pad(&self.to_hex())
+        }
+    }
+    /// Serializes as [hexadecimal](Id::to_hex), since the integer can be too large for formats like JSON to represent losslessly.
+    impl<T> serde::Serialize for Id<T> {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(&self.to_hex())
+        }
+    }
+    /// Deserializes from an integer, or a string in any representation [`FromStr`](core::str::FromStr) accepts;
+    /// except that sixteen digits are always read as hexadecimal, since that's how IDs are serialized.
+    impl<'de, T> serde::Deserialize<'de> for Id<T> {
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct Visitor<T>(PhantomData<T>);
+            impl<T> serde::de::Visitor<'_> for Visitor<T> {
+                type Value = Id<T>;
+                fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                    f.write_str("a persistent ID, as an integer or a hexadecimal or decimal string")
+                }
+                fn visit_u64<E: serde::de::Error>(self, value: u64) -> Result<Self::Value, E> {
+                    Ok(Id::new(value))
+                }
+                fn visit_i64<E: serde::de::Error>(self, value: i64) -> Result<Self::Value, E> {
+                    // a signed representation of the same bits, as in SQLite
+                    Ok(Id::new(value.cast_unsigned()))
+                }
+                fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                    if value.len() == HEX_LENGTH { Id::from_hex(value) } else { value.parse() }.map_err(E::custom)
+                }
+            }
+            deserializer.deserialize_any(Visitor(PhantomData))
+        }
+    }
     impl<T: Possessor> core::fmt::Debug for Id<T> {
         fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
             f.pad(&format!("PersistentId<{:?}>({})", T::IDENTITY, self.0))
         }
     }
 
+    /// How many digits the [hexadecimal representation](Id::to_hex) has.
+    const HEX_LENGTH: usize = 16;
+
     pub trait Possessor {
         type Id: Clone + Copy + Hash + PartialEq + Eq;
         #[allow(private_interfaces)]
@@ -268,3 +334,41 @@ pub mod cloud {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{de::IntoDeserializer, Deserialize};
+
+    type Id = PersistentId<crate::Track<'static>>;
+    const RAW: u64 = 0x0A1B_2C3D_4E5F_6071;
+
+    #[test]
+    fn persistent_id_formats() {
+        assert_eq!(Id::new(RAW).to_hex(), "0A1B2C3D4E5F6071");
+        assert_eq!(Id::new(0xFF).to_hex(), "00000000000000FF");
+        assert_eq!(Id::new(RAW).to_string(), Id::new(RAW).to_hex());
+
+        assert_eq!("0A1B2C3D4E5F6071".parse(), Ok(Id::new(RAW)));
+        assert_eq!("0a1b2c3d4e5f6071".parse(), Ok(Id::new(RAW)));
+        assert_eq!("0xFF".parse(), Ok(Id::new(0xFF)));
+        assert_eq!(RAW.to_string().parse(), Ok(Id::new(RAW)));
+        assert_eq!("255".parse(), Ok(Id::new(255)));
+        assert_eq!("1234567890123456".parse(), Ok(Id::new(1_234_567_890_123_456)));
+        assert!("FF".parse::<Id>().is_err());
+        assert!("".parse::<Id>().is_err());
+    }
+
+    #[test]
+    fn persistent_id_deserialization() {
+        fn deserialize<'de>(deserializer: impl serde::Deserializer<'de, Error = serde::de::value::Error>) -> Id {
+            Id::deserialize(deserializer).unwrap()
+        }
+        assert_eq!(deserialize(RAW.into_deserializer()), Id::new(RAW));
+        assert_eq!(deserialize(RAW.cast_signed().into_deserializer()), Id::new(RAW));
+        assert_eq!(deserialize("0A1B2C3D4E5F6071".into_deserializer()), Id::new(RAW));
+        assert_eq!(deserialize(RAW.to_string().as_str().into_deserializer()), Id::new(RAW));
+        let digits = Id::new(0x0123_4567_8901_2345);
+        assert_eq!(deserialize(digits.to_hex().as_str().into_deserializer()), digits);
+    }
+}
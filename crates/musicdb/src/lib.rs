@@ -10,45 +10,130 @@ pub fn setup_tracing_subscriber() {
 #[cfg(feature = "cli")]
 pub mod cli;
 
+pub mod artwork;
+pub mod changes;
 pub mod chunk;
 mod chunks;
 pub mod encoded;
+pub mod reader;
+pub use reader::MusicDbReader;
 
 pub mod id;
 pub mod boma;
 pub mod units;
 pub use id::*;
-mod version;
+pub mod version;
 use boma::*;
 use chunk::*;
 pub use chunks::*;
 
-pub(crate) fn convert_timestamp(seconds: u32) -> Option<chrono::DateTime<chrono::Utc>> {
-    if seconds == 0 { return None }
-    
-    use chrono::TimeZone;
-
-    const EPOCH_OFFSET: i64 = 2082819600;
-
-    Some(chrono::Utc.timestamp_opt(seconds as i64 - EPOCH_OFFSET, 0).unwrap())
-}
+/// Synthetic libraries, shared with the benchmarks and with dependents' tests.
+#[cfg(any(test, feature = "fixture"))]
+#[doc(hidden)]
+pub mod fixture;
 
+/// The header at the start of the decoded data, duplicating some of the information of the [packed header](encoded::PackedFileInfo).
 #[derive(Debug)]
-struct HeaderRepeat {}
-impl Chunk for HeaderRepeat {
+struct Header<'a> {
+    format_version: version::FormatVersion,
+    app_version: &'a core::ffi::CStr,
+}
+impl Chunk for Header<'_> {
     const SIGNATURE: Signature = Signature::new(*b"hfma");
 }
-impl<'a> SizedFirstReadableChunk<'a> for HeaderRepeat {
+impl<'a> SizedFirstReadableChunk<'a> for Header<'a> {
     type ReadError = std::io::Error;
     fn read_sized_content(cursor: &mut Cursor<&'a [u8]>, offset: u64, length: u32) -> Result<Self, Self::ReadError> where Self: Sized {
         setup_eaters!(cursor, offset, length);
+        skip!(4)?;
+        let major = u16!()?;
+        let minor = u16!()?;
+        let app_version = cstr_exact!(0x20)?;
         skip_to_end!()?; // skip the rest of the section
-        Ok(Self {})
+        Ok(Self { format_version: version::FormatVersion { major, minor }, app_version })
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ParseError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("unsupported library version (format {format_version}): {error}")]
+    UnsupportedVersion {
+        format_version: version::FormatVersion,
+        error: version::UnsupportedVersionError,
+    },
+    #[error("can't read {section:?} section: {message}")]
+    Section {
+        section: SectionKind,
+        message: String,
+    },
+    #[error("missing required {0:?} section")]
+    MissingSection(SectionKind),
+    #[error("expected a section boundary @0x{0:X}")]
+    MisplacedBoundary(u64),
+    #[error("section @0x{offset:X} claims a length of {length}, which doesn't cover its own boundary")]
+    BadSectionLength {
+        offset: u64,
+        length: u32,
+    },
+}
+
+/// The location of a section within the decoded data, as found by hopping between [section boundaries](chunks::SectionBoundary).
+#[derive(Debug)]
+struct SectionBounds {
+    kind: Option<SectionKind>,
+    /// The position of the first chunk within the section, immediately after its boundary.
+    start: u64,
+}
+impl SectionBounds {
+    fn scan(mut cursor: Cursor<&[u8]>) -> Result<Vec<Self>, ParseError> {
+        let mut sections = Vec::with_capacity(6);
+        let end = cursor.get_ref().len() as u64;
+        while cursor.position() < end {
+            let offset = cursor.position();
+            if cursor.peek_signature()? != chunks::SectionBoundary::<u32>::SIGNATURE {
+                return Err(ParseError::MisplacedBoundary(offset))
+            }
+            let boundary = chunks::SectionBoundary::<u32>::read(&mut cursor)?;
+            let kind = SectionKind::from_repr(boundary.subtype);
+            #[cfg(feature = "tracing")]
+            if kind.is_none() { tracing::debug!(subtype = boundary.subtype, "skipping unrecognized section"); }
+            let start = cursor.position();
+            // A length that doesn't get past the boundary would have the same boundary read again, forever.
+            let next = offset.checked_add(u64::from(boundary.section_length))
+                .filter(|&next| next >= start)
+                .ok_or(ParseError::BadSectionLength { offset, length: boundary.section_length })?;
+            sections.push(Self { kind, start });
+            cursor.set_position(next);
+        }
+        Ok(sections)
+    }
+
+    fn read<'a, T: ReadableChunk<'a>>(&self, data: &'a [u8]) -> Result<T, ParseError> {
+        let mut cursor = Cursor::new(data);
+        cursor.set_position(self.start);
+        T::read(&mut cursor).map_err(|error| ParseError::Section {
+            section: self.kind.expect("only recognized sections are read"),
+            message: error.to_string()
+        })
     }
 }
 
+#[derive(thiserror::Error, Debug)]
+pub enum ReadError {
+    #[error("decode error: {0}")]
+    Decode(#[from] encoded::DecodeError),
+    #[error("parse error: {0}")]
+    Parse(#[from] ParseError),
+}
+
 #[derive(Debug)]
 pub struct MusicDbView<'a> {
+    /// The version of the format of the file.
+    pub format_version: version::FormatVersion,
+    /// The layout that the file was determined to have, based off of the version of the application which wrote it.
+    pub layout: version::Layout,
     pub library: LibraryMaster<'a>,
     pub albums: AlbumMap<'a>,
     pub artists: ArtistMap<'a>,
@@ -62,43 +147,8 @@ pub struct MusicDbView<'a> {
     pub collections: CollectionList<'a>
 }
 impl<'a> MusicDbView<'a> {
-    pub(crate) fn with_cursor(mut cursor: Cursor<&'a [u8]>) -> Self {
-        macro_rules! expect_boundary {
-            ($cursor: ident) => {
-                chunks::SectionBoundary::<u32>::read(&mut $cursor).expect("can't read section boundary");        
-            }
-        }
-
-        
-        expect_boundary!(cursor);
-        HeaderRepeat::read(&mut cursor).expect("can't read header duplicate");
-
-        expect_boundary!(cursor);
-        let library = LibraryMaster::read(&mut cursor).expect("can't read library master");
-        
-        expect_boundary!(cursor);
-        let albums = AlbumMap::read(&mut cursor).expect("can't read albums list");
-
-        expect_boundary!(cursor);
-        let artists = ArtistMap::read(&mut cursor).expect("can't read artists list");
-
-        expect_boundary!(cursor);
-        let accounts = AccountInfoList::read_optional(&mut cursor).expect("can't read account list");
-
-        if accounts.is_some() { expect_boundary!(cursor); }
-        let tracks = TrackMap::read(&mut cursor).expect("can't read track list");
-
-        expect_boundary!(cursor);
-        let collections = CollectionList::read(&mut cursor).expect("can't read collection list");
-
-        Self {
-            library,
-            albums,
-            artists,
-            accounts,
-            tracks,
-            collections
-        }
+    pub(crate) fn with_cursor(cursor: Cursor<&'a [u8]>) -> Result<Self, ParseError> {
+        MusicDbReader::new(cursor.get_ref())?.hydrate(reader::ALL_SECTIONS)
     }
 
     /// Returns the value with the given ID (be it a track, album, artist, et cetera).
@@ -161,15 +211,19 @@ impl_db_collection_coercion!(CollectionList, collections);
 pub struct MusicDB {
     view: MusicDbView<'static>, // not really static; lifetime is 'self (as long as `_owned_data` exists)
     path: std::path::PathBuf,
+    /// When the file was last modified as of it being read, if it was read from disk.
+    modified: Option<std::time::SystemTime>,
     _owned_data: Pin<Box<[u8]>>,
 }
 
 impl MusicDB {
-    pub fn read_path(path: impl AsRef<Path>) -> Result<MusicDB, encoded::DecodeError> {
+    pub fn read_path(path: impl AsRef<Path>) -> Result<MusicDB, ReadError> {
+        // taken beforehand so that a write during the read is noticed by the next check
+        let modified = Self::modified_at(path.as_ref()).map_err(encoded::DecodeError::from)?;
         let decoded = Self::decode(&path)?;
-        Ok(Self::from_decoded(decoded.into_boxed_slice(), path))
+        Ok(Self { modified: Some(modified), ..Self::from_decoded(decoded.into_boxed_slice(), path)? })
     }
-    pub fn from_decoded(data: Box<[u8]>, path: impl AsRef<Path>) -> MusicDB {
+    pub fn from_decoded(data: Box<[u8]>, path: impl AsRef<Path>) -> Result<MusicDB, ParseError> {
         let path = path.as_ref().to_path_buf();
         let data = Pin::new(data);
 
@@ -183,9 +237,9 @@ impl MusicDB {
         };
 
         let cursor = Cursor::new(slice);
-        let view = MusicDbView::with_cursor(cursor);
+        let view = MusicDbView::with_cursor(cursor)?;
 
-        Self { view, path, _owned_data: data }
+        Ok(Self { view, path, modified: None, _owned_data: data })
     }
     /// Decrypts and decompresses the `.musicdb` file at the given path, returning the internal contents.
     pub fn decode(path: impl AsRef<Path>) -> Result<Vec<u8>, encoded::DecodeError> {
@@ -206,10 +260,33 @@ impl MusicDB {
         unsafe { core::mem::transmute(&mut self.view) }
     }
     /// Updates the view by re-reading/decoding the file from disk.
-    pub fn update_view(&mut self) -> Result<(), encoded::DecodeError> {
+    pub fn update_view(&mut self) -> Result<(), ReadError> {
         *self = Self::read_path(self.path.as_path())?;
         Ok(())
     }
+    fn modified_at(path: &Path) -> std::io::Result<std::time::SystemTime> {
+        std::fs::metadata(path)?.modified()
+    }
+    /// Whether the file on disk has been modified since it was read.
+    /// Always true if it wasn't read from disk in the first place.
+    pub fn has_changed(&self) -> std::io::Result<bool> {
+        Ok(self.modified != Some(Self::modified_at(&self.path)?))
+    }
+    /// Like [`Self::update_view`], but only if the file has [changed](Self::has_changed) since it was read.
+    /// Returns whether it was re-read.
+    pub fn reload_if_changed(&mut self) -> Result<bool, ReadError> {
+        if !self.has_changed().map_err(encoded::DecodeError::from)? { return Ok(false) }
+        self.update_view()?;
+        Ok(true)
+    }
+    /// The path of the file this was read from.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+    /// Returns the local artwork cache belonging to this library.
+    pub fn artwork_cache(&self) -> artwork::ArtworkCache {
+        artwork::ArtworkCache::for_library(&self.path)
+    }
     pub fn default_path() -> std::path::PathBuf {
         std::env::home_dir().expect("no user home directory detected").as_path()
             .join("Music/Music/Music Library.musiclibrary/Library.musicdb")
@@ -280,8 +357,11 @@ fn try_all_samples() {
                 },
                 Some("decoded") => {
                     let decoded = std::fs::read(&path).expect("fs error");
-                    let _ = MusicDB::from_decoded(decoded.into_boxed_slice(), &path);
-                    tracing::info!(?path, "successfully read pre-decoded sample");
+                    if let Err(error) = MusicDB::from_decoded(decoded.into_boxed_slice(), &path) {
+                        tracing::error!(?path, ?error, "failed to parse pre-decoded sample");
+                    } else {
+                        tracing::info!(?path, "successfully read pre-decoded sample");
+                    }
                 }
                 _ => {}
             }
@@ -326,3 +406,46 @@ pub(crate) fn xxd(mut slice: &[u8]) -> String {
     }
     out
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn boundary(section_length: u32) -> Vec<u8> {
+        let mut bytes = b"hsma".to_vec();
+        bytes.extend(16u32.to_le_bytes());
+        bytes.extend(section_length.to_le_bytes());
+        bytes.extend(3u32.to_le_bytes());
+        bytes
+    }
+
+    #[test]
+    fn sections_must_get_past_their_boundary() {
+        for length in [0, 8] {
+            let data = boundary(length);
+            assert!(matches!(
+                SectionBounds::scan(Cursor::new(&data)),
+                Err(ParseError::BadSectionLength { offset: 0, .. })
+            ));
+        }
+
+        let mut data = boundary(16);
+        data.extend(boundary(16));
+        assert_eq!(SectionBounds::scan(Cursor::new(&data)).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn chunks_must_cover_their_header() {
+        // A boundary which is itself too short to be skipped past would be read again in place of the next.
+        let mut data = boundary(0);
+        data[4..8].copy_from_slice(&0u32.to_le_bytes());
+        assert!(SectionBounds::scan(Cursor::new(&data)).is_err());
+
+        let mut boma = b"boma".to_vec();
+        boma.extend(20u32.to_le_bytes());
+        boma.extend(0u32.to_le_bytes()); // length
+        boma.extend(TrackPlayStatistics::BOMA_SUBTYPE.to_le_bytes());
+        boma.resize(64, 0);
+        assert!(Boma::read(&mut Cursor::new(&boma)).is_err());
+    }
+}
@@ -0,0 +1,244 @@
+//! Reading a library a section at a time, rather than all at once.
+use std::{io::Cursor, marker::PhantomData};
+
+use byteorder::{LittleEndian, ReadBytesExt as _};
+
+use crate::{
+    chunk::{invalid_data, Chunk, CursorReadingExtensions as _, ReadableChunk, Signature},
+    AccountInfoList, Album, AlbumMap, Artist, ArtistMap, CollectionList, Header, LibraryMaster, MusicDbView,
+    ParseError, SectionBounds, SectionKind, Track, TrackMap,
+};
+
+/// Every section which can be [hydrated](MusicDbReader::hydrate).
+pub const ALL_SECTIONS: &[SectionKind] = &[
+    SectionKind::LibraryMaster,
+    SectionKind::Albums,
+    SectionKind::Artists,
+    SectionKind::Accounts,
+    SectionKind::Tracks,
+    SectionKind::Collections,
+];
+
+/// A reader over decoded library data which only locates its sections up front,
+/// leaving their contents to be parsed once (and if) they're asked for.
+///
+/// The file still has to be decoded as a whole beforehand, but nothing within it is parsed
+/// until it's iterated over or [hydrated](Self::hydrate).
+///
+/// ```no_run
+/// # use musicdb::{MusicDB, MusicDbReader};
+/// let data = MusicDB::decode(MusicDB::default_path())?;
+/// let reader = MusicDbReader::new(&data)?;
+/// for track in reader.tracks()? {
+///     println!("{:?}", track?.name);
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug)]
+pub struct MusicDbReader<'a> {
+    /// The version of the format of the file.
+    pub format_version: crate::version::FormatVersion,
+    /// The layout that the file was determined to have, based off of the version of the application which wrote it.
+    pub layout: crate::version::Layout,
+    data: &'a [u8],
+    sections: Vec<SectionBounds>,
+}
+impl<'a> MusicDbReader<'a> {
+    /// Reads the header of the decoded data and finds where each of its sections are.
+    pub fn new(data: &'a [u8]) -> Result<Self, ParseError> {
+        let mut cursor = Cursor::new(data);
+        crate::chunks::SectionBoundary::<u32>::read(&mut cursor)?;
+        let header = Header::read(&mut cursor)?;
+        let format_version = header.format_version;
+        let layout = crate::version::Layout::detect(&header.app_version.to_string_lossy())
+            .map_err(|error| ParseError::UnsupportedVersion { format_version, error })?;
+
+        let sections = SectionBounds::scan(cursor)?;
+        if let Some(&kind) = ALL_SECTIONS.iter().find(|&&kind| {
+            layout.requires(kind) && !sections.iter().any(|section| section.kind == Some(kind))
+        }) {
+            return Err(ParseError::MissingSection(kind))
+        }
+
+        Ok(Self { format_version, layout, data, sections })
+    }
+
+    /// Whether the data has a section of the given kind.
+    pub fn has_section(&self, kind: SectionKind) -> bool {
+        self.section(kind).is_some()
+    }
+
+    fn section(&self, kind: SectionKind) -> Option<&SectionBounds> {
+        self.sections.iter().find(|section| section.kind == Some(kind))
+    }
+
+    /// Returns an iterator which parses each track as it's reached.
+    pub fn tracks(&self) -> Result<Items<'a, Track<'a>>, ParseError> {
+        self.items(SectionKind::Tracks, TrackMap::SIGNATURE)
+    }
+    /// Returns an iterator which parses each album as it's reached.
+    pub fn albums(&self) -> Result<Items<'a, Album<'a>>, ParseError> {
+        self.items(SectionKind::Albums, AlbumMap::SIGNATURE)
+    }
+    /// Returns an iterator which parses each artist as it's reached.
+    pub fn artists(&self) -> Result<Items<'a, Artist<'a>>, ParseError> {
+        self.items(SectionKind::Artists, ArtistMap::SIGNATURE)
+    }
+
+    /// Reads the header of the list that a section consists of, leaving its items to the returned iterator.
+    /// Sections that aren't present are treated as empty.
+    fn items<T: ReadableChunk<'a>>(&self, kind: SectionKind, list: Signature) -> Result<Items<'a, T>, ParseError> {
+        let Some(section) = self.section(kind) else {
+            return Ok(Items { cursor: Cursor::new(self.data), kind, remaining: 0, _item: PhantomData })
+        };
+
+        let mut cursor = Cursor::new(self.data);
+        cursor.set_position(section.start);
+        let remaining = (|| {
+            let offset = cursor.position();
+            let signature = cursor.read_signature()?;
+            if signature != list {
+                return Err(invalid_data(format!("invalid header @0x{offset:X}, expected {list:?} got {signature:?}")))
+            }
+            let length = cursor.read_u32::<LittleEndian>()?;
+            let count = cursor.read_u32::<LittleEndian>()?;
+            // The items start wherever the list header says it ends, which mustn't be within what was just read.
+            if length < 12 {
+                return Err(invalid_data(format!("list @0x{offset:X} claims a length of {length}, which doesn't cover its own header")))
+            }
+            cursor.set_position(offset + u64::from(length));
+            Ok(count as usize)
+        })().map_err(|error| ParseError::Section { section: kind, message: error.to_string() })?;
+
+        Ok(Items { cursor, kind, remaining, _item: PhantomData })
+    }
+
+    /// Parses only the given sections into a view, leaving every other one empty (or [`None`], for accounts).
+    pub fn hydrate(&self, sections: &[SectionKind]) -> Result<MusicDbView<'a>, ParseError> {
+        let data = self.data;
+        let find = |kind: SectionKind| self.section(kind).filter(|_| sections.contains(&kind));
+
+        let library = find(SectionKind::LibraryMaster);
+        let albums = find(SectionKind::Albums);
+        let artists = find(SectionKind::Artists);
+        let accounts = find(SectionKind::Accounts);
+        let tracks = find(SectionKind::Tracks);
+        let collections = find(SectionKind::Collections);
+
+        // Sections are independent of one another once their boundaries are known, so they can be parsed in parallel.
+        std::thread::scope(|scope| {
+            macro_rules! spawn {
+                ($section: ident, $type: ty) => {
+                    $section.map(|section| scope.spawn(move || section.read::<$type>(data)))
+                }
+            }
+            macro_rules! join {
+                ($handle: expr) => {
+                    $handle.map(|handle| handle.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic))).transpose()
+                }
+            }
+
+            let library = spawn!(library, LibraryMaster<'a>);
+            let albums = spawn!(albums, AlbumMap<'a>);
+            let artists = spawn!(artists, ArtistMap<'a>);
+            let accounts = spawn!(accounts, AccountInfoList<'a>);
+            let tracks = spawn!(tracks, TrackMap<'a>);
+            let collections = spawn!(collections, CollectionList<'a>);
+
+            let mut view = MusicDbView {
+                format_version: self.format_version,
+                layout: self.layout,
+                library: join!(library)?.unwrap_or_default(),
+                albums: join!(albums)?.unwrap_or_default(),
+                artists: join!(artists)?.unwrap_or_default(),
+                accounts: join!(accounts)?,
+                tracks: join!(tracks)?.unwrap_or_default(),
+                collections: join!(collections)?.unwrap_or_default(),
+            };
+            view.collections.classify_folders();
+            Ok(view)
+        })
+    }
+}
+
+/// An iterator over the items of a section, parsing each one only as it's reached.
+///
+/// Iteration stops after the first item which fails to parse, as where the next one starts is no longer known.
+pub struct Items<'a, T> {
+    cursor: Cursor<&'a [u8]>,
+    kind: SectionKind,
+    remaining: usize,
+    _item: PhantomData<T>,
+}
+impl<'a, T: ReadableChunk<'a>> Iterator for Items<'a, T> {
+    type Item = Result<T, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        Some(T::read(&mut self.cursor).map_err(|error| {
+            self.remaining = 0;
+            ParseError::Section { section: self.kind, message: error.to_string() }
+        }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining.min(1), Some(self.remaining))
+    }
+}
+impl<'a, T: ReadableChunk<'a>> core::iter::FusedIterator for Items<'a, T> {}
+impl<T> core::fmt::Debug for Items<'_, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Items")
+            .field("section", &self.kind)
+            .field("position", &self.cursor.position())
+            .field("remaining", &self.remaining)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::{Fixture, Size};
+
+    #[test]
+    fn matches_full_view() {
+        let data = Fixture::build(Size::SMALL).data;
+        let reader = MusicDbReader::new(&data).expect("fixture should parse");
+        let view = reader.hydrate(ALL_SECTIONS).expect("fixture should parse");
+
+        let tracks = reader.tracks().unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(tracks.len(), view.tracks.len());
+        for track in &tracks {
+            assert_eq!(format!("{track:?}"), format!("{:?}", view.tracks[&track.persistent_id]));
+        }
+        assert_eq!(reader.albums().unwrap().count(), view.albums.len());
+        assert_eq!(reader.artists().unwrap().count(), view.artists.len());
+    }
+
+    #[test]
+    fn hydrates_only_what_is_asked_for() {
+        let data = Fixture::build(Size::SMALL).data;
+        let reader = MusicDbReader::new(&data).expect("fixture should parse");
+        let view = reader.hydrate(&[SectionKind::Albums]).expect("fixture should parse");
+        assert_eq!(view.albums.len(), Size::SMALL.tracks.div_ceil(12));
+        assert!(view.tracks.is_empty() && view.artists.is_empty());
+    }
+
+    #[test]
+    fn stops_after_a_bad_item() {
+        let mut data = Fixture::build(Size::SMALL).data;
+        let reader = MusicDbReader::new(&data).expect("fixture should parse");
+        let first = reader.tracks().unwrap().cursor.position() as usize;
+        drop(reader);
+
+        data[first..first + 4].copy_from_slice(b"xxxx");
+        let reader = MusicDbReader::new(&data).expect("items aren't parsed up front");
+        let mut tracks = reader.tracks().unwrap();
+        assert!(matches!(tracks.next(), Some(Err(ParseError::Section { section: SectionKind::Tracks, .. }))));
+        assert!(tracks.next().is_none());
+    }
+}
@@ -21,3 +21,134 @@ impl From<KilobitsPerSecond> for u64 {
         val.0 as u64
     }
 }
+
+/// The amount of seconds between the "classic" Mac OS epoch of 1904-01-01, which timestamps within the library count from, and the Unix epoch.
+pub const MAC_EPOCH_OFFSET: i64 = 2082844800;
+
+/// Converts a timestamp as stored within the library into a [`chrono::DateTime`].
+/// Returns `None` if the timestamp is zero, which is used to indicate the absence of a value.
+///
+/// As on classic Mac OS, timestamps are of the local time of the machine, so they're read as being in its current time zone.
+pub fn timestamp(seconds: u32) -> Option<chrono::DateTime<chrono::Utc>> {
+    timestamp_in(seconds, &chrono::Local)
+}
+
+fn timestamp_in<Tz: chrono::TimeZone>(seconds: u32, zone: &Tz) -> Option<chrono::DateTime<chrono::Utc>> {
+    if seconds == 0 { return None }
+    let local = chrono::DateTime::from_timestamp(seconds as i64 - MAC_EPOCH_OFFSET, 0)?.naive_utc();
+    zone.from_local_datetime(&local).earliest().map(|time| time.to_utc())
+}
+
+/// Converts a [`chrono::DateTime`] back into the form it'd be stored as within the library, in the local time of the machine.
+/// Returns `None` if the time can't be represented (i.e. it's before the epoch or too far in the future).
+pub fn timestamp_to_raw(time: chrono::DateTime<chrono::Utc>) -> Option<u32> {
+    timestamp_to_raw_in(time, &chrono::Local)
+}
+
+fn timestamp_to_raw_in<Tz: chrono::TimeZone>(time: chrono::DateTime<chrono::Utc>, zone: &Tz) -> Option<u32> {
+    let local = time.with_timezone(zone).naive_local().and_utc().timestamp();
+    u32::try_from(local + MAC_EPOCH_OFFSET).ok().filter(|raw| *raw != 0)
+}
+
+/// Converts a millisecond count as stored within the library into a [`core::time::Duration`].
+pub const fn duration_from_millis(milliseconds: u32) -> core::time::Duration {
+    core::time::Duration::from_millis(milliseconds as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timestamp_zero_is_absent() {
+        assert_eq!(timestamp(0), None);
+    }
+
+    #[test]
+    fn timestamp_known_values() {
+        // Unix epoch.
+        assert_eq!(timestamp_in(MAC_EPOCH_OFFSET as u32, &chrono::Utc), chrono::DateTime::from_timestamp(0, 0));
+        // 2020-01-01T00:00:00Z
+        let time = timestamp_in(3660681600, &chrono::Utc).unwrap();
+        assert_eq!(time.to_rfc3339(), "2020-01-01T00:00:00+00:00");
+        assert_eq!(timestamp_to_raw_in(time, &chrono::Utc), Some(3660681600));
+    }
+
+    /// Libraries of machines on Pacific time were seen to be seven hours short of the epoch during daylight saving time.
+    #[test]
+    fn timestamp_is_local() {
+        let pacific = chrono::FixedOffset::west_opt(7 * 60 * 60).unwrap();
+        let time = timestamp_in(3660656400, &pacific).unwrap();
+        assert_eq!(time.to_rfc3339(), "2020-01-01T00:00:00+00:00");
+        assert_eq!(timestamp_to_raw_in(time, &pacific), Some(3660656400));
+    }
+
+    #[test]
+    fn timestamp_out_of_range() {
+        assert_eq!(timestamp_to_raw_in(chrono::DateTime::UNIX_EPOCH - chrono::TimeDelta::days(365 * 100), &chrono::Utc), None);
+    }
+
+    #[test]
+    fn timestamp_from_fixture() {
+        use crate::fixture::{Fixture, COLLABORATION_TRACK};
+        let db = crate::MusicDB::from_decoded(Fixture::collaboration().data.into_boxed_slice(), "Library.musicdb").expect("fixture should parse");
+        let track = db.tracks().get(&crate::PersistentId::new(COLLABORATION_TRACK)).expect("track should be in the library");
+        let added = track.numerics.date_added.expect("track should have been added");
+        let expected = chrono::NaiveDate::from_ymd_opt(2018, 1, 28).unwrap().and_hms_opt(16, 0, 0).unwrap();
+        assert_eq!(added.with_timezone(&chrono::Local).naive_local(), expected);
+        assert_eq!(timestamp_to_raw(added), Some(3_600_000_000));
+    }
+
+    #[test]
+    fn duration() {
+        assert_eq!(duration_from_millis(215_367), core::time::Duration::new(215, 367_000_000));
+    }
+
+    /// Compares what's decoded against the library's XML export (made with "File > Library > Export Library…"), which Apple Music writes itself.
+    /// Each sample should be a folder within the samples directory containing both `Library.musicdb` and its export as `Library.xml`.
+    #[test]
+    #[ignore = "needs populated samples directory"]
+    fn against_exported_samples() {
+        use xml::{arena::DefaultArena, plist::PlistValue, span::Span, Document};
+
+        let samples = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("samples");
+        let mut compared = 0;
+        for entry in std::fs::read_dir(samples).expect("fs error") {
+            let folder = entry.expect("fs error").path();
+            let (library, export) = (folder.join("Library.musicdb"), folder.join("Library.xml"));
+            if !library.exists() || !export.exists() { continue }
+
+            let musicdb = crate::MusicDB::read_path(&library).expect("failed to read sample");
+            let export = std::fs::read_to_string(&export).expect("fs error");
+            let mut arena = DefaultArena::default();
+            let document = Document::parse(&Span::new_root(&export), &mut arena).expect("failed to parse export");
+            let export = PlistValue::from_root(document.root(&arena), &arena).expect("failed to interpret export");
+            let tracks = export.as_dictionary().and_then(|export| export.get("Tracks")).and_then(PlistValue::as_dictionary).expect("export should list tracks");
+
+            for (_, exported) in tracks.iter() {
+                let exported = exported.as_dictionary().expect("exported track should be a dictionary");
+                let id = exported.get("Persistent ID").and_then(PlistValue::as_str).expect("exported track should have a persistent ID");
+                let Some(track) = musicdb.tracks().get(&crate::PersistentId::new(u64::from_str_radix(id, 16).expect("bad persistent ID"))) else { continue };
+                if let Some(added) = exported.get("Date Added").and_then(PlistValue::as_date) {
+                    assert_eq!(track.numerics.date_added.as_ref(), Some(added), "date added of {id}");
+                }
+                if let Some(total) = exported.get("Total Time").and_then(PlistValue::as_integer) {
+                    assert_eq!(i64::try_from(track.numerics.duration.as_millis()).ok(), Some(total), "duration of {id}");
+                }
+                for (key, decoded) in [
+                    ("Name", track.name),
+                    ("Composer", track.composer),
+                    ("Comments", track.comment),
+                    ("Grouping", track.grouping),
+                    ("Work", track.classical_work_name),
+                    ("Movement Name", track.classical_movement_title),
+                ] {
+                    let exported = exported.get(key).and_then(PlistValue::as_str);
+                    assert_eq!(decoded.map(|decoded| decoded.to_string()).as_deref(), exported, "{key} of {id}");
+                }
+                compared += 1;
+            }
+        }
+        assert!(compared > 0, "no tracks of exported sample libraries were found");
+    }
+}
@@ -65,8 +65,86 @@ impl core::cmp::PartialOrd for AppleMusicVersion {
 impl core::cmp::Ord for AppleMusicVersion {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         self.major.cmp(&other.major)
-            .then_with(|| self.minor.cmp(&self.minor))
-            .then_with(|| self.patch.cmp(&self.patch))
-            .then_with(|| self.revision.cmp(&self.revision))
+            .then_with(|| self.minor.cmp(&other.minor))
+            .then_with(|| self.patch.cmp(&other.patch))
+            .then_with(|| self.revision.cmp(&other.revision))
+    }
+}
+
+/// The version of the format of a library file, as declared in its header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FormatVersion {
+    pub major: u16,
+    pub minor: u16,
+}
+impl core::fmt::Display for FormatVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum UnsupportedVersionError {
+    #[error("unrecognizable application version {0:?}")]
+    Unrecognizable(String),
+    #[error("library was written by iTunes {0}, which predates the musicdb format")]
+    PredatesFormat(AppleMusicVersion),
+}
+
+/// How the sections of a library file are laid out, and thus how they need to be read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// Sections appear in a fixed order (library master, albums, artists, accounts if present, tracks, collections),
+    /// and all but the accounts are always present. This is what current versions of Music.app write.
+    Sequential,
+    /// Sections appear in any order, and all but the tracks may be absent.
+    /// Used for files written by iTunes, which order their sections differently and lack some of them entirely.
+    Scanning,
+}
+impl Layout {
+    /// The major version of iTunes which wrote musicdb files; earlier versions only wrote `.itl` files.
+    const ITUNES_MAJOR: u8 = 12;
+
+    /// Whether a section of the given kind must be present in a file with this layout.
+    pub fn requires(self, kind: crate::SectionKind) -> bool {
+        use crate::SectionKind;
+        match self {
+            Self::Sequential => !matches!(kind, SectionKind::Accounts | SectionKind::Header),
+            Self::Scanning => kind == SectionKind::Tracks,
+        }
+    }
+
+    /// Determines the layout of a file from the version of the application which wrote it.
+    pub fn detect(app_version: &str) -> Result<Self, UnsupportedVersionError> {
+        let version: AppleMusicVersion = app_version.parse()
+            .map_err(|_| UnsupportedVersionError::Unrecognizable(app_version.to_owned()))?;
+
+        match version.major {
+            // Music.app restarted its versioning at 1.0 when it split off from iTunes.
+            1 => Ok(Self::Sequential),
+            Self::ITUNES_MAJOR => Ok(Self::Scanning),
+            2..Self::ITUNES_MAJOR => Err(UnsupportedVersionError::PredatesFormat(version)),
+            _ => Err(UnsupportedVersionError::Unrecognizable(app_version.to_owned())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layout_detection() {
+        assert_eq!(Layout::detect("1.4.5.7"), Ok(Layout::Sequential));
+        assert_eq!(Layout::detect("12.13.2.3"), Ok(Layout::Scanning));
+        assert!(matches!(Layout::detect("11.4.0.18"), Err(UnsupportedVersionError::PredatesFormat(_))));
+        assert!(matches!(Layout::detect("garbage"), Err(UnsupportedVersionError::Unrecognizable(_))));
+    }
+
+    #[test]
+    fn version_ordering() {
+        let a: AppleMusicVersion = "1.4.5.7".parse().unwrap();
+        let b: AppleMusicVersion = "1.5.0.1".parse().unwrap();
+        assert!(a < b);
     }
 }
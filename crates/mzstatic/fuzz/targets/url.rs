@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mzstatic::image::MzStaticImage;
+
+fuzz_target!(|input: &str| {
+    if let Ok(image) = MzStaticImage::parse(input) {
+        let _ = image.to_string();
+    }
+    let _ = MzStaticImage::with_pool_and_token(input.into());
+});
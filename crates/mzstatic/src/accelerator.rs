@@ -45,6 +45,33 @@ impl<'a> From<UnknownRegion<'a>> for ReadError<'a> {
     }
 }
 
+/// A number as it's written in a directive, keeping its leading zeros; they aren't insignificant (e.x. `0` can work where `000` doesn't).
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Padded {
+    pub value: u32,
+    /// How many digits it's written with, at the least.
+    pub width: usize,
+}
+impl Padded {
+    pub const fn new(value: u32, width: usize) -> Self {
+        Self { value, width }
+    }
+
+    fn parse(digits: &str) -> Result<Self, core::num::ParseIntError> {
+        Ok(Self { value: digits.parse()?, width: digits.len() })
+    }
+}
+impl From<u32> for Padded {
+    fn from(value: u32) -> Self {
+        Self { value, width: 0 }
+    }
+}
+impl core::fmt::Display for Padded {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:0width$}", self.value, width = self.width)
+    }
+}
+
 /// ## Accelerator Directives
 /// 
 /// Presumably some sort of parameters for the "Accelerator" reverse proxy.
@@ -66,8 +93,9 @@ impl<'a> From<UnknownRegion<'a>> for ReadError<'a> {
 /// ```
 /// 
 /// The region and "v" value are both optional, but an r-value will always be present.
+/// The [displayed](core::fmt::Display) form has no trailing slash.
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub struct Directives<'a>  {
+pub struct Directives {
     /// ### Region
     /// 
     /// This can be omitted in certain circumstances. <!-- When? -->
@@ -89,18 +117,37 @@ pub struct Directives<'a>  {
     /// - <https://s1.mzstatic.com/us/r1000/0/Music122/v4/c8/03/57/c803571e-6d17-f10f-fddf-fd4f7fc00d5e/22UMGIM37441.rgb.jpg>
     ///   - Usage of an "r" value of 30 results in an Akamai EdgeSuite error.
     pub r: u16,
-    /// ### Unknown "n" value.
+    /// ### Unknown "v" value.
     /// 
     /// Of varying stability and presence.
     /// - <https://s1.mzstatic.com/us/r1000/000/Features/atv/AutumnResources/videos/entries.json>
     ///   - Any other values don't work. Not compared numerically, as "0" doesn't work.
     /// - <https://s1.mzstatic.com/us/r1000/0/Music122/v4/c8/03/57/c803571e-6d17-f10f-fddf-fd4f7fc00d5e/22UMGIM37441.rgb.jpg>
     ///   - Any other value **does** work.
-    pub v: Option<&'a str>
+    pub v: Option<Padded>,
 }
-impl<'a> Directives<'a> {
+impl Directives {
+    pub const fn new(r: u16) -> Self {
+        Self { region: None, r, v: None }
+    }
+
+    pub const fn with_region(mut self, region: Option<Region>) -> Self {
+        self.region = region;
+        self
+    }
+
+    pub const fn with_r(mut self, r: u16) -> Self {
+        self.r = r;
+        self
+    }
+
+    pub fn with_v(mut self, v: Option<impl Into<Padded>>) -> Self {
+        self.v = v.map(Into::into);
+        self
+    }
+
     // expected to pass contents after prefix (if present), no leading slash
-    pub(crate) fn read(mut input: &'a str) -> Result<Option<Read<Directives<'a>>>, ReadError<'a>> {
+    pub(crate) fn read(mut input: &str) -> Result<Option<Read<Directives>>, ReadError<'_>> {
         let start_ptr = input.as_ptr();
 
         macro_rules! read_r {
@@ -129,12 +176,12 @@ impl<'a> Directives<'a> {
 
         let region = if !read_r!(input, r) {
             if let Some(region) = read!(input, delimit char: '/') {
-                let region = Region::try_from(region);
-
-                // idk how to handle this better
-                if region.is_err() { return Ok(None) }
-                let region = region.unwrap();
-
+                let region = match Region::try_from(region) {
+                    Ok(region) => region,
+                    // Only an error if it's followed by an r-value; otherwise it's just not directives, but some other part of the path.
+                    Err(unknown) if Self::starts_with_r_value(input) => return Err(unknown.into()),
+                    Err(_) => return Ok(None),
+                };
                 read_r!(input, r);
                 Some(region)
             } else { None }
@@ -144,12 +191,15 @@ impl<'a> Directives<'a> {
 
         let mut bytes = unsafe { core::num::NonZeroUsize::new_unchecked(input.as_ptr().sub(start_ptr as usize) as usize) };
 
-        let v = if let Some(after) = read!(input, delimit char: '/').filter(|s| s.chars().all(|char| char.is_ascii_digit())) {
+        let v = if let Some(after) = read!(input, delimit char: '/').filter(|s| !s.is_empty() && s.chars().all(|char| char.is_ascii_digit())) {
             bytes = match bytes.checked_add('/'.len_utf8() + after.len()) {
                 Some(read) => read,
                 None => unsafe { core::hint::unreachable_unchecked() } // we'll always be <= size of `input`, and input is max len usize, so we won't go beyond that
             };
-            Some(after)
+            Some(Padded::parse(after).map_err(|error| ReadError::BadNumeric {
+                inner: error,
+                variant: ParseValueVariant::V
+            })?)
         } else { None };
 
         Ok(Some(Read {
@@ -160,15 +210,19 @@ impl<'a> Directives<'a> {
             }
         }))
     }
+
+    fn starts_with_r_value(input: &str) -> bool {
+        input.strip_prefix('r').and_then(|after| after.split_once('/')).is_some_and(|(digits, _)| !digits.is_empty() && digits.bytes().all(|byte| byte.is_ascii_digit()))
+    }
 }
-impl core::fmt::Display for Directives<'_> {
+impl core::fmt::Display for Directives {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         if let Some(region) = self.region {
             write!(f, "{region}/")?;
         }
-        write!(f, "r{}/", self.r)?;
+        write!(f, "r{}", self.r)?;
         if let Some(v) = self.v {
-            write!(f, "{v}")?;
+            write!(f, "/{v}")?;
         }
         Ok(())
     }
@@ -229,7 +283,7 @@ mod tests {
         assert_eq!(Directives::read("au/r32/123/"), Ok(Some(Read { bytes: core::num::NonZeroUsize::new(11).unwrap(), value: Directives {
             region: Some(Region::AU),
             r: 32,
-            v: Some("123"),
+            v: Some(Padded::new(123, 3)),
         }})));
         assert_eq!(Directives::read("au/r32/not-numeric/"), Ok(Some(Read { bytes: core::num::NonZeroUsize::new(7).unwrap(), value: Directives {
             region: Some(Region::AU),
@@ -239,6 +293,30 @@ mod tests {
 
     }
 
+    #[test]
+    fn display() {
+        assert_eq!(Directives::read("us/r30/049/").unwrap().unwrap().value.to_string(), "us/r30/049");
+        assert_eq!(Directives::read("r1000/000/").unwrap().unwrap().value.to_string(), "r1000/000");
+        assert_eq!(Directives::read("au/r32/a1/").unwrap().unwrap().value.to_string(), "au/r32");
+    }
+
+    #[test]
+    fn modification() {
+        let directives = Directives::read("us/r30/049/").unwrap().unwrap().value;
+        assert_eq!(directives.with_region(Some(Region::JP)).to_string(), "jp/r30/049");
+        assert_eq!(directives.with_region(None).with_r(1000).to_string(), "r1000/049");
+        assert_eq!(directives.with_v(Some(50)).to_string(), "us/r30/50");
+        assert_eq!(directives.with_v(Some(Padded::new(50, 3))).to_string(), "us/r30/050");
+        assert_eq!(directives.with_v(None::<Padded>).to_string(), "us/r30");
+        assert_eq!(Directives::new(10).with_region(Some(Region::EU)).to_string(), "eu/r10");
+    }
+
+    #[test]
+    fn not_directives() {
+        assert_eq!(Directives::read("Music4/v4/a1/"), Ok(None));
+        assert_eq!(Directives::read("image/thumb/"), Ok(None));
+    }
+
     #[test]
     fn r_value_and_v_value() {
         assert_eq!(Directives::read("r32/not-numeric/"), Ok(Some(Read { bytes: core::num::NonZeroUsize::new(4).unwrap(), value: Directives {
@@ -249,7 +327,7 @@ mod tests {
         assert_eq!(Directives::read("r32/000/"), Ok(Some(Read { bytes: core::num::NonZeroUsize::new(8).unwrap(), value: Directives {
             region: None,
             r: 32,
-            v: Some("000"),
+            v: Some(Padded::new(0, 3)),
         }})));
     }
 }
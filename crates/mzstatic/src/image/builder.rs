@@ -0,0 +1,220 @@
+use maybe_owned_string::MaybeOwnedString;
+
+use crate::accelerator::Directives;
+use super::{effect::Effect, quality, Details, ImageFormat, MzStaticImage, PoolOrSagaSpecifier, Prefix, Resolution};
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    #[error("no pool was provided")]
+    MissingPool,
+    #[error("no asset token was provided")]
+    MissingToken,
+    #[error("asset token \"{0}\" is empty, has a leading or trailing slash, or contains characters which cannot appear in the path")]
+    InvalidToken(String),
+    #[error("subdomain \"{0}\" is empty or contains characters other than ASCII alphanumerics and dashes")]
+    InvalidSubdomain(String),
+    #[error("resolution {0} has a zero-length dimension")]
+    EmptyResolution(Resolution),
+    #[error("quality {0} is out of range: {1}")]
+    QualityOutOfRange(u16, quality::OutOfRangeError),
+    #[error("language \"{0}\" is not a well-formed language tag")]
+    InvalidLanguage(String),
+}
+
+/// Incrementally constructs an [`MzStaticImage`], validating every component upon [`Builder::build`].
+///
+/// Unless otherwise specified, the image is retrieved over HTTPS from the `is1-ssl` subdomain with the [`Prefix::ImageThumbnail`] prefix,
+/// as that is what's needed for the resolution and effect to be respected.
+#[derive(Debug, Clone)]
+pub struct Builder<'a> {
+    https: bool,
+    subdomain: MaybeOwnedString<'a>,
+    prefix: Option<Prefix>,
+    accelerator_directives: Option<Directives>,
+    pool: Option<PoolOrSagaSpecifier>,
+    asset_token: Option<MaybeOwnedString<'a>>,
+    resolution: Resolution,
+    effect: Option<Effect>,
+    quality: Option<u16>,
+    image_format: ImageFormat,
+    language: Option<MaybeOwnedString<'a>>,
+}
+impl Default for Builder<'_> {
+    fn default() -> Self {
+        let details = Details::default();
+        Self {
+            https: true,
+            subdomain: MaybeOwnedString::Borrowed("is1-ssl"),
+            prefix: Some(Prefix::ImageThumbnail),
+            accelerator_directives: None,
+            pool: None,
+            asset_token: None,
+            resolution: details.resolution,
+            effect: details.effect,
+            quality: None,
+            image_format: details.image_format,
+            language: None,
+        }
+    }
+}
+impl<'a> Builder<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn https(mut self, https: bool) -> Self {
+        self.https = https;
+        self
+    }
+
+    pub fn subdomain(mut self, subdomain: impl Into<MaybeOwnedString<'a>>) -> Self {
+        self.subdomain = subdomain.into();
+        self
+    }
+
+    pub fn prefix(mut self, prefix: Option<Prefix>) -> Self {
+        self.prefix = prefix;
+        self
+    }
+
+    pub fn accelerator_directives(mut self, directives: Option<Directives>) -> Self {
+        self.accelerator_directives = directives;
+        self
+    }
+
+    pub fn pool(mut self, pool: impl Into<PoolOrSagaSpecifier>) -> Self {
+        self.pool = Some(pool.into());
+        self
+    }
+
+    /// The path between the pool and the image details, without any leading or trailing slash.
+    pub fn token(mut self, token: impl Into<MaybeOwnedString<'a>>) -> Self {
+        self.asset_token = Some(token.into());
+        self
+    }
+
+    pub fn resolution(mut self, resolution: impl Into<Resolution>) -> Self {
+        self.resolution = resolution.into();
+        self
+    }
+
+    pub fn effect(mut self, effect: Option<Effect>) -> Self {
+        self.effect = effect;
+        self
+    }
+
+    /// The quality of the image, which must be in the range accepted by [`quality::Quality::new`].
+    pub fn quality(mut self, quality: u16) -> Self {
+        self.quality = Some(quality);
+        self
+    }
+
+    pub fn format(mut self, format: ImageFormat) -> Self {
+        self.image_format = format;
+        self
+    }
+
+    /// The language to use for the framing text, as an IETF language tag (e.x. `en-US`).
+    pub fn language(mut self, language: impl Into<MaybeOwnedString<'a>>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    pub fn build(self) -> Result<MzStaticImage<'a>, BuildError> {
+        let pool = self.pool.ok_or(BuildError::MissingPool)?;
+        let asset_token = match (&pool, self.asset_token) {
+            // Generated artwork has no asset token; it's described entirely by its details.
+            (PoolOrSagaSpecifier::Generated, None) => MaybeOwnedString::Borrowed(""),
+            (PoolOrSagaSpecifier::Generated, Some(token)) => return Err(BuildError::InvalidToken(token.to_string())),
+            (_, None) => return Err(BuildError::MissingToken),
+            (_, Some(token)) => {
+                if token.is_empty() || token.starts_with('/') || token.ends_with('/') || token.contains(|char: char| matches!(char, '?' | '#') || char.is_whitespace()) {
+                    return Err(BuildError::InvalidToken(token.to_string()))
+                }
+                token
+            }
+        };
+
+        if self.subdomain.is_empty() || !self.subdomain.chars().all(|char| char.is_ascii_alphanumeric() || char == '-') {
+            return Err(BuildError::InvalidSubdomain(self.subdomain.to_string()))
+        }
+
+        if let Resolution::Filled(x, y) = self.resolution {
+            if x == 0 || y == 0 {
+                return Err(BuildError::EmptyResolution(self.resolution))
+            }
+        }
+
+        let quality = self.quality.map(|quality| quality::Quality::new(quality).map_err(|error| BuildError::QualityOutOfRange(quality, error))).transpose()?;
+
+        if let Some(language) = &self.language {
+            let well_formed = language.split('-').all(|subtag| (1..=8).contains(&subtag.len()) && subtag.chars().all(|char| char.is_ascii_alphanumeric()));
+            if !well_formed {
+                return Err(BuildError::InvalidLanguage(language.to_string()))
+            }
+        }
+
+        Ok(MzStaticImage {
+            https: self.https,
+            accelerator_directives: self.accelerator_directives,
+            pool,
+            prefix: self.prefix,
+            asset_token,
+            subdomain: self.subdomain,
+            parameters: Details {
+                image_format: self.image_format,
+                effect: self.effect,
+                quality,
+                resolution: self.resolution,
+                language: self.language,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pool::{Pool, Variant};
+
+    const TOKEN: &str = "v4/47/98/ae/4798ae9f-3199-dffa-980c-1d7c9ba56189/artwork.jpg";
+
+    fn music() -> Pool {
+        Pool { variant: Variant::Music, number: core::num::NonZeroU8::new(221) }
+    }
+
+    #[test]
+    fn build() {
+        let image = MzStaticImage::builder()
+            .pool(music())
+            .token(TOKEN)
+            .resolution((520, 520))
+            .effect(Some(Effect::SquareFitCircle))
+            .quality(80)
+            .format(ImageFormat::Webp)
+            .language("en-US")
+            .build()
+            .unwrap();
+        assert_eq!(image.to_string(), format!("https://is1-ssl.mzstatic.com/image/thumb/Music221/{TOKEN}/520x520ac-80.webp?l=en-US"));
+
+        let image = MzStaticImage::builder().pool(music()).token(TOKEN).build().unwrap();
+        assert_eq!(image.to_string(), format!("https://is1-ssl.mzstatic.com/image/thumb/Music221/{TOKEN}/300x300.png"));
+    }
+
+    #[test]
+    fn validation() {
+        let base = || MzStaticImage::builder().pool(music()).token(TOKEN);
+        assert!(matches!(MzStaticImage::builder().token(TOKEN).build(), Err(BuildError::MissingPool)));
+        assert!(matches!(MzStaticImage::builder().pool(music()).build(), Err(BuildError::MissingToken)));
+        assert!(matches!(base().token("/v4/a.jpg").build(), Err(BuildError::InvalidToken(_))));
+        assert!(matches!(base().token("v4/a.jpg?x=1").build(), Err(BuildError::InvalidToken(_))));
+        assert!(matches!(base().subdomain("is1.ssl").build(), Err(BuildError::InvalidSubdomain(_))));
+        assert!(matches!(base().resolution((0, 600)).build(), Err(BuildError::EmptyResolution(_))));
+        assert!(matches!(base().quality(1000).build(), Err(BuildError::QualityOutOfRange(1000, _))));
+        assert!(matches!(base().language("en US").build(), Err(BuildError::InvalidLanguage(_))));
+        assert!(matches!(base().language("en--US").build(), Err(BuildError::InvalidLanguage(_))));
+        assert!(base().quality(999).language("es-419").build().is_ok());
+        assert!(MzStaticImage::builder().pool(PoolOrSagaSpecifier::Generated).build().is_ok());
+        assert!(matches!(MzStaticImage::builder().pool(PoolOrSagaSpecifier::Generated).token(TOKEN).build(), Err(BuildError::InvalidToken(_))));
+    }
+}
@@ -25,53 +25,158 @@ pub enum Effect {
     /// Literal representation: "ac".
     // TODO: Test & document behavior on non-square images.
     SquareFitCircle,
+    /// Overlay a square frame onto the image.
+    /// 
+    /// Literal representation: "SC." followed by the framing.
     Frame(Framing),
-    MusicVideo, // = mv ; could be "music video" ??
-    BackgroundBlur, // ? = bb
-    BackgroundFill, // ? = bf
-
-
-    // mv => music video ?
-    // sr => irrelevant (used at home)
-    // ac => pad to square to fit in circle with blurred color around where doesnt fit
-    // bf => background fill?
-    // bb => could be background blur but i haven't seen it in high res or anything that matters. only typically used 80x80 or 220x220 (search results)
-    // mv => used in music videos. could maybe sstand for movie for all i know
-    // sr => used in wide editorial cards on homepage also this https://is1-ssl.mzstatic.com/image/thumb/Features/v4/a5/fc/4b/a5fc4bf1-aecc-538a-6c11-a181dd8e93a2/cb81b7ec-9b6f-4795-8c91-f80f6016809e.png/220x220sr.webp
-    // sc => ? seen on podcasts, user icons
-    // cc => ? will force to square
-    // vf => real wide. king crimson banner https://is1-ssl.mzstatic.com/image/thumb/Features125/v4/30/b8/fc/30b8fc23-fc6d-8006-fabd-b265b9c5a180/mzl.wnqeoeqa.jpg/2400x933vf-60.jpg
-    // ea => real wide. kendrick lamar banner https://is1-ssl.mzstatic.com/image/thumb/Features122/v4/3a/64/ae/3a64aedb-3b2a-3eb0-74de-6018eb900fc5/fd0d1a37-3179-4978-ba51-1bae0fc6e993.png/2400x933ea-60.jpg
-
+    /// Overlay a wide frame onto the image. Looks wacky even after adjusting the resolution.
+    /// - <https://is1-ssl.mzstatic.com/image/thumb/Features116/v4/78/4e/b1/784eb1dc-50df-632a-c7f0-8928d54dc070/mza_4913277387679559953.png/300x172SH.FPUD02.webp?l=en-US>
+    /// 
+    /// Literal representation: "SH." followed by the framing.
+    WideFrame(Framing),
+    /// Used for music video thumbnails. Could maybe stand for "movie" for all I know.
+    /// 
+    /// Literal representation: "mv".
+    MusicVideo,
+    /// Could be a background blur, but I haven't seen it in high resolution or anything that matters; typically used at 80x80 or 220x220 (search results).
+    /// 
+    /// Literal representation: "bb".
+    BackgroundBlur,
+    /// Literal representation: "bf".
+    BackgroundFill, // ?
+    /// Forces the image to be square.
+    /// 
+    /// Literal representation: "cc".
+    CenterCrop,
+    /// Seen on podcasts and user icons.
+    /// 
+    /// Literal representation: "sc".
+    ProfileIcon, // ?
+    /// Used in the wide editorial cards on the homepage.
+    /// - <https://is1-ssl.mzstatic.com/image/thumb/Features/v4/a5/fc/4b/a5fc4bf1-aecc-538a-6c11-a181dd8e93a2/cb81b7ec-9b6f-4795-8c91-f80f6016809e.png/220x220sr.webp>
+    /// - <https://is1-ssl.mzstatic.com/image/thumb/WkLx7oCZ0vBL7G2rzdkcbQ/626x392sr.webp> ("Happening Now")
+    /// 
+    /// Literal representation: "sr".
+    EditorialCard,
+    /// Real wide; used for the banner of a featured artist.
+    /// - <https://is1-ssl.mzstatic.com/image/thumb/Features125/v4/30/b8/fc/30b8fc23-fc6d-8006-fabd-b265b9c5a180/mzl.wnqeoeqa.jpg/2400x933vf-60.jpg>
+    /// 
+    /// Literal representation: "vf".
+    FeaturedBanner,
+    /// Real wide; used for the banner at the top of an artist page.
+    /// - <https://is1-ssl.mzstatic.com/image/thumb/Features122/v4/3a/64/ae/3a64aedb-3b2a-3eb0-74de-6018eb900fc5/fd0d1a37-3179-4978-ba51-1bae0fc6e993.png/2400x933ea-60.jpg>
+    /// 
+    /// Literal representation: "ea".
+    ArtistBanner,
+    /// Used for the cast & crew on Apple TV.
+    /// - <https://is1-ssl.mzstatic.com/image/thumb/BwzocRAAEgP6sUF5tTNW9w/492x492ve.webp>
+    /// 
+    /// Literal representation: "ve".
+    CastAndCrew,
+    /// Used for the link preview of a podcast, at 1200x630.
+    /// - <https://is2-ssl.mzstatic.com/image/thumb/Podcasts112/v4/e7/a1/3c/e7a13c39-bf73-774f-174e-53b0edd4baad/mza_13783091193329431505.jpeg/1200x630wp.png>
+    /// 
+    /// Literal representation: "wp".
+    PodcastLinkPreview,
+    /// Used for the link preview of a book, at 1200x630.
+    /// - <https://is1-ssl.mzstatic.com/image/thumb/Publication113/v4/7f/c5/a0/7fc5a0ee-a55b-4319-4741-c7e8dd669333/9781501194313.jpg/1200x630wz.png>
+    /// 
+    /// Literal representation: "wz".
+    BookLinkPreview,
+    /// Used for App Store screenshots, typically with a resolution of 0x0.
+    /// 
+    /// Literal representation: "ss".
+    Screenshot,
+    /// Infer the height from the width, keeping the native aspect ratio (e.x. `300x0w`).
+    /// 
+    /// Literal representation: "w".
+    InferHeight,
+    /// Infer the width from the height, keeping the native aspect ratio (e.x. `0x300h`).
+    /// 
+    /// Literal representation: "h".
+    InferWidth,
+    /// Seen on the Apple TV logo; I don't understand what the resolution means on there.
+    /// - <https://is1-ssl.mzstatic.com/image/thumb/oEYYIjc6-3zT0jgpyUiIaw/1x40at.png>
+    /// 
+    /// Literal representation: "at".
+    AppleTvLogo,
+    /// A cover generated from a title and a set of colors, as used for playlists without artwork.
+    /// 
+    /// Literal representation: "AM." followed by the layout (e.x. `AM.PDCXS01`); the payload is carried in the query parameters.
+    GeneratedCover(GeneratedCover),
+    /// An effect that isn't otherwise recognized, stored as its literal representation so that it can be displayed again.
+    Unknown(String),
 }
 impl Effect {
-    /// Returns a two-character representation of the transformation variant to apply to the asset.
-    pub fn variant(&self) -> &'static str {
+    pub const FRAME_PREFIX: &'static str = "SC.";
+    pub const WIDE_FRAME_PREFIX: &'static str = "SH.";
+
+    /// Returns the representation of the transformation variant to apply to the asset.
+    ///
+    /// This is two characters for most effects, with the exception of single-character ones like [`Self::InferHeight`] and those which are [unknown](Self::Unknown).
+    pub fn variant(&self) -> &str {
         match self {
             Self::SquareFitCircle => "ac",
             Self::MusicVideo => "mv",
             Self::BackgroundBlur => "bb",
             Self::BackgroundFill => "bf",
-            Self::Frame(..) => "SC"
+            Self::CenterCrop => "cc",
+            Self::ProfileIcon => "sc",
+            Self::EditorialCard => "sr",
+            Self::FeaturedBanner => "vf",
+            Self::ArtistBanner => "ea",
+            Self::CastAndCrew => "ve",
+            Self::PodcastLinkPreview => "wp",
+            Self::BookLinkPreview => "wz",
+            Self::Screenshot => "ss",
+            Self::InferHeight => "w",
+            Self::InferWidth => "h",
+            Self::AppleTvLogo => "at",
+            Self::Frame(..) => "SC",
+            Self::WideFrame(..) => "SH",
+            Self::GeneratedCover(..) => "AM",
+            Self::Unknown(literal) => literal,
         }
     }
+
+    /// Whether the given string could be the literal representation of an effect, even if it isn't a known one.
+    fn is_well_formed(value: &str) -> bool {
+        !value.is_empty() && value.chars().all(|char| char.is_ascii_alphanumeric() || char == '.')
+    }
 }
 impl<'a> TryFrom<&'a str> for Effect {
     type Error = UnknownEffectError;
 
     fn try_from(value: &'a str) -> Result<Self, Self::Error> {
-        match value {
-            "ac" => Ok(Self::SquareFitCircle),
-            "mv" => Ok(Self::MusicVideo),
-            "bb" => Ok(Self::BackgroundBlur),
-            "bf" => Ok(Self::BackgroundFill),
-            _ if &value[0..=1] == "SC" => {
-                // todo PDCXS
-                // todo can out of bounds in [3..]?
-                Ok(Self::Frame(Framing::try_from(&value[3..]).unwrap())) // todo handle error
+        Ok(match value {
+            "ac" => Self::SquareFitCircle,
+            "mv" => Self::MusicVideo,
+            "bb" => Self::BackgroundBlur,
+            "bf" => Self::BackgroundFill,
+            "cc" => Self::CenterCrop,
+            "sc" => Self::ProfileIcon,
+            "sr" => Self::EditorialCard,
+            "vf" => Self::FeaturedBanner,
+            "ea" => Self::ArtistBanner,
+            "ve" => Self::CastAndCrew,
+            "wp" => Self::PodcastLinkPreview,
+            "wz" => Self::BookLinkPreview,
+            "ss" => Self::Screenshot,
+            "w" => Self::InferHeight,
+            "h" => Self::InferWidth,
+            "at" => Self::AppleTvLogo,
+            _ if !Self::is_well_formed(value) => return Err(UnknownEffectError),
+            _ => {
+                let framing = |prefix: &str| value.strip_prefix(prefix).and_then(|framing| Framing::try_from(framing).ok());
+                if let Some(framing) = framing(Self::FRAME_PREFIX) {
+                    Self::Frame(framing)
+                } else if let Some(framing) = framing(Self::WIDE_FRAME_PREFIX) {
+                    Self::WideFrame(framing)
+                } else {
+                    Self::Unknown(value.to_owned())
+                }
             }
-            _ => Err(UnknownEffectError)
-        }
+        })
     }
 }
 impl core::str::FromStr for Effect {
@@ -84,10 +189,10 @@ impl core::str::FromStr for Effect {
 impl core::fmt::Display for Effect {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let variant = self.variant();
-        if let Self::Frame(frame) = self {
-            write!(f, "{variant}.{frame}")
-        } else {
-            write!(f, "{variant}")
+        match self {
+            Self::Frame(frame) | Self::WideFrame(frame) => write!(f, "{variant}.{frame}"),
+            Self::GeneratedCover(cover) => write!(f, "{variant}.{}{:02}", GeneratedCover::LAYOUT, cover.variant),
+            _ => f.write_str(variant)
         }
     }
 }
@@ -98,7 +203,9 @@ pub enum FramingParseError<'a> {
     #[error("unknown frame type \"{0}\"")]
     UnknownFrame(&'a str),
     #[error("frame variant parse failure: {0}")]
-    VariantParseError(#[from] core::num::ParseIntError)
+    VariantParseError(#[from] core::num::ParseIntError),
+    #[error("unexpected characters after frame variant: \"{0}\"")]
+    TrailingCharacters(&'a str),
 }
 
 // SC 
@@ -203,7 +310,7 @@ impl FeaturedPlaylist {
 }
 impl core::fmt::Display for FeaturedPlaylist {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "FP{}{:02}", self.static_str(), self.get_variant_number())
+        write!(f, "{}{}{:02}", Self::PREFIX, self.static_str(), self.get_variant_number())
     }
 }
 
@@ -230,7 +337,7 @@ pub enum FeaturedCategory {
     // CADC01 => same as DC01 ?? just watermark branding i guess
 }
 impl FeaturedCategory {
-    pub const PREFIX: &'static str = "FC";
+    pub const PREFIX: &'static str = "CA";
 
     /// Returns the static discriminator *without* any prefix.
     pub const fn static_str(&self) -> &'static str {
@@ -257,7 +364,7 @@ impl FeaturedCategory {
 }
 impl core::fmt::Display for FeaturedCategory {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "CA{}{:02}", self.static_str(), self.get_variant_number())
+        write!(f, "{}{}{:02}", Self::PREFIX, self.static_str(), self.get_variant_number())
     }
 }
 
@@ -281,7 +388,6 @@ pub enum Framing {
     FeaturedPlaylist(FeaturedPlaylist),
     FeaturedCategory(FeaturedCategory),
     AppleMusicWatermarkTopRight { classical: bool },
-    PDCXS { variant: u8, payload: GeneratedPlaylistCoverPayload, signature: String }
 }
 impl core::fmt::Display for Framing {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
@@ -289,14 +395,6 @@ impl core::fmt::Display for Framing {
             Self::FeaturedCategory(fc) => fc.fmt(f),
             Self::FeaturedPlaylist(fp) => fp.fmt(f),
             Self::AppleMusicWatermarkTopRight { classical } => f.write_str(if *classical { "DNC01" } else { "DN01" }),
-            Self::PDCXS { .. } => f.write_str("PDCXS"),
-        }?;
-
-        match self {
-            Self::PDCXS { variant, .. } => {
-                write!(f, "{variant:02}")
-            }
-            _ => Ok(()),
         }
     }
 }
@@ -308,14 +406,17 @@ impl<'a> TryFrom<&'a str> for Framing {
         let variant = read!(value, while: |char| char.is_ascii_digit()).parse()?;
 
 
-        if frame == "PDXCS" {
-            unimplemented!()
-        } else if let Some(sub) = frame.strip_prefix(FeaturedPlaylist::PREFIX) {
+        if !value.is_empty() {
+            return Err(FramingParseError::TrailingCharacters(value))
+        }
+
+        if let Some(sub) = frame.strip_prefix(FeaturedPlaylist::PREFIX) {
             FeaturedPlaylist::from_deconstructed(sub, variant).map(Framing::FeaturedPlaylist).ok_or(FramingParseError::UnknownFrame(sub))
         } else if let Some(sub) = frame.strip_prefix(FeaturedCategory::PREFIX) {
             FeaturedCategory::from_deconstructed(sub, variant).map(Framing::FeaturedCategory).ok_or(FramingParseError::UnknownFrame(sub))
+        } else if variant == 1 && (frame == "DN" || frame == "DNC") {
+            Ok(Framing::AppleMusicWatermarkTopRight { classical: frame == "DNC" })
         } else {
-            // TODO: DN01/DNC01 too
             Err(FramingParseError::UnknownFrame(frame))
         }
     }
@@ -331,6 +432,12 @@ impl Rgb {
     pub const fn new(r: u8, g: u8, b: u8) -> Self {
         Self { r, g, b }
     }
+    /// Parses a color from exactly six hexadecimal digits, without any leading `#`.
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        if hex.len() != 6 || !hex.chars().all(|char| char.is_ascii_hexdigit()) { return None }
+        let channel = |at: usize| u8::from_str_radix(&hex[at..at + 2], 16).ok();
+        Some(Self::new(channel(0)?, channel(2)?, channel(4)?))
+    }
     const fn to_raw_hex_with_alphabet(self, alphabet: [u8; 16]) -> [u8; 6] {
         [
             alphabet[(self.r >> 4) as usize],
@@ -359,24 +466,135 @@ impl core::fmt::UpperHex for Rgb {
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct GeneratedPlaylistCoverPayload {
-    background_colors: [Rgb; 4],
-    text: String,
-    text_color: Rgb,
-    // vkey: u16, // I've only seen it as '1' so far.
+    /// The colors of the background gradient (`c1` through `c4`).
+    pub background_colors: [Rgb; 4],
+    /// The title written onto the cover (`t`), which is base64-encoded in the URL.
+    pub text: String,
+    /// The color of the title (`tc`).
+    pub text_color: Rgb,
+    /// I've only seen it as '1' so far.
+    pub vkey: u16,
 }
-impl core::fmt::Display for GeneratedPlaylistCoverPayload {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for (i, color) in self.background_colors.iter().enumerate() {
+
+/// - <https://is1-ssl.mzstatic.com/image/thumb/gen/600x600AM.PDCXS01.jpg?c1=FFFFFF&c2=CCA3A3&c3=960019&c4=1A1414&signature=cd00baed652789cfa36f326160fcf46c7786df4366fd6f2fbd189bbc0199627b&t=VGlrVG9rIFNvbmdz&tc=000000&vkey=1>
+/// 
+/// The query parameters may be reordered and duplicated (though only the first one matters), but excess ones are denied.
+/// Colors must be written with all six digits.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GeneratedCover {
+    /// The number following the layout (e.x. the `01` in `PDCXS01`).
+    pub variant: u8,
+    pub payload: GeneratedPlaylistCoverPayload,
+    /// A hex-encoded signature over the payload; the cover won't be generated if it doesn't match.
+    pub signature: String,
+}
+impl GeneratedCover {
+    pub const PREFIX: &'static str = "AM.";
+    pub const LAYOUT: &'static str = "PDCXS";
+
+    fn engine() -> base64::engine::GeneralPurpose {
+        use base64::engine::{GeneralPurpose, GeneralPurposeConfig, DecodePaddingMode};
+        GeneralPurpose::new(&base64::alphabet::URL_SAFE, GeneralPurposeConfig::new().with_encode_padding(false).with_decode_padding_mode(DecodePaddingMode::Indifferent))
+    }
+
+    /// Creates the cover from the literal representation of the effect (e.x. `AM.PDCXS01`) and the query parameters of the URL.
+    pub(crate) fn from_parameters<'a>(literal: &'a str, parameters: &GeneratedCoverParameters<'a>) -> Result<Self, super::DetailsParseError<'a>> {
+        use super::DetailsParseError;
+
+        let variant = literal.strip_prefix(Self::PREFIX).and_then(|literal| literal.strip_prefix(Self::LAYOUT))
+            .filter(|variant| !variant.is_empty() && variant.chars().all(|char| char.is_ascii_digit()))
+            .and_then(|variant| variant.parse().ok())
+            .ok_or(DetailsParseError::UnknownEffect(UnknownEffectError, literal))?;
+
+        let color = |key: &'static str, value: Option<&'a str>| {
+            let value = value.ok_or(DetailsParseError::MissingGeneratedCoverParameter(key))?;
+            Rgb::from_hex(value).ok_or(DetailsParseError::InvalidGeneratedCoverParameter(key, value))
+        };
+
+        let text = parameters.text.ok_or(DetailsParseError::MissingGeneratedCoverParameter("t"))?;
+        let text = {
+            use base64::Engine;
+            Self::engine().decode(text).ok().and_then(|text| String::from_utf8(text).ok())
+                .ok_or(DetailsParseError::InvalidGeneratedCoverParameter("t", text))?
+        };
+
+        let signature = parameters.signature.ok_or(DetailsParseError::MissingGeneratedCoverParameter("signature"))?;
+        if !signature.chars().all(|char| char.is_ascii_hexdigit()) {
+            return Err(DetailsParseError::InvalidGeneratedCoverParameter("signature", signature))
+        }
+
+        let vkey = parameters.vkey.ok_or(DetailsParseError::MissingGeneratedCoverParameter("vkey"))?;
+        let vkey = vkey.parse().map_err(|_| DetailsParseError::InvalidGeneratedCoverParameter("vkey", vkey))?;
+
+        Ok(Self {
+            variant,
+            payload: GeneratedPlaylistCoverPayload {
+                background_colors: [
+                    color("c1", parameters.colors[0])?,
+                    color("c2", parameters.colors[1])?,
+                    color("c3", parameters.colors[2])?,
+                    color("c4", parameters.colors[3])?,
+                ],
+                text,
+                text_color: color("tc", parameters.text_color)?,
+                vkey,
+            },
+            signature: signature.to_owned(),
+        })
+    }
+
+    /// Writes the query parameters carrying the payload, each preceded by `&` (or `?` for the first if `first` is set), in the order Apple uses.
+    /// 
+    /// The language parameter is alphabetically between the colors and the signature, so it's written by the caller through `language`.
+    pub(crate) fn write_parameters(&self, f: &mut core::fmt::Formatter<'_>, language: Option<&str>) -> core::fmt::Result {
+        let payload = &self.payload;
+        for (i, color) in payload.background_colors.iter().enumerate() {
             let delimiter = if i == 0 { "?" } else { "&" };
             write!(f, "{delimiter}c{}={color:X}", i + 1)?
         }
+        if let Some(language) = language {
+            write!(f, "&l={language}")?;
+        }
+        write!(f, "&signature={}", self.signature)?;
         let encoded_text = {
-            use base64::prelude::*;
-            BASE64_URL_SAFE.encode(&self.text)
+            use base64::Engine;
+            Self::engine().encode(&payload.text)
         };
         write!(f, "&t={encoded_text}")?;
-        write!(f, "&tc={:X}", self.text_color)?;
-        write!(f, "&vkey=1")?; // idk
-        Ok(())
+        write!(f, "&tc={:X}", payload.text_color)?;
+        write!(f, "&vkey={}", payload.vkey)
+    }
+}
+
+/// The raw values of the query parameters which can make up a [`GeneratedCover`], as they're encountered in the URL.
+#[derive(Debug, Default)]
+pub(crate) struct GeneratedCoverParameters<'a> {
+    colors: [Option<&'a str>; 4],
+    text: Option<&'a str>,
+    text_color: Option<&'a str>,
+    signature: Option<&'a str>,
+    vkey: Option<&'a str>,
+}
+impl<'a> GeneratedCoverParameters<'a> {
+    /// Records the parameter, returning false if it isn't one which is relevant to a generated cover.
+    /// Only the first occurrence of a parameter is kept.
+    pub(crate) fn set(&mut self, key: &str, value: &'a str) -> bool {
+        let slot = match key {
+            "c1" => &mut self.colors[0],
+            "c2" => &mut self.colors[1],
+            "c3" => &mut self.colors[2],
+            "c4" => &mut self.colors[3],
+            "t" => &mut self.text,
+            "tc" => &mut self.text_color,
+            "signature" => &mut self.signature,
+            "vkey" => &mut self.vkey,
+            _ => return false
+        };
+        slot.get_or_insert(value);
+        true
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.colors.iter().all(Option::is_none) && self.text.is_none() && self.text_color.is_none() && self.signature.is_none() && self.vkey.is_none()
     }
 }
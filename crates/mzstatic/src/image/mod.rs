@@ -2,7 +2,10 @@ use maybe_owned_string::MaybeOwnedString;
 
 use crate::{accelerator::Directives, pool::Pool, read};
 
+pub mod builder;
 pub mod effect;
+#[cfg(feature = "reqwest")]
+pub mod probe;
 pub mod quality;
 
 /// The image format to output.
@@ -99,7 +102,11 @@ pub enum DetailsParseError<'a> {
     #[error("cannot find resolution dimension delimiter")]
     MissingResolutionDelimiter,
     #[error("unknown url parameter(s) present")]
-    UnknownUrlParameter
+    UnknownUrlParameter,
+    #[error("missing generated cover parameter \"{0}\"")]
+    MissingGeneratedCoverParameter(&'static str),
+    #[error("invalid generated cover parameter \"{0}\": \"{1}\"")]
+    InvalidGeneratedCoverParameter(&'static str, &'a str),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -178,43 +185,57 @@ pub struct Details<'a> {
 }
 impl<'a> Details<'a> {
     pub fn edit_url(url: &'a str, edit: impl FnOnce(Details) -> Details) -> Result<String, DetailsParseError<'a>> {
-        let last_slash: usize = url.rfind('/').unwrap();
-        let image = edit(Details::new(&url[(last_slash + 1)..])?);
-        Ok(format!("{}{image}", &url[..=last_slash]))
+        let details_start = url.rfind('/').map_or(0, |last_slash| last_slash + 1);
+        let image = edit(Details::new(&url[details_start..])?);
+        Ok(format!("{}{image}", &url[..details_start]))
     }
     pub fn new(mut url: &'a str) -> Result<Self, DetailsParseError<'a>> {
         let resolution = Resolution::parse(&mut url)?;
-        let framing_or_file_extension_delimiter = url.find('.').ok_or(DetailsParseError::MissingFileExtensionDelimiter)?;
-        let maybe_quality_delimiter = url.find("-");
-
-        // The effect, if present, will directly follow the resolution.
-        // (So far, it's always been primarily specified by two characters, aside from a framing specifier)
-        let effect = read!(url, delimit_at: maybe_quality_delimiter.unwrap_or(framing_or_file_extension_delimiter));
-        let effect = if effect.is_empty() { None } else {
-            Some(effect::Effect::try_from(effect).map_err(|e| DetailsParseError::UnknownEffect(e, effect))?)
+        // The effect, if present, will directly follow the resolution, and is terminated by either the quality or file extension delimiter.
+        // Framing effects have their own delimiter (e.x. `SC.FPESS03`), which mustn't be mistaken for the file extension delimiter.
+        let effect_offset = [effect::Effect::FRAME_PREFIX, effect::Effect::WIDE_FRAME_PREFIX, effect::GeneratedCover::PREFIX].into_iter()
+            .find(|prefix| url.starts_with(prefix))
+            .map_or(0, str::len);
+        let effect_delimiter = url[effect_offset..].find(['-', '.']).ok_or(DetailsParseError::MissingFileExtensionDelimiter)? + effect_offset;
+
+        // Generated covers can only be parsed once their query parameters have been.
+        let effect_literal = read!(url, delimit_at: effect_delimiter, 0);
+        let is_generated_cover = effect_literal.starts_with(effect::GeneratedCover::PREFIX);
+        let effect = if effect_literal.is_empty() || is_generated_cover { None } else {
+            Some(effect::Effect::try_from(effect_literal).map_err(|e| DetailsParseError::UnknownEffect(e, effect_literal))?)
         };
 
-        let quality = if maybe_quality_delimiter.is_some() {
-            // The modifier doesn't really have a delimiter, it's shoved in right after the Y-resolution.
+        let quality = if let Some(after) = url.strip_prefix('-') {
+            url = after;
+            // The modifier doesn't really have a delimiter, it's shoved in right after the effect (or Y-resolution).
             let quality = read!(url, while: |char| char.is_ascii_digit()).parse().map_err(DetailsParseError::QualityNotParsable)?;
             let quality = quality::Quality::new(quality).map_err(DetailsParseError::QualityOutOfBounds)?;
-            url = &url[1..]; // Pass the file extension delimiter.
             Some(quality)
         } else { None };
 
-        let maybe_parameters_delimiter = url.find("?");
+        url = url.strip_prefix('.').ok_or(DetailsParseError::MissingFileExtensionDelimiter)?; // Pass the file extension delimiter.
 
-        let (file_extension, language) = if let Some(parameters_delimiter) = maybe_parameters_delimiter {
-            let file_extension = read!(url, delimit_at: parameters_delimiter);
+        let (file_extension, parameters) = match url.split_once('?') {
+            Some((file_extension, parameters)) => (file_extension, Some(parameters)),
+            None => (url, None)
+        };
 
-            if &url.as_bytes()[0..=1] != b"l=" || url.find('&').is_some() {
+        let mut language = None;
+        let mut generated_cover = effect::GeneratedCoverParameters::default();
+        for parameter in parameters.into_iter().flat_map(|parameters| parameters.split('&')) {
+            let (key, value) = parameter.split_once('=').ok_or(DetailsParseError::UnknownUrlParameter)?;
+            if key == "l" {
+                language.get_or_insert(MaybeOwnedString::Borrowed(value));
+            } else if !generated_cover.set(key, value) {
                 return Err(DetailsParseError::UnknownUrlParameter)
             }
+        }
 
-            let language = MaybeOwnedString::Borrowed(&url["l=".len()..]);
-
-            (file_extension, Some(language))
-        } else { (url, None) };
+        let effect = if is_generated_cover {
+            Some(effect::Effect::GeneratedCover(effect::GeneratedCover::from_parameters(effect_literal, &generated_cover)?))
+        } else if !generated_cover.is_empty() {
+            return Err(DetailsParseError::UnknownUrlParameter)
+        } else { effect };
 
         let image_format = ImageFormat::try_from(file_extension)
             .map_err(|_| DetailsParseError::UnsupportedImageFormat(file_extension))?;
@@ -238,6 +259,9 @@ impl core::fmt::Display for Details<'_> {
             write!(f, "-{quality}")?;
         }
         write!(f, ".{}", self.image_format)?;
+        if let Some(effect::Effect::GeneratedCover(cover)) = &self.effect {
+            return cover.write_parameters(f, self.language.as_deref())
+        }
         if let Some(language) = &self.language {
             write!(f, "?l={language}")?;
         }
@@ -297,29 +321,39 @@ impl core::fmt::Display for Prefix {
 
 
 
-#[derive(Debug)]
+/// An error encountered while parsing an [`MzStaticImage`], carrying the byte range of the offending part of the URL.
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
 pub enum ParseError<'a> {
-    BadImageParameters(Option<DetailsParseError<'a>>),
-    BadDirectives(crate::accelerator::ReadError<'a>),
-    BadPool(crate::pool::ParseError),
-    BadDetails(DetailsParseError<'a>),
-    BadProtocol,
-    BadDomain,
-    NoPool,
+    #[error("bad accelerator directives at {1:?}: {0}")]
+    BadDirectives(crate::accelerator::ReadError<'a>, core::ops::Range<usize>),
+    #[error("bad pool at {1:?}: {0}")]
+    BadPool(crate::pool::ParseError, core::ops::Range<usize>),
+    #[error("bad saga specifier at {1:?}: {0}")]
+    BadSaga(crate::saga::ParseError, core::ops::Range<usize>),
+    #[error("bad image details at {1:?}: {0}")]
+    BadDetails(DetailsParseError<'a>, core::ops::Range<usize>),
+    #[error("unsupported protocol at {0:?}")]
+    BadProtocol(core::ops::Range<usize>),
+    #[error("not an mzstatic domain at {0:?}")]
+    BadDomain(core::ops::Range<usize>),
+    #[error("no pool at {0:?}")]
+    NoPool(core::ops::Range<usize>),
+    #[error("no asset token at {0:?}")]
+    NoToken(core::ops::Range<usize>),
 }
-impl<'a> From<crate::accelerator::ReadError<'a>> for ParseError<'a> {
-    fn from(value: crate::accelerator::ReadError<'a>) -> Self {
-        Self::BadDirectives(value)
-    }
-}
-impl<'a> From<DetailsParseError<'a>> for ParseError<'a> {
-    fn from(value: DetailsParseError<'a>) -> Self {
-        Self::BadDetails(value)
-    }
-}
-impl From<crate::pool::ParseError> for ParseError<'_> {
-    fn from(value: crate::pool::ParseError) -> Self {
-        Self::BadPool(value)
+impl ParseError<'_> {
+    /// The byte range of the offending part of the URL.
+    pub fn span(&self) -> core::ops::Range<usize> {
+        match self {
+            Self::BadDirectives(_, span) |
+            Self::BadPool(_, span) |
+            Self::BadSaga(_, span) |
+            Self::BadDetails(_, span) |
+            Self::BadProtocol(span) |
+            Self::BadDomain(span) |
+            Self::NoPool(span) |
+            Self::NoToken(span) => span.clone()
+        }
     }
 }
 
@@ -358,15 +392,25 @@ pub enum PoolOrSagaSpecifier {
     /// More live-notes are in the "saga.txt". I'm calling this "Saga" because one error referred to this as a Saga Token.
     /// 
     /// Anyways, fuck all of this. I'm not touching it for a ten foot pole, at least for a while.
-    Saga(String), // What's the connection to accelerator directive?
+    /// 
+    /// (I touched it.) The components which could be made sense of are parsed into a [`SagaSpecifier`](crate::saga::SagaSpecifier).
+    Saga(crate::saga::SagaSpecifier), // What's the connection to accelerator directive?
+    /// Artwork which is generated on-the-fly (`/image/thumb/gen/`) rather than being stored under an asset token.
+    /// 
+    /// The image is described entirely by its [details](Details), through an [`effect::Effect::GeneratedCover`].
+    Generated,
 }
 impl PoolOrSagaSpecifier {
-    fn read(input: &str) -> Option<crate::Read<Self>> {
-        let v = crate::pool::Pool::read(input).unwrap(); // cannot be arsed
-        Some(crate::Read {
-            bytes: v.bytes,
-            value: Self::Pool(v.value)
-        })
+    pub const GENERATED: &'static str = "gen";
+}
+impl From<Pool> for PoolOrSagaSpecifier {
+    fn from(value: Pool) -> Self {
+        Self::Pool(value)
+    }
+}
+impl From<crate::saga::SagaSpecifier> for PoolOrSagaSpecifier {
+    fn from(value: crate::saga::SagaSpecifier) -> Self {
+        Self::Saga(value)
     }
 }
 impl core::fmt::Display for PoolOrSagaSpecifier {
@@ -374,6 +418,7 @@ impl core::fmt::Display for PoolOrSagaSpecifier {
         match self {
             Self::Pool(pool) => write!(f, "{pool}"),
             Self::Saga(saga) => write!(f, "{saga}"),
+            Self::Generated => f.write_str(Self::GENERATED),
         }
     }
 }
@@ -382,7 +427,7 @@ impl core::fmt::Display for PoolOrSagaSpecifier {
 #[derive(Debug, Clone)]
 pub struct MzStaticImage<'a> {
     pub https: bool,
-    pub accelerator_directives: Option<crate::accelerator::Directives<'a>>,
+    pub accelerator_directives: Option<crate::accelerator::Directives>,
     pub pool: PoolOrSagaSpecifier,
     pub prefix: Option<Prefix>,
     pub asset_token: MaybeOwnedString<'a>,
@@ -398,76 +443,118 @@ pub struct MzStaticImage<'a> {
     pub parameters: Details<'a>
 }
 impl<'a> MzStaticImage<'a> {
-    // todo: return result
-    pub fn parse(mut url: &'a str) -> Result<Self, ParseError<'a>> {
-        macro_rules! eat {
-            ($str: ident, [assert] $literal: literal, $($reason: tt)*) => {
-                if !$str.starts_with($literal) {
-                    return Err(ParseError::$($reason)*);
-                };
-                $str = &$str[$literal.len()..];
-            };
-            ($str: ident, [optional] $literal: literal) => {
-                {
-                    let present = $str.starts_with($literal);
-                    if present {
-                        $str = &$str[$literal.len()..];
-                    };
-                    present
-                }
-            };
-            ($str: ident, [strip] $literal: literal) => {
-                {
-                    let present = $str.starts_with($literal);
-                    if present {
-                        $str = &$str[$literal.len()..];
-                    };
-                    present
-                }
-            };
-            ($str: ident, [pass] $expr: expr) => {
-                {
-                    if let Some(read) = $expr {
-                        url = &url[read.bytes.get()..];
-                        Some(read.value)
-                    } else { None }
-                }
-            };
-        }
+    /// Returns a [`builder::Builder`] for constructing a URL from its components.
+    pub fn builder() -> builder::Builder<'a> {
+        builder::Builder::new()
+    }
 
-        eat!(url, [assert] "http", BadProtocol);
-        let tls = eat!(url, [optional] "s");
-        eat!(url, [assert] "://", BadProtocol);
+    pub fn parse(input: &'a str) -> Result<Self, ParseError<'a>> {
+        let mut url = input;
 
-        // FIXME: Don't panic!
-        let subdomain = read!(url, delimit: ".").unwrap().into();
+        // The byte range of the path segment at the start of the unparsed part of the input.
+        let segment = |rest: &str| {
+            let start = input.len() - rest.len();
+            start..start + rest.find('/').unwrap_or(rest.len())
+        };
 
-        eat!(url, [assert] "mzstatic.com/", BadDomain);
+        let https = if let Some(after) = url.strip_prefix("https://") {
+            url = after; true
+        } else if let Some(after) = url.strip_prefix("http://") {
+            url = after; false
+        } else {
+            return Err(ParseError::BadProtocol(0..input.find("://").map_or(segment(url).end, |at| at + "://".len())))
+        };
+
+        let host = segment(url);
+        let subdomain = url[..host.len()].strip_suffix(".mzstatic.com")
+            .filter(|subdomain| !subdomain.is_empty())
+            .ok_or(ParseError::BadDomain(host.clone()))?
+            .into();
+        url = url[host.len()..].strip_prefix('/').ok_or(ParseError::NoPool(host.end..host.end))?;
+
+        let prefix = Prefix::read(url).map(|read| {
+            url = &url[read.bytes.get()..];
+            read.value
+        });
+
+        if let Some(details) = url.strip_prefix(PoolOrSagaSpecifier::GENERATED).and_then(|after| after.strip_prefix('/')) {
+            return Ok(Self {
+                https,
+                accelerator_directives: None,
+                asset_token: MaybeOwnedString::Borrowed(""),
+                subdomain,
+                parameters: Details::new(details).map_err(|error| ParseError::BadDetails(error, segment(details)))?,
+                pool: PoolOrSagaSpecifier::Generated,
+                prefix
+            })
+        }
+
+        let directives = Directives::read(url).map_err(|error| ParseError::BadDirectives(error, segment(url)))?.map(|read| {
+            url = &url[read.bytes.get()..];
+            read.value
+        });
 
-        let prefix = eat!(url, [pass] Prefix::read(url));
-        let directives = eat!(url, [pass] Directives::read(url)?);
-        let pool =  eat!(url, [pass] Some(Pool::read(url)?)).unwrap(); // FIXME: Don't panic!
-    
-        let last_slash = url.rfind('/').unwrap(); // FIXME: Don't panic!
-        let (path, details) = url.split_at(last_slash);
-        let details = Details::new(&details[1..])?;
+        if url.is_empty() { return Err(ParseError::NoPool(input.len()..input.len())) }
+        let pool = if crate::saga::SagaSpecifier::is_at_start(url) {
+            let saga = crate::saga::SagaSpecifier::read(url).map_err(|error| ParseError::BadSaga(error, segment(url)))?;
+            url = &url[saga.bytes.get()..];
+            PoolOrSagaSpecifier::Saga(saga.value)
+        } else {
+            let pool = Pool::read(url).map_err(|error| ParseError::BadPool(error, segment(url)))?;
+            url = &url[pool.bytes.get()..];
+            PoolOrSagaSpecifier::Pool(pool.value)
+        };
+
+        // The query parameters aren't guaranteed to be free of slashes.
+        let path_end = url.find('?').unwrap_or(url.len());
+        let (token, details) = url[..path_end].rsplit_once('/').map(|(token, _)| (token, &url[token.len() + '/'.len_utf8()..]))
+            .filter(|(token, _)| !token.is_empty())
+            .ok_or(ParseError::NoToken(segment(url)))?;
+        let details = Details::new(details).map_err(|error| ParseError::BadDetails(error, (input.len() - details.len())..input.len()))?;
 
         Ok(Self {
-            https: tls,
+            https,
             accelerator_directives: directives,
-            asset_token: path.into(),
+            asset_token: token.into(),
             subdomain,
             parameters: details,
-            pool: PoolOrSagaSpecifier::Pool(pool),
+            pool,
             prefix
         })
     }
 
+    /// Rewrites this image into a single canonical form of the URLs which serve the same asset, so that they're equal once displayed.
+    ///
+    /// - HTTPS is always used.
+    /// - Accelerator directives are removed, as they don't change what's served.
+    /// - The interchangeable `/^a[1-5]$/` and `/^is[1-5](?:-ssl)?$/` subdomains become `is1-ssl`, with a thumbnail prefix being added if there was none, since the details are only respected with one.
+    ///
+    /// Other subdomains are left alone, as they aren't known to be interchangeable.
+    pub fn canonicalize(&mut self) {
+        fn is_interchangeable(subdomain: &str) -> bool {
+            let is_server_number = |number: &str| matches!(number, "1" | "2" | "3" | "4" | "5");
+            if let Some(number) = subdomain.strip_prefix("is") {
+                is_server_number(number.strip_suffix("-ssl").unwrap_or(number))
+            } else {
+                subdomain.strip_prefix('a').is_some_and(is_server_number)
+            }
+        }
+
+        self.https = true;
+        self.accelerator_directives = None;
+        if is_interchangeable(&self.subdomain) {
+            self.subdomain = MaybeOwnedString::Borrowed("is1-ssl");
+            self.prefix.get_or_insert(Prefix::ImageThumbnail);
+        }
+    }
+
     pub fn with_pool_and_token(pool_and_token: MaybeOwnedString<'a>) -> Result<Self, ParseError<'a>> {
         if let Ok(pool) = Pool::read(&pool_and_token) {
-            let token: MaybeOwnedString<'_> = match pool_and_token {
-                MaybeOwnedString::Borrowed(borrowed) => MaybeOwnedString::Borrowed(&borrowed[pool.bytes.get() + '/'.len_utf8()..]),
-                MaybeOwnedString::Owned(owned) => MaybeOwnedString::Owned((owned[pool.bytes.get() + '/'.len_utf8()..]).to_string())
+            let token_start = pool.bytes.get() + '/'.len_utf8();
+            let no_token = || ParseError::NoToken(pool.bytes.get()..pool_and_token.len());
+            let token: MaybeOwnedString<'_> = match &pool_and_token {
+                MaybeOwnedString::Borrowed(borrowed) => MaybeOwnedString::Borrowed(borrowed.get(token_start..).ok_or_else(no_token)?),
+                owned => MaybeOwnedString::Owned(owned.get(token_start..).ok_or_else(no_token)?.to_string())
             };
             Ok(Self {
                 accelerator_directives: None,
@@ -479,8 +566,7 @@ impl<'a> MzStaticImage<'a> {
                 parameters: Details::default(),
             })
         } else {
-            //idfk
-            Err(ParseError::NoPool)
+            Err(ParseError::NoPool(0..pool_and_token.find('/').unwrap_or(pool_and_token.len())))
         }
     }
 }
@@ -490,7 +576,9 @@ impl core::fmt::Display for MzStaticImage<'_> {
         write!(f, "{}.mzstatic.com/", self.subdomain)?;
         if let Some(prefix) = self.prefix { write!(f, "{prefix}/")?; }
         if let Some(accelerator_directives) = self.accelerator_directives { write!(f, "{accelerator_directives}/")?; }
-        write!(f, "{}/{}/{}", self.pool, self.asset_token, self.parameters)
+        write!(f, "{}/", self.pool)?;
+        if !self.asset_token.is_empty() { write!(f, "{}/", self.asset_token)?; }
+        write!(f, "{}", self.parameters)
     }
 }
 
@@ -525,6 +613,140 @@ mod tests {
         }));
     }
 
+    #[test]
+    fn framed_without_quality() {
+        assert_eq!(Details::new("600x600SC.FPESS03.jpg").map(|details| details.effect), Ok(Some(Effect::Frame(Framing::FeaturedPlaylist(FeaturedPlaylist::Essentials(3))))));
+        assert_eq!(Details::new("300x172SH.FPUD02.webp?l=en-US").map(|details| details.effect), Ok(Some(Effect::WideFrame(Framing::FeaturedPlaylist(FeaturedPlaylist::Undiscovered(2))))));
+        assert_eq!(Details::new("296x296SC.DNC01.webp").map(|details| details.effect), Ok(Some(Effect::Frame(Framing::AppleMusicWatermarkTopRight { classical: true }))));
+        assert_eq!(Details::new("600x600SC.CAHGOY01.jpg").map(|details| details.effect), Ok(Some(Effect::Frame(Framing::FeaturedCategory(FeaturedCategory::HitsOfTheYear(1))))));
+        assert_eq!(Details::new("600x600bb.jpg?l=en-US").map(|details| details.quality), Ok(None));
+    }
+
+    #[test]
+    fn effects() {
+        for (literal, effect) in [
+            ("492x492ve.webp", Effect::CastAndCrew),
+            ("626x392sr.webp", Effect::EditorialCard),
+            ("0x300h.webp", Effect::InferWidth),
+            ("300x0w.webp", Effect::InferHeight),
+            ("1x40at.png", Effect::AppleTvLogo),
+            ("256x256sc.jpg", Effect::ProfileIcon),
+            ("632x632SC.MVESS04.webp", Effect::Unknown("SC.MVESS04".to_owned())),
+            ("600x600zz.jpg", Effect::Unknown("zz".to_owned())),
+        ] {
+            let details = Details::new(literal).unwrap();
+            assert_eq!(details.effect.as_ref(), Some(&effect), "{literal}");
+            assert_eq!(details.to_string(), literal);
+        }
+        assert!(matches!(Details::new("600x600a_c.jpg"), Err(DetailsParseError::UnknownEffect(UnknownEffectError, "a_c"))));
+    }
+
+    #[test]
+    fn generated_cover() {
+        let image = MzStaticImage::parse("https://is1-ssl.mzstatic.com/image/thumb/gen/600x600AM.PDCXS01.jpg?tc=000000&t=VGlrVG9rIFNvbmdz&vkey=1&c1=FFFFFF&c2=CCA3A3&c3=960019&c4=1A1414&c4=000000&signature=cd00baed").unwrap();
+        assert!(matches!(image.pool, PoolOrSagaSpecifier::Generated));
+        assert_eq!(image.parameters.effect, Some(Effect::GeneratedCover(GeneratedCover {
+            variant: 1,
+            payload: GeneratedPlaylistCoverPayload {
+                background_colors: [Rgb::new(0xFF, 0xFF, 0xFF), Rgb::new(0xCC, 0xA3, 0xA3), Rgb::new(0x96, 0x00, 0x19), Rgb::new(0x1A, 0x14, 0x14)],
+                text: "TikTok Songs".to_owned(),
+                text_color: Rgb::new(0, 0, 0),
+                vkey: 1,
+            },
+            signature: "cd00baed".to_owned(),
+        })));
+        // Parameters are re-rendered in the order Apple uses, and only the first of any duplicates is kept.
+        assert_eq!(image.to_string(), "https://is1-ssl.mzstatic.com/image/thumb/gen/600x600AM.PDCXS01.jpg?c1=FFFFFF&c2=CCA3A3&c3=960019&c4=1A1414&signature=cd00baed&t=VGlrVG9rIFNvbmdz&tc=000000&vkey=1");
+
+        assert!(matches!(Details::new("600x600AM.PDCXS01.jpg?c1=FFFFFF"), Err(DetailsParseError::MissingGeneratedCoverParameter(_))));
+        assert!(matches!(Details::new("600x600AM.PDCXS01.jpg?c1=FFF&c2=CCA3A3&c3=960019&c4=1A1414&signature=00&t=VGlr&tc=000000&vkey=1"), Err(DetailsParseError::InvalidGeneratedCoverParameter("c1", "FFF"))));
+        assert!(matches!(Details::new("600x600bb.jpg?c1=FFFFFF"), Err(DetailsParseError::UnknownUrlParameter)));
+    }
+
+    #[test]
+    fn parse_errors() {
+        fn span(url: &str) -> &str {
+            &url[MzStaticImage::parse(url).unwrap_err().span()]
+        }
+
+        assert_eq!(span("ftp://is1-ssl.mzstatic.com/image/thumb/Music221/v4/a.jpg/600x600bb.jpg"), "ftp://");
+        assert_eq!(span("is1-ssl.mzstatic.com/image/thumb/Music221/v4/a.jpg/600x600bb.jpg"), "is1-ssl.mzstatic.com");
+        assert_eq!(span("https://is1-ssl.example.com/image/thumb/Music221/v4/a.jpg/600x600bb.jpg"), "is1-ssl.example.com");
+        assert_eq!(span("https://mzstatic.com/image/thumb/Music221/v4/a.jpg/600x600bb.jpg"), "mzstatic.com");
+        assert!(matches!(MzStaticImage::parse("https://is1-ssl.mzstatic.com"), Err(ParseError::NoPool(_))));
+        assert!(matches!(MzStaticImage::parse("https://is1-ssl.mzstatic.com/image/thumb/"), Err(ParseError::NoPool(_))));
+        assert_eq!(span("https://is1-ssl.mzstatic.com/image/thumb/WkLx7oCZ0vBL7G2rzdkcbQ/626x392sr.webp"), "WkLx7oCZ0vBL7G2rzdkcbQ");
+        assert_eq!(span("https://is1-ssl.mzstatic.com/image/thumb/Music221/600x600bb.jpg"), "600x600bb.jpg");
+        assert_eq!(span("https://is1-ssl.mzstatic.com/image/thumb/Music221/v4/a.jpg/600x600bb.gif"), "600x600bb.gif");
+        assert_eq!(span("https://is1-ssl.mzstatic.com/image/thumb/Music221/v4/a.jpg/600x600bb.jpg?x=a/b"), "600x600bb.jpg?x=a/b");
+        assert_eq!(span("https://a1.mzstatic.com/au/r50abc/Music221/v4/a.jpg/600x600bb.jpg"), "au");
+        assert_eq!(span("https://is1-ssl.mzstatic.com/image/thumb/Músic/v4/a.jpg/600x600bb.jpg"), "Músic");
+        assert_eq!(span("https://is1-ssl.mzstatic.com/image/thumb/SG-S3-US/v4/a.jpg/600x600bb.jpg"), "SG-S3-US");
+
+        assert!(matches!(MzStaticImage::with_pool_and_token(MaybeOwnedString::Borrowed("Music/")), Err(ParseError::NoToken(_))));
+    }
+
+    #[test]
+    fn canonicalize() {
+        fn canonical(url: &str) -> String {
+            let mut image = MzStaticImage::parse(url).unwrap();
+            image.canonicalize();
+            image.to_string()
+        }
+
+        const PF: &str = "https://is1-ssl.mzstatic.com/image/pf/Music4/v4/a1/61/b1/a161b1f0-4882-82f5-017b-da8f9c8aea49/0724352141050_1500x1500_300dpi.jpg/1200x1200-75.jpg";
+        assert_eq!(canonical("http://is5.mzstatic.com/image/pf/us/r30/Music4/v4/a1/61/b1/a161b1f0-4882-82f5-017b-da8f9c8aea49/0724352141050_1500x1500_300dpi.jpg/1200x1200-75.jpg"), PF);
+        assert_eq!(canonical("https://is3-ssl.mzstatic.com/image/pf/r30/Music4/v4/a1/61/b1/a161b1f0-4882-82f5-017b-da8f9c8aea49/0724352141050_1500x1500_300dpi.jpg/1200x1200-75.jpg"), PF);
+        assert_eq!(canonical(PF), PF);
+
+        const THUMB: &str = "https://is1-ssl.mzstatic.com/image/thumb/Music221/v4/47/98/ae/4798ae9f-3199-dffa-980c-1d7c9ba56189/artwork.jpg/600x600bb.jpg";
+        assert_eq!(canonical("https://a3.mzstatic.com/us/r30/Music221/v4/47/98/ae/4798ae9f-3199-dffa-980c-1d7c9ba56189/artwork.jpg/600x600bb.jpg"), THUMB);
+        assert_eq!(canonical("https://is4-ssl.mzstatic.com/image/thumb/Music221/v4/47/98/ae/4798ae9f-3199-dffa-980c-1d7c9ba56189/artwork.jpg/600x600bb.jpeg"), THUMB);
+        assert_eq!(canonical("http://is2.mzstatic.com/image/thumb/Music221/v4/47/98/ae/4798ae9f-3199-dffa-980c-1d7c9ba56189/artwork.jpg/600x600bb.jpg"), THUMB);
+
+        // Not known to be interchangeable.
+        assert_eq!(canonical("https://s1.mzstatic.com/Music221/v4/47/98/ae/4798ae9f-3199-dffa-980c-1d7c9ba56189/artwork.jpg/600x600bb.jpg"), "https://s1.mzstatic.com/Music221/v4/47/98/ae/4798ae9f-3199-dffa-980c-1d7c9ba56189/artwork.jpg/600x600bb.jpg");
+        assert_eq!(canonical("https://a6.mzstatic.com/Music221/v4/47/98/ae/4798ae9f-3199-dffa-980c-1d7c9ba56189/artwork.jpg/600x600bb.jpg"), "https://a6.mzstatic.com/Music221/v4/47/98/ae/4798ae9f-3199-dffa-980c-1d7c9ba56189/artwork.jpg/600x600bb.jpg");
+        assert_eq!(canonical("https://a1-ssl.mzstatic.com/Music221/v4/47/98/ae/4798ae9f-3199-dffa-980c-1d7c9ba56189/artwork.jpg/600x600bb.jpg"), "https://a1-ssl.mzstatic.com/Music221/v4/47/98/ae/4798ae9f-3199-dffa-980c-1d7c9ba56189/artwork.jpg/600x600bb.jpg");
+    }
+
+    /// Real URLs which should be displayed exactly as they were parsed.
+    const CORPUS: &[&str] = &[
+        "https://is1-ssl.mzstatic.com/image/thumb/AMCArtistImages126/v4/94/06/4d/94064d6b-c650-84a8-ae0a-bd3cf427898e/be14d48b-0f96-45d5-b15e-d255e87c48b6_ami-identity-795f9bb1320daa20b961333f6f8c6511-2023-08-17T07-24-42.519Z_cropped.png/600x600cc.jpg",
+        "https://is1-ssl.mzstatic.com/image/thumb/Features125/v4/8c/2d/b0/8c2db00d-c4e0-792c-7978-a87a6097225e/mzl.umyvofta.jpg/600x600SC.FPESS03.jpg",
+        "https://is1-ssl.mzstatic.com/image/thumb/Features/v4/fd/9f/6c/fd9f6cd2-ba7b-4fcd-8f5b-309329f7d5e6/edd8c7ef-bdf9-4065-916f-9fb5527b5fe4.png/600x600SC.CAHGOY01.jpg",
+        "https://is1-ssl.mzstatic.com/image/thumb/Music221/v4/47/98/ae/4798ae9f-3199-dffa-980c-1d7c9ba56189/artwork.jpg/520x520ac.jpg",
+        "https://is1-ssl.mzstatic.com/image/thumb/AMCArtistImages211/v4/a6/fc/cc/a6fcccca-d0e5-884e-f20b-fc69885c150a/0360f9e4-6080-4161-992f-fe6195c8c1a3_file_cropped.png/520x520bb.jpg",
+        "https://is1-ssl.mzstatic.com/image/thumb/Features/v4/a5/fc/4b/a5fc4bf1-aecc-538a-6c11-a181dd8e93a2/cb81b7ec-9b6f-4795-8c91-f80f6016809e.png/220x220sr.webp",
+        "https://is1-ssl.mzstatic.com/image/thumb/Features125/v4/30/b8/fc/30b8fc23-fc6d-8006-fabd-b265b9c5a180/mzl.wnqeoeqa.jpg/2400x933vf-60.jpg",
+        "https://is1-ssl.mzstatic.com/image/thumb/Features122/v4/3a/64/ae/3a64aedb-3b2a-3eb0-74de-6018eb900fc5/fd0d1a37-3179-4978-ba51-1bae0fc6e993.png/2400x933ea-60.jpg",
+        "https://is1-ssl.mzstatic.com/image/thumb/Features112/v4/ab/50/ef/ab50ef3b-c936-44f0-7b01-bb113a133547/mza_5527770582281912184.png/632x632SC.MVESS04.webp?l=en-US",
+        "https://is1-ssl.mzstatic.com/image/thumb/Features116/v4/6c/a1/67/6ca167a2-3345-fb31-6399-f73c531088ec/8efea0a6-8ce9-414c-b0b5-c54f9679f09a.png/632x632SC.FPMAF01.webp?l=en-US",
+        "https://is1-ssl.mzstatic.com/image/thumb/Features116/v4/6d/4c/1e/6d4c1e44-aed2-6225-8fc2-9e24c16b30ff/4f8fa624-f639-45c7-80da-f2d09842adc4.png/296x296SC.DNC01.webp",
+        "https://is1-ssl.mzstatic.com/image/thumb/Features116/v4/78/4e/b1/784eb1dc-50df-632a-c7f0-8928d54dc070/mza_4913277387679559953.png/300x172SH.FPUD02.webp?l=en-US",
+        "https://is1-ssl.mzstatic.com/image/thumb/Features211/v4/fa/c9/88/fac98880-913c-b62e-48cb-7048801789fa/mza_1932705350931991483.png/296x296SC.FPESSC02.webp?l=en-US",
+        "https://is1-ssl.mzstatic.com/image/thumb/Features/v4/82/89/43/828943e4-6e29-5aff-cb7d-b83853581163/e8475a08-6ffe-4216-86ca-151eeda0d51d.png/296x296SC.CAESS02.webp?l=en-US",
+        "https://is1-ssl.mzstatic.com/image/thumb/FuseSocial124/v4/69/21/8c/69218c18-37ec-e67a-34c5-0af724e0cb08/Job5e9c5524-8fce-444c-b364-7476d7a0b5aa-108342134-PreviewImage_preview_image_nonvideo_sdr-Time1608577588485.png/680x382mv.webp",
+        "https://is1-ssl.mzstatic.com/image/thumb/Features125/v4/97/65/1a/97651ac2-a24c-0f40-1809-84c08075a2da/U0MtTVMtV1ctVG9wXzI1LUF0bGFudGEtQURBTV9JRD0xNTU1OTkzODkwLnBuZw.png/296x296cc-60.jpg",
+        "https://is2-ssl.mzstatic.com/image/thumb/Podcasts112/v4/e7/a1/3c/e7a13c39-bf73-774f-174e-53b0edd4baad/mza_13783091193329431505.jpeg/1200x630wp.png",
+        "https://is1-ssl.mzstatic.com/image/thumb/Publication113/v4/7f/c5/a0/7fc5a0ee-a55b-4319-4741-c7e8dd669333/9781501194313.jpg/1200x630wz.png",
+        "https://is1-ssl.mzstatic.com/image/thumb/PurpleSource211/v4/79/ca/8a/79ca8aec-2c59-83d4-065d-f0b19aa57880/c57609fe-03c2-4064-9633-24ce513d46e5_0x0ss.png/626x0w.webp",
+        "https://is1-ssl.mzstatic.com/image/thumb/Purple69/v4/dc/2d/0e/dc2d0e06-3aff-b319-fdd2-d3bc33852bf6/pr_source.png/0x0ss.jpg",
+        "https://is5-ssl.mzstatic.com/image/thumb/Video116/v4/bb/87/22/bb87226e-0207-7574-cb38-671dbde126c3/pr_source.lsr/3840x2160.jpg",
+        "https://is2-ssl.mzstatic.com/image/thumb/Music/0a/1f/85/mzi.adskaamt.tif/600x600bb.jpg",
+        "https://is1-ssl.mzstatic.com/image/thumb/SG-MQ-US-035-Image000001/v4/8d/46/70/8d467083-d1f9-a588-7a50-ff916291021f/image/600x600cc.jpg",
+        "https://is2-ssl.mzstatic.com/image/thumb/SG-S3-US-Std-Image-000001/v4/4b/57/4a/4b574a76-7ef8-5c16-b3a2-36a275e34851/image/500x500cc.jpg",
+        "https://is1-ssl.mzstatic.com/image/thumb/gen/600x600AM.PDCXS01.jpg?c1=FFFFFF&c2=CCA3A3&c3=960019&c4=1A1414&signature=cd00baed652789cfa36f326160fcf46c7786df4366fd6f2fbd189bbc0199627b&t=VGlrVG9rIFNvbmdz&tc=000000&vkey=1",
+    ];
+
+    #[test]
+    fn round_trip() {
+        for url in CORPUS {
+            let image = MzStaticImage::parse(url).unwrap_or_else(|error| panic!("{url}: {error:?}"));
+            assert_eq!(&image.to_string(), url);
+        }
+    }
+
     // #[test]
     // fn edit() {
     //     const BASE: &str = "https://is1-ssl.mzstatic.com/image/thumb/AMCArtistImages126/v4/94/06/4d/94064d6b-c650-84a8-ae0a-bd3cf427898e/be14d48b-0f96-45d5-b15e-d255e87c48b6_ami-identity-795f9bb1320daa20b961333f6f8c6511-2023-08-17T07-24-42.519Z_cropped.png";
@@ -0,0 +1,124 @@
+//! Probing the CDN for the largest resolution it will serve an image at.
+use super::{MzStaticImage, Resolution};
+
+/// A binary search over the length of the longer edge of an image, for the largest one which is served.
+///
+/// This assumes that if an edge length is served, every smaller one is too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bisection {
+    /// The largest edge length known to be served.
+    served: u16,
+    /// The smallest edge length known to not be served; one greater than the maximum if there is none.
+    unserved: u32,
+}
+impl Bisection {
+    /// Starts a search between an edge length which is known to be served and the largest one which should be considered (inclusive).
+    pub fn new(served: u16, max: u16) -> Self {
+        Self { served, unserved: u32::from(max.max(served)) + 1 }
+    }
+
+    /// Returns the next edge length to check, or `None` if the search is over.
+    pub fn next(&self) -> Option<u16> {
+        let served = u32::from(self.served);
+        if self.unserved - served <= 1 { return None }
+        Some((served + (self.unserved - served) / 2) as u16)
+    }
+
+    /// Records whether an edge length returned from [`Self::next`] was served.
+    pub fn report(&mut self, edge: u16, served: bool) {
+        if served {
+            self.served = self.served.max(edge);
+        } else {
+            self.unserved = self.unserved.min(u32::from(edge));
+        }
+    }
+
+    /// The largest edge length known to be served.
+    pub fn best(&self) -> u16 {
+        self.served
+    }
+}
+
+/// Whether the CDN responds to a request for the image successfully and with a non-empty body.
+async fn is_served(client: &reqwest::Client, image: &MzStaticImage<'_>) -> Result<bool, reqwest::Error> {
+    let response = client.head(image.to_string()).send().await?;
+    let length = response.headers().get(reqwest::header::CONTENT_LENGTH)
+        .and_then(|length| length.to_str().ok())
+        .and_then(|length| length.parse::<u64>().ok());
+    Ok(response.status().is_success() && length.is_some_and(|length| length != 0))
+}
+
+impl<'a> MzStaticImage<'a> {
+    /// Returns a copy of this image with the resolution changed so that its longer edge is of the given length, keeping the aspect ratio.
+    ///
+    /// Images with an [unfilled](Resolution::Unfilled) resolution are treated as square.
+    pub fn with_longer_edge(&self, edge: u16) -> Self {
+        let resolution = match self.parameters.resolution {
+            Resolution::Filled(x, y) if x != 0 && y != 0 => {
+                let scale = |dimension: u16, longer: u16| (u32::from(dimension) * u32::from(edge) + u32::from(longer) / 2) / u32::from(longer);
+                let scale = |dimension, longer| scale(dimension, longer).clamp(1, u32::from(u16::MAX)) as u16;
+                if x >= y { (edge, scale(y, x)) } else { (scale(x, y), edge) }
+            },
+            _ => (edge, edge)
+        };
+        let mut image = self.clone();
+        image.parameters.resolution = resolution.into();
+        image
+    }
+
+    /// Searches for the largest resolution (with a longer edge of at most `max_edge`) that the CDN will serve this image at,
+    /// returning a copy of the image with that resolution.
+    ///
+    /// The search starts from the current resolution, and `None` is returned if even that isn't served.
+    pub async fn probe_best_resolution(&self, client: &reqwest::Client, max_edge: u16) -> Result<Option<MzStaticImage<'a>>, reqwest::Error> {
+        let start = match self.parameters.resolution {
+            Resolution::Filled(x, y) => x.max(y),
+            Resolution::Unfilled => 1
+        }.clamp(1, max_edge.max(1));
+
+        if !is_served(client, &self.with_longer_edge(start)).await? {
+            return Ok(None)
+        }
+
+        let mut bisection = Bisection::new(start, max_edge);
+        while let Some(edge) = bisection.next() {
+            let served = is_served(client, &self.with_longer_edge(edge)).await?;
+            bisection.report(edge, served);
+        }
+
+        Ok(Some(self.with_longer_edge(bisection.best())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bisection() {
+        for limit in [300, 301, 1000, 2999, 3000, u16::MAX] {
+            let mut bisection = Bisection::new(300, u16::MAX);
+            let mut probes = 0;
+            while let Some(edge) = bisection.next() {
+                bisection.report(edge, edge <= limit);
+                probes += 1;
+            }
+            assert_eq!(bisection.best(), limit);
+            assert!(probes <= 16);
+        }
+
+        assert_eq!(Bisection::new(600, 600).next(), None);
+        assert_eq!(Bisection::new(600, 100).next(), None);
+    }
+
+    #[test]
+    fn longer_edge() {
+        let image = MzStaticImage::parse("https://is1-ssl.mzstatic.com/image/thumb/Features125/v4/30/b8/fc/30b8fc23-fc6d-8006-fabd-b265b9c5a180/mzl.wnqeoeqa.jpg/2400x933vf-60.jpg").unwrap();
+        assert_eq!(image.with_longer_edge(1200).parameters.resolution, Resolution::Filled(1200, 467));
+        assert!(image.with_longer_edge(1200).to_string().ends_with("/1200x467vf-60.jpg"));
+
+        let image = MzStaticImage::parse("https://is1-ssl.mzstatic.com/image/thumb/Music221/v4/47/98/ae/4798ae9f-3199-dffa-980c-1d7c9ba56189/artwork.jpg/300x600ac.jpg").unwrap();
+        assert_eq!(image.with_longer_edge(100).parameters.resolution, Resolution::Filled(50, 100));
+        assert_eq!(image.with_longer_edge(1).parameters.resolution, Resolution::Filled(1, 1));
+    }
+}
@@ -1,6 +1,7 @@
 #![allow(unused)]
 pub mod accelerator;
 pub mod pool;
+pub mod saga;
 pub mod image;
 
 // todo: refactor quality to be struct to Make Invalid State Unrepresentable
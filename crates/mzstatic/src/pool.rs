@@ -1,12 +1,16 @@
-#[derive(Debug, PartialEq, Eq)]
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
 pub enum ParseError {
+    #[error("empty pool")]
     Empty,
+    #[error("unknown pool variant")]
     UnknownVariant,
+    #[error("pool did not terminate")]
     DidNotTerminate,
+    #[error("bad pool number: {0}")]
     BadNumber(core::num::ParseIntError)
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
 pub struct Pool {
    pub variant: Variant,
    pub number: Option<core::num::NonZeroU8>
@@ -14,7 +18,7 @@ pub struct Pool {
 impl Pool {
     pub(crate) fn read(input: &str) -> Result<super::Read<Self>, ParseError>  {
         let mut no_digit = false;
-        let stop = input.chars().enumerate().find(|(_, v)| v.is_ascii_digit() || {
+        let stop = input.char_indices().find(|(_, v)| v.is_ascii_digit() || {
             no_digit = v == &'/';
             no_digit
         }).map(|v| v.0).ok_or(ParseError::DidNotTerminate)?;
@@ -27,7 +31,7 @@ impl Pool {
                 }
             }
         } else {
-            let slash = stop + input[stop..].chars().enumerate().find(|(_, v)| v == &'/').ok_or(ParseError::DidNotTerminate)?.0;
+            let slash = stop + input[stop..].find('/').ok_or(ParseError::DidNotTerminate)?;
             let number = input[stop..slash].parse().map_err(ParseError::BadNumber)?;
             super::Read {
                 bytes: unsafe { core::num::NonZeroUsize::new_unchecked(slash + '/'.len_utf8()) },
@@ -48,8 +52,31 @@ impl core::fmt::Display for Pool {
         Ok(())
     }
 }
+impl Pool {
+    pub const fn category(&self) -> Category {
+        self.variant.category()
+    }
+}
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+/// What kind of asset a pool holds.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub enum Category {
+    /// Album, song and playlist artwork.
+    MusicArtwork,
+    /// Artist profile pictures and banners.
+    ArtistImage,
+    /// App icons, screenshots and their sources.
+    App,
+    /// Book and audiobook covers.
+    Book,
+    /// Video stills, posters and streams.
+    Video,
+    Podcast,
+    /// A pool whose contents are mixed or not well understood, such as editorial features.
+    Unknown,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
 pub enum Variant {
     AppStore,
     AppStoreSource,
@@ -65,6 +92,33 @@ pub enum Variant {
     
 }
 impl Variant {
+    /// Every known pool.
+    pub const ALL: [Self; 11] = [
+        Self::AppStore,
+        Self::AppStoreSource,
+        Self::Podcasts,
+        Self::Features,
+        Self::VideoHLS,
+        Self::Video,
+        Self::MusicArtistImages,
+        Self::Music,
+        Self::Books,
+        Self::FuseSocial,
+        Self::CobaltPublic,
+    ];
+
+    pub const fn category(&self) -> Category {
+        match self {
+            Self::Music => Category::MusicArtwork,
+            Self::MusicArtistImages => Category::ArtistImage,
+            Self::AppStore | Self::AppStoreSource => Category::App,
+            Self::Books => Category::Book,
+            Self::Video | Self::VideoHLS => Category::Video,
+            Self::Podcasts => Category::Podcast,
+            Self::Features | Self::FuseSocial | Self::CobaltPublic => Category::Unknown,
+        }
+    }
+
     #[allow(clippy::should_implement_trait)]
     pub fn from_str(input: &str) -> Option<Self> {
         match input {
@@ -114,4 +168,24 @@ mod tests {
         assert_eq!(Pool::read("Music/"), Ok(crate::Read { bytes: NonZero::new(6).unwrap(), value: Pool { variant: Variant::Music, number: None }}));
         assert_eq!(Pool::read("Music4/"), Ok(crate::Read { bytes: NonZero::new(7).unwrap(), value: Pool { variant: Variant::Music, number: Some(NonZero::new(4).unwrap()) }}));
     }
+
+    #[test]
+    fn catalog() {
+        for variant in Variant::ALL {
+            assert_eq!(Variant::from_str(variant.to_str()), Some(variant));
+        }
+        assert_eq!(Variant::from_str("Unheard"), None);
+    }
+
+    #[test]
+    fn categories() {
+        let category = |pool: &str| Pool::read(pool).unwrap().value.category();
+        assert_eq!(category("Music221/"), Category::MusicArtwork);
+        assert_eq!(category("AMCArtistImages126/"), Category::ArtistImage);
+        assert_eq!(category("Purple69/"), Category::App);
+        assert_eq!(category("PurpleSource211/"), Category::App);
+        assert_eq!(category("Publication113/"), Category::Book);
+        assert_eq!(category("Video116/"), Category::Video);
+        assert_eq!(category("Features125/"), Category::Unknown);
+    }
 }
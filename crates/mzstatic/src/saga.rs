@@ -0,0 +1,106 @@
+//! Saga tokens, which take the place of a [pool](crate::pool) on some older assets.
+//!
+//! See [`PoolOrSagaSpecifier::Saga`](crate::image::PoolOrSagaSpecifier::Saga) for the (lengthy) background.
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    #[error("saga specifier doesn't start with \"SG-\"")]
+    MissingPrefix,
+    #[error("saga specifier did not terminate")]
+    DidNotTerminate,
+    #[error("saga specifier is missing its {0}")]
+    MissingComponent(&'static str),
+    #[error("bad saga image index: {0}")]
+    BadIndex(core::num::ParseIntError),
+}
+
+/// A specifier of the form `SG-{provider}-{region}-{class}-Image[-]{index}`.
+///
+/// - `SG-MQ-US-035-Image000001`
+/// - `SG-S3-US-Std-Image-000001`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SagaSpecifier {
+    /// The external storage provider (e.x. `S3`, which is presumably Amazon S3).
+    pub provider: String,
+    /// Seemingly a region code (e.x. `US`). Changing it gives some sort of Spring Boot error.
+    pub region: String,
+    /// Unknown; seen as `035` and `Std` (perhaps a storage class?).
+    pub class: String,
+    /// The number following `Image`.
+    pub index: u32,
+    /// Whether the index is separated from `Image` with a dash, as in `Image-000001`.
+    pub dashed_index: bool,
+}
+impl SagaSpecifier {
+    pub const PREFIX: &'static str = "SG-";
+    /// The number of digits the index is padded to when displayed; the only width that's been seen.
+    pub const INDEX_WIDTH: usize = 6;
+
+    /// Whether the input starts with something which is meant to be a saga specifier, as opposed to a pool.
+    pub fn is_at_start(input: &str) -> bool {
+        input.starts_with(Self::PREFIX)
+    }
+
+    pub(crate) fn read(input: &str) -> Result<super::Read<Self>, ParseError> {
+        let end = input.find('/').ok_or(ParseError::DidNotTerminate)?;
+        let specifier = input[..end].strip_prefix(Self::PREFIX).ok_or(ParseError::MissingPrefix)?;
+
+        let mut components = specifier.splitn(4, '-');
+        let mut component = |name: &'static str| components.next()
+            .filter(|component| !component.is_empty() && component.chars().all(|char| char.is_ascii_alphanumeric()))
+            .ok_or(ParseError::MissingComponent(name));
+        let provider = component("provider")?.to_owned();
+        let region = component("region")?.to_owned();
+        let class = component("class")?.to_owned();
+
+        let index = components.next().and_then(|image| image.strip_prefix("Image")).ok_or(ParseError::MissingComponent("image index"))?;
+        let (index, dashed_index) = match index.strip_prefix('-') {
+            Some(index) => (index, true),
+            None => (index, false)
+        };
+        if !index.chars().all(|char| char.is_ascii_digit()) {
+            return Err(ParseError::MissingComponent("image index"))
+        }
+        let index = index.parse().map_err(ParseError::BadIndex)?;
+
+        Ok(super::Read {
+            bytes: unsafe { core::num::NonZeroUsize::new_unchecked(end + '/'.len_utf8()) },
+            value: Self { provider, region, class, index, dashed_index }
+        })
+    }
+}
+impl core::fmt::Display for SagaSpecifier {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}{}-{}-{}-Image", Self::PREFIX, self.provider, self.region, self.class)?;
+        if self.dashed_index { f.write_str("-")?; }
+        write!(f, "{:0width$}", self.index, width = Self::INDEX_WIDTH)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn basic() {
+        let read = SagaSpecifier::read("SG-MQ-US-035-Image000001/v4/").unwrap();
+        assert_eq!(read.bytes.get(), "SG-MQ-US-035-Image000001/".len());
+        assert_eq!(read.value, SagaSpecifier { provider: "MQ".to_owned(), region: "US".to_owned(), class: "035".to_owned(), index: 1, dashed_index: false });
+        assert_eq!(read.value.to_string(), "SG-MQ-US-035-Image000001");
+
+        let read = SagaSpecifier::read("SG-S3-US-Std-Image-000001/v4/").unwrap();
+        assert_eq!(read.value, SagaSpecifier { provider: "S3".to_owned(), region: "US".to_owned(), class: "Std".to_owned(), index: 1, dashed_index: true });
+        assert_eq!(read.value.to_string(), "SG-S3-US-Std-Image-000001");
+    }
+
+    #[test]
+    fn malformed() {
+        assert_eq!(SagaSpecifier::read("Music/").unwrap_err(), ParseError::MissingPrefix);
+        assert_eq!(SagaSpecifier::read("SG-S3-US-Std-Image-000001").unwrap_err(), ParseError::DidNotTerminate);
+        assert_eq!(SagaSpecifier::read("SG-S3-US/").unwrap_err(), ParseError::MissingComponent("class"));
+        assert_eq!(SagaSpecifier::read("SG-S3--Std-Image1/").unwrap_err(), ParseError::MissingComponent("region"));
+        assert_eq!(SagaSpecifier::read("SG-S3-US-Std-Photo1/").unwrap_err(), ParseError::MissingComponent("image index"));
+        assert_eq!(SagaSpecifier::read("SG-S3-US-Std-Image-1a/").unwrap_err(), ParseError::MissingComponent("image index"));
+        assert!(matches!(SagaSpecifier::read("SG-S3-US-Std-Image/"), Err(ParseError::BadIndex(_))));
+    }
+}
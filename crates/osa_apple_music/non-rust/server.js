@@ -942,6 +942,47 @@ function copy_err_to_plain_object(err) {
     return plain
 }
 
+/**
+ * @typedef { "not-running" | "permission-denied" | "timed-out" | "unknown-command" | "script" } ErrorCode
+ * @typedef { { code: ErrorCode, message: string, errorNumber?: number, stack?: string, line?: number, column?: number } } ErrorEnvelope
+ */
+
+/**
+ * Apple event error numbers which point to something other than a bug in this script.
+ * @see https://developer.apple.com/library/archive/documentation/AppleScript/Conceptual/AppleScriptLangGuide/reference/ASLR_error_codes.html
+ * @type { Record<number, ErrorCode> }
+ */
+const APPLE_EVENT_ERROR_CODES = {
+    [-600]: "not-running", // procNotFound; the application quit mid-query
+    [-609]: "not-running", // connectionInvalid
+    [-1712]: "timed-out", // errAETimeout
+    [-1743]: "permission-denied", // errAEEventNotPermitted
+    [-1744]: "permission-denied", // errAEEventWouldRequireUserConsent
+};
+
+/**
+ * @param { ErrorCode } code
+ * @param { string } message
+ * @returns { Error & { code: ErrorCode } }
+ */
+function coded_error(code, message) {
+    const err = new Error(message);
+    err.code = code;
+    return err;
+}
+
+/**
+ * Builds the value of an error response: whatever the error carries, plus a `code` classifying it.
+ * @param { unknown } err
+ * @returns { ErrorEnvelope }
+ */
+function error_envelope(err) {
+    const plain = err instanceof Error ? copy_err_to_plain_object(err) : Object.create(null);
+    plain.message ??= String(err);
+    plain.code ??= APPLE_EVENT_ERROR_CODES[plain.errorNumber] ?? "script";
+    return plain;
+}
+
 const prelude = ["osascript", "-l", "JavaScript", "<script-file>"];
 const usage = [...prelude, "<socket-path>"]
 const args = $.NSProcessInfo.processInfo.arguments.js.slice(prelude.length).map(arg => arg.js)
@@ -958,6 +999,19 @@ const server = new Server(path, {
 
 const APPLE_MUSIC = "com.apple.Music";
 
+/**
+ * Only the properties which are cheap to get; a playlist's size and duration have to be tallied up from its tracks.
+ * @returns { { name: string, class: string, persistentID: string } | null }
+ */
+function current_playlist(app) {
+    try {
+        const playlist = app.currentPlaylist;
+        return { name: playlist.name(), class: playlist.class(), persistentID: playlist.persistentID() };
+    } catch {
+        return null; // nothing is playing
+    }
+}
+
 server.listen((connection, [data]) => {
     /**
      * @type { PointerWithSize }
@@ -965,13 +1019,13 @@ server.listen((connection, [data]) => {
     let str;
     try {
         const app = Application(APPLE_MUSIC);
-        if (!app.running()) throw new Error("Application not running");
+        if (!app.running()) throw coded_error("not-running", "Application not running");
 
         let output;
         switch (uncstr(data).trim()) {
-            case "application":   { output = app             .properties(); break }
+            case "application":   { output = { ...app.properties(), currentPlaylist: current_playlist(app) }; break }
             case "current track": { output = app.currentTrack.properties(); break }
-            default: throw new Error("Unknown command");
+            default: throw coded_error("unknown-command", "Unknown command");
         }
 
         str = cstr.sized(JSON.stringify({
@@ -981,7 +1035,7 @@ server.listen((connection, [data]) => {
     } catch (err) {
         str = cstr.sized(JSON.stringify({
             type: "error",
-            value: copy_err_to_plain_object(err)
+            value: error_envelope(err)
         }))
     }
     connection.write_all(str);
@@ -13,7 +13,7 @@ pub enum PlayerState {
 }
 
 /// How the application is configured to shuffle tracks.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
 #[serde(rename_all = "lowercase")]
 pub enum ShuffleMode {
     Songs,
@@ -21,8 +21,8 @@ pub enum ShuffleMode {
     Groupings
 }
 
-
-#[derive(Deserialize, Debug)]
+/// How the application is configured to repeat tracks.
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
 #[serde(rename_all = "lowercase")]
 pub enum RepeatMode {
     /// There is no repeat target.
@@ -33,6 +33,41 @@ pub enum RepeatMode {
     All,
 }
 
+/// The kind of a playlist, going by its scripting class.
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum PlaylistKind {
+    /// The whole library.
+    LibraryPlaylist,
+    /// A playlist made by the user, including smart playlists and folders.
+    UserPlaylist,
+    /// A playlist from Apple Music which was added to the library.
+    SubscriptionPlaylist,
+    /// An internet radio station.
+    RadioTunesPlaylist,
+    #[serde(rename = "audioCDPlaylist")]
+    AudioCdPlaylist,
+    #[serde(other)]
+    Other,
+}
+
+/// The playlist which the current track is being played from.
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Playlist {
+    pub name: String,
+    #[serde(rename = "class")]
+    pub kind: PlaylistKind,
+    /// The library's persistent ID for the playlist, as a 16-character uppercase hexadecimal string.
+    #[serde(rename = "persistentID", default)]
+    pub persistent_id: Option<String>,
+}
+impl Playlist {
+    /// Whether the "playlist" is really a radio station.
+    pub fn is_station(&self) -> bool {
+        self.kind == PlaylistKind::RadioTunesPlaylist
+    }
+}
+
 /// The state of the Apple Music application.
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -47,10 +82,11 @@ pub struct ApplicationData {
     /// Whether the application is muted.
     pub mute: bool,
 
+    /// Whether the application is shuffling tracks.
     #[serde(rename = "shuffleEnabled")]
     pub shuffling: bool,
 
-    /// The configured shuffle mode of the application.
+    /// The configured shuffle mode of the application; `None` if it isn't [shuffling](Self::shuffling).
     #[serde(rename = "shuffleMode")]
     pub shuffle: Option<ShuffleMode>,
 
@@ -65,6 +101,12 @@ pub struct ApplicationData {
     /// The position of the current track in seconds.
     #[serde(rename = "playerPosition")]
     pub position: Option<f32>,
+
+    /// The playlist which the current track is being played from, if any.
+    ///
+    /// Only present when fetched through a [`Session`](crate::Session).
+    #[serde(rename = "currentPlaylist", default)]
+    pub playlist: Option<Playlist>,
 }
 impl ApplicationData {
     pub(crate) fn fix(mut self) -> Self {
@@ -74,6 +116,11 @@ impl ApplicationData {
         self
     }
 
+    /// Whether the current track will start over once it ends, rather than moving on to the next.
+    pub fn repeats_track(&self) -> bool {
+        self.repeat == RepeatMode::One
+    }
+
     /// Fetches and returns the application state.
     /// If you find yourself doing this repeatedly, consider using [`Session`](crate::Session) instead.
     pub async fn fetch() -> Result<Self, crate::error::SingleEvaluationError> {
@@ -83,3 +130,37 @@ impl ApplicationData {
             .and_then(|output| Ok(serde_json::from_str(&output.stdout()).map(ApplicationData::fix)?))
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn application(shuffle_enabled: bool, repeat: &str) -> ApplicationData {
+        let json = format!(r#"{{
+            "playerState": "playing",
+            "version": "1.5.0.73",
+            "mute": false,
+            "shuffleEnabled": {shuffle_enabled},
+            "shuffleMode": "albums",
+            "songRepeat": "{repeat}",
+            "soundVolume": 50,
+            "playerPosition": 12.5
+        }}"#);
+        serde_json::from_str::<ApplicationData>(&json).unwrap().fix()
+    }
+
+    #[test]
+    fn shuffle_and_repeat() {
+        let data = application(true, "one");
+        assert!(data.shuffling);
+        assert_eq!(data.shuffle, Some(ShuffleMode::Albums));
+        assert_eq!(data.repeat, RepeatMode::One);
+        assert!(data.repeats_track());
+
+        let data = application(false, "all");
+        assert_eq!(data.shuffle, None);
+        assert_eq!(data.repeat, RepeatMode::All);
+        assert!(!data.repeats_track());
+    }
+}
@@ -20,6 +20,8 @@ pub mod error {
         SessionFailure(#[from] osascript::repl::Error),
         #[error("io failure: {0}")]
         IoFailure(#[from] tokio::io::Error),
+        #[error("not permitted to control the application: {}", .0.message)]
+        PermissionDenied(JavaScriptError),
         #[error("javascript error: {}", .0.message)]
         QueryFailure(#[from] JavaScriptError)
     }
@@ -35,16 +37,53 @@ pub mod error {
     }
 }
 
+/// How the server classified a failure; see `error_envelope` in `server.js`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ErrorCode {
+    /// The application isn't running, or quit partway through the query.
+    NotRunning,
+    /// The user hasn't allowed (or has revoked) automation access to the application.
+    PermissionDenied,
+    /// The application didn't respond to an Apple event in time.
+    TimedOut,
+    /// The server was sent a command it doesn't know.
+    UnknownCommand,
+    /// Anything else, which is most likely a bug in the script.
+    #[default]
+    #[serde(other)]
+    Script,
+}
+
 #[derive(Debug, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[allow(unused)]
 pub struct JavaScriptError {
+    #[serde(default)]
+    code: ErrorCode,
     message: String,
+    #[serde(default)]
     stack: String,
+    #[serde(default)]
     line: u32,
+    #[serde(default)]
     column: u32,
     error_number: Option<i32> // jxa extension
 }
+impl JavaScriptError {
+    pub const fn code(&self) -> ErrorCode {
+        self.code
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The AppleScript error number, if the error came from an Apple event.
+    pub const fn error_number(&self) -> Option<i32> {
+        self.error_number
+    }
+}
 impl core::error::Error for JavaScriptError {}
 impl core::fmt::Display for JavaScriptError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
@@ -120,18 +159,14 @@ impl Session {
             error::SessionEvaluationError::DeserializationFailure { issue: err, data: bytes, is_utf8: true }
         })?;
         
-        let result = match response {
+        match response {
             Response::Success(v) => Ok(Some(v)),
-            Response::Error(err) => Err(error::SessionEvaluationError::QueryFailure(err))
-        };
-
-        if let Err(error::SessionEvaluationError::QueryFailure(err)) = &result {
-            if err.message == "Application not running" {
-                return Ok(None)
+            Response::Error(err) => match err.code {
+                ErrorCode::NotRunning => Ok(None),
+                ErrorCode::PermissionDenied => Err(error::SessionEvaluationError::PermissionDenied(err)),
+                _ => Err(error::SessionEvaluationError::QueryFailure(err))
             }
         }
-
-        result
     }
 
     pub async fn application(&mut self) -> Result<Option<ApplicationData>, error::SessionEvaluationError> {
@@ -157,6 +192,30 @@ impl Drop for Session {
 mod tests {
     use super::*;
 
+    #[test]
+    fn error_envelope() {
+        let err: JavaScriptError = serde_json::from_str(r#"{
+            "code": "permission-denied",
+            "message": "Error: Not authorized to send Apple events to Music.",
+            "errorNumber": -1743,
+            "line": 1012,
+            "column": 37,
+            "stack": "global code"
+        }"#).unwrap();
+        assert_eq!(err.code(), ErrorCode::PermissionDenied);
+        assert_eq!(err.error_number(), Some(-1743));
+
+        let err: JavaScriptError = serde_json::from_str(r#"{ "code": "not-running", "message": "Application not running" }"#).unwrap();
+        assert_eq!(err.code(), ErrorCode::NotRunning);
+        assert_eq!(err.error_number(), None);
+
+        // codes added to the server later shouldn't break deserialization
+        let err: JavaScriptError = serde_json::from_str(r#"{ "code": "something-new", "message": "?" }"#).unwrap();
+        assert_eq!(err.code(), ErrorCode::Script);
+        let err: JavaScriptError = serde_json::from_str(r#"{ "message": "TypeError: undefined is not an object" }"#).unwrap();
+        assert_eq!(err.code(), ErrorCode::Script);
+    }
+
     #[tokio::test]
     #[ignore = "must be manually run with the correct environment setup"]
     async fn test_session() {
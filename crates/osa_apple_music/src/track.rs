@@ -136,7 +136,7 @@ pub struct TrackPurchaser {
 }
 
 // FIXME: can't run tests cuz no sqlx backend defined so no type to derive for
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 #[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
 #[cfg_attr(feature = "sqlx", sqlx(rename_all = "lowercase"))]
@@ -145,8 +145,16 @@ pub enum MediaKind {
     #[cfg_attr(feature = "sqlx", sqlx(rename = "music video"))]
     #[serde(rename = "music video")]
     MusicVideo,
+    Podcast,
+    Audiobook,
     Unknown
 }
+impl MediaKind {
+    /// Whether this is music, as opposed to spoken-word media like podcasts and audiobooks.
+    pub const fn is_music(self) -> bool {
+        matches!(self, Self::Song | Self::MusicVideo | Self::Unknown)
+    }
+}
 
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -191,11 +199,11 @@ pub struct SkippedInfo {
 pub struct MovementInfo {
     /// The name of the movement.
     #[serde(rename = "movement")]
-    name: String,
+    pub name: String,
 
     /// The index of this movement in the work.
     #[serde(rename = "movementNumber")]
-    index: u16,
+    pub index: u16,
 }
 
 serde_with::serde_conv!(
@@ -80,17 +80,15 @@ fn internal_get_imbalanced_stack(input: &str, allow_template_strings: bool) -> R
         if let Some(region) = Region::get_from_closer(char) {
             if recent == Some(region) {
                 stack.pop();
+            } else if recent.is_some_and(Region::is_quote) {
+                // Part of a string literal.
+                continue;
             } else if region.is_quote() {
-                if recent.is_some_and(Region::is_quote) {
-                    // Quote is part of a string literal.
-                    continue;
-                } else {
-                    if !allow_template_strings && region == Region::TemplateString {
-                        return Err(InvalidCharacterPlacementError);
-                    }
-                    // Treat as an opener.
-                    stack.push(region);
+                if !allow_template_strings && region == Region::TemplateString {
+                    return Err(InvalidCharacterPlacementError);
                 }
+                // Treat as an opener.
+                stack.push(region);
             } else {
                 return Err(InvalidCharacterPlacementError);
             }
@@ -138,5 +136,7 @@ mod tests {
         assert_eq!(internal_get_imbalanced_stack("`${'\"\\", true), Ok(vec![Region::TemplateString, Region::CurlyBracket, Region::SingleQuote, Region::Escaping]));
         assert_eq!(internal_get_imbalanced_stack("`${'\"\\", false), Err(InvalidCharacterPlacementError));
         assert_eq!(internal_get_imbalanced_stack(")", true), Err(InvalidCharacterPlacementError));
+        assert_eq!(internal_get_imbalanced_stack("'})]'", true), Ok(vec![]));
+        assert_eq!(internal_get_imbalanced_stack("`}`", true), Ok(vec![]));
     }   
 }
@@ -15,6 +15,12 @@ pub enum Error {
     FailedToTestForMultiline,
     #[error("the REPL process does not exist; it may have crashed or been killed")]
     ProcessDoesNotExist,
+    #[error("definitions aren't supported in {0} sessions")]
+    DefinitionsUnsupported(Language),
+    #[error("couldn't define {name:?}: {}", .output.raw.as_lossy_str())]
+    DefinitionFailed { name: String, output: Output },
+    #[error("nothing has been defined as {0:?}")]
+    Undefined(String),
     #[error("{0}")]
     Io(#[from] std::io::Error),
 }
@@ -36,6 +42,8 @@ pub struct Session {
     language: Language,
     process: Child,
     out: std::sync::Arc<tokio::io::BufReader<tokio::process::ChildStdout>>,
+    /// The source of everything which has been [defined](Self::define), by name.
+    definitions: std::collections::HashMap<String, String>,
 }
 impl core::fmt::Debug for Session {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
@@ -69,7 +77,7 @@ impl Session {
         let out = tokio::io::BufReader::new(out);
         let out = std::sync::Arc::new(out);
 
-        let mut session = Self { process: child, out, language };
+        let mut session = Self { process: child, out, language, definitions: Default::default() };
 
         // A preliminary evaluation needs to be performed because it decides to write it's own
         // command to stdout twice instead of once on the first execution, for whatever reason.
@@ -153,6 +161,30 @@ impl Session {
         Ok(out)
     } 
 
+    /// Compiles a JavaScript function expression once, so that it can be [called](Self::call) by name
+    /// without its source having to be sent and parsed again. Unlike with [`Self::run`], the source can span multiple lines.
+    ///
+    /// Definitions are carried over when the session is [restarted](Self::restart).
+    pub async fn define(&mut self, name: &str, source: &str) -> Result<(), Error> {
+        if self.language != Language::JavaScript {
+            return Err(Error::DefinitionsUnsupported(self.language))
+        }
+        let output = self.run(&definition(name, source)).await?;
+        if output.guess().ok().as_deref() != Some("true") {
+            return Err(Error::DefinitionFailed { name: name.to_owned(), output })
+        }
+        self.definitions.insert(name.to_owned(), source.to_owned());
+        Ok(())
+    }
+
+    /// Calls a function which was [defined](Self::define), with each argument being a JavaScript expression (such as a JSON value).
+    pub async fn call(&mut self, name: &str, args: &[&str]) -> Result<Output, Error> {
+        if !self.definitions.contains_key(name) {
+            return Err(Error::Undefined(name.to_owned()))
+        }
+        self.run(&format!("{DEFINITIONS}[{}]({})", js_string_literal(name), args.join(", "))).await
+    }
+
     /// Kills the REPL session.
     pub async fn kill(&mut self) -> Result<(), std::io::Error> {
         self.process.kill().await?;
@@ -161,8 +193,15 @@ impl Session {
 
     /// Restarts the REPL session.
     pub async fn restart(&mut self) -> Result<(), std::io::Error> {
+        let definitions = core::mem::take(&mut self.definitions);
         // Previous self will be dropped and automatically killed.
         *self = Self::new(self.language).await?;
+        for (name, source) in definitions {
+            self.define(&name, &source).await.map_err(|err| match err {
+                Error::Io(io) => io,
+                err => std::io::Error::other(err)
+            })?;
+        }
         Ok(())
     }
 
@@ -177,6 +216,35 @@ impl Drop for Session {
     }
 }
 
+/// Where [definitions](Session::define) are kept within the JavaScript context.
+const DEFINITIONS: &str = "globalThis.__osascriptDefinitions";
+
+/// A single line which compiles the source and stores it under the name, evaluating to `true` if that worked.
+fn definition(name: &str, source: &str) -> String {
+    // The closing parenthesis goes on its own line so that a trailing line comment can't swallow it.
+    format!("(({DEFINITIONS} ??= {{}})[{}] = eval({}), true)", js_string_literal(name), js_string_literal(&format!("({source}\n)")))
+}
+
+/// Quotes the value as a JavaScript string literal which fits on a single line.
+fn js_string_literal(value: &str) -> String {
+    use core::fmt::Write;
+    let mut literal = String::with_capacity(value.len() + 2);
+    literal.push('"');
+    for char in value.chars() {
+        match char {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            // the line and paragraph separators end lines too, as far as the REPL's concerned
+            char if char.is_control() || matches!(char, '\u{2028}' | '\u{2029}') => { let _ = write!(literal, "\\u{:04x}", char as u32); },
+            char => literal.push(char),
+        }
+    }
+    literal.push('"');
+    literal
+}
 
 pub(crate) mod iter {
     const CARRIAGE_RETURNED_NEWLINE: &[u8] = b"\r\n";
@@ -552,6 +620,30 @@ mod tests {
         assert!(matches!(session.run("hi").await, Err(Error::ProcessDoesNotExist)));
     }
 
+    #[test]
+    fn string_literals() {
+        use super::js_string_literal;
+        assert_eq!(js_string_literal("plain"), r#""plain""#);
+        assert_eq!(js_string_literal("say \"hi\"\\"), r#""say \"hi\"\\""#);
+        assert_eq!(js_string_literal("a\nb\r\tc\u{2028}\u{7}"), r#""a\nb\r\tc\u2028\u0007""#);
+        assert!(super::balanced::is_balanced(&super::definition("f", "(x) => {\n    return `${x}`; // \"\n}"), Language::JavaScript).unwrap());
+    }
+
+    #[tokio::test]
+    async fn definitions() {
+        let mut session = Session::new(Language::JavaScript).await.unwrap();
+        session.define("add", "function (a, b) {\n    return a + b; // sum\n}").await.unwrap();
+        assert_eq!(session.call("add", &["1", "2"]).await.unwrap().guess().unwrap(), "3");
+        assert!(matches!(session.call("subtract", &["1", "2"]).await, Err(Error::Undefined(_))));
+        assert!(matches!(session.define("broken", "function (").await, Err(Error::DefinitionFailed { .. })));
+
+        session.restart().await.unwrap();
+        assert_eq!(session.call("add", &["\"a\"", "\"b\""]).await.unwrap().guess().unwrap(), "\"ab\"");
+
+        let mut session = Session::new(Language::AppleScript).await.unwrap();
+        assert!(matches!(session.define("add", "function (a, b) { return a + b }").await, Err(Error::DefinitionsUnsupported(Language::AppleScript))));
+    }
+
     #[tokio::test]
     async fn multiline_input_prevention() {
         let mut session = Session::new(Language::JavaScript).await.unwrap();
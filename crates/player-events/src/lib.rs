@@ -0,0 +1,397 @@
+//! The events which [am-osx-status](https://github.com/homomorphist/am-osx-status) publishes about what's playing in Apple Music.
+//!
+//! Those subscribed over its IPC socket are given each as a line of JSON, in the shape of a [`Frame`]:
+//! ```json
+//! {"at":1700000000000,"event":"volume_changed","volume":40,"muted":false}
+//! ```
+use std::borrow::Cow;
+use serde::{Serialize, Deserialize};
+
+mod track;
+pub use track::{Track, PersistentId, MediaKind, MovementInfo};
+
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlayerStatus {
+    Playing,
+    /// The music stopped and there is no more music that will start playing soon.
+    // TODO: uhh fact-check this it's been so long
+    Stopped,
+    /// A temporary pause in track playback.
+    /// This may not be a result of user action— this may also be dispatched when encountering playback buffering issues.
+    Paused,
+    Closed
+}
+
+/// How much of a track was listened to, as of when it ended.
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct Listened {
+    /// The total time spent listening to the track, in seconds.
+    ///
+    /// Re-listening to part of the track (e.x. after seeking backwards) counts each time it's heard.
+    pub total: f32,
+    /// The fraction of the track that was heard, from zero to one, with overlapping segments only being counted once.
+    /// This isn't known if the track's duration isn't.
+    #[serde(default)]
+    pub coverage: Option<f32>,
+}
+
+/// A stretch of listening that was brought to an end by nothing having played for a while afterwards,
+/// such as from a sleep timer having paused the player.
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct ListeningSession {
+    /// When the first track of the session started, in milliseconds since the Unix epoch.
+    pub started: i64,
+    /// When playback last stopped, in milliseconds since the Unix epoch.
+    pub ended: i64,
+    /// How many tracks were listened to; including one that was left paused.
+    pub tracks: u32,
+    /// The total time spent listening across every track, in seconds.
+    pub listened: f32,
+}
+impl ListeningSession {
+    /// The total time spent listening, in whole minutes.
+    #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss, reason = "never negative, and far from overflowing")]
+    pub fn minutes(&self) -> u32 {
+        (self.listened / 60.) as u32
+    }
+}
+
+/// Where playback was in the track as of some moment, and how quickly it's moving along.
+///
+/// The player's position is only known as often as it's polled, so this is given for the position in between
+/// to be [worked out](Self::position_at) rather than jumping from one poll to the next.
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct Playback {
+    /// The position in the track, in seconds.
+    pub position: f32,
+    /// When playback was at the position, in milliseconds since the Unix epoch.
+    pub at: i64,
+    /// How many seconds of the track are played each second; zero if it isn't playing.
+    pub rate: f32,
+}
+impl Playback {
+    /// The expected position in the track at the given time, in milliseconds since the Unix epoch.
+    ///
+    /// It's never before the position as of [`Self::at`], nor past the end of the track if its `duration` is given.
+    pub fn position_at(&self, at: i64, duration: Option<core::time::Duration>) -> f32 {
+        #[expect(clippy::cast_precision_loss, reason = "well within precision for any reasonable gap between polls")]
+        let elapsed = (at - self.at).max(0) as f32 / 1000.;
+        let position = self.position + elapsed * self.rate;
+        duration.map_or(position, |duration| position.min(duration.as_secs_f32()))
+    }
+
+    /// How far along playback actually was compared to where it was expected to be, in seconds; positive if it was further along.
+    pub fn drift(&self, actual: &Self) -> f32 {
+        actual.position - self.position_at(actual.at, None)
+    }
+}
+
+/// The artwork of a track, in the [format](ArtworkFormat) the subscriber asked for; tagged with which under `kind`.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Artwork {
+    /// Where it can be fetched from.
+    Url { url: String },
+    /// A small copy of it, encoded inline.
+    Thumbnail {
+        /// The media type of the image, such as `image/jpeg`.
+        mime: String,
+        /// The image, encoded with standard (padded) base64.
+        data: String,
+    },
+    /// Where a copy of it is on the disk of the publisher.
+    Path { path: String },
+}
+impl Artwork {
+    pub const fn format(&self) -> ArtworkFormat {
+        match self {
+            Self::Url { .. } => ArtworkFormat::Url,
+            Self::Thumbnail { .. } => ArtworkFormat::Thumbnail,
+            Self::Path { .. } => ArtworkFormat::Path,
+        }
+    }
+}
+
+/// Which form of [`Artwork`] a subscriber wants to be given.
+#[derive(Debug, Default, PartialEq, Eq, Hash, Copy, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArtworkFormat {
+    #[default]
+    Url,
+    Thumbnail,
+    Path,
+}
+impl ArtworkFormat {
+    pub const ALL: [Self; 3] = [Self::Url, Self::Thumbnail, Self::Path];
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Url => "url",
+            Self::Thumbnail => "thumbnail",
+            Self::Path => "path",
+        }
+    }
+}
+impl core::fmt::Display for ArtworkFormat {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+impl core::str::FromStr for ArtworkFormat {
+    type Err = UnknownArtworkFormat;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter().find(|format| format.as_str() == s).ok_or(UnknownArtworkFormat)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct UnknownArtworkFormat;
+impl core::fmt::Display for UnknownArtworkFormat {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("expected one of `url`, `thumbnail`, or `path`")
+    }
+}
+impl std::error::Error for UnknownArtworkFormat {}
+
+/// Something that happened with the player; tagged with its [kind](EventKind) under `event`.
+///
+/// Tracks are borrowed when publishing, and owned once deserialized.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event<'a> {
+    TrackStarted {
+        track: Cow<'a, Track>,
+        /// Left out if it wasn't known by the time the track started; it'll follow in an [`Event::ArtworkResolved`] if it's found later.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        artwork: Option<Artwork>,
+    },
+    /// The artwork of the track which is playing was found after it had started.
+    ArtworkResolved {
+        track: Cow<'a, Track>,
+        artwork: Artwork,
+    },
+    TrackEnded {
+        track: Cow<'a, Track>,
+        listened: Listened,
+    },
+    /// The position in the track jumped, such as from seeking.
+    ProgressJolt {
+        track: Cow<'a, Track>,
+        /// The expected position in the track, in seconds.
+        position: Option<f32>,
+    },
+    PlayerStatus {
+        status: PlayerStatus,
+        /// Where playback is in the current track, corrected to what the player reported as of each poll.
+        /// Left out if there isn't a track.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        playback: Option<Playback>,
+    },
+    VolumeChanged {
+        /// From 0 to 100, inclusive on both ends; kept as it was while muted.
+        volume: u8,
+        muted: bool,
+    },
+    /// Nothing has played for long enough after the last track that listening is considered to have stopped.
+    ListeningSessionEnded {
+        session: ListeningSession,
+    },
+    /// The publisher is about to exit.
+    Terminating,
+}
+impl Event<'_> {
+    pub const fn kind(&self) -> EventKind {
+        match self {
+            Self::TrackStarted { .. } => EventKind::TrackStarted,
+            Self::ArtworkResolved { .. } => EventKind::ArtworkResolved,
+            Self::TrackEnded { .. } => EventKind::TrackEnded,
+            Self::ProgressJolt { .. } => EventKind::ProgressJolt,
+            Self::PlayerStatus { .. } => EventKind::PlayerStatus,
+            Self::VolumeChanged { .. } => EventKind::VolumeChanged,
+            Self::ListeningSessionEnded { .. } => EventKind::ListeningSessionEnded,
+            Self::Terminating => EventKind::Terminating,
+        }
+    }
+
+    /// The track the event is about, if it's about one.
+    pub fn track(&self) -> Option<&Track> {
+        match self {
+            Self::TrackStarted { track, .. } |
+            Self::ArtworkResolved { track, .. } |
+            Self::TrackEnded { track, .. } |
+            Self::ProgressJolt { track, .. } => Some(track),
+            Self::PlayerStatus { .. } | Self::VolumeChanged { .. } | Self::ListeningSessionEnded { .. } | Self::Terminating => None,
+        }
+    }
+
+    pub fn into_owned(self) -> Event<'static> {
+        match self {
+            Self::TrackStarted { track, artwork } => Event::TrackStarted { track: Cow::Owned(track.into_owned()), artwork },
+            Self::ArtworkResolved { track, artwork } => Event::ArtworkResolved { track: Cow::Owned(track.into_owned()), artwork },
+            Self::TrackEnded { track, listened } => Event::TrackEnded { track: Cow::Owned(track.into_owned()), listened },
+            Self::ProgressJolt { track, position } => Event::ProgressJolt { track: Cow::Owned(track.into_owned()), position },
+            Self::PlayerStatus { status, playback } => Event::PlayerStatus { status, playback },
+            Self::VolumeChanged { volume, muted } => Event::VolumeChanged { volume, muted },
+            Self::ListeningSessionEnded { session } => Event::ListeningSessionEnded { session },
+            Self::Terminating => Event::Terminating,
+        }
+    }
+}
+
+/// Which kind of [`Event`] something is, as it's written under `event`.
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    TrackStarted,
+    ArtworkResolved,
+    TrackEnded,
+    ProgressJolt,
+    PlayerStatus,
+    VolumeChanged,
+    ListeningSessionEnded,
+    Terminating,
+}
+impl EventKind {
+    pub const ALL: [Self; 8] = [
+        Self::TrackStarted,
+        Self::ArtworkResolved,
+        Self::TrackEnded,
+        Self::ProgressJolt,
+        Self::PlayerStatus,
+        Self::VolumeChanged,
+        Self::ListeningSessionEnded,
+        Self::Terminating,
+    ];
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::TrackStarted => "track_started",
+            Self::ArtworkResolved => "artwork_resolved",
+            Self::TrackEnded => "track_ended",
+            Self::ProgressJolt => "progress_jolt",
+            Self::PlayerStatus => "player_status",
+            Self::VolumeChanged => "volume_changed",
+            Self::ListeningSessionEnded => "listening_session_ended",
+            Self::Terminating => "terminating",
+        }
+    }
+}
+impl core::fmt::Display for EventKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// An [`Event`] as it's written to subscribers, along with when it was published.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Frame<'a> {
+    /// When the event was published, in milliseconds since the Unix epoch.
+    pub at: i64,
+    #[serde(flatten)]
+    pub event: Event<'a>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track() -> Track {
+        Track {
+            name: "Fire Meet Gasoline".to_owned(),
+            album: Some("1000 Forms of Fear".to_owned()),
+            album_artist: None,
+            artist: Some("Sia".to_owned()),
+            persistent_id: PersistentId::new(1),
+            duration: Some(core::time::Duration::from_secs(245)),
+            media_kind: MediaKind::Song,
+            track_number: core::num::NonZero::new(5),
+            apple_music_url: None,
+            genre: None,
+            composer: None,
+            work: None,
+            movement: None,
+        }
+    }
+
+    #[test]
+    fn kinds() {
+        let track = track();
+        let events = [
+            Event::TrackStarted { track: Cow::Borrowed(&track), artwork: None },
+            Event::ArtworkResolved { track: Cow::Borrowed(&track), artwork: Artwork::Url { url: "https://example.com/artwork.jpg".to_owned() } },
+            Event::TrackEnded { track: Cow::Borrowed(&track), listened: Listened { total: 120., coverage: Some(0.5) } },
+            Event::ProgressJolt { track: Cow::Borrowed(&track), position: None },
+            Event::PlayerStatus { status: PlayerStatus::Paused, playback: None },
+            Event::VolumeChanged { volume: 40, muted: false },
+            Event::ListeningSessionEnded { session: ListeningSession { started: 1_700_000_000_000, ended: 1_700_003_600_000, tracks: 14, listened: 3150. } },
+            Event::Terminating,
+        ];
+        for (event, kind) in events.iter().zip(EventKind::ALL) {
+            assert_eq!(event.kind(), kind);
+            let json = serde_json::to_value(event).unwrap();
+            assert_eq!(json["event"], kind.as_str());
+        }
+    }
+
+    #[test]
+    fn frames() {
+        let track = track();
+        let frame = Frame { at: 1_700_000_000_000, event: Event::TrackEnded { track: Cow::Borrowed(&track), listened: Listened { total: 120., coverage: Some(0.5) } } };
+        let line = serde_json::to_string(&frame).unwrap();
+        let read: Frame<'static> = serde_json::from_str(&line).unwrap();
+        assert_eq!(read, frame);
+        assert_eq!(read.event.track(), Some(&track));
+
+        let read: Frame<'static> = serde_json::from_str(r#"{"at":1700000000000,"event":"volume_changed","volume":40,"muted":false}"#).unwrap();
+        assert_eq!(read.event, Event::VolumeChanged { volume: 40, muted: false });
+    }
+
+    #[test]
+    fn playback() {
+        let playback = Playback { position: 30., at: 1_700_000_000_000, rate: 1. };
+        assert!((playback.position_at(1_700_000_000_250, None) - 30.25).abs() < f32::EPSILON);
+        assert!((playback.position_at(1_699_999_999_000, None) - 30.).abs() < f32::EPSILON, "shouldn't go backwards");
+        assert!((playback.position_at(1_700_000_060_000, Some(core::time::Duration::from_secs(45))) - 45.).abs() < f32::EPSILON);
+
+        let paused = Playback { rate: 0., ..playback };
+        assert!((paused.position_at(1_700_000_010_000, None) - 30.).abs() < f32::EPSILON);
+
+        let polled = Playback { position: 30.4, at: 1_700_000_000_500, rate: 1. };
+        assert!((playback.drift(&polled) - -0.1).abs() < 1e-4);
+
+        let status = Event::PlayerStatus { status: PlayerStatus::Playing, playback: Some(playback) };
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["playback"]["at"], 1_700_000_000_000_i64);
+        assert!(serde_json::to_value(Event::PlayerStatus { status: PlayerStatus::Closed, playback: None }).unwrap().get("playback").is_none());
+        let read: Event<'static> = serde_json::from_str(r#"{"event":"player_status","status":"paused"}"#).unwrap();
+        assert_eq!(read, Event::PlayerStatus { status: PlayerStatus::Paused, playback: None });
+    }
+
+    #[test]
+    fn artwork() {
+        let track = track();
+        let started = Event::TrackStarted { track: Cow::Borrowed(&track), artwork: None };
+        assert!(serde_json::to_value(&started).unwrap().get("artwork").is_none());
+
+        let artwork = Artwork::Thumbnail { mime: "image/jpeg".to_owned(), data: "AAAA".to_owned() };
+        let json = serde_json::to_value(&artwork).unwrap();
+        assert_eq!(json["kind"], artwork.format().as_str());
+        assert_eq!(serde_json::from_value::<Artwork>(json).unwrap(), artwork);
+
+        for format in ArtworkFormat::ALL {
+            assert_eq!(format.as_str().parse(), Ok(format));
+            assert_eq!(serde_json::to_value(format).unwrap(), format.as_str());
+        }
+        assert_eq!("base64".parse::<ArtworkFormat>(), Err(UnknownArtworkFormat));
+    }
+
+    #[test]
+    fn omitted_track_fields() {
+        let json = r#"{"name":"Untitled","album":null,"album_artist":null,"artist":null,"persistent_id":-1,"duration":null,"media_kind":"music video","track_number":null,"apple_music_url":null}"#;
+        let track: Track = serde_json::from_str(json).unwrap();
+        assert_eq!(track.persistent_id.get(), u64::MAX);
+        assert_eq!(track.media_kind, MediaKind::MusicVideo);
+        assert_eq!(track.movement, None);
+    }
+}
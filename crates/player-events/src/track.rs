@@ -0,0 +1,147 @@
+use serde::{Serialize, Deserialize};
+
+/// Identifies a track within the user's library; stable across launches of the application, unlike its database ID.
+///
+/// Serialized as a signed 64-bit integer with the same bits as the (unsigned) ID, since that's all SQLite can hold.
+/// The application itself displays it as [hexadecimal](Self::to_hex).
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type), sqlx(transparent))]
+pub struct PersistentId(i64);
+impl PersistentId {
+    /// How many digits the ID is formatted with by the application, in hexadecimal.
+    pub const HEX_LENGTH: usize = 16;
+
+    pub const fn new(id: u64) -> Self {
+        Self(u64::cast_signed(id))
+    }
+
+    pub fn from_hex(value: &str) -> Result<Self, core::num::ParseIntError> {
+        u64::from_str_radix(value, 16).map(Self::new)
+    }
+
+    /// Formats the ID the way the application does: as sixteen uppercase hexadecimal digits (e.x. `0A1B2C3D4E5F6071`).
+    pub fn to_hex(self) -> String {
+        format!("{:016X}", self.get())
+    }
+    pub fn to_hex_upper(self) -> String {
+        format!("{:X}", self.get())
+    }
+    pub fn to_hex_lower(self) -> String {
+        format!("{:x}", self.get())
+    }
+
+    pub const fn get(self) -> u64 {
+        i64::cast_unsigned(self.0)
+    }
+
+    pub const fn signed(self) -> i64 {
+        self.0
+    }
+}
+impl From<PersistentId> for u64 {
+    fn from(val: PersistentId) -> Self {
+        val.get()
+    }
+}
+impl From<u64> for PersistentId {
+    fn from(value: u64) -> Self {
+        Self::new(value)
+    }
+}
+/// Accepts hexadecimal when it's prefixed with `0x` or is as long as the application formats it, and decimal otherwise.
+impl core::str::FromStr for PersistentId {
+    type Err = core::num::ParseIntError;
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if let Some(hex) = value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+            Self::from_hex(hex)
+        } else if value.len() == Self::HEX_LENGTH {
+            Self::from_hex(value)
+        } else {
+            value.parse().map(Self::new)
+        }
+    }
+}
+impl core::fmt::Debug for PersistentId {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "PersistentId({})", self.get())
+    }
+}
+impl core::fmt::Display for PersistentId {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.get())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(feature = "sqlx", sqlx(rename_all = "lowercase"))]
+pub enum MediaKind {
+    Song,
+    #[cfg_attr(feature = "sqlx", sqlx(rename = "music video"))]
+    #[serde(rename = "music video")]
+    MusicVideo,
+    Podcast,
+    Audiobook,
+    Unknown
+}
+impl MediaKind {
+    /// Whether this is music, as opposed to spoken-word media like podcasts and audiobooks.
+    pub const fn is_music(self) -> bool {
+        matches!(self, Self::Song | Self::MusicVideo | Self::Unknown)
+    }
+}
+
+/// Where a track sits within the classical [work](Track::work) it's a part of.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct MovementInfo {
+    /// The name of the movement.
+    #[serde(rename = "movement")]
+    pub name: String,
+
+    /// The index of this movement in the work.
+    #[serde(rename = "movementNumber")]
+    pub index: u16,
+}
+
+/// The minimum data about a track that's given to backends and subscribers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Track {
+    pub name: String,
+    pub album: Option<String>,
+    pub album_artist: Option<String>,
+    pub artist: Option<String>,
+    pub persistent_id: PersistentId,
+    pub duration: Option<core::time::Duration>,
+    pub media_kind: MediaKind,
+    pub track_number: Option<core::num::NonZero<u16>>,
+    pub apple_music_url: Option<String>,
+    #[serde(default)]
+    pub genre: Option<String>,
+    #[serde(default)]
+    pub composer: Option<String>,
+    /// The classical work which the track is a movement of.
+    #[serde(default)]
+    pub work: Option<String>,
+    #[serde(default)]
+    pub movement: Option<MovementInfo>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn persistent_id() {
+        const VALUE: u64 = 10_213_095_753_550_683_260;
+        let id = PersistentId::new(VALUE);
+        assert!(id.signed() < 0, "value is stored with the same bits as it has unsigned");
+        assert_eq!(id.get(), VALUE);
+        assert_eq!(id.to_hex(), "8DBC3479FF705C7C");
+        assert_eq!(PersistentId::from_hex(&id.to_hex()), Ok(id));
+        assert_eq!("8DBC3479FF705C7C".parse(), Ok(id));
+        assert_eq!("0x8dbc3479ff705c7c".parse(), Ok(id));
+        assert_eq!(VALUE.to_string().parse(), Ok(id));
+        assert_eq!(PersistentId::new(10).to_hex(), "000000000000000A");
+    }
+}
@@ -13,16 +13,16 @@ pub struct Deserializer<'de> {
 
 impl<'de> Deserializer<'de> {
     pub fn parse(input: &'de str) -> Result<Option<Self>, Error<'de>> {
-        let input = &input["<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n".len()..];
-        // ^^ fixme so robust
-        // dbg!(input);
         let mut arena = VecNodeArena::new();
         let input = Span::new_root(input);
-        let index = xml::Node::parse(&input, &mut arena).map_err(Error::ParseError)?;
-        let index = if let Some(xml::Read { value: index, .. }) = index { index } else { return Ok(None) };
+        let document = match xml::Document::parse(&input, &mut arena) {
+            Ok(document) => document,
+            Err(xml::error::DocumentParseError::NoRootElement) => return Ok(None),
+            Err(error) => return Err(Error::ParseError(error)),
+        };
         Ok(Some(Self { 
             arena,
-            stack: vec![index]
+            stack: vec![document.root]
         }))
     }
 
@@ -44,6 +44,9 @@ impl<'de> Deserializer<'de> {
                 Node::Comment(comment) => {
                     out.push_str(&format!("<!--{}-->", comment.as_str()));
                 }
+                node @ (Node::Declaration(_) | Node::DocumentType(_) | Node::ProcessingInstruction(_)) => {
+                    out.push_str(node.span().as_str());
+                }
             }
         }
 
@@ -72,7 +75,7 @@ pub enum Error<'a> {
     #[error("expected only one child @ {}", .0.span().start_location())]
     ExpectedOnlyOneChild(xml::Node<'a, NA<'a>>),
     #[error("{0}")]
-    ParseError(xml::error::NodeParseError<'a,  NA<'a>>),
+    ParseError(xml::error::DocumentParseError<'a,  NA<'a>>),
     #[error("cannot decode character: {0}")]
     CharacterReferenceDecodingError(#[from] CharacterEntityDecodingError)
 }
@@ -88,7 +91,10 @@ impl<'de> serde::de::Deserializer<'de> for &mut Deserializer<'de> {
 
     fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
         let out = match self.get_current_node() {
-            Node::Comment(..) => {
+            Node::Comment(..) |
+            Node::Declaration(..) |
+            Node::DocumentType(..) |
+            Node::ProcessingInstruction(..) => {
                 if self.goto_next_sibling().is_none() {
                     visitor.visit_unit()
                 } else {
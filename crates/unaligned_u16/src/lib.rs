@@ -124,6 +124,48 @@ pub mod iter {
             Some(u16)
         }
     }
+
+    /// An iterator over the subslices of an [`UnalignedU16Slice`] separated by a sequence of one or two `u16` elements.
+    ///
+    /// Created by [`UnalignedU16Slice::split`].
+    #[derive(Debug, Clone)]
+    pub struct Split<'a> {
+        remaining: Option<&'a UnalignedU16Slice>,
+        separator: [u16; 2],
+        separator_len: usize,
+        endianness: super::Endianness,
+    }
+    impl<'a> Split<'a> {
+        /// # Panics
+        /// - If the separator is empty or longer than two elements.
+        pub(crate) fn new(slice: &'a UnalignedU16Slice, separator: &[u16], endianness: super::Endianness) -> Self {
+            assert!(matches!(separator.len(), 1 | 2), "separator must be one or two elements long");
+            let mut buffer = [0; 2];
+            buffer[..separator.len()].copy_from_slice(separator);
+            Self {
+                remaining: Some(slice),
+                separator: buffer,
+                separator_len: separator.len(),
+                endianness,
+            }
+        }
+    }
+    impl<'a> Iterator for Split<'a> {
+        type Item = &'a UnalignedU16Slice;
+        fn next(&mut self) -> Option<Self::Item> {
+            let remaining = self.remaining?;
+            let separator = &self.separator[..self.separator_len];
+            if let Some(index) = remaining.find_slice(separator, self.endianness) {
+                let (before, after) = remaining.split_at(index);
+                self.remaining = Some(after.split_at(separator.len()).1);
+                Some(before)
+            } else {
+                self.remaining = None;
+                Some(remaining)
+            }
+        }
+    }
+    impl core::iter::FusedIterator for Split<'_> {}
 }
 
 #[must_use]
@@ -228,6 +270,115 @@ impl<'a> UnalignedU16Slice {
     pub fn iter(&'a self, endianness: Endianness) -> iter::UnalignedU16SliceIterator<'a> {
         iter::UnalignedU16SliceIterator::new(self, endianness)
     }
+
+    /// Copies all elements into `out`, read with the specified endianness.
+    ///
+    /// This copies the bytes wholesale and then byte-swaps them in place if necessary, which is considerably faster than going through [`Self::iter`].
+    ///
+    /// # Panics
+    /// - If `out` is not the same length as this slice.
+    pub fn copy_to(&self, out: &mut [u16], endianness: Endianness) {
+        assert_eq!(out.len(), self.len(), "destination and source slices have different lengths");
+        // SAFETY: The destination has room for exactly `byte_len` bytes, any bit pattern is a valid `u16`, and the two can't overlap as `out` is borrowed mutably.
+        unsafe { core::ptr::copy_nonoverlapping(self.0.as_ptr(), out.as_mut_ptr().cast::<u8>(), self.byte_len()) }
+        if !endianness.is_system() {
+            for unit in out {
+                *unit = unit.swap_bytes();
+            }
+        }
+    }
+
+    /// Appends all elements to the end of `out`, read with the specified endianness.
+    ///
+    /// See [`Self::copy_to`].
+    #[cfg(feature = "alloc")]
+    pub fn decode_into(&self, out: &mut alloc::vec::Vec<u16>, endianness: Endianness) {
+        let start = out.len();
+        out.resize(start + self.len(), 0);
+        self.copy_to(&mut out[start..], endianness);
+    }
+
+    /// Collects all elements into a vector, read with the specified endianness.
+    ///
+    /// See [`Self::copy_to`].
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub fn to_vec(&self, endianness: Endianness) -> alloc::vec::Vec<u16> {
+        let mut vec = alloc::vec::Vec::new();
+        self.decode_into(&mut vec, endianness);
+        vec
+    }
+
+    /// Divides the slice into two at the specified element index.
+    ///
+    /// # Panics
+    /// - If `mid` is greater than the length of the slice.
+    #[must_use]
+    pub const fn split_at(&self, mid: usize) -> (&Self, &Self) {
+        let (left, right) = self.bytes().split_at(mid * 2);
+        unsafe { (Self::new_unchecked(left), Self::new_unchecked(right)) }
+    }
+
+    /// Returns the index of the first element equal to `needle` when read with the specified endianness.
+    #[must_use]
+    pub fn find(&self, needle: u16, endianness: Endianness) -> Option<usize> {
+        let needle = endianness.split_u16(needle);
+        self.bytes().chunks_exact(2).position(|pair| pair == needle)
+    }
+
+    /// Returns the index of the last element equal to `needle` when read with the specified endianness.
+    #[must_use]
+    pub fn rfind(&self, needle: u16, endianness: Endianness) -> Option<usize> {
+        let needle = endianness.split_u16(needle);
+        self.bytes().chunks_exact(2).rposition(|pair| pair == needle)
+    }
+
+    /// Returns the starting index of the first occurrence of `needle` when read with the specified endianness.
+    /// An empty needle matches at index zero.
+    #[must_use]
+    pub fn find_slice(&self, needle: &[u16], endianness: Endianness) -> Option<usize> {
+        let last_start = self.len().checked_sub(needle.len())?;
+        (0..=last_start).find(|start| self.matches_at(*start, needle, endianness))
+    }
+
+    /// Whether `needle` occurs anywhere within the slice when read with the specified endianness.
+    #[must_use]
+    pub fn contains_slice(&self, needle: &[u16], endianness: Endianness) -> bool {
+        self.find_slice(needle, endianness).is_some()
+    }
+
+    /// Whether the slice starts with `prefix` when read with the specified endianness.
+    #[must_use]
+    pub fn starts_with(&self, prefix: &[u16], endianness: Endianness) -> bool {
+        prefix.len() <= self.len() && self.matches_at(0, prefix, endianness)
+    }
+
+    /// Whether the slice starts with the elements yielded by `prefix` when read with the specified endianness.
+    #[must_use]
+    pub fn starts_with_iter(&self, prefix: impl IntoIterator<Item = u16>, endianness: Endianness) -> bool {
+        let mut elements = self.iter(endianness);
+        prefix.into_iter().all(|unit| elements.next() == Some(unit))
+    }
+
+    /// Whether the slice ends with `suffix` when read with the specified endianness.
+    #[must_use]
+    pub fn ends_with(&self, suffix: &[u16], endianness: Endianness) -> bool {
+        self.len().checked_sub(suffix.len()).is_some_and(|start| self.matches_at(start, suffix, endianness))
+    }
+
+    /// Returns an iterator over the subslices separated by elements equal to `separator` when read with the specified endianness.
+    /// The separators themselves are not included.
+    #[must_use]
+    pub fn split(&'a self, separator: u16, endianness: Endianness) -> iter::Split<'a> {
+        iter::Split::new(self, &[separator], endianness)
+    }
+
+    /// Whether the elements starting at `start` are equal to `needle`.
+    /// The caller must ensure `start + needle.len()` is in bounds.
+    fn matches_at(&self, start: usize, needle: &[u16], endianness: Endianness) -> bool {
+        debug_assert!(start + needle.len() <= self.len());
+        needle.iter().enumerate().all(|(offset, unit)| unsafe { self.get_unchecked(start + offset, endianness) } == *unit)
+    }
 }
 impl<'a> TryFrom<&'a [u8]> for &'a UnalignedU16Slice {
     type Error = error::BadByteLength;
@@ -254,13 +405,105 @@ impl<'a> TryFrom<&'a UnalignedU16Slice> for &'a [u16] {
     }
 }
 
+/// A mutable slice of `u16` values that may not be aligned to `u16` boundaries.
+///
+/// Everything that can be done with an [`UnalignedU16Slice`] can be done with this through [`Deref`](core::ops::Deref).
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct UnalignedU16SliceMut([u8]);
+impl UnalignedU16SliceMut {
+    /// Creates a new `UnalignedU16SliceMut` from the provided byte slice.
+    /// Returns an error if the length of the slice is not a multiple of two.
+    ///
+    /// # Errors
+    /// - [`error::BadByteLength`]: The length of the slice is not a multiple of two.
+    pub const fn new(slice: &mut [u8]) -> Result<&mut Self, error::BadByteLength> {
+        if !slice.len().is_multiple_of(2) { return Err(error::BadByteLength) }
+        Ok(unsafe { Self::new_unchecked(slice) })
+    }
+
+    /// # Safety
+    /// - The provided slice must have a length that is a multiple of two.
+    #[must_use]
+    pub const unsafe fn new_unchecked(slice: &mut [u8]) -> &mut Self {
+        unsafe { core::mem::transmute(slice) }
+    }
+
+    /// The underlying byte slice.
+    #[must_use]
+    pub const fn bytes_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+
+    /// Sets the `u16` element at the specified index, written with the specified endianness.
+    ///
+    /// # Panics
+    /// - If the index is out of bounds.
+    pub const fn set(&mut self, index: usize, value: u16, endianness: Endianness) {
+        assert!(index < self.0.len() / 2, "index out of bounds");
+        let [first, second] = endianness.split_u16(value);
+        self.0[index * 2] = first;
+        self.0[index * 2 + 1] = second;
+    }
+
+    /// Copies all elements from `source`, written with the specified endianness.
+    ///
+    /// This is the counterpart of [`UnalignedU16Slice::copy_to`], and is similarly faster than [setting](Self::set) each element.
+    ///
+    /// # Panics
+    /// - If `source` is not the same length as this slice.
+    pub fn copy_from(&mut self, source: &[u16], endianness: Endianness) {
+        assert_eq!(source.len(), self.len(), "destination and source slices have different lengths");
+        self.0.copy_from_slice(u16_slice_as_u8_slice(source));
+        if !endianness.is_system() {
+            self.swap_endianness_in_place();
+        }
+    }
+
+    /// Swaps the bytes of every element, such that what was read with one endianness is now read with the other.
+    pub fn swap_endianness_in_place(&mut self) {
+        for pair in self.0.chunks_exact_mut(2) {
+            pair.swap(0, 1);
+        }
+    }
+
+    /// Divides the slice into two at the specified element index.
+    ///
+    /// # Panics
+    /// - If `mid` is greater than the length of the slice.
+    #[must_use]
+    pub const fn split_at_mut(&mut self, mid: usize) -> (&mut Self, &mut Self) {
+        let (left, right) = self.0.split_at_mut(mid * 2);
+        unsafe { (Self::new_unchecked(left), Self::new_unchecked(right)) }
+    }
+}
+impl core::ops::Deref for UnalignedU16SliceMut {
+    type Target = UnalignedU16Slice;
+    fn deref(&self) -> &Self::Target {
+        unsafe { UnalignedU16Slice::new_unchecked(&self.0) }
+    }
+}
+impl<'a> TryFrom<&'a mut [u8]> for &'a mut UnalignedU16SliceMut {
+    type Error = error::BadByteLength;
+    fn try_from(value: &'a mut [u8]) -> Result<Self, Self::Error> {
+        UnalignedU16SliceMut::new(value)
+    }
+}
+impl<'a> From<&'a mut [u16]> for &'a mut UnalignedU16SliceMut {
+    fn from(value: &'a mut [u16]) -> Self {
+        let len = value.len() * 2;
+        let bytes = unsafe { core::slice::from_raw_parts_mut(value.as_mut_ptr().cast::<u8>(), len) };
+        unsafe { UnalignedU16SliceMut::new_unchecked(bytes) }
+    }
+}
+
 impl core::ops::Index<core::ops::Range<usize>> for UnalignedU16Slice {
     type Output = Self;
     fn index(&self, index: core::ops::Range<usize>) -> &Self::Output {
         let len: usize = self.len();
         let s = index.start;
         let e = index.end;
-        assert!(s < len && e <= len, "index out of bounds");
+        assert!(s <= e && e <= len, "index out of bounds");
         let slice = &self.0[(s * 2)..(e * 2)];
         unsafe { Self::new_unchecked(slice) }
     }
@@ -269,7 +512,7 @@ impl core::ops::Index<core::ops::RangeFrom<usize>> for UnalignedU16Slice {
     type Output = Self;
     fn index(&self, index: core::ops::RangeFrom<usize>) -> &Self::Output {
         let len: usize = self.len();
-        assert!(index.start < len, "index out of bounds");
+        assert!(index.start <= len, "index out of bounds");
         let slice = &self.0[(index.start * 2)..];
         unsafe { Self::new_unchecked(slice) }
     }
@@ -352,5 +595,86 @@ mod tests {
         assert_eq!(unaligned[1..],   *UnalignedU16Slice::new(&slice[2..]).unwrap());
         assert_eq!(unaligned[.. 2],  *UnalignedU16Slice::new(&slice[..=3]).unwrap());
         assert_eq!(unaligned[..=2],  *UnalignedU16Slice::new(&slice[..=5]).unwrap());
+        assert!(unaligned[4..].is_empty());
+        assert!(unaligned[4..4].is_empty());
+    }
+
+    #[test]
+    fn bulk_decoding() {
+        #[expect(clippy::cast_possible_truncation)]
+        let bytes: [u8; 256] = core::array::from_fn(|index| index as u8);
+        // Start at an odd offset so that the elements straddle `u16` alignment.
+        let unaligned = UnalignedU16Slice::new(&bytes[1..255]).unwrap();
+        for endianness in Endianness::VARIANTS {
+            let mut out = [0; 127];
+            unaligned.copy_to(&mut out, endianness);
+            assert!(out.iter().copied().eq(unaligned.iter(endianness)));
+
+            #[cfg(feature = "alloc")]
+            {
+                let mut vec = alloc::vec![0xFFFF];
+                unaligned.decode_into(&mut vec, endianness);
+                assert_eq!(vec[0], 0xFFFF);
+                assert_eq!(vec[1..], out);
+                assert_eq!(unaligned.to_vec(endianness), out);
+            }
+        }
+    }
+
+    #[test]
+    fn mutation() {
+        let mut bytes = [0; 7];
+        // Start at an odd offset so that the elements straddle `u16` alignment.
+        let unaligned = UnalignedU16SliceMut::new(&mut bytes[1..]).unwrap();
+        unaligned.set(0, 0x0102, Endianness::Little);
+        unaligned.set(2, 0x0506, Endianness::Big);
+        assert_eq!(unaligned.bytes(), [0x02, 0x01, 0x00, 0x00, 0x05, 0x06]);
+        assert_eq!(unaligned.get(2, Endianness::Big), Some(0x0506));
+
+        unaligned.swap_endianness_in_place();
+        assert_eq!(unaligned.bytes(), [0x01, 0x02, 0x00, 0x00, 0x06, 0x05]);
+        assert_eq!(unaligned.get(0, Endianness::Big), Some(0x0102));
+
+        for endianness in Endianness::VARIANTS {
+            unaligned.copy_from(&[0x0A0B, 0x0C0D, 0x0E0F], endianness);
+            assert!(unaligned.iter(endianness).eq([0x0A0B, 0x0C0D, 0x0E0F]));
+        }
+
+        let (left, right) = unaligned.split_at_mut(1);
+        left.set(0, 0, Endianness::Little);
+        right.set(1, 0, Endianness::Little);
+        assert_eq!(unaligned.get(0, Endianness::Little), Some(0));
+        assert_eq!(unaligned.get(2, Endianness::Little), Some(0));
+        assert!(UnalignedU16SliceMut::new(&mut bytes[..3]).is_err());
+    }
+
+    #[test]
+    #[should_panic = "index out of bounds"]
+    fn setting_out_of_bounds() {
+        let mut bytes = [0; 2];
+        UnalignedU16SliceMut::new(&mut bytes).unwrap().set(1, 0, Endianness::Little);
+    }
+
+    #[test]
+    fn search() {
+        let slice = [0x01, 0x00, 0x2C, 0x00, 0x02, 0x00, 0x2C, 0x00];
+        let unaligned = UnalignedU16Slice::new(&slice).unwrap();
+        assert_eq!(unaligned.find(0x2C, Endianness::Little), Some(1));
+        assert_eq!(unaligned.rfind(0x2C, Endianness::Little), Some(3));
+        assert_eq!(unaligned.find(0x2C00, Endianness::Little), None);
+        assert_eq!(unaligned.find(0x2C00, Endianness::Big), Some(1));
+        assert_eq!(unaligned.find_slice(&[0x2C, 0x02], Endianness::Little), Some(1));
+        assert_eq!(unaligned.find_slice(&[], Endianness::Little), Some(0));
+        assert!(!unaligned.contains_slice(&[0x02, 0x01], Endianness::Little));
+        assert!(unaligned.starts_with(&[0x01, 0x2C], Endianness::Little));
+        assert!(!unaligned.starts_with(&[0x01, 0x2C, 0x02, 0x2C, 0x03], Endianness::Little));
+        assert!(unaligned.ends_with(&[0x02, 0x2C], Endianness::Little));
+        assert!(unaligned.ends_with(&[], Endianness::Little));
+
+        let mut split = unaligned.split(0x2C, Endianness::Little);
+        assert_eq!(split.next().map(UnalignedU16Slice::bytes), Some(&[0x01, 0x00][..]));
+        assert_eq!(split.next().map(UnalignedU16Slice::bytes), Some(&[0x02, 0x00][..]));
+        assert_eq!(split.next().map(UnalignedU16Slice::bytes), Some(&[][..]));
+        assert_eq!(split.next(), None);
     }
 }
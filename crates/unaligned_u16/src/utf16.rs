@@ -1,6 +1,6 @@
 use core::cmp::Ordering;
 
-use crate::{UnalignedU16Slice, endian::{Endian, Endianness}};
+use crate::{UnalignedU16Slice, endian::{Endian, Endianness, LittleEndian, BigEndian}, iter::UnalignedU16SliceIterator};
 
 impl Endianness {
     /// Determines the endianness from a byte order mark (BOM) at the start of a byte slice.
@@ -14,13 +14,79 @@ impl Endianness {
     }
 }
 
+/// A surrogate code unit which isn't part of a valid surrogate pair.
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("unpaired surrogate {unit:#06x} at index {index}")]
+pub struct UnpairedSurrogateError {
+    /// The index of the offending code unit (not byte) within the slice.
+    pub index: usize,
+    pub unit: u16,
+}
+
 /// Validation errors that can occur when constructing the [`Utf16Str`] data abstraction.
 #[derive(thiserror::Error, Debug)]
 pub enum InvalidUtf16Error {
     #[error("{0}")]
     BadByteLength(#[from] crate::error::BadByteLength),
     #[error("{0}")]
-    IncorrectEncoding(#[from] core::char::DecodeUtf16Error),
+    IncorrectEncoding(#[from] UnpairedSurrogateError),
+}
+
+impl UnalignedU16Slice {
+    /// The byte order mark, as a code unit.
+    pub const BOM: u16 = 0xFEFF;
+
+    /// Creates a new `UnalignedU16Slice` from the provided byte slice, with any leading byte order mark stripped.
+    /// Returns the endianness indicated by the byte order mark, or `fallback` if there wasn't one.
+    ///
+    /// # Errors
+    /// - [`crate::error::BadByteLength`]: The length of the slice is not a multiple of two.
+    pub const fn new_detecting_endianness(slice: &[u8], fallback: Endianness) -> Result<(&Self, Endianness), crate::error::BadByteLength> {
+        let (slice, endianness) = match Endianness::from_bom(slice) {
+            Some(endianness) => (slice.split_at(2).1, endianness),
+            None => (slice, fallback),
+        };
+        match Self::new(slice) {
+            Ok(slice) => Ok((slice, endianness)),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Returns an iterator over the characters of the slice, interpreted as UTF-16 with the specified endianness.
+    /// Unpaired surrogates are yielded as errors.
+    #[must_use]
+    pub fn chars(&self, endianness: Endianness) -> core::char::DecodeUtf16<UnalignedU16SliceIterator<'_>> {
+        char::decode_utf16(self.iter(endianness))
+    }
+
+    /// Returns an iterator over the characters of the slice, interpreted as UTF-16 with the specified endianness.
+    /// Unpaired surrogates are replaced with [`char::REPLACEMENT_CHARACTER`].
+    pub fn chars_lossy(&self, endianness: Endianness) -> impl Iterator<Item = char> + '_ {
+        self.chars(endianness).map(|result| result.unwrap_or(char::REPLACEMENT_CHARACTER))
+    }
+
+    /// Checks whether the slice is valid UTF-16 with the specified endianness.
+    ///
+    /// # Errors
+    /// - [`UnpairedSurrogateError`] for the first unpaired surrogate in the slice.
+    pub fn validate_utf16(&self, endianness: Endianness) -> Result<(), UnpairedSurrogateError> {
+        let mut index = 0;
+        for result in self.chars(endianness) {
+            match result {
+                Ok(char) => index += char.len_utf16(),
+                Err(error) => return Err(UnpairedSurrogateError { index, unit: error.unpaired_surrogate() }),
+            }
+        }
+        Ok(())
+    }
+
+    /// Converts the slice, interpreted as UTF-16 with the specified endianness, into a string.
+    /// Unpaired surrogates are replaced with [`char::REPLACEMENT_CHARACTER`].
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub fn to_string_lossy(&self, endianness: Endianness) -> alloc::string::String {
+        self.chars_lossy(endianness).collect()
+    }
 }
 
 /// A UTF-16 encoded string with unaligned u16 data.
@@ -29,6 +95,7 @@ pub enum InvalidUtf16Error {
 /// 
 /// The endianness of the UTF-16 string is determined by the type parameter `T`.
 /// Byte order markers are not handled or respected; the caller is responsible for ensuring the correct endianness.
+/// Use [`DetectedUtf16Str`] if the endianness should instead be determined by a byte order mark.
 #[allow(private_bounds)]
 #[repr(transparent)]
 pub struct Utf16Str<T: Endian> {
@@ -45,10 +112,21 @@ impl<'a, T: Endian> Utf16Str<T> {
     /// - [`InvalidUtf16Error`] if the contents of the slice are not valid UTF-16.
     pub fn new(slice: impl TryInto<&'a UnalignedU16Slice, Error = crate::error::BadByteLength>) -> Result<&'a Self, InvalidUtf16Error> {
         let slice: &UnalignedU16Slice = slice.try_into()?;
-        for result in char::decode_utf16(slice.iter(T::to_variant())) { result?; }
+        slice.validate_utf16(T::to_variant())?;
         Ok(unsafe { Self::new_unchecked(slice.bytes()) })
     }
 
+    /// Checks whether the provided byte slice could be used to construct a UTF-16 string, without constructing it.
+    ///
+    /// # Errors
+    /// - [`crate::error::BadByteLength`] if the length of the slice is not a multiple of two.
+    /// - [`InvalidUtf16Error`] with the position of the first unpaired surrogate if the contents of the slice are not valid UTF-16.
+    pub fn validate(slice: impl TryInto<&'a UnalignedU16Slice, Error = crate::error::BadByteLength>) -> Result<(), InvalidUtf16Error> {
+        let slice: &UnalignedU16Slice = slice.try_into()?;
+        slice.validate_utf16(T::to_variant())?;
+        Ok(())
+    }
+
     /// # Safety
     /// - The provided slice must have a length that is a multiple of two.
     /// - The contents of the slice must be valid UTF-16.
@@ -87,11 +165,31 @@ impl<'a, T: Endian> Utf16Str<T> {
         prefix.is_prefix_of(self)
     }
 
+    /// Whether this string ends with the given code units.
+    #[must_use]
+    pub fn ends_with(&self, suffix: &[u16]) -> bool {
+        self.slice.ends_with(suffix, T::to_variant())
+    }
+
     /// Whether this string contains the given substring.
     #[expect(clippy::needless_pass_by_value, reason = "this trait can be implemented for borrows")]
     pub fn contains(&self, substring: impl traits::contains::SubstringChecker) -> bool {
         substring.is_substring_of(self)
     }
+
+    /// Returns the code unit index of the first occurrence of the given character.
+    #[must_use]
+    pub fn find(&self, char: char) -> Option<usize> {
+        self.slice.find_slice(char.encode_utf16(&mut [0; 2]), T::to_variant())
+    }
+
+    /// Returns an iterator over the substrings separated by the given character.
+    /// The separators themselves are not included.
+    pub fn split(&'a self, separator: char) -> impl Iterator<Item = &'a Self> + 'a {
+        crate::iter::Split::new(&self.slice, separator.encode_utf16(&mut [0; 2]), T::to_variant())
+            // SAFETY: Splitting around a whole character can't produce an unpaired surrogate.
+            .map(|slice| unsafe { Self::new_unchecked(slice.bytes()) })
+    }
     
     /// How many bytes this string would take up if encoded as UTF-8.
     #[must_use]
@@ -277,6 +375,70 @@ impl<T: Endian> From<&Utf16Str<T>> for alloc::string::String {
     }
 }
 
+/// A UTF-16 string whose endianness was determined at runtime from its byte order mark, which is not included in the string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DetectedUtf16Str<'a> {
+    Little(&'a Utf16Str<LittleEndian>),
+    Big(&'a Utf16Str<BigEndian>),
+}
+impl<'a> DetectedUtf16Str<'a> {
+    /// Creates a new UTF-16 string from the provided byte slice, with the endianness indicated by a leading byte order mark.
+    /// If there isn't a byte order mark, `fallback` is used.
+    ///
+    /// # Errors
+    /// - [`crate::error::BadByteLength`] if the length of the slice is not a multiple of two.
+    /// - [`InvalidUtf16Error`] if the contents of the slice are not valid UTF-16.
+    pub fn new(slice: &'a [u8], fallback: Endianness) -> Result<Self, InvalidUtf16Error> {
+        let (slice, endianness) = UnalignedU16Slice::new_detecting_endianness(slice, fallback)?;
+        Ok(match endianness {
+            Endianness::Little => Self::Little(Utf16Str::new(slice.bytes())?),
+            Endianness::Big => Self::Big(Utf16Str::new(slice.bytes())?),
+        })
+    }
+
+    #[must_use]
+    pub const fn endianness(&self) -> Endianness {
+        match self {
+            Self::Little(_) => Endianness::Little,
+            Self::Big(_) => Endianness::Big,
+        }
+    }
+
+    /// Returns an [`UnalignedU16Slice`] of this data, excluding the byte order mark.
+    #[must_use]
+    pub const fn unaligned_shorts(&self) -> &'a UnalignedU16Slice {
+        match self {
+            Self::Little(str) => str.unaligned_shorts(),
+            Self::Big(str) => str.unaligned_shorts(),
+        }
+    }
+
+    /// Returns an iterator over the characters of the string.
+    #[must_use]
+    pub fn chars(&self) -> iter::UnalignedUtf16StrCharacterIterator<'a> {
+        match self {
+            Self::Little(str) => str.chars(),
+            Self::Big(str) => str.chars(),
+        }
+    }
+}
+impl core::fmt::Display for DetectedUtf16Str<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Little(str) => core::fmt::Display::fmt(str, f),
+            Self::Big(str) => core::fmt::Display::fmt(str, f),
+        }
+    }
+}
+impl PartialEq<str> for DetectedUtf16Str<'_> {
+    fn eq(&self, other: &str) -> bool {
+        match self {
+            Self::Little(str) => *str == other,
+            Self::Big(str) => *str == other,
+        }
+    }
+}
+
 impl<T: Endian> PartialEq<Utf16Str<T>> for str {
     fn eq(&self, other: &Utf16Str<T>) -> bool {
         other == self
@@ -398,7 +560,30 @@ pub mod traits {
 
         impl<T: Endian> PrefixChecker for Utf16Str<T> {
             fn is_prefix_of<U: Endian>(&self, against: &Utf16Str<U>) -> bool {
-                self.bytes().starts_with(against.bytes())
+                if T::IS_LITTLE == U::IS_LITTLE {
+                    against.bytes().starts_with(self.bytes())
+                } else {
+                    against.unaligned_shorts().starts_with_iter(self.unaligned_shorts().iter(T::to_variant()), U::to_variant())
+                }
+            }
+        }
+
+        impl<T: Endian> PrefixChecker for &Utf16Str<T> {
+            fn is_prefix_of<U: Endian>(&self, against: &Utf16Str<U>) -> bool {
+                (**self).is_prefix_of(against)
+            }
+        }
+
+        /// Checks against the raw code units.
+        impl PrefixChecker for [u16] {
+            fn is_prefix_of<T: Endian>(&self, against: &Utf16Str<T>) -> bool {
+                against.unaligned_shorts().starts_with(self, T::to_variant())
+            }
+        }
+
+        impl PrefixChecker for &[u16] {
+            fn is_prefix_of<T: Endian>(&self, against: &Utf16Str<T>) -> bool {
+                (**self).is_prefix_of(against)
             }
         }
     }
@@ -715,6 +900,63 @@ mod tests {
         );
     }
 
+    #[test]
+    fn validation() {
+        use crate::{UnalignedU16Slice, endian::{Endianness, LittleEndian}};
+        use super::{Utf16Str, InvalidUtf16Error, UnpairedSurrogateError};
+
+        let bytes = [0x68, 0x00, 0x3D, 0xD8, 0x43, 0xDE, 0x69, 0x00, 0x00, 0xDC, 0x21, 0x00]; // "h🙃i", lone low surrogate, "!"
+        let slice = UnalignedU16Slice::new(&bytes).unwrap();
+        assert_eq!(slice.validate_utf16(Endianness::Little), Err(UnpairedSurrogateError { index: 4, unit: 0xDC00 }));
+        assert!(matches!(Utf16Str::<LittleEndian>::validate(&bytes[..]), Err(InvalidUtf16Error::IncorrectEncoding(UnpairedSurrogateError { index: 4, .. }))));
+        assert!(Utf16Str::<LittleEndian>::new(&bytes[..]).is_err());
+        assert!(Utf16Str::<LittleEndian>::validate(&bytes[..8]).is_ok());
+        assert!(matches!(Utf16Str::<LittleEndian>::validate(&bytes[..7]), Err(InvalidUtf16Error::BadByteLength(_))));
+
+        let truncated = UnalignedU16Slice::new(&bytes[..4]).unwrap(); // "h", then a high surrogate without its pair
+        assert_eq!(truncated.validate_utf16(Endianness::Little), Err(UnpairedSurrogateError { index: 1, unit: 0xD83D }));
+        assert!(slice.chars_lossy(Endianness::Little).eq("h🙃i\u{FFFD}!".chars()));
+        assert_eq!(slice.chars(Endianness::Little).filter(Result::is_err).count(), 1);
+
+        #[cfg(feature = "alloc")]
+        assert_eq!(truncated.to_string_lossy(Endianness::Little), "h\u{FFFD}");
+    }
+
+    #[test]
+    fn byte_order_mark() {
+        use crate::{UnalignedU16Slice, endian::Endianness};
+        use super::DetectedUtf16Str;
+
+        let (slice, endianness) = UnalignedU16Slice::new_detecting_endianness(&[0xFE, 0xFF, 0x00, 0x68], Endianness::Little).unwrap();
+        assert_eq!((slice.bytes(), endianness), (&[0x00, 0x68][..], Endianness::Big));
+        let (slice, endianness) = UnalignedU16Slice::new_detecting_endianness(&[0x68, 0x00], Endianness::Little).unwrap();
+        assert_eq!((slice.bytes(), endianness), (&[0x68, 0x00][..], Endianness::Little));
+        assert!(UnalignedU16Slice::new_detecting_endianness(&[0xFF, 0xFE, 0x68], Endianness::Big).is_err());
+
+        let little = DetectedUtf16Str::new(&[0xFF, 0xFE, 0x68, 0x00, 0x69, 0x00], Endianness::Big).unwrap();
+        let big = DetectedUtf16Str::new(&[0xFE, 0xFF, 0x00, 0x68, 0x00, 0x69], Endianness::Little).unwrap();
+        assert_eq!(little.endianness(), Endianness::Little);
+        assert_eq!(big.endianness(), Endianness::Big);
+        assert!(little == *"hi" && big == *"hi");
+        assert!(matches!(DetectedUtf16Str::new(&[0x00, 0x68], Endianness::Big), Ok(DetectedUtf16Str::Big(str)) if str == "h"));
+        assert!(DetectedUtf16Str::new(&[0xFF, 0xFE, 0x00, 0xDC], Endianness::Big).is_err());
+    }
+
+    #[test]
+    fn search() {
+        let str = utf16!(LE, "a,🙃,,b");
+        assert_eq!(str.find(','), Some(1));
+        assert_eq!(str.find('🙃'), Some(2));
+        assert_eq!(str.find('z'), None);
+        assert!(str.split(',').eq(["a", "🙃", "", "b"]));
+        assert!(str.split('🙃').eq(["a,", ",,b"]));
+        assert!(utf16!(LE, "").split(',').eq([""]));
+        assert!(str.starts_with(&[u16::from(b'a'), u16::from(b',')][..]));
+        assert!(!str.ends_with(&[u16::from(b',')]) && str.ends_with(&[u16::from(b'b')]));
+        assert!(str.starts_with(utf16!(BE, "a,")) && str.starts_with(utf16!(LE, "a,")));
+        assert!(!utf16!(LE, "a,").starts_with(str));
+    }
+
     #[test]
     fn contains() {
         let utf16_str = utf16!(sys, "hello, 👨‍👩‍👧‍👦!");
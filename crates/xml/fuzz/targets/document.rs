@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use xml::{arena::DefaultArena, plist::PlistValue, span::Span, Document, Limits};
+
+fuzz_target!(|input: &str| {
+    let mut arena = DefaultArena::default();
+    if let Ok(document) = Document::parse_with_limits(&Span::new_root(input), &mut arena, &Limits::UNTRUSTED) {
+        let _ = PlistValue::from_root(document.root(&arena), &arena);
+    }
+});
@@ -1,3 +1,7 @@
+//! Storage for parsed nodes, which refer to one another through references handed out by the arena.
+//!
+//! [`SlabArena`](slab::SlabArena) is the one to use unless there's reason not to; [`VecNodeArena`](vec::VecNodeArena)
+//! additionally allows nodes to be taken out of it (at the cost of a cell around each of them).
 use crate::Node;
 
 use core::fmt::Debug;
@@ -19,12 +23,145 @@ pub trait NodeArena<'a> where Self: Sized {
     type NodeReferenceList: NodeReferenceCollection<'a, NodeReference = Self::NodeReference>;
     fn add(&mut self, node: Node<'a, Self>) -> Result<Self::NodeReference, Self::Error>;
     fn len(&self) -> usize;
-    fn get(&self, index: &Self::NodeReference) -> &super::Node<'a, Self>;
+    /// Returns the node with the given reference, or `None` if it doesn't belong to this arena (or was taken out of it).
+    fn try_get(&self, index: &Self::NodeReference) -> Option<&super::Node<'a, Self>>;
+    /// Returns the node with the given reference, panicking if it doesn't belong to this arena (or was taken out of it).
+    fn get(&self, index: &Self::NodeReference) -> &super::Node<'a, Self> {
+        self.try_get(index).expect("invalid reference")
+    }
     fn is_empty(&self) -> bool {
         self.len() == 0
     }
 }
 
+/// The arena to use when there isn't reason to use another.
+pub type DefaultArena<'a> = slab::SlabArena<'a>;
+
+pub mod slab {
+    use super::NodeArena;
+
+    /// A reference to a node within a [`SlabArena`].
+    ///
+    /// It's only meaningful to the arena it was handed out by; the nodes of an arena are never removed, so it stays valid for as long as that is around.
+    #[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
+    pub struct SlabIndex(u32);
+    impl SlabIndex {
+        /// The position of the node within the arena, which is the order it was added in.
+        pub fn position(self) -> usize {
+            self.0 as usize
+        }
+    }
+
+    #[derive(thiserror::Error, Debug, PartialEq, Eq)]
+    #[error("arena is full; it can only hold {} nodes", u32::MAX)]
+    pub struct ArenaFull;
+
+    impl super::NodeReferenceCollection<'_> for Vec<SlabIndex> {
+        type Error = core::convert::Infallible;
+        type NodeReference = SlabIndex;
+        fn add(&mut self, reference: Self::NodeReference) -> Result<(), Self::Error> {
+            self.push(reference);
+            Ok(())
+        }
+        fn len(&self) -> usize {
+            self.len()
+        }
+        fn iter(&self) -> Box<dyn Iterator<Item = &Self::NodeReference> + '_> {
+            Box::new(self[..].iter())
+        }
+        fn is_empty(&self) -> bool {
+            self.is_empty()
+        }
+    }
+
+    /// An arena which keeps its nodes next to one another, in the order that they were added.
+    #[derive(PartialEq, Debug, Default)]
+    pub struct SlabArena<'a>(Vec<super::Node<'a, SlabArena<'a>>>);
+    impl<'a> super::NodeArena<'a> for SlabArena<'a> {
+        type Error = ArenaFull;
+        type NodeReference = SlabIndex;
+        type NodeReferenceList = Vec<Self::NodeReference>;
+        fn add(&mut self, node: crate::Node<'a, Self>) -> Result<Self::NodeReference, Self::Error> {
+            let index = SlabIndex(u32::try_from(self.0.len()).map_err(|_| ArenaFull)?);
+            self.0.push(node);
+            Ok(index)
+        }
+        fn len(&self) -> usize {
+            self.0.len()
+        }
+        fn try_get(&self, index: &Self::NodeReference) -> Option<&super::Node<'a, Self>> {
+            self.0.get(index.position())
+        }
+        fn is_empty(&self) -> bool {
+            self.0.is_empty()
+        }
+    }
+    impl<'a> SlabArena<'a> {
+        pub const fn new() -> Self {
+            Self(Vec::new())
+        }
+
+        pub fn with_capacity(capacity: usize) -> Self {
+            Self(Vec::with_capacity(capacity))
+        }
+
+        pub fn capacity(&self) -> usize {
+            self.0.capacity()
+        }
+
+        /// Frees the memory that was set aside for nodes which were never added, such as once parsing is done.
+        pub fn shrink_to_fit(&mut self) {
+            self.0.shrink_to_fit();
+        }
+
+        pub fn try_get_mut(&mut self, index: &SlabIndex) -> Option<&mut super::Node<'a, Self>> {
+            self.0.get_mut(index.position())
+        }
+
+        /// Iterates over every node in the arena, in the order they were added, alongside their references.
+        ///
+        /// A node is added once it's been fully parsed, so children come before their parents.
+        pub fn iter(&self) -> impl ExactSizeIterator<Item = (SlabIndex, &super::Node<'a, Self>)> {
+            self.0.iter().enumerate().map(|(index, node)| (SlabIndex(index as u32), node))
+        }
+    }
+    impl<'a> core::ops::Index<SlabIndex> for SlabArena<'a> {
+        type Output = super::Node<'a, Self>;
+        fn index(&self, index: SlabIndex) -> &Self::Output {
+            self.get(&index)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::{SlabArena, SlabIndex, NodeArena};
+        use crate::{span::Span, Document, Node, Read};
+
+        #[test]
+        fn references() {
+            let mut arena = SlabArena::with_capacity(64);
+            let Read { value: root, .. } = Node::parse(&Span::new_root("<a><b/>text</a>"), &mut arena).unwrap().unwrap();
+            assert_eq!(arena[root].as_element().unwrap().tag_name(), "a");
+            assert!(arena.try_get(&SlabIndex(u32::try_from(arena.len()).unwrap())).is_none());
+
+            assert_eq!(arena.iter().len(), 3);
+            assert!(arena.iter().all(|(index, node)| arena.get(&index) == node));
+            assert_eq!(arena.iter().last().map(|(index, _)| index), Some(root));
+
+            arena.shrink_to_fit();
+            assert_eq!(arena.capacity(), arena.len());
+        }
+
+        #[test]
+        fn documents() {
+            let mut arena = SlabArena::new();
+            let document = Document::parse(&Span::new_root("<?xml version=\"1.0\"?><plist><dict/></plist>"), &mut arena).unwrap();
+            assert_eq!(document.root(&arena).tag_name(), "plist");
+            assert!(document.declaration(&arena).is_some());
+        }
+    }
+}
+
 pub mod vec {
     use std::{cell::{Cell, RefCell, UnsafeCell}, ops::Deref};
 
@@ -65,8 +202,8 @@ pub mod vec {
         fn len(&self) -> usize {
             self.0.len()
         }
-        fn get(&self, index: &Self::NodeReference) -> &super::Node<'a, Self> {
-            unsafe { &* self.0.get(index.0).expect("invalid reference").as_ptr() }.as_ref().expect("taken")
+        fn try_get(&self, index: &Self::NodeReference) -> Option<&super::Node<'a, Self>> {
+            unsafe { &* self.0.get(index.0)?.as_ptr() }.as_ref()
         }
         fn is_empty(&self) -> bool {
             self.0.is_empty()
@@ -88,7 +225,11 @@ pub mod vec {
         }
 
         pub fn take(&self, index: &<VecNodeArena<'a> as super::NodeArena::<'a>>::NodeReference) -> super::Node<'a, Self> {
-            self.0.get(index.0).expect("invalid reference").take().expect("already taken")
+            self.try_take(index).expect("invalid reference or already taken")
+        }
+        /// Takes the node out of the arena, or returns `None` if it doesn't belong to it or was already taken.
+        pub fn try_take(&self, index: &<VecNodeArena<'a> as super::NodeArena::<'a>>::NodeReference) -> Option<super::Node<'a, Self>> {
+            self.0.get(index.0)?.take()
         }
         pub fn replace(&self, index: &<VecNodeArena<'a> as super::NodeArena::<'a>>::NodeReference, node: super::Node<'a, Self>) -> Option<super::Node<'a, Self>> {
             self.0.get(index.0).expect("invalid reference").replace(Some(node))
@@ -102,4 +243,22 @@ pub mod vec {
             Self::new()
         }
     }
+
+    #[cfg(test)]
+    mod tests {
+        use super::{NodeIndex, VecNodeArena, NodeArena};
+        use crate::{span::Span, Node, Read};
+
+        #[test]
+        fn missing_nodes() {
+            let mut arena = VecNodeArena::default();
+            let Read { value: root, .. } = Node::parse(&Span::new_root("<a/>"), &mut arena).unwrap().unwrap();
+            assert!(arena.try_get(&root).is_some());
+            assert!(arena.try_get(&NodeIndex(arena.len())).is_none());
+
+            assert!(arena.try_take(&root).is_some());
+            assert!(arena.try_take(&root).is_none());
+            assert!(arena.try_get(&root).is_none());
+        }
+    }
 }
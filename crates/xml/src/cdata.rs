@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 use crate::MaybeOwnedString;
 
 pub mod error {
@@ -60,6 +62,18 @@ pub mod error {
 
 use error::*;
 
+/// How references to entities other than the five predefined ones (e.x. `&nbsp;`) are handled when decoding.
+///
+/// Document type definitions aren't supported, so such entities can never be resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
+pub enum UnknownEntityPolicy {
+    /// Fail with [`CharacterEntityDecodingError::UnknownEntity`].
+    #[default]
+    Error,
+    /// Leave the reference in the output as-is.
+    PassThrough,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 enum CharacterEntity {
     QuotationMark,
@@ -74,8 +88,8 @@ impl CharacterEntity {
             Self::QuotationMark => '"',
             Self::Ampersand => '&',
             Self::Apostrophe => '\'',
-            Self::LessThan => '>',
-            Self::GreaterThan => '<'
+            Self::LessThan => '<',
+            Self::GreaterThan => '>'
         }
     }
     pub const fn to_str(self) -> &'static str {
@@ -83,8 +97,8 @@ impl CharacterEntity {
             Self::QuotationMark => "\"",
             Self::Ampersand => "&",
             Self::Apostrophe => "'",
-            Self::LessThan => ">",
-            Self::GreaterThan => "<",
+            Self::LessThan => "<",
+            Self::GreaterThan => ">",
         }
     }
 }
@@ -169,10 +183,14 @@ struct EscapeInfo {
 struct EscapeIterator<'a> {
     str: &'a str,
     pos: usize,
+    policy: UnknownEntityPolicy,
 }
 impl<'a> EscapeIterator<'a> {
     const fn new(str: &'a str) -> Self {
-        Self { str, pos: 0 }
+        Self::with_policy(str, UnknownEntityPolicy::Error)
+    }
+    const fn with_policy(str: &'a str, policy: UnknownEntityPolicy) -> Self {
+        Self { str, pos: 0, policy }
     }
 }
 impl Iterator for EscapeIterator<'_> {
@@ -192,19 +210,28 @@ impl Iterator for EscapeIterator<'_> {
 
         let slice = &self.str[ampersand + '&'.len_utf8()..semicolon];
 
-        let escaped = if slice.starts_with('#') {
-            let hex = slice.as_bytes()[1] == b'x';
-            let radix = if hex { 16 } else { 10 };
-            let slice = &slice[1 + if hex { 1 } else { 0 }..];
-            let codepoint = propagate_error_presence!(u32::from_str_radix(slice, radix));
-            let char = propagate_error_presence!(char::from_u32(codepoint).ok_or(CharacterEntityDecodingError::InvalidCharacter));
+        let escaped = if let Some(numeric) = slice.strip_prefix('#') {
+            let (digits, radix) = match numeric.strip_prefix('x') {
+                Some(digits) => (digits, 16),
+                None => (numeric, 10),
+            };
+            // `from_str_radix` would otherwise permit a leading sign.
+            if digits.starts_with(['+', '-']) { return Some(Err(CharacterEntityDecodingError::InvalidCharacter)) }
+            let codepoint = propagate_error_presence!(u32::from_str_radix(digits, radix));
+            let char = propagate_error_presence!(char::from_u32(codepoint).filter(|char| *char != '\0').ok_or(CharacterEntityDecodingError::InvalidCharacter));
             Escape::Codepoint(char)
         } else {
-            let entity = propagate_error_presence!(CharacterEntity::try_from(slice));
-            Escape::Entity(entity)
+            match CharacterEntity::try_from(slice) {
+                Ok(entity) => Escape::Entity(entity),
+                Err(_) if self.policy == UnknownEntityPolicy::PassThrough => {
+                    self.pos = semicolon;
+                    return self.next()
+                },
+                Err(error) => return Some(Err(error.into())),
+            }
         };
 
-        let length = semicolon - ampersand + 1;;
+        let length = semicolon - ampersand + 1;
         self.pos = semicolon;
         Some(Ok(EscapeInfo {
             character: escaped,
@@ -344,15 +371,15 @@ impl<'a> XmlCharacterDataWithEscaping<'a> {
         EscapeIterator::new(value)
     }
     
-    fn unescape(value: &'a str) -> Result<String, CharacterEntityDecodingError> {
+    fn unescape(value: &'a str, policy: UnknownEntityPolicy) -> Result<String, CharacterEntityDecodingError> {
         let mut out = String::with_capacity(value.len()); // approx
         let mut ended = 0;
 
-        for escape in Self::escapes(value) {
+        for escape in EscapeIterator::with_policy(value, policy) {
             let escape = escape?;
             out.push_str(&value[ended..escape.position]);
             out.push(escape.character.into());
-            ended = escape.position + escape.length - 1;
+            ended = escape.position + escape.length;
         }
 
         out.push_str(&value[ended..]);
@@ -367,7 +394,16 @@ impl<'a> XmlCharacterDataWithEscaping<'a> {
     }
 
     pub fn get(&self) -> Result<&str, &CharacterEntityDecodingError> {
-        self.unescaped.get_or_init(|| Self::unescape(self.raw)).as_ref().map(String::as_str)
+        self.unescaped.get_or_init(|| Self::unescape(self.raw, UnknownEntityPolicy::Error)).as_ref().map(String::as_str)
+    }
+
+    /// Like [`Self::get`], but with control over how unknown entities are handled.
+    /// Only the default policy's result is cached.
+    pub fn get_with_policy(&self, policy: UnknownEntityPolicy) -> Result<Cow<'_, str>, CharacterEntityDecodingError> {
+        match policy {
+            UnknownEntityPolicy::Error => self.get().map(Cow::Borrowed).map_err(Clone::clone),
+            UnknownEntityPolicy::PassThrough => Self::unescape(self.raw, policy).map(Cow::Owned),
+        }
     }
 
     pub const fn get_unescaped(&self) -> &str {
@@ -375,7 +411,7 @@ impl<'a> XmlCharacterDataWithEscaping<'a> {
     }
 
     pub fn into_string(mut self) -> Result<String, CharacterEntityDecodingError> {
-        self.unescaped.take().unwrap_or_else(|| Self::unescape(self.raw))
+        self.unescaped.take().unwrap_or_else(|| Self::unescape(self.raw, UnknownEntityPolicy::Error))
     }
 
     pub fn did_unescape(&self) -> bool {
@@ -427,6 +463,13 @@ impl<'a> XmlCharacterData<'a> {
             Self::WithEntities(inner) => inner.get()
         }
     }
+    /// Like [`Self::get`], but with control over how unknown entities are handled.
+    pub fn get_with_policy(&self, policy: UnknownEntityPolicy) -> Result<Cow<'_, str>, CharacterEntityDecodingError> {
+        match self {
+            Self::Plain(text) => Ok(Cow::Borrowed(*text)),
+            Self::WithEntities(inner) => inner.get_with_policy(policy)
+        }
+    }
     pub fn into_maybe_owned(self) -> Result<MaybeOwnedString<'a>, CharacterEntityDecodingError> {
         Ok(match self {
             Self::Plain(text) => MaybeOwnedString::Borrowed(text),
@@ -493,3 +536,26 @@ impl Ord for XmlCharacterData<'_> {
     }
 }
 
+#[cfg(test)]
+mod decoding {
+    use super::*;
+
+    #[test]
+    fn references() {
+        let data = XmlCharacterData::maybe_escaping("&lt;&gt;&amp;&quot;&apos; &#169;&#xA9;&#x1F600;");
+        assert_eq!(data.get(), Ok("<>&\"' ©©😀"));
+        assert!(matches!(XmlCharacterData::maybe_escaping("&#;").get(), Err(CharacterEntityDecodingError::InvalidForm(_))));
+        assert!(matches!(XmlCharacterData::maybe_escaping("&#x;").get(), Err(CharacterEntityDecodingError::InvalidForm(_))));
+        assert_eq!(XmlCharacterData::maybe_escaping("&#0;").get(), Err(&CharacterEntityDecodingError::InvalidCharacter));
+        assert_eq!(XmlCharacterData::maybe_escaping("&#-1;").get(), Err(&CharacterEntityDecodingError::InvalidCharacter));
+        assert_eq!(XmlCharacterData::maybe_escaping("&#xD800;").get(), Err(&CharacterEntityDecodingError::InvalidCharacter));
+    }
+
+    #[test]
+    fn unknown_entity_policy() {
+        let data = XmlCharacterData::maybe_escaping("a&nbsp;b &amp; c");
+        assert_eq!(data.get(), Err(&CharacterEntityDecodingError::UnknownEntity(UnknownCharacterEntity)));
+        assert_eq!(data.get_with_policy(UnknownEntityPolicy::PassThrough).unwrap(), "a&nbsp;b & c");
+        assert!(data.get_with_policy(UnknownEntityPolicy::Error).is_err());
+    }
+}
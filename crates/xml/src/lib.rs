@@ -4,10 +4,17 @@ pub mod cdata;
 pub mod defs;
 pub mod arena;
 pub mod span;
+pub mod query;
+pub mod prolog;
+pub mod owned;
+pub mod writer;
+#[cfg(feature = "plist")]
+pub mod plist;
 use arena::*;
 use error::*;
 use cdata::XmlCharacterData;
-use span::{Span, SingleFileLocation};
+use span::{Span, SingleFileLocation, Spanned};
+use prolog::{Declaration, DocumentType, ProcessingInstruction};
 
 use maybe_owned_string::MaybeOwnedString;
 
@@ -31,8 +38,26 @@ pub mod error {
         TagDidNotClose(super::Span<'a>),
         #[error("invalid tag name")]
         InvalidTagName,
+        #[error("malformed document type declaration at {}", .0.start_location())]
+        InvalidDocumentType(super::Span<'a>),
+        #[error("duplicate attribute {:?} at {}", .0.as_str(), .0.start_location())]
+        DuplicateAttribute(super::Span<'a>),
         #[error("attribute parse error: {0}")]
-        AttributeParseError(#[from] AttributeParseError)
+        AttributeParseError(#[from] AttributeParseError),
+        #[error("{0}")]
+        LimitExceeded(#[from] LimitExceeded),
+    }
+
+    #[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum LimitExceeded {
+        #[error("elements nested deeper than {0} levels")]
+        Depth(usize),
+        #[error("more than {0} nodes")]
+        Nodes(usize),
+        #[error("more than {0} attributes on a single tag")]
+        Attributes(usize),
+        #[error("input longer than {0} bytes")]
+        InputLength(usize),
     }
 
     #[derive(thiserror::Error, Debug)]
@@ -46,11 +71,33 @@ pub mod error {
         #[error("element opened at {} did not close", .0.span.start_location())]
         ElementDidNotClose(super::OpeningTagSpan<'a>),
 
+        #[error("{0}")]
+        LimitExceeded(LimitExceeded),
+
         #[error("arena push failure")]
         ArenaPushFailure(A::Error),
         #[error("arena reference list push failure")]
         ArenaReferenceListPushFailure(<A::NodeReferenceList as super::arena::NodeReferenceCollection<'a>>::Error),
     }
+
+    #[derive(thiserror::Error, Debug)]
+    pub enum DocumentParseError<'a, A: super::NodeArena<'a>> {
+        #[error("{0}")]
+        Node(NodeParseError<'a, A>),
+        #[error("document has no root element")]
+        NoRootElement,
+        #[error("second root element at {}", .0.start_location())]
+        MultipleRootElements(super::Span<'a>),
+        #[error("text outside of the root element at {}", .0.start_location())]
+        TextOutsideOfRoot(super::Span<'a>),
+        #[error("arena reference list push failure")]
+        ArenaReferenceListPushFailure(<A::NodeReferenceList as super::arena::NodeReferenceCollection<'a>>::Error),
+    }
+    impl<'a, A: super::NodeArena<'a>> From<NodeParseError<'a, A>> for DocumentParseError<'a, A> {
+        fn from(error: NodeParseError<'a, A>) -> Self {
+            Self::Node(error)
+        }
+    }
 }
 
 
@@ -71,15 +118,105 @@ impl<T> AsRef<T> for Read<T> {
     }
 }
 
+/// What to do upon encountering an attribute whose key was already used on the same tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DuplicateAttributePolicy {
+    /// Fail with [`SectionOpenerReadError::DuplicateAttribute`].
+    #[default]
+    Error,
+    /// Keep every occurrence; [`Attributes::get`] returns the first, and [`Attributes::duplicates`] can be used to warn about the rest.
+    Keep,
+}
 
+/// Bounds on the size of what's parsed, for use with untrusted input, and how strictly it's interpreted.
+///
+/// Parsing doesn't recurse, so deep nesting can't overflow the stack; these instead bound memory usage.
+/// Nor do [queries](query), [owned copies](owned), or [plist interpretation](plist) of what's parsed, nor writing or dropping
+/// the values those produce; cloning, comparing, hashing or debug-formatting those values does recurse, however.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Limits {
+    /// The maximum length of the input, in bytes.
+    pub max_input_len: usize,
+    /// The maximum amount of elements which can be nested within one another, including the outermost.
+    pub max_depth: usize,
+    /// The maximum amount of nodes created by a single parse.
+    pub max_nodes: usize,
+    /// The maximum amount of attributes on a single tag.
+    pub max_attributes: usize,
+    pub duplicate_attributes: DuplicateAttributePolicy,
+}
+impl Limits {
+    pub const UNLIMITED: Self = Self {
+        max_input_len: usize::MAX,
+        max_depth: usize::MAX,
+        max_nodes: usize::MAX,
+        max_attributes: usize::MAX,
+        duplicate_attributes: DuplicateAttributePolicy::Error,
+    };
+
+    /// Reasonable limits for documents from an untrusted source.
+    pub const UNTRUSTED: Self = Self {
+        max_input_len: 64 * 1024 * 1024,
+        max_depth: 256,
+        max_nodes: 1_000_000,
+        max_attributes: 256,
+        duplicate_attributes: DuplicateAttributePolicy::Error,
+    };
+
+    /// Like [`Self::UNLIMITED`], but tolerating duplicate attributes.
+    pub const LENIENT: Self = Self {
+        duplicate_attributes: DuplicateAttributePolicy::Keep,
+        ..Self::UNLIMITED
+    };
+
+    const fn check_input_len(&self, input: &Span<'_>) -> Result<(), LimitExceeded> {
+        if input.length > self.max_input_len { Err(LimitExceeded::InputLength(self.max_input_len)) } else { Ok(()) }
+    }
+}
+impl Default for Limits {
+    fn default() -> Self {
+        Self::UNLIMITED
+    }
+}
+
+
+/// The attributes of a tag, in the order they appear in the document.
 #[derive(Debug, PartialEq, Default)]
 pub struct Attributes<'a>(
     // TODO: Use an arena for this too?
-    std::collections::HashMap<
-        Span<'a>,
-        XmlCharacterData<'a>,
-    >
+    Vec<(Span<'a>, XmlCharacterData<'a>)>
 );
+impl<'a> Attributes<'a> {
+    /// Returns the value of the first attribute with the given key.
+    pub fn get(&self, key: &str) -> Option<&XmlCharacterData<'a>> {
+        self.0.iter().find(|(k, _)| *k == key).map(|(_, value)| value)
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Iterates over the attributes in source order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Span<'a>, &XmlCharacterData<'a>)> {
+        self.0.iter().map(|(key, value)| (key, value))
+    }
+
+    /// Iterates over the keys of attributes which repeat an earlier key.
+    /// This is only ever non-empty when parsed with [`DuplicateAttributePolicy::Keep`].
+    pub fn duplicates(&self) -> impl Iterator<Item = &Span<'a>> {
+        self.0.iter().enumerate()
+            .filter(|(index, (key, _))| self.0[..*index].iter().any(|(earlier, _)| earlier == key.as_str()))
+            .map(|(_, (key, _))| key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
 
 
 #[derive(Debug)]
@@ -89,7 +226,7 @@ pub enum NonNestingSection {
 }
 
 pub mod block_span {
-    use super::Span;
+    use super::{Span, Spanned};
 
     pub mod side {
         use super::BlockSpan;
@@ -275,6 +412,9 @@ pub mod block_span {
                 unsafe fn new_unchecked(span: Span<'a>) -> Self { Self(span) }
                 fn as_span(&self) -> Span<'a> { self.0 }
             }
+            impl<'a> Spanned<'a> for $ident<'a> {
+                fn span(&self) -> Span<'a> { self.0 }
+            }
             impl<'a> core::ops::Deref for $ident<'a> {
                 type Target = Span<'a>;
                 fn deref(&self) -> &Self::Target {
@@ -325,15 +465,28 @@ impl<'a> OpeningTagSpan<'a> {
         b'/' == unsafe { *self.span.top.add(self.span.offset + self.span.length - 2) }
     }
 }
+impl<'a> Spanned<'a> for OpeningTagSpan<'a> {
+    fn span(&self) -> Span<'a> {
+        self.span
+    }
+}
 
 #[derive(Debug, PartialEq)]
 pub enum SectionOpener<'a> {
     Tag(OpeningTagSpan<'a>), 
     CharacterData(Span<'a>),
     Comment(Span<'a>),
+    // These don't nest, and so are parsed in their entirety.
+    Declaration(Declaration<'a>),
+    DocumentType(DocumentType<'a>),
+    ProcessingInstruction(ProcessingInstruction<'a>),
 }
 impl<'a> SectionOpener<'a> {
     pub fn parse(input: &Span<'a>) -> Result<Option<SectionOpener<'a>>, SectionOpenerReadError<'a>> {
+        Self::parse_with_limits(input, &Limits::UNLIMITED)
+    }
+
+    pub fn parse_with_limits(input: &Span<'a>, limits: &Limits) -> Result<Option<SectionOpener<'a>>, SectionOpenerReadError<'a>> {
         if !input.starts_with("<") { return Ok(None) };
 
         if input.starts_with(CommentSpan::OPENER) {
@@ -346,102 +499,27 @@ impl<'a> SectionOpener<'a> {
             return Ok(Some(SectionOpener::CharacterData(span)))
         }
 
-        let name_ends_at = input.find(['/', '>', ' ', '\t', '\r', '\n']).ok_or(SectionOpenerReadError::TagDidNotClose(*input))?;
-        let name_last_character_index = input.find(' ').map(|v| v.min(name_ends_at)).unwrap_or(name_ends_at);
-        let name_last_character_index = NonZeroUsize::new(name_last_character_index).ok_or(SectionOpenerReadError::InvalidTagName)?;
-        let name_length = name_last_character_index.get() - '<'.len_utf8();
-        let name = input.range(1..name_last_character_index.get());
-
-        #[derive(Debug, PartialEq)]
-        enum Quote {
-            Single,
-            Double,
+        if input.starts_with(DocumentType::OPENER) {
+            return DocumentType::parse(input).map(|doctype| Some(SectionOpener::DocumentType(doctype)))
         }
 
-        #[derive(Debug, PartialEq)]
-        enum Parsing {
-            Key,
-            Value,
-            AfterDelimiter,
+        if Declaration::is_at_start(input) {
+            return Declaration::parse(input, limits).map(|declaration| Some(SectionOpener::Declaration(declaration)))
         }
-        
-        let mut parsing = None;
-        let mut parsing_started_at = None;
-        let mut key: Option<Span> = None;
-        let mut quote: Option<Quote> = None;
-        let mut attributes = Attributes::default();
-        let mut index = name_ends_at + 1;
-
-        if input.as_bytes().get(name_ends_at) != Some(&b'>') {
-            loop {
-                let char = input.as_bytes().get(index).ok_or(SectionOpenerReadError::TagDidNotClose(*input))?;
-                if key.is_none() {
-                    debug_assert!(matches!(parsing, None | Some(Parsing::Key)));
-                    if parsing.is_none() && (name != "xml" || *char != b'?') {
-                        //
-                        // Find the end of the tag, or the start of a key, skipping whitespace.
-                        //
-                        //  <tag   attribute="value" [...]
-                        //      ╚═╝┆
-                        //         ╰╴`parsing_started_at`
-                        //
-                        //  <?xml [...] ?>
-                        //              |
-                        //              └ Exemption to not treat this as the start of a key.
-                        //
-                        //  <tag [...]/>
-                        //             |
-                        //             └ Signifies the end of a tag; stops parsing attributes.
-                        //
-                        if crate::defs::WHITESPACE_U8.contains(char) { index += 1; continue }
-                        if *char == b'>' { index += 1; break }
-                        if *char == b'/' && input.as_bytes().get(index + 1) == Some(&b'>') {dbg!("meow"); index += 2; break }
-                        parsing = Some(Parsing::Key);
-                        parsing_started_at = Some(unsafe { NonZeroUsize::new_unchecked(index) });
-                    } else {
-                        debug_assert_eq!(parsing, Some(Parsing::Key));
-                        // Continually read letters of the key until we reach either whitespace or a ".".
-                        // If we don't read a "=", we'll match that in the next case. This is just to stop reading what we consider a "key". 
-                        match char {
-                            b' ' | b'\t' | b'\r' | b'\n' | b'=' => {
-                                key = Some(input.range(parsing_started_at.unwrap().get()..index));
-                                parsing = if *char == b'=' { Some(Parsing::AfterDelimiter) } else { None }
-                            },
-                            b'>' => return Err(SectionOpenerReadError::AttributeParseError(AttributeParseError::ExpectedDelimiterAfterKey)),
-                            // TODO: Prohibit characters like '<', maybe '&' and such.
-                            _ => {}
-                        }
-                    }
-                } else if !matches!(parsing, Some(Parsing::Key | Parsing::Value)) {
-                    // Find the delimiter of a key-value pair if it hasn't yet been encountered because of whitespace padding terminating the key end index search.
-                    // Then, find the start of the value by searching for a quote, ignoring whitespace padding.
-                    match char {
-                        b' ' | b'\t' | b'\r' | b'\n' => {},
-                        b'=' if parsing.is_none() => { parsing = Some(Parsing::AfterDelimiter); }
-                        b'\'' if parsing == Some(Parsing::AfterDelimiter) => { quote = Some(Quote::Single); parsing = Some(Parsing::Value); parsing_started_at = Some(unsafe { core::num::NonZero::new_unchecked(index) })}
-                        b'\"' if parsing == Some(Parsing::AfterDelimiter) => { quote = Some(Quote::Double); parsing = Some(Parsing::Value); parsing_started_at = Some(unsafe { core::num::NonZero::new_unchecked(index) })}
-                        _ if parsing == Some(Parsing::AfterDelimiter) => return Err(AttributeParseError::ExpectedValueAfterDelimiter.into()),
-                        _  => return Err(AttributeParseError::ExpectedDelimiterAfterKey.into())
-                    }
-                } else if match quote {
-                    Some(Quote::Double) => *char == b'\"',
-                    Some(Quote::Single) => *char == b'\'',
-                    None => unreachable!("parsing value without opening quote")
-                } {
-                    let span = input.slice_with((parsing_started_at.unwrap().get() + 1)..index);
-                    attributes.0.insert(
-                        key.expect("no key for value (unreachable)"),
-                        cdata::XmlCharacterData::maybe_escaping(span.as_str())
-                    );
-                    key = None;
-                    parsing = None;
-                }
 
-                index += 1;
-                continue 
-            }
+        if input.starts_with(ProcessingInstruction::OPENER) {
+            return ProcessingInstruction::parse(input).map(|instruction| Some(SectionOpener::ProcessingInstruction(instruction)))
         }
 
+        let name_ends_at = input.find(['/', '>', ' ', '\t', '\r', '\n']).ok_or(SectionOpenerReadError::TagDidNotClose(*input))?;
+        let name_last_character_index = NonZeroUsize::new(name_ends_at).ok_or(SectionOpenerReadError::InvalidTagName)?;
+        let name_length = name_last_character_index.get() - '<'.len_utf8();
+
+        let (attributes, index) = if input.as_bytes().get(name_ends_at) == Some(&b'>') {
+            (Attributes::default(), name_ends_at + 1)
+        } else {
+            parse_attributes(input, name_ends_at + 1, TagEnd::Element, limits)?
+        };
 
         Ok(Some(Self::Tag(OpeningTagSpan { 
             span: input.range(..index),
@@ -455,10 +533,120 @@ impl<'a> SectionOpener<'a> {
             Self::Tag(tag) => &tag.span,
             Self::Comment(span) |
             Self::CharacterData(span) => span,
+            Self::Declaration(declaration) => &declaration.span,
+            Self::DocumentType(doctype) => &doctype.span,
+            Self::ProcessingInstruction(instruction) => &instruction.span,
         }
     }
 }
 
+/// How a tag containing attributes is terminated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TagEnd {
+    /// `>`, or `/>` if self-closing.
+    Element,
+    /// `?>`
+    ProcessingInstruction,
+}
+
+/// Parses attributes starting at `index` up until the end of the tag.
+/// Returns the attributes and the index after the end of the tag.
+pub(crate) fn parse_attributes<'a>(input: &Span<'a>, mut index: usize, end: TagEnd, limits: &Limits) -> Result<(Attributes<'a>, usize), SectionOpenerReadError<'a>> {
+    #[derive(Debug, PartialEq)]
+    enum Quote {
+        Single,
+        Double,
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Parsing {
+        Key,
+        Value,
+        AfterDelimiter,
+    }
+    
+    let mut parsing = None;
+    let mut parsing_started_at = None;
+    let mut key: Option<Span> = None;
+    let mut quote: Option<Quote> = None;
+    let mut attributes = Attributes::default();
+
+    loop {
+        let char = input.as_bytes().get(index).ok_or(SectionOpenerReadError::TagDidNotClose(*input))?;
+        if key.is_none() {
+            debug_assert!(matches!(parsing, None | Some(Parsing::Key)));
+            if parsing.is_none() {
+                //
+                // Find the end of the tag, or the start of a key, skipping whitespace.
+                //
+                //  <tag   attribute="value" [...]
+                //      ╚═╝┆
+                //         ╰╴`parsing_started_at`
+                //
+                //  <tag [...]/>
+                //             |
+                //             └ Signifies the end of a tag; stops parsing attributes.
+                //
+                //  <?xml [...] ?>
+                //              |
+                //              └ Likewise, for processing instructions.
+                //
+                if crate::defs::WHITESPACE_U8.contains(char) { index += 1; continue }
+                let next = input.as_bytes().get(index + 1);
+                match end {
+                    TagEnd::Element if *char == b'>' => { index += 1; break }
+                    TagEnd::Element if *char == b'/' && next == Some(&b'>') => { index += 2; break }
+                    TagEnd::ProcessingInstruction if *char == b'?' && next == Some(&b'>') => { index += 2; break }
+                    _ => {}
+                }
+                parsing = Some(Parsing::Key);
+                parsing_started_at = Some(unsafe { NonZeroUsize::new_unchecked(index) });
+            } else {
+                debug_assert_eq!(parsing, Some(Parsing::Key));
+                // Continually read letters of the key until we reach either whitespace or a ".".
+                // If we don't read a "=", we'll match that in the next case. This is just to stop reading what we consider a "key". 
+                match char {
+                    b' ' | b'\t' | b'\r' | b'\n' | b'=' => {
+                        key = Some(input.range(parsing_started_at.unwrap().get()..index));
+                        parsing = if *char == b'=' { Some(Parsing::AfterDelimiter) } else { None }
+                    },
+                    b'>' => return Err(SectionOpenerReadError::AttributeParseError(AttributeParseError::ExpectedDelimiterAfterKey)),
+                    // TODO: Prohibit characters like '<', maybe '&' and such.
+                    _ => {}
+                }
+            }
+        } else if !matches!(parsing, Some(Parsing::Key | Parsing::Value)) {
+            // Find the delimiter of a key-value pair if it hasn't yet been encountered because of whitespace padding terminating the key end index search.
+            // Then, find the start of the value by searching for a quote, ignoring whitespace padding.
+            match char {
+                b' ' | b'\t' | b'\r' | b'\n' => {},
+                b'=' if parsing.is_none() => { parsing = Some(Parsing::AfterDelimiter); }
+                b'\'' if parsing == Some(Parsing::AfterDelimiter) => { quote = Some(Quote::Single); parsing = Some(Parsing::Value); parsing_started_at = Some(unsafe { core::num::NonZero::new_unchecked(index) })}
+                b'\"' if parsing == Some(Parsing::AfterDelimiter) => { quote = Some(Quote::Double); parsing = Some(Parsing::Value); parsing_started_at = Some(unsafe { core::num::NonZero::new_unchecked(index) })}
+                _ if parsing == Some(Parsing::AfterDelimiter) => return Err(AttributeParseError::ExpectedValueAfterDelimiter.into()),
+                _  => return Err(AttributeParseError::ExpectedDelimiterAfterKey.into())
+            }
+        } else if match quote {
+            Some(Quote::Double) => *char == b'\"',
+            Some(Quote::Single) => *char == b'\'',
+            None => unreachable!("parsing value without opening quote")
+        } {
+            if attributes.len() >= limits.max_attributes { return Err(LimitExceeded::Attributes(limits.max_attributes).into()) }
+            let name = key.take().expect("no key for value (unreachable)");
+            if limits.duplicate_attributes == DuplicateAttributePolicy::Error && attributes.contains_key(&name) {
+                return Err(SectionOpenerReadError::DuplicateAttribute(name))
+            }
+            let span = input.slice_with((parsing_started_at.unwrap().get() + 1)..index);
+            attributes.0.push((name, cdata::XmlCharacterData::maybe_escaping(span.as_str())));
+            parsing = None;
+        }
+
+        index += 1;
+    }
+
+    Ok((attributes, index))
+}
+
 pub union CharacterDataSpan<'a> {
     as_block: CharacterDataBlockSpan<'a>,
     as_abrupt_node: Span<'a>,
@@ -496,6 +684,11 @@ impl<'a> CharacterDataSpan<'a> {
         }
     }
 }
+impl<'a> Spanned<'a> for CharacterDataSpan<'a> {
+    fn span(&self) -> Span<'a> {
+        self.as_raw_span()
+    }
+}
 impl core::fmt::Debug for CharacterDataSpan<'_> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         if self.is_block() {
@@ -512,26 +705,104 @@ impl PartialEq for CharacterDataSpan<'_> {
 }
 
 
+enum Parsed<'a, A: NodeArena<'a>> {
+    Node(Node<'a, A>),
+    /// The opening tag of an element which has yet to be closed.
+    Opened(OpeningTagSpan<'a>),
+}
+
 #[derive(Debug, PartialEq)]
 pub enum Node<'a, A: NodeArena<'a>> {
     Element(Element<'a, A>),
     Comment(CommentSpan<'a>),
     Text(XmlCharacterData<'a>, CharacterDataSpan<'a>), // including whitespace indentation
+    Declaration(Declaration<'a>),
+    DocumentType(DocumentType<'a>),
+    ProcessingInstruction(ProcessingInstruction<'a>),
 }
 impl<'a, A: NodeArena<'a>> Node<'a, A> {
+    /// Parses the node at the start of the input, including all of its descendants.
     pub fn parse(input: &Span<'a>, arena: &mut A) -> Result<Option<Read<A::NodeReference>>, NodeParseError<'a, A>> {
-        if input.is_empty() { return Ok(None) }
+        Self::parse_with_limits(input, arena, &Limits::UNLIMITED)
+    }
+
+    pub fn parse_with_limits(input: &Span<'a>, arena: &mut A, limits: &Limits) -> Result<Option<Read<A::NodeReference>>, NodeParseError<'a, A>> {
+        limits.check_input_len(input).map_err(NodeParseError::LimitExceeded)?;
+        Self::parse_counting(input, arena, limits, &mut 0)
+    }
+
+    /// Parses a node, tracking the amount of nodes created across calls so that [`Limits::max_nodes`] can apply to a whole document.
+    ///
+    /// Elements which are still open are kept on an explicit stack, rather than being parsed recursively.
+    fn parse_counting(input: &Span<'a>, arena: &mut A, limits: &Limits, nodes: &mut usize) -> Result<Option<Read<A::NodeReference>>, NodeParseError<'a, A>> {
+        struct OpenElement<'a, A: NodeArena<'a>> {
+            opener: OpeningTagSpan<'a>,
+            children: A::NodeReferenceList,
+        }
+
+        let mut open: Vec<OpenElement<'a, A>> = Vec::new();
+        let mut after = *input;
+
+        loop {
+            let closer = if open.is_empty() { None } else { parse_closing_tag(after) };
+            let node = if let Some(closer) = closer {
+                let OpenElement { opener, children } = open.pop().expect("an element should be open");
+                if closer.get_name_span() != opener.get_name_span() {
+                    return Err(NodeParseError::ElementDidNotClose(opener))
+                }
+                Node::Element(Element { opener, closer: Some(closer), children })
+            } else if after.is_empty() {
+                return match open.pop() {
+                    Some(unclosed) => Err(NodeParseError::ElementDidNotClose(unclosed.opener)),
+                    None => Ok(None),
+                }
+            } else {
+                match Self::parse_single(&after, limits)? {
+                    Parsed::Node(Node::Element(_)) if open.len() >= limits.max_depth => return Err(NodeParseError::LimitExceeded(LimitExceeded::Depth(limits.max_depth))),
+                    Parsed::Node(node) => node,
+                    Parsed::Opened(opener) => {
+                        if open.len() >= limits.max_depth { return Err(NodeParseError::LimitExceeded(LimitExceeded::Depth(limits.max_depth))) }
+                        after = after.range(opener.span.length..);
+                        open.push(OpenElement { opener, children: A::NodeReferenceList::default() });
+                        continue
+                    }
+                }
+            };
+
+            // Elements only include their closing tag (and everything before it) once complete.
+            let consumed = match &node {
+                Node::Element(element) => element.closer.as_ref().map_or(element.opener.span.length, |closer| closer.span.length),
+                _ => node.span().length,
+            };
+            after = after.range(consumed..);
+
+            if *nodes >= limits.max_nodes { return Err(NodeParseError::LimitExceeded(LimitExceeded::Nodes(limits.max_nodes))) }
+            *nodes += 1;
+            let reference = arena.add(node).map_err(NodeParseError::ArenaPushFailure)?;
 
-        let node = if let Some(opener) = SectionOpener::parse(input).map_err(|err| NodeParseError::BadSectionOpener(err))? {
+            match open.last_mut() {
+                Some(parent) => parent.children.add(reference).map_err(NodeParseError::ArenaReferenceListPushFailure)?,
+                None => return Ok(Some(Read { value: reference, consumed_bytes: after.offset - input.offset })),
+            }
+        }
+    }
+
+    /// Parses a node which doesn't have children, or the opening tag of one which does.
+    fn parse_single(input: &Span<'a>, limits: &Limits) -> Result<Parsed<'a, A>, NodeParseError<'a, A>> {
+        let node = if let Some(opener) = SectionOpener::parse_with_limits(input, limits).map_err(NodeParseError::BadSectionOpener)? {
             match opener {
+                SectionOpener::Tag(opener) if !opener.is_self_closing() => return Ok(Parsed::Opened(opener)),
+                SectionOpener::Tag(opener) => Node::Element(Element { opener, closer: None, children: A::NodeReferenceList::default() }),
                 // TODO: Don't use `ok().flatten()` here; properly propagate errors.
-                SectionOpener::Tag(opener) => Node::Element(Element::parse_after_opening(input, opener, arena)?),
-                SectionOpener::Comment(opener) => Node::Comment(CommentSpan::parse_after_opening(input).ok().flatten().ok_or(NodeParseError::NonNestingDidNotClose(opener, NonNestingSection::Comment))?),
+                SectionOpener::Comment(opener) => Node::Comment(CommentSpan::parse_after_opening(&input.range(opener.len()..)).ok().flatten().ok_or(NodeParseError::NonNestingDidNotClose(opener, NonNestingSection::Comment))?),
                 SectionOpener::CharacterData(opener) => {
-                    let span = CharacterDataBlockSpan::parse_after_opening(input).ok().flatten().ok_or(NodeParseError::NonNestingDidNotClose(opener, NonNestingSection::UnescapedCharacterData))?;
+                    let span = CharacterDataBlockSpan::parse_after_opening(&input.range(opener.len()..)).ok().flatten().ok_or(NodeParseError::NonNestingDidNotClose(opener, NonNestingSection::UnescapedCharacterData))?;
                     let text = XmlCharacterData::Plain(span.content().as_str());
                     Self::Text(text, CharacterDataSpan::block(span))
                 },
+                SectionOpener::Declaration(declaration) => Node::Declaration(declaration),
+                SectionOpener::DocumentType(doctype) => Node::DocumentType(doctype),
+                SectionOpener::ProcessingInstruction(instruction) => Node::ProcessingInstruction(instruction),
             }
         } else {
             let text = match input.find('<') {
@@ -540,9 +811,7 @@ impl<'a, A: NodeArena<'a>> Node<'a, A> {
             };
             Self::Text(XmlCharacterData::maybe_escaping(text.as_str()), CharacterDataSpan::abrupt_node(text))
         };
-
-        let consumed_bytes = node.span().length;
-        Ok(Some(Read { value: arena.add(node).map_err(NodeParseError::ArenaPushFailure)?, consumed_bytes }))
+        Ok(Parsed::Node(node))
     }
 
     pub fn span(&self) -> Span<'a> {
@@ -550,39 +819,45 @@ impl<'a, A: NodeArena<'a>> Node<'a, A> {
             Self::Comment(span) => span.as_span(),
             Self::Text(_, span) => span.as_raw_span(),
             Self::Element(element) => element.span(),
+            Self::Declaration(declaration) => declaration.span,
+            Self::DocumentType(doctype) => doctype.span,
+            Self::ProcessingInstruction(instruction) => instruction.span,
         }
     }
 
     pub fn into_element(self) -> Option<Element<'a, A>> {
         match self {
             Self::Element(element) => Some(element),
-            Self::Comment(_) | Self::Text(..) => None,
+            _ => None,
         }
     }
 
     pub const fn as_element(&self) -> Option<&Element<'a, A>> {
         match self {
             Self::Element(element) => Some(element),
-            Self::Comment(_) | Self::Text(..) => None,
+            _ => None,
         }
     }
 
     pub const fn as_cdata(&self) -> Option<&XmlCharacterData<'a>> {
         match self {
-            Self::Comment(_) |
-            Self::Element(_) => None,
-            Self::Text(cdata, _) => Some(cdata)
+            Self::Text(cdata, _) => Some(cdata),
+            _ => None,
         }
     }
 
     pub fn into_cdata(self) -> Option<XmlCharacterData<'a>> {
         match self {
-            Self::Comment(_) |
-            Self::Element(_) => None,
-            Self::Text(cdata, _) => Some(cdata)
+            Self::Text(cdata, _) => Some(cdata),
+            _ => None,
         }
     }
 }
+impl<'a, A: NodeArena<'a>> Spanned<'a> for Node<'a, A> {
+    fn span(&self) -> Span<'a> {
+        Node::span(self)
+    }
+}
 
 #[derive(Debug, PartialEq)]
 pub struct Element<'a, A: NodeArena<'a>> {
@@ -591,48 +866,6 @@ pub struct Element<'a, A: NodeArena<'a>> {
     pub children: A::NodeReferenceList,
 }
 impl<'a, A: NodeArena<'a>> Element<'a, A> {
-    fn parse_after_opening(input: &Span<'a>, opener: OpeningTagSpan<'a>, arena: &mut A) -> Result<Element<'a, A>, NodeParseError<'a, A>>  {
-        if opener.is_self_closing() {
-            return Ok(Self {
-                opener,
-                closer: None,
-                children: A::NodeReferenceList::default()
-            })
-        };
-
-        let mut children = A::NodeReferenceList::default();
-        let mut after = input.range(opener.span.length..);
-
-        if &*opener.get_name_span() == "?xml" {
-            return Ok(Self {
-                opener,
-                closer: None,
-                children
-            });
-        }
-
-        loop {
-            if let Some(closer) = parse_closing_tag(after) {
-                if closer.get_name_span() != opener.get_name_span() {
-                    return Err(NodeParseError::ElementDidNotClose(opener))
-                }
-                return Ok(Self {
-                    opener,
-                    closer: Some(closer),
-                    children
-                })
-            }
-
-            if let Some(next) = Node::parse(&after, arena)? {
-                let Read { value: index, consumed_bytes } = next;
-                after = after.range(consumed_bytes..);
-                children.add(index).map_err(NodeParseError::ArenaReferenceListPushFailure)?;
-            } else {
-                return Err(NodeParseError::ElementDidNotClose(opener))
-            }
-        }
-    }
-
     pub const fn attributes(&self) -> &Attributes<'_> {
         &self.opener.attributes
     }
@@ -653,6 +886,74 @@ impl<'a, A: NodeArena<'a>> Element<'a, A> {
         }
     }
 }
+impl<'a, A: NodeArena<'a>> Spanned<'a> for Element<'a, A> {
+    fn span(&self) -> Span<'a> {
+        Element::span(self)
+    }
+}
+
+
+/// A complete document: a single root element, along with the nodes surrounding it.
+#[derive(Debug, PartialEq)]
+pub struct Document<'a, A: NodeArena<'a>> {
+    /// Nodes preceding the root element, such as the declaration, the document type, comments, and whitespace.
+    pub prolog: A::NodeReferenceList,
+    pub root: A::NodeReference,
+    /// Nodes following the root element.
+    pub epilog: A::NodeReferenceList,
+}
+impl<'a, A: NodeArena<'a>> Document<'a, A> {
+    pub fn parse(input: &Span<'a>, arena: &mut A) -> Result<Self, DocumentParseError<'a, A>> {
+        Self::parse_with_limits(input, arena, &Limits::UNLIMITED)
+    }
+
+    pub fn parse_with_limits(input: &Span<'a>, arena: &mut A, limits: &Limits) -> Result<Self, DocumentParseError<'a, A>> {
+        limits.check_input_len(input).map_err(NodeParseError::LimitExceeded)?;
+        let mut nodes = 0;
+        let mut prolog = A::NodeReferenceList::default();
+        let mut epilog = A::NodeReferenceList::default();
+        let mut root = None;
+        let mut after = *input;
+
+        while let Some(Read { value: reference, consumed_bytes }) = Node::parse_counting(&after, arena, limits, &mut nodes)? {
+            after = after.range(consumed_bytes..);
+            match arena.get(&reference) {
+                Node::Element(element) if root.is_some() => return Err(DocumentParseError::MultipleRootElements(element.opener.span)),
+                Node::Text(text, span) if !text.is_just_whitespace().unwrap_or(false) => return Err(DocumentParseError::TextOutsideOfRoot(span.as_raw_span())),
+                Node::Element(_) => { root = Some(reference); continue }
+                _ => {}
+            }
+            match root {
+                None => prolog.add(reference),
+                Some(_) => epilog.add(reference),
+            }.map_err(DocumentParseError::ArenaReferenceListPushFailure)?;
+        }
+
+        Ok(Self {
+            prolog,
+            root: root.ok_or(DocumentParseError::NoRootElement)?,
+            epilog,
+        })
+    }
+
+    pub fn root<'b>(&self, arena: &'b A) -> &'b Element<'a, A> {
+        arena.get(&self.root).as_element().expect("root should always be an element")
+    }
+
+    pub fn declaration<'b>(&'b self, arena: &'b A) -> Option<&'b Declaration<'a>> {
+        self.prolog.iter().find_map(|reference| match arena.get(reference) {
+            Node::Declaration(declaration) => Some(declaration),
+            _ => None,
+        })
+    }
+
+    pub fn document_type<'b>(&'b self, arena: &'b A) -> Option<&'b DocumentType<'a>> {
+        self.prolog.iter().find_map(|reference| match arena.get(reference) {
+            Node::DocumentType(doctype) => Some(doctype),
+            _ => None,
+        })
+    }
+}
 
 
 #[derive(Debug, PartialEq)]
@@ -666,10 +967,15 @@ impl<'a> ClosingTagSpan<'a> {
         self.span.range(OFFSET..(OFFSET + self.name_length))
     }
 }
+impl<'a> Spanned<'a> for ClosingTagSpan<'a> {
+    fn span(&self) -> Span<'a> {
+        self.span
+    }
+}
 // TODO: error on closing tag not terminating
 fn parse_closing_tag(span: Span<'_>) -> Option<ClosingTagSpan<'_>> {
     if !span.starts_with("</") { return None }
-    let end = span.find(">").unwrap();
+    let end = span.find('>')?;
     // TODO: allow whitespace where applicable, only allow valid characters
     Some(ClosingTagSpan {
         span: span.range(..(end + 1)),
@@ -758,8 +1064,30 @@ mod tests {
             assert!(!tag.is_self_closing());
             assert_eq!(tag.get_name_span().as_str(), "tag");
             assert_eq!(tag.attributes.0.len(), 2);
-            assert!(tag.attributes.0.contains_key(&Span::new_root("attr")));
-            assert!(tag.attributes.0.contains_key(&Span::new_root("another")));
+            assert!(tag.attributes.contains_key("attr"));
+            assert!(tag.attributes.contains_key("another"));
+        }
+
+        #[test]
+        fn attribute_order_and_duplicates() {
+            let SectionOpener::Tag(tag) = p!("<tag z='1' a='2' m='3'>").unwrap().unwrap() else { panic!("wasn't parsed as an opener tag") };
+            assert_eq!(tag.attributes.iter().map(|(key, _)| key.as_str()).collect::<Vec<_>>(), ["z", "a", "m"]);
+
+            let input = "<tag a='1' b='2' a='3'>";
+            let Err(SectionOpenerReadError::DuplicateAttribute(key)) = p!(input) else { panic!("duplicate wasn't rejected") };
+            assert_eq!(key.offset, input.rfind('a').unwrap());
+
+            let Ok(Some(SectionOpener::Tag(tag))) = SectionOpener::parse_with_limits(&Span::new_root(input), &Limits::LENIENT) else { panic!("wasn't parsed as an opener tag") };
+            assert_eq!(tag.attributes.len(), 3);
+            assert_eq!(tag.attributes.get("a").unwrap().raw(), "1");
+            assert_eq!(tag.attributes.duplicates().map(|key| key.offset).collect::<Vec<_>>(), [key.offset]);
+        }
+
+        #[test]
+        fn attribute_limit() {
+            let limits = Limits { max_attributes: 2, ..Limits::UNLIMITED };
+            assert!(SectionOpener::parse_with_limits(&Span::new_root("<a b='1' c='2'>"), &limits).is_ok());
+            assert_eq!(SectionOpener::parse_with_limits(&Span::new_root("<a b='1' c='2' d='3'>"), &limits), Err(SectionOpenerReadError::LimitExceeded(LimitExceeded::Attributes(2))));
         }
 
         mod blocks {
@@ -819,5 +1147,130 @@ mod tests {
             check!(arena.get(&children[0]), "tag", "hello");
             check!(arena.get(&children[1]), "tag", "world");
         }
+
+        #[test]
+        fn locations() {
+            let input = "<a>\n  <b>text</b>\n  <!-- c -->\n</a>";
+            let index = span::LineIndex::new(input);
+            let mut arena = VecNodeArena::default();
+            let Read { value: root, .. } = Node::parse(&Span::new_root(input), &mut arena).unwrap().unwrap();
+            let root = arena.get(&root).as_element().unwrap();
+            let b = root.child_elements(&arena).next().unwrap();
+            assert_eq!(b.start_location_in(&index), span::SingleFileLocation { line: 1, column: 2, offset: 6 });
+            assert_eq!(b.end_location_in(&index), span::SingleFileLocation { line: 1, column: 13, offset: 17 });
+            assert_eq!(b.closer.as_ref().unwrap().start_location_in(&index).column, 9);
+            let comment = root.child_nodes(&arena).find(|node| matches!(node, Node::Comment(_))).unwrap();
+            assert_eq!(comment.start_location(), comment.start_location_in(&index));
+            assert_eq!(comment.start_location().line, 2);
+            assert_eq!(root.end_location_in(&index), root.end_location());
+            assert_eq!(root.byte_range(), 0..input.len());
+        }
+    }
+
+    mod limits {
+        use crate::arena::vec::VecNodeArena;
+
+        use super::*;
+
+        #[test]
+        fn deep_nesting_does_not_overflow() {
+            const DEPTH: usize = 100_000;
+            let input = "<a>".repeat(DEPTH) + &"</a>".repeat(DEPTH);
+            let mut arena = VecNodeArena::default();
+            let Read { value: root, consumed_bytes } = Node::parse(&Span::new_root(&input), &mut arena).unwrap().unwrap();
+            assert_eq!(consumed_bytes, input.len());
+            assert_eq!(arena.len(), DEPTH);
+            assert_eq!(arena.get(&root).as_element().unwrap().children.len(), 1);
+        }
+
+        #[test]
+        fn walking_deep_nesting_does_not_overflow() {
+            const DEPTH: usize = 100_000;
+            let input = "<array>".repeat(DEPTH) + "<string>x</string>" + &"</array>".repeat(DEPTH);
+            let mut arena = VecNodeArena::default();
+            let Read { value: root, .. } = Node::parse(&Span::new_root(&input), &mut arena).unwrap().unwrap();
+            let root = arena.get(&root).as_element().unwrap();
+
+            assert_eq!(root.text_content(&arena).unwrap(), "x");
+            assert_eq!(root.descendants(&arena).count(), DEPTH + 1);
+            assert_eq!(root.descendants_breadth_first(&arena).count(), DEPTH + 1);
+            let path = "array/".repeat(DEPTH - 1) + "string";
+            assert_eq!(root.find(&arena, &path).unwrap().tag_name(), "string");
+
+            let owned = root.to_owned_element(&arena).unwrap();
+            assert_eq!(owned.text_content(), "x");
+            let mut writer = crate::writer::Writer::new(String::new(), crate::writer::Style::Compact);
+            owned.write(&mut writer).unwrap();
+            assert_eq!(writer.finish().unwrap(), input);
+            drop(owned);
+
+            #[cfg(feature = "plist")]
+            {
+                let value = crate::plist::PlistValue::from_element(root, &arena).unwrap();
+                let mut innermost = &value;
+                for _ in 1..DEPTH {
+                    innermost = &innermost.as_array().unwrap()[0];
+                }
+                assert_eq!(innermost.as_array().unwrap()[0].as_str(), Some("x"));
+            }
+        }
+
+        #[test]
+        fn exceeded() {
+            let mut arena = VecNodeArena::default();
+            let limits = Limits { max_depth: 2, ..Limits::UNLIMITED };
+            assert!(Node::parse_with_limits(&Span::new_root("<a><b/></a>"), &mut arena, &limits).is_ok());
+            assert!(matches!(Node::parse_with_limits(&Span::new_root("<a><b><c/></b></a>"), &mut arena, &limits), Err(NodeParseError::LimitExceeded(LimitExceeded::Depth(2)))));
+
+            let limits = Limits { max_input_len: 8, ..Limits::UNLIMITED };
+            assert!(Node::parse_with_limits(&Span::new_root("<a></a>"), &mut arena, &limits).is_ok());
+            assert!(matches!(Node::parse_with_limits(&Span::new_root("<a>b</a>!"), &mut arena, &limits), Err(NodeParseError::LimitExceeded(LimitExceeded::InputLength(8)))));
+            assert!(matches!(Document::parse_with_limits(&Span::new_root("<a>bc</a>"), &mut arena, &limits), Err(DocumentParseError::Node(NodeParseError::LimitExceeded(LimitExceeded::InputLength(8))))));
+
+            let limits = Limits { max_nodes: 3, ..Limits::UNLIMITED };
+            assert!(Node::parse_with_limits(&Span::new_root("<a><b/>c</a>"), &mut arena, &limits).is_ok());
+            assert!(matches!(Node::parse_with_limits(&Span::new_root("<a><b/>c<d/></a>"), &mut arena, &limits), Err(NodeParseError::LimitExceeded(LimitExceeded::Nodes(3)))));
+            assert!(matches!(Document::parse_with_limits(&Span::new_root("<!-- 1 --><!-- 2 --><a/><!-- 4 -->"), &mut arena, &limits), Err(DocumentParseError::Node(NodeParseError::LimitExceeded(LimitExceeded::Nodes(3))))));
+        }
+
+        #[test]
+        fn unclosed() {
+            let mut arena = VecNodeArena::default();
+            assert!(matches!(Node::parse(&Span::new_root("<a><b></b>"), &mut arena), Err(NodeParseError::ElementDidNotClose(opener)) if opener.get_name_span() == "a"));
+            assert!(matches!(Node::parse(&Span::new_root("<a></b>"), &mut arena), Err(NodeParseError::ElementDidNotClose(_))));
+            assert!(Node::parse(&Span::new_root("<a></a"), &mut arena).is_err());
+        }
+    }
+
+    mod document {
+        use crate::arena::vec::VecNodeArena;
+
+        use super::*;
+
+        #[test]
+        fn prolog() {
+            let input = concat!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+                "<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n",
+                "<?xml-stylesheet href=\"a.css\"?>\n",
+                "<plist version=\"1.0\"><true/></plist>\n",
+                "<!-- trailing -->",
+            );
+            let mut arena = VecNodeArena::default();
+            let document = Document::parse(&Span::new_root(input), &mut arena).unwrap();
+            assert_eq!(document.declaration(&arena).unwrap().encoding().unwrap().raw(), "UTF-8");
+            assert_eq!(document.document_type(&arena).unwrap().name(), "plist");
+            assert_eq!(document.root(&arena).tag_name(), "plist");
+            assert_eq!(document.prolog.len(), 6);
+            assert_eq!(document.epilog.len(), 2);
+        }
+
+        #[test]
+        fn errors() {
+            let mut arena = VecNodeArena::default();
+            assert!(matches!(Document::parse(&Span::new_root("<?xml version=\"1.0\"?>"), &mut arena), Err(DocumentParseError::NoRootElement)));
+            assert!(matches!(Document::parse(&Span::new_root("<a/><b/>"), &mut arena), Err(DocumentParseError::MultipleRootElements(_))));
+            assert!(matches!(Document::parse(&Span::new_root("hi<a/>"), &mut arena), Err(DocumentParseError::TextOutsideOfRoot(_))));
+        }
     }
 }
@@ -0,0 +1,248 @@
+//! An owned representation of a parsed tree, which doesn't borrow from the input or from an arena.
+//!
+//! Conversion decodes all character data, so it fails in the same cases [`XmlCharacterData::get`] does.
+use crate::{Document, Element, Node, NodeArena, NodeReferenceCollection, block_span::BlockSpan};
+use crate::cdata::{XmlCharacterData, error::CharacterEntityDecodingError};
+use crate::writer::{Writer, WriteError};
+
+fn decode(data: &XmlCharacterData<'_>) -> Result<String, CharacterEntityDecodingError> {
+    data.get().map(str::to_owned).map_err(Clone::clone)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum OwnedNode {
+    Element(OwnedElement),
+    Comment(String),
+    Text(String),
+    Declaration {
+        attributes: Vec<(String, String)>,
+    },
+    DocumentType {
+        name: String,
+        public_id: Option<String>,
+        system_id: Option<String>,
+        internal_subset: Option<String>,
+    },
+    ProcessingInstruction {
+        target: String,
+        data: String,
+    },
+}
+impl OwnedNode {
+    pub const fn as_element(&self) -> Option<&OwnedElement> {
+        if let Self::Element(element) = self { Some(element) } else { None }
+    }
+
+    pub fn into_element(self) -> Option<OwnedElement> {
+        if let Self::Element(element) = self { Some(element) } else { None }
+    }
+
+    pub fn write<W: core::fmt::Write>(&self, writer: &mut Writer<W>) -> Result<(), WriteError> {
+        match self {
+            Self::Element(element) => return element.write(writer),
+            Self::Comment(comment) => writer.comment(comment)?,
+            Self::Text(text) => writer.text(text)?,
+            Self::Declaration { attributes } => {
+                let get = |key: &str| attributes.iter().find(|(k, _)| k == key).map(|(_, value)| value.as_str());
+                let standalone = get("standalone").and_then(|standalone| match standalone {
+                    "yes" => Some(true),
+                    "no" => Some(false),
+                    _ => None,
+                });
+                writer.declaration(get("version").unwrap_or("1.0"), get("encoding"), standalone)?
+            },
+            Self::DocumentType { name, public_id, system_id, internal_subset } => writer.document_type(name, public_id.as_deref(), system_id.as_deref(), internal_subset.as_deref())?,
+            Self::ProcessingInstruction { target, data } => writer.processing_instruction(target, data)?,
+        };
+        Ok(())
+    }
+}
+impl<'a, A: NodeArena<'a>> Node<'a, A> {
+    /// Creates an owned copy of this node and all of its descendants.
+    pub fn to_owned_node(&self, arena: &A) -> Result<OwnedNode, CharacterEntityDecodingError> {
+        Ok(match self {
+            Self::Element(element) => OwnedNode::Element(element.to_owned_element(arena)?),
+            Self::Comment(comment) => OwnedNode::Comment(comment.content().as_str().to_owned()),
+            Self::Text(text, _) => OwnedNode::Text(decode(text)?),
+            Self::Declaration(declaration) => OwnedNode::Declaration {
+                attributes: declaration.attributes.iter().map(|(key, value)| Ok((key.as_str().to_owned(), decode(value)?))).collect::<Result<_, CharacterEntityDecodingError>>()?
+            },
+            Self::DocumentType(doctype) => OwnedNode::DocumentType {
+                name: doctype.name().as_str().to_owned(),
+                public_id: doctype.public_id().map(|id| id.as_str().to_owned()),
+                system_id: doctype.system_id().map(|id| id.as_str().to_owned()),
+                internal_subset: doctype.internal_subset().map(|subset| subset.as_str().to_owned()),
+            },
+            Self::ProcessingInstruction(instruction) => OwnedNode::ProcessingInstruction {
+                target: instruction.target().as_str().to_owned(),
+                data: instruction.data().as_str().to_owned(),
+            },
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct OwnedElement {
+    pub name: String,
+    /// Attributes, with their values decoded.
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<OwnedNode>,
+}
+impl OwnedElement {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into(), attributes: Vec::new(), children: Vec::new() }
+    }
+
+    pub fn attribute(&self, key: &str) -> Option<&str> {
+        self.attributes.iter().find(|(k, _)| k == key).map(|(_, value)| value.as_str())
+    }
+
+    pub fn child_elements(&self) -> impl Iterator<Item = &OwnedElement> {
+        self.children.iter().filter_map(OwnedNode::as_element)
+    }
+
+    pub fn children_named<'b>(&'b self, name: &'b str) -> impl Iterator<Item = &'b OwnedElement> + 'b {
+        self.child_elements().filter(move |element| element.name == name)
+    }
+
+    /// Concatenates all text within this element and its descendants.
+    pub fn text_content(&self) -> String {
+        let mut out = String::new();
+        // In reverse, so that the earliest is popped first.
+        let mut pending = self.children.iter().rev().collect::<Vec<_>>();
+        while let Some(child) = pending.pop() {
+            match child {
+                OwnedNode::Text(text) => out.push_str(text),
+                OwnedNode::Element(element) => pending.extend(element.children.iter().rev()),
+                _ => {}
+            }
+        }
+        out
+    }
+
+    pub fn write<W: core::fmt::Write>(&self, writer: &mut Writer<W>) -> Result<(), WriteError> {
+        fn start<W: core::fmt::Write>(element: &OwnedElement, writer: &mut Writer<W>) -> Result<(), WriteError> {
+            writer.start(&element.name)?;
+            for (key, value) in &element.attributes {
+                writer.attribute(key, value)?;
+            }
+            Ok(())
+        }
+
+        start(self, writer)?;
+        let mut stack = vec![self.children.iter()];
+        while let Some(children) = stack.last_mut() {
+            match children.next() {
+                Some(OwnedNode::Element(element)) => {
+                    start(element, writer)?;
+                    stack.push(element.children.iter());
+                },
+                Some(node) => node.write(writer)?,
+                None => {
+                    writer.end()?;
+                    stack.pop();
+                }
+            }
+        }
+        Ok(())
+    }
+}
+impl Drop for OwnedElement {
+    /// Descendants are taken out and dropped one by one, so that deep nesting can't overflow the stack.
+    fn drop(&mut self) {
+        let mut pending = core::mem::take(&mut self.children);
+        while let Some(node) = pending.pop() {
+            if let OwnedNode::Element(mut element) = node {
+                pending.append(&mut element.children);
+            }
+        }
+    }
+}
+impl<'a, A: NodeArena<'a>> Element<'a, A> {
+    /// Creates an owned copy of this element and all of its descendants.
+    pub fn to_owned_element(&self, arena: &A) -> Result<OwnedElement, CharacterEntityDecodingError> {
+        /// An element whose children are still being copied, along with those left to copy (in reverse).
+        fn open<'a, 'b, A: NodeArena<'a>>(element: &'b Element<'a, A>, arena: &'b A) -> Result<(OwnedElement, Vec<&'b Node<'a, A>>), CharacterEntityDecodingError> {
+            let mut remaining = element.child_nodes(arena).collect::<Vec<_>>();
+            remaining.reverse();
+            Ok((OwnedElement {
+                name: element.tag_name().to_owned(),
+                attributes: element.attributes().iter().map(|(key, value)| Ok((key.as_str().to_owned(), decode(value)?))).collect::<Result<_, CharacterEntityDecodingError>>()?,
+                children: Vec::with_capacity(remaining.len()),
+            }, remaining))
+        }
+
+        let mut stack = vec![open(self, arena)?];
+        loop {
+            let (_, remaining) = stack.last_mut().expect("an element should be open");
+            match remaining.pop() {
+                Some(Node::Element(child)) => stack.push(open(child, arena)?),
+                Some(node) => {
+                    let node = node.to_owned_node(arena)?;
+                    stack.last_mut().expect("an element should be open").0.children.push(node);
+                },
+                None => {
+                    let (element, _) = stack.pop().expect("an element should be open");
+                    match stack.last_mut() {
+                        Some((parent, _)) => parent.children.push(OwnedNode::Element(element)),
+                        None => return Ok(element),
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OwnedDocument {
+    pub prolog: Vec<OwnedNode>,
+    pub root: OwnedElement,
+    pub epilog: Vec<OwnedNode>,
+}
+impl OwnedDocument {
+    pub fn write<W: core::fmt::Write>(&self, writer: &mut Writer<W>) -> Result<(), WriteError> {
+        for node in &self.prolog { node.write(writer)?; }
+        self.root.write(writer)?;
+        for node in &self.epilog { node.write(writer)?; }
+        Ok(())
+    }
+}
+impl<'a, A: NodeArena<'a>> Document<'a, A> {
+    /// Creates an owned copy of the document, which no longer borrows from the input or the arena.
+    pub fn into_owned(self, arena: &A) -> Result<OwnedDocument, CharacterEntityDecodingError> {
+        let owned = |list: &A::NodeReferenceList| list.iter().map(|reference| arena.get(reference).to_owned_node(arena)).collect::<Result<Vec<_>, _>>();
+        Ok(OwnedDocument {
+            prolog: owned(&self.prolog)?,
+            root: self.root(arena).to_owned_element(arena)?,
+            epilog: owned(&self.epilog)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{arena::vec::VecNodeArena, span::Span, writer::Style};
+
+    #[test]
+    fn owned_outlives_input() {
+        fn parse() -> OwnedDocument {
+            let input = String::from("<?xml version=\"1.0\" standalone=\"yes\"?>\n<!DOCTYPE a><a b=\"&amp;\"><!--c--><?pi data?>d &lt; e<f/></a>");
+            let mut arena = VecNodeArena::default();
+            let document = Document::parse(&Span::new_root(&input), &mut arena).unwrap();
+            document.into_owned(&arena).unwrap()
+        }
+
+        let document = std::thread::spawn(parse).join().unwrap();
+        assert_eq!(document.root.name, "a");
+        assert_eq!(document.root.attribute("b"), Some("&"));
+        assert_eq!(document.root.text_content(), "d < e");
+        assert_eq!(document.root.children_named("f").count(), 1);
+        assert_eq!(document.prolog[0], OwnedNode::Declaration { attributes: vec![("version".to_owned(), "1.0".to_owned()), ("standalone".to_owned(), "yes".to_owned())] });
+        assert_eq!(document.root.children[1], OwnedNode::ProcessingInstruction { target: "pi".to_owned(), data: "data".to_owned() });
+
+        let mut writer = Writer::new(String::new(), Style::Compact);
+        document.write(&mut writer).unwrap();
+        assert_eq!(writer.finish().unwrap(), "<?xml version=\"1.0\" standalone=\"yes\"?>\n<!DOCTYPE a><a b=\"&amp;\"><!--c--><?pi data?>d &lt; e<f/></a>");
+    }
+}
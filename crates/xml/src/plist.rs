@@ -0,0 +1,309 @@
+//! Interpretation of parsed Apple [property lists](https://developer.apple.com/library/archive/documentation/Cocoa/Conceptual/PropertyLists/AboutPropertyLists/AboutPropertyLists.html) into typed values.
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use maybe_owned_string::MaybeOwnedString;
+
+use crate::{Element, Node, NodeArena, NodeReferenceCollection};
+use crate::cdata::{XmlCharacterData, error::CharacterEntityDecodingError};
+
+pub mod error {
+    use crate::cdata::error::CharacterEntityDecodingError;
+
+    #[derive(thiserror::Error, Debug, Clone, PartialEq)]
+    pub enum PlistError {
+        #[error("unknown value type \"{0}\"")]
+        UnknownType(String),
+        #[error("expected a <key> within <dict>, found <{0}>")]
+        ExpectedKey(String),
+        #[error("key \"{0}\" has no value")]
+        KeyWithoutValue(String),
+        #[error("<plist> must contain exactly one value")]
+        ExpectedSingleValue,
+        #[error("unexpected text \"{0}\" within <{1}>")]
+        UnexpectedText(String, &'static str),
+        #[error("unexpected declaration within <{0}>")]
+        UnexpectedProlog(&'static str),
+        #[error("<{0}> cannot have children")]
+        UnexpectedChildren(String),
+        #[error("invalid integer: {0}")]
+        InvalidInteger(#[from] core::num::ParseIntError),
+        #[error("invalid real: {0}")]
+        InvalidReal(#[from] core::num::ParseFloatError),
+        #[error("invalid date: {0}")]
+        InvalidDate(#[from] chrono::ParseError),
+        #[error("invalid data: {0}")]
+        InvalidData(#[from] base64::DecodeError),
+        #[error("cannot decode character: {0}")]
+        CharacterEntity(#[from] CharacterEntityDecodingError),
+    }
+}
+
+pub use error::PlistError;
+
+/// A dictionary, with its entries kept in document order.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Dictionary<'a>(pub Vec<(MaybeOwnedString<'a>, PlistValue<'a>)>);
+impl<'a> Dictionary<'a> {
+    pub fn get(&self, key: &str) -> Option<&PlistValue<'a>> {
+        self.0.iter().find(|(k, _)| k == key).map(|(_, value)| value)
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(|(key, _)| key.as_ref())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &PlistValue<'a>)> {
+        self.0.iter().map(|(key, value)| (key.as_ref(), value))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlistValue<'a> {
+    Dictionary(Dictionary<'a>),
+    Array(Vec<PlistValue<'a>>),
+    String(MaybeOwnedString<'a>),
+    Integer(i64),
+    Real(f64),
+    Boolean(bool),
+    Date(DateTime<Utc>),
+    /// Base64-decoded `<data>`.
+    Data(Vec<u8>),
+}
+impl<'a> PlistValue<'a> {
+    /// Interprets the `<plist>` root element, or a bare value element.
+    pub fn from_root<A: NodeArena<'a>>(element: &Element<'a, A>, arena: &A) -> Result<Self, PlistError> {
+        if element.tag_name() != "plist" {
+            return Self::from_element(element, arena)
+        }
+        let mut values = child_elements(element, arena, "plist")?;
+        match (values.next().transpose()?, values.next()) {
+            (Some(value), None) => Self::from_element(value, arena),
+            _ => Err(PlistError::ExpectedSingleValue),
+        }
+    }
+
+    /// Interprets a single value element, such as `<dict>` or `<string>`.
+    ///
+    /// Containers which are still being filled are kept on an explicit stack, rather than being interpreted recursively.
+    pub fn from_element<A: NodeArena<'a>>(element: &Element<'a, A>, arena: &A) -> Result<Self, PlistError> {
+        enum Container<'a> {
+            Dictionary {
+                entries: Vec<(MaybeOwnedString<'a>, PlistValue<'a>)>,
+                /// The key of the value being interpreted.
+                key: Option<MaybeOwnedString<'a>>,
+            },
+            Array(Vec<PlistValue<'a>>),
+        }
+        struct Open<'a, 'b, A: NodeArena<'a>> {
+            container: Container<'a>,
+            /// The child elements yet to be interpreted, in reverse.
+            remaining: Vec<&'b Element<'a, A>>,
+        }
+        fn open<'a, 'b, A: NodeArena<'a>>(element: &'b Element<'a, A>, arena: &'b A, container: Container<'a>, name: &'static str) -> Result<Open<'a, 'b, A>, PlistError> {
+            let mut remaining = child_elements(element, arena, name)?.collect::<Result<Vec<_>, _>>()?;
+            remaining.reverse();
+            Ok(Open { container, remaining })
+        }
+
+        let mut stack: Vec<Open<'a, '_, A>> = Vec::new();
+        let mut next = element;
+        loop {
+            let mut value = match next.tag_name() {
+                "dict" => { stack.push(open(next, arena, Container::Dictionary { entries: Vec::new(), key: None }, "dict")?); None },
+                "array" => { stack.push(open(next, arena, Container::Array(Vec::new()), "array")?); None },
+                _ => Some(Self::from_leaf(next, arena)?),
+            };
+
+            // Hand the value to its container, closing those which are complete, until there's another element to interpret.
+            next = loop {
+                let Some(Open { container, remaining }) = stack.last_mut() else {
+                    return Ok(value.expect("the outermost value should be complete"))
+                };
+                match container {
+                    Container::Dictionary { entries, key } => {
+                        if let Some(value) = value.take() {
+                            entries.push((key.take().expect("a value should have a key"), value));
+                        }
+                        if let Some(child) = remaining.pop() {
+                            if child.tag_name() != "key" { return Err(PlistError::ExpectedKey(child.tag_name().to_owned())) }
+                            let name = text(child, arena)?;
+                            let Some(child) = remaining.pop() else { return Err(PlistError::KeyWithoutValue(name.to_string())) };
+                            *key = Some(name);
+                            break child
+                        }
+                    },
+                    Container::Array(values) => {
+                        if let Some(value) = value.take() {
+                            values.push(value);
+                        }
+                        if let Some(child) = remaining.pop() {
+                            break child
+                        }
+                    },
+                }
+                value = Some(match stack.pop().expect("a container should be open").container {
+                    Container::Dictionary { entries, .. } => Self::Dictionary(Dictionary(entries)),
+                    Container::Array(values) => Self::Array(values),
+                });
+            };
+        }
+    }
+
+    /// Interprets a value element which isn't a container.
+    fn from_leaf<A: NodeArena<'a>>(element: &Element<'a, A>, arena: &A) -> Result<Self, PlistError> {
+        Ok(match element.tag_name() {
+            "string" => Self::String(text(element, arena)?),
+            "integer" => Self::Integer(text(element, arena)?.trim().parse()?),
+            "real" => Self::Real(text(element, arena)?.trim().parse()?),
+            "true" | "false" => {
+                if !element.children.is_empty() { return Err(PlistError::UnexpectedChildren(element.tag_name().to_owned())) }
+                Self::Boolean(element.tag_name() == "true")
+            },
+            "date" => Self::Date(DateTime::parse_from_rfc3339(text(element, arena)?.trim())?.to_utc()),
+            "data" => {
+                let encoded = text(element, arena)?.chars().filter(|char| !char.is_whitespace()).collect::<String>();
+                Self::Data(base64::engine::general_purpose::STANDARD.decode(encoded)?)
+            },
+            other => return Err(PlistError::UnknownType(other.to_owned())),
+        })
+    }
+
+    pub const fn as_dictionary(&self) -> Option<&Dictionary<'a>> {
+        if let Self::Dictionary(dictionary) = self { Some(dictionary) } else { None }
+    }
+
+    pub fn as_array(&self) -> Option<&[PlistValue<'a>]> {
+        if let Self::Array(array) = self { Some(array) } else { None }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        if let Self::String(string) = self { Some(string) } else { None }
+    }
+
+    pub const fn as_integer(&self) -> Option<i64> {
+        if let Self::Integer(integer) = self { Some(*integer) } else { None }
+    }
+
+    pub const fn as_real(&self) -> Option<f64> {
+        if let Self::Real(real) = self { Some(*real) } else { None }
+    }
+
+    pub const fn as_boolean(&self) -> Option<bool> {
+        if let Self::Boolean(boolean) = self { Some(*boolean) } else { None }
+    }
+
+    pub const fn as_date(&self) -> Option<&DateTime<Utc>> {
+        if let Self::Date(date) = self { Some(date) } else { None }
+    }
+
+    pub fn as_data(&self) -> Option<&[u8]> {
+        if let Self::Data(data) = self { Some(data) } else { None }
+    }
+}
+
+impl Drop for PlistValue<'_> {
+    /// Nested values are taken out and dropped one by one, so that deep nesting can't overflow the stack.
+    fn drop(&mut self) {
+        fn take<'a>(value: &mut PlistValue<'a>, into: &mut Vec<PlistValue<'a>>) {
+            match value {
+                PlistValue::Array(values) => into.append(values),
+                PlistValue::Dictionary(dictionary) => into.extend(dictionary.0.drain(..).map(|(_, value)| value)),
+                _ => {}
+            }
+        }
+
+        let mut pending = Vec::new();
+        take(self, &mut pending);
+        while let Some(mut value) = pending.pop() {
+            take(&mut value, &mut pending);
+        }
+    }
+}
+
+/// The child elements of a container, erroring on any non-whitespace text.
+fn child_elements<'a, 'b, A: NodeArena<'a>>(element: &'b Element<'a, A>, arena: &'b A, container: &'static str) -> Result<impl Iterator<Item = Result<&'b Element<'a, A>, PlistError>> + 'b, PlistError> {
+    Ok(element.child_nodes(arena).filter_map(move |node| match node {
+        Node::Element(element) => Some(Ok(element)),
+        Node::Comment(_) | Node::ProcessingInstruction(_) => None,
+        Node::Declaration(_) | Node::DocumentType(_) => Some(Err(PlistError::UnexpectedProlog(container))),
+        Node::Text(text, _) => match text.is_just_whitespace() {
+            Ok(true) => None,
+            Ok(false) => Some(Err(PlistError::UnexpectedText(text.raw().to_owned(), container))),
+            Err(error) => Some(Err(error.clone().into())),
+        }
+    }))
+}
+
+/// The text content of a leaf element, borrowed from the input where possible.
+fn text<'a, A: NodeArena<'a>>(element: &Element<'a, A>, arena: &A) -> Result<MaybeOwnedString<'a>, PlistError> {
+    let mut nodes = element.child_nodes(arena);
+    match (nodes.next(), nodes.next()) {
+        (None, _) => Ok(MaybeOwnedString::Borrowed("")),
+        (Some(Node::Text(XmlCharacterData::Plain(text), _)), None) => Ok(MaybeOwnedString::Borrowed(text)),
+        (Some(Node::Text(XmlCharacterData::WithEntities(text), _)), None) => Ok(MaybeOwnedString::Owned(text.get().map_err(CharacterEntityDecodingError::clone)?.to_owned())),
+        _ => {
+            if element.child_elements(arena).next().is_some() {
+                return Err(PlistError::UnexpectedChildren(element.tag_name().to_owned()))
+            }
+            Ok(MaybeOwnedString::Owned(element.text_content(arena)?))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{arena::vec::VecNodeArena, span::Span, Read};
+
+    fn parse(input: &str) -> Result<PlistValue<'_>, PlistError> {
+        let mut arena = VecNodeArena::default();
+        let Read { value: root, .. } = Node::parse(&Span::new_root(input), &mut arena).unwrap().unwrap();
+        PlistValue::from_root(arena.get(&root).as_element().unwrap(), &arena)
+    }
+
+    #[test]
+    fn values() {
+        let value = parse(concat!(
+            "<plist version=\"1.0\">\n<dict>\n",
+            "\t<key>Name</key><string>Tom &amp; Jerry</string>\n",
+            "\t<key>Track ID</key><integer>-42</integer>\n",
+            "\t<key>Rating</key><real>0.5</real>\n",
+            "\t<key>Loved</key><true/>\n",
+            "\t<key>Date Added</key><date>2020-01-01T00:00:00Z</date>\n",
+            "\t<key>Blob</key><data>\n\taGVs\n\tbG8=\n\t</data>\n",
+            "\t<key>Empty</key><string></string>\n",
+            "\t<key>Raw</key><string><![CDATA[a < b]]></string>\n",
+            "\t<key>List</key><array><false/><!-- :) --><integer>1</integer></array>\n",
+            "</dict>\n</plist>",
+        )).unwrap();
+        let dict = value.as_dictionary().unwrap();
+        assert_eq!(dict.keys().collect::<Vec<_>>(), ["Name", "Track ID", "Rating", "Loved", "Date Added", "Blob", "Empty", "Raw", "List"]);
+        assert_eq!(dict.get("Name").unwrap().as_str(), Some("Tom & Jerry"));
+        assert_eq!(dict.get("Track ID").unwrap().as_integer(), Some(-42));
+        assert_eq!(dict.get("Rating").unwrap().as_real(), Some(0.5));
+        assert_eq!(dict.get("Loved").unwrap().as_boolean(), Some(true));
+        assert_eq!(dict.get("Date Added").unwrap().as_date().unwrap().timestamp(), 1577836800);
+        assert_eq!(dict.get("Blob").unwrap().as_data(), Some(&b"hello"[..]));
+        assert_eq!(dict.get("Empty").unwrap().as_str(), Some(""));
+        assert_eq!(dict.get("Raw").unwrap().as_str(), Some("a < b"));
+        assert_eq!(dict.get("List").unwrap().as_array(), Some(&[PlistValue::Boolean(false), PlistValue::Integer(1)][..]));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(parse("<dict><key>a</key></dict>"), Err(PlistError::KeyWithoutValue("a".to_owned())));
+        assert_eq!(parse("<dict><string>a</string></dict>"), Err(PlistError::ExpectedKey("string".to_owned())));
+        assert_eq!(parse("<set></set>"), Err(PlistError::UnknownType("set".to_owned())));
+        assert_eq!(parse("<array>stray</array>"), Err(PlistError::UnexpectedText("stray".to_owned(), "array")));
+        assert_eq!(parse("<plist><true/><false/></plist>"), Err(PlistError::ExpectedSingleValue));
+        assert!(matches!(parse("<integer>one</integer>"), Err(PlistError::InvalidInteger(_))));
+    }
+}
@@ -0,0 +1,240 @@
+//! Nodes which typically appear before the root element: the XML declaration, the document type declaration, and processing instructions.
+use crate::{Attributes, span::{Span, Spanned}, cdata::XmlCharacterData, defs::WHITESPACE_U8};
+use crate::error::SectionOpenerReadError;
+
+fn skip_whitespace(bytes: &[u8], index: &mut usize) {
+    while bytes.get(*index).is_some_and(|byte| WHITESPACE_U8.contains(byte)) { *index += 1 }
+}
+
+/// A processing instruction (e.x. `<?xml-stylesheet href="style.css"?>`).
+///
+/// The content of a processing instruction isn't interpreted beyond separating out its target.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ProcessingInstruction<'a> {
+    pub span: Span<'a>,
+    target_length: usize,
+}
+impl<'a> ProcessingInstruction<'a> {
+    pub const OPENER: &'static str = "<?";
+    pub const CLOSER: &'static str = "?>";
+
+    /// Parses the processing instruction at the start of the input, which must start with [`Self::OPENER`].
+    pub(crate) fn parse(input: &Span<'a>) -> Result<Self, SectionOpenerReadError<'a>> {
+        // The closer is searched for after the opener, since they overlap in `<?>`.
+        let end = input[Self::OPENER.len()..].find(Self::CLOSER).ok_or(SectionOpenerReadError::TagDidNotClose(*input))? + Self::OPENER.len();
+        let target_length = input[Self::OPENER.len()..end].find(crate::defs::WHITESPACE).unwrap_or(end - Self::OPENER.len());
+        if target_length == 0 { return Err(SectionOpenerReadError::InvalidTagName) }
+        Ok(Self {
+            span: input.range(..end + Self::CLOSER.len()),
+            target_length
+        })
+    }
+
+    /// The name of the application the instruction is directed to (e.x. `xml-stylesheet`).
+    pub fn target(&self) -> Span<'a> {
+        self.span.slice(Self::OPENER.len(), self.target_length)
+    }
+
+    /// Everything after the target, with leading whitespace removed.
+    pub fn data(&self) -> Span<'a> {
+        let mut start = Self::OPENER.len() + self.target_length;
+        skip_whitespace(self.span.as_bytes(), &mut start);
+        let end = self.span.len() - Self::CLOSER.len();
+        self.span.range(start.min(end)..end)
+    }
+}
+impl<'a> Spanned<'a> for ProcessingInstruction<'a> {
+    fn span(&self) -> Span<'a> {
+        self.span
+    }
+}
+
+/// The XML declaration (e.x. `<?xml version="1.0" encoding="UTF-8"?>`).
+#[derive(Debug, PartialEq)]
+pub struct Declaration<'a> {
+    pub span: Span<'a>,
+    pub attributes: Attributes<'a>,
+}
+impl<'a> Declaration<'a> {
+    pub const OPENER: &'static str = "<?xml";
+
+    /// Whether the input starts with a declaration, as opposed to another processing instruction whose target merely starts with `xml`.
+    pub(crate) fn is_at_start(input: &str) -> bool {
+        input.strip_prefix(Self::OPENER).is_some_and(|after| after.starts_with(crate::defs::WHITESPACE) || after.starts_with(ProcessingInstruction::CLOSER))
+    }
+
+    /// Parses the declaration at the start of the input, which must satisfy [`Self::is_at_start`].
+    pub(crate) fn parse(input: &Span<'a>, limits: &crate::Limits) -> Result<Self, SectionOpenerReadError<'a>> {
+        let (attributes, end) = crate::parse_attributes(input, Self::OPENER.len(), crate::TagEnd::ProcessingInstruction, limits)?;
+        Ok(Self { span: input.range(..end), attributes })
+    }
+
+    pub fn version(&self) -> Option<&XmlCharacterData<'a>> {
+        self.attributes.get("version")
+    }
+
+    pub fn encoding(&self) -> Option<&XmlCharacterData<'a>> {
+        self.attributes.get("encoding")
+    }
+
+    /// Returns `None` if the declaration doesn't specify whether the document is standalone, or if the value is neither `yes` nor `no`.
+    pub fn standalone(&self) -> Option<bool> {
+        match self.attributes.get("standalone")?.raw() {
+            "yes" => Some(true),
+            "no" => Some(false),
+            _ => None,
+        }
+    }
+}
+impl<'a> Spanned<'a> for Declaration<'a> {
+    fn span(&self) -> Span<'a> {
+        self.span
+    }
+}
+
+/// A document type declaration (e.x. `<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">`).
+///
+/// The internal subset is kept as-is and not interpreted.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct DocumentType<'a> {
+    pub span: Span<'a>,
+    name: Span<'a>,
+    public_id: Option<Span<'a>>,
+    system_id: Option<Span<'a>>,
+    internal_subset: Option<Span<'a>>,
+}
+impl<'a> DocumentType<'a> {
+    pub const OPENER: &'static str = "<!DOCTYPE";
+
+    /// Parses the document type declaration at the start of the input, which must start with [`Self::OPENER`].
+    pub(crate) fn parse(input: &Span<'a>) -> Result<Self, SectionOpenerReadError<'a>> {
+        let bytes = input.as_bytes();
+        let invalid = || SectionOpenerReadError::InvalidDocumentType(*input);
+        let did_not_close = || SectionOpenerReadError::TagDidNotClose(*input);
+
+        let quoted = |index: &mut usize| -> Result<Span<'a>, SectionOpenerReadError<'a>> {
+            let quote = *bytes.get(*index).ok_or_else(did_not_close)?;
+            if quote != b'"' && quote != b'\'' { return Err(invalid()) }
+            let length = bytes[*index + 1..].iter().position(|byte| *byte == quote).ok_or_else(did_not_close)?;
+            let span = input.slice(*index + 1, length);
+            *index += length + 2;
+            Ok(span)
+        };
+
+        let mut index = Self::OPENER.len();
+        skip_whitespace(bytes, &mut index);
+        let name_start = index;
+        while !matches!(bytes.get(index).ok_or_else(did_not_close)?, b' ' | b'\t' | b'\r' | b'\n' | b'[' | b'>') { index += 1 }
+        if index == name_start { return Err(invalid()) }
+        let name = input.range(name_start..index);
+
+        skip_whitespace(bytes, &mut index);
+        let mut public_id = None;
+        let mut system_id = None;
+        if input[index..].starts_with("PUBLIC") {
+            index += "PUBLIC".len();
+            skip_whitespace(bytes, &mut index);
+            public_id = Some(quoted(&mut index)?);
+            skip_whitespace(bytes, &mut index);
+            if matches!(bytes.get(index), Some(b'"' | b'\'')) {
+                system_id = Some(quoted(&mut index)?);
+            }
+        } else if input[index..].starts_with("SYSTEM") {
+            index += "SYSTEM".len();
+            skip_whitespace(bytes, &mut index);
+            system_id = Some(quoted(&mut index)?);
+        }
+
+        skip_whitespace(bytes, &mut index);
+        let mut internal_subset = None;
+        if bytes.get(index) == Some(&b'[') {
+            let length = input[index + 1..].find(']').ok_or_else(did_not_close)?;
+            internal_subset = Some(input.slice(index + 1, length));
+            index += length + 2;
+            skip_whitespace(bytes, &mut index);
+        }
+
+        match bytes.get(index) {
+            Some(b'>') => Ok(Self {
+                span: input.range(..index + 1),
+                name,
+                public_id,
+                system_id,
+                internal_subset,
+            }),
+            Some(_) => Err(invalid()),
+            None => Err(did_not_close()),
+        }
+    }
+
+    /// The name of the root element (e.x. `plist`).
+    pub const fn name(&self) -> Span<'a> {
+        self.name
+    }
+
+    pub const fn public_id(&self) -> Option<Span<'a>> {
+        self.public_id
+    }
+
+    pub const fn system_id(&self) -> Option<Span<'a>> {
+        self.system_id
+    }
+
+    /// The raw content between the square brackets, if present.
+    pub const fn internal_subset(&self) -> Option<Span<'a>> {
+        self.internal_subset
+    }
+}
+impl<'a> Spanned<'a> for DocumentType<'a> {
+    fn span(&self) -> Span<'a> {
+        self.span
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn document_type() {
+        let input = "<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\"><plist/>";
+        let doctype = DocumentType::parse(&Span::new_root(input)).unwrap();
+        assert_eq!(doctype.span.len(), input.len() - "<plist/>".len());
+        assert_eq!(doctype.name(), "plist");
+        assert_eq!(doctype.public_id().unwrap(), "-//Apple//DTD PLIST 1.0//EN");
+        assert_eq!(doctype.system_id().unwrap(), "http://www.apple.com/DTDs/PropertyList-1.0.dtd");
+        assert!(doctype.internal_subset().is_none());
+
+        let doctype = DocumentType::parse(&Span::new_root("<!DOCTYPE note SYSTEM 'note.dtd' [ <!ENTITY a \"b\"> ]>")).unwrap();
+        assert_eq!(doctype.name(), "note");
+        assert!(doctype.public_id().is_none());
+        assert_eq!(doctype.system_id().unwrap(), "note.dtd");
+        assert_eq!(doctype.internal_subset().unwrap(), " <!ENTITY a \"b\"> ");
+
+        assert!(matches!(DocumentType::parse(&Span::new_root("<!DOCTYPE html")), Err(SectionOpenerReadError::TagDidNotClose(_))));
+        assert!(matches!(DocumentType::parse(&Span::new_root("<!DOCTYPE html PUBLIC oops>")), Err(SectionOpenerReadError::InvalidDocumentType(_))));
+    }
+
+    #[test]
+    fn declaration() {
+        let input = Span::new_root("<?xml version=\"1.0\" encoding='UTF-8' standalone=\"yes\" ?>\n<a/>");
+        assert!(Declaration::is_at_start(&input));
+        assert!(!Declaration::is_at_start("<?xml-stylesheet href=\"a.css\"?>"));
+        let declaration = Declaration::parse(&input, &crate::Limits::UNLIMITED).unwrap();
+        assert_eq!(declaration.span.len(), input.find('\n').unwrap());
+        assert_eq!(declaration.version().unwrap().raw(), "1.0");
+        assert_eq!(declaration.encoding().unwrap().raw(), "UTF-8");
+        assert_eq!(declaration.standalone(), Some(true));
+    }
+
+    #[test]
+    fn processing_instruction() {
+        let instruction = ProcessingInstruction::parse(&Span::new_root("<?xml-stylesheet  href=\"a.css\"?><a/>")).unwrap();
+        assert_eq!(instruction.target(), "xml-stylesheet");
+        assert_eq!(instruction.data(), "href=\"a.css\"");
+        let instruction = ProcessingInstruction::parse(&Span::new_root("<?empty?>")).unwrap();
+        assert_eq!(instruction.target(), "empty");
+        assert_eq!(instruction.data(), "");
+        assert_eq!(ProcessingInstruction::parse(&Span::new_root("<?>")), Err(SectionOpenerReadError::TagDidNotClose(Span::new_root("<?>"))));
+    }
+}
@@ -0,0 +1,174 @@
+//! Lightweight path-based lookups over parsed elements.
+//!
+//! Paths are `/`-separated element names relative to the element being queried (e.x. `dict/key`),
+//! where a segment of `*` matches an element of any name.
+//!
+//! Elements can also be walked through in their entirety with [`Element::descendants`] and [`Element::descendants_breadth_first`],
+//! neither of which recurse (so deep nesting can't overflow the stack).
+use std::collections::VecDeque;
+
+use crate::{Element, Node, NodeArena, NodeReferenceCollection};
+use crate::cdata::error::CharacterEntityDecodingError;
+
+fn segments(path: &str) -> impl Iterator<Item = &str> + Clone {
+    path.split('/').filter(|segment| !segment.is_empty())
+}
+
+fn matches_segment<'a, A: NodeArena<'a>>(element: &Element<'a, A>, segment: &str) -> bool {
+    segment == "*" || element.tag_name() == segment
+}
+
+impl<'a, A: NodeArena<'a>> Element<'a, A> {
+    /// Iterates over the nodes directly within this element.
+    pub fn child_nodes<'b>(&'b self, arena: &'b A) -> impl Iterator<Item = &'b Node<'a, A>> + 'b {
+        self.children.iter().map(|reference| arena.get(reference))
+    }
+
+    /// Iterates over the elements directly within this element, skipping text and comments.
+    pub fn child_elements<'b>(&'b self, arena: &'b A) -> impl Iterator<Item = &'b Element<'a, A>> + 'b {
+        self.child_nodes(arena).filter_map(Node::as_element)
+    }
+
+    /// Iterates over the elements directly within this element with the given tag name.
+    pub fn children_named<'b>(&'b self, arena: &'b A, name: &'b str) -> impl Iterator<Item = &'b Element<'a, A>> + 'b {
+        self.child_elements(arena).filter(move |element| element.tag_name() == name)
+    }
+
+    /// Iterates over every node within this element, however deeply nested, depth-first (which is document order).
+    pub fn descendants<'b>(&'b self, arena: &'b A) -> DepthFirst<'a, 'b, A> {
+        let mut pending = self.child_nodes(arena).collect::<Vec<_>>();
+        pending.reverse();
+        DepthFirst { arena, pending }
+    }
+
+    /// Iterates over every node within this element, however deeply nested, breadth-first;
+    /// all of the children come before any of the grandchildren, and so on.
+    pub fn descendants_breadth_first<'b>(&'b self, arena: &'b A) -> BreadthFirst<'a, 'b, A> {
+        BreadthFirst { arena, pending: self.child_nodes(arena).collect() }
+    }
+
+    /// Iterates over every element within this element, however deeply nested, in document order.
+    pub fn descendant_elements<'b>(&'b self, arena: &'b A) -> impl Iterator<Item = &'b Element<'a, A>> + 'b {
+        self.descendants(arena).filter_map(Node::as_element)
+    }
+
+    /// Returns the first element matching the path, in document order.
+    /// An empty path matches this element.
+    pub fn find<'b>(&'b self, arena: &'b A, path: &str) -> Option<&'b Element<'a, A>> {
+        let segments = segments(path).collect::<Vec<_>>();
+        // Depth-first, with each element paired with how many of the segments lead up to it.
+        let mut pending = vec![(self, 0)];
+        while let Some((element, matched)) = pending.pop() {
+            let Some(segment) = segments.get(matched) else { return Some(element) };
+            let start = pending.len();
+            pending.extend(element.child_elements(arena).filter(|child| matches_segment(child, segment)).map(|child| (child, matched + 1)));
+            // So that the earliest is popped first.
+            pending[start..].reverse();
+        }
+        None
+    }
+
+    /// Returns all elements matching the path, in document order.
+    pub fn find_all<'b>(&'b self, arena: &'b A, path: &str) -> Vec<&'b Element<'a, A>> {
+        let mut matched = vec![self];
+        for segment in segments(path) {
+            matched = matched.into_iter()
+                .flat_map(|element| element.child_elements(arena))
+                .filter(|child| matches_segment(child, segment))
+                .collect();
+        }
+        matched
+    }
+
+    /// Concatenates all text within this element and its descendants, with entities decoded.
+    /// Comments are skipped.
+    pub fn text_content(&self, arena: &A) -> Result<String, CharacterEntityDecodingError> {
+        let mut out = String::new();
+        for node in self.descendants(arena) {
+            if let Node::Text(text, _) = node {
+                out.push_str(text.get().map_err(Clone::clone)?);
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// A depth-first walk through the descendants of an element, created by [`Element::descendants`].
+pub struct DepthFirst<'a, 'b, A: NodeArena<'a>> {
+    arena: &'b A,
+    /// The nodes yet to be visited, in reverse, so that the earliest is popped first.
+    pending: Vec<&'b Node<'a, A>>,
+}
+impl<'a, 'b, A: NodeArena<'a>> Iterator for DepthFirst<'a, 'b, A> {
+    type Item = &'b Node<'a, A>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.pending.pop()?;
+        if let Node::Element(element) = node {
+            let start = self.pending.len();
+            self.pending.extend(element.child_nodes(self.arena));
+            self.pending[start..].reverse();
+        }
+        Some(node)
+    }
+}
+
+/// A breadth-first walk through the descendants of an element, created by [`Element::descendants_breadth_first`].
+pub struct BreadthFirst<'a, 'b, A: NodeArena<'a>> {
+    arena: &'b A,
+    pending: VecDeque<&'b Node<'a, A>>,
+}
+impl<'a, 'b, A: NodeArena<'a>> Iterator for BreadthFirst<'a, 'b, A> {
+    type Item = &'b Node<'a, A>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.pending.pop_front()?;
+        if let Node::Element(element) = node {
+            self.pending.extend(element.child_nodes(self.arena));
+        }
+        Some(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{arena::vec::VecNodeArena, span::Span, Element, Node, Read, NodeArena};
+
+    const INPUT: &str = "<plist><dict><key>a</key><string>one</string><key>b &amp; c</key><dict><key>nested</key></dict></dict></plist>";
+
+    #[test]
+    fn queries() {
+        let span = Span::new_root(INPUT);
+        let mut arena = VecNodeArena::default();
+        let Read { value: root, .. } = Node::parse(&span, &mut arena).unwrap().unwrap();
+        let root = arena.get(&root).as_element().unwrap();
+
+        assert_eq!(root.find(&arena, "dict/key").unwrap().text_content(&arena).unwrap(), "a");
+        assert_eq!(root.find(&arena, "dict/dict/key").unwrap().text_content(&arena).unwrap(), "nested");
+        assert_eq!(root.find(&arena, "*/string").unwrap().text_content(&arena).unwrap(), "one");
+        assert!(root.find(&arena, "dict/array").is_none());
+        assert_eq!(root.find(&arena, "").unwrap().tag_name(), "plist");
+
+        let dict = root.find(&arena, "dict").unwrap();
+        let keys = dict.children_named(&arena, "key").map(|key| key.text_content(&arena).unwrap()).collect::<Vec<_>>();
+        assert_eq!(keys, ["a", "b & c"]);
+        assert_eq!(root.find_all(&arena, "dict/*/key").len(), 1);
+        assert_eq!(root.find_all(&arena, "*/key").len(), 2);
+    }
+
+    #[test]
+    fn descendants() {
+        let span = Span::new_root(INPUT);
+        let mut arena = VecNodeArena::default();
+        let Read { value: root, .. } = Node::parse(&span, &mut arena).unwrap().unwrap();
+        let root = arena.get(&root).as_element().unwrap();
+
+        fn names<'a>(nodes: impl Iterator<Item = &'a Node<'a, VecNodeArena<'a>>>) -> Vec<&'a str> {
+            nodes.map(|node| node.as_element().map_or("#text", Element::tag_name)).collect()
+        }
+        assert_eq!(names(root.descendants(&arena)), ["dict", "key", "#text", "string", "#text", "key", "#text", "dict", "key", "#text"]);
+        assert_eq!(names(root.descendants_breadth_first(&arena)), ["dict", "key", "string", "key", "dict", "#text", "#text", "#text", "key", "#text"]);
+        assert_eq!(root.descendant_elements(&arena).filter(|element| element.tag_name() == "key").count(), 3);
+
+        let empty = root.find(&arena, "dict/dict/key").unwrap().find(&arena, "").unwrap();
+        assert_eq!(empty.descendant_elements(&arena).count(), 0);
+    }
+}
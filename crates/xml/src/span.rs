@@ -51,8 +51,29 @@ impl<'a> Span<'a> {
         }
     }
     
+    /// The range of bytes this span covers within the top-level string.
+    pub const fn byte_range(&self) -> core::ops::Range<usize> {
+        self.offset..(self.offset + self.length)
+    }
+
+    /// The top-level string, up to the given offset.
+    const fn top_until(&self, offset: usize) -> &'a str {
+        let slice = unsafe { core::slice::from_raw_parts(self.top, offset) };
+        unsafe { core::str::from_utf8_unchecked(slice) }
+    }
+
+    /// Computes the location of the start of this span.
+    ///
+    /// This scans everything preceding the span; use a [`LineIndex`] if many spans are to be located.
     pub fn start_location(&self) -> SingleFileLocation {
-        SingleFileLocation::from(self)
+        SingleFileLocation::at_end_of(self.top_until(self.offset))
+    }
+
+    /// Computes the location directly after the end of this span.
+    ///
+    /// This scans everything preceding the end of the span; use a [`LineIndex`] if many spans are to be located.
+    pub fn end_location(&self) -> SingleFileLocation {
+        SingleFileLocation::at_end_of(self.top_until(self.offset + self.length))
     }
 
     /// View the string content of the span.
@@ -183,39 +204,130 @@ impl SpanSlicer for core::ops::RangeToInclusive<usize> {
 
 /// The location of a character in a document, zero-indexed.
 /// The [`core::fmt::Display`] implementation prints it out as one-indexed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct SingleFileLocation {
     pub line: u32,
+    /// The column, in characters.
     pub column: u32,
+    /// The offset from the start of the document, in bytes.
+    pub offset: usize,
 }
-impl<'a> From<&'a Span<'a>> for SingleFileLocation {
-    fn from(span: &'a Span) -> Self {
-        let mut row = 0;
-        let mut col = 0;
-        let before = span.as_str();
-        for char in before.chars() {
-            if char == '\n' {
-                col = 0;
-                row += 1;
-            } else {
-                col += 1;
-            }
-        };
+impl SingleFileLocation {
+    /// Computes the location at the end of the given text, which should span from the start of the document.
+    fn at_end_of(preceding: &str) -> Self {
+        let line_start = preceding.rfind('\n').map_or(0, |newline| newline + 1);
         Self {
-            line: row,
-            column: col
+            line: preceding.as_bytes().iter().filter(|byte| **byte == b'\n').count() as u32,
+            column: preceding[line_start..].chars().count() as u32,
+            offset: preceding.len(),
         }
     }
 }
+impl<'a> From<&'a Span<'a>> for SingleFileLocation {
+    fn from(span: &'a Span) -> Self {
+        span.start_location()
+    }
+}
 impl core::fmt::Display for SingleFileLocation {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "line {} column {}", self.line + 1, self.column + 1)
     }
 }
 
+/// The starting offsets of each line of a document, for cheaply locating spans within it.
+#[derive(Debug, Clone)]
+pub struct LineIndex<'a> {
+    source: &'a str,
+    line_starts: Vec<usize>,
+}
+impl<'a> LineIndex<'a> {
+    pub fn new(source: &'a str) -> Self {
+        let newlines = source.bytes().enumerate().filter(|(_, byte)| *byte == b'\n').map(|(index, _)| index + 1);
+        Self {
+            source,
+            line_starts: core::iter::once(0).chain(newlines).collect(),
+        }
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Locates a byte offset within the document.
+    ///
+    /// # Panics
+    /// If the offset is out of bounds or not on a character boundary.
+    pub fn locate(&self, offset: usize) -> SingleFileLocation {
+        let line = self.line_starts.partition_point(|start| *start <= offset) - 1;
+        SingleFileLocation {
+            line: line as u32,
+            column: self.source[self.line_starts[line]..offset].chars().count() as u32,
+            offset,
+        }
+    }
+
+    fn assert_contains(&self, span: &Span<'_>) {
+        debug_assert!(core::ptr::eq(span.top, self.source.as_ptr()), "span is not from the indexed document");
+    }
+}
+
+/// Something which occupies a contiguous region of a document.
+pub trait Spanned<'a> {
+    fn span(&self) -> Span<'a>;
+
+    fn byte_range(&self) -> core::ops::Range<usize> {
+        self.span().byte_range()
+    }
+
+    /// See [`Span::start_location`].
+    fn start_location(&self) -> SingleFileLocation {
+        self.span().start_location()
+    }
+
+    /// See [`Span::end_location`].
+    fn end_location(&self) -> SingleFileLocation {
+        self.span().end_location()
+    }
+
+    fn start_location_in(&self, index: &LineIndex<'_>) -> SingleFileLocation {
+        let span = self.span();
+        index.assert_contains(&span);
+        index.locate(span.offset)
+    }
+
+    fn end_location_in(&self, index: &LineIndex<'_>) -> SingleFileLocation {
+        let span = self.span();
+        index.assert_contains(&span);
+        index.locate(span.offset + span.length)
+    }
+}
+impl<'a> Spanned<'a> for Span<'a> {
+    fn span(&self) -> Span<'a> {
+        *self
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn locations() {
+        const INPUT: &str = "<a>\n\t<bé>\r\n  <c/>\n</a>";
+        let index = LineIndex::new(INPUT);
+        let root = Span::new_root(INPUT);
+        let c = root.range(INPUT.find("<c/>").unwrap()..INPUT.find("\n</a>").unwrap());
+        assert_eq!(c.start_location(), SingleFileLocation { line: 2, column: 2, offset: 14 });
+        assert_eq!(c.end_location(), SingleFileLocation { line: 2, column: 6, offset: 18 });
+        assert_eq!(c.start_location_in(&index), c.start_location());
+        assert_eq!(c.end_location_in(&index), c.end_location());
+        let after_accent = root.range(INPUT.find('é').unwrap() + 'é'.len_utf8()..);
+        assert_eq!(after_accent.start_location_in(&index), SingleFileLocation { line: 1, column: 4, offset: 9 });
+        assert_eq!(after_accent.start_location().to_string(), "line 2 column 5");
+        assert_eq!(root.end_location_in(&index), SingleFileLocation { line: 3, column: 4, offset: INPUT.len() });
+        assert_eq!(index.line_count(), 4);
+    }
+
     mod span {
         use super::*;
 
@@ -0,0 +1,337 @@
+//! Document writing, through an escaping-aware builder.
+//!
+//! ```
+//! use xml::writer::{Writer, Style};
+//!
+//! let mut writer = Writer::new(String::new(), Style::Compact);
+//! writer.start("dict")?;
+//! writer.start("key")?.text("Tom & Jerry")?.end()?;
+//! writer.start("true")?.end()?;
+//! writer.end()?;
+//! assert_eq!(writer.finish()?, "<dict><key>Tom &amp; Jerry</key><true/></dict>");
+//! # Ok::<(), xml::writer::WriteError>(())
+//! ```
+use std::borrow::Cow;
+
+use crate::block_span::{BlockSpan, CharacterDataBlockSpan, CommentSpan};
+
+pub mod error {
+    #[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+    pub enum WriteError {
+        #[error("formatting error")]
+        Fmt(#[from] core::fmt::Error),
+        #[error("attributes can only be written directly after an element is started")]
+        AttributeOutsideOfTag,
+        #[error("no element is open")]
+        NoOpenElement,
+        #[error("{0} element(s) were left unclosed")]
+        UnclosedElements(usize),
+        #[error("invalid name {0:?}")]
+        InvalidName(String),
+        #[error("character data cannot contain \"]]>\"")]
+        InvalidCharacterData,
+        #[error("comments cannot contain \"--\"")]
+        InvalidComment,
+        #[error("processing instructions cannot contain \"?>\"")]
+        InvalidProcessingInstruction,
+        #[error("identifier {0:?} cannot contain both kinds of quotes")]
+        InvalidIdentifier(String),
+    }
+}
+
+pub use error::WriteError;
+
+/// Escapes text for use as the content of an element.
+///
+/// Carriage returns are written as character references, as parsers would otherwise normalize them away.
+pub fn escape_text(text: &str) -> Cow<'_, str> {
+    escape(text, |char| matches!(char, '&' | '<' | '>' | '\r'))
+}
+
+/// Escapes text for use as the value of an attribute.
+///
+/// Whitespace other than spaces is written as character references, as parsers would otherwise normalize it into spaces.
+pub fn escape_attribute(text: &str) -> Cow<'_, str> {
+    escape(text, |char| matches!(char, '&' | '<' | '>' | '"' | '\'' | '\t' | '\n' | '\r'))
+}
+
+fn escape(text: &str, needs_escaping: impl Fn(char) -> bool) -> Cow<'_, str> {
+    if !text.contains(&needs_escaping) {
+        return Cow::Borrowed(text)
+    }
+
+    let mut out = String::with_capacity(text.len() + 8);
+    for char in text.chars() {
+        if needs_escaping(char) {
+            out.push_str(match char {
+                '&' => "&amp;",
+                '<' => "&lt;",
+                '>' => "&gt;",
+                '"' => "&quot;",
+                '\'' => "&apos;",
+                '\t' => "&#x9;",
+                '\n' => "&#xA;",
+                '\r' => "&#xD;",
+                _ => unreachable!("no entity for {char:?}")
+            });
+        } else {
+            out.push(char);
+        }
+    }
+    Cow::Owned(out)
+}
+
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|char| char.is_alphabetic() || char == '_' || char == ':')
+        && chars.all(|char| char.is_alphanumeric() || matches!(char, '_' | ':' | '-' | '.'))
+}
+
+/// How the output should be formatted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Style {
+    /// No whitespace is added between nodes.
+    #[default]
+    Compact,
+    /// Each element and comment is placed on its own line, indented by its depth.
+    /// Elements which only contain text are kept on one line.
+    Pretty {
+        indent: &'static str,
+    },
+}
+
+#[derive(Debug)]
+struct OpenElement {
+    name: String,
+    /// Whether an element or comment has been written within this element, in which case the closing tag is put on its own line.
+    has_block_children: bool,
+}
+
+/// An escaping-aware XML builder.
+///
+/// Text and attribute values are escaped as they're written; names and the content of comments and CDATA blocks are validated instead.
+#[derive(Debug)]
+pub struct Writer<W: core::fmt::Write> {
+    out: W,
+    style: Style,
+    open: Vec<OpenElement>,
+    /// Whether the opening tag of the innermost element hasn't yet been terminated with a `>`, such that attributes can still be written.
+    tag_pending: bool,
+    written: bool,
+}
+impl<W: core::fmt::Write> Writer<W> {
+    pub fn new(out: W, style: Style) -> Self {
+        Self { out, style, open: Vec::new(), tag_pending: false, written: false }
+    }
+
+    fn terminate_pending_tag(&mut self) -> Result<(), WriteError> {
+        if self.tag_pending {
+            self.out.write_char('>')?;
+            self.tag_pending = false;
+        }
+        Ok(())
+    }
+
+    /// Prepares to write a node which sits on its own line when pretty-printing.
+    fn begin_block(&mut self) -> Result<(), WriteError> {
+        self.terminate_pending_tag()?;
+        if let Some(parent) = self.open.last_mut() {
+            parent.has_block_children = true;
+        }
+        if let Style::Pretty { indent } = self.style {
+            if self.written { self.out.write_char('\n')?; }
+            for _ in 0..self.open.len() { self.out.write_str(indent)?; }
+        }
+        self.written = true;
+        Ok(())
+    }
+
+    /// Writes an XML declaration; this should only be done before anything else is written.
+    pub fn declaration(&mut self, version: &str, encoding: Option<&str>, standalone: Option<bool>) -> Result<&mut Self, WriteError> {
+        self.begin_block()?;
+        write!(self.out, "<?xml version=\"{}\"", escape_attribute(version))?;
+        if let Some(encoding) = encoding {
+            write!(self.out, " encoding=\"{}\"", escape_attribute(encoding))?;
+        }
+        if let Some(standalone) = standalone {
+            write!(self.out, " standalone=\"{}\"", if standalone { "yes" } else { "no" })?;
+        }
+        self.out.write_str("?>")?;
+        Ok(self)
+    }
+
+    /// Writes a document type declaration; the internal subset, if any, is written verbatim.
+    pub fn document_type(&mut self, name: &str, public_id: Option<&str>, system_id: Option<&str>, internal_subset: Option<&str>) -> Result<&mut Self, WriteError> {
+        fn quoted(id: &str) -> Result<String, WriteError> {
+            match (id.contains('"'), id.contains('\'')) {
+                (false, _) => Ok(format!("\"{id}\"")),
+                (true, false) => Ok(format!("'{id}'")),
+                (true, true) => Err(WriteError::InvalidIdentifier(id.to_owned())),
+            }
+        }
+
+        if !is_valid_name(name) { return Err(WriteError::InvalidName(name.to_owned())) }
+        self.begin_block()?;
+        write!(self.out, "{} {name}", crate::prolog::DocumentType::OPENER)?;
+        match (public_id, system_id) {
+            (Some(public_id), system_id) => {
+                write!(self.out, " PUBLIC {}", quoted(public_id)?)?;
+                if let Some(system_id) = system_id { write!(self.out, " {}", quoted(system_id)?)?; }
+            },
+            (None, Some(system_id)) => write!(self.out, " SYSTEM {}", quoted(system_id)?)?,
+            (None, None) => {}
+        }
+        if let Some(internal_subset) = internal_subset {
+            write!(self.out, " [{internal_subset}]")?;
+        }
+        self.out.write_char('>')?;
+        Ok(self)
+    }
+
+    pub fn processing_instruction(&mut self, target: &str, data: &str) -> Result<&mut Self, WriteError> {
+        use crate::prolog::ProcessingInstruction;
+        if !is_valid_name(target) || target.eq_ignore_ascii_case("xml") { return Err(WriteError::InvalidName(target.to_owned())) }
+        if data.contains(ProcessingInstruction::CLOSER) { return Err(WriteError::InvalidProcessingInstruction) }
+        self.begin_block()?;
+        write!(self.out, "{}{target}", ProcessingInstruction::OPENER)?;
+        if !data.is_empty() { write!(self.out, " {data}")?; }
+        self.out.write_str(ProcessingInstruction::CLOSER)?;
+        Ok(self)
+    }
+
+    /// Starts a new element, which must later be closed with [`Writer::end`].
+    pub fn start(&mut self, name: &str) -> Result<&mut Self, WriteError> {
+        if !is_valid_name(name) { return Err(WriteError::InvalidName(name.to_owned())) }
+        self.begin_block()?;
+        write!(self.out, "<{name}")?;
+        self.open.push(OpenElement { name: name.to_owned(), has_block_children: false });
+        self.tag_pending = true;
+        Ok(self)
+    }
+
+    /// Adds an attribute to the element which was just started.
+    pub fn attribute(&mut self, key: &str, value: &str) -> Result<&mut Self, WriteError> {
+        if !self.tag_pending { return Err(WriteError::AttributeOutsideOfTag) }
+        if !is_valid_name(key) { return Err(WriteError::InvalidName(key.to_owned())) }
+        write!(self.out, " {key}=\"{}\"", escape_attribute(value))?;
+        Ok(self)
+    }
+
+    /// Writes text, escaping it as necessary.
+    pub fn text(&mut self, text: &str) -> Result<&mut Self, WriteError> {
+        self.terminate_pending_tag()?;
+        self.out.write_str(&escape_text(text))?;
+        self.written = true;
+        Ok(self)
+    }
+
+    /// Writes an unescaped character data block.
+    pub fn cdata(&mut self, text: &str) -> Result<&mut Self, WriteError> {
+        if text.contains(CharacterDataBlockSpan::CLOSER) { return Err(WriteError::InvalidCharacterData) }
+        self.terminate_pending_tag()?;
+        write!(self.out, "{}{text}{}", CharacterDataBlockSpan::OPENER, CharacterDataBlockSpan::CLOSER)?;
+        self.written = true;
+        Ok(self)
+    }
+
+    pub fn comment(&mut self, text: &str) -> Result<&mut Self, WriteError> {
+        if text.contains("--") || text.ends_with('-') { return Err(WriteError::InvalidComment) }
+        self.begin_block()?;
+        write!(self.out, "{}{text}{}", CommentSpan::OPENER, CommentSpan::CLOSER)?;
+        Ok(self)
+    }
+
+    /// Closes the innermost open element; if it has no content, it's written as self-closing.
+    pub fn end(&mut self) -> Result<&mut Self, WriteError> {
+        let element = self.open.pop().ok_or(WriteError::NoOpenElement)?;
+        if self.tag_pending {
+            self.out.write_str("/>")?;
+            self.tag_pending = false;
+            return Ok(self)
+        }
+        if let Style::Pretty { indent } = self.style {
+            if element.has_block_children {
+                self.out.write_char('\n')?;
+                for _ in 0..self.open.len() { self.out.write_str(indent)?; }
+            }
+        }
+        write!(self.out, "</{}>", element.name)?;
+        Ok(self)
+    }
+
+    /// The amount of elements which are currently open.
+    pub fn depth(&self) -> usize {
+        self.open.len()
+    }
+
+    /// Returns the output, erroring if any elements were left open.
+    pub fn finish(self) -> Result<W, WriteError> {
+        if !self.open.is_empty() { return Err(WriteError::UnclosedElements(self.open.len())) }
+        Ok(self.out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escaping() {
+        assert!(matches!(escape_text("plain"), Cow::Borrowed("plain")));
+        assert_eq!(escape_text("<a> & \"b\""), "&lt;a&gt; &amp; \"b\"");
+        assert_eq!(escape_attribute("it's \"quoted\""), "it&apos;s &quot;quoted&quot;");
+    }
+
+    #[test]
+    fn round_trip() {
+        use crate::{arena::{vec::VecNodeArena, NodeArena}, span::Span, Node, Read};
+
+        const TEXT: &str = "<&amp;> \"quoted\" 'single'\r\n\t©";
+        let mut writer = Writer::new(String::new(), Style::Compact);
+        writer.start("a").unwrap().attribute("b", TEXT).unwrap().text(TEXT).unwrap().end().unwrap();
+        let output = writer.finish().unwrap();
+
+        let mut arena = VecNodeArena::default();
+        let Read { value: root, .. } = Node::parse(&Span::new_root(&output), &mut arena).unwrap().unwrap();
+        let root = arena.get(&root).as_element().unwrap();
+        assert_eq!(root.attributes().get("b").unwrap().get(), Ok(TEXT));
+        assert_eq!(root.text_content(&arena).unwrap(), TEXT);
+    }
+
+    #[test]
+    fn pretty() {
+        let mut writer = Writer::new(String::new(), Style::Pretty { indent: "\t" });
+        writer.declaration("1.0", Some("UTF-8"), None).unwrap();
+        writer.start("plist").unwrap().attribute("version", "1.0").unwrap();
+        writer.start("dict").unwrap();
+        writer.comment(" hi ").unwrap();
+        writer.start("key").unwrap().text("name").unwrap().end().unwrap();
+        writer.start("string").unwrap().cdata("a < b").unwrap().end().unwrap();
+        writer.start("array").unwrap().end().unwrap();
+        writer.end().unwrap();
+        writer.end().unwrap();
+        assert_eq!(writer.finish().unwrap(), concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<plist version=\"1.0\">\n",
+            "\t<dict>\n",
+            "\t\t<!-- hi -->\n",
+            "\t\t<key>name</key>\n",
+            "\t\t<string><![CDATA[a < b]]></string>\n",
+            "\t\t<array/>\n",
+            "\t</dict>\n",
+            "</plist>",
+        ));
+    }
+
+    #[test]
+    fn misuse() {
+        let mut writer = Writer::new(String::new(), Style::Compact);
+        assert_eq!(writer.end().err(), Some(WriteError::NoOpenElement));
+        assert_eq!(writer.start("1abc").err(), Some(WriteError::InvalidName("1abc".to_owned())));
+        writer.start("a").unwrap().text("b").unwrap();
+        assert_eq!(writer.attribute("c", "d").err(), Some(WriteError::AttributeOutsideOfTag));
+        assert_eq!(writer.cdata("]]>").err(), Some(WriteError::InvalidCharacterData));
+        assert_eq!(writer.comment("a--b").err(), Some(WriteError::InvalidComment));
+        assert_eq!(writer.finish().err(), Some(WriteError::UnclosedElements(1)));
+    }
+}
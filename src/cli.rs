@@ -1,10 +1,11 @@
 
-use clap_verbosity_flag::Verbosity;
+use clap_verbosity_flag::{InfoLevel, Verbosity};
 use clap::{Parser, Subcommand};
 
 /// Apple Music status utility for MacOS.
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
+#[allow(clippy::struct_excessive_bools, reason = "they're independent flags")]
 pub struct Cli {
     /// The path to the configuration file to load.
     #[arg(short, long = "config", value_name = "PATH", global = true)]
@@ -14,7 +15,20 @@ pub struct Cli {
     pub running_as_service: bool,
 
     #[command(flatten)]
-    pub verbose: Verbosity,
+    pub verbose: Verbosity<InfoLevel>,
+
+    /// Don't color output, even when it's to a terminal.
+    #[arg(long, global = true, default_value = "false")]
+    pub no_color: bool,
+
+    /// Print information as JSON instead of prose, for use by scripts.
+    #[arg(long, global = true, default_value = "false")]
+    pub json: bool,
+
+    /// Never prompt; take the default answer instead, or fail if there isn't one.
+    /// This is implied when standard input isn't a terminal, or there's no graphical login session (such as over SSH).
+    #[arg(long, global = true, default_value = "false", env = "AM_OSX_STATUS_NON_INTERACTIVE")]
+    pub non_interactive: bool,
 
     #[command(subcommand)]
     pub command: Command,
@@ -33,6 +47,57 @@ pub enum Command {
         /// Kill any existing instances before starting.
         #[arg(short, long, default_value = "false", hide = true)]
         kill_existing: bool,
+        /// Log what every backend would submit instead of submitting it.
+        #[arg(long, default_value = "false")]
+        dry_run: bool,
+    },
+    /// Print the currently playing track once, according to a template.
+    Now {
+        /// The template to print.
+        ///
+        /// Placeholders: `{title}`, `{artist}`, `{album}`, `{album_artist}`, `{track_number}`, `{elapsed}`, `{duration}`,
+        /// `{remaining}`, `{progress}` (a percentage), `{state}` (playing, paused, or stopped), and `{id}` (the persistent ID).
+        /// Placeholders without a value are left empty; literal braces are written as `{{` and `}}`.
+        #[arg(short, long, value_name = "TEMPLATE", default_value = crate::now::DEFAULT_FORMAT)]
+        format: String,
+    },
+    /// Print the most recent listens.
+    History {
+        /// Print where podcast episodes and audiobooks were left off instead.
+        #[arg(long, default_value = "false")]
+        podcasts: bool,
+        /// The most entries to print.
+        #[arg(short = 'n', long, default_value = "20")]
+        limit: u32,
+    },
+    /// Give the listens recorded since a point in time to a backend again, such as after fixing its credentials.
+    /// Only listens recorded on this machine can be replayed.
+    Replay {
+        /// How far back to go; a date (`2025-03-14`), a time (`2025-03-14T09:30:00Z`), or an age (`36h`, `5d`, `2w`).
+        #[arg(long, value_name = "TIME", value_parser = parse_since)]
+        since: chrono::DateTime<chrono::Utc>,
+        /// The configuration key of the backend to replay to, like `lastfm` or `listenbrainz`.
+        #[arg(long, value_name = "BACKEND", value_parser = parse_backend)]
+        backend: crate::subscribers::BackendIdentity,
+        /// Log what would be submitted instead of submitting it.
+        #[arg(long, default_value = "false")]
+        dry_run: bool,
+    },
+    /// Inspect or correct the uncensored title cached for a track.
+    Uncensor {
+        #[command(subcommand)]
+        action: UncensorAction
+    },
+    /// Seed the local listening history with data from elsewhere.
+    #[cfg(feature = "musicdb")]
+    Import {
+        #[command(subcommand)]
+        source: ImportSource
+    },
+    /// Gather what's useful for a bug report, to be attached to an issue.
+    Report {
+        #[command(subcommand)]
+        action: ReportAction
     },
     /// Configure the application.
     #[clap(visible_alias("config"))]
@@ -46,7 +111,11 @@ pub enum Command {
 #[derive(Subcommand)]
 pub enum ServiceAction {
     /// Start the background service. It will then automatically start on every login.
-    Start,
+    Start {
+        /// If an instance is already running outside of the service, have it exit so that the service can take its place.
+        #[arg(long, default_value = "false")]
+        adopt: bool,
+    },
     /// Stop the background service. It will start again on the next login, or when started again manually.
     Stop,
     /// Log information about the status of the background service.
@@ -55,11 +124,100 @@ pub enum ServiceAction {
     Remove,
     /// Fully restart the background service.
     Restart,
+    /// Print what the background service dispatches as it happens, as lines of JSON.
+    Events {
+        /// How the artwork of tracks is given: its `url`, a base64-encoded `thumbnail`, or the `path` to a copy of it on disk.
+        #[arg(long, default_value_t, value_parser = parse_artwork_format)]
+        artwork: player_events::ArtworkFormat,
+    },
     #[cfg_attr(debug_assertions, doc = "Reload the background service's configuration. (This may result in some funky behavior.)")]
     #[cfg(debug_assertions)]
     Reload
 }
 
+fn parse_artwork_format(value: &str) -> Result<player_events::ArtworkFormat, String> {
+    value.parse().map_err(|error: player_events::UnknownArtworkFormat| error.to_string())
+}
+
+fn parse_persistent_id(value: &str) -> Result<crate::store::types::StoredPersistentId, String> {
+    crate::store::types::StoredPersistentId::from_hex(value).map_err(|error| format!("not a hexadecimal persistent ID: {error}"))
+}
+
+/// Parses a date (as of local midnight), an RFC 3339 time, or an age relative to now.
+fn parse_since(value: &str) -> Result<chrono::DateTime<chrono::Utc>, String> {
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Ok(time.to_utc())
+    }
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return date.and_time(chrono::NaiveTime::MIN).and_local_timezone(chrono::Local).earliest()
+            .map(|time| time.to_utc())
+            .ok_or_else(|| format!("{value} has no local midnight"))
+    }
+    let split = value.find(|char: char| !char.is_ascii_digit()).unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+    let amount = amount.parse::<i64>().map_err(|_| format!("not a date, time, or age: {value}"))?;
+    let age = match unit {
+        "m" => chrono::TimeDelta::try_minutes(amount),
+        "h" => chrono::TimeDelta::try_hours(amount),
+        "d" => chrono::TimeDelta::try_days(amount),
+        "w" => chrono::TimeDelta::try_weeks(amount),
+        _ => return Err(format!("unknown unit of age {unit:?}; expected m, h, d, or w")),
+    }.ok_or_else(|| format!("age is too large: {value}"))?;
+    Ok(chrono::Utc::now() - age)
+}
+
+fn parse_backend(value: &str) -> Result<crate::subscribers::BackendIdentity, String> {
+    crate::subscribers::BackendIdentity::from_key(value).ok_or_else(|| format!("no backend is known as {value:?}"))
+}
+
+#[derive(Subcommand)]
+pub enum UncensorAction {
+    /// Print the uncensored title cached for a track.
+    Show {
+        /// The persistent ID of the track, in hexadecimal.
+        #[arg(value_parser = parse_persistent_id)]
+        persistent_id: crate::store::types::StoredPersistentId,
+    },
+    /// Set the uncensored title of a track, replacing whatever is cached.
+    Set {
+        /// The persistent ID of the track, in hexadecimal.
+        #[arg(value_parser = parse_persistent_id)]
+        persistent_id: crate::store::types::StoredPersistentId,
+        /// The uncensored title.
+        title: String,
+    },
+    /// Clear the uncensored title cached for a track, so that it'll be looked up again the next time it's played.
+    Clear {
+        /// The persistent ID of the track, in hexadecimal.
+        #[arg(value_parser = parse_persistent_id)]
+        persistent_id: crate::store::types::StoredPersistentId,
+    },
+}
+
+#[cfg(feature = "musicdb")]
+#[derive(Subcommand)]
+pub enum ImportSource {
+    /// Import play counts and last-played dates from the Apple Music library.
+    Musicdb {
+        /// The path to the library's `.musicdb` file, if not the one in the configuration.
+        #[arg(short, long, value_name = "PATH")]
+        path: Option<std::path::PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ReportAction {
+    /// Bundle the version, recent logs, and any kept crash reports into a `.tar.gz`. Nothing is sent anywhere.
+    Bundle {
+        /// Where to write the bundle; by default, a timestamped file in the current directory.
+        #[arg(short, long, value_name = "PATH")]
+        output: Option<std::path::PathBuf>,
+        /// How many of the most recent log lines to include.
+        #[arg(short = 'n', long, default_value_t = crate::report::DEFAULT_LOG_LINES)]
+        lines: usize,
+    },
+}
+
 #[derive(Subcommand)]
 pub enum ConfigurationAction {
     /// Run the configuration wizard. This will clear any existing settings.
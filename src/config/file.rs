@@ -49,11 +49,11 @@ impl ConfigPathChoice {
         }
     }
 
-    pub const fn describe_for_choice_reasoning_suffix(&self) -> &'static str {
+    pub fn describe_for_choice_reasoning_suffix(&self) -> String {
         match self {
-            Self::Explicit(_) => "explicitly provided",
-            Self::Automatic(_) => "the application default",
-            Self::Environmental(_) => concat!("sourced from the ", get_path_env_var!(), " environmental variable")
+            Self::Explicit(_) => crate::i18n::config_path_explicit(),
+            Self::Automatic(_) => crate::i18n::config_path_automatic(),
+            Self::Environmental(_) => crate::i18n::config_path_environmental(PATH_ENV_VAR)
         }
     }
 
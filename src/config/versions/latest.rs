@@ -17,9 +17,51 @@ pub struct Config {
     )]
     pub socket_path: std::path::PathBuf,
 
+    #[serde(default)]
+    pub service: crate::service::Config,
+
     #[serde(default)]
     pub artwork_hosts: HostConfigurations,
 
+    #[serde(default)]
+    pub http_server: crate::http::Config,
+
+    #[serde(default)]
+    pub sync: crate::sync::Config,
+
+    #[serde(default)]
+    pub network: http_client::Options,
+
+    #[serde(default)]
+    pub connectivity: crate::connectivity::Config,
+
+    #[serde(default)]
+    pub report: crate::report::Config,
+
+    #[serde(default)]
+    pub fetch_limits: crate::data_fetching::limits::Config,
+
+    #[serde(default)]
+    pub itunes: crate::data_fetching::services::itunes::Config,
+
+    #[serde(default)]
+    pub normalization: crate::subscribers::normalize::Config,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub overrides: Vec<crate::subscribers::overrides::Override>,
+
+    /// Playlists and radio stations whose playback is ignored entirely.
+    #[serde(default, skip_serializing_if = "crate::player::sources::Config::is_default")]
+    pub sources: crate::player::sources::Config,
+
+    /// When listening is considered to have stopped, for a summary of it to be sent.
+    #[serde(default, skip_serializing_if = "crate::listening_session::Config::is_default")]
+    pub listening_sessions: crate::listening_session::Config,
+
+    /// Whether backends should log what they would submit instead of submitting it.
+    #[serde(default, skip_serializing_if = "core::ops::Not::not")]
+    pub dry_run: bool,
+
     #[cfg(feature = "musicdb")]
     #[serde(default)]
     pub musicdb: MusicDbConfiguration
@@ -30,7 +72,20 @@ impl Default for Config {
             path: ConfigPathChoice::default(),
             backends: ConfigurableBackends::default(),
             socket_path: crate::service::ipc::socket_path::clone_default(),
+            service: crate::service::Config::default(),
             artwork_hosts: HostConfigurations::default(),
+            http_server: crate::http::Config::default(),
+            sync: crate::sync::Config::default(),
+            network: http_client::Options::default(),
+            connectivity: crate::connectivity::Config::default(),
+            report: crate::report::Config::default(),
+            fetch_limits: crate::data_fetching::limits::Config::default(),
+            itunes: crate::data_fetching::services::itunes::Config::default(),
+            normalization: crate::subscribers::normalize::Config::default(),
+            overrides: Vec::new(),
+            sources: crate::player::sources::Config::default(),
+            listening_sessions: crate::listening_session::Config::default(),
+            dry_run: false,
             #[cfg(feature = "musicdb")]
             musicdb: MusicDbConfiguration::default()
         }
@@ -70,7 +125,16 @@ pub struct ConfigurableBackends {
     pub lastfm: Option<crate::subscribers::lastfm::Config>,
     #[cfg(feature = "listenbrainz")]
     #[cfg_attr(feature = "listenbrainz", serde(default))]
-    pub listenbrainz: Option<crate::subscribers::listenbrainz::Config>
+    pub listenbrainz: Option<crate::subscribers::listenbrainz::Config>,
+    #[cfg(feature = "notifications")]
+    #[cfg_attr(feature = "notifications", serde(default))]
+    pub notifications: Option<crate::subscribers::notifications::Config>,
+    #[cfg(feature = "mqtt")]
+    #[cfg_attr(feature = "mqtt", serde(default))]
+    pub mqtt: Option<crate::subscribers::mqtt::Config>,
+    #[cfg(feature = "maloja")]
+    #[cfg_attr(feature = "maloja", serde(default))]
+    pub maloja: Option<crate::subscribers::maloja::Config>
 }
 #[allow(clippy::derivable_impls)]
 impl Default for ConfigurableBackends {
@@ -82,6 +146,12 @@ impl Default for ConfigurableBackends {
             lastfm: None,
             #[cfg(feature = "listenbrainz")]
             listenbrainz: None,
+            #[cfg(feature = "notifications")]
+            notifications: None,
+            #[cfg(feature = "mqtt")]
+            mqtt: None,
+            #[cfg(feature = "maloja")]
+            maloja: None,
         }
     }
 }
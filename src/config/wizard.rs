@@ -10,8 +10,9 @@ fn loose_matches(str: &str, one_of: &[&str]) -> bool {
 
 fn str_to_boolish(str: &str) -> Option<bool>  {
     let str = str.trim();
-    if loose_matches(str, &["y", "yes", "true"]) { return Some(true) }
-    if loose_matches(str, &["n", "no", "false"]) { return Some(false) }
+    let (yes, no) = crate::i18n::boolish_answers();
+    if loose_matches(str, yes) || loose_matches(str, &["y", "yes", "true"]) { return Some(true) }
+    if loose_matches(str, no) || loose_matches(str, &["n", "no", "false"]) { return Some(false) }
     None
 }
 
@@ -20,6 +21,7 @@ pub mod io {
     use std::io::{Write, BufRead};
 
     use super::*;
+    use crate::i18n;
     
     pub fn prompt(prompt: &str, initial_capacity: usize) -> String {
         {
@@ -44,14 +46,14 @@ pub mod io {
             {
                 let mut stdout = std::io::stdout().lock();
                 stdout.write_all(prompt.as_bytes()).unwrap();
-                stdout.write_all(b" (y/n)\n=> ").unwrap();
+                stdout.write_all(format!(" {}\n=> ", i18n::prompt_yes_or_no()).as_bytes()).unwrap();
                 stdout.flush().unwrap();
             }
     
             std::io::stdin().lock().read_line(&mut answer).expect("could not process user input");
             if let Some(bool) = str_to_boolish(&answer) { return bool }
-            println!(r#"Invalid input! Enter "yes" or "no"."#);
-            println!();
+            crate::console::out!("{}", i18n::prompt_invalid_boolish());
+            crate::console::out!();
             answer.clear();
         }
     }
@@ -72,7 +74,7 @@ pub mod io {
                 stdout.write_all(prompt.as_bytes()).unwrap();
 
                 if marked_optional {
-                    stdout.write_all(b"\n(optional; press enter without any value to skip)\n").unwrap();
+                    stdout.write_all(format!("\n{}\n", i18n::prompt_optional()).as_bytes()).unwrap();
                 }
                 
                 stdout.write_all(b"\n").unwrap();
@@ -92,8 +94,8 @@ pub mod io {
                 return None
             }
             
-            println!("Invalid input! Enter a number from 0 to {}.", options.len() - 1);
-            println!();
+            crate::console::out!("{}", i18n::prompt_invalid_choice(options.len() - 1));
+            crate::console::out!();
             answer.clear();
         }
     }
@@ -103,7 +105,9 @@ pub mod io {
         prompt_choice_maybe_optional(options, prompt, false).expect("prompt returned `None` despite being marked as non-optional")
     }
 
+    /// Skipped (as though nothing was entered) when the user [can't be prompted](crate::console::is_interactive).
     pub fn prompt_choice_optional(options: &[&str], prompt: &str) -> Option<usize> {
+        if !crate::console::is_interactive() { return None }
         prompt_choice_maybe_optional(options, prompt, true)
     }
 
@@ -113,7 +117,7 @@ pub mod io {
         use crate::subscribers::discord::{self, DisplayedField};
 
         pub fn prompt(config: &mut Option<discord::Config>, force_enable: bool) {
-            if force_enable || prompt_bool("Enable Discord Rich Presence?") {
+            if force_enable || prompt_bool(&i18n::wizard_discord_enable()) {
                 if let Some(config) = config.as_mut() {
                     config.enabled = true;
                 } else {
@@ -130,12 +134,12 @@ pub mod io {
         }
 
         pub fn prompt_display_type() -> Option<DisplayedField> {
-            let options = &[
-                "Listening to <activity-name> // Typically the application name, e.g. \"Apple Music\"",
-                "Listening to <artist>",
-                "Listening to <album>",
+            let options = [
+                i18n::wizard_discord_display_application(),
+                i18n::wizard_discord_display_artist(),
+                i18n::wizard_discord_display_album(),
             ];
-            prompt_choice_optional(options, "How should your activity display? (\"Listening to _________\")").map(|choice| match choice {
+            prompt_choice_optional(&options.each_ref().map(String::as_str), &i18n::wizard_discord_display()).map(|choice| match choice {
                 0 => DisplayedField::ApplicationName,
                 1 => DisplayedField::State,
                 2 => DisplayedField::Details,
@@ -145,20 +149,21 @@ pub mod io {
 
         pub fn prompt_application_id() -> Option<u64> {
             use discord::EnumeratedApplicationIdentifier;
+            let custom = i18n::wizard_discord_custom_application();
             let mut options = Vec::<&str>::with_capacity(EnumeratedApplicationIdentifier::VARIANT_COUNT + 1);
-            options.push("Other (requires a custom application ID)");
+            options.push(&custom);
             for id in &EnumeratedApplicationIdentifier::VARIANTS {
                 options.push(id.get_display_text());
             }
 
             loop {
-                if let Some(choice) = prompt_choice_optional(&options, "What should the activity name be?") {
+                if let Some(choice) = prompt_choice_optional(&options, &i18n::wizard_discord_activity_name()) {
                     match choice {
                         0 => {
                             const MAX_U64_LENGTH_IN_BASE_TEN: usize = 20;
-                            let id = super::prompt("Enter your custom application ID:", MAX_U64_LENGTH_IN_BASE_TEN + '\n'.len_utf8());
+                            let id = super::prompt(&i18n::wizard_discord_enter_application_id(), MAX_U64_LENGTH_IN_BASE_TEN + '\n'.len_utf8());
                             if let Ok(id) = id.trim().parse() { return Some(id) }
-                            eprintln!("could not parse application id; please try again");
+                            crate::console::error!("{}", i18n::wizard_discord_invalid_application_id());
                             continue;
                         },
                         index => return Some(EnumeratedApplicationIdentifier::VARIANTS[choice - index].get_id())
@@ -176,7 +181,7 @@ pub mod io {
         use crate::subscribers::lastfm;
 
         pub async fn prompt(config: &mut Option<lastfm::Config>)  {
-            if prompt_bool("Enable last.fm Scrobbling?") {
+            if prompt_bool(&i18n::wizard_lastfm_enable()) {
                 if let Some(config) = config.as_mut() {
                     config.enabled = true;
                 } else {
@@ -192,22 +197,24 @@ pub mod io {
             let auth = match client.generate_authorization_token().await {
                 Ok(auth) => auth,
                 Err(error) => {
-                    eprintln!("Error: {error}");
-                    eprintln!("Continuing with last.fm support disabled. This can be reconfigured later.");
+                    crate::console::error!("{error}");
+                    crate::console::note!("{}", i18n::wizard_lastfm_disabled_after_error());
                     return None;
                 }
             };
             let auth_url = auth.generate_authorization_url(client);
-            println!("Continue after authorizing the application: {auth_url}");
-            if prompt_bool("Have you authorized the application?") {
+            crate::console::out!("{}", i18n::wizard_lastfm_authorize(auth_url));
+            if prompt_bool(&i18n::wizard_lastfm_authorized()) {
                 match auth.generate_session_key(client).await {
                     Ok(key) => Some(crate::subscribers::lastfm::Config {
                         enabled: true,
                         identity: (*client).clone(),
-                        session_key: Some(key)
+                        session_key: Some(key),
+                        tags: crate::subscribers::lastfm::TagSubmission::default(),
+                        timestamp_policy: crate::subscribers::timestamp::TimestampPolicy::default(),
                     }),
                     Err(error) => {
-                        crate::util::ferror!("couldn't create session key: {error}");
+                        crate::util::ferror!("{}", i18n::wizard_lastfm_session_failed(error));
                     }
                 }
             } else { None }
@@ -220,7 +227,7 @@ pub mod io {
         use crate::subscribers::listenbrainz;
 
         pub async fn prompt(config: &mut Option<listenbrainz::Config>) {
-            if prompt_bool("Enable ListenBrainz synchronization?") {
+            if prompt_bool(&i18n::wizard_listenbrainz_enable()) {
                 if let Some(config) = config.as_mut() {
                     config.enabled = true;
                 } else {
@@ -234,22 +241,24 @@ pub mod io {
         pub async fn authorize() -> Option<listenbrainz::Config> {
             loop {
                 const HYPHENATED_UUID_LENGTH: usize = 36;
-                let token = super::prompt(r#"Paste your access token (from https://listenbrainz.org/settings/) or type "cancel":"#, HYPHENATED_UUID_LENGTH + '\n'.len_utf8());
+                let cancel = i18n::wizard_listenbrainz_cancel_word();
+                let token = super::prompt(&i18n::wizard_listenbrainz_token(&cancel), HYPHENATED_UUID_LENGTH + '\n'.len_utf8());
                 let token = &token[..token.len().saturating_sub('\n'.len_utf8())];
-                if token == "cancel" { break None; }
+                if token == cancel { break None; }
                 match brainz::listen::v1::UserToken::new(token).await {
                     Ok(token) => {
                         break Some(crate::subscribers::listenbrainz::Config {
                             enabled: true,
                             program_info: crate::subscribers::listenbrainz::DEFAULT_PROGRAM_INFO.clone(),
                             user_token: Some(token),
+                            timestamp_policy: crate::subscribers::timestamp::TimestampPolicy::default(),
                         })
                     },
                     Err(error) => {
                         use brainz::listen::v1::token_validity::ValidTokenInstantiationError;
                         match error {
-                            ValidTokenInstantiationError::Invalid(..) => eprintln!("Invalid token!"),
-                            ValidTokenInstantiationError::ValidityCheckFailure(failure) => eprintln!("Network failure: {}", failure.without_url())
+                            ValidTokenInstantiationError::Invalid(..) => crate::console::error!("{}", i18n::wizard_listenbrainz_invalid_token()),
+                            ValidTokenInstantiationError::ValidityCheckFailure(failure) => crate::console::error!("{}", i18n::wizard_listenbrainz_network_failure(failure.without_url()))
                         }
                     }
                 }
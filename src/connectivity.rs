@@ -0,0 +1,77 @@
+//! Keeping track of whether the network is reachable, so that backends which need it aren't dispatched to while it isn't;
+//! otherwise every dispatch would wait out its timeouts and retries before failing anyway.
+//!
+//! Reachability is determined by periodically opening a connection to a well-known host. A dispatch failing to connect
+//! prompts that to be done immediately, so that going offline is noticed without waiting for the next check.
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+
+/// How long the probe can take to connect before the network is considered offline.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub enabled: bool,
+    /// The `host:port` which is connected to in order to check that the network is reachable.
+    pub probe: String,
+    /// How often to check while online, in seconds.
+    pub interval: u64,
+    /// How often to check while offline, in seconds.
+    pub offline_interval: u64,
+}
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            probe: "captive.apple.com:80".to_owned(),
+            interval: 30,
+            offline_interval: 5,
+        }
+    }
+}
+
+static ONLINE: AtomicBool = AtomicBool::new(true);
+static PROBE_NOW: Notify = Notify::const_new();
+
+/// Whether the network was reachable when last checked.
+///
+/// This is always true if the monitor isn't running.
+pub fn is_online() -> bool {
+    ONLINE.load(Ordering::Relaxed)
+}
+
+/// Have the network be checked right away, since something suggests it might be offline.
+pub fn suspect() {
+    PROBE_NOW.notify_one();
+}
+
+async fn probe(address: &str) -> bool {
+    matches!(tokio::time::timeout(PROBE_TIMEOUT, tokio::net::TcpStream::connect(address)).await, Ok(Ok(_)))
+}
+
+pub fn spawn(config: Config) -> Option<tokio::task::AbortHandle> {
+    if !config.enabled { return None }
+
+    Some(tokio::spawn(async move {
+        loop {
+            let online = probe(&config.probe).await;
+            if ONLINE.swap(online, Ordering::Relaxed) != online {
+                if online {
+                    tracing::info!("network is back online; resuming dispatches");
+                } else {
+                    tracing::warn!(probe = %config.probe, "network is offline; deferring dispatches to backends which need it");
+                }
+            }
+
+            let interval = if online { config.interval } else { config.offline_interval };
+            tokio::select! {
+                () = tokio::time::sleep(Duration::from_secs(interval.max(1))) => {},
+                () = PROBE_NOW.notified() => {},
+            }
+        }
+    }).abort_handle())
+}
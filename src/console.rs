@@ -0,0 +1,136 @@
+//! What the command line interface tells the user, kept apart from the diagnostics which go through `tracing`.
+//!
+//! - [`out!`]: what a command was asked to produce; always printed to stdout.
+//! - [`note!`]: progress and confirmations; printed to stdout unless `--quiet` or `--json`.
+//! - [`detail!`]: elaboration which is usually uninteresting; printed to stdout only if `--verbose` (and not `--json`).
+//! - [`warning!`]: printed to stderr unless `--quiet`.
+//! - [`error!`]: always printed to stderr.
+//!
+//! Warnings and errors are labelled as such, in color if stderr is a terminal, `--no-color` isn't given, and `NO_COLOR` isn't set.
+//!
+//! With `--json`, commands which print information print it as a single JSON value with [`json`] instead,
+//! and nothing else is printed to stdout so that it can be parsed as-is.
+use core::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum Level {
+    Quiet = 0,
+    Normal = 1,
+    Verbose = 2,
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(Level::Normal as u8);
+static COLOR: AtomicBool = AtomicBool::new(false);
+static JSON: AtomicBool = AtomicBool::new(false);
+static INTERACTIVE: AtomicBool = AtomicBool::new(false);
+
+pub fn configure(args: &crate::cli::Cli) {
+    use clap_verbosity_flag::VerbosityFilter;
+    use std::io::IsTerminal as _;
+
+    let level = match args.verbose.filter() {
+        VerbosityFilter::Off | VerbosityFilter::Error | VerbosityFilter::Warn => Level::Quiet,
+        VerbosityFilter::Info => Level::Normal,
+        VerbosityFilter::Debug | VerbosityFilter::Trace => Level::Verbose,
+    };
+    LEVEL.store(level as u8, Ordering::Relaxed);
+
+    let color = !args.no_color
+        && std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+        && std::io::stderr().is_terminal();
+    COLOR.store(color, Ordering::Relaxed);
+    JSON.store(args.json, Ordering::Relaxed);
+
+    let interactive = !args.non_interactive
+        && std::io::stdin().is_terminal()
+        && !crate::gui::is_headless();
+    INTERACTIVE.store(interactive, Ordering::Relaxed);
+}
+
+pub fn level() -> Level {
+    match LEVEL.load(Ordering::Relaxed) {
+        0 => Level::Quiet,
+        1 => Level::Normal,
+        _ => Level::Verbose,
+    }
+}
+
+/// Whether output to stderr may be colored.
+pub fn is_colored() -> bool {
+    COLOR.load(Ordering::Relaxed)
+}
+
+/// Whether information should be printed as JSON rather than prose.
+pub fn is_json() -> bool {
+    JSON.load(Ordering::Relaxed)
+}
+
+/// Whether the user can be prompted for answers.
+pub fn is_interactive() -> bool {
+    INTERACTIVE.load(Ordering::Relaxed)
+}
+
+/// Prints a value to stdout as a single line of JSON.
+pub fn json(value: &impl serde::Serialize) {
+    match serde_json::to_string(value) {
+        Ok(json) => println!("{json}"),
+        Err(error) => labelled(&crate::i18n::label_error(), 31, format_args!("could not serialize output: {error}")),
+    }
+}
+
+/// Whether [`note!`] and [`detail!`] output of the given level should be printed.
+#[doc(hidden)]
+pub fn is_shown(level: Level) -> bool {
+    !is_json() && self::level() >= level
+}
+
+#[doc(hidden)]
+pub fn labelled(label: &str, color: u8, message: core::fmt::Arguments<'_>) {
+    if is_colored() {
+        eprintln!("\x1b[1;{color}m{label}:\x1b[0m {message}");
+    } else {
+        eprintln!("{label}: {message}");
+    }
+}
+
+macro_rules! out {
+    () => {
+        println!()
+    };
+    ($($t: tt)*) => {
+        println!($($t)*)
+    }
+}
+
+macro_rules! note {
+    ($($t: tt)*) => {
+        if $crate::console::is_shown($crate::console::Level::Normal) {
+            println!($($t)*);
+        }
+    }
+}
+
+macro_rules! detail {
+    ($($t: tt)*) => {
+        if $crate::console::is_shown($crate::console::Level::Verbose) {
+            println!($($t)*);
+        }
+    }
+}
+
+macro_rules! warning {
+    ($($t: tt)*) => {
+        if $crate::console::level() >= $crate::console::Level::Normal {
+            $crate::console::labelled(&$crate::i18n::label_warning(), 33, format_args!($($t)*));
+        }
+    }
+}
+
+macro_rules! error {
+    ($($t: tt)*) => {
+        $crate::console::labelled(&$crate::i18n::label_error(), 31, format_args!($($t)*))
+    }
+}
+
+pub(crate) use {out, note, detail, warning, error};
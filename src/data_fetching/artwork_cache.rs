@@ -0,0 +1,236 @@
+//! Copies of track artwork kept on disk, so that it can be given to [event subscribers](crate::service::ipc::events)
+//! in whichever [format](ArtworkFormat) they asked for without each of them having to fetch it.
+//!
+//! Artwork is only fetched (and thumbnailed) once the first subscriber which wants it that way comes along,
+//! and is kept by the persistent ID of the track along with where the artwork came from, so that artwork which
+//! has since changed is fetched anew; copies which were made a while ago are [pruned](prune).
+use std::{hash::{Hash as _, Hasher as _}, path::{Path, PathBuf}, sync::LazyLock};
+
+use base64::Engine as _;
+use player_events::{Artwork, ArtworkFormat};
+use tokio::sync::OnceCell;
+
+use super::components::artwork::LocatedResource;
+use crate::store::types::StoredPersistentId;
+
+static FOLDER: LazyLock<PathBuf> = LazyLock::new(|| crate::util::APPLICATION_SUPPORT_FOLDER.join("artwork-cache"));
+
+/// The longest side of a thumbnail, in pixels.
+const THUMBNAIL_SIZE: u16 = 128;
+/// How long artwork is kept for after it was last written.
+const MAX_AGE: core::time::Duration = core::time::Duration::from_hours(30 * 24);
+
+/// The artwork of a track, and the copies of it that have been made so far.
+#[derive(Debug)]
+pub struct CachedArtwork {
+    persistent_id: StoredPersistentId,
+    source: LocatedResource,
+    original: OnceCell<Option<PathBuf>>,
+    thumbnail: OnceCell<Option<String>>,
+}
+impl CachedArtwork {
+    pub const fn new(persistent_id: StoredPersistentId, source: LocatedResource) -> Self {
+        Self { persistent_id, source, original: OnceCell::const_new(), thumbnail: OnceCell::const_new() }
+    }
+
+    /// The artwork in the given format, making the copies it needs if they haven't been already.
+    /// Returns `None` if they couldn't be made.
+    pub async fn represent(&self, format: ArtworkFormat) -> Option<Artwork> {
+        match format {
+            ArtworkFormat::Url => self.source.as_url().map(|url| Artwork::Url { url: url.to_owned() }),
+            ArtworkFormat::Path => self.original().await.map(|path| Artwork::Path { path: path.to_string_lossy().into_owned() }),
+            ArtworkFormat::Thumbnail => self.thumbnail().await.map(|data| Artwork::Thumbnail { mime: "image/jpeg".to_owned(), data: data.to_owned() }),
+        }
+    }
+
+    /// What the copies are named by; the persistent ID of the track, and a hash of where the artwork came from
+    /// (and when it was last modified, if it's on disk) so that it changes along with the artwork.
+    async fn key(&self) -> String {
+        let mut hasher = std::hash::DefaultHasher::new();
+        let (LocatedResource::Remote(location) | LocatedResource::Local(location)) = &self.source;
+        location.hash(&mut hasher);
+        if let LocatedResource::Local(path) = &self.source
+        && let Ok(modified) = tokio::fs::metadata(path).await.and_then(|metadata| metadata.modified()) {
+            modified.hash(&mut hasher);
+        }
+        format!("{}-{:016X}", self.persistent_id.to_hex_upper(), hasher.finish())
+    }
+
+    /// The full-size copy of the artwork.
+    async fn original(&self) -> Option<&Path> {
+        self.original.get_or_init(|| async {
+            let path = FOLDER.join(format!("{}.{}", self.key().await, extension(&self.source)));
+            if tokio::fs::try_exists(&path).await.unwrap_or(false) { return Some(path) }
+            copy(&self.source, &path).await
+                .inspect_err(|error| tracing::warn!(?error, source = ?self.source, "failed to cache artwork"))
+                .ok().map(|()| path)
+        }).await.as_deref()
+    }
+
+    /// The thumbnail of the artwork, encoded with base64.
+    async fn thumbnail(&self) -> Option<&str> {
+        self.thumbnail.get_or_init(|| async {
+            let original = self.original().await?;
+            let path = thumbnail_path(original);
+            if !tokio::fs::try_exists(&path).await.unwrap_or(false) {
+                shrink(original, &path).await
+                    .inspect_err(|error| tracing::warn!(?error, ?original, "failed to make thumbnail of artwork"))
+                    .ok()?;
+            }
+            let bytes = tokio::fs::read(&path).await
+                .inspect_err(|error| tracing::warn!(?error, ?path, "failed to read thumbnail of artwork"))
+                .ok()?;
+            Some(base64::engine::general_purpose::STANDARD.encode(bytes))
+        }).await.as_deref()
+    }
+}
+
+/// The file extension of the image, going by where it is; JPEG is assumed if it isn't clear.
+fn extension(source: &LocatedResource) -> &'static str {
+    let (LocatedResource::Remote(location) | LocatedResource::Local(location)) = source;
+    let name = location.rsplit('/').next().unwrap_or_default();
+    let name = name.split(['?', '#']).next().unwrap_or_default();
+    match name.rsplit_once('.').map(|(_, extension)| extension.to_ascii_lowercase()).as_deref() {
+        Some("png") => "png",
+        Some("webp") => "webp",
+        Some("heic") => "heic",
+        _ => "jpg",
+    }
+}
+
+/// Where the thumbnail of the copy at the given path goes.
+fn thumbnail_path(original: &Path) -> PathBuf {
+    let stem = original.file_stem().unwrap_or_default().to_string_lossy();
+    original.with_file_name(format!("{stem}-thumbnail.jpg"))
+}
+
+/// Where a file is written before being renamed to the given path, so that one which was only partly written
+/// (such as from the download being cut off) is never mistaken for a finished copy.
+fn partial_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_owned();
+    name.push(".partial");
+    path.with_file_name(name)
+}
+
+/// Moves the file written at the [partial path](partial_path) into place, or cleans it up if writing it failed.
+async fn finish(written: std::io::Result<()>, partial: &Path, to: &Path) -> std::io::Result<()> {
+    let result = match written {
+        Ok(()) => tokio::fs::rename(partial, to).await,
+        Err(error) => Err(error),
+    };
+    if result.is_err() {
+        let _ = tokio::fs::remove_file(partial).await;
+    }
+    result
+}
+
+async fn copy(source: &LocatedResource, to: &Path) -> std::io::Result<()> {
+    if let Some(parent) = to.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let partial = partial_path(to);
+    let written = match source {
+        LocatedResource::Local(path) => tokio::fs::copy(path, &partial).await.map(drop),
+        LocatedResource::Remote(url) => async {
+            let response = crate::net::client().get(url).send().await
+                .and_then(reqwest::Response::error_for_status)
+                .map_err(std::io::Error::other)?;
+            let bytes = response.bytes().await.map_err(std::io::Error::other)?;
+            tokio::fs::write(&partial, bytes).await
+        }.await,
+    };
+    finish(written, &partial, to).await
+}
+
+/// Writes a JPEG copy of the image, scaled down to fit within the [thumbnail size](THUMBNAIL_SIZE), with `sips`.
+async fn shrink(image: &Path, to: &Path) -> std::io::Result<()> {
+    let partial = partial_path(to);
+    let written = tokio::process::Command::new("sips")
+        .arg("--resampleHeightWidthMax").arg(THUMBNAIL_SIZE.to_string())
+        .args(["--setProperty", "format", "jpeg"])
+        .arg(image)
+        .arg("--out").arg(&partial)
+        .output().await
+        .and_then(|output| if output.status.success() {
+            Ok(())
+        } else {
+            Err(std::io::Error::other(String::from_utf8_lossy(&output.stderr).trim().to_owned()))
+        });
+    finish(written, &partial, to).await
+}
+
+/// Deletes the artwork which hasn't been written in a while.
+pub async fn prune() {
+    let mut entries = match tokio::fs::read_dir(&*FOLDER).await {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return,
+        Err(error) => return tracing::warn!(?error, "could not read artwork cache"),
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let stale = entry.metadata().await.ok()
+            .and_then(|metadata| metadata.modified().ok())
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age > MAX_AGE);
+        if stale && let Err(error) = tokio::fs::remove_file(entry.path()).await {
+            tracing::warn!(?error, path = ?entry.path(), "could not remove stale artwork from cache");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extensions() {
+        assert_eq!(extension(&LocatedResource::Remote("https://is1-ssl.mzstatic.com/image/thumb/Music/v4/a/b/c/source/500x500bb.png".to_owned())), "png");
+        assert_eq!(extension(&LocatedResource::Remote("https://example.com/cover.WEBP?size=large".to_owned())), "webp");
+        assert_eq!(extension(&LocatedResource::Remote("https://example.com/cover".to_owned())), "jpg");
+        assert_eq!(extension(&LocatedResource::Local("/Users/someone/artwork/1A2B3C.jpeg".to_owned())), "jpg");
+    }
+
+    #[tokio::test]
+    async fn urls_are_given_as_they_are() {
+        let url = "https://example.com/cover.jpg".to_owned();
+        let artwork = CachedArtwork::new(StoredPersistentId::new(1), LocatedResource::Remote(url.clone()));
+        assert_eq!(artwork.represent(ArtworkFormat::Url).await, Some(Artwork::Url { url }));
+
+        let local = CachedArtwork::new(StoredPersistentId::new(1), LocatedResource::Local("/nonexistent.jpg".to_owned()));
+        assert_eq!(local.represent(ArtworkFormat::Url).await, None);
+    }
+
+    #[tokio::test]
+    async fn keys_follow_the_artwork() {
+        let artwork = |location: &str| CachedArtwork::new(StoredPersistentId::new(1), LocatedResource::Remote(location.to_owned()));
+        let key = artwork("https://example.com/a.jpg").key().await;
+        assert_eq!(key, artwork("https://example.com/a.jpg").key().await);
+        assert_ne!(key, artwork("https://example.com/b.jpg").key().await);
+        assert_ne!(key, CachedArtwork::new(StoredPersistentId::new(2), LocatedResource::Remote("https://example.com/a.jpg".to_owned())).key().await);
+        assert!(key.starts_with(&StoredPersistentId::new(1).to_hex_upper()));
+
+        let original = Path::new("/cache/0000000000000001-ABCDEF.png");
+        assert_eq!(thumbnail_path(original), Path::new("/cache/0000000000000001-ABCDEF-thumbnail.jpg"));
+        assert_eq!(partial_path(original), Path::new("/cache/0000000000000001-ABCDEF.png.partial"));
+    }
+
+    #[tokio::test]
+    async fn copies_are_only_put_in_place_once_whole() {
+        let folder = std::env::temp_dir().join(format!("am-osx-status-artwork-test-{}", std::process::id()));
+        let source = folder.join("source.png");
+        tokio::fs::create_dir_all(&folder).await.unwrap();
+        tokio::fs::write(&source, b"image").await.unwrap();
+
+        let to = folder.join("copy.png");
+        copy(&LocatedResource::Local(source.to_string_lossy().into_owned()), &to).await.unwrap();
+        assert_eq!(tokio::fs::read(&to).await.unwrap(), b"image");
+        assert!(!tokio::fs::try_exists(partial_path(&to)).await.unwrap());
+
+        let missing = folder.join("missing.png");
+        let to = folder.join("failed.png");
+        assert!(copy(&LocatedResource::Local(missing.to_string_lossy().into_owned()), &to).await.is_err());
+        assert!(!tokio::fs::try_exists(&to).await.unwrap());
+        assert!(!tokio::fs::try_exists(partial_path(&to)).await.unwrap());
+
+        tokio::fs::remove_dir_all(&folder).await.unwrap();
+    }
+}
@@ -63,6 +63,7 @@ impl ArtworkManager {
         }   
 
         for identity in &self.host_order.0 {
+            let _permit = crate::data_fetching::limits::acquire(crate::data_fetching::limits::Service::ArtworkHost).await;
             match self.hosts.get(*identity).await?.upload(&pool, track, file_path.as_ref()).await {
                 Ok(url) => return Some(url),
                 Err(err) => tracing::warn!(?err, "failed to upload custom artwork")
@@ -76,72 +77,74 @@ impl ArtworkManager {
         None
     }
 
-    pub async fn get(&self,
-        solicitation: &crate::data_fetching::ComponentSolicitation,
+    /// The image of the track's artist, as the music library has it.
+    #[cfg_attr(not(feature = "musicdb"), expect(unused_variables, clippy::missing_const_for_fn, reason = "only the library has artist images"))]
+    pub fn artist_image(
         track: &crate::subscribers::DispatchableTrack,
-        track_itunes: Option<&itunes_api::Track>,
         #[cfg(feature = "musicdb")] musicdb: Option<&musicdb::MusicDB>,
-    ) -> TrackArtworkData {
-        use crate::data_fetching::{Component, services::artworkd};
-
-        let mut images = TrackArtworkData::none();
-
+    ) -> Option<LocatedResource> {
         #[cfg(feature = "musicdb")]
-        if solicitation.contains(Component::ArtistImage) && let Some(db) = musicdb {
-            let id = musicdb::PersistentId::from(track.persistent_id);
-            images.artist = db.tracks().get(&id)
+        if let Some(db) = musicdb {
+            let id = musicdb::PersistentId::new(track.persistent_id.get());
+            return db.tracks().get(&id)
                 .and_then(|track| db.get(track.artist_id))
                 .and_then(|artist| artist.artwork_url.as_ref())
                 .filter(|mz| mz.parameters.effect != Some(mzstatic::image::effect::Effect::SquareFitCircle)) // ugly auto-generated
                 .map(LocatedResource::from);
         }
+        None
+    }
 
-        if solicitation.contains(Component::AlbumImage) {
-             if let Some(itunes) = track_itunes.as_ref() {
-                images.track = itunes.artwork_mzstatic().map(|mut mzstatic|{
-                    use mzstatic::image::quality::Quality;
-                    mzstatic.parameters.quality = Some(Quality::new(500).unwrap());
-                    LocatedResource::from(&mzstatic)
-                }).ok();
-            }
+    /// The artwork of the track's album, preferring that from iTunes, then from the music library,
+    /// and lastly uploading the locally cached artwork to a custom host.
+    pub async fn album_image(&self,
+        track: &crate::subscribers::DispatchableTrack,
+        track_itunes: Option<&itunes_api::SearchResult>,
+        #[cfg(feature = "musicdb")] musicdb: Option<&musicdb::MusicDB>,
+    ) -> Option<LocatedResource> {
+        use crate::data_fetching::services::artworkd;
 
-            #[cfg(feature = "musicdb")]
-            if images.track.is_none() && let Some(db) = musicdb {
-                let id = musicdb::PersistentId::from(track.persistent_id);
-                images.track = db.tracks().get(&id)
-                    .and_then(|track| track.artwork.clone())
-                    .map(|mut mz| {
-                        if mz.subdomain.starts_with('a') {
-                            mz.subdomain = "is1-ssl".into();
-                            mz.prefix = Some(mzstatic::image::Prefix::ImageThumbnail);
-                            mz.asset_token = mz.asset_token.replacen("4/", "v4/", 1).into();
-                        }
-                        LocatedResource::from(&mz)
-                    });
-            }
+        let mut image = track_itunes.and_then(|itunes| itunes.artwork_mzstatic().map(|mut mzstatic| {
+            use mzstatic::image::quality::Quality;
+            mzstatic.parameters.quality = Some(Quality::new(500).unwrap());
+            LocatedResource::from(&mzstatic)
+        }).ok());
 
-            if images.track.is_none() {
-                let artwork = match artworkd::get_artwork(track.persistent_id.signed()).await {
-                    Ok(artwork) => artwork,
-                    Err(err) => {
-                        tracing::error!(?err, id = %track.persistent_id, "failed to get artwork");
-                        None
+        #[cfg(feature = "musicdb")]
+        if image.is_none() && let Some(db) = musicdb {
+            let id = musicdb::PersistentId::new(track.persistent_id.get());
+            image = db.tracks().get(&id)
+                .and_then(|track| track.artwork.clone())
+                .map(|mut mz| {
+                    if mz.subdomain.starts_with('a') {
+                        mz.subdomain = "is1-ssl".into();
+                        mz.prefix = Some(mzstatic::image::Prefix::ImageThumbnail);
+                        mz.asset_token = mz.asset_token.replacen("4/", "v4/", 1).into();
                     }
-                };
+                    LocatedResource::from(&mz)
+                });
+        }
 
-                images.track = match artwork {
-                    None => None,
-                    Some(artwork) => artwork.into_uploaded(self, track).await.map(LocatedResource::Remote)
-                };
-            }
+        if image.is_none() {
+            let artwork = match artworkd::get_artwork(track.persistent_id.signed()).await {
+                Ok(artwork) => artwork,
+                Err(err) => {
+                    tracing::error!(?err, id = %track.persistent_id, "failed to get artwork");
+                    None
+                }
+            };
+
+            image = match artwork {
+                None => None,
+                Some(artwork) => artwork.into_uploaded(self, track).await.map(LocatedResource::Remote)
+            };
         }
 
-        images
+        image
     }
 }
 
-
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone)]
 #[allow(dead_code, reason = "used only by certain featured-gated backends")]
 pub struct TrackArtworkData<T = LocatedResource> {
     pub artist: Option<T>,
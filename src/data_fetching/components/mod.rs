@@ -2,6 +2,7 @@ use enum_bitset::EnumBitset;
 
 pub mod artwork;
 
+/// A piece of additional data about a track which backends can solicit.
 #[derive(Copy, Clone, PartialEq, Eq, Debug, EnumBitset)]
 #[bitset(name = ComponentSolicitation)]
 pub enum Component {
@@ -9,3 +10,70 @@ pub enum Component {
     ArtistImage,
     ITunesData
 }
+impl Component {
+    /// The components whose data is used to fetch this one, and so must be fetched before it.
+    pub const fn dependencies(self) -> ComponentSolicitation {
+        match self {
+            Self::AlbumImage => ComponentSolicitation::from_array([Self::ITunesData]),
+            Self::ArtistImage | Self::ITunesData => ComponentSolicitation::empty(),
+        }
+    }
+
+    /// How long after fetching begins that this component is waited upon before dispatching without it.
+    pub const fn timeout(self) -> core::time::Duration {
+        match self {
+            Self::ArtistImage => core::time::Duration::from_secs(1),
+            Self::ITunesData => core::time::Duration::from_secs(2),
+            // Might need to be uploaded to a custom host.
+            Self::AlbumImage => core::time::Duration::from_secs(3),
+        }
+    }
+}
+impl ComponentSolicitation {
+    /// The solicited components along with everything they (transitively) depend on.
+    pub fn resolved(self) -> Self {
+        let mut resolved = self;
+        loop {
+            let next = resolved.iter().fold(resolved, |set, component| set.union(&component.dependencies()));
+            if next == resolved { return resolved }
+            resolved = next;
+        }
+    }
+
+    /// Orders the [resolved](Self::resolved) components into stages which only depend on the stages before them,
+    /// such that the components within each stage can be fetched concurrently.
+    pub fn stages(self) -> Vec<Self> {
+        let mut remaining = self.resolved();
+        let mut fetched = Self::empty();
+        let mut stages = Vec::new();
+        while !remaining.is_empty() {
+            let ready = Self::from(remaining.iter().filter(|component| component.dependencies().is_subset_of(&fetched)));
+            assert!(!ready.is_empty(), "component dependencies are cyclic");
+            fetched = fetched.union(&ready);
+            remaining = remaining.difference(&ready);
+            stages.push(ready);
+        }
+        stages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stages() {
+        assert_eq!(ComponentSolicitation::empty().stages(), Vec::<ComponentSolicitation>::new());
+        assert_eq!(
+            ComponentSolicitation::from_array([Component::ArtistImage]).stages(),
+            vec![ComponentSolicitation::from_array([Component::ArtistImage])]
+        );
+        assert_eq!(
+            ComponentSolicitation::from_array([Component::AlbumImage, Component::ArtistImage]).stages(),
+            vec![
+                ComponentSolicitation::from_array([Component::ITunesData, Component::ArtistImage]),
+                ComponentSolicitation::from_array([Component::AlbumImage]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,114 @@
+//! Limits on how many requests can be outbound at once, so that skipping through a bunch of tracks
+//! doesn't fire off dozens of simultaneous requests and trip the rate limits of the services involved.
+use std::sync::{Arc, LazyLock, RwLock};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// An external service which data is fetched from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Service {
+    ITunes,
+    /// Any of the [custom artwork hosts](super::services::custom_artwork_host).
+    ArtworkHost,
+    /// Only searched by the [last.fm](crate::subscribers::lastfm) backend, for the MusicBrainz IDs of recordings.
+    #[cfg(feature = "lastfm")]
+    MusicBrainz,
+}
+
+/// The maximum amount of requests that can be outbound at once; a limit of zero is treated as one.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct Config {
+    /// Across every service.
+    pub global: u16,
+    pub itunes: u16,
+    pub artwork_hosts: u16,
+    pub musicbrainz: u16,
+}
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            global: 6,
+            itunes: 2,
+            artwork_hosts: 1,
+            musicbrainz: 1,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Limiter {
+    global: Arc<Semaphore>,
+    itunes: Arc<Semaphore>,
+    artwork_hosts: Arc<Semaphore>,
+    #[cfg(feature = "lastfm")]
+    musicbrainz: Arc<Semaphore>,
+}
+impl Limiter {
+    fn new(config: &Config) -> Self {
+        let semaphore = |limit: u16| Arc::new(Semaphore::new(usize::from(limit.max(1))));
+        Self {
+            global: semaphore(config.global),
+            itunes: semaphore(config.itunes),
+            artwork_hosts: semaphore(config.artwork_hosts),
+            #[cfg(feature = "lastfm")]
+            musicbrainz: semaphore(config.musicbrainz),
+        }
+    }
+
+    const fn get(&self, service: Service) -> &Arc<Semaphore> {
+        match service {
+            Service::ITunes => &self.itunes,
+            Service::ArtworkHost => &self.artwork_hosts,
+            #[cfg(feature = "lastfm")]
+            Service::MusicBrainz => &self.musicbrainz,
+        }
+    }
+
+    async fn acquire(&self, service: Service) -> Permit {
+        // The service's permit is waited on first so that requests queued behind a busy service don't hold up the others.
+        let service = self.get(service).clone().acquire_owned().await.expect("semaphore is never closed");
+        let global = self.global.clone().acquire_owned().await.expect("semaphore is never closed");
+        Permit { _service: service, _global: global }
+    }
+}
+
+static LIMITER: LazyLock<RwLock<Arc<Limiter>>> = LazyLock::new(|| RwLock::new(Arc::new(Limiter::new(&Config::default()))));
+
+/// Replaces the limits in use; requests which are already outbound or waiting are unaffected.
+pub fn configure(config: &Config) {
+    *LIMITER.write().expect("lock poisoned") = Arc::new(Limiter::new(config));
+}
+
+/// Allows a request to a service to be made, for as long as it's held.
+#[derive(Debug)]
+#[must_use = "the request isn't limited once the permit is dropped"]
+pub struct Permit {
+    _service: OwnedSemaphorePermit,
+    _global: OwnedSemaphorePermit,
+}
+
+/// Waits until a request can be made to the service without going over its limit or the global one.
+pub async fn acquire(service: Service) -> Permit {
+    let limiter = LIMITER.read().expect("lock poisoned").clone();
+    limiter.acquire(service).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn limits() {
+        const WAIT: core::time::Duration = core::time::Duration::from_millis(10);
+        let limiter = Limiter::new(&Config { global: 2, itunes: 0, artwork_hosts: 2, ..Default::default() });
+
+        let itunes = limiter.acquire(Service::ITunes).await;
+        assert!(tokio::time::timeout(WAIT, limiter.acquire(Service::ITunes)).await.is_err(), "service limit should be reached");
+        let artwork = limiter.acquire(Service::ArtworkHost).await;
+        assert!(tokio::time::timeout(WAIT, limiter.acquire(Service::ArtworkHost)).await.is_err(), "global limit should be reached");
+
+        drop(itunes);
+        let _more_artwork = limiter.acquire(Service::ArtworkHost).await;
+        drop(artwork);
+    }
+}
@@ -1,38 +1,194 @@
+pub mod artwork_cache;
 pub mod services;
 pub mod components;
+pub mod limits;
 
 use components::{Component, ComponentSolicitation};
 use components::artwork::TrackArtworkData;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[allow(dead_code, reason = "used only by certain featured-gated backends")]
 pub struct AdditionalTrackData {
-    pub itunes: Option<itunes_api::Track>,
+    pub itunes: Option<itunes_api::SearchResult>,
     pub images: TrackArtworkData
 }
 impl AdditionalTrackData {
+    const fn none() -> Self {
+        Self { itunes: None, images: TrackArtworkData::none() }
+    }
+
+    /// The artwork of the track, to be [cached](artwork_cache) for event subscribers, if it's been found.
+    pub fn cached_artwork(&self, track: &crate::subscribers::DispatchableTrack) -> Option<alloc::sync::Arc<artwork_cache::CachedArtwork>> {
+        self.images.track.clone().map(|artwork| alloc::sync::Arc::new(artwork_cache::CachedArtwork::new(track.persistent_id, artwork)))
+    }
+
+    /// Fetches the solicited components, along with those they depend upon.
+    ///
+    /// Each component is only waited upon for its [timeout](Component::timeout); the data returned has
+    /// whatever arrived by then, and those which didn't can be picked up later from the [`PendingTrackData`].
     pub async fn from_solicitation(
         solicitation: ComponentSolicitation,
+        track: alloc::sync::Arc<crate::subscribers::DispatchableTrack>,
+        #[cfg(feature = "musicdb")]
+        musicdb: alloc::sync::Arc<Option<musicdb::MusicDB>>,
+        artwork_manager: alloc::sync::Arc<components::artwork::ArtworkManager>
+    ) -> (Self, Option<PendingTrackData>) {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let task = tokio::spawn(Self::fetch_stages(solicitation, track, #[cfg(feature = "musicdb")] musicdb, artwork_manager, tx));
+
+        let started = tokio::time::Instant::now();
+        let mut data = Self::none();
+        let mut awaiting = solicitation.resolved();
+        while let Some(deadline) = awaiting.iter().map(Component::timeout).max().map(|timeout| started + timeout) {
+            match tokio::time::timeout_at(deadline, rx.recv()).await {
+                Ok(Some(fetched)) => {
+                    awaiting = awaiting.difference(&fetched.component().into());
+                    data.apply(fetched);
+                },
+                Ok(None) => break,
+                Err(_) => {
+                    tracing::debug!(?awaiting, "components were not fetched in time; proceeding without them");
+                    let pending = PendingTrackData { data: data.clone(), rx, task: task.abort_handle(), complete: false };
+                    return (data, Some(pending))
+                }
+            }
+        }
+
+        (data, None)
+    }
+
+    /// Fetches the components of each [stage](ComponentSolicitation::stages) concurrently, sending them off as they arrive.
+    async fn fetch_stages(
+        solicitation: ComponentSolicitation,
+        track: alloc::sync::Arc<crate::subscribers::DispatchableTrack>,
+        #[cfg(feature = "musicdb")]
+        musicdb: alloc::sync::Arc<Option<musicdb::MusicDB>>,
+        artwork_manager: alloc::sync::Arc<components::artwork::ArtworkManager>,
+        tx: tokio::sync::mpsc::UnboundedSender<Fetched>,
+    ) {
+        use futures_util::StreamExt as _;
+
+        let mut data = Self::none();
+        for stage in solicitation.stages() {
+            let mut fetching = stage.iter().map(|component| data.fetch(component, &track,
+                #[cfg(feature = "musicdb")]
+                musicdb.as_ref().as_ref(),
+                &artwork_manager
+            )).collect::<futures_util::stream::FuturesUnordered<_>>();
+
+            let mut fetched = Vec::with_capacity(fetching.len());
+            while let Some(component) = fetching.next().await {
+                let _ = tx.send(component.clone());
+                fetched.push(component);
+            }
+            drop(fetching);
+
+            for component in fetched {
+                data.apply(component);
+            }
+        }
+    }
+
+    async fn fetch(&self,
+        component: Component,
         track: &crate::subscribers::DispatchableTrack,
         #[cfg(feature = "musicdb")]
         musicdb: Option<&musicdb::MusicDB>,
-        artwork_manager: alloc::sync::Arc<components::artwork::ArtworkManager>
-    ) -> Self {
-        let itunes = if solicitation.contains(Component::ITunesData) {
-            services::itunes::find_track(&services::itunes::Query {
+        artwork_manager: &components::artwork::ArtworkManager
+    ) -> Fetched {
+        match component {
+            Component::ITunesData => Fetched::ITunesData(services::itunes::find_track(&services::itunes::Query {
+                entity: services::itunes::entity(track.media_kind),
                 title: track.name.as_ref(),
                 artist: track.artist.as_deref(),
-                album: track.album.as_deref()
-            }).await.inspect_err(|error| tracing::error!(?error, %track.persistent_id, "failed to get iTunes data")).ok().flatten()
-        } else { None };
-
-        Self {
-            images: artwork_manager.get(&solicitation, track, itunes.as_ref(),
+                album: track.album.as_deref(),
+                duration: track.duration,
+            }).await.inspect_err(|error| tracing::error!(?error, %track.persistent_id, "failed to get iTunes data")).ok().flatten()),
+            Component::AlbumImage => Fetched::AlbumImage(artwork_manager.album_image(track, self.itunes.as_ref(),
                 #[cfg(feature = "musicdb")]
                 musicdb
-            ).await,
-            itunes,
+            ).await),
+            Component::ArtistImage => Fetched::ArtistImage(components::artwork::ArtworkManager::artist_image(track,
+                #[cfg(feature = "musicdb")]
+                musicdb
+            )),
+        }
+    }
+
+    fn apply(&mut self, fetched: Fetched) {
+        match fetched {
+            Fetched::AlbumImage(image) => self.images.track = image,
+            Fetched::ArtistImage(image) => self.images.artist = image,
+            Fetched::ITunesData(itunes) => self.itunes = itunes,
+        }
+    }
+}
+
+/// The data of a single component, to be applied to [`AdditionalTrackData`].
+#[derive(Debug, Clone)]
+enum Fetched {
+    AlbumImage(Option<components::artwork::LocatedResource>),
+    ArtistImage(Option<components::artwork::LocatedResource>),
+    ITunesData(Option<itunes_api::SearchResult>),
+}
+impl Fetched {
+    const fn component(&self) -> Component {
+        match self {
+            Self::AlbumImage(_) => Component::AlbumImage,
+            Self::ArtistImage(_) => Component::ArtistImage,
+            Self::ITunesData(_) => Component::ITunesData,
         }
     }
 }
 
+/// Components which didn't arrive in time to be dispatched with the rest of the [`AdditionalTrackData`].
+///
+/// Fetching is abandoned once this is dropped.
+#[derive(Debug)]
+pub struct PendingTrackData {
+    data: AdditionalTrackData,
+    rx: tokio::sync::mpsc::UnboundedReceiver<Fetched>,
+    task: tokio::task::AbortHandle,
+    complete: bool,
+}
+impl PendingTrackData {
+    /// Applies the components which have arrived since this was last checked,
+    /// returning the data with everything that's arrived so far if anything new did.
+    pub fn take_arrived(&mut self) -> Option<AdditionalTrackData> {
+        use tokio::sync::mpsc::error::TryRecvError;
+
+        let mut arrived = false;
+        loop {
+            match self.rx.try_recv() {
+                Ok(fetched) => {
+                    self.data.apply(fetched);
+                    arrived = true;
+                },
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.complete = true;
+                    break
+                }
+            }
+        }
+        arrived.then(|| self.data.clone())
+    }
+
+    /// Whether every component has arrived (or failed to), such that there's nothing more to take.
+    pub const fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    /// Waits for all of the remaining components.
+    pub async fn complete(mut self) -> AdditionalTrackData {
+        while let Some(fetched) = self.rx.recv().await {
+            self.data.apply(fetched);
+        }
+        core::mem::replace(&mut self.data, AdditionalTrackData::none())
+    }
+}
+impl Drop for PendingTrackData {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
@@ -1,3 +1,4 @@
+use core::sync::atomic::{AtomicU32, Ordering};
 use itunes_api::Client;
 use unicode_normalization::UnicodeNormalization;
 
@@ -5,30 +6,170 @@ fn normalize(string: &str) -> String {
     string.trim().nfkc().collect::<String>().to_lowercase()
 }
 
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Config {
+    /// How [confident](score) we have to be that a search result is the track being searched for to accept it, from zero to one.
+    pub min_confidence: f32,
+}
+impl Default for Config {
+    fn default() -> Self {
+        Self { min_confidence: DEFAULT_MIN_CONFIDENCE }
+    }
+}
+
+const DEFAULT_MIN_CONFIDENCE: f32 = 0.7;
+static MIN_CONFIDENCE: AtomicU32 = AtomicU32::new(DEFAULT_MIN_CONFIDENCE.to_bits());
+
+pub fn configure(config: &Config) {
+    MIN_CONFIDENCE.store(config.min_confidence.to_bits(), Ordering::Relaxed);
+}
+
+#[derive(Debug, Clone, Copy)]
 pub struct Query<'a> {
+    /// What kind of thing the track is; podcast episodes and audiobook chapters are searched for by the show or book they're from.
+    pub entity: itunes_api::Entity,
     pub title: &'a str,
     pub album: Option<&'a str>,
     pub artist: Option<&'a str>,
+    pub duration: Option<core::time::Duration>,
 }
 
-// TODO: Rank with numeric. With Levenshtein; after removing parentheses, ignoring album, stuff like that.
-fn does_track_match_search(track: &Query, found: &itunes_api::Track) -> bool {
-    let name = normalize(track.title);
-    let artist = normalize(track.artist.unwrap_or_default());
-    let collection = normalize(track.album.unwrap_or_default());
+/// How similar two strings are, from zero to one, by the character pairs they share (the Sørensen–Dice coefficient).
+#[expect(clippy::cast_precision_loss, reason = "titles aren't anywhere near long enough to lose precision")]
+fn similarity(a: &str, b: &str) -> f32 {
+    let (a, b) = (normalize(a), normalize(b));
+    if a == b { return 1. }
+
+    let bigrams = |string: &str| string.chars().zip(string.chars().skip(1)).collect::<Vec<_>>();
+    let (a, mut b) = (bigrams(&a), bigrams(&b));
+    let total = a.len() + b.len();
+    if total == 0 { return 0. }
+
+    let mut shared = 0;
+    for pair in a {
+        if let Some(index) = b.iter().position(|other| *other == pair) {
+            b.swap_remove(index);
+            shared += 1;
+        }
+    }
+    (2 * shared) as f32 / total as f32
+}
+
+/// How confident we are that a search result is the queried track, from zero to one.
+///
+/// Album and duration are given a middling score when the query doesn't have them, so as to not count for or against a result.
+pub fn score(query: &Query, found: &itunes_api::SearchResult) -> f32 {
+    const TITLE: f32 = 0.4;
+    const ARTIST: f32 = 0.25;
+    const ALBUM: f32 = 0.15;
+    const DURATION: f32 = 0.2;
+    /// The difference in duration at which a result is considered to be a different track entirely.
+    const MAX_DURATION_DELTA_SECONDS: f32 = 10.;
+
+    let title = similarity(query.title, found.name()).max(similarity(query.title, found.name_censored()));
+    let artist = similarity(query.artist.unwrap_or_default(), found.artist_name());
+    let album = match (query.album, found) {
+        (Some(album), itunes_api::SearchResult::Song(found)) => similarity(album, &found.collection_name).max(similarity(album, &found.collection_name_censored)),
+        (Some(album), found) => found.collection_name().map_or(0.5, |collection| similarity(album, collection)),
+        (None, _) => 0.5,
+    };
+    let duration = match (query.duration, found.duration()) {
+        (Some(expected), Some(actual)) => 1. - (expected.abs_diff(actual).as_secs_f32() / MAX_DURATION_DELTA_SECONDS).min(1.),
+        _ => 0.5,
+    };
 
-    (
-        normalize(&found.name) == name ||
-        normalize(&found.name_censored) == name
-    )
-        && (normalize(&found.artist_name) == artist)
-        && (normalize(&found.collection_name) == collection)
+    title.mul_add(TITLE, artist.mul_add(ARTIST, album.mul_add(ALBUM, duration * DURATION)))
 }
 
-pub async fn find_track(query: &Query<'_>) -> Result<Option<itunes_api::Track>, itunes_api::Error> {
+/// What to search for to find a track of the given kind.
+pub const fn entity(kind: player_events::MediaKind) -> itunes_api::Entity {
+    use player_events::MediaKind;
+    use itunes_api::Entity;
+    match kind {
+        MediaKind::Song | MediaKind::Unknown => Entity::Song,
+        MediaKind::MusicVideo => Entity::MusicVideo,
+        MediaKind::Podcast => Entity::Podcast,
+        MediaKind::Audiobook => Entity::Audiobook,
+    }
+}
+
+/// Searches for the track, returning the best result which we're [confident enough](Config::min_confidence) in.
+pub async fn find_track(query: &Query<'_>) -> Result<Option<itunes_api::SearchResult>, itunes_api::Error> {
+    use itunes_api::Entity;
+    // An episode or chapter isn't found by itself, but by the podcast or audiobook that it's from, which is its album.
+    let query = match query.entity {
+        Entity::Podcast | Entity::Audiobook => Query { title: query.album.unwrap_or(query.title), album: None, duration: None, ..*query },
+        Entity::Song | Entity::MusicVideo => *query,
+    };
     let search = format!("{} {}", query.artist.unwrap_or_default(), query.title);
     let search = search.trim();
-    let client = Client::new(reqwest::Client::new()); // TODO: use a shared client.
-    let songs = client.search_songs(search, 10).await?;
-    Ok(songs.into_iter().find(|result| does_track_match_search(query, result)))
+    let client = Client::new(crate::net::client()).with_retry(crate::net::retry());
+    let _permit = crate::data_fetching::limits::acquire(crate::data_fetching::limits::Service::ITunes).await;
+    let results = client.search_entity(query.entity, search, 10).await?;
+
+    let min_confidence = f32::from_bits(MIN_CONFIDENCE.load(Ordering::Relaxed));
+    let mut best: Option<(f32, itunes_api::SearchResult)> = None;
+    for result in results {
+        let confidence = score(&query, &result);
+        if confidence < min_confidence {
+            tracing::debug!(confidence, title = %result.name(), artist = %result.artist_name(), album = ?result.collection_name(), "rejected iTunes search result");
+        } else if best.as_ref().is_none_or(|(best, _)| confidence > *best) {
+            best = Some((confidence, result));
+        }
+    }
+    Ok(best.map(|(_, result)| result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn found(name: &str, artist: &str, album: &str, seconds: u64) -> itunes_api::SearchResult {
+        itunes_api::SearchResult::Song(itunes_api::Track {
+            artist_apple_music_url: None,
+            artist_name: artist.to_owned(),
+            name_censored: name.to_owned(),
+            name: name.to_owned(),
+            artwork_preview_url: String::new(),
+            apple_music_url: String::new(),
+            collection_name_censored: album.to_owned(),
+            collection_name: album.to_owned(),
+            duration_millis: Some(seconds * 1000),
+        })
+    }
+
+    #[test]
+    fn scoring() {
+        let min = DEFAULT_MIN_CONFIDENCE;
+        let query = Query {
+            entity: itunes_api::Entity::Song,
+            title: "Snorkel",
+            artist: Some("Lumpy"),
+            album: Some("Acoustic Hotel"),
+            duration: Some(core::time::Duration::from_secs(200)),
+        };
+
+        assert!(score(&query, &found("Snorkel", "Lumpy", "Acoustic Hotel", 200)) > 0.99);
+        assert!(score(&query, &found("Snorkel", "Lumpy", "Acoustic Hotel (Deluxe)", 201)) >= min);
+        assert!(score(&query, &found("Snorkel", "Lumpy", "Live at the Hotel", 205)) >= min);
+        assert!(score(&query, &found("Dinghy", "Lumpy", "Acoustic Hotel", 180)) < min);
+        assert!(score(&query, &found("Snorkel", "The Snorkelers", "Ocean Songs", 120)) < min);
+
+        let sparse = Query { entity: itunes_api::Entity::Song, title: "Snorkel", artist: Some("Lumpy"), album: None, duration: None };
+        assert!(score(&sparse, &found("Snorkel", "Lumpy", "Anything", 1)) >= min);
+
+        let podcast = itunes_api::SearchResult::Podcast(itunes_api::Podcast {
+            artist_name: "Lumpy".to_owned(),
+            name_censored: "Snorkel Talk".to_owned(),
+            name: "Snorkel Talk".to_owned(),
+            artwork_preview_url: String::new(),
+            apple_podcasts_url: String::new(),
+            feed_url: None,
+            genre: None,
+            episode_count: Some(40),
+        });
+        let show = Query { entity: itunes_api::Entity::Podcast, title: "Snorkel Talk", artist: Some("Lumpy"), album: None, duration: None };
+        assert!(score(&show, &podcast) >= min, "having no album or duration shouldn't count against it");
+    }
 }
@@ -1,6 +1,25 @@
-#[allow(unused)]
-pub struct DebuggingGuards {
-    appender: Option<tracing_appender::non_blocking::WorkerGuard>
+pub mod supervisor;
+
+pub static LOGS_FOLDER: std::sync::LazyLock<std::path::PathBuf> = std::sync::LazyLock::new(|| {
+    crate::util::HOME.join("Library/Logs/am-osx-status")
+});
+
+/// Held onto outside of the [guards](DebuggingGuards) so that the panic hook can flush it before exiting.
+static APPENDER_GUARD: std::sync::Mutex<Option<tracing_appender::non_blocking::WorkerGuard>> = std::sync::Mutex::new(None);
+
+/// Writes out whatever's yet to make it to the logfile; nothing is written to it afterwards.
+fn flush_appender() {
+    // If it's locked, it's already being flushed.
+    if let Ok(mut guard) = APPENDER_GUARD.try_lock() {
+        drop(guard.take());
+    }
+}
+
+pub struct DebuggingGuards(());
+impl Drop for DebuggingGuards {
+    fn drop(&mut self) {
+        flush_appender();
+    }
 }
 
 pub struct DebuggingSession {
@@ -12,9 +31,13 @@ impl DebuggingSession {
         use tracing_subscriber::prelude::*;
 
         let mut layers = Vec::with_capacity(4);
-        let mut appender_guard = None;
 
-        layers.push(tracing_subscriber::fmt::layer().boxed());
+        // Diagnostics go to stderr, leaving stdout to what's meant for the user.
+        layers.push(tracing_subscriber::fmt::layer()
+            .with_writer(std::io::stderr)
+            .with_ansi(crate::console::is_colored())
+            .boxed()
+        );
 
         if cfg!(debug_assertions) && !args.running_as_service {
             #[cfg(feature = "tokio_console")]
@@ -32,7 +55,7 @@ impl DebuggingSession {
                 .filename_suffix("log")
                 .rotation(tracing_appender::rolling::Rotation::DAILY)
                 .max_log_files(3)
-                .build(crate::util::HOME.join("Library/Logs/am-osx-status"))
+                .build(&*LOGS_FOLDER)
                 .expect("failed to create rolling file appender");
 
             let (non_blocking, guard) = tracing_appender::non_blocking(appender);
@@ -43,9 +66,9 @@ impl DebuggingSession {
                 .boxed()
             );
 
-            appender_guard = Some(guard);
+            *APPENDER_GUARD.lock().expect("appender guard was poisoned") = Some(guard);
         } else {
-            eprintln!("WARNING: failed to create logging directory, file logging disabled");
+            crate::console::warning!("{}", crate::i18n::logging_directory_unavailable());
         }
 
         layers.push(tracing_oslog::OsLogger::new(crate::util::REVERSE_DNS_IDENTIFIER, "default").boxed());
@@ -57,18 +80,14 @@ impl DebuggingSession {
 
         std::panic::set_hook(Box::new(panic_hook));
     
-        let guards = DebuggingGuards {
-            appender: appender_guard
-        };
-    
         Self {
-            guards
+            guards: DebuggingGuards(())
         }
     }
 
     /// Create the logging directory if it doesn't already exist. Returns `Ok(true)` if it was created, `Ok(false)` if it already existed.
     fn make_logging_dir() -> Result<bool, std::io::Error> {
-        match std::fs::create_dir(crate::util::HOME.join("Library/Logs/am-osx-status")) {
+        match std::fs::create_dir(&*LOGS_FOLDER) {
             Ok(()) => Ok(true),
             Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => Ok(false),
             Err(err) => Err(err)
@@ -82,26 +101,18 @@ impl DebuggingSession {
         const ENV: &str = "AMXS_LOG";
         if std::env::var_os(ENV).is_some() {
             if args.verbose.is_present() {
-                eprintln!("WARNING: Provided verbosity arguments were ignored as environmental variable {ENV} is set");
+                crate::console::warning!("{}", crate::i18n::logging_verbosity_overridden(ENV));
             }
             EnvFilter::try_from_env(ENV).expect("bad log filter")
         } else {
-            let level = if args.verbose.is_present() {
-                args.verbose.tracing_level().map_or("none", |level| level.as_str())
-            } else {
-                tracing::Level::INFO.as_str()
-            };
-
-            EnvFilter::new(level)
+            EnvFilter::new(args.verbose.tracing_level().map_or("off", |level| level.as_str()))
         }
     }
 }
 impl core::default::Default for DebuggingSession {
     fn default() -> Self {
         Self {
-            guards: DebuggingGuards {
-                appender: None
-            }
+            guards: DebuggingGuards(())
         }
     }
 }
@@ -121,27 +132,42 @@ fn panic_hook(info: &std::panic::PanicHookInfo) {
     use std::backtrace::*;
     use core::panic::Location;
 
-    let backtrace = Backtrace::capture();
+    // Captured regardless of `RUST_BACKTRACE`, since the logfile is usually all there is to go off of.
+    let backtrace = Backtrace::force_capture();
     let location = info.location().map(Location::to_string);
     let message = info.payload_as_str();
     let thread = std::thread::current();
     let thread_id = extract_thread_id(thread.id());
+    let thread_name = thread.name().map_or_else(|| "unnamed thread".to_owned(), |name| format!("thread '{name}'"));
+    let message = message.unwrap_or("<no message>");
+    let backend = supervisor::attributed().map(crate::subscribers::BackendIdentity::get_name);
 
     tracing::error!(
         location = location,
+        backend,
         backtrace = tracing::field::display(match backtrace.status() {
             BacktraceStatus::Captured => format!("r#\"\n{backtrace}\"#"),
-            BacktraceStatus::Disabled => "disabled (run with RUST_BACKTRACE=1)".to_string(),
+            BacktraceStatus::Disabled => "disabled".to_string(),
             BacktraceStatus::Unsupported => "unsupported".to_string(),
             opt => format!("unknown (unrecognized status {opt:?})"),
         }),
-        "{} (T{}) panicked at {}",
-        thread.name().map_or_else(|| "unnamed thread".to_owned(), |name| format!("thread '{name}'")),
-        thread_id,
-        message.unwrap_or("<no message>")
+        "{thread_name} (T{thread_id}) panicked at {}",
+        message
     );
 
+    crate::report::record_panic(&format!(
+        "{thread_name} (T{thread_id}) panicked at {}: {message}\n\nbacktrace:\n{backtrace}",
+        location.as_deref().unwrap_or("<unknown location>")
+    ));
+
     if thread_id.get() == 1 {
+        flush_appender();
         std::process::exit(1)
     }
+
+    // A backend that panicked is disabled by whatever dispatched to it; supervised work which hadn't yet
+    // been attributed to one can't be pinned on any, so it's treated like anything else.
+    if backend.is_none() {
+        supervisor::request_shutdown();
+    }
 }
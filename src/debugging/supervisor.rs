@@ -0,0 +1,62 @@
+//! Keeping a panic from leaving the program in a state it can't get itself out of.
+//!
+//! Work done on behalf of a backend is [supervised](supervise) and [attributed](attribute) to it, so that a panic within it is known to only concern
+//! that backend; it's then [disabled](crate::subscribers::Backends::disable) rather than being locked and dispatched to again.
+//! Anything else that panics might've been holding up the main loop, or left something it shares inconsistent,
+//! so a graceful shutdown is [requested](request_shutdown) instead, which the service manager would then restart the program after.
+use core::cell::Cell;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::subscribers::BackendIdentity;
+
+tokio::task_local! {
+    /// The backend which the current task is doing work for, once known.
+    static SUPERVISED: Cell<Option<BackendIdentity>>;
+}
+
+/// Runs the future as work done on behalf of a backend, which it should [attribute](attribute) itself to once it's known which.
+pub async fn supervise<F: Future>(future: F) -> F::Output {
+    SUPERVISED.scope(Cell::new(None), future).await
+}
+
+/// Attributes the [supervised](supervise) work being done to the backend, such that a panic from here on is pinned on it.
+pub fn attribute(identity: BackendIdentity) {
+    if SUPERVISED.try_with(|supervised| supervised.set(Some(identity))).is_err() {
+        tracing::warn!(backend = identity.get_name(), "attributing unsupervised work to a backend");
+    }
+}
+
+/// The backend that the current task's work is [attributed](attribute) to, if any.
+pub fn attributed() -> Option<BackendIdentity> {
+    SUPERVISED.try_with(Cell::get).ok().flatten()
+}
+
+static SHUTDOWN_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Asks for the program to exit the same way it would if it were terminated, so that the session is still finalized.
+///
+/// This is called from the panic hook, so it doesn't panic itself; only the first request does anything.
+pub fn request_shutdown() {
+    if SHUTDOWN_REQUESTED.swap(true, Ordering::SeqCst) { return }
+    tracing::warn!("requesting graceful shutdown after unrecoverable panic");
+    // Handled by whatever is watching for termination.
+    if unsafe { libc::raise(libc::SIGTERM) } != 0 {
+        tracing::error!(error = %std::io::Error::last_os_error(), "failed to request shutdown");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn attributes_supervised_work() {
+        assert!(attributed().is_none());
+        supervise(async {
+            assert!(attributed().is_none(), "supervised work isn't pinned on a backend until attributed");
+            attribute(BackendIdentity::Mock);
+            assert_eq!(attributed(), Some(BackendIdentity::Mock));
+        }).await;
+        assert!(attributed().is_none());
+    }
+}
@@ -0,0 +1,93 @@
+//! Whether there's a graphical login session (an "Aqua" session) to work within.
+//!
+//! Apple Music can only be scripted from within one, and the background service is registered to one.
+//! There isn't one over SSH or on a machine nobody is logged into the desktop of; what needs one [errors](require) clearly instead,
+//! and nothing is prompted for, as though `--non-interactive` was given.
+//!
+//! The detection can be overridden by setting [`OVERRIDE_VARIABLE`] to a truthy (headless) or falsy (graphical) value.
+
+use std::sync::LazyLock;
+
+pub const OVERRIDE_VARIABLE: &str = "AM_OSX_STATUS_HEADLESS";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Session {
+    /// Logged into the desktop, even if this is being run from a terminal within it.
+    Graphical,
+    /// Such as over SSH, or on a machine nobody is logged into.
+    Headless,
+}
+impl Session {
+    /// Interprets the name of the launchd session type this is running within, as printed by `launchctl managername`.
+    fn from_manager_name(name: &str) -> Self {
+        match name.trim() {
+            "Aqua" => Self::Graphical,
+            _ => Self::Headless,
+        }
+    }
+
+    fn from_override(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "1" | "true" | "yes" => Some(Self::Headless),
+            "0" | "false" | "no" => Some(Self::Graphical),
+            _ => None,
+        }
+    }
+
+    fn detect() -> Self {
+        if let Some(value) = std::env::var_os(OVERRIDE_VARIABLE) {
+            if let Some(session) = Self::from_override(&value.to_string_lossy()) { return session }
+            tracing::warn!(?value, "ignoring unrecognized value of {OVERRIDE_VARIABLE}");
+        }
+
+        match std::process::Command::new("launchctl").arg("managername").output() {
+            Ok(output) if output.status.success() => Self::from_manager_name(&String::from_utf8_lossy(&output.stdout)),
+            result => {
+                tracing::warn!(?result, "could not determine the launchd session type; guessing by whether this is over SSH");
+                if std::env::var_os("SSH_CONNECTION").is_some() { Self::Headless } else { Self::Graphical }
+            }
+        }
+    }
+
+    pub fn current() -> Self {
+        static CURRENT: LazyLock<Session> = LazyLock::new(|| {
+            let session = Session::detect();
+            tracing::debug!(?session, "detected session type");
+            session
+        });
+        *CURRENT
+    }
+}
+
+pub fn is_headless() -> bool {
+    Session::current() == Session::Headless
+}
+
+/// Exits with an error explaining that what was asked for can't be done without a graphical login session, if there isn't one.
+pub fn require(action: &str) {
+    if is_headless() {
+        crate::console::error!("{}", crate::i18n::gui_required(action));
+        crate::console::note!("{}", crate::i18n::gui_required_hint(OVERRIDE_VARIABLE));
+        std::process::exit(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manager_names() {
+        assert_eq!(Session::from_manager_name("Aqua\n"), Session::Graphical);
+        assert_eq!(Session::from_manager_name("Background\n"), Session::Headless);
+        assert_eq!(Session::from_manager_name("System\n"), Session::Headless);
+    }
+
+    #[test]
+    fn overrides() {
+        assert_eq!(Session::from_override("1"), Some(Session::Headless));
+        assert_eq!(Session::from_override("True"), Some(Session::Headless));
+        assert_eq!(Session::from_override("0"), Some(Session::Graphical));
+        assert_eq!(Session::from_override("maybe"), None);
+    }
+}
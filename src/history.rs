@@ -0,0 +1,122 @@
+//! Printing the history recorded in the store.
+use std::process::ExitCode;
+
+use crate::store::{MaybeStaticSqlError, entities::{CanonicalTrack, FromKey, Listen, MediaProgress}, types::MillisecondTimestamp};
+
+fn format_date(timestamp: MillisecondTimestamp) -> impl core::fmt::Display {
+    timestamp.0.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+}
+
+/// A listen as it's printed with `--json`.
+#[derive(serde::Serialize)]
+struct ListenEntry {
+    /// In milliseconds since the epoch.
+    started_at: i64,
+    persistent_id: String,
+    artist: Option<String>,
+    title: Option<String>,
+    origin: Option<String>,
+}
+
+async fn print_listens(pool: &sqlx::SqlitePool, limit: u32) -> Result<(), MaybeStaticSqlError> {
+    let json = crate::console::is_json();
+    let mut entries = Vec::new();
+    for listen in Listen::get_recent(pool, limit).await? {
+        let track = match listen.canonical {
+            Some(canonical) => Some(CanonicalTrack::get_in_pool(canonical, pool).await?),
+            None => None
+        };
+        if json {
+            entries.push(ListenEntry {
+                started_at: listen.started_at.0.timestamp_millis(),
+                persistent_id: listen.persistent_id.to_hex_upper(),
+                artist: track.as_ref().map(|track| track.artist.clone()).filter(|artist| !artist.is_empty()),
+                title: track.map(|track| track.title),
+                origin: listen.origin,
+            });
+            continue;
+        }
+        let started_at = format_date(listen.started_at);
+        let origin = listen.origin.as_deref().map(crate::i18n::history_origin).unwrap_or_default();
+        match track {
+            Some(track) if !track.artist.is_empty() => crate::console::out!("{started_at}  {} — {}{origin}", track.artist, track.title),
+            Some(track) => crate::console::out!("{started_at}  {}{origin}", track.title),
+            None => crate::console::out!("{started_at}  {}{origin}", listen.persistent_id),
+        }
+    }
+    if json { crate::console::json(&entries); }
+    Ok(())
+}
+
+/// Where a podcast episode or audiobook was left off, as it's printed with `--json`.
+#[derive(serde::Serialize)]
+struct ProgressEntry {
+    /// In milliseconds since the epoch.
+    recorded_at: i64,
+    persistent_id: String,
+    title: String,
+    album: Option<String>,
+    /// In seconds.
+    position: f64,
+    /// In seconds.
+    duration: Option<f64>,
+}
+
+#[expect(clippy::cast_possible_truncation, reason = "precision beyond seconds is irrelevant")]
+async fn print_progress(pool: &sqlx::SqlitePool, limit: u32) -> Result<(), MaybeStaticSqlError> {
+    use crate::now::format_time;
+    let progresses = MediaProgress::get_latest(pool, limit).await?;
+    if crate::console::is_json() {
+        crate::console::json(&progresses.into_iter().map(|progress| ProgressEntry {
+            recorded_at: progress.recorded_at.0.timestamp_millis(),
+            persistent_id: progress.persistent_id.to_hex_upper(),
+            title: progress.title,
+            album: progress.album,
+            position: progress.position,
+            duration: progress.duration,
+        }).collect::<Vec<_>>());
+        return Ok(());
+    }
+    for progress in progresses {
+        let recorded_at = format_date(progress.recorded_at);
+        let album = progress.album.map(|album| format!(" ({album})")).unwrap_or_default();
+        let position = progress.position as f32;
+        match progress.duration.filter(|duration| *duration > 0.) {
+            Some(duration) => crate::console::out!(
+                "{recorded_at}  {}{album}  {}/{} ({:.0}%)",
+                progress.title,
+                format_time(position),
+                format_time(duration as f32),
+                (progress.position / duration * 100.).min(100.)
+            ),
+            None => crate::console::out!("{recorded_at}  {}{album}  {}", progress.title, format_time(position)),
+        }
+    }
+    Ok(())
+}
+
+/// Prints the most recent listens, or where podcast episodes and audiobooks were left off.
+pub async fn print(podcasts: bool, limit: u32) -> ExitCode {
+    crate::store::migrations::migrate().await;
+    let pool = match crate::store::DB_POOL.get().await {
+        Ok(pool) => pool,
+        Err(error) => {
+            crate::console::error!("{}", crate::i18n::database_unavailable(error));
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let printed = if podcasts {
+        print_progress(&pool, limit).await
+    } else {
+        print_listens(&pool, limit).await
+    };
+
+    match printed {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            crate::console::error!("{}", crate::i18n::history_unreadable(error));
+            ExitCode::FAILURE
+        }
+    }
+}
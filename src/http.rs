@@ -0,0 +1,247 @@
+//! A tiny read-only HTTP server on localhost, so that scripts (and things like Raycast extensions)
+//! can query what's playing with nothing more than `curl`.
+//!
+//! - `GET /now-playing`: the current track and playback position, as JSON.
+//! - `GET /health`: whether the daemon is alive, as JSON.
+//!
+//! If this machine is the hub that others [sync](crate::sync) with, it also serves:
+//! - `GET /sync/listens?after=<id>`: the listens which the requesting machine doesn't have yet, as JSON.
+//! - `POST /sync/listens`: stores the listens recorded by the requesting machine.
+use alloc::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tokio::{io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader}, net::{TcpListener, TcpStream}, sync::Mutex};
+
+/// The largest request body that'll be read.
+const MAX_BODY_LENGTH: usize = 16 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Config {
+    pub enabled: bool,
+    /// The port to listen on; the server is only ever bound to the loopback interface.
+    pub port: u16,
+}
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 5867,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Health {
+    status: &'static str,
+    version: &'static str,
+    pid: libc::pid_t,
+}
+
+#[derive(Debug, Serialize)]
+struct NowPlaying<'a> {
+    player_open: bool,
+    /// Whether time is currently being accumulated towards the listen of the track.
+    playing: bool,
+    track: Option<NowPlayingTrack<'a>>,
+    /// The expected position in the track, in seconds.
+    position: Option<f32>,
+    /// The total time spent listening to the track, in seconds.
+    listened: f32,
+}
+
+#[derive(Debug, Serialize)]
+struct NowPlayingTrack<'a> {
+    title: &'a str,
+    artist: Option<&'a str>,
+    album: Option<&'a str>,
+    album_artist: Option<&'a str>,
+    track_number: Option<u16>,
+    persistent_id: String,
+    /// In seconds.
+    duration: Option<f32>,
+    apple_music_url: Option<&'a str>,
+}
+impl<'a> From<&'a crate::DispatchableTrack> for NowPlayingTrack<'a> {
+    fn from(track: &'a crate::DispatchableTrack) -> Self {
+        Self {
+            title: &track.name,
+            artist: track.artist.as_deref(),
+            album: track.album.as_deref(),
+            album_artist: track.album_artist.as_deref(),
+            track_number: track.track_number.map(core::num::NonZero::get),
+            persistent_id: track.persistent_id.to_hex_upper(),
+            duration: track.duration.map(|duration| duration.as_secs_f32()),
+            apple_music_url: track.apple_music_url.as_deref(),
+        }
+    }
+}
+
+struct Response {
+    status: &'static str,
+    body: String,
+}
+impl Response {
+    fn json(value: &impl Serialize) -> Self {
+        Self { status: "200 OK", body: serde_json::to_string(value).expect("failed to serialize response") }
+    }
+    fn error(status: &'static str) -> Self {
+        Self { status, body: serde_json::json!({ "error": status }).to_string() }
+    }
+
+    async fn write(&self, stream: &mut (impl AsyncWriteExt + Unpin)) -> std::io::Result<()> {
+        let head = format!(
+            "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            self.status,
+            self.body.len()
+        );
+        stream.write_all(head.as_bytes()).await?;
+        stream.write_all(self.body.as_bytes()).await?;
+        stream.flush().await
+    }
+}
+
+async fn now_playing(context: &Mutex<crate::PollingContext>) -> Response {
+    use crate::listened::TimeDeltaExtension;
+    let context = context.lock().await;
+    let (playing, listened) = {
+        let listened = context.listened.lock().await;
+        (listened.current.is_some(), listened.total())
+    };
+    let track = context.last_track.as_deref();
+    let position = context.playback.zip(track).map(|(playback, track)| playback.position_at(chrono::Utc::now().timestamp_millis(), track.duration));
+    Response::json(&NowPlaying {
+        player_open: context.player_open,
+        playing: playing && track.is_some(),
+        track: track.map(NowPlayingTrack::from),
+        position,
+        listened: listened.as_secs_f32(),
+    })
+}
+
+/// The parts of a request that are relevant to us.
+#[derive(Debug, Default)]
+struct Headers {
+    content_length: usize,
+    authorization: Option<String>,
+    machine: Option<String>,
+}
+impl Headers {
+    fn read(&mut self, line: &str) {
+        let Some((name, value)) = line.split_once(':') else { return };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            self.content_length = value.parse().unwrap_or_default();
+        } else if name.eq_ignore_ascii_case("authorization") {
+            self.authorization = Some(value.to_owned());
+        } else if name.eq_ignore_ascii_case(crate::sync::MACHINE_HEADER) {
+            self.machine = Some(value.to_owned());
+        }
+    }
+}
+
+async fn sync_listens(method: &str, query: &str, headers: &Headers, body: &[u8], config: &crate::sync::Config) -> Response {
+    if config.token.is_empty() || headers.authorization.as_deref().and_then(|value| value.strip_prefix("Bearer ")) != Some(config.token.as_str()) {
+        return Response::error("401 Unauthorized")
+    }
+    let Some(machine) = headers.machine.as_deref() else { return Response::error("400 Bad Request") };
+    let pool = match crate::store::DB_POOL.get().await {
+        Ok(pool) => pool,
+        Err(error) => {
+            tracing::error!(?error, "failed to get database pool to serve sync");
+            return Response::error("500 Internal Server Error")
+        }
+    };
+
+    match method {
+        "GET" => {
+            let after = query.split('&')
+                .find_map(|parameter| parameter.strip_prefix("after="))
+                .and_then(|after| after.parse().ok())
+                .unwrap_or_default();
+            match crate::sync::pull(&pool, config, machine, after).await {
+                Ok(pulled) => Response::json(&pulled),
+                Err(error) => {
+                    tracing::error!(?error, machine, "failed to serve listens for sync");
+                    Response::error("500 Internal Server Error")
+                }
+            }
+        },
+        "POST" => {
+            let Ok(listens) = serde_json::from_slice::<Vec<crate::sync::SyncedListen>>(body) else { return Response::error("400 Bad Request") };
+            if listens.iter().any(|listen| listen.origin != machine) { return Response::error("400 Bad Request") }
+            match crate::sync::receive(&pool, config, &listens).await {
+                Ok(stored) => Response::json(&serde_json::json!({ "stored": stored })),
+                Err(error) => {
+                    tracing::error!(?error, machine, "failed to store synced listens");
+                    Response::error("500 Internal Server Error")
+                }
+            }
+        },
+        _ => Response::error("405 Method Not Allowed")
+    }
+}
+
+async fn handle(mut stream: TcpStream, context: Arc<Mutex<crate::PollingContext>>, sync: Option<Arc<crate::sync::Config>>) -> std::io::Result<()> {
+    let (reader, mut writer) = stream.split();
+    let mut lines = BufReader::new(reader).lines();
+
+    let Some(request) = lines.next_line().await? else { return Ok(()) };
+    let mut headers = Headers::default();
+    while let Some(header) = lines.next_line().await? {
+        if header.is_empty() { break }
+        headers.read(&header);
+    }
+
+    if headers.content_length > MAX_BODY_LENGTH {
+        return Response::error("413 Content Too Large").write(&mut writer).await
+    }
+    let mut body = vec![0; headers.content_length];
+    lines.get_mut().read_exact(&mut body).await?;
+
+    let mut request = request.split_ascii_whitespace();
+    let (method, path) = (request.next().unwrap_or_default(), request.next().unwrap_or_default());
+    let (path, query) = path.split_once('?').unwrap_or((path, ""));
+
+    let response = match (method, path) {
+        (_, "/sync/listens") => match sync.as_deref() {
+            Some(sync) => sync_listens(method, query, &headers, &body, sync).await,
+            None => Response::error("404 Not Found")
+        },
+        ("GET", "/now-playing") => now_playing(&context).await,
+        ("GET", "/health") => Response::json(&Health {
+            status: "ok",
+            version: clap::crate_version!(),
+            pid: *crate::util::OWN_PID,
+        }),
+        (_, "/now-playing" | "/health") => Response::error("405 Method Not Allowed"),
+        _ => Response::error("404 Not Found"),
+    };
+
+    response.write(&mut writer).await
+}
+
+/// Sync endpoints are only served if the configuration for them is provided.
+pub async fn serve(context: Arc<Mutex<crate::PollingContext>>, config: &Config, sync: Option<crate::sync::Config>) -> std::io::Result<tokio::task::AbortHandle> {
+    let listener = TcpListener::bind((std::net::Ipv4Addr::LOCALHOST, config.port)).await?;
+    tracing::info!(port = config.port, "serving now playing status over http");
+    let sync = sync.map(Arc::new);
+
+    Ok(tokio::spawn(async move {
+        loop {
+            let stream = match listener.accept().await {
+                Ok((stream, _)) => stream,
+                Err(error) => {
+                    tracing::error!(?error, "failed to accept http connection");
+                    continue;
+                }
+            };
+            let context = context.clone();
+            let sync = sync.clone();
+            tokio::spawn(async move {
+                if let Err(error) = handle(stream, context, sync).await {
+                    tracing::debug!(?error, "http connection failed");
+                }
+            });
+        }
+    }).abort_handle())
+}
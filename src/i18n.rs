@@ -0,0 +1,258 @@
+//! The text shown to the user by the command line interface and configuration wizard,
+//! kept out of the code which shows it so that it can be translated.
+//!
+//! Each message is a function which formats its text in the [current locale](Locale::current).
+//! To add a translation, add a [`Locale`], recognize its language code in [`Locale::from_code`],
+//! and give every message its text in it; a message without one won't compile.
+//!
+//! Diagnostics (what's logged through `tracing`) and the `--help` text aren't translated.
+
+use std::sync::LazyLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    English,
+}
+impl Locale {
+    /// Recognizes a POSIX locale name (e.x. `en_US.UTF-8`) or language tag (e.x. `en-US`) by its language.
+    fn from_code(code: &str) -> Option<Self> {
+        let language = code.split(['_', '-', '.', '@']).next().unwrap_or_default();
+        match language.to_ascii_lowercase().as_str() {
+            "en" | "c" | "posix" => Some(Self::English),
+            _ => None,
+        }
+    }
+
+    /// Picks the locale from the first of `LC_ALL`, `LC_MESSAGES`, and `LANG` which is set,
+    /// falling back to English if none are or it isn't one there's a translation for.
+    fn detect() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"].into_iter()
+            .filter_map(std::env::var_os)
+            .find(|value| !value.is_empty())
+            .and_then(|value| Self::from_code(&value.to_string_lossy()))
+            .unwrap_or(Self::English)
+    }
+
+    pub fn current() -> Self {
+        static CURRENT: LazyLock<Locale> = LazyLock::new(Locale::detect);
+        *CURRENT
+    }
+}
+
+macro_rules! messages {
+    ($(
+        $(#[$meta: meta])*
+        $name: ident($($arg: ident: $ty: ty),* $(,)?) {
+            $($locale: ident => $text: literal),+ $(,)?
+        }
+    )*) => {
+        $(
+            $(#[$meta])*
+            #[allow(clippy::useless_format, reason = "messages without arguments are formatted like those with them")]
+            pub fn $name($($arg: $ty),*) -> String {
+                match Locale::current() {
+                    $(Locale::$locale => format!($text)),+
+                }
+            }
+        )*
+    }
+}
+
+/// Words which answer a yes-or-no question, in the current locale.
+/// English answers are always accepted too.
+pub fn boolish_answers() -> (&'static [&'static str], &'static [&'static str]) {
+    match Locale::current() {
+        Locale::English => (&["y", "yes", "true"], &["n", "no", "false"]),
+    }
+}
+
+messages! {
+    // Labels
+
+    label_warning() { English => "warning" }
+    label_error() { English => "error" }
+
+    // Prompts
+
+    prompt_yes_or_no() { English => "(y/n)" }
+    prompt_optional() { English => "(optional; press enter without any value to skip)" }
+    prompt_invalid_boolish() { English => r#"Invalid input! Enter "yes" or "no"."# }
+    prompt_invalid_choice(max: usize) { English => "Invalid input! Enter a number from 0 to {max}." }
+
+    // Configuration
+
+    config_unreadable(error: impl core::fmt::Display) { English => "could not read config: {error}" }
+    config_undeserializable(error: impl core::fmt::Display) { English => "could not read config: deserialization failure: {error}" }
+    config_permission_denied(path: impl core::fmt::Display) { English => "could not read config: lacking permission to read {path}" }
+    config_missing(path: impl core::fmt::Display) { English => "no configuration file @ {path}" }
+    config_offer_wizard_automatic() { English => "No configuration has been set up! Would you like to use the wizard to build one?" }
+    config_offer_wizard_explicit() { English => "No configuration exists at the provided file! Would you like to use the wizard to build it?" }
+    config_offer_wizard_environmental() { English => "No configuration exists at the file specified in the environmental variable! Would you like to use the wizard to build it?" }
+    config_wizard_not_offered() { English => "No configuration has been set up, and the wizard can't be offered non-interactively." }
+    config_saved() { English => "Configuration file has been saved." }
+    config_using_temporary_default() { English => "Proceeding with a temporary default configuration." }
+    config_creating(path: impl core::fmt::Display) { English => "Creating configuration file @ {path}" }
+    config_modifying(path: impl core::fmt::Display) { English => "Modifying configuration file @ {path}" }
+    config_changes_saved() { English => "Successfully saved changes!" }
+    config_path_reason(reason: impl core::fmt::Display) { English => "This path is used because it is {reason}." }
+    config_path_reason_despite(reason: impl core::fmt::Display, problem: impl core::fmt::Display) { English => "This path is used because it is {reason}, but {problem}." }
+    config_path_explicit() { English => "explicitly provided" }
+    config_path_automatic() { English => "the application default" }
+    config_path_environmental(variable: &str) { English => "sourced from the {variable} environmental variable" }
+    config_problem_undeserializable() { English => "it couldn't be successfully deserialized" }
+    config_problem_nonexistent() { English => "it currently doesn't exist" }
+    config_problem_not_found() { English => "it couldn't be found" }
+    config_problem_permission_denied() { English => "the required permissions to read it are not available" }
+    config_problem_unknown(error: impl core::fmt::Display) { English => "an unknown error occurred trying to read it ({error})" }
+
+    // Running
+
+    instance_already_running(pid: libc::pid_t) { English => "another instance of the program is already running! (pid {pid})" }
+    instance_stop_service_hint() { English => "You can turn off the service with `am-osx-status service stop`." }
+
+    // Graphical login session
+
+    gui_required(action: &str) { English => "{action} requires a graphical login session, and there isn't one (such as when connected over SSH)" }
+    gui_required_hint(variable: &str) { English => "Log into the Mac's desktop (e.x. with Screen Sharing) and try again. If there is a graphical session and this is wrong, set {variable}=0." }
+    gui_action_watching() { English => "watching Apple Music" }
+    gui_action_now_playing() { English => "querying Apple Music" }
+    gui_action_service_stop() { English => "stopping the service" }
+
+    // Service
+
+    service_instance_running_independently(pid: libc::pid_t) { English => "an instance of the program is already running independently of the service! (pid {pid})" }
+    service_adopt_hint() { English => "Close it first, or use `am-osx-status service start --adopt` to have the service take its place." }
+    service_adopting(pid: libc::pid_t) { English => "Asking the running instance (pid {pid}) to exit..." }
+    service_adopting_by_signal() { English => "Couldn't reach the running instance over IPC; sending it a termination signal instead." }
+    service_adoption_timed_out(pid: libc::pid_t) { English => "the running instance (pid {pid}) didn't exit in time; not starting the service" }
+    service_write_definition_failed(error: impl core::fmt::Display) { English => "failed to write job definition file: {error}" }
+    service_register_failed(error: impl core::fmt::Display) { English => "failed to register service: {error}" }
+    service_unregister_failed(error: impl core::fmt::Display) { English => "failed to unregister service: {error}" }
+    service_started() { English => "Service registered and started!" }
+    service_registration_deferred() { English => "Service installed! There's no graphical login session to start it in now, so it will start on the next login." }
+    service_already_registered() { English => "Service already registered; not registering again. (Changes to its configuration will apply once it's restarted.)" }
+    service_restarted() { English => "Service restarted!" }
+    service_stopped(pid: libc::pid_t) { English => "Service (pid {pid}) stopped and temporarily unregistered! It will start again on the next login, or when started again manually." }
+    service_unregistered_temporarily() { English => "Service temporarily unregistered! It will start again on the next login, or when started again manually." }
+    service_remove_hint() { English => "(If you want to fully remove the service, use `am-osx-status service remove`.)" }
+    service_stopped_and_removed(pid: libc::pid_t) { English => "Service (pid {pid}) stopped and unregistered! To add it again, use `am-osx-status service start`." }
+    service_removed() { English => "Service unregistered! To add it again, use `am-osx-status service start`." }
+    service_was_not_registered() { English => "Service wasn't registered to begin with." }
+    service_running(pid: libc::pid_t) { English => "Service is running with PID {pid}." }
+    service_running_but_removed() { English => "The definition has since been removed, though, so it will not start automatically after shutdown." }
+    service_not_running_but_independent(pid: libc::pid_t) { English => "Service is not running, but an instance of the program is running independently with PID {pid}." }
+    service_installed_behind_independent() { English => "It is installed and will start automatically on login, or can be manually started after the running instance is closed (or in its place, with `--adopt`)." }
+    service_not_installed() { English => "The service is not currently installed." }
+    service_not_running_but_installed() { English => "Service is not running, but it is installed and will start automatically on login." }
+    service_not_running_nor_installed() { English => "Service is not running and is not installed." }
+    service_not_running() { English => "Service is not running." }
+    service_installation_indeterminate(error: impl core::fmt::Display) { English => "could not determine if the service is installed: {error}" }
+    service_session_summary(uptime: impl core::fmt::Display, track_fetches: i64, player_fetches: i64) { English => "It has been up for {uptime}, fetching the current track {track_fetches} times and the player status {player_fetches} times." }
+    service_session_no_dispatches() { English => "Nothing has been dispatched to any backend yet." }
+    service_session_dispatches(backend: &str, dispatched: u64, failed: u64) { English => "  {backend}: {dispatched} dispatches, {failed} failed" }
+    service_status_unavailable(error: impl core::fmt::Display) { English => "could not get a summary from the running instance: {error}" }
+    service_reload_sent() { English => "Reload command sent to service." }
+    service_unreachable(error: impl core::fmt::Display) { English => "could not connect to the service: {error}" }
+    service_connection_lost(error: impl core::fmt::Display) { English => "lost connection to the service: {error}" }
+
+    // Store
+
+    session_creation_failed(error: impl core::fmt::Display) { English => "failed to create session in database: {error}" }
+    database_unavailable(error: impl core::fmt::Display) { English => "could not open the database: {error}" }
+    history_unreadable(error: impl core::fmt::Display) { English => "could not read the history: {error}" }
+    history_origin(origin: &str) { English => " (on {origin})" }
+
+    // Uncensored titles
+
+    uncensored_not_found(id: &str) { English => "No uncensored title could be found for {id}." }
+    uncensored_not_cached(id: &str) { English => "Nothing is cached for {id}." }
+    uncensored_set(id: &str, title: &str) { English => "Set the uncensored title of {id} to {title:?}." }
+    uncensored_cleared(id: &str) { English => "Cleared the uncensored title of {id}; it'll be looked up again the next time it's played." }
+    uncensored_cache_unavailable(error: impl core::fmt::Display) { English => "could not access the cache: {error}" }
+
+    // Now playing
+
+    now_invalid_format(error: impl core::fmt::Display) { English => "invalid format: {error}" }
+    now_player_unavailable(error: impl core::fmt::Display) { English => "could not retrieve player status: {error}" }
+    now_track_unavailable(error: impl core::fmt::Display) { English => "could not retrieve current track: {error}" }
+
+    // Importing
+
+    #[cfg(feature = "musicdb")]
+    import_library_unreadable(error: impl core::fmt::Display) { English => "could not read the music library: {error}" }
+    #[cfg(feature = "musicdb")]
+    import_done(plays: u64, tracks: u64) { English => "Imported {plays} plays across {tracks} tracks." }
+    #[cfg(feature = "musicdb")]
+    import_store_failed(error: impl core::fmt::Display) { English => "could not store the imported plays: {error}" }
+
+    // Replaying
+
+    replay_history_unreadable(error: impl core::fmt::Display) { English => "could not read the listens to replay: {error}" }
+    replay_untracked(count: usize) { English => "{count} listens were recorded before what was listened to was kept alongside them, so they can't be replayed." }
+    replay_backend_unavailable(backend: &str) { English => "{backend} isn't enabled, or can't be given listens after the fact" }
+    replay_nothing() { English => "There are no listens to replay." }
+    replay_done(count: usize, backend: &str) { English => "Replayed {count} listens to {backend}." }
+    replay_failed(failed: usize, count: usize, backend: &str) { English => "{failed} of {count} listens couldn't be replayed to {backend}; see the log for why" }
+
+    // Reports
+
+    report_bundled(path: impl core::fmt::Display) { English => "Saved the report to {path}. Paths within your home folder have been shortened to ~, but please look over what's in it before sharing it, since the logs may still mention what you've listened to or other personal details." }
+    report_bundle_failed(error: impl core::fmt::Display) { English => "could not bundle the report: {error}" }
+
+    // Logging
+
+    logging_directory_unavailable() { English => "failed to create logging directory; file logging disabled" }
+    logging_verbosity_overridden(variable: &str) { English => "provided verbosity arguments were ignored as environmental variable {variable} is set" }
+
+    // Wizard
+
+    wizard_not_interactive(path: impl core::fmt::Display) { English => "the wizard needs to prompt for answers, which can't be done non-interactively; run it from a terminal on the Mac's desktop, or edit {path} directly" }
+
+    // Wizard: Discord
+
+    #[cfg(feature = "discord")]
+    wizard_discord_enable() { English => "Enable Discord Rich Presence?" }
+    #[cfg(feature = "discord")]
+    wizard_discord_display() { English => r#"How should your activity display? ("Listening to _________")"# }
+    #[cfg(feature = "discord")]
+    wizard_discord_display_application() { English => r#"Listening to <activity-name> // Typically the application name, e.g. "Apple Music""# }
+    #[cfg(feature = "discord")]
+    wizard_discord_display_artist() { English => "Listening to <artist>" }
+    #[cfg(feature = "discord")]
+    wizard_discord_display_album() { English => "Listening to <album>" }
+    #[cfg(feature = "discord")]
+    wizard_discord_activity_name() { English => "What should the activity name be?" }
+    #[cfg(feature = "discord")]
+    wizard_discord_custom_application() { English => "Other (requires a custom application ID)" }
+    #[cfg(feature = "discord")]
+    wizard_discord_enter_application_id() { English => "Enter your custom application ID:" }
+    #[cfg(feature = "discord")]
+    wizard_discord_invalid_application_id() { English => "could not parse application id; please try again" }
+
+    // Wizard: last.fm
+
+    #[cfg(feature = "lastfm")]
+    wizard_lastfm_enable() { English => "Enable last.fm Scrobbling?" }
+    #[cfg(feature = "lastfm")]
+    wizard_lastfm_disabled_after_error() { English => "Continuing with last.fm support disabled. This can be reconfigured later." }
+    #[cfg(feature = "lastfm")]
+    wizard_lastfm_authorize(url: impl core::fmt::Display) { English => "Continue after authorizing the application: {url}" }
+    #[cfg(feature = "lastfm")]
+    wizard_lastfm_authorized() { English => "Have you authorized the application?" }
+    #[cfg(feature = "lastfm")]
+    wizard_lastfm_session_failed(error: impl core::fmt::Display) { English => "couldn't create session key: {error}" }
+
+    // Wizard: ListenBrainz
+
+    #[cfg(feature = "listenbrainz")]
+    wizard_listenbrainz_enable() { English => "Enable ListenBrainz synchronization?" }
+    /// The word the user types to cancel must match [`wizard_listenbrainz_cancel_word`].
+    #[cfg(feature = "listenbrainz")]
+    wizard_listenbrainz_token(cancel: &str) { English => r#"Paste your access token (from https://listenbrainz.org/settings/) or type "{cancel}":"# }
+    #[cfg(feature = "listenbrainz")]
+    wizard_listenbrainz_cancel_word() { English => "cancel" }
+    #[cfg(feature = "listenbrainz")]
+    wizard_listenbrainz_invalid_token() { English => "invalid token" }
+    #[cfg(feature = "listenbrainz")]
+    wizard_listenbrainz_network_failure(error: impl core::fmt::Display) { English => "network failure: {error}" }
+}
@@ -0,0 +1,61 @@
+//! Seeding the local store with history from elsewhere, so that statistics aren't empty for long-time listeners.
+use std::process::ExitCode;
+
+use crate::store::{canonical::CanonicalKey, entities::{CanonicalTrack, LibraryPlayCount}, types::StoredPersistentId};
+
+/// Imports the play count and last-played date of every track in the library, as recorded in the `musicdb` file.
+///
+/// Tracks which have never been played are skipped. Importing again replaces what was previously imported.
+/// Each imported track is also resolved to its [canonical track](crate::store::canonical), so that its plays are
+/// counted alongside those of any other copies of it.
+pub async fn musicdb(path: std::path::PathBuf) -> ExitCode {
+    let db = match tokio::task::spawn_blocking(move || musicdb::MusicDB::read_path(path)).await.expect("musicdb read panicked") {
+        Ok(db) => db,
+        Err(error) => {
+            crate::console::error!("{}", crate::i18n::import_library_unreadable(error));
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let played = db.tracks().values()
+        .filter(|track| track.played.times != 0 || track.played.last.is_some())
+        .collect::<Vec<_>>();
+    let keys = played.iter()
+        .map(|track| {
+            let artist = track.artist_name.map(ToString::to_string);
+            let title = track.name.map(ToString::to_string).unwrap_or_default();
+            (StoredPersistentId::new(track.persistent_id.get_raw()), CanonicalKey::new(artist.as_deref(), &title, Some(track.numerics.duration)))
+        })
+        .collect::<Vec<_>>();
+    let statistics = played.iter()
+        .map(|track| (StoredPersistentId::new(track.persistent_id.get_raw()), track.played.times, track.played.last))
+        .collect::<Vec<_>>();
+    let plays = statistics.iter().map(|(_, times, _)| u64::from(*times)).sum::<u64>();
+    drop(db);
+
+    crate::store::migrations::migrate().await;
+    let pool = match crate::store::DB_POOL.get().await {
+        Ok(pool) => pool,
+        Err(error) => {
+            crate::console::error!("{}", crate::i18n::database_unavailable(error));
+            return ExitCode::FAILURE;
+        }
+    };
+
+    for (persistent_id, key) in &keys {
+        if let Err(error) = CanonicalTrack::resolve(&pool, *persistent_id, key).await {
+            tracing::error!(?error, %persistent_id, "failed to resolve canonical track of imported track");
+        }
+    }
+
+    match LibraryPlayCount::import(&pool, statistics).await {
+        Ok(tracks) => {
+            crate::console::note!("{}", crate::i18n::import_done(plays, tracks));
+            ExitCode::SUCCESS
+        },
+        Err(error) => {
+            crate::console::error!("{}", crate::i18n::import_store_failed(error));
+            ExitCode::FAILURE
+        }
+    }
+}
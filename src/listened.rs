@@ -29,9 +29,24 @@ pub struct ListenedChunk {
     started_at_song_position: f32,
     /// The actual time when this chunk started.
     started_at: DateTime,
-    duration: chrono::TimeDelta 
+    duration: chrono::TimeDelta,
+    /// The position the player reported when this chunk ended, in seconds, if it's known.
+    /// This can be compared against [`Self::ended_at_song_position`] to find how far playback drifted.
+    actual_ended_at_song_position: Option<f32>,
 }
 impl ListenedChunk {
+    pub const fn started_at(&self) -> DateTime {
+        self.started_at
+    }
+    pub const fn started_at_song_position(&self) -> f32 {
+        self.started_at_song_position
+    }
+    pub const fn duration(&self) -> TimeDelta {
+        self.duration
+    }
+    pub const fn actual_ended_at_song_position(&self) -> Option<f32> {
+        self.actual_ended_at_song_position
+    }
     #[expect(dead_code, reason = "might be useful later")]
     pub fn ended_at(&self) -> DateTime {
         self.started_at.checked_add_signed(self.duration).expect("date out of range")
@@ -41,6 +56,13 @@ impl ListenedChunk {
     }
 }
 
+#[cfg(test)]
+impl ListenedChunk {
+    pub const fn new(started_at_song_position: f32, started_at: DateTime, duration: TimeDelta) -> Self {
+        Self { started_at_song_position, started_at, duration, actual_ended_at_song_position: None }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct CurrentListened {
     started_at_song_position: f32, // seconds
@@ -52,6 +74,7 @@ impl From<CurrentListened> for ListenedChunk {
             started_at: value.started_at,
             started_at_song_position: value.started_at_song_position,
             duration: chrono::Utc::now().signed_duration_since(value.started_at),
+            actual_ended_at_song_position: None,
         }
     }
 }
@@ -117,68 +140,128 @@ impl Listened {
     /// Rid the current listening session to place it into the ordered
     /// array of listening sessions.
     pub fn flush_current(&mut self) {
+        self.flush_current_at(None);
+    }
+
+    /// Like [`Self::flush_current`], but also records the position the player reported
+    /// when the current listening session ended, if it's known.
+    pub fn flush_current_at(&mut self, actual_position: Option<f32>) {
         if let Some(current) = self.current.take() {
             let index = self.find_index_for_current(&current);
-            self.contiguous.insert(index, current.into());
+            let mut chunk = ListenedChunk::from(current);
+            chunk.actual_ended_at_song_position = actual_position;
+            self.contiguous.insert(index, chunk);
         }
     }
     
+    /// Where in the song playback was when the most recently started segment ended, in seconds.
+    pub fn last_position(&self) -> Option<f32> {
+        self.contiguous.iter()
+            .max_by_key(|chunk| chunk.started_at)
+            .map(|chunk| chunk.actual_ended_at_song_position.unwrap_or_else(|| chunk.ended_at_song_position()))
+    }
+
     pub fn set_new_current(&mut self, current_song_position: f32) {
         if self.current.replace(CurrentListened::new_with_position(current_song_position)).is_some() {
             tracing::warn!("overwrote current before it was flushed");
         }
     }
     
-    // TODO: Allow user to configure this behavior for checks instead.
-    #[expect(unused)]
-    pub fn total_heard_unique(&self) -> chrono::TimeDelta {
-        if self.contiguous.is_empty() {
-            return self.current.as_ref()
-                .map(|current| chrono::Utc::now().signed_duration_since(current.started_at))
-                .unwrap_or_default()
-        }
-        
-        let mut total = chrono::TimeDelta::zero();
-        let mut last_end_position = 0.0;
-
-        let current = self.current.clone().map(|current| (
-            self.find_index_for_current(&current),
-            Into::<ListenedChunk>::into(current),
-        ));
-        
-        for index in 0..self.contiguous.len() + usize::from(current.is_some()) {
-            let chunk = if let Some((current_idx, current)) = &current {
-                use core::cmp::Ordering;
-                match index.cmp(current_idx) {
-                    Ordering::Greater => &self.contiguous[index - 1],
-                    Ordering::Equal => current,
-                    Ordering::Less => &self.contiguous[index]
-                }
-            } else { &self.contiguous[index] };
-
-            let chunk_start = chunk.started_at_song_position;
-            let chunk_end = chunk.ended_at_song_position();
-
-            if chunk_end > last_end_position {
-                let len = chunk_end - chunk_start.max(last_end_position);
-                total += chrono::TimeDelta::from_secs_f32(len);
-                last_end_position = chunk_end;
+    /// The segments of the song that have been heard as of the given time, as `(start, end)` positions in seconds,
+    /// sorted and with any overlapping or touching segments merged together.
+    fn heard_ranges_at(&self, now: DateTime) -> Vec<(f32, f32)> {
+        let current = self.current.as_ref().map(|current| {
+            let elapsed = now.signed_duration_since(current.started_at).as_secs_f32();
+            (current.started_at_song_position, current.started_at_song_position + elapsed)
+        });
+
+        let mut ranges = self.contiguous.iter()
+            .map(|chunk| (chunk.started_at_song_position, chunk.ended_at_song_position()))
+            .chain(current)
+            .filter(|(start, end)| end > start)
+            .collect::<Vec<_>>();
+        ranges.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut merged: Vec<(f32, f32)> = Vec::with_capacity(ranges.len());
+        for (start, end) in ranges {
+            match merged.last_mut() {
+                Some(last) if start <= last.1 => last.1 = last.1.max(end),
+                _ => merged.push((start, end))
             }
         }
+        merged
+    }
 
-        total
+    /// Like [`Self::total`], but as of the given moment; listening that's still going on is only counted up to it.
+    pub fn total_at(&self, now: DateTime) -> TimeDelta {
+        self.contiguous.iter()
+            .map(|chunk| chunk.duration)
+            .chain(self.current.as_ref().map(|current| now.signed_duration_since(current.started_at)))
+            .sum()
     }
 
+    fn reached_at_as_of(&self, amount: TimeDelta, now: DateTime) -> Option<DateTime> {
+        let mut spans = self.contiguous.iter()
+            .map(|chunk| (chunk.started_at, chunk.duration))
+            .chain(self.current.as_ref().map(|current| (current.started_at, now.signed_duration_since(current.started_at))))
+            .collect::<Vec<_>>();
+        spans.sort_by_key(|(started_at, _)| *started_at);
+
+        let mut remaining = amount;
+        for (started_at, duration) in spans {
+            if duration >= remaining { return Some(started_at + remaining) }
+            remaining -= duration;
+        }
+        None
+    }
+
+    fn coverage_at(&self, track_duration: core::time::Duration, now: DateTime) -> f32 {
+        let track_duration = track_duration.as_secs_f32();
+        if track_duration <= 0. { return 0. }
+        let heard: f32 = self.heard_ranges_at(now).into_iter()
+            .map(|(start, end)| end.min(track_duration) - start.max(0.))
+            .filter(|length| *length > 0.)
+            .sum();
+        (heard / track_duration).clamp(0., 1.)
+    }
+
+    /// The total amount of time spent listening, including the current listening session.
+    ///
+    /// Re-listening to part of the song (e.x. after seeking backwards) counts each time it's heard;
+    /// see [`Self::coverage`] for how much of the song was actually heard.
     #[allow(unused, reason = "used only by certain featured-gated backends")]
-    pub fn total_heard(&self) -> chrono::TimeDelta {
-        self.contiguous.iter()
-            .map(|d| d.duration)
-            .fold(
-                self.current.as_ref()
-                    .map(|c| chrono::Utc::now().signed_duration_since(c.started_at))
-                    .unwrap_or_default(),
-                |a, b| a + b
-            )
+    pub fn total(&self) -> TimeDelta {
+        self.total_at(chrono::Utc::now())
+    }
+
+    /// When the total amount of time spent listening first reached the given amount, if it has yet.
+    ///
+    /// Segments are counted in the order they were listened to, so time spent paused between them is skipped over.
+    #[allow(unused, reason = "used only by certain featured-gated backends")]
+    pub fn reached_at(&self, amount: TimeDelta) -> Option<DateTime> {
+        self.reached_at_as_of(amount, chrono::Utc::now())
+    }
+
+    /// The fraction of the track that's been heard, from zero to one, with overlapping segments only being counted once.
+    #[allow(unused, reason = "used only by certain featured-gated backends")]
+    pub fn coverage(&self, track_duration: core::time::Duration) -> f32 {
+        self.coverage_at(track_duration, chrono::Utc::now())
+    }
+
+    /// How much of the track has been listened to, as it's published to [subscribers](crate::service::ipc::events).
+    pub fn summarize(&self, track_duration: Option<core::time::Duration>) -> player_events::Listened {
+        let now = chrono::Utc::now();
+        player_events::Listened {
+            total: self.total_at(now).as_secs_f32(),
+            coverage: track_duration.map(|duration| self.coverage_at(duration, now)),
+        }
+    }
+
+    // TODO: Allow user to configure this behavior for checks instead.
+    #[expect(unused)]
+    pub fn total_heard_unique(&self) -> TimeDelta {
+        let heard: f32 = self.heard_ranges_at(chrono::Utc::now()).into_iter().map(|(start, end)| end - start).sum();
+        TimeDelta::from_secs_f32(heard)
     }
 }
 impl Default for Listened {
@@ -186,3 +269,68 @@ impl Default for Listened {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(seconds: i64) -> DateTime {
+        DateTime::from_timestamp(1_700_000_000 + seconds, 0).expect("timestamp in range")
+    }
+
+    fn chunk(started_at: i64, position: f32, seconds: i64) -> ListenedChunk {
+        ListenedChunk {
+            started_at_song_position: position,
+            started_at: at(started_at),
+            duration: TimeDelta::seconds(seconds),
+            actual_ended_at_song_position: None,
+        }
+    }
+
+    #[test]
+    fn total() {
+        let mut listened = Listened::new();
+        assert_eq!(listened.total_at(at(0)), TimeDelta::zero());
+
+        listened.contiguous = vec![chunk(0, 0., 30), chunk(30, 10., 20)];
+        assert_eq!(listened.total_at(at(50)), TimeDelta::seconds(50));
+
+        listened.current = Some(CurrentListened { started_at_song_position: 100., started_at: at(60) });
+        assert_eq!(listened.total_at(at(70)), TimeDelta::seconds(60));
+    }
+
+    #[test]
+    fn coverage() {
+        let track = core::time::Duration::from_secs(100);
+        let mut listened = Listened::new();
+        assert!(listened.coverage_at(track, at(0)).abs() < f32::EPSILON);
+
+        // 0-30 and 10-30 overlap, 50-60 is disjoint.
+        listened.contiguous = vec![chunk(0, 0., 30), chunk(30, 10., 20), chunk(50, 50., 10)];
+        assert!((listened.coverage_at(track, at(60)) - 0.4).abs() < 1e-6);
+        assert_eq!(listened.heard_ranges_at(at(60)), vec![(0., 30.), (50., 60.)]);
+
+        // The current segment joins the two, and runs past the end of the track.
+        listened.current = Some(CurrentListened { started_at_song_position: 25., started_at: at(60) });
+        assert_eq!(listened.heard_ranges_at(at(150)), vec![(0., 115.)]);
+        assert!((listened.coverage_at(track, at(150)) - 1.).abs() < f32::EPSILON);
+
+        assert!(listened.coverage_at(core::time::Duration::ZERO, at(150)).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn reached_at() {
+        let mut listened = Listened::new();
+        assert_eq!(listened.reached_at_as_of(TimeDelta::zero(), at(0)), None);
+
+        // Listened for 30 seconds, paused for a minute, then listened for another 20; the segments are ordered by song position, not time.
+        listened.contiguous = vec![chunk(90, 0., 20), chunk(0, 40., 30)];
+        assert_eq!(listened.reached_at_as_of(TimeDelta::seconds(10), at(110)), Some(at(10)));
+        assert_eq!(listened.reached_at_as_of(TimeDelta::seconds(30), at(110)), Some(at(30)));
+        assert_eq!(listened.reached_at_as_of(TimeDelta::seconds(40), at(110)), Some(at(100)));
+        assert_eq!(listened.reached_at_as_of(TimeDelta::seconds(60), at(110)), None);
+
+        listened.current = Some(CurrentListened { started_at_song_position: 60., started_at: at(200) });
+        assert_eq!(listened.reached_at_as_of(TimeDelta::seconds(60), at(230)), Some(at(210)));
+    }
+}
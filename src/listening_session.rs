@@ -0,0 +1,149 @@
+//! Summing up a stretch of listening once it's over, which is taken to be once nothing has played for a while.
+//!
+//! A short pause or the gap between two albums doesn't end it, but the player being stopped,
+//! paused by a sleep timer, or closed for long enough does.
+use chrono::TimeDelta;
+use serde::{Deserialize, Serialize};
+
+use crate::listened::{Listened, TimeDeltaExtension as _};
+
+type DateTime = chrono::DateTime<chrono::Utc>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub enabled: bool,
+    /// How long nothing has to have played for before the session is over, in seconds.
+    pub idle: u64,
+}
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            idle: 15 * 60,
+        }
+    }
+}
+impl Config {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Debug)]
+struct Tally {
+    started: DateTime,
+    tracks: u32,
+    listened: TimeDelta,
+}
+
+/// Tallies up what's listened to, ending the session once nothing has played for long enough.
+#[derive(Debug)]
+pub struct Tracker {
+    config: Config,
+    tally: Option<Tally>,
+    /// When playback last stopped, unless it's since resumed.
+    idle_since: Option<DateTime>,
+    /// How much of a track that was left paused was counted towards the session, for it to not be counted twice once it ends.
+    counted: Option<TimeDelta>,
+}
+impl Tracker {
+    pub const fn new(config: Config) -> Self {
+        Self { config, tally: None, idle_since: None, counted: None }
+    }
+
+    pub const fn reconfigure(&mut self, config: Config) {
+        self.config = config;
+    }
+
+    /// Counts a track which has finished towards the session, starting one if there isn't one going.
+    pub fn track_ended(&mut self, listened: &Listened, now: DateTime) {
+        self.count(listened, now);
+        self.counted = None;
+    }
+
+    /// Counts whatever of the track hasn't been already, remembering how much has been in case it's resumed.
+    fn count(&mut self, listened: &Listened, now: DateTime) {
+        if !self.config.enabled { return }
+        let total = listened.total_at(now);
+        let uncounted = total - self.counted.unwrap_or_default();
+        if uncounted <= TimeDelta::zero() { return }
+
+        let tally = self.tally.get_or_insert_with(|| Tally {
+            started: listened.started_at().filter(|_| self.counted.is_none()).unwrap_or(now - uncounted),
+            tracks: 0,
+            listened: TimeDelta::zero(),
+        });
+        if self.counted.is_none() {
+            tally.tracks += 1;
+        }
+        tally.listened += uncounted;
+        self.counted = Some(total);
+    }
+
+    /// Notes whether anything is playing as of a poll, returning the session if nothing has for long enough for it to be over.
+    /// What's been heard of a track that's left `paused` is counted towards it.
+    pub fn poll(&mut self, playing: bool, paused: Option<&Listened>, now: DateTime) -> Option<player_events::ListeningSession> {
+        if playing {
+            self.idle_since = None;
+            return None
+        }
+
+        let idle_since = *self.idle_since.get_or_insert(now);
+        let idle_for = TimeDelta::seconds(self.config.idle.try_into().unwrap_or(i64::MAX));
+        if !self.config.enabled || now - idle_since < idle_for { return None }
+
+        if let Some(paused) = paused {
+            // Only up until it was paused, as it's otherwise seen as still going.
+            self.count(paused, idle_since);
+        }
+        let tally = self.tally.take()?;
+        Some(player_events::ListeningSession {
+            started: tally.started.timestamp_millis(),
+            ended: idle_since.timestamp_millis(),
+            tracks: tally.tracks,
+            listened: tally.listened.as_secs_f32(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::listened::ListenedChunk;
+
+    fn listened(at: DateTime, seconds: i64) -> Listened {
+        Listened { contiguous: vec![ListenedChunk::new(0., at, TimeDelta::seconds(seconds))], current: None }
+    }
+
+    #[test]
+    fn ends_after_going_idle() {
+        let start = chrono::Utc::now();
+        let minutes = |n: i64| start + TimeDelta::minutes(n);
+        let mut tracker = Tracker::new(Config::default());
+
+        assert!(tracker.poll(true, None, start).is_none());
+        tracker.track_ended(&listened(start, 180), minutes(3));
+        tracker.track_ended(&listened(minutes(3), 240), minutes(7));
+        assert!(tracker.poll(false, None, minutes(7)).is_none());
+        assert!(tracker.poll(false, None, minutes(10)).is_none(), "a short break shouldn't end the session");
+        assert!(tracker.poll(true, None, minutes(10)).is_none());
+
+        // left paused, such as by a sleep timer
+        let paused = listened(minutes(10), 60);
+        assert!(tracker.poll(false, Some(&paused), minutes(11)).is_none());
+        let session = tracker.poll(false, Some(&paused), minutes(26)).expect("session should be over");
+        assert_eq!(session.started, start.timestamp_millis());
+        assert_eq!(session.ended, minutes(11).timestamp_millis());
+        assert_eq!(session.tracks, 3);
+        assert_eq!(session.minutes(), 8);
+        assert!(tracker.poll(false, Some(&paused), minutes(40)).is_none(), "it should only end once");
+
+        // resuming the paused track doesn't count it again
+        assert!(tracker.poll(true, None, minutes(60)).is_none());
+        tracker.track_ended(&listened(minutes(10), 90), minutes(61));
+        assert!(tracker.poll(false, None, minutes(61)).is_none());
+        let session = tracker.poll(false, None, minutes(80)).expect("session should be over");
+        assert_eq!((session.tracks, session.listened), (0, 30.));
+    }
+}
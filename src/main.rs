@@ -26,6 +26,7 @@ use crate::config::LoadableConfig;
 
 mod subscribers;
 mod listened;
+mod listening_session;
 mod debugging;
 mod data_fetching;
 mod service;
@@ -33,6 +34,21 @@ mod config;
 mod cli;
 mod util;
 mod store;
+mod http;
+mod net;
+mod now;
+mod player;
+mod history;
+mod uncensored;
+mod replay;
+mod sync;
+mod connectivity;
+mod console;
+mod gui;
+mod i18n;
+#[cfg(feature = "musicdb")]
+mod import;
+mod report;
 
 const POLL_INTERVAL: Duration = Duration::from_millis(500);
 
@@ -74,6 +90,7 @@ async fn main() -> ExitCode {
     use cli::Command;
 
     let args = Box::leak(Box::new(<cli::Cli as clap::Parser>::parse()));
+    console::configure(args);
     let config = config::Config::get(args).await;
     let debugging = debugging::DebuggingSession::new(args);
     let (terminating, termination_signal) = watch_for_termination();
@@ -83,9 +100,9 @@ async fn main() -> ExitCode {
             match config {
                 Ok(config) => Ok(config),
                 Err(error) => match error {
-                    ConfigRetrievalError::UnknownFs { inner, .. } => util::ferror!("could not read config: {inner}"),
-                    ConfigRetrievalError::DeserializationFailure { inner, .. } => util::ferror!("could not read config: deserialization failure: {inner}"),
-                    ConfigRetrievalError::PermissionDenied(path) => util::ferror!("could not read config: lacking permission to read {}", path.to_string_lossy()),
+                    ConfigRetrievalError::UnknownFs { inner, .. } => util::ferror!("{}", i18n::config_unreadable(inner)),
+                    ConfigRetrievalError::DeserializationFailure { inner, .. } => util::ferror!("{}", i18n::config_undeserializable(inner)),
+                    ConfigRetrievalError::PermissionDenied(path) => util::ferror!("{}", i18n::config_permission_denied(path.to_string_lossy())),
                     ConfigRetrievalError::NotFound(path) => { Err(path) }
                 }
             }
@@ -94,21 +111,23 @@ async fn main() -> ExitCode {
 
     macro_rules! get_config_or_error {
         () => {
-            get_config_or_path!().unwrap_or_else(|path| util::ferror!("no configuration file @ {}", path.to_string_lossy()))
+            get_config_or_path!().unwrap_or_else(|path| util::ferror!("{}", i18n::config_missing(path.to_string_lossy())))
         }
     }
 
     match args.command {
-        Command::Start { kill_existing } => {
+        Command::Start { kill_existing, dry_run } => {
+            gui::require(&i18n::gui_action_watching());
+
             if let Some(pid) = ActiveProcessLockfile::get().await {
                 if kill_existing {
                     unsafe { libc::kill(pid, libc::SIGTERM); }
                     tokio::time::sleep(Duration::from_millis(500)).await;
                 } else {
-                    eprintln!("Another instance of the program is already running! (pid {pid})");
+                    console::error!("{}", i18n::instance_already_running(pid));
 
                     if service::ServiceController::is_running().await {
-                        eprintln!("You can turn off the service with `am-osx-status service stop`.");
+                        console::note!("{}", i18n::instance_stop_service_hint());
                     }
 
                     return ExitCode::FAILURE;
@@ -124,32 +143,57 @@ async fn main() -> ExitCode {
                     config.save_to_disk().await;
                     config
                 },
-                Err(path) => if config::wizard::io::prompt_bool(match path {
-                    ConfigPathChoice::Automatic(..) => "No configuration has been set up! Would you like to use the wizard to build one?",
-                    ConfigPathChoice::Explicit(..) => "No configuration exists at the provided file! Would you like to use the wizard to build it?",
-                    ConfigPathChoice::Environmental(..) => "No configuration exists at the file specified in the environmental variable! Would you like to use the wizard to build it?",
+                Err(_) if !console::is_interactive() => {
+                    console::note!("{}", i18n::config_wizard_not_offered());
+                    console::note!("{}", i18n::config_using_temporary_default());
+                    config::Config::default()
+                },
+                Err(path) => if config::wizard::io::prompt_bool(&match path {
+                    ConfigPathChoice::Automatic(..) => i18n::config_offer_wizard_automatic(),
+                    ConfigPathChoice::Explicit(..) => i18n::config_offer_wizard_explicit(),
+                    ConfigPathChoice::Environmental(..) => i18n::config_offer_wizard_environmental(),
                 }) {
                     let config = config::Config::create_with_wizard(path).await;
                     config.save_to_disk().await;
-                    println!("Configuration file has been saved.");
+                    console::note!("{}", i18n::config_saved());
                     config
                 } else {
-                    println!("Proceeding with a temporary default configuration.");
+                    console::note!("{}", i18n::config_using_temporary_default());
                     config::Config::default()
                 }
             };
 
-            let context = Arc::new(Mutex::new(PollingContext::from_config(&config, Arc::clone(&terminating)).await));
+            let context = Arc::new(Mutex::new(PollingContext::from_config(&config, dry_run, Arc::clone(&terminating)).await));
             let context_for_finalizer = Arc::clone(&context);
 
             let config = Arc::new(Mutex::new(config));
 
-            let ipc_listener = if args.running_as_service {
-                Some(service::ipc::listen(
-                    context.clone(),
-                    config.clone()
-                ).await)
-            } else { None };
+            // Instances outside of the service listen too, so that the service can ask them to make way for it.
+            let ipc_listener = service::ipc::listen(
+                context.clone(),
+                config.clone()
+            ).await;
+            tokio::spawn(data_fetching::artwork_cache::prune());
+
+            let http_server = {
+                let config = config.lock().await;
+                let hub = (config.sync.enabled && config.sync.hub.is_none()).then(|| config.sync.clone());
+                if config.http_server.enabled {
+                    crate::http::serve(context.clone(), &config.http_server, hub).await
+                        .inspect_err(|error| tracing::error!(?error, "failed to start http server"))
+                        .ok()
+                } else {
+                    if hub.is_some() { tracing::warn!("this machine is configured as the sync hub, but the http server it's served over is disabled"); }
+                    None
+                }
+            };
+
+            let sync = {
+                let config = config.lock().await;
+                if config.sync.enabled { sync::spawn(config.sync.clone()) } else { None }
+            };
+
+            let connectivity = connectivity::spawn(config.lock().await.connectivity.clone());
 
             let main_loop = tokio::spawn(async move {
                 tracing::info!("starting main loop");
@@ -171,8 +215,12 @@ async fn main() -> ExitCode {
                     () = tokio::time::sleep(Duration::from_secs(5)) => { tracing::warn!("main loop did not quickly exit after termination signal; proceeding regardless"); }
                 }
 
-                let context = context_for_finalizer.lock().await;
-                if let Some(ipc_listener) = ipc_listener { ipc_listener.abort(); }
+                let mut context = context_for_finalizer.lock().await;
+                context.session.dispatches = subscribers::statistics::summarize();
+                ipc_listener.abort();
+                if let Some(http_server) = http_server { http_server.abort(); }
+                if let Some(sync) = sync { sync.abort(); }
+                if let Some(connectivity) = connectivity { connectivity.abort(); }
 
                 let db_pool = &store::DB_POOL.get().await.expect("failed to get database pool");
                 let (cleared_lockfile, session_finished, ()) = tokio::join!(
@@ -189,13 +237,46 @@ async fn main() -> ExitCode {
 
             finalizer.await.expect("finalizer task panicked");
         },
+        Command::Now { ref format } => return now::print(format).await,
+        Command::History { podcasts, limit } => return history::print(podcasts, limit).await,
+        Command::Uncensor { ref action } => return uncensored::run(action).await,
+        Command::Replay { since, backend, dry_run } => return replay::run(&get_config_or_error!(), since, backend, dry_run).await,
+        Command::Report { ref action } => match action {
+            cli::ReportAction::Bundle { output, lines } => return report::bundle(output.clone(), *lines).await,
+        },
+        #[cfg(feature = "musicdb")]
+        Command::Import { ref source } => match source {
+            cli::ImportSource::Musicdb { path } => {
+                let path = path.clone()
+                    .or_else(|| config.as_ref().ok().map(|config| config.musicdb.path.clone()))
+                    .unwrap_or_else(musicdb::MusicDB::default_path);
+                return import::musicdb(path).await
+            }
+        },
         Command::Service { ref action } => {
             use cli::ServiceAction;
             use service::{ServiceController, ipc};
 
             match action {
-                ServiceAction::Start => ServiceController::start(get_config_or_error!().path.as_path(), true).await,
-                ServiceAction::Stop => ServiceController::stop(true).await,
+                ServiceAction::Start { adopt } => {
+                    let config = get_config_or_error!();
+                    if let Some(pid) = ActiveProcessLockfile::get().await && ServiceController::pid().await != Some(pid) {
+                        if !adopt {
+                            console::error!("{}", i18n::service_instance_running_independently(pid));
+                            console::note!("{}", i18n::service_adopt_hint());
+                            return ExitCode::FAILURE;
+                        }
+                        console::note!("{}", i18n::service_adopting(pid));
+                        if !ServiceController::adopt(pid, &config.socket_path).await {
+                            ferror!("{}", i18n::service_adoption_timed_out(pid));
+                        }
+                    }
+                    ServiceController::start(config.path.as_path(), &config.service, true).await;
+                },
+                ServiceAction::Stop => {
+                    gui::require(&i18n::gui_action_service_stop());
+                    ServiceController::stop(true).await;
+                },
                 ServiceAction::Status => {
                     enum ServiceDefinitionStatus {
                         Installed,
@@ -209,31 +290,80 @@ async fn main() -> ExitCode {
                         Err(err) => ServiceDefinitionStatus::Indeterminate(err)
                     };
 
-                    if let Some(pid) = ServiceController::pid().await {
-                        println!("Service is running with PID {pid}.");
+                    let service_pid = ServiceController::pid().await;
+                    let independent_pid = if service_pid.is_none() { ActiveProcessLockfile::get().await } else { None };
+
+                    let session = if service_pid.is_some() || independent_pid.is_some() {
+                        let path = config.as_ref().map_or_else(|_| ipc::socket_path::clone_default(), |config| config.socket_path.clone());
+                        ipc::query_status(path).await
+                            .inspect_err(|err| console::warning!("{}", i18n::service_status_unavailable(err)))
+                            .ok()
+                    } else { None };
+
+                    if console::is_json() {
+                        #[derive(serde::Serialize)]
+                        struct Status {
+                            running: bool,
+                            pid: Option<libc::pid_t>,
+                            /// `None` if it couldn't be determined.
+                            installed: Option<bool>,
+                            /// The PID of an instance running outside of the service.
+                            independent_pid: Option<libc::pid_t>,
+                            /// What the running instance reported about itself, if it could be reached.
+                            session: Option<ipc::packets::Status>,
+                        }
+
+                        let installed = match status {
+                            ServiceDefinitionStatus::Installed => Some(true),
+                            ServiceDefinitionStatus::NotInstalled => Some(false),
+                            ServiceDefinitionStatus::Indeterminate(err) => {
+                                console::warning!("{}", i18n::service_installation_indeterminate(err));
+                                None
+                            }
+                        };
+                        console::json(&Status { running: service_pid.is_some(), pid: service_pid, installed, independent_pid, session });
+                    } else if let Some(pid) = service_pid {
+                        console::out!("{}", i18n::service_running(pid));
                         match status {
                             ServiceDefinitionStatus::Installed => {}
-                            ServiceDefinitionStatus::NotInstalled => println!("The definition has since been removed, though, so it will not start automatically after shutdown."),
-                            ServiceDefinitionStatus::Indeterminate(err) => println!("Could not determine if the service is installed: {err}"),
+                            ServiceDefinitionStatus::NotInstalled => console::out!("{}", i18n::service_running_but_removed()),
+                            ServiceDefinitionStatus::Indeterminate(err) => console::warning!("{}", i18n::service_installation_indeterminate(err)),
                         }   
-                    } else if let Some(pid) = ActiveProcessLockfile::get().await {
-                        println!("Service is not running, but an instance of the program is running independently with PID {pid}.");
+                        if let Some(session) = session { session.print(); }
+                    } else if let Some(pid) = independent_pid {
+                        console::out!("{}", i18n::service_not_running_but_independent(pid));
                         match status {
-                            ServiceDefinitionStatus::Installed => println!("It is installed and will start automatically on login, or can be manually started after the running instance is closed."),
-                            ServiceDefinitionStatus::NotInstalled => println!("The service is not currently installed."),
-                            ServiceDefinitionStatus::Indeterminate(err) => println!("Could not determine if the service is installed: {err}"),
+                            ServiceDefinitionStatus::Installed => console::out!("{}", i18n::service_installed_behind_independent()),
+                            ServiceDefinitionStatus::NotInstalled => console::out!("{}", i18n::service_not_installed()),
+                            ServiceDefinitionStatus::Indeterminate(err) => console::warning!("{}", i18n::service_installation_indeterminate(err)),
                         }
+                        if let Some(session) = session { session.print(); }
                     } else {
-                        print!("Service is not running");
                         match status {
-                            ServiceDefinitionStatus::Installed => println!(", but it is installed and will start automatically on login."),
-                            ServiceDefinitionStatus::NotInstalled => println!(" and is not installed."),
-                            ServiceDefinitionStatus::Indeterminate(err) => println!(".\nCould not determine if it is installed: {err}"),
+                            ServiceDefinitionStatus::Installed => console::out!("{}", i18n::service_not_running_but_installed()),
+                            ServiceDefinitionStatus::NotInstalled => console::out!("{}", i18n::service_not_running_nor_installed()),
+                            ServiceDefinitionStatus::Indeterminate(err) => {
+                                console::out!("{}", i18n::service_not_running());
+                                console::warning!("{}", i18n::service_installation_indeterminate(err));
+                            },
                         }
                     }
                 },
-                ServiceAction::Restart => ServiceController::restart(get_config_or_error!().path.as_path()).await,
+                ServiceAction::Restart => {
+                    let config = get_config_or_error!();
+                    ServiceController::restart(config.path.as_path(), &config.service).await;
+                },
                 ServiceAction::Remove => ServiceController::remove().await,
+                ServiceAction::Events { artwork } => {
+                    use ipc::{Packet, PacketConnection};
+                    let path = get_config_or_error!().socket_path;
+                    let mut connection = PacketConnection::from_path(path).await.unwrap_or_else(|err| ferror!("{}", i18n::service_unreachable(err)));
+                    connection.send(Packet::hello()).await.expect("failed to send hello packet");
+                    let subscription = ipc::packets::Subscribe { artwork: *artwork };
+                    if let Err(err) = connection.follow_events(subscription, &mut tokio::io::stdout()).await {
+                        ferror!("{}", i18n::service_connection_lost(err));
+                    }
+                },
                 #[cfg(debug_assertions)]
                 ServiceAction::Reload => {
                     use ipc::{Packet, PacketConnection};
@@ -241,7 +371,7 @@ async fn main() -> ExitCode {
                     let mut connection = PacketConnection::from_path(path).await.unwrap();
                     connection.send(Packet::hello()).await.expect("failed to send hello packet");
                     connection.send(Packet::ReloadConfiguration).await.expect("failed to send reload packet");
-                    println!("Reload command sent to service.");
+                    console::note!("{}", i18n::service_reload_sent());
                 }
             }
         },
@@ -271,36 +401,65 @@ async fn main() -> ExitCode {
 
                     let show_reason = show_reason.unwrap_or_else(|| std::io::stdout().is_terminal());
 
-                    println!("{path_str}");
+                    if console::is_json() {
+                        #[derive(serde::Serialize)]
+                        struct Location<'a> {
+                            path: &'a str,
+                            /// Why the path is used.
+                            source: &'static str,
+                            /// Why the configuration couldn't be read from it, if it couldn't.
+                            problem: Option<&'static str>,
+                        }
+
+                        console::json(&Location {
+                            path: &path_str,
+                            source: match path {
+                                ConfigPathChoice::Explicit(_) => "explicit",
+                                ConfigPathChoice::Environmental(_) => "environmental",
+                                ConfigPathChoice::Automatic(_) => "automatic",
+                            },
+                            problem: config.as_ref().err().map(|err| match err {
+                                ConfigRetrievalError::DeserializationFailure { .. } => "deserialization_failure",
+                                ConfigRetrievalError::NotFound { .. } => "not_found",
+                                ConfigRetrievalError::PermissionDenied(_) => "permission_denied",
+                                ConfigRetrievalError::UnknownFs { .. } => "unknown",
+                            }),
+                        });
+                        return ExitCode::SUCCESS;
+                    }
+
+                    console::out!("{path_str}");
                     if show_reason {
-                        use config::ConfigRetrievalError;
-                        eprint!("This path is used because it is {}", path.describe_for_choice_reasoning_suffix());
+                        let reason = path.describe_for_choice_reasoning_suffix();
                         if let Err(err) = &config {
-                            use alloc::borrow::Cow;
-                            eprintln!(", but {}", match err {
-                                ConfigRetrievalError::DeserializationFailure { .. } => Cow::Borrowed("it couldn't be successfully deserialized"),
-                                ConfigRetrievalError::NotFound { .. } => Cow::Borrowed(if path.was_auto() { "it currently doesn't exist" } else { "it couldn't be found" }),
-                                ConfigRetrievalError::PermissionDenied(_) => Cow::Borrowed("the required permissions to read it are not available"),
-                                ConfigRetrievalError::UnknownFs { inner, .. } => Cow::Owned(format!("an unknown error occurred trying to read it ({inner})"))
-                            });
+                            eprintln!("{}", i18n::config_path_reason_despite(reason, match err {
+                                ConfigRetrievalError::DeserializationFailure { .. } => i18n::config_problem_undeserializable(),
+                                ConfigRetrievalError::NotFound { .. } => if path.was_auto() { i18n::config_problem_nonexistent() } else { i18n::config_problem_not_found() },
+                                ConfigRetrievalError::PermissionDenied(_) => i18n::config_problem_permission_denied(),
+                                ConfigRetrievalError::UnknownFs { inner, .. } => i18n::config_problem_unknown(inner),
+                            }));
                         } else {
-                            eprintln!(".");
+                            eprintln!("{}", i18n::config_path_reason(reason));
                         }
                     }
                 },
                 ConfigurationAction::Wizard => {
+                    if !console::is_interactive() {
+                        let path = config.as_ref().map_or_else(|error| error.path().to_string_lossy().into_owned(), |config| config.path.to_string_lossy().into_owned());
+                        ferror!("{}", i18n::wizard_not_interactive(path));
+                    }
                     match get_config_or_path!() {
                         Err(path) => {
-                            println!("Creating configuration file @ {}", path.to_string_lossy());
+                            console::note!("{}", i18n::config_creating(path.to_string_lossy()));
                             let config = config::Config::create_with_wizard(path).await;
                             config.save_to_disk().await;
-                            println!("Successfully saved changes!");
+                            console::note!("{}", i18n::config_changes_saved());
                         }
                         Ok(mut config) => {
-                            println!("Modifying configuration file @ {}", config.path.to_string_lossy());
+                            console::note!("{}", i18n::config_modifying(config.path.to_string_lossy()));
                             config.edit_with_wizard().await;
                             config.save_to_disk().await;
-                            println!("Successfully saved changes!");
+                            console::note!("{}", i18n::config_changes_saved());
                         },
                     }
                 },
@@ -334,14 +493,25 @@ struct PollingContext {
     pub last_track: Option<Arc<DispatchableTrack>>,
     pub listened: Arc<Mutex<Listened>>,
     artwork_manager: Arc<data_fetching::components::artwork::ArtworkManager>,
+    /// Additional data about the current track which didn't arrive in time to be dispatched with its start.
+    pending_track_data: Option<data_fetching::PendingTrackData>,
     
     #[cfg(feature = "musicdb")]
     musicdb: Arc<Option<musicdb::MusicDB>>,
-    jxa: osa_apple_music::Session,
+    jxa: Box<dyn player::Player>,
     player_open: bool,
     #[expect(dead_code, reason = "planned to be used in the future")]
     player_paused: Option<bool>,
+    /// The volume as of the last poll, so that changes to it can be dispatched.
+    player_volume: Option<subscribers::DispatchedVolume>,
+    /// Where playback was as of the last poll, re-anchored on each one so that drift between them doesn't build up.
+    playback: Option<player_events::Playback>,
     session: store::entities::Session,
+    /// Whether a dry run was requested from the command line, regardless of configuration.
+    dry_run: bool,
+    overrides: Arc<[subscribers::overrides::Override]>,
+    sources: player::sources::Config,
+    listening_session: listening_session::Tracker,
 
     redispatch_start_requesters: Arc<Mutex<crate::subscribers::BackendIdentitySet>>, 
     redispatch_start_request_tx: tokio::sync::mpsc::Sender<crate::subscribers::BackendIdentity>,   
@@ -349,7 +519,24 @@ struct PollingContext {
     redispatch_start_request_rx_processor: tokio::task::JoinHandle<()>,
 }
 impl PollingContext {
-    async fn from_config(config: &config::Config, terminating: Terminating) -> Self {
+    async fn from_config(config: &config::Config, dry_run: bool, terminating: Terminating) -> Self {
+        Self::with_player(config, dry_run, terminating, async {
+            let jxa_socket = crate::util::APPLICATION_SUPPORT_FOLDER.join("osa-socket");
+            Box::new(osa_apple_music::Session::new(jxa_socket).await.expect("failed to create JXA session")) as Box<dyn player::Player>
+        }).await
+    }
+
+    /// Like [`Self::from_config`], but asking the given player what's playing instead of Apple Music.
+    async fn with_player(
+        config: &config::Config,
+        dry_run: bool,
+        terminating: Terminating,
+        player: impl Future<Output = Box<dyn player::Player>> + Send,
+    ) -> Self {
+        net::configure(&config.network);
+        report::configure(&config.report);
+        data_fetching::limits::configure(&config.fetch_limits);
+        data_fetching::services::itunes::configure(&config.itunes);
         #[cfg(feature = "musicdb")]
         let musicdb: core::pin::Pin<Box<dyn Send + Future<Output = Result<Option<musicdb::MusicDB>, _>>>> = {
             let path = config.musicdb.path.clone();
@@ -385,21 +572,22 @@ impl PollingContext {
         };
 
         let (backends, artwork_manager, migration_id, musicdb, (jxa, player_version)) = tokio::join!(
-            subscribers::Backends::new(config, redispatch_start_request_tx.clone()),
+            subscribers::Backends::new(config, dry_run, redispatch_start_request_tx.clone()),
             data_fetching::components::artwork::ArtworkManager::new(&config.artwork_hosts),
             store::migrations::migrate(),
             musicdb,
             async {
-                let jxa_socket = crate::util::APPLICATION_SUPPORT_FOLDER.join("osa-socket");
-                let mut jxa = osa_apple_music::Session::new(jxa_socket).await.expect("failed to create JXA session");
+                let mut jxa = player.await;
                 // TODO: Get the player version without JXA, so that the player doesn't need to be open.
-                let player_version = jxa.application().await.expect("failed to retrieve application data").map_or_else(|| "?".into(), |app| app.version);
+                let player_version = jxa.version().await.unwrap_or_else(|| "?".into());
                 (jxa, player_version)
             }
         );
 
         let session = store::entities::Session::new(&player_version, migration_id)
-            .await.unwrap_or_else(|err| ferror!("failed to create session in database: {}", err));
+            .await.unwrap_or_else(|err| ferror!("{}", i18n::session_creation_failed(err)));
+
+        backends.replay_journal().await;
 
         #[cfg_attr(not(feature = "musicdb"), expect(unused_variables, reason = "unused when disabled"))]
         let musicdb = match musicdb {
@@ -416,12 +604,19 @@ impl PollingContext {
             last_track: None,
             listened: Arc::new(Mutex::new(Listened::new())),
             artwork_manager: Arc::new(artwork_manager),
+            pending_track_data: None,
             #[cfg(feature = "musicdb")]
             musicdb,
             jxa,
             player_open: player_version != "?",
             player_paused: None,
+            player_volume: None,
+            playback: None,
             session,
+            dry_run,
+            overrides: config.overrides.clone().into(),
+            sources: config.sources.clone(),
+            listening_session: listening_session::Tracker::new(config.listening_sessions.clone()),
 
             redispatch_start_requesters,
             redispatch_start_request_tx,
@@ -430,12 +625,124 @@ impl PollingContext {
     }
 
     async fn reload_from_config(&mut self, config: &config::Config) {
-        self.backends = subscribers::Backends::new(config, self.redispatch_start_request_tx.clone()).await;
+        net::configure(&config.network);
+        report::configure(&config.report);
+        data_fetching::limits::configure(&config.fetch_limits);
+        data_fetching::services::itunes::configure(&config.itunes);
+        self.backends = subscribers::Backends::new(config, self.dry_run, self.redispatch_start_request_tx.clone()).await;
+        self.overrides = config.overrides.clone().into();
+        self.sources = config.sources.clone();
+        self.listening_session.reconfigure(config.listening_sessions.clone());
+    }
+
+    /// Records the player being on a different version than it was last seen with, such as from it having been
+    /// updated and relaunched, and lets the backends know.
+    async fn update_player_version(&mut self, version: &str) {
+        let previous_version = (self.session.player_version != "?").then(|| Arc::from(self.session.player_version.as_str()));
+        tracing::info!(previous = ?previous_version, version, "player version changed");
+        let recorded = match store::DB_POOL.get().await {
+            Ok(pool) => self.session.set_player_version(&pool, version).await
+                .inspect_err(|error| tracing::error!(?error, "failed to record new player version"))
+                .is_ok(),
+            Err(error) => {
+                tracing::error!(?error, "failed to get database pool to record new player version");
+                false
+            }
+        };
+        if !recorded {
+            // Otherwise it'd be seen as having changed again on every poll.
+            version.clone_into(&mut self.session.player_version);
+        }
+        self.backends.dispatch_application_status(subscribers::DispatchedApplicationStatus {
+            version: Arc::from(version),
+            previous_version,
+        }).await;
+    }
+
+    /// Ends the listening session if nothing has been `playing` for long enough, letting the backends know.
+    async fn poll_listening_session(&mut self, playing: bool) {
+        let paused = if self.last_track.is_some() { Some(self.listened.lock().await) } else { None };
+        let ended = self.listening_session.poll(playing, paused.as_deref(), chrono::Utc::now());
+        drop(paused);
+        if let Some(session) = ended {
+            tracing::info!(tracks = session.tracks, minutes = session.minutes(), "listening session ended");
+            self.backends.dispatch_listening_session_ended(session).await;
+        }
     }
 
     pub fn is_terminating(&self) -> bool {
         self.terminating.load(core::sync::atomic::Ordering::Relaxed)
     }
+
+    /// Ends the listen of the current track, if there is one, as there's nothing playing anymore.
+    async fn end_track(&mut self, player: &Arc<osa_apple_music::ApplicationData>) {
+        self.listened.lock().await.flush_current();
+
+        if let Some(previous) = self.last_track.clone() {
+            let listened = self.listened.clone();
+            self.listened = Arc::new(Mutex::new(Listened::new()));
+            self.last_track = None;
+            self.pending_track_data = None;
+            self.listening_session.track_ended(&*listened.lock().await, chrono::Utc::now());
+            self.record_listen(&previous, listened.clone());
+            self.backends.dispatch_track_ended(BackendContext {
+                listened,
+                track: previous,
+                player: player.clone(),
+                data: ().into(),
+                #[cfg(feature = "musicdb")]
+                musicdb: self.musicdb.clone()
+            }).await;
+        }
+    }
+
+    /// Persist the flushed segments of a finished listen to the store in the background.
+    ///
+    /// Podcasts and audiobooks instead have where they were left off recorded, so that they can be resumed.
+    fn record_listen(&self, track: &DispatchableTrack, listened: Arc<Mutex<Listened>>) {
+        if !track.media_kind.is_music() {
+            self.record_progress(track, listened);
+            return
+        }
+
+        let session = self.session.id();
+        let key = store::canonical::CanonicalKey::from(track);
+        let track = track.clone();
+        tokio::spawn(async move {
+            let pool = match store::DB_POOL.get().await {
+                Ok(pool) => pool,
+                Err(error) => {
+                    tracing::error!(?error, "failed to get database pool to record listen");
+                    return
+                }
+            };
+            let canonical = store::entities::CanonicalTrack::resolve(&pool, track.persistent_id, &key).await
+                .inspect_err(|error| tracing::error!(?error, "failed to resolve canonical track of listen"))
+                .ok();
+            let listened = listened.lock().await;
+            if let Err(error) = store::entities::Listen::new(&pool, session, &track, canonical, &listened).await {
+                tracing::error!(?error, "failed to record listen");
+            }
+        }.instrument(tracing::trace_span!("listen recording")));
+    }
+
+    fn record_progress(&self, track: &DispatchableTrack, listened: Arc<Mutex<Listened>>) {
+        let session = self.session.id();
+        let track = track.clone();
+        tokio::spawn(async move {
+            let Some(position) = listened.lock().await.last_position() else { return };
+            let pool = match store::DB_POOL.get().await {
+                Ok(pool) => pool,
+                Err(error) => {
+                    tracing::error!(?error, "failed to get database pool to record progress");
+                    return
+                }
+            };
+            if let Err(error) = store::entities::MediaProgress::new(&pool, session, &track, position).await {
+                tracing::error!(?error, "failed to record progress");
+            }
+        }.instrument(tracing::trace_span!("progress recording")));
+    }
 }
 
 #[expect(clippy::significant_drop_tightening, reason = "concurrent execution of this function is undesirable")]
@@ -450,10 +757,13 @@ async fn proc_once(context: Arc<Mutex<PollingContext>>) {
             Arc::new(player)
         },
         Ok(None) => {
+            context.poll_listening_session(false).await;
             if !context.player_open { return; }
             tracing::debug!("player was closed; dispatching event");
             context.player_open = false;
-            context.backends.dispatch_status(subscribers::DispatchedPlayerStatus::Closed).await;
+            context.player_volume = None;
+            context.playback = None;
+            context.backends.dispatch_status(subscribers::DispatchedPlayerStatus::Closed, None).await;
             return;
         },
         Err(err) => {
@@ -468,8 +778,11 @@ async fn proc_once(context: Arc<Mutex<PollingContext>>) {
                         tracing::debug!("could not deserialize: {:?}", String::from_utf8_lossy(&data));
                     }
                 },
+                SessionEvaluationError::PermissionDenied(err) => {
+                    tracing::error!(?err, "not permitted to control Music; allow it under System Settings > Privacy & Security > Automation");
+                },
                 SessionEvaluationError::QueryFailure(err) => {
-                    tracing::error!(?err, "failed to query player data");
+                    tracing::error!(?err, code = ?err.code(), "failed to query player data");
                 }
             }
             return;
@@ -477,27 +790,33 @@ async fn proc_once(context: Arc<Mutex<PollingContext>>) {
     };
 
     context.session.osa_fetches_player += 1;
-    context.backends.dispatch_status(player.state.into()).await;
+    if player.version != context.session.player_version {
+        context.update_player_version(&player.version).await;
+    }
+    if context.sources.ignores(player.playlist.as_ref()) {
+        tracing::trace!(playlist = ?player.playlist, "ignoring playback");
+        // As far as anything's concerned, whatever was playing beforehand has finished.
+        context.end_track(&player).await;
+        context.poll_listening_session(false).await;
+        return;
+    }
+    context.poll_listening_session(subscribers::player_status(player.state) == subscribers::DispatchedPlayerStatus::Playing).await;
+
+    let playback = subscribers::playback(&player);
+    if let (Some(expected), Some(actual)) = (context.playback, playback) {
+        tracing::trace!(drift = expected.drift(&actual), "corrected playback position");
+    }
+    context.playback = playback;
+    context.backends.dispatch_status(subscribers::player_status(player.state), playback).await;
+
+    let volume = subscribers::DispatchedVolume::from(&*player);
+    if context.player_volume.replace(volume).is_some_and(|previous| previous != volume) {
+        context.backends.dispatch_volume(volume).await;
+    }
 
     use osa_apple_music::application::PlayerState;
     match player.state {
-        PlayerState::Stopped => {
-            context.listened.lock().await.flush_current();
-            
-            if let Some(previous) = context.last_track.clone() {
-                let listened = context.listened.clone();
-                context.listened = Arc::new(Mutex::new(Listened::new()));
-                context.last_track = None;
-                context.backends.dispatch_track_ended(BackendContext {
-                    listened,
-                    track: previous,
-                    player: player.clone(),
-                    data: ().into(),
-                    #[cfg(feature = "musicdb")]
-                    musicdb: context.musicdb.clone()
-                }).await;
-            }
-        }
+        PlayerState::Stopped => context.end_track(&player).await,
         PlayerState::Paused => {},
         state @ (PlayerState::Playing | PlayerState::FastForwarding | PlayerState::Rewinding) => {
             if state != PlayerState::Playing {
@@ -520,8 +839,11 @@ async fn proc_once(context: Arc<Mutex<PollingContext>>) {
                                 tracing::debug!("could not deserialize: {:?}", String::from_utf8_lossy(&data));
                             }
                         },
+                        SessionEvaluationError::PermissionDenied(err) => {
+                            tracing::error!(?err, "not permitted to control Music; allow it under System Settings > Privacy & Security > Automation");
+                        },
                         SessionEvaluationError::QueryFailure(err) => {
-                            tracing::error!(?err, "failed to query application data");
+                            tracing::error!(?err, code = ?err.code(), "failed to query application data");
                         }
                     }
                     return;
@@ -536,20 +858,31 @@ async fn proc_once(context: Arc<Mutex<PollingContext>>) {
             }
 
             let track_playable_range = track.playable_range;
-            let track = Arc::new(DispatchableTrack::from_track(track, #[cfg(feature = "musicdb")] context.musicdb.as_ref().as_ref()).await);
+            let track = Arc::new(DispatchableTrack::from_track(track, #[cfg(feature = "musicdb")] context.musicdb.as_ref().as_ref(), &context.overrides).await);
 
             let previous = context.last_track.as_ref().map(|v| &v.persistent_id);
             if previous != Some(&track.persistent_id) {
                 tracing::debug!(?track, "new track");
 
-                let solicitation = context.backends.get_solicitations(subscription::Identity::TrackStarted).await;
-                let additional_data_pending = data_fetching::AdditionalTrackData::from_solicitation(solicitation, track.as_ref(),
+                context.pending_track_data = None;
+                let mut solicitation = context.backends.get_solicitations(subscription::Identity::TrackStarted).await;
+                if service::ipc::events::has_subscribers() {
+                    // so that its artwork can be given to them
+                    solicitation.insert(data_fetching::components::Component::AlbumImage);
+                }
+                let additional_data_pending = data_fetching::AdditionalTrackData::from_solicitation(solicitation, track.clone(),
                     #[cfg(feature = "musicdb")]
-                    context.musicdb.as_ref().as_ref(),
+                    context.musicdb.clone(),
                     context.artwork_manager.clone()
                 );
 
-                let additional_data = if let Some(previous) = context.last_track.clone() {
+                let (additional_data, pending_track_data) = if let Some(previous) = context.last_track.clone() {
+                    {
+                        let mut listened = context.listened.lock().await;
+                        listened.flush_current();
+                        context.listening_session.track_ended(&listened, chrono::Utc::now());
+                    }
+                    context.record_listen(&previous, context.listened.clone());
                     let pending_dispatch = context.backends.dispatch_track_ended(BackendContext {
                         player: player.clone(),
                         track: previous,
@@ -581,7 +914,24 @@ async fn proc_once(context: Arc<Mutex<PollingContext>>) {
                     #[cfg(feature = "musicdb")]
                     musicdb: context.musicdb.clone()
                 }).await;
+                context.pending_track_data = pending_track_data;
             } else if let Some(position) = player.position {
+                if let Some(pending) = context.pending_track_data.as_mut() {
+                    if let Some(additional_data) = pending.take_arrived() {
+                        context.backends.dispatch_data_enriched(BackendContext {
+                            track: track.clone(),
+                            player: player.clone(),
+                            data: additional_data.into(),
+                            listened: context.listened.clone(),
+                            #[cfg(feature = "musicdb")]
+                            musicdb: context.musicdb.clone()
+                        }).await;
+                    }
+                    if pending.is_complete() {
+                        context.pending_track_data = None;
+                    }
+                }
+
                 {
                     use subscribers::subscription::type_identity::TrackStarted;
                     use subscribers::BackendIdentitySet;
@@ -590,14 +940,17 @@ async fn proc_once(context: Arc<Mutex<PollingContext>>) {
                     if !requesting_redispatch.is_empty() { let list = *requesting_redispatch; tracing::debug!(?list, "performing start redispatch"); }
                     let backends = context.backends.get_many(*requesting_redispatch);
 
-                    let solicitation = context.backends.get_solicitations_from(backends.clone(), subscription::Identity::TrackStarted).await; // why clone needed :(
-                    let additional_data_pending = data_fetching::AdditionalTrackData::from_solicitation(solicitation, track.as_ref(),
+                    let solicitation = context.backends.get_solicitations_from(backends, subscription::Identity::TrackStarted).await;
+                    let additional_data_pending = match data_fetching::AdditionalTrackData::from_solicitation(solicitation, track.clone(),
                         #[cfg(feature = "musicdb")]
-                        context.musicdb.as_ref().as_ref(),
+                        context.musicdb.clone(),
                         context.artwork_manager.clone()
-                    ).await;
+                    ).await {
+                        (_, Some(pending)) => pending.complete().await,
+                        (additional_data, None) => additional_data,
+                    };
 
-                    context.backends.dispatch_to::<TrackStarted>(backends, BackendContext {
+                    context.backends.dispatch_for_track::<TrackStarted, _>(Some(*requesting_redispatch), BackendContext {
                         track: track.clone(),
                         player: player.clone(),
                         data: additional_data_pending.into(),
@@ -617,7 +970,7 @@ async fn proc_once(context: Arc<Mutex<PollingContext>>) {
                         const MAX_DRIFT_BEFORE_REDISPATCH: f32 = 2.; // seconds;
                         let expected = current.get_expected_song_position();
                         if (expected - position).abs() >= MAX_DRIFT_BEFORE_REDISPATCH {
-                            listened.flush_current();
+                            listened.flush_current_at(Some(position));
                             listened.set_new_current(position);
                             drop(listened); // give up lock
                             context.backends.dispatch_current_progress(BackendContext {
@@ -0,0 +1,43 @@
+//! The HTTP client shared by everything that makes requests, built from the [configured](http_client::Options) options.
+use std::sync::{LazyLock, RwLock};
+
+struct Shared {
+    options: http_client::Options,
+    client: reqwest::Client,
+}
+impl Shared {
+    fn new(options: &http_client::Options) -> Self {
+        let mut options = options.clone();
+        options.user_agent.get_or_insert_with(|| concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION")).to_owned());
+        let client = options.build().unwrap_or_else(|error| {
+            tracing::error!(?error, "failed to build network client; proceeding without the proxy");
+            options.proxy = None;
+            options.build().expect("cannot construct network client")
+        });
+        Self { options, client }
+    }
+}
+
+static SHARED: LazyLock<RwLock<Shared>> = LazyLock::new(|| RwLock::new(Shared::new(&http_client::Options::default())));
+
+/// Replaces the shared client with one built from the given options.
+///
+/// Clients which were already handed out (like those held by backends) keep the options they were built with.
+pub fn configure(options: &http_client::Options) {
+    *SHARED.write().expect("lock poisoned") = Shared::new(options);
+}
+
+/// The shared client; it's reference-counted, so this is cheap.
+pub fn client() -> reqwest::Client {
+    SHARED.read().expect("lock poisoned").client.clone()
+}
+
+/// A builder with the configured options applied, for API clients which need to add something of their own.
+#[cfg(feature = "listenbrainz")]
+pub fn builder() -> reqwest::ClientBuilder {
+    SHARED.read().expect("lock poisoned").options.builder().expect("options were already built successfully")
+}
+
+pub fn retry() -> http_client::Retry {
+    SHARED.read().expect("lock poisoned").options.retry.clone()
+}
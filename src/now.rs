@@ -0,0 +1,208 @@
+//! One-shot, templated output of what's currently playing, for use by launcher extensions and shell prompts.
+//!
+//! Placeholders are written in braces (e.x. `{title}`), and a literal brace is written by doubling it (`{{` or `}}`).
+//! Placeholders without a value (e.x. the album of a track without one) are replaced with nothing.
+//!
+//! | Placeholder      | Value                                                   |
+//! |------------------|---------------------------------------------------------|
+//! | `{title}`        | The name of the track.                                  |
+//! | `{artist}`       | The artist of the track.                                |
+//! | `{album}`        | The album the track is in.                              |
+//! | `{album_artist}` | The artist of the album the track is in.                |
+//! | `{track_number}` | The position of the track in its album.                 |
+//! | `{elapsed}`      | The current position in the track (e.x. `1:05`).        |
+//! | `{duration}`     | The length of the track.                                |
+//! | `{remaining}`    | The time left in the track.                             |
+//! | `{progress}`     | The percentage of the track that's elapsed (e.x. `42`). |
+//! | `{state}`        | `playing`, `paused`, or `stopped`.                      |
+//! | `{id}`           | The persistent ID of the track, in hexadecimal.         |
+
+use std::process::ExitCode;
+
+pub const DEFAULT_FORMAT: &str = "{artist} — {title} [{elapsed}/{duration}]";
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TemplateError {
+    #[error("unknown placeholder {{{0}}}")]
+    UnknownPlaceholder(String),
+    #[error("placeholder at byte {0} is never closed")]
+    Unclosed(usize),
+    #[error("unmatched closing brace at byte {0}")]
+    Unopened(usize),
+}
+
+/// The values which can be substituted into a template.
+///
+/// With `--json`, these are printed as-is instead.
+#[derive(Debug, Default, serde::Serialize)]
+pub struct Fields<'a> {
+    pub title: Option<&'a str>,
+    pub artist: Option<&'a str>,
+    pub album: Option<&'a str>,
+    pub album_artist: Option<&'a str>,
+    pub track_number: Option<u16>,
+    /// In seconds.
+    pub elapsed: Option<f32>,
+    /// In seconds.
+    pub duration: Option<f32>,
+    pub state: Option<&'static str>,
+    pub id: Option<&'a str>,
+}
+impl Fields<'_> {
+    fn get(&self, placeholder: &str) -> Result<Option<String>, TemplateError> {
+        Ok(match placeholder {
+            "title" => self.title.map(ToOwned::to_owned),
+            "artist" => self.artist.map(ToOwned::to_owned),
+            "album" => self.album.map(ToOwned::to_owned),
+            "album_artist" => self.album_artist.map(ToOwned::to_owned),
+            "track_number" => self.track_number.map(|number| number.to_string()),
+            "elapsed" => self.elapsed.map(format_time),
+            "duration" => self.duration.map(format_time),
+            "remaining" => self.elapsed.zip(self.duration).map(|(elapsed, duration)| format_time(duration - elapsed)),
+            "progress" => self.elapsed.zip(self.duration)
+                .filter(|(_, duration)| *duration > 0.)
+                .map(|(elapsed, duration)| format!("{:.0}", (elapsed / duration * 100.).clamp(0., 100.))),
+            "state" => self.state.map(ToOwned::to_owned),
+            "id" => self.id.map(ToOwned::to_owned),
+            _ => return Err(TemplateError::UnknownPlaceholder(placeholder.to_owned()))
+        })
+    }
+}
+
+/// Formats a number of seconds as `m:ss`, or `h:mm:ss` if it's at least an hour.
+#[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss, reason = "clamped to be non-negative; precision beyond seconds is irrelevant")]
+pub fn format_time(seconds: f32) -> String {
+    let seconds = seconds.max(0.) as u64;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+pub fn render(template: &str, fields: &Fields<'_>) -> Result<String, TemplateError> {
+    let mut output = String::with_capacity(template.len());
+    let mut chars = template.char_indices().peekable();
+    while let Some((index, char)) = chars.next() {
+        match char {
+            '{' if chars.next_if(|(_, char)| *char == '{').is_some() => output.push('{'),
+            '}' if chars.next_if(|(_, char)| *char == '}').is_some() => output.push('}'),
+            '{' => {
+                let start = index + '{'.len_utf8();
+                let length = template[start..].find('}').ok_or(TemplateError::Unclosed(index))?;
+                let placeholder = &template[start..start + length];
+                if let Some(value) = fields.get(placeholder.trim())? {
+                    output.push_str(&value);
+                }
+                while chars.next_if(|(index, _)| *index <= start + length).is_some() {}
+            },
+            '}' => return Err(TemplateError::Unopened(index)),
+            char => output.push(char)
+        }
+    }
+    Ok(output)
+}
+
+/// Prints the currently playing track according to the template, or nothing if there isn't one.
+///
+/// With `--json`, the template is ignored and its [fields](Fields) are printed instead, or `null` if there isn't one.
+pub async fn print(template: &str) -> ExitCode {
+    use osa_apple_music::{application::PlayerState, ApplicationData, Track};
+
+    let json = crate::console::is_json();
+
+    // Fail on a bad template before spending time querying the player.
+    if !json && let Err(error) = render(template, &Fields::default()) {
+        crate::console::error!("{}", crate::i18n::now_invalid_format(error));
+        return ExitCode::FAILURE;
+    }
+
+    crate::gui::require(&crate::i18n::gui_action_now_playing());
+
+    let (application, track) = tokio::join!(ApplicationData::fetch(), Track::get_now_playing());
+    let application = match application {
+        Ok(application) => application,
+        Err(error) => {
+            crate::console::error!("{}", crate::i18n::now_player_unavailable(error));
+            return ExitCode::FAILURE;
+        }
+    };
+    let track = match track {
+        Ok(Some(track)) => track,
+        Ok(None) => {
+            if json { crate::console::json(&()); }
+            return ExitCode::SUCCESS
+        },
+        Err(error) => {
+            crate::console::error!("{}", crate::i18n::now_track_unavailable(error));
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let fields = Fields {
+        title: Some(&track.name),
+        artist: track.artist.as_deref(),
+        album: track.album.name.as_deref(),
+        album_artist: track.album.artist.as_deref(),
+        track_number: track.track_number.map(core::num::NonZero::get),
+        elapsed: application.position,
+        duration: track.duration.map(|duration| duration.as_secs_f32()),
+        state: Some(match application.state {
+            PlayerState::Stopped => "stopped",
+            PlayerState::Paused => "paused",
+            PlayerState::Playing | PlayerState::FastForwarding | PlayerState::Rewinding => "playing",
+        }),
+        id: Some(&track.persistent_id),
+    };
+
+    if json {
+        crate::console::json(&fields);
+        return ExitCode::SUCCESS;
+    }
+
+    match render(template, &fields) {
+        Ok(rendered) => {
+            crate::console::out!("{rendered}");
+            ExitCode::SUCCESS
+        },
+        Err(error) => {
+            crate::console::error!("{}", crate::i18n::now_invalid_format(error));
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields() -> Fields<'static> {
+        Fields {
+            title: Some("Snorkel"),
+            artist: Some("Lumpy"),
+            album: Some("Acoustic Hotel"),
+            track_number: Some(9),
+            elapsed: Some(65.4),
+            duration: Some(256.24),
+            state: Some("playing"),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn placeholders() {
+        assert_eq!(render(DEFAULT_FORMAT, &fields()).unwrap(), "Lumpy — Snorkel [1:05/4:16]");
+        assert_eq!(render("{track_number}. {title} ({remaining} left, {progress}%, {state})", &fields()).unwrap(), "9. Snorkel (3:10 left, 26%, playing)");
+        assert_eq!(render("{album_artist}|{id}", &fields()).unwrap(), "|");
+        assert_eq!(render("{{{ title }}}", &fields()).unwrap(), "{Snorkel}");
+        assert_eq!(render("{duration}", &Fields { duration: Some(3725.), ..Default::default() }).unwrap(), "1:02:05");
+    }
+
+    #[test]
+    fn malformed() {
+        assert_eq!(render("{titel}", &fields()), Err(TemplateError::UnknownPlaceholder("titel".to_owned())));
+        assert_eq!(render("— {title", &fields()), Err(TemplateError::Unclosed("— ".len())));
+        assert_eq!(render("title}", &fields()), Err(TemplateError::Unopened(5)));
+    }
+}
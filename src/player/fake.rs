@@ -0,0 +1,277 @@
+//! A [player](super::Player) which follows a script, so that the polling loop can be run against it.
+//!
+//! Each poll of the player takes the next [tick](Tick) of the script. While a track plays, its position
+//! advances in real time (as the polling loop expects it to), rather than by how many polls have happened.
+
+use std::collections::VecDeque;
+use osa_apple_music::{error::SessionEvaluationError, ApplicationData, Track};
+
+pub const VERSION: &str = "1.0.0 (fake)";
+
+#[derive(Debug, Clone, Copy)]
+pub struct FakeTrack {
+    /// In hexadecimal.
+    pub persistent_id: &'static str,
+    pub name: &'static str,
+    pub artist: &'static str,
+    /// In seconds.
+    pub duration: f32,
+}
+impl FakeTrack {
+    fn to_track(self) -> Track {
+        use serde_json::{json, Map, Value};
+        // Built up field by field, since a single `json!` literal with this many fields exceeds its recursion limit.
+        let mut fields = Map::new();
+        for (key, value) in [
+            ("persistentID", json!(self.persistent_id)),
+            ("name", json!(self.name)),
+            ("artist", json!(self.artist)),
+            ("albumArtist", json!(self.artist)),
+            ("duration", json!(self.duration)),
+            ("finish", json!(self.duration)),
+            ("databaseID", json!(1)),
+            ("album", json!("Album")),
+            ("albumDisliked", json!(false)),
+            ("albumFavorited", json!(false)),
+            ("trackCount", json!(1)),
+            ("trackNumber", json!(1)),
+            ("discCount", json!(1)),
+            ("discNumber", json!(1)),
+            ("movementCount", json!(0)),
+            ("start", json!(0)),
+            ("mediaKind", json!("song")),
+            ("enabled", json!(true)),
+            ("playedCount", json!(0)),
+            ("unplayed", json!(true)),
+            ("skippedCount", json!(0)),
+            ("compilation", json!(false)),
+            ("bookmarkable", json!(false)),
+            ("bookmark", json!(0)),
+            ("bpm", json!(0)),
+            ("rating", json!(0)),
+            ("shufflable", json!(true)),
+            ("favorited", json!(false)),
+            ("disliked", json!(false)),
+            ("volumeAdjustment", json!(0)),
+            ("year", json!(2000)),
+            ("composer", json!("")),
+            ("genre", json!("")),
+            ("grouping", json!("")),
+            ("comment", json!("")),
+            ("category", json!("")),
+            ("description", json!("")),
+            ("eq", json!("")),
+            ("kind", json!("")),
+            ("work", json!("")),
+            ("movement", json!("")),
+            ("movementNumber", json!(0)),
+            ("sortName", json!("")),
+            ("sortAlbum", json!("")),
+            ("sortArtist", json!("")),
+            ("sortComposer", json!("")),
+            ("sortAlbumArtist", json!("")),
+        ] {
+            fields.insert(key.to_owned(), value);
+        }
+        serde_json::from_value(Value::Object(fields)).expect("fake track should deserialize")
+    }
+}
+
+/// What the player is doing for one poll.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tick {
+    /// Playing a track (by its index), having started from the position (in seconds).
+    /// Consecutive ticks of the same track from the same position are one continuous stretch of playback.
+    Playing { track: usize, from: f32 },
+    /// Paused where the last track left off.
+    Paused,
+    /// Playing again from where the last track was paused.
+    Resumed,
+    Stopped,
+    /// The application isn't open.
+    Closed,
+    /// Asking the player fails, as if the JXA session broke. Playback carries on regardless.
+    Crashed,
+}
+
+#[derive(Debug)]
+struct Stretch {
+    track: usize,
+    from: f32,
+    started: std::time::Instant,
+}
+impl Stretch {
+    fn position(&self) -> f32 {
+        self.from + self.started.elapsed().as_secs_f32()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct FakePlayer {
+    tracks: Vec<FakeTrack>,
+    script: VecDeque<Tick>,
+    /// The last tick which wasn't a crash, and the track it was of.
+    last: Option<(Tick, usize)>,
+    playing: Option<Stretch>,
+    /// Where the last track was paused.
+    paused_at: Option<f32>,
+    /// Changes to the volume (and whether it's muted), each with the poll it takes effect on.
+    volume_changes: VecDeque<(usize, u8, bool)>,
+    /// The volume and whether it's muted, if it's been changed from what [`Self::application_data`] gives.
+    volume: Option<(u8, bool)>,
+    polled: usize,
+}
+impl FakePlayer {
+    pub fn new(tracks: impl Into<Vec<FakeTrack>>) -> Self {
+        Self { tracks: tracks.into(), ..Default::default() }
+    }
+
+    /// How many polls the script lasts; any beyond that find the player closed.
+    pub fn polls(&self) -> usize {
+        self.script.len()
+    }
+
+    fn then(mut self, tick: Tick, polls: usize) -> Self {
+        self.script.extend(core::iter::repeat_n(tick, polls));
+        self
+    }
+
+    fn last_track(&self) -> usize {
+        self.script.iter().rev().find_map(|tick| match tick {
+            Tick::Playing { track, .. } => Some(*track),
+            _ => None
+        }).expect("no track has been played yet")
+    }
+
+    /// Plays a track from the position, in seconds.
+    pub fn play(self, track: usize, from: f32, polls: usize) -> Self {
+        self.then(Tick::Playing { track, from }, polls)
+    }
+
+    /// Jumps to a position in the track that was last played.
+    pub fn seek(self, to: f32, polls: usize) -> Self {
+        let track = self.last_track();
+        self.play(track, to, polls)
+    }
+
+    /// Plays another track from its start.
+    pub fn skip(self, track: usize, polls: usize) -> Self {
+        self.play(track, 0., polls)
+    }
+
+    pub fn pause(self, polls: usize) -> Self {
+        self.then(Tick::Paused, polls)
+    }
+
+    pub fn resume(self, polls: usize) -> Self {
+        self.then(Tick::Resumed, polls)
+    }
+
+    pub fn stop(self, polls: usize) -> Self {
+        self.then(Tick::Stopped, polls)
+    }
+
+    pub fn close(self, polls: usize) -> Self {
+        self.then(Tick::Closed, polls)
+    }
+
+    pub fn crash(self, polls: usize) -> Self {
+        self.then(Tick::Crashed, polls)
+    }
+
+    /// Changes the volume (and whether it's muted) from the next poll on.
+    pub fn set_volume(mut self, level: u8, muted: bool) -> Self {
+        self.volume_changes.push_back((self.script.len(), level, muted));
+        self
+    }
+
+    pub fn application_data(state: &str, position: Option<f32>) -> ApplicationData {
+        serde_json::from_value(serde_json::json!({
+            "playerState": state,
+            "version": VERSION,
+            "mute": false,
+            "shuffleEnabled": false,
+            "shuffleMode": "songs",
+            "songRepeat": "off",
+            "soundVolume": 100,
+            "playerPosition": position,
+        })).expect("fake application data should deserialize")
+    }
+
+    /// Advances the script by a poll.
+    fn advance(&mut self) -> Result<Option<ApplicationData>, SessionEvaluationError> {
+        while let Some(&(_, level, muted)) = self.volume_changes.front().filter(|(at, ..)| *at <= self.polled) {
+            self.volume_changes.pop_front();
+            self.volume = Some((level, muted));
+        }
+        self.polled += 1;
+
+        let volume = self.volume;
+        self.advance_playback().map(|data| data.map(|mut data| {
+            if let Some((level, muted)) = volume {
+                data.volume = level;
+                data.mute = muted;
+            }
+            data
+        }))
+    }
+
+    fn advance_playback(&mut self) -> Result<Option<ApplicationData>, SessionEvaluationError> {
+        let tick = self.script.pop_front().unwrap_or(Tick::Closed);
+        let track = self.last.map(|(_, track)| track);
+        Ok(match tick {
+            Tick::Crashed => return Err(SessionEvaluationError::IoFailure(std::io::Error::other("scripted crash"))),
+            Tick::Playing { track, from } => {
+                if self.last != Some((tick, track)) {
+                    self.playing = Some(Stretch { track, from, started: std::time::Instant::now() });
+                }
+                self.last = Some((tick, track));
+                Some(Self::application_data("playing", self.playing.as_ref().map(Stretch::position)))
+            },
+            Tick::Resumed => {
+                let track = track.expect("nothing to resume");
+                if self.last.is_some_and(|(last, _)| last != Tick::Resumed) {
+                    let from = self.paused_at.take().expect("resumed without having paused");
+                    self.playing = Some(Stretch { track, from, started: std::time::Instant::now() });
+                }
+                self.last = Some((tick, track));
+                Some(Self::application_data("playing", self.playing.as_ref().map(Stretch::position)))
+            },
+            Tick::Paused => {
+                let track = track.expect("nothing to pause");
+                if let Some(playing) = self.playing.take() {
+                    self.paused_at = Some(playing.position());
+                }
+                self.last = Some((tick, track));
+                Some(Self::application_data("paused", self.paused_at))
+            },
+            Tick::Stopped => {
+                self.last = None;
+                self.playing = None;
+                self.paused_at = None;
+                Some(Self::application_data("stopped", None))
+            },
+            Tick::Closed => {
+                self.last = None;
+                self.playing = None;
+                self.paused_at = None;
+                None
+            },
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl super::Player for FakePlayer {
+    async fn application(&mut self) -> Result<Option<ApplicationData>, SessionEvaluationError> {
+        self.advance()
+    }
+
+    async fn current_track(&mut self) -> Result<Option<Track>, SessionEvaluationError> {
+        Ok(self.playing.as_ref().map(|playing| self.tracks[playing.track].to_track()))
+    }
+
+    async fn version(&mut self) -> Option<String> {
+        Some(VERSION.to_owned())
+    }
+}
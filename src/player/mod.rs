@@ -0,0 +1,36 @@
+//! Where the polling loop learns what the player is doing.
+//!
+//! Outside of tests, this is the [JXA session](osa_apple_music::Session); tests [script](fake::FakePlayer) it instead.
+
+use osa_apple_music::{error::SessionEvaluationError, ApplicationData, Track};
+
+pub mod sources;
+#[cfg(test)]
+pub mod fake;
+#[cfg(test)]
+mod simulation;
+
+#[async_trait::async_trait]
+pub trait Player: Send + core::fmt::Debug {
+    /// The state of the player, or `None` if it isn't open.
+    async fn application(&mut self) -> Result<Option<ApplicationData>, SessionEvaluationError>;
+
+    /// The track that's playing, if there is one.
+    async fn current_track(&mut self) -> Result<Option<Track>, SessionEvaluationError>;
+
+    /// The version of the player, or `None` if it isn't open.
+    async fn version(&mut self) -> Option<String> {
+        self.application().await.expect("failed to retrieve application data").map(|application| application.version)
+    }
+}
+
+#[async_trait::async_trait]
+impl Player for osa_apple_music::Session {
+    async fn application(&mut self) -> Result<Option<ApplicationData>, SessionEvaluationError> {
+        Self::application(self).await
+    }
+
+    async fn current_track(&mut self) -> Result<Option<Track>, SessionEvaluationError> {
+        self.now_playing().await
+    }
+}
@@ -0,0 +1,149 @@
+//! Runs the polling loop against [scripted players](FakePlayer), checking what it dispatches.
+
+use alloc::sync::Arc;
+use tokio::sync::{Mutex, broadcast::error::TryRecvError};
+
+use super::fake::{FakePlayer, FakeTrack};
+use crate::{PollingContext, proc_once};
+
+const TRACKS: [FakeTrack; 2] = [
+    FakeTrack { persistent_id: "1A2B3C4D5E6F7081", name: "First", artist: "Someone", duration: 180. },
+    FakeTrack { persistent_id: "8070F6E5D4C3B2A1", name: "Second", artist: "Someone Else", duration: 240. },
+];
+
+/// How long to wait between polls. Positions advance in real time, so this only needs to let spawned work run.
+const POLL_INTERVAL: core::time::Duration = core::time::Duration::from_millis(10);
+
+fn config() -> crate::config::Config {
+    use crate::config::{Config, ConfigurableBackends};
+    Config {
+        // Nothing should be sent anywhere.
+        backends: ConfigurableBackends {
+            #[cfg(feature = "discord")]
+            discord: None,
+            #[cfg(feature = "lastfm")]
+            lastfm: None,
+            #[cfg(feature = "listenbrainz")]
+            listenbrainz: None,
+            #[cfg(feature = "notifications")]
+            notifications: None,
+            #[cfg(feature = "mqtt")]
+            mqtt: None,
+            #[cfg(feature = "maloja")]
+            maloja: None,
+        },
+        #[cfg(feature = "musicdb")]
+        musicdb: crate::config::MusicDbConfiguration { enabled: false, path: std::path::PathBuf::new() },
+        ..Default::default()
+    }
+}
+
+/// Plays the script through, returning what was dispatched (other than player status updates)
+/// as the name of the event and the name of the track it was for.
+async fn simulate(player: FakePlayer) -> Vec<(String, Option<String>)> {
+    let _exclusive = crate::service::ipc::events::EXCLUSIVE.lock().await;
+
+    let mut events = crate::service::ipc::events::subscribe();
+    let polls = player.polls();
+    let context = PollingContext::with_player(&config(), true, Arc::default(), async {
+        Box::new(player) as Box<dyn super::Player>
+    }).await;
+    let context = Arc::new(Mutex::new(context));
+
+    let mut dispatched = Vec::new();
+    for _ in 0..polls {
+        proc_once(context.clone()).await;
+        tokio::time::sleep(POLL_INTERVAL).await;
+
+        // Drained every poll so that the channel never fills up.
+        loop {
+            let published = match events.try_recv() {
+                Ok(published) => published,
+                Err(TryRecvError::Empty) => break,
+                Err(error) => panic!("missed events: {error}"),
+            };
+            let Some(line) = published.to_line(player_events::ArtworkFormat::Url).await else { continue };
+            let event: serde_json::Value = serde_json::from_str(&line).expect("event should be JSON");
+            let name = event["event"].as_str().expect("event should be named");
+            if name == "player_status" { continue }
+            dispatched.push((name.to_owned(), event["track"]["name"].as_str().map(ToOwned::to_owned)));
+        }
+    }
+    dispatched
+}
+
+fn event(name: &str, track: usize) -> (String, Option<String>) {
+    (name.to_owned(), Some(TRACKS[track].name.to_owned()))
+}
+
+#[tokio::test]
+async fn plays_through_tracks() {
+    let player = FakePlayer::new(TRACKS).play(0, 0., 3).skip(1, 3).stop(1);
+    assert_eq!(simulate(player).await, [
+        event("track_started", 0),
+        event("track_ended", 0),
+        event("track_started", 1),
+        event("track_ended", 1),
+    ]);
+}
+
+#[tokio::test]
+async fn pausing_does_not_end_the_track() {
+    let player = FakePlayer::new(TRACKS).play(0, 0., 2).pause(3).resume(2).stop(1);
+    assert_eq!(simulate(player).await, [
+        event("track_started", 0),
+        event("track_ended", 0),
+    ]);
+}
+
+#[tokio::test]
+async fn seeking_jolts_the_progress() {
+    let player = FakePlayer::new(TRACKS).play(0, 0., 2).seek(90., 2).stop(1);
+    assert_eq!(simulate(player).await, [
+        event("track_started", 0),
+        event("progress_jolt", 0),
+        event("track_ended", 0),
+    ]);
+}
+
+#[tokio::test]
+async fn failing_to_reach_the_player_does_not_end_the_track() {
+    let player = FakePlayer::new(TRACKS).play(0, 0., 2).crash(2).play(0, 0., 2).stop(1);
+    assert_eq!(simulate(player).await, [
+        event("track_started", 0),
+        event("track_ended", 0),
+    ]);
+}
+
+#[tokio::test]
+async fn reopening_the_player_ends_what_was_playing_once() {
+    let player = FakePlayer::new(TRACKS).play(0, 0., 2).close(2).skip(1, 2).stop(1);
+    assert_eq!(simulate(player).await, [
+        event("track_started", 0),
+        event("track_ended", 0),
+        event("track_started", 1),
+        event("track_ended", 1),
+    ]);
+}
+
+#[tokio::test]
+async fn replaying_after_stopping_starts_the_track_again() {
+    let player = FakePlayer::new(TRACKS).play(0, 0., 2).stop(1).play(0, 0., 2).stop(1);
+    assert_eq!(simulate(player).await, [
+        event("track_started", 0),
+        event("track_ended", 0),
+        event("track_started", 0),
+        event("track_ended", 0),
+    ]);
+}
+
+#[tokio::test]
+async fn changing_the_volume_is_dispatched() {
+    let player = FakePlayer::new(TRACKS).play(0, 0., 2).set_volume(40, false).play(0, 0., 2).set_volume(40, true).play(0, 0., 1).stop(1);
+    assert_eq!(simulate(player).await, [
+        event("track_started", 0),
+        ("volume_changed".to_owned(), None),
+        ("volume_changed".to_owned(), None),
+        event("track_ended", 0),
+    ]);
+}
@@ -0,0 +1,77 @@
+//! Ignoring whatever's played from certain playlists and radio stations, such as a playlist of white noise that's
+//! put on to fall asleep to; nothing about it is recorded or sent anywhere.
+//!
+//! Each is given by its name (ignoring case) or its persistent ID.
+//!
+//! ```toml
+//! [sources]
+//! ignore = ["Sleep Sounds", "9C7E988AD00DBDFF"]
+//! ```
+//!
+//! Or, to pay attention only to what's played from certain ones:
+//! ```toml
+//! [sources]
+//! only = ["Favorites", "Chill Radio"]
+//! ```
+use osa_apple_music::application::Playlist;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct Config {
+    /// The playlists and stations whose playback is ignored.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ignore: Vec<String>,
+    /// If any are given, playback is ignored unless it's from one of these playlists or stations.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub only: Vec<String>,
+}
+impl Config {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether playback from the playlist (or from none at all, such as when a track is played by itself) should be ignored.
+    pub fn ignores(&self, playlist: Option<&Playlist>) -> bool {
+        let listed = |list: &[String]| playlist.is_some_and(|playlist| list.iter().any(|entry| is(entry, playlist)));
+        listed(&self.ignore) || (!self.only.is_empty() && !listed(&self.only))
+    }
+}
+
+fn is(entry: &str, playlist: &Playlist) -> bool {
+    let entry = entry.trim();
+    entry.eq_ignore_ascii_case(playlist.name.trim())
+        || playlist.persistent_id.as_deref().is_some_and(|id| entry.eq_ignore_ascii_case(id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use osa_apple_music::application::PlaylistKind;
+
+    fn playlist(name: &str, persistent_id: &str) -> Playlist {
+        Playlist { name: name.to_owned(), kind: PlaylistKind::UserPlaylist, persistent_id: Some(persistent_id.to_owned()) }
+    }
+
+    #[test]
+    fn ignores() {
+        let sleep = playlist("Sleep Sounds", "9C7E988AD00DBDFF");
+        let favorites = playlist("Favorites", "0123456789ABCDEF");
+
+        let config = Config::default();
+        assert!(!config.ignores(Some(&sleep)) && !config.ignores(None));
+
+        let config = Config { ignore: vec!["sleep sounds".to_owned()], ..Default::default() };
+        assert!(config.ignores(Some(&sleep)));
+        assert!(!config.ignores(Some(&favorites)) && !config.ignores(None));
+
+        let config = Config { ignore: vec!["9c7e988ad00dbdff".to_owned()], ..Default::default() };
+        assert!(config.ignores(Some(&sleep)), "persistent IDs should match regardless of case");
+
+        let config = Config { only: vec!["Favorites".to_owned()], ..Default::default() };
+        assert!(!config.ignores(Some(&favorites)));
+        assert!(config.ignores(Some(&sleep)) && config.ignores(None));
+
+        let config = Config { ignore: vec!["Favorites".to_owned()], only: vec!["Favorites".to_owned()] };
+        assert!(config.ignores(Some(&favorites)), "being ignored should win out");
+    }
+}
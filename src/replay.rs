@@ -0,0 +1,96 @@
+//! Giving the listens kept in the [listening history](crate::store::entities::Listen) to a backend again, by hand;
+//! for when its credentials were broken for a while, or it was set up partway through the week.
+//!
+//! Only listens recorded on this machine can be replayed, since those [synced](crate::sync) from elsewhere were dispatched there.
+use alloc::sync::Arc;
+use std::process::ExitCode;
+
+use crate::store::entities::{FromKey, Listen, Session};
+use crate::subscribers::{Backends, BackendIdentity, BackendIdentitySet, DeferredListen};
+
+pub async fn run(config: &crate::config::Config, since: chrono::DateTime<chrono::Utc>, backend: BackendIdentity, dry_run: bool) -> ExitCode {
+    crate::store::migrations::migrate().await;
+    let pool = match crate::store::DB_POOL.get().await {
+        Ok(pool) => pool,
+        Err(error) => {
+            crate::console::error!("{}", crate::i18n::database_unavailable(error));
+            return ExitCode::FAILURE;
+        }
+    };
+    let history = match Listen::get_recorded_here_since(&pool, since).await {
+        Ok(history) => history,
+        Err(error) => {
+            crate::console::error!("{}", crate::i18n::replay_history_unreadable(error));
+            return ExitCode::FAILURE;
+        }
+    };
+    let listens = match to_listens(&pool, &history).await {
+        Ok(listens) => listens,
+        Err(error) => {
+            crate::console::error!("{}", crate::i18n::replay_history_unreadable(error));
+            return ExitCode::FAILURE;
+        }
+    };
+    if listens.len() < history.len() {
+        crate::console::warning!("{}", crate::i18n::replay_untracked(history.len() - listens.len()));
+    }
+    if listens.is_empty() {
+        crate::console::note!("{}", crate::i18n::replay_nothing());
+        return ExitCode::SUCCESS;
+    }
+
+    crate::net::configure(&config.network);
+    crate::data_fetching::limits::configure(&config.fetch_limits);
+    let mut among = BackendIdentitySet::empty();
+    among.insert(backend);
+    let backends = Backends::new_among(config, dry_run, tokio::sync::mpsc::channel(1).0, Some(among)).await;
+    if !backends.can_replay(backend).await {
+        crate::console::error!("{}", crate::i18n::replay_backend_unavailable(backend.get_key()));
+        return ExitCode::FAILURE;
+    }
+
+    backends.replay(&listens).await;
+    match backends.deferred_count(backend).await {
+        0 => {
+            crate::console::note!("{}", crate::i18n::replay_done(listens.len(), backend.get_key()));
+            ExitCode::SUCCESS
+        },
+        failed => {
+            crate::console::error!("{}", crate::i18n::replay_failed(failed, listens.len(), backend.get_key()));
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Restores the listens as they were dispatched, dropping those whose track wasn't kept.
+async fn to_listens(pool: &sqlx::SqlitePool, history: &[Listen]) -> Result<Vec<DeferredListen>, crate::store::MaybeStaticSqlError> {
+    let mut player_versions = std::collections::HashMap::<i64, Arc<str>>::new();
+    let mut listens = Vec::with_capacity(history.len());
+    for listen in history {
+        let track = match listen.track() {
+            Some(Ok(track)) => track,
+            Some(Err(error)) => {
+                tracing::error!(?error, id = ?listen.id(), "failed to read kept track; dropping listen");
+                continue
+            },
+            None => continue,
+        };
+        let Some(session) = listen.session else { continue };
+        let player_version = if let Some(version) = player_versions.get(&session.get()) { version.clone() } else {
+            let version: Arc<str> = Session::get_in_pool(session, pool).await?.player_version.into();
+            player_versions.insert(session.get(), version.clone());
+            version
+        };
+        let listened = listen.segments(pool).await?.iter()
+            .map(|segment| chrono::TimeDelta::milliseconds(segment.duration))
+            .sum();
+        listens.push(DeferredListen {
+            track: Arc::new(track),
+            player_version,
+            started_at: listen.started_at.0,
+            listened,
+            journaled: None,
+        });
+    }
+    Ok(listens)
+}
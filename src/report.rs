@@ -0,0 +1,206 @@
+//! Gathering what's useful for a bug report into an archive that the user can look over and attach to an issue themselves.
+//! Nothing is ever sent anywhere.
+//!
+//! If opted into, a report of each panic is kept so that it can be included in the next bundle.
+
+use core::sync::atomic::{AtomicBool, Ordering};
+use std::{path::{Path, PathBuf}, process::ExitCode, sync::LazyLock};
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct Config {
+    /// Whether to keep a report of each panic, to be included in the next [bundle].
+    pub keep_crash_reports: bool,
+}
+
+static KEEP_CRASH_REPORTS: AtomicBool = AtomicBool::new(false);
+
+pub fn configure(config: &Config) {
+    KEEP_CRASH_REPORTS.store(config.keep_crash_reports, Ordering::Relaxed);
+}
+
+pub static CRASH_REPORTS_FOLDER: LazyLock<PathBuf> = LazyLock::new(|| {
+    crate::util::APPLICATION_SUPPORT_FOLDER.join("crash-reports")
+});
+
+/// How many crash reports are kept before the oldest are removed.
+const MAX_CRASH_REPORTS: usize = 10;
+
+/// How many of the most recent log lines are bundled by default.
+pub const DEFAULT_LOG_LINES: usize = 500;
+
+fn version() -> String {
+    const FEATURES: &[(&str, bool)] = &[
+        ("discord", cfg!(feature = "discord")),
+        ("listenbrainz", cfg!(feature = "listenbrainz")),
+        ("lastfm", cfg!(feature = "lastfm")),
+        ("catbox", cfg!(feature = "catbox")),
+        ("musicdb", cfg!(feature = "musicdb")),
+        ("notifications", cfg!(feature = "notifications")),
+        ("mqtt", cfg!(feature = "mqtt")),
+        ("maloja", cfg!(feature = "maloja")),
+    ];
+    let features = FEATURES.iter().filter(|(_, enabled)| *enabled).map(|(name, _)| *name).collect::<Vec<_>>();
+    format!(
+        "{} {} ({}, {})\nfeatures: {}\n",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::ARCH,
+        if cfg!(debug_assertions) { "debug" } else { "release" },
+        features.join(", ")
+    )
+}
+
+/// Writes a report of a panic, if they're [kept](Config::keep_crash_reports).
+///
+/// This is called from the panic hook, so it's synchronous and doesn't panic itself.
+pub fn record_panic(description: &str) {
+    if !KEEP_CRASH_REPORTS.load(Ordering::Relaxed) { return }
+
+    let write = || -> std::io::Result<()> {
+        std::fs::create_dir_all(&*CRASH_REPORTS_FOLDER)?;
+        let path = CRASH_REPORTS_FOLDER.join(format!("{}.txt", chrono::Utc::now().timestamp_millis()));
+        std::fs::write(path, format!("{}\n{description}\n", version()))?;
+
+        let mut reports = std::fs::read_dir(&*CRASH_REPORTS_FOLDER)?
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .collect::<Vec<_>>();
+        if reports.len() > MAX_CRASH_REPORTS {
+            // Named by when they were written, so the oldest sort first.
+            reports.sort();
+            for report in &reports[..reports.len() - MAX_CRASH_REPORTS] {
+                std::fs::remove_file(report)?;
+            }
+        }
+        Ok(())
+    };
+
+    if let Err(error) = write() {
+        tracing::error!(?error, "failed to write crash report");
+    }
+}
+
+/// Hides who the user is: their home directory becomes `~`, and the names of any other users' home directories are removed.
+fn redact(text: &str, home: &str) -> String {
+    const USERS: &str = "/Users/";
+    let text = if home.is_empty() { text.to_owned() } else { text.replace(home, "~") };
+    let mut redacted = String::with_capacity(text.len());
+    let mut rest = text.as_str();
+    while let Some(index) = rest.find(USERS) {
+        let (before, after) = rest.split_at(index + USERS.len());
+        redacted.push_str(before);
+        let name = after.find(|char: char| char == '/' || char == '"' || char.is_whitespace()).unwrap_or(after.len());
+        if name != 0 { redacted.push_str("[redacted]"); }
+        rest = &after[name..];
+    }
+    redacted.push_str(rest);
+    redacted
+}
+
+/// The last `count` lines written to the log files, oldest first.
+async fn recent_log_lines(count: usize) -> std::io::Result<Vec<String>> {
+    let mut files = Vec::new();
+    let mut entries = match tokio::fs::read_dir(&*crate::debugging::LOGS_FOLDER).await {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error),
+    };
+    while let Some(entry) = entries.next_entry().await? {
+        if entry.path().extension().is_some_and(|extension| extension == "log") {
+            files.push(entry.path());
+        }
+    }
+    // Rotated daily and suffixed with the date, so the newest sort last.
+    files.sort();
+
+    let mut lines = Vec::with_capacity(count);
+    for file in files.iter().rev() {
+        let contents = tokio::fs::read_to_string(file).await?;
+        let mut preceding = contents.lines().rev().take(count - lines.len()).map(ToOwned::to_owned).collect::<Vec<_>>();
+        preceding.reverse();
+        preceding.append(&mut lines);
+        lines = preceding;
+        if lines.len() >= count { break }
+    }
+    Ok(lines)
+}
+
+/// Copies the files in a folder, [redacting](redact) each.
+async fn copy_dir_redacted(from: &Path, to: &Path, home: &str) -> std::io::Result<()> {
+    let mut entries = match tokio::fs::read_dir(from).await {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(error) => return Err(error),
+    };
+    tokio::fs::create_dir_all(to).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let contents = tokio::fs::read(entry.path()).await?;
+        tokio::fs::write(to.join(entry.file_name()), redact(&String::from_utf8_lossy(&contents), home)).await?;
+    }
+    Ok(())
+}
+
+/// Writes what's in the bundle to a folder.
+async fn stage(folder: &Path, log_lines: usize) -> std::io::Result<()> {
+    tokio::fs::create_dir_all(folder).await?;
+
+    let macos = crate::util::get_macos_version().await.unwrap_or_else(|| "unknown".to_owned());
+    tokio::fs::write(folder.join("version.txt"), format!("{}macOS: {macos}\n", version())).await?;
+
+    let home = crate::util::HOME.to_string_lossy();
+    let mut lines = recent_log_lines(log_lines).await?.join("\n");
+    lines.push('\n');
+    tokio::fs::write(folder.join("log.txt"), redact(&lines, &home)).await?;
+
+    copy_dir_redacted(&CRASH_REPORTS_FOLDER, &folder.join("crash-reports"), &home).await
+}
+
+/// Bundles the version, the last `log_lines` lines of the logs, and any crash reports into a `.tar.gz`.
+pub async fn bundle(output: Option<PathBuf>, log_lines: usize) -> ExitCode {
+    let name = format!("am-osx-status-report-{}", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+    let output = output.unwrap_or_else(|| PathBuf::from(format!("{name}.tar.gz")));
+    let staging = std::env::temp_dir().join(&name);
+
+    let result = match stage(&staging, log_lines).await {
+        Ok(()) => tokio::process::Command::new("tar")
+            .arg("-czf").arg(&output)
+            .arg("-C").arg(std::env::temp_dir())
+            .arg(&name)
+            .status().await
+            .and_then(|status| if status.success() { Ok(()) } else {
+                Err(std::io::Error::other(format!("tar exited with {status}")))
+            }),
+        Err(error) => Err(error),
+    };
+
+    if let Err(error) = tokio::fs::remove_dir_all(&staging).await {
+        tracing::warn!(?error, "failed to remove staged report");
+    }
+
+    match result {
+        Ok(()) => {
+            crate::console::note!("{}", crate::i18n::report_bundled(output.to_string_lossy()));
+            if crate::console::is_json() { crate::console::json(&output); }
+            ExitCode::SUCCESS
+        },
+        Err(error) => {
+            crate::console::error!("{}", crate::i18n::report_bundle_failed(error));
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::redact;
+
+    #[test]
+    fn redacts_home_directories() {
+        let home = "/Users/someone";
+        assert_eq!(redact("path=\"/Users/someone/Music/a.jpg\"", home), "path=\"~/Music/a.jpg\"");
+        assert_eq!(redact("copied /Users/other/file to /Users/Shared", home), "copied /Users/[redacted]/file to /Users/[redacted]");
+        assert_eq!(redact("nothing to hide in /Users/", home), "nothing to hide in /Users/");
+        assert_eq!(redact("/Users/someone", ""), "/Users/[redacted]");
+    }
+}
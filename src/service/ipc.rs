@@ -106,6 +106,66 @@ pub mod packets {
         }
     }
 
+    /// What a connection [subscribing](super::Packet::Subscribe) to [events](super::events) wants to be given.
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+    pub struct Subscribe {
+        /// How the artwork of tracks is given.
+        pub artwork: player_events::ArtworkFormat,
+    }
+    impl From<Subscribe> for super::Packet {
+        fn from(val: Subscribe) -> Self {
+            Self::Subscribe(val)
+        }
+    }
+
+    /// A summary of the running process, sent in response to [`Packet::QueryStatus`](super::Packet::QueryStatus).
+    #[derive(Serialize, Deserialize, Debug, Clone)]
+    pub struct Status {
+        /// The (semver) version of the running program.
+        pub version: String,
+        /// When the current session started, in milliseconds since the unix epoch.
+        pub started_at: i64,
+        /// How long the current session has been going for, in milliseconds.
+        pub uptime: u64,
+        /// How many times information about the current track was fetched from the player.
+        pub osa_fetches_track: i64,
+        /// How many times the status of the player was fetched.
+        pub osa_fetches_player: i64,
+        pub dispatches: crate::subscribers::statistics::Summary,
+    }
+    impl Status {
+        pub fn of(session: &crate::store::entities::Session) -> Self {
+            Self {
+                version: session.version.clone(),
+                started_at: session.started_at.0.timestamp_millis(),
+                uptime: session.duration().num_milliseconds().try_into().unwrap_or_default(),
+                osa_fetches_track: session.osa_fetches_track,
+                osa_fetches_player: session.osa_fetches_player,
+                dispatches: crate::subscribers::statistics::summarize(),
+            }
+        }
+
+        /// Writes out the summary for a person to read.
+        pub fn print(&self) {
+            use crate::{console, i18n, subscribers::BackendIdentity};
+
+            #[expect(clippy::cast_precision_loss, reason = "precision beyond seconds is irrelevant")]
+            let uptime = crate::now::format_time(self.uptime as f32 / 1000.);
+            console::out!("{}", i18n::service_session_summary(uptime, self.osa_fetches_track, self.osa_fetches_player));
+            if self.dispatches.is_empty() {
+                console::out!("{}", i18n::service_session_no_dispatches());
+            }
+            for (key, counts) in &self.dispatches {
+                let backend = BackendIdentity::from_key(key).map_or(key.as_str(), |identity| identity.get_name());
+                console::out!("{}", i18n::service_session_dispatches(backend, counts.dispatched, counts.failed));
+            }
+        }
+    }
+    impl From<Status> for super::Packet {
+        fn from(val: Status) -> Self {
+            Self::Status(val)
+        }
+    }
 }
 
 #[expect(clippy::unsafe_derive_deserialize, reason = "safe transmutation of enum discriminants")]
@@ -115,6 +175,13 @@ pub enum Packet {
     Hello(packets::Hello) = 0,
     GeneralFailure(packets::GeneralFailure) = 1,
     ReloadConfiguration = 2,
+    /// Turns the connection into a one-way stream of dispatch [events], each written as a line of JSON in place of packets.
+    Subscribe(packets::Subscribe) = 3,
+    /// Asks the process to exit cleanly, as it would when terminated.
+    Shutdown = 4,
+    /// Asks the process for a [summary](packets::Status) of how it's been running.
+    QueryStatus = 5,
+    Status(packets::Status) = 6,
 }
 impl Packet {
     pub fn hello() -> Self {
@@ -161,7 +228,7 @@ impl Listener {
                         }
                     }
                     Err(e) => {
-                        eprintln!("IPC accept error: {e}");
+                        tracing::error!("IPC accept error: {e}");
                         break;
                     }
                 }
@@ -236,6 +303,169 @@ impl PacketConnection {
         self.outgoing.send(packet.into()).await?;
         Ok(())
     }
+
+    /// Writes [events] to the other end as they're published, until it hangs up.
+    async fn stream_events(self, subscription: packets::Subscribe) {
+        use tokio::{io::AsyncWriteExt, sync::broadcast::error::RecvError};
+        let mut events = events::subscribe();
+        let mut write = self.outgoing.into_inner().into_inner();
+        // Giving artwork in some formats means downloading or shrinking it first, so each line is made off to the side
+        // rather than holding up receiving the events after it; they're still written in the order they were published.
+        let (lines, mut pending) = tokio::sync::mpsc::channel::<tokio::task::JoinHandle<Option<String>>>(events::CAPACITY);
+        let receive = async move {
+            loop {
+                let published = match events.recv().await {
+                    Ok(published) => published,
+                    Err(RecvError::Lagged(missed)) => {
+                        tracing::warn!(missed, "IPC subscriber fell behind; skipped events");
+                        continue
+                    },
+                    Err(RecvError::Closed) => break,
+                };
+                let line = tokio::spawn(async move { published.to_line(subscription.artwork).await });
+                // only fails once the writing below has stopped
+                if lines.send(line).await.is_err() { break }
+            }
+        };
+        let write = async move {
+            while let Some(line) = pending.recv().await {
+                let Ok(Some(line)) = line.await else { continue };
+                if write.write_all(line.as_bytes()).await.is_err() { break }
+            }
+        };
+        tokio::join!(receive, write);
+    }
+
+    /// Asks the process on the other end for a [summary](packets::Status) of how it's been running.
+    pub async fn query_status(&mut self) -> Result<packets::Status, std::io::Error> {
+        self.send(Packet::QueryStatus).await?;
+        match self.recv().await? {
+            Some(Packet::Status(status)) => Ok(status),
+            Some(Packet::GeneralFailure(failure)) => Err(std::io::Error::other(failure.reason)),
+            Some(packet) => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("expected a status packet but got one with {}", packet.discriminant()))),
+            None => Err(std::io::ErrorKind::UnexpectedEof.into()),
+        }
+    }
+
+    /// [Subscribes](Packet::Subscribe) to the events of the process on the other end, copying them into the writer until it hangs up.
+    pub async fn follow_events(mut self, subscription: packets::Subscribe, to: &mut (impl tokio::io::AsyncWrite + Unpin)) -> Result<u64, std::io::Error> {
+        self.send(subscription).await?;
+        let mut read = self.incoming.into_inner().into_inner();
+        tokio::io::copy(&mut read, to).await
+    }
+}
+
+/// The dispatch events which are pushed to connections that have [subscribed](Packet::Subscribe).
+///
+/// Each is written as a single line of JSON in the shape of a [`Frame`]; see the `player-events` crate.
+/// Artwork is written in the [format](ArtworkFormat) each subscriber asked for, from the [disk cache](crate::data_fetching::artwork_cache).
+pub mod events {
+    use alloc::{borrow::Cow, sync::Arc};
+    use std::sync::LazyLock;
+
+    use tokio::sync::broadcast;
+
+    pub use player_events::{ArtworkFormat, Event, Frame, Track};
+    use crate::data_fetching::artwork_cache::CachedArtwork;
+
+    /// How many events a subscriber can fall behind by before it starts missing them.
+    pub(super) const CAPACITY: usize = 64;
+
+    static EVENTS: LazyLock<broadcast::Sender<Arc<Published>>> = LazyLock::new(|| broadcast::channel(CAPACITY).0);
+
+    /// Held by tests which dispatch, since what one publishes would reach any other's subscription.
+    #[cfg(test)]
+    pub static EXCLUSIVE: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+    /// An event as it's handed to each subscriber, before it's been serialized.
+    #[derive(Debug)]
+    pub struct Published {
+        at: i64,
+        content: Content,
+    }
+    #[derive(Debug)]
+    enum Content {
+        Event(Event<'static>),
+        /// The start of a track which has artwork, which is filled in for each subscriber.
+        TrackStarted { track: Track, artwork: Arc<CachedArtwork> },
+        /// Filled in for each subscriber like [`Content::TrackStarted`], and left out for those it couldn't be given to.
+        ArtworkResolved { track: Track, artwork: Arc<CachedArtwork> },
+    }
+    impl Published {
+        /// Serializes the event as a line of JSON, with any artwork in the given format.
+        /// Returns `None` if there's nothing to write.
+        pub async fn to_line(&self, format: ArtworkFormat) -> Option<String> {
+            let event = match &self.content {
+                Content::Event(event) => Cow::Borrowed(event),
+                Content::TrackStarted { track, artwork } => Cow::Owned(Event::TrackStarted {
+                    track: Cow::Borrowed(track),
+                    artwork: artwork.represent(format).await,
+                }),
+                Content::ArtworkResolved { track, artwork } => Cow::Owned(Event::ArtworkResolved {
+                    track: Cow::Borrowed(track),
+                    artwork: artwork.represent(format).await?,
+                }),
+            };
+            let frame = Frame { at: self.at, event: event.into_owned() };
+            match serde_json::to_string(&frame) {
+                Ok(mut line) => {
+                    line.push('\n');
+                    Some(line)
+                },
+                Err(error) => {
+                    tracing::error!(?error, event = ?frame.event, "failed to serialize event for IPC subscribers");
+                    None
+                }
+            }
+        }
+    }
+
+    fn send(content: Content) {
+        let at = chrono::Utc::now().timestamp_millis();
+        // only fails if everyone has unsubscribed since
+        let _ = EVENTS.send(Arc::new(Published { at, content }));
+    }
+
+    pub fn has_subscribers() -> bool {
+        EVENTS.receiver_count() != 0
+    }
+
+    /// Sends the event to every subscriber; it isn't copied at all if there aren't any.
+    pub fn publish(event: Event<'_>) {
+        if !has_subscribers() { return }
+        send(Content::Event(event.into_owned()));
+    }
+
+    /// Sends the start of a track to every subscriber, along with its artwork if it's known.
+    pub fn publish_track_started(track: &Track, artwork: Option<Arc<CachedArtwork>>) {
+        if !has_subscribers() { return }
+        send(artwork.map_or_else(
+            || Content::Event(Event::TrackStarted { track: Cow::Owned(track.clone()), artwork: None }),
+            |artwork| Content::TrackStarted { track: track.clone(), artwork },
+        ));
+    }
+
+    /// Sends the artwork of the playing track to every subscriber, if it was found after the track started.
+    pub fn publish_artwork_resolved(track: &Track, artwork: Arc<CachedArtwork>) {
+        if !has_subscribers() { return }
+        send(Content::ArtworkResolved { track: track.clone(), artwork });
+    }
+
+    pub fn subscribe() -> broadcast::Receiver<Arc<Published>> {
+        EVENTS.subscribe()
+    }
+}
+
+/// How long a process is given to answer a [status query](query_status); it can't until it's done polling the player.
+const STATUS_TIMEOUT: core::time::Duration = core::time::Duration::from_secs(5);
+
+/// Connects to the process listening at the path and asks it for a [summary](packets::Status) of how it's been running.
+pub async fn query_status(path: impl AsRef<std::path::Path>) -> Result<packets::Status, std::io::Error> {
+    tokio::time::timeout(STATUS_TIMEOUT, async {
+        let mut connection = PacketConnection::from_path(path).await?;
+        connection.send(Packet::hello()).await?;
+        connection.query_status().await
+    }).await.unwrap_or_else(|_| Err(std::io::ErrorKind::TimedOut.into()))
 }
 
 pub async fn listen(
@@ -267,6 +497,11 @@ pub async fn listen(
                 match act_upon_next_packet(&hello, &mut connection, context.clone(), config.clone()).await {
                     ConnectionAction::Continue => {},
                     ConnectionAction::Break => break,
+                    ConnectionAction::Subscribe(subscription) => {
+                        tracing::debug!(pid = hello.process, ?subscription, "IPC connection subscribed to events");
+                        tokio::spawn(connection.stream_events(subscription));
+                        break
+                    }
                 }
             }
         }
@@ -276,6 +511,8 @@ pub async fn listen(
 enum ConnectionAction {
     Continue,
     Break,
+    /// Hand the connection off to [stream events](PacketConnection::stream_events) to it.
+    Subscribe(packets::Subscribe),
 }
 
 #[expect(clippy::significant_drop_tightening, reason = "holding a config lock is desired, since possible race conditions would be wacky")]
@@ -305,6 +542,24 @@ async fn act_upon_next_packet(
                 context.lock().await.reload_from_config(&config).await;
                 ConnectionAction::Continue
             }
+            Packet::Subscribe(subscription) => ConnectionAction::Subscribe(subscription),
+            Packet::Shutdown => {
+                tracing::info!(pid = hello.process, "asked to shut down over IPC");
+                unsafe { libc::kill(*crate::util::OWN_PID, libc::SIGTERM); }
+                ConnectionAction::Break
+            }
+            Packet::QueryStatus => {
+                let status = packets::Status::of(&context.lock().await.session);
+                if let Err(err) = connection.send(status).await {
+                    tracing::error!(?err, "could not send status over IPC");
+                    return ConnectionAction::Break;
+                }
+                ConnectionAction::Continue
+            }
+            Packet::Status(status) => {
+                tracing::warn!(?status, "received unsolicited status from process {pid}", pid = hello.process);
+                ConnectionAction::Continue
+            }
         },
         Ok(None) => ConnectionAction::Break,
         Err(err) => {
@@ -313,3 +568,33 @@ async fn act_upon_next_packet(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn events_are_streamed_in_order() {
+        use tokio::io::AsyncBufReadExt as _;
+        use player_events::Event;
+        let _exclusive = events::EXCLUSIVE.lock().await;
+
+        let (ours, theirs) = UnixStream::pair().unwrap();
+        tokio::spawn(PacketConnection::from_stream(ours).stream_events(packets::Subscribe::default()));
+        while !events::has_subscribers() { tokio::task::yield_now().await }
+
+        for volume in 0..10 {
+            events::publish(Event::VolumeChanged { volume, muted: false });
+        }
+        events::publish(Event::Terminating);
+
+        let mut lines = tokio::io::BufReader::new(theirs).lines();
+        for volume in 0..10 {
+            let line = lines.next_line().await.unwrap().unwrap();
+            let frame: serde_json::Value = serde_json::from_str(&line).unwrap();
+            assert_eq!(frame["volume"], volume);
+        }
+        let line = lines.next_line().await.unwrap().unwrap();
+        assert!(line.contains("terminating"), "{line}");
+    }
+}
@@ -10,14 +10,90 @@ fn is_process_running(pid: libc::pid_t) -> bool {
     unsafe { libc::kill(pid, 0) == 0 }
 }
 
+/// What the OS knows about a running process.
+struct ProcessInfo {
+    /// When the process started, in seconds since the epoch.
+    started_at: u64,
+    executable: std::path::PathBuf,
+}
+impl ProcessInfo {
+    #[cfg(target_os = "macos")]
+    fn get(pid: libc::pid_t) -> Option<Self> {
+        use std::os::unix::ffi::OsStrExt as _;
+
+        let mut info = core::mem::MaybeUninit::<libc::proc_bsdinfo>::uninit();
+        #[expect(clippy::cast_possible_truncation, clippy::cast_possible_wrap, reason = "struct is far smaller than i32::MAX")]
+        let size = core::mem::size_of::<libc::proc_bsdinfo>() as libc::c_int;
+        if unsafe { libc::proc_pidinfo(pid, libc::PROC_PIDTBSDINFO, 0, info.as_mut_ptr().cast(), size) } != size { return None }
+        let info = unsafe { info.assume_init() };
+
+        #[expect(clippy::cast_sign_loss, reason = "constant is positive")]
+        let mut path = [0_u8; libc::PROC_PIDPATHINFO_MAXSIZE as usize];
+        #[expect(clippy::cast_possible_truncation, reason = "buffer is PROC_PIDPATHINFO_MAXSIZE long")]
+        let length = unsafe { libc::proc_pidpath(pid, path.as_mut_ptr().cast(), path.len() as u32) };
+        let length = usize::try_from(length).ok().filter(|&length| length > 0)?;
+
+        Some(Self {
+            started_at: info.pbi_start_tvsec,
+            executable: std::ffi::OsStr::from_bytes(&path[..length]).into(),
+        })
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    const fn get(_: libc::pid_t) -> Option<Self> {
+        None
+    }
+
+    /// Whether the process is running the same program as this one.
+    fn is_own_program(&self) -> bool {
+        std::env::current_exe().is_ok_and(|own| own.file_name() == self.executable.file_name())
+    }
+}
+
+/// The process which last claimed the lockfile.
+#[derive(Debug, PartialEq, Eq)]
+struct Lock {
+    pid: libc::pid_t,
+    /// When the process started, in seconds since the epoch, so that another process later given the same PID isn't mistaken for it.
+    /// Absent in lockfiles written before this was recorded.
+    started_at: Option<u64>,
+}
+impl Lock {
+    fn parse(contents: &str) -> Result<Self, core::num::ParseIntError> {
+        let mut parts = contents.split_whitespace();
+        Ok(Self {
+            pid: parts.next().unwrap_or_default().parse()?,
+            started_at: parts.next().map(str::parse).transpose()?,
+        })
+    }
+
+    /// Whether the process which claimed the lockfile is still running.
+    fn is_held(&self) -> bool {
+        if !is_process_running(self.pid) { return false }
+        // If the OS won't say, the process is given the benefit of the doubt.
+        ProcessInfo::get(self.pid).is_none_or(|info| {
+            self.started_at.is_none_or(|started_at| started_at == info.started_at) && info.is_own_program()
+        })
+    }
+}
+impl core::fmt::Display for Lock {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.pid)?;
+        if let Some(started_at) = self.started_at {
+            write!(f, " {started_at}")?;
+        }
+        Ok(())
+    }
+}
+
 pub struct ActiveProcessLockfile;
 impl ActiveProcessLockfile {
-    /// Returns the stored PID, which may not necessarily still be running.
-    async fn read() -> Option<libc::pid_t>  {
+    /// Returns what's stored, which may not necessarily still be running.
+    async fn read() -> Option<Lock>  {
         match tokio::fs::read_to_string(&*LOCKFILE_PATH).await {
             Ok(contents) => {
-                match contents.trim().parse::<libc::pid_t>() {
-                    Ok(pid) => Some(pid),
+                match Lock::parse(&contents) {
+                    Ok(lock) => Some(lock),
                     Err(err) => {
                         tracing::error!("failed to parse pid from lockfile: {}", err);
                         None
@@ -33,15 +109,41 @@ impl ActiveProcessLockfile {
     }
 
     /// Returns the stored PID if it is still running.
+    ///
+    /// A lockfile left behind by a process which didn't get to clear it (such as if it was killed) is cleared.
     pub async fn get() -> Option<libc::pid_t> {
-        Self::read().await.filter(|&pid| is_process_running(pid))
+        let lock = Self::read().await?;
+        if lock.is_held() { return Some(lock.pid) }
+
+        tracing::info!(pid = lock.pid, "clearing stale lockfile; the process which left it is no longer running");
+        if let Err(err) = Self::clear().await && err.kind() != std::io::ErrorKind::NotFound {
+            tracing::error!("failed to clear stale lockfile: {}", err);
+        }
+        None
     }
 
     pub async fn write() -> Result<(), std::io::Error> {
-        tokio::fs::write(&*LOCKFILE_PATH, OWN_PID.to_string()).await
+        let lock = Lock {
+            pid: *OWN_PID,
+            started_at: ProcessInfo::get(*OWN_PID).map(|info| info.started_at),
+        };
+        tokio::fs::write(&*LOCKFILE_PATH, lock.to_string()).await
     }
 
     pub async fn clear() -> Result<(), std::io::Error> {
         tokio::fs::remove_file(&*LOCKFILE_PATH).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Lock;
+
+    #[test]
+    fn lock_contents() {
+        let lock = Lock { pid: 123, started_at: Some(1_700_000_000) };
+        assert_eq!(Lock::parse(&lock.to_string()), Ok(lock));
+        assert_eq!(Lock::parse("456\n"), Ok(Lock { pid: 456, started_at: None }), "lockfiles written with only the pid are still read");
+        assert!(Lock::parse("").is_err());
+    }
+}
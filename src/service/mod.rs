@@ -7,12 +7,78 @@ use crate::util::{ferror, REVERSE_DNS_IDENTIFIER};
 pub mod ipc;
 pub mod lockfile;
 
+/// How long an instance which is asked to exit so that the service can [take its place](ServiceController::adopt) has to do so.
+const ADOPTION_TIMEOUT: core::time::Duration = core::time::Duration::from_secs(15);
+
 const JOB_DEFINITION_TEMPLATE: &str = include_str!("definition.plist.template");
 
 static JOB_DEFINITION_LOCATION: LazyLock<std::path::PathBuf> = LazyLock::new(|| {
     crate::util::HOME.join(concat!("Library/LaunchAgents/", crate::util::get_reverse_dns_identifier!(), ".plist"))
 });
 
+/// When and how launchd runs the background service.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Whether the service is started as soon as it's registered, such as on login.
+    pub run_at_load: bool,
+    pub keep_alive: KeepAlive,
+    /// The least number of seconds between launches of the service, if it keeps exiting; launchd defaults to ten.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub throttle_interval: Option<u32>,
+    /// The scheduling priority to run the service with, from -20 (most favorable) to 20 (least favorable).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nice: Option<i8>,
+}
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            run_at_load: true,
+            keep_alive: KeepAlive::Always,
+            throttle_interval: None,
+            nice: None,
+        }
+    }
+}
+impl Config {
+    /// The keys of the job definition which carry out the policy.
+    fn render(&self) -> String {
+        const fn boolean(value: bool) -> &'static str {
+            if value { "<true/>" } else { "<false/>" }
+        }
+
+        let mut keys = vec![
+            "<key>RunAtLoad</key>".to_owned(),
+            boolean(self.run_at_load).to_owned(),
+            "<key>KeepAlive</key>".to_owned(),
+            match self.keep_alive {
+                KeepAlive::Never => boolean(false).to_owned(),
+                KeepAlive::Crashed => format!("<dict><key>Crashed</key>{}</dict>", boolean(true)),
+                KeepAlive::Always => boolean(true).to_owned(),
+            },
+        ];
+        if let Some(interval) = self.throttle_interval {
+            keys.push("<key>ThrottleInterval</key>".to_owned());
+            keys.push(format!("<integer>{interval}</integer>"));
+        }
+        if let Some(nice) = self.nice {
+            keys.push("<key>Nice</key>".to_owned());
+            keys.push(format!("<integer>{}</integer>", nice.clamp(-20, 20)));
+        }
+        keys.join("\n        ")
+    }
+}
+
+/// Whether launchd starts the background service again after it exits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum KeepAlive {
+    Never,
+    /// Only if it exited because of a crash.
+    Crashed,
+    Always,
+}
+
 static USER_ID: LazyLock<libc::uid_t> = LazyLock::new(|| unsafe { libc::getuid() });
 static DOMAIN_TARGET: LazyLock<String> = LazyLock::new(|| format!("gui/{}", *USER_ID));
 
@@ -23,9 +89,10 @@ impl ServiceController {
         LaunchAgent::new(&JOB_DEFINITION_LOCATION)
     }
 
-    fn render_job_definition(config_path: impl AsRef<std::path::Path>) -> String {
+    fn render_job_definition(config_path: impl AsRef<std::path::Path>, policy: &Config) -> String {
         JOB_DEFINITION_TEMPLATE
             .replace("{{ reverse_dns_identifier }}", REVERSE_DNS_IDENTIFIER)
+            .replace("{{ policy }}", &policy.render())
             .replace("{{ app_path }}", std::env::current_exe().expect("cannot get own executable path").to_string_lossy().as_ref())
             .replace("{{ config_path }}", config_path.as_ref().to_string_lossy().as_ref())
     }
@@ -34,27 +101,34 @@ impl ServiceController {
         &JOB_DEFINITION_LOCATION
     }
 
-    async fn write_job_definition(config_path: impl AsRef<std::path::Path>) -> Result<(), std::io::Error> {
-        LaunchAgent::write_definition(Self::get_definition_path(), Self::render_job_definition(config_path)).await
+    async fn write_job_definition(config_path: impl AsRef<std::path::Path>, policy: &Config) -> Result<(), std::io::Error> {
+        LaunchAgent::write_definition(Self::get_definition_path(), Self::render_job_definition(config_path, policy)).await
     }
 
     async fn delete_job_definition() -> Result<bool, std::io::Error> {
         Self::agent().remove_definition().await
     }
 
-    pub async fn start(config_path: impl AsRef<std::path::Path> + Send + Sync, log: bool) {
-        if let Err(err) = Self::write_job_definition(&config_path).await {
-            ferror!("Failed to write job definition file: {}", err);
+    /// Regenerates the job definition per the configured policy, then registers it.
+    pub async fn start(config_path: impl AsRef<std::path::Path> + Send + Sync, policy: &Config, log: bool) {
+        if let Err(err) = Self::write_job_definition(&config_path, policy).await {
+            ferror!("{}", crate::i18n::service_write_definition_failed(err));
+        }
+
+        // It can't be registered without a session to register it to, but launchd will on the next login.
+        if crate::gui::is_headless() {
+            if log { crate::console::note!("{}", crate::i18n::service_registration_deferred()); }
+            return;
         }
 
         match Self::agent().register().await {
-            Err(err) => ferror!("Failed to register service: {}", err),
+            Err(err) => ferror!("{}", crate::i18n::service_register_failed(err)),
             Ok(was_registered) => {
                 if log {
                     if was_registered {
-                        println!("Service registered and started!");
+                        crate::console::note!("{}", crate::i18n::service_started());
                     } else {
-                        println!("Service already registered; not registering again.");
+                        crate::console::note!("{}", crate::i18n::service_already_registered());
                     }
                 }
             }
@@ -62,10 +136,36 @@ impl ServiceController {
         }
     }
 
-    pub async fn restart(config_path: impl AsRef<std::path::Path> + Send + Sync) {
+    /// Asks the instance of the program that's running outside of the service to exit, so that the service can take its place.
+    /// It's asked over IPC, falling back to a termination signal if it can't be reached that way.
+    ///
+    /// Returns whether it exited in time.
+    pub async fn adopt(pid: libc::pid_t, socket_path: impl AsRef<std::path::Path>) -> bool {
+        use ipc::{Packet, PacketConnection};
+        let asked = match PacketConnection::from_path(socket_path).await {
+            Ok(mut connection) => connection.send(Packet::hello()).await.is_ok() && connection.send(Packet::Shutdown).await.is_ok(),
+            Err(_) => false,
+        };
+        if !asked {
+            crate::console::detail!("{}", crate::i18n::service_adopting_by_signal());
+            unsafe { libc::kill(pid, libc::SIGTERM); }
+        }
+
+        let deadline = tokio::time::Instant::now() + ADOPTION_TIMEOUT;
+        while lockfile::ActiveProcessLockfile::get().await == Some(pid) {
+            if tokio::time::Instant::now() >= deadline { return false }
+            tokio::time::sleep(core::time::Duration::from_millis(100)).await;
+        }
+        true
+    }
+
+    pub async fn restart(config_path: impl AsRef<std::path::Path> + Send + Sync, policy: &Config) {
+        if crate::gui::is_headless() {
+            return Self::start(config_path, policy, true).await;
+        }
         Self::stop(false).await;
-        Self::start(config_path, false).await;
-        println!("Service restarted!");
+        Self::start(config_path, policy, false).await;
+        crate::console::note!("{}", crate::i18n::service_restarted());
     }
 
     pub async fn stop(log: bool) {
@@ -74,39 +174,40 @@ impl ServiceController {
             Ok(was_registered) => {
                 if log {
                     if was_registered {
-                        print!("Service");
-                        if let Some(pid) = pid {
-                            print!(" (pid {pid}) stopped and");
-                        }
-                        println!(" temporarily unregistered! It will start again on the next login, or when started again manually.");
-                        println!("(If you want to fully remove the service, use `am-osx-status service remove`.)");
+                        crate::console::note!("{}", pid.map_or_else(crate::i18n::service_unregistered_temporarily, crate::i18n::service_stopped));
+                        crate::console::note!("{}", crate::i18n::service_remove_hint());
                     } else {
-                        println!("Service wasn't registered to begin with.");
+                        crate::console::note!("{}", crate::i18n::service_was_not_registered());
                     }
                 }
             },
             Err(err) => {
-                ferror!("Failed to unregister service: {}", err);
+                ferror!("{}", crate::i18n::service_unregister_failed(err));
             }
         }
     }
 
     pub async fn remove() {
+        // Without a session, it can't be registered; there's only the definition to remove.
+        if crate::gui::is_headless() {
+            match Self::delete_job_definition().await {
+                Ok(true) => crate::console::note!("{}", crate::i18n::service_removed()),
+                Ok(false) => crate::console::note!("{}", crate::i18n::service_was_not_registered()),
+                Err(err) => ferror!("{}", crate::i18n::service_unregister_failed(err)),
+            }
+            return;
+        }
         let pid = Self::agent().get_pid().await;
         match Self::agent().unregister(true).await {
             Ok(was_registered) => {
                 if was_registered {
-                    print!("Service");
-                    if let Some(pid) = pid {
-                        print!(" (pid {pid}) stopped and");
-                    }
-                    println!(" unregistered! To add it again, use `am-osx-status service start`.");
+                    crate::console::note!("{}", pid.map_or_else(crate::i18n::service_removed, crate::i18n::service_stopped_and_removed));
                 } else {
-                    println!("Service wasn't registered to begin with.");
+                    crate::console::note!("{}", crate::i18n::service_was_not_registered());
                 }
             },
             Err(err) => {
-                ferror!("Failed to unregister service: {}", err);
+                ferror!("{}", crate::i18n::service_unregister_failed(err));
             }
         }
     }
@@ -305,12 +406,21 @@ mod tests {
         CONFIG_PATH.as_path()
     }
    
+    #[test]
+    fn policy() {
+        let policy = Config { keep_alive: KeepAlive::Crashed, nice: Some(5), ..Config::default() };
+        let definition = ServiceController::render_job_definition(get_config_path(), &policy);
+        assert!(definition.contains("<key>KeepAlive</key>\n        <dict><key>Crashed</key><true/></dict>"));
+        assert!(definition.contains("<key>Nice</key>\n        <integer>5</integer>"));
+        assert!(!definition.contains("ThrottleInterval"), "unset options are left up to launchd");
+    }
+
     #[tokio::test]
     #[ignore = "has race condition with other tests; will override real service too"]
     async fn double_register() {
         let agent = ServiceController::agent();
         assert!(agent.unregister(true).await.is_ok());
-        ServiceController::write_job_definition(get_config_path()).await.expect("failed to write service definition");
+        ServiceController::write_job_definition(get_config_path(), &Config::default()).await.expect("failed to write service definition");
         assert!( agent.register().await.expect("failed to register service")); //  true if it was not already registered
         assert!(!agent.register().await.expect("failed to register service")); // false if it was     already registered
         assert!(agent.unregister(true).await.is_ok());
@@ -320,7 +430,7 @@ mod tests {
     #[ignore = "has race condition with other tests; will override real service too"]
     async fn double_unregister() {
         let agent = ServiceController::agent();
-        ServiceController::write_job_definition(get_config_path()).await.expect("failed to write service definition");
+        ServiceController::write_job_definition(get_config_path(), &Config::default()).await.expect("failed to write service definition");
         agent.register().await.expect("failed to register service");
         assert!( agent.unregister(true).await.expect("failed to unregister service")); //  true if it was     registered
         assert!(!agent.unregister(true).await.expect("failed to unregister service")); // false if it was not registered
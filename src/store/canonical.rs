@@ -0,0 +1,89 @@
+//! Collapsing the copies of a song which have different persistent IDs (e.x. a local file and its streaming match)
+//! into one [canonical track](super::entities::CanonicalTrack), so that history isn't split across them.
+//!
+//! Copies are considered the same song if their artist and title are equal once [normalized](normalize),
+//! and their durations are within [`DURATION_TOLERANCE`] of each other.
+
+/// How far apart the durations of two copies of a song can be while still being considered the same.
+pub const DURATION_TOLERANCE: core::time::Duration = core::time::Duration::from_secs(2);
+
+/// Case-folds the text, drops punctuation, and collapses whitespace.
+///
+/// Apostrophes are dropped without splitting the word, so that `Don't` and `Don’t` are both `dont`.
+pub fn normalize(text: &str) -> String {
+    text.split(|char: char| !char.is_alphanumeric() && char != '\'' && char != '’')
+        .map(|word| word.chars().filter(|char| char.is_alphanumeric()).flat_map(char::to_lowercase).collect::<String>())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// What identifies a song across the copies of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CanonicalKey {
+    /// The normalized artist name; empty if unknown.
+    pub artist: String,
+    /// The normalized title.
+    pub title: String,
+    pub duration: Option<core::time::Duration>,
+}
+impl CanonicalKey {
+    pub fn new(artist: Option<&str>, title: &str, duration: Option<core::time::Duration>) -> Self {
+        Self {
+            artist: artist.map(normalize).unwrap_or_default(),
+            title: normalize(title),
+            duration
+        }
+    }
+
+    /// Whether the two keys identify the same song.
+    ///
+    /// Stored tracks are matched by [the same rule](super::entities::CanonicalTrack::resolve) in the query for them instead.
+    #[cfg(test)]
+    pub fn matches(&self, other: &Self) -> bool {
+        self.artist == other.artist && self.title == other.title && match (self.duration, other.duration) {
+            (Some(a), Some(b)) => a.abs_diff(b) <= DURATION_TOLERANCE,
+            (None, None) => true,
+            _ => false
+        }
+    }
+
+    /// The duration in milliseconds, as stored.
+    pub fn duration_millis(&self) -> Option<i64> {
+        self.duration.map(|duration| i64::try_from(duration.as_millis()).unwrap_or(i64::MAX))
+    }
+}
+impl From<&crate::DispatchableTrack> for CanonicalKey {
+    fn from(track: &crate::DispatchableTrack) -> Self {
+        Self::new(track.artist.as_deref(), &track.name, track.duration)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::time::Duration;
+
+    #[test]
+    fn normalization() {
+        assert_eq!(normalize("  Don't   Stop Me  Now "), "dont stop me now");
+        assert_eq!(normalize("Don’t Stop Me Now"), "dont stop me now");
+        assert_eq!(normalize("MYTH & ROID"), "myth roid");
+        assert_eq!(normalize("Ágape (Live)"), "ágape live");
+        assert_eq!(normalize("!!!"), "");
+    }
+
+    #[test]
+    fn matching() {
+        let local = CanonicalKey::new(Some("The Age of Rockets"), "Pictures of Space", Some(Duration::from_millis(241_200)));
+        let streamed = CanonicalKey::new(Some("the age of rockets"), "Pictures Of Space", Some(Duration::from_secs(243)));
+        assert!(local.matches(&streamed));
+
+        let extended = CanonicalKey { duration: Some(Duration::from_millis(243_300)), ..local.clone() };
+        assert!(!local.matches(&extended));
+
+        let unknown = CanonicalKey { duration: None, ..local.clone() };
+        assert!(!local.matches(&unknown));
+        assert!(unknown.matches(&unknown));
+    }
+}
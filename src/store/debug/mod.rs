@@ -20,7 +20,7 @@ macro_rules! mk_test_db {
                 // this is also a problem in non-test environments but we can refresh the pool for that
                 // that isn't an option here though since it's in-memory and would reset so we work-around
             let pool = SqlitePoolOptions::new().max_connections(1);
-            super::super::GlobalPoolOptions { connect, pool }
+            $crate::store::GlobalPoolOptions { connect, pool }
         });
 
         let $ident = POOL.get().await.expect("failed to get pool");
@@ -23,6 +23,11 @@ impl<T> sqlx::Type<sqlx::Sqlite> for Key<T> where i64: sqlx::Type<sqlx::Sqlite>
         <i64 as sqlx::Type<sqlx::Sqlite>>::compatible(ty)
     }
 }
+impl<T> Key<T> {
+    pub const fn get(self) -> i64 {
+        self.0
+    }
+}
 impl<T> From<i64> for Key<T> {
     fn from(value: i64) -> Self {
         Self(value, core::marker::PhantomData)
@@ -146,7 +151,7 @@ impl DeferredTrack {
             .bind(track.track_number)
             .bind(track.persistent_id)
             .bind(track.duration.map(|d| f64::from(d.as_secs_f32())))
-            .bind(&track.media_kind)
+            .bind(track.media_kind)
             .fetch_one(pool).await
             .map(|v| v.id)
     }
@@ -193,10 +198,19 @@ pub struct Session {
     /// A positive integer.
     pub osa_fetches_player: i64,
 
+    /// How many dispatches each backend was given over the session, and how many of them failed.
+    /// Only stored once the session [finishes](Self::finish); see [`crate::subscribers::statistics`].
+    #[sqlx(json)]
+    pub dispatches: crate::subscribers::statistics::Summary,
+
     pub started_at: MillisecondTimestamp,
     pub ended_at: Option<MillisecondTimestamp>,
 }
 impl Session {
+    pub const fn id(&self) -> Key<Self> {
+        self.id
+    }
+    /// How long the session lasted, or has lasted so far if it hasn't finished.
     pub fn duration(&self) -> chrono::Duration {
         self.ended_at.map_or_else(chrono::Utc::now, |v| v.0) - self.started_at.0
     }
@@ -223,31 +237,45 @@ impl Session {
             .bind(migration_id)
             .fetch_one(&crate::store::DB_POOL.get().await.expect("couldn't get db pool")).await
     }
+    /// Records that the player is now on a different version than it was when the session started,
+    /// such as from having been updated and relaunched.
+    pub async fn set_player_version(&mut self, pool: &sqlx::SqlitePool, player_version: &str) -> sqlx::Result<()> {
+        sqlx::query!("UPDATE sessions SET ver_player = ? WHERE id = ?", player_version, self.id)
+            .execute(pool).await?;
+        player_version.clone_into(&mut self.player_version);
+        Ok(())
+    }
     pub async fn update(&self, pool: &sqlx::SqlitePool) -> sqlx::Result<()> {
+        let dispatches = serde_json::to_string(&self.dispatches).map_err(|error| sqlx::Error::Encode(error.into()))?;
         sqlx::query!(r#"
             UPDATE sessions SET
                 osa_fetches_track = ?,
-                osa_fetches_player = ?
+                osa_fetches_player = ?,
+                dispatches = ?
             WHERE id = ?
         "#, 
             self.osa_fetches_track,
             self.osa_fetches_player,
+            dispatches,
             self.id
         ).execute(pool).await?;
         Ok(())
     }
     pub async fn finish(&self, pool: &sqlx::SqlitePool) -> sqlx::Result<()> {
         let now = chrono::Utc::now().timestamp_millis();
+        let dispatches = serde_json::to_string(&self.dispatches).map_err(|error| sqlx::Error::Encode(error.into()))?;
         sqlx::query!(r#"
             UPDATE sessions SET
                 ended_at = ?,
                 osa_fetches_track = ?,
-                osa_fetches_player = ?
+                osa_fetches_player = ?,
+                dispatches = ?
             WHERE id = ?
         "#, 
             now,
             self.osa_fetches_track,
             self.osa_fetches_player,
+            dispatches,
             self.id,
         ).execute(pool).await.and_then(|v| {
             if v.rows_affected() == 0 {
@@ -469,5 +497,897 @@ impl CachedUncensoredTitle {
             .bind(persistent_id)
             .fetch_optional(pool).await
     }
+
+    /// Replaces whatever is cached for the track, such as to correct a wrong title.
+    pub async fn set(
+        pool: &sqlx::SqlitePool,
+        persistent_id: StoredPersistentId,
+        uncensored: Option<&str>,
+    ) -> sqlx::Result<Self> {
+        let mut transaction = pool.begin().await?;
+        sqlx::query!("DELETE FROM uncensored_titles WHERE persistent_id = ?", persistent_id)
+            .execute(&mut *transaction).await?;
+        let cached = sqlx::query_as!(Self, r#"
+            INSERT INTO uncensored_titles (
+                persistent_id,
+                uncensored
+            ) VALUES (?, ?) RETURNING
+                id as "id: _",
+                persistent_id as "persistent_id: _",
+                uncensored,
+                timestamp as "timestamp: _"
+        "#,
+            persistent_id,
+            uncensored,
+        ).fetch_one(&mut *transaction).await?;
+        transaction.commit().await?;
+        Ok(cached)
+    }
+
+    /// Removes whatever is cached for the track, so that it'll be looked up again the next time it's played.
+    ///
+    /// Returns whether anything was cached.
+    pub async fn clear(
+        pool: &sqlx::SqlitePool,
+        persistent_id: StoredPersistentId,
+    ) -> sqlx::Result<bool> {
+        sqlx::query!("DELETE FROM uncensored_titles WHERE persistent_id = ?", persistent_id)
+            .execute(pool).await
+            .map(|result| result.rows_affected() != 0)
+    }
+}
+
+
+/// A single listen of a track, made up of the [segments](ListenSegment) of it that were actually played.
+#[derive(Debug, sqlx::FromRow)]
+pub struct Listen {
+    id: Key<Self>,
+    /// `None` if the listen was [synced](crate::sync) from another machine.
+    pub session: Option<Key<Session>>,
+    /// The persistent ID of the track as it is on the machine the listen was recorded on.
+    pub persistent_id: StoredPersistentId,
+    /// When the earliest segment of the listen started.
+    pub started_at: MillisecondTimestamp,
+    pub recorded_at: MillisecondTimestamp,
+    pub canonical: Option<Key<CanonicalTrack>>,
+    /// The name of the machine the listen was recorded on, if it wasn't this one.
+    pub origin: Option<String>,
+    /// The ID of the listen on the machine it was recorded on, if it wasn't this one.
+    pub origin_id: Option<i64>,
+    /// Whether an overlapping listen of the same track was kept instead of this one.
+    pub superseded: bool,
+    /// The dispatched track, as JSON; `None` if the listen was synced, or recorded before this was kept.
+    track: Option<String>,
+    /// The [keys](crate::subscribers::BackendIdentity::get_key) of the backends the track is excluded from, separated by spaces.
+    excluded: Option<String>,
+}
+impl FromKey for Listen {
+    const TABLE_NAME: &'static str = "listens";
+}
+impl Listen {
+    /// Records a listen and all of its flushed segments.
+    /// 
+    /// Returns `None` if nothing was listened to, in which case nothing is stored.
+    pub async fn new(
+        pool: &sqlx::SqlitePool,
+        session: Key<Session>,
+        track: &crate::DispatchableTrack,
+        canonical: Option<Key<CanonicalTrack>>,
+        listened: &crate::listened::Listened,
+    ) -> sqlx::Result<Option<Self>> {
+        let Some(started_at) = listened.contiguous.iter().map(crate::listened::ListenedChunk::started_at).min() else { return Ok(None) };
+
+        let json = serde_json::to_string(track).map_err(|error| sqlx::Error::Encode(Box::new(error)))?;
+        let excluded = track.excluded_backends.to_keys();
+        let mut transaction = pool.begin().await?;
+        let started_at = started_at.timestamp_millis();
+        let listen = sqlx::query_as!(Self, r#"
+            INSERT INTO listens (
+                session,
+                persistent_id,
+                started_at,
+                canonical,
+                track,
+                excluded
+            ) VALUES (?, ?, ?, ?, ?, ?) RETURNING
+                id as "id: _",
+                session as "session: _",
+                persistent_id as "persistent_id: _",
+                started_at as "started_at: _",
+                recorded_at as "recorded_at: _",
+                canonical as "canonical: _",
+                origin,
+                origin_id,
+                superseded as "superseded: _",
+                track,
+                excluded
+        "#,
+            session,
+            track.persistent_id,
+            started_at,
+            canonical,
+            json,
+            excluded,
+        ).fetch_one(&mut *transaction).await?;
+
+        for chunk in &listened.contiguous {
+            let started_at = chunk.started_at().timestamp_millis();
+            let duration = chunk.duration().num_milliseconds();
+            let started_at_position = f64::from(chunk.started_at_song_position());
+            let expected_end_position = f64::from(chunk.ended_at_song_position());
+            let actual_end_position = chunk.actual_ended_at_song_position().map(f64::from);
+            sqlx::query!(r#"
+                INSERT INTO listen_segments (
+                    listen,
+                    started_at,
+                    duration,
+                    started_at_position,
+                    expected_end_position,
+                    actual_end_position
+                ) VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+                listen.id,
+                started_at,
+                duration,
+                started_at_position,
+                expected_end_position,
+                actual_end_position,
+            ).execute(&mut *transaction).await?;
+        }
+
+        if let Some(canonical) = canonical {
+            Self::resolve_overlaps(&mut transaction, canonical, listen.id).await?;
+        }
+
+        transaction.commit().await?;
+        Ok(Some(listen))
+    }
+
+    pub const fn id(&self) -> Key<Self> {
+        self.id
+    }
+
+    /// Stores a listen that was recorded on another machine, unless it's already been stored.
+    ///
+    /// Returns whether it was stored.
+    pub async fn insert_synced(
+        pool: &sqlx::SqlitePool,
+        listen: &crate::sync::SyncedListen,
+    ) -> sqlx::Result<bool> {
+        let mut transaction = pool.begin().await?;
+
+        let canonical = match &listen.track {
+            Some(track) => Some(CanonicalTrack::find_or_insert(&mut transaction, &track.into()).await?),
+            None => None
+        };
+
+        let id = sqlx::query_scalar!(r#"
+            INSERT INTO listens (
+                persistent_id,
+                started_at,
+                canonical,
+                origin,
+                origin_id
+            ) VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT(origin, origin_id) DO NOTHING
+            RETURNING id as "id: Key<Listen>"
+        "#,
+            listen.persistent_id,
+            listen.started_at,
+            canonical,
+            listen.origin,
+            listen.id,
+        ).fetch_optional(&mut *transaction).await?;
+        let Some(id) = id else { return Ok(false) };
+
+        for segment in &listen.segments {
+            sqlx::query!(r#"
+                INSERT INTO listen_segments (
+                    listen,
+                    started_at,
+                    duration,
+                    started_at_position,
+                    expected_end_position,
+                    actual_end_position
+                ) VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+                id,
+                segment.started_at,
+                segment.duration,
+                segment.started_at_position,
+                segment.expected_end_position,
+                segment.actual_end_position,
+            ).execute(&mut *transaction).await?;
+        }
+
+        if let Some(canonical) = canonical {
+            Self::resolve_overlaps(&mut transaction, canonical, id).await?;
+        }
+
+        transaction.commit().await?;
+        Ok(true)
+    }
+
+    /// Marks every listen of the track which overlaps the given one as superseded, except for whichever of them
+    /// was listened to the longest; it's assumed that they're the same listen, recorded by more than one machine.
+    ///
+    /// The order is total (ties go to the earliest, then to the lowest persistent ID), so that every machine which
+    /// has the same listens will agree on which to keep, regardless of the order they were received in.
+    async fn resolve_overlaps(
+        connection: &mut sqlx::SqliteConnection,
+        canonical: Key<CanonicalTrack>,
+        listen: Key<Self>,
+    ) -> sqlx::Result<()> {
+        let overlapping = sqlx::query_scalar!(r#"
+            WITH spans AS (
+                SELECT
+                    listens.id,
+                    listens.started_at,
+                    listens.persistent_id,
+                    MAX(listen_segments.started_at + listen_segments.duration) AS ended_at,
+                    SUM(listen_segments.duration) AS listened
+                FROM listens JOIN listen_segments ON listen_segments.listen = listens.id
+                WHERE listens.canonical = ?1
+                GROUP BY listens.id
+            )
+            SELECT spans.id as "id!: Key<Listen>" FROM spans, spans AS target
+            WHERE target.id = ?2 AND spans.started_at < target.ended_at AND target.started_at < spans.ended_at
+            ORDER BY spans.listened DESC, spans.started_at, spans.persistent_id
+        "#,
+            canonical,
+            listen,
+        ).fetch_all(&mut *connection).await?;
+
+        if overlapping.len() > 1 {
+            tracing::debug!(?overlapping, "resolving overlapping listens");
+            for (index, id) in overlapping.into_iter().enumerate() {
+                let superseded = index != 0;
+                sqlx::query!("UPDATE listens SET superseded = ? WHERE id = ?", superseded, id)
+                    .execute(&mut *connection).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The most recent listens which weren't superseded, most recent first.
+    pub async fn get_recent(pool: &sqlx::SqlitePool, limit: u32) -> sqlx::Result<Vec<Self>> {
+        sqlx::query_as!(Self, r#"
+            SELECT
+                id as "id!: _",
+                session as "session: _",
+                persistent_id as "persistent_id: _",
+                started_at as "started_at: _",
+                recorded_at as "recorded_at: _",
+                canonical as "canonical: _",
+                origin,
+                origin_id,
+                superseded as "superseded: _",
+                track,
+                excluded
+            FROM listens WHERE NOT superseded ORDER BY started_at DESC LIMIT ?
+        "#, limit).fetch_all(pool).await
+    }
+
+    /// Listens recorded on this machine, in the order they were stored, starting after the one with the given ID.
+    pub async fn get_recorded_here_after(pool: &sqlx::SqlitePool, after: i64, limit: u32) -> sqlx::Result<Vec<Self>> {
+        sqlx::query_as!(Self, r#"
+            SELECT
+                id as "id!: _",
+                session as "session: _",
+                persistent_id as "persistent_id: _",
+                started_at as "started_at: _",
+                recorded_at as "recorded_at: _",
+                canonical as "canonical: _",
+                origin,
+                origin_id,
+                superseded as "superseded: _",
+                track,
+                excluded
+            FROM listens WHERE origin IS NULL AND id > ? ORDER BY id LIMIT ?
+        "#, after, limit).fetch_all(pool).await
+    }
+
+    /// Listens recorded on this machine which started at or after the given time, oldest first.
+    /// These are what can be [replayed](crate::replay) by hand.
+    pub async fn get_recorded_here_since(pool: &sqlx::SqlitePool, since: chrono::DateTime<chrono::Utc>) -> sqlx::Result<Vec<Self>> {
+        let since = MillisecondTimestamp(since);
+        sqlx::query_as!(Self, r#"
+            SELECT
+                id as "id!: _",
+                session as "session: _",
+                persistent_id as "persistent_id: _",
+                started_at as "started_at: _",
+                recorded_at as "recorded_at: _",
+                canonical as "canonical: _",
+                origin,
+                origin_id,
+                superseded as "superseded: _",
+                track,
+                excluded
+            FROM listens WHERE origin IS NULL AND started_at >= ? ORDER BY started_at
+        "#, since).fetch_all(pool).await
+    }
+
+    /// Listens from any machine but the one given, in the order they were stored, starting after the one with the given ID.
+    ///
+    /// Listens recorded on this machine are considered to be from `here`.
+    pub async fn get_not_from_after(pool: &sqlx::SqlitePool, here: &str, machine: &str, after: i64, limit: u32) -> sqlx::Result<Vec<Self>> {
+        sqlx::query_as!(Self, r#"
+            SELECT
+                id as "id!: _",
+                session as "session: _",
+                persistent_id as "persistent_id: _",
+                started_at as "started_at: _",
+                recorded_at as "recorded_at: _",
+                canonical as "canonical: _",
+                origin,
+                origin_id,
+                superseded as "superseded: _",
+                track,
+                excluded
+            FROM listens WHERE COALESCE(origin, ?) != ? AND id > ? ORDER BY id LIMIT ?
+        "#, here, machine, after, limit).fetch_all(pool).await
+    }
+
+    /// The dispatched track, with the backends it's excluded from restored; `None` if it wasn't kept.
+    pub fn track(&self) -> Option<serde_json::Result<crate::DispatchableTrack>> {
+        let json = self.track.as_deref()?;
+        Some(serde_json::from_str::<crate::DispatchableTrack>(json).map(|mut track| {
+            track.excluded_backends = crate::subscribers::BackendIdentitySet::from_keys(self.excluded.as_deref().unwrap_or_default());
+            track
+        }))
+    }
+
+    pub async fn segments(&self, pool: &sqlx::SqlitePool) -> sqlx::Result<Vec<ListenSegment>> {
+        sqlx::query_as!(ListenSegment, r#"
+            SELECT
+                id as "id: _",
+                listen as "listen: _",
+                started_at as "started_at: _",
+                duration,
+                started_at_position,
+                expected_end_position,
+                actual_end_position
+            FROM listen_segments WHERE listen = ? ORDER BY started_at_position
+        "#, self.id).fetch_all(pool).await
+    }
+}
+
+/// A contiguous stretch of a [`Listen`].
+#[derive(Debug, sqlx::FromRow)]
+pub struct ListenSegment {
+    id: Key<Self>,
+    pub listen: Key<Listen>,
+    pub started_at: MillisecondTimestamp,
+    /// In milliseconds.
+    pub duration: i64,
+    /// In seconds.
+    pub started_at_position: f64,
+    /// Where the segment would've ended were playback uninterrupted, in seconds.
+    pub expected_end_position: f64,
+    /// Where the player reported being when the segment ended, in seconds, if it was known.
+    pub actual_end_position: Option<f64>,
+}
+impl FromKey for ListenSegment {
+    const TABLE_NAME: &'static str = "listen_segments";
+}
+
+/// A song, which any number of copies with differing persistent IDs can be [collapsed](super::canonical) into.
+#[derive(Debug, sqlx::FromRow)]
+pub struct CanonicalTrack {
+    id: Key<Self>,
+    /// Normalized; empty if unknown.
+    pub artist: String,
+    /// Normalized.
+    pub title: String,
+    /// In milliseconds.
+    pub duration: Option<i64>,
 }
+impl FromKey for CanonicalTrack {
+    const TABLE_NAME: &'static str = "canonical_tracks";
+}
+impl CanonicalTrack {
+    pub const fn id(&self) -> Key<Self> {
+        self.id
+    }
+
+    /// Returns the canonical track that the copy with the given persistent ID belongs to,
+    /// creating one if no song matching the key has been seen before.
+    ///
+    /// A persistent ID stays with the first canonical track it was resolved to, even if its metadata later changes.
+    pub async fn resolve(
+        pool: &sqlx::SqlitePool,
+        persistent_id: StoredPersistentId,
+        key: &super::canonical::CanonicalKey,
+    ) -> sqlx::Result<Key<Self>> {
+        let mut transaction = pool.begin().await?;
+
+        let existing = sqlx::query_scalar!(r#"SELECT canonical as "canonical: Key<CanonicalTrack>" FROM canonical_track_members WHERE persistent_id = ?"#, persistent_id)
+            .fetch_optional(&mut *transaction).await?;
+        if let Some(canonical) = existing {
+            return Ok(canonical)
+        }
 
+        let canonical = Self::find_or_insert(&mut transaction, key).await?;
+
+        sqlx::query!("INSERT INTO canonical_track_members (persistent_id, canonical) VALUES (?, ?)", persistent_id, canonical)
+            .execute(&mut *transaction).await?;
+
+        transaction.commit().await?;
+        Ok(canonical)
+    }
+
+    /// Returns the canonical track closest to matching the key, creating one if none do.
+    async fn find_or_insert(
+        connection: &mut sqlx::SqliteConnection,
+        key: &super::canonical::CanonicalKey,
+    ) -> sqlx::Result<Key<Self>> {
+        let tolerance = i64::try_from(super::canonical::DURATION_TOLERANCE.as_millis()).expect("tolerance fits");
+        let duration = key.duration_millis();
+        let matching = sqlx::query_scalar!(r#"
+            SELECT id as "id!: Key<CanonicalTrack>" FROM canonical_tracks
+            WHERE artist = ?1 AND title = ?2 AND ((duration IS NULL AND ?3 IS NULL) OR abs(duration - ?3) <= ?4)
+            ORDER BY abs(duration - ?3) LIMIT 1
+        "#,
+            key.artist,
+            key.title,
+            duration,
+            tolerance,
+        ).fetch_optional(&mut *connection).await?;
+
+        match matching {
+            Some(canonical) => Ok(canonical),
+            None => sqlx::query_scalar!(
+                r#"INSERT INTO canonical_tracks (artist, title, duration) VALUES (?, ?, ?) RETURNING id as "id: Key<CanonicalTrack>""#,
+                key.artist,
+                key.title,
+                duration,
+            ).fetch_one(&mut *connection).await
+        }
+    }
+
+    /// The persistent IDs of every known copy of this track.
+    pub async fn members(&self, pool: &sqlx::SqlitePool) -> sqlx::Result<Vec<StoredPersistentId>> {
+        sqlx::query_scalar!(r#"SELECT persistent_id as "persistent_id: StoredPersistentId" FROM canonical_track_members WHERE canonical = ?"#, self.id)
+            .fetch_all(pool).await
+    }
+
+    /// The number of times any copy of this track has been played;
+    /// those recorded by the program, plus those [imported](LibraryPlayCount) from the library.
+    ///
+    /// The library's count of a copy already includes the listens of it which started before it was imported, so only those after are added.
+    /// Superseded listens aren't counted, so a listen recorded by multiple [synced](crate::sync) machines counts once.
+    pub async fn play_count(&self, pool: &sqlx::SqlitePool) -> sqlx::Result<i64> {
+        sqlx::query_scalar!(r#"
+            SELECT
+                (SELECT COUNT(*) FROM listens WHERE canonical = ?1 AND NOT superseded AND started_at > COALESCE(
+                    (SELECT imported_at FROM library_play_counts WHERE library_play_counts.persistent_id = listens.persistent_id),
+                    0
+                )) +
+                (SELECT COALESCE(SUM(play_count), 0) FROM library_play_counts
+                    WHERE persistent_id IN (SELECT persistent_id FROM canonical_track_members WHERE canonical = ?1))
+                as "play_count!: i64"
+        "#, self.id).fetch_one(pool).await
+    }
+}
+
+/// The play statistics of a track as the music library knew them when they were imported,
+/// serving as a baseline for the history recorded by the program itself.
+#[derive(Debug, sqlx::FromRow)]
+pub struct LibraryPlayCount {
+    id: Key<Self>,
+    pub persistent_id: StoredPersistentId,
+    pub play_count: i64,
+    pub last_played_at: Option<MillisecondTimestamp>,
+    pub imported_at: MillisecondTimestamp,
+}
+impl FromKey for LibraryPlayCount {
+    const TABLE_NAME: &'static str = "library_play_counts";
+}
+impl LibraryPlayCount {
+    /// Stores the play statistics of each track, replacing those of any track that was previously imported.
+    ///
+    /// Returns the number of tracks stored.
+    pub async fn import(
+        pool: &sqlx::SqlitePool,
+        tracks: impl IntoIterator<Item = (StoredPersistentId, u32, Option<chrono::DateTime<chrono::Utc>>)>,
+    ) -> sqlx::Result<u64> {
+        let mut transaction = pool.begin().await?;
+        let mut imported = 0;
+        for (persistent_id, play_count, last_played_at) in tracks {
+            let play_count = i64::from(play_count);
+            let last_played_at = last_played_at.map(|at| at.timestamp_millis());
+            imported += sqlx::query!(r"
+                INSERT INTO library_play_counts (
+                    persistent_id,
+                    play_count,
+                    last_played_at
+                ) VALUES (?, ?, ?)
+                ON CONFLICT(persistent_id) DO UPDATE SET
+                    play_count = excluded.play_count,
+                    last_played_at = excluded.last_played_at,
+                    imported_at = excluded.imported_at
+            ",
+                persistent_id,
+                play_count,
+                last_played_at,
+            ).execute(&mut *transaction).await?
+                .rows_affected();
+        }
+        transaction.commit().await?;
+        Ok(imported)
+    }
+
+    pub async fn get_by_persistent_id(
+        pool: &sqlx::SqlitePool,
+        persistent_id: StoredPersistentId,
+    ) -> sqlx::Result<Option<Self>> {
+        sqlx::query_as!(Self, r#"
+            SELECT
+                id as "id!: _",
+                persistent_id as "persistent_id: _",
+                play_count,
+                last_played_at as "last_played_at: _",
+                imported_at as "imported_at: _"
+            FROM library_play_counts WHERE persistent_id = ?
+        "#, persistent_id).fetch_optional(pool).await
+    }
+}
+
+/// Where playback of a podcast episode or audiobook was left off, which is recorded in place of a [`Listen`].
+#[derive(Debug, sqlx::FromRow)]
+pub struct MediaProgress {
+    id: Key<Self>,
+    pub session: Key<Session>,
+    pub persistent_id: StoredPersistentId,
+    pub media_kind: player_events::MediaKind,
+    pub title: String,
+    /// The show of a podcast episode.
+    pub album: Option<String>,
+    /// In seconds.
+    pub position: f64,
+    /// In seconds.
+    pub duration: Option<f64>,
+    pub recorded_at: MillisecondTimestamp,
+}
+impl FromKey for MediaProgress {
+    const TABLE_NAME: &'static str = "media_progress";
+}
+impl MediaProgress {
+    pub async fn new(
+        pool: &sqlx::SqlitePool,
+        session: Key<Session>,
+        track: &crate::DispatchableTrack,
+        position: f32,
+    ) -> sqlx::Result<Self> {
+        let position = f64::from(position);
+        let duration = track.duration.map(|duration| duration.as_secs_f64());
+        sqlx::query_as!(Self, r#"
+            INSERT INTO media_progress (
+                session,
+                persistent_id,
+                media_kind,
+                title,
+                album,
+                position,
+                duration
+            ) VALUES (?, ?, ?, ?, ?, ?, ?) RETURNING
+                id as "id!: _",
+                session as "session: _",
+                persistent_id as "persistent_id: _",
+                media_kind as "media_kind: _",
+                title,
+                album,
+                position,
+                duration,
+                recorded_at as "recorded_at: _"
+        "#,
+            session,
+            track.persistent_id,
+            track.media_kind,
+            track.name,
+            track.album,
+            position,
+            duration,
+        ).fetch_one(pool).await
+    }
+
+    /// The latest progress of each podcast episode or audiobook, most recently recorded first.
+    pub async fn get_latest(pool: &sqlx::SqlitePool, limit: u32) -> sqlx::Result<Vec<Self>> {
+        sqlx::query_as!(Self, r#"
+            SELECT
+                id as "id!: _",
+                session as "session: _",
+                persistent_id as "persistent_id: _",
+                media_kind as "media_kind: _",
+                title,
+                album,
+                position,
+                duration,
+                recorded_at as "recorded_at: _"
+            FROM media_progress
+            WHERE id IN (SELECT MAX(id) FROM media_progress GROUP BY persistent_id)
+            ORDER BY recorded_at DESC LIMIT ?
+        "#, limit).fetch_all(pool).await
+    }
+}
+
+/// A listen recorded before being dispatched, so that it isn't lost if the program stops before every backend is done with it.
+/// Those left uncommitted are [replayed](crate::subscribers::journal) on startup.
+#[derive(Debug, sqlx::FromRow)]
+pub struct JournalEntry {
+    id: Key<Self>,
+    /// The dispatched track, as JSON.
+    track: String,
+    /// The [keys](crate::subscribers::BackendIdentity::get_key) of the backends the track is excluded from, separated by spaces.
+    excluded: String,
+    pub player_version: String,
+    pub started_at: MillisecondTimestamp,
+    /// How much of the track was listened to, in milliseconds.
+    pub listened: i64,
+    pub recorded_at: MillisecondTimestamp,
+    pub committed_at: Option<MillisecondTimestamp>,
+}
+impl FromKey for JournalEntry {
+    const TABLE_NAME: &'static str = "event_journal";
+}
+impl JournalEntry {
+    pub async fn append(
+        pool: &sqlx::SqlitePool,
+        track: &crate::DispatchableTrack,
+        player_version: &str,
+        started_at: chrono::DateTime<chrono::Utc>,
+        listened: chrono::TimeDelta,
+    ) -> sqlx::Result<Key<Self>> {
+        let json = serde_json::to_string(track).map_err(|error| sqlx::Error::Encode(Box::new(error)))?;
+        let excluded = track.excluded_backends.to_keys();
+        let started_at = MillisecondTimestamp(started_at);
+        let listened = listened.num_milliseconds();
+        sqlx::query_scalar!(r#"
+            INSERT INTO event_journal (
+                track,
+                excluded,
+                player_version,
+                started_at,
+                listened
+            ) VALUES (?, ?, ?, ?, ?) RETURNING id as "id: Key<JournalEntry>"
+        "#,
+            json,
+            excluded,
+            player_version,
+            started_at,
+            listened,
+        ).fetch_one(pool).await
+    }
+
+    /// Marks the entry as having been accepted by every backend, unless it already was.
+    pub async fn commit(pool: &sqlx::SqlitePool, id: Key<Self>) -> sqlx::Result<()> {
+        let committed_at = MillisecondTimestamp(chrono::Utc::now());
+        sqlx::query!("UPDATE event_journal SET committed_at = ? WHERE id = ? AND committed_at IS NULL", committed_at, id)
+            .execute(pool).await?;
+        Ok(())
+    }
+
+    /// The entries which were never committed, oldest first.
+    pub async fn get_uncommitted(pool: &sqlx::SqlitePool) -> sqlx::Result<Vec<Self>> {
+        sqlx::query_as!(Self, r#"
+            SELECT
+                id as "id!: _",
+                track,
+                excluded,
+                player_version,
+                started_at as "started_at: _",
+                listened,
+                recorded_at as "recorded_at: _",
+                committed_at as "committed_at: _"
+            FROM event_journal WHERE committed_at IS NULL ORDER BY id
+        "#).fetch_all(pool).await
+    }
+
+    /// Removes the entries which were committed before the given time, which are no longer needed.
+    ///
+    /// Returns the number of entries removed.
+    pub async fn prune(pool: &sqlx::SqlitePool, committed_before: chrono::DateTime<chrono::Utc>) -> sqlx::Result<u64> {
+        let committed_before = MillisecondTimestamp(committed_before);
+        sqlx::query!("DELETE FROM event_journal WHERE committed_at IS NOT NULL AND committed_at < ?", committed_before)
+            .execute(pool).await
+            .map(|result| result.rows_affected())
+    }
+
+    pub const fn id(&self) -> Key<Self> {
+        self.id
+    }
+
+    /// The dispatched track, with the backends it's excluded from restored.
+    pub fn track(&self) -> serde_json::Result<crate::DispatchableTrack> {
+        let mut track = serde_json::from_str::<crate::DispatchableTrack>(&self.track)?;
+        track.excluded_backends = crate::subscribers::BackendIdentitySet::from_keys(&self.excluded);
+        Ok(track)
+    }
+
+    pub const fn listened(&self) -> chrono::TimeDelta {
+        chrono::TimeDelta::milliseconds(self.listened)
+    }
+}
+
+/// How far along syncing with a [hub](crate::sync) is.
+#[derive(Debug, Default, sqlx::FromRow)]
+pub struct SyncCursor {
+    /// The ID of the latest listen recorded on this machine that the hub has received.
+    pub pushed: i64,
+    /// The ID, as it is on the hub, of the latest listen received from it.
+    pub pulled: i64,
+}
+impl SyncCursor {
+    pub async fn get(pool: &sqlx::SqlitePool, hub: &str) -> sqlx::Result<Self> {
+        sqlx::query_as!(Self, "SELECT pushed, pulled FROM sync_cursors WHERE hub = ?", hub)
+            .fetch_optional(pool).await
+            .map(Option::unwrap_or_default)
+    }
+
+    pub async fn set_pushed(pool: &sqlx::SqlitePool, hub: &str, pushed: i64) -> sqlx::Result<()> {
+        sqlx::query!("INSERT INTO sync_cursors (hub, pushed) VALUES (?, ?) ON CONFLICT(hub) DO UPDATE SET pushed = excluded.pushed", hub, pushed)
+            .execute(pool).await?;
+        Ok(())
+    }
+
+    pub async fn set_pulled(pool: &sqlx::SqlitePool, hub: &str, pulled: i64) -> sqlx::Result<()> {
+        sqlx::query!("INSERT INTO sync_cursors (hub, pulled) VALUES (?, ?) ON CONFLICT(hub) DO UPDATE SET pulled = excluded.pulled", hub, pulled)
+            .execute(pool).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::debug::*;
+    use crate::sync::{SyncedListen, SyncedSegment, SyncedTrack};
+
+    fn synced(origin: &str, id: i64, started_at: i64, seconds: u32) -> SyncedListen {
+        SyncedListen {
+            origin: origin.to_owned(),
+            id,
+            persistent_id: StoredPersistentId::new(0xABCD),
+            started_at,
+            track: Some(SyncedTrack { artist: "the age of rockets".to_owned(), title: "pictures of space".to_owned(), duration: Some(241_200) }),
+            segments: vec![SyncedSegment {
+                started_at,
+                duration: i64::from(seconds) * 1000,
+                started_at_position: 0.,
+                expected_end_position: f64::from(seconds),
+                actual_end_position: None,
+            }],
+        }
+    }
+
+    async fn superseded(pool: &sqlx::SqlitePool) -> Vec<(Option<i64>, bool)> {
+        sqlx::query!(r#"SELECT origin_id, superseded as "superseded: bool" FROM listens ORDER BY origin_id"#)
+            .fetch_all(pool).await
+            .expect("failed to query listens")
+            .into_iter()
+            .map(|row| (row.origin_id, row.superseded))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn synced_overlapping_listens() {
+        mk_test_db!("synced-overlapping-listens", pool);
+        seed_empty(&pool, true).await;
+
+        assert!(Listen::insert_synced(&pool, &synced("laptop", 1, 1_000_000, 60)).await.unwrap());
+        assert!(!Listen::insert_synced(&pool, &synced("laptop", 1, 1_000_000, 60)).await.unwrap(), "duplicates aren't stored");
+        assert!(Listen::insert_synced(&pool, &synced("desktop", 2, 1_030_000, 200)).await.unwrap());
+        assert!(Listen::insert_synced(&pool, &synced("desktop", 3, 5_000_000, 10)).await.unwrap());
+
+        assert_eq!(superseded(&pool).await, [(Some(1), true), (Some(2), false), (Some(3), false)]);
+    }
+
+    #[tokio::test]
+    async fn play_count_excludes_listens_before_import() {
+        mk_test_db!("play-count-excludes-listens-before-import", pool);
+        seed_empty(&pool, true).await;
+
+        let now = chrono::Utc::now().timestamp_millis();
+        assert!(Listen::insert_synced(&pool, &synced("laptop", 1, 1_000_000, 60)).await.unwrap());
+        LibraryPlayCount::import(&pool, [(StoredPersistentId::new(0xABCD), 7, None)]).await.unwrap();
+        assert!(Listen::insert_synced(&pool, &synced("laptop", 2, now + 60_000, 60)).await.unwrap());
+        assert!(Listen::insert_synced(&pool, &synced("laptop", 3, now + 600_000, 60)).await.unwrap());
+
+        let track = synced("laptop", 1, 1_000_000, 60).track.unwrap();
+        let canonical = CanonicalTrack::resolve(&pool, StoredPersistentId::new(0xABCD), &(&track).into()).await.unwrap();
+        let canonical = CanonicalTrack::get_in_pool(canonical, &pool).await.unwrap();
+        assert_eq!(canonical.play_count(&pool).await.unwrap(), 7 + 2, "the listen before the import is already within the library's count");
+    }
+
+    #[tokio::test]
+    async fn uncensored_title_corrections() {
+        mk_test_db!("uncensored-title-corrections", pool);
+        seed_empty(&pool, true).await;
+
+        let id = StoredPersistentId::new(0xABCD);
+        CachedUncensoredTitle::new(&pool, id, Some("Wrong")).await.unwrap();
+        CachedUncensoredTitle::set(&pool, id, Some("Right")).await.unwrap();
+        let cached = CachedUncensoredTitle::get_by_persistent_id(&pool, id).await.unwrap().unwrap();
+        assert_eq!(cached.uncensored.as_deref(), Some("Right"));
+
+        assert!(CachedUncensoredTitle::clear(&pool, id).await.unwrap());
+        assert!(!CachedUncensoredTitle::clear(&pool, id).await.unwrap(), "nothing should be left to clear");
+        assert!(CachedUncensoredTitle::get_by_persistent_id(&pool, id).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn journal_commits() {
+        mk_test_db!("journal-commits", pool);
+        seed_empty(&pool, true).await;
+
+        let track = crate::DispatchableTrack::from(player_events::Track {
+            name: "pictures of space".to_owned(),
+            album: None,
+            album_artist: None,
+            artist: Some("the age of rockets".to_owned()),
+            persistent_id: StoredPersistentId::new(0xABCD),
+            duration: Some(core::time::Duration::from_secs(241)),
+            media_kind: player_events::MediaKind::Song,
+            track_number: None,
+            apple_music_url: None,
+            genre: None,
+            composer: None,
+            work: None,
+            movement: None,
+        });
+        let started_at = chrono::DateTime::<chrono::Utc>::from_timestamp_millis(1_000_000).unwrap();
+        let first = JournalEntry::append(&pool, &track, "1.5", started_at, chrono::TimeDelta::seconds(200)).await.unwrap();
+        JournalEntry::append(&pool, &track, "1.5", started_at, chrono::TimeDelta::seconds(30)).await.unwrap();
+        JournalEntry::commit(&pool, first).await.unwrap();
+
+        let uncommitted = JournalEntry::get_uncommitted(&pool).await.unwrap();
+        assert_eq!(uncommitted.len(), 1, "only the uncommitted entry should be left to replay");
+        assert_eq!(uncommitted[0].listened(), chrono::TimeDelta::seconds(30));
+        assert_eq!(uncommitted[0].started_at, started_at);
+        assert_eq!(uncommitted[0].track().unwrap().name, track.name);
+
+        assert_eq!(JournalEntry::prune(&pool, chrono::Utc::now() - chrono::TimeDelta::days(1)).await.unwrap(), 0, "recently committed entries are kept");
+        assert_eq!(JournalEntry::prune(&pool, chrono::Utc::now() + chrono::TimeDelta::seconds(1)).await.unwrap(), 1);
+        assert_eq!(JournalEntry::get_uncommitted(&pool).await.unwrap().len(), 1, "uncommitted entries aren't pruned");
+    }
+
+    #[tokio::test]
+    async fn listens_keep_their_track() {
+        mk_test_db!("listens-keep-their-track", pool);
+        seed_empty(&pool, true).await;
+
+        let session = sqlx::query_scalar!(r#"
+            INSERT INTO sessions (ver_crate, ver_player, ver_os, migration_id) VALUES ('0.0.0', '1.5', '15.0', 13)
+            RETURNING id as "id: Key<Session>"
+        "#).fetch_one(&pool).await.unwrap();
+        let mut track = crate::DispatchableTrack::from(player_events::Track {
+            name: "pictures of space".to_owned(),
+            album: None,
+            album_artist: None,
+            artist: Some("the age of rockets".to_owned()),
+            persistent_id: StoredPersistentId::new(0xABCD),
+            duration: Some(core::time::Duration::from_secs(241)),
+            media_kind: player_events::MediaKind::Song,
+            track_number: None,
+            apple_music_url: None,
+            genre: None,
+            composer: None,
+            work: None,
+            movement: None,
+        });
+        track.excluded_backends.insert(crate::subscribers::BackendIdentity::Mock);
+        let started_at = chrono::DateTime::<chrono::Utc>::from_timestamp_millis(1_000_000).unwrap();
+        let mut listened = crate::listened::Listened::new();
+        listened.contiguous.push(crate::listened::ListenedChunk::new(0., started_at, chrono::TimeDelta::seconds(200)));
+        Listen::new(&pool, session, &track, None, &listened).await.unwrap().unwrap();
+        assert!(Listen::insert_synced(&pool, &synced("elsewhere", 1, 2_000_000, 60)).await.unwrap());
+
+        let listens = Listen::get_recorded_here_since(&pool, started_at).await.unwrap();
+        assert_eq!(listens.len(), 1, "synced listens aren't replayed from here");
+        let kept = listens[0].track().expect("the track is kept").unwrap();
+        assert_eq!(kept.name, track.name);
+        assert!(kept.excluded_backends.contains(crate::subscribers::BackendIdentity::Mock));
+        assert!(Listen::get_recorded_here_since(&pool, started_at + chrono::TimeDelta::milliseconds(1)).await.unwrap().is_empty());
+    }
+}
@@ -4,14 +4,17 @@ use tokio::sync::Mutex;
 pub mod migrations;
 pub mod types;
 pub mod entities;
+pub mod canonical;
 
 #[cfg(any(test, debug_assertions))]
-mod debug;
+pub mod debug;
 
+#[cfg_attr(test, allow(dead_code, reason = "tests use a database of their own"))]
 pub static DB_PATH: LazyLock<std::path::PathBuf> = LazyLock::new(|| {
     crate::util::APPLICATION_SUPPORT_FOLDER.join("sqlite.db")
 });
 
+#[cfg(not(test))]
 pub static DB_POOL: GlobalPool = GlobalPool::new(|| {
     use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
     let connect = SqliteConnectOptions::new()
@@ -21,6 +24,21 @@ pub static DB_POOL: GlobalPool = GlobalPool::new(|| {
     GlobalPoolOptions { connect, pool }
 });
 
+/// Tests which go through the global pool (such as by running the polling loop) get a database of their own.
+#[cfg(test)]
+pub static DB_POOL: GlobalPool = GlobalPool::new(|| {
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    static FRESH: std::sync::Once = std::sync::Once::new();
+    let path = std::env::temp_dir().join(format!("am-osx-status-test-{}.db", std::process::id()));
+    // Left behind by an earlier process with the same pid; only once, since the pool is re-opened after every migration.
+    FRESH.call_once(|| { let _ = std::fs::remove_file(&path); });
+    let connect = SqliteConnectOptions::new()
+        .filename(path)
+        .create_if_missing(true);
+    let pool = SqlitePoolOptions::new().max_connections(3);
+    GlobalPoolOptions { connect, pool }
+});
+
 
 pub struct GlobalPoolOptions {
     pub connect: sqlx::sqlite::SqliteConnectOptions,
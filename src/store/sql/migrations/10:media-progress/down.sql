@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS media_progress_by_persistent_id;
+DROP TABLE IF EXISTS media_progress;
+VACUUM;
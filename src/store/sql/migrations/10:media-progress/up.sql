@@ -0,0 +1,14 @@
+-- where podcast episodes and audiobooks were left off; recorded in place of listens
+CREATE TABLE IF NOT EXISTS media_progress (
+    id             INTEGER PRIMARY KEY AUTOINCREMENT,
+    session        INTEGER NOT NULL,
+    persistent_id  INTEGER NOT NULL,
+    media_kind     TEXT NOT NULL,
+    title          TEXT NOT NULL,
+    album          TEXT,             -- the show of a podcast episode. null if unknown
+    position       REAL NOT NULL,    -- in seconds
+    duration       REAL,             -- in seconds. null if unknown
+    recorded_at    INTEGER NOT NULL DEFAULT(unixepoch('subsec') * 1000),
+    FOREIGN KEY(session) REFERENCES sessions(id)
+) STRICT;
+CREATE INDEX IF NOT EXISTS media_progress_by_persistent_id ON media_progress(persistent_id);
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS event_journal_uncommitted;
+DROP TABLE IF EXISTS event_journal;
+VACUUM;
@@ -0,0 +1,12 @@
+-- listens which were about to be dispatched, so that they can be replayed if the program stops before the dispatch finishes
+CREATE TABLE IF NOT EXISTS event_journal (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    track           TEXT NOT NULL,    -- the dispatched track, as JSON
+    excluded        TEXT NOT NULL,    -- the configuration keys of the backends the track is excluded from, separated by spaces
+    player_version  TEXT NOT NULL,
+    started_at      INTEGER NOT NULL, -- unix epoch, milliseconds
+    listened        INTEGER NOT NULL, -- milliseconds
+    recorded_at     INTEGER NOT NULL DEFAULT(unixepoch('subsec') * 1000),
+    committed_at    INTEGER           -- null until every backend is done with it
+) STRICT;
+CREATE INDEX IF NOT EXISTS event_journal_uncommitted ON event_journal(committed_at) WHERE committed_at IS NULL;
@@ -0,0 +1,2 @@
+-- how many dispatches each backend was given over the session and how many failed, as JSON: {"<backend key>": {"dispatched": n, "failed": n}}
+ALTER TABLE sessions ADD COLUMN dispatches TEXT NOT NULL DEFAULT '{}';
@@ -0,0 +1,3 @@
+ALTER TABLE listens DROP COLUMN track;
+ALTER TABLE listens DROP COLUMN excluded;
+VACUUM;
@@ -0,0 +1,3 @@
+-- what's needed to replay a listen to a backend by hand; null if it was synced from another machine or recorded before this
+ALTER TABLE listens ADD COLUMN track TEXT;    -- the dispatched track, as JSON
+ALTER TABLE listens ADD COLUMN excluded TEXT; -- the keys of the backends the track is excluded from, separated by spaces
@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS listen_segments;
+DROP TABLE IF EXISTS listens;
+VACUUM;
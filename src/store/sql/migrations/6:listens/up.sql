@@ -0,0 +1,19 @@
+CREATE TABLE IF NOT EXISTS listens (
+    id             INTEGER PRIMARY KEY AUTOINCREMENT,
+    session        INTEGER NOT NULL,
+    persistent_id  INTEGER NOT NULL,
+    started_at     INTEGER NOT NULL, -- unix epoch, milliseconds; the start of the earliest segment
+    recorded_at    INTEGER NOT NULL DEFAULT(unixepoch('subsec') * 1000),
+    FOREIGN KEY(session) REFERENCES sessions(id)
+) STRICT;
+
+CREATE TABLE IF NOT EXISTS listen_segments (
+    id                     INTEGER PRIMARY KEY AUTOINCREMENT,
+    listen                 INTEGER NOT NULL,
+    started_at             INTEGER NOT NULL, -- unix epoch, milliseconds
+    duration               INTEGER NOT NULL, -- milliseconds
+    started_at_position    REAL    NOT NULL, -- in seconds
+    expected_end_position  REAL    NOT NULL, -- in seconds; where the segment should've ended were playback uninterrupted
+      actual_end_position  REAL,             -- in seconds; where the player reported being when the segment ended. null if unknown
+    FOREIGN KEY(listen) REFERENCES listens(id) ON DELETE CASCADE
+) STRICT;
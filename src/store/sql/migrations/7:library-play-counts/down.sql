@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS library_play_counts;
+VACUUM;
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS library_play_counts (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    persistent_id   INTEGER NOT NULL UNIQUE,
+    play_count      INTEGER NOT NULL,
+    last_played_at  INTEGER, -- unix epoch, milliseconds. null if the library doesn't know
+    imported_at     INTEGER NOT NULL DEFAULT(unixepoch('subsec') * 1000)
+) STRICT;
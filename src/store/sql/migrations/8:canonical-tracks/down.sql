@@ -0,0 +1,4 @@
+ALTER TABLE listens DROP COLUMN canonical;
+DROP TABLE IF EXISTS canonical_track_members;
+DROP TABLE IF EXISTS canonical_tracks;
+VACUUM;
@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS canonical_tracks (
+    id        INTEGER PRIMARY KEY AUTOINCREMENT,
+    artist    TEXT NOT NULL, -- normalized; empty if unknown
+    title     TEXT NOT NULL, -- normalized
+    duration  INTEGER        -- milliseconds. null if unknown
+) STRICT;
+CREATE INDEX IF NOT EXISTS canonical_tracks_by_name ON canonical_tracks(artist, title);
+
+-- every persistent id which is known to be a copy of a canonical track
+CREATE TABLE IF NOT EXISTS canonical_track_members (
+    persistent_id  INTEGER PRIMARY KEY,
+    canonical      INTEGER NOT NULL,
+    FOREIGN KEY(canonical) REFERENCES canonical_tracks(id) ON DELETE CASCADE
+) STRICT;
+
+ALTER TABLE listens ADD COLUMN canonical INTEGER REFERENCES canonical_tracks(id); -- null if it wasn't resolved
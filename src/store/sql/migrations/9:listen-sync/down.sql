@@ -0,0 +1,28 @@
+PRAGMA foreign_keys = OFF;
+BEGIN TRANSACTION;
+
+DROP TABLE IF EXISTS sync_cursors;
+DROP INDEX IF EXISTS listens_by_canonical;
+
+DELETE FROM listen_segments WHERE listen IN (SELECT id FROM listens WHERE session IS NULL);
+DELETE FROM listens WHERE session IS NULL;
+
+CREATE TABLE listens_old (
+    id             INTEGER PRIMARY KEY AUTOINCREMENT,
+    session        INTEGER NOT NULL,
+    persistent_id  INTEGER NOT NULL,
+    started_at     INTEGER NOT NULL,
+    recorded_at    INTEGER NOT NULL DEFAULT(unixepoch('subsec') * 1000),
+    canonical      INTEGER REFERENCES canonical_tracks(id),
+    FOREIGN KEY(session) REFERENCES sessions(id)
+) STRICT;
+
+INSERT INTO listens_old (id, session, persistent_id, started_at, recorded_at, canonical)
+    SELECT id, session, persistent_id, started_at, recorded_at, canonical
+    FROM listens;
+
+DROP TABLE listens;
+ALTER TABLE listens_old RENAME TO listens;
+
+COMMIT;
+PRAGMA foreign_keys = ON;
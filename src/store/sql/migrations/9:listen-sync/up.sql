@@ -0,0 +1,37 @@
+-- listens can now come from other machines, which don't share our sessions
+PRAGMA foreign_keys = OFF;
+BEGIN TRANSACTION;
+
+CREATE TABLE listens_new (
+    id             INTEGER PRIMARY KEY AUTOINCREMENT,
+    session        INTEGER,          -- null if the listen was synced from another machine
+    persistent_id  INTEGER NOT NULL, -- as it is on the machine the listen was recorded on
+    started_at     INTEGER NOT NULL, -- unix epoch, milliseconds; the start of the earliest segment
+    recorded_at    INTEGER NOT NULL DEFAULT(unixepoch('subsec') * 1000),
+    canonical      INTEGER,          -- null if it wasn't resolved
+    origin         TEXT,             -- the name of the machine the listen was recorded on. null if it was recorded on this one
+    origin_id      INTEGER,          -- the id of the listen on the machine it was recorded on. null if it was recorded on this one
+    superseded     INTEGER NOT NULL DEFAULT(0), -- boolean; whether an overlapping listen of the same track was kept instead of this one
+    FOREIGN KEY(session) REFERENCES sessions(id),
+    FOREIGN KEY(canonical) REFERENCES canonical_tracks(id),
+    UNIQUE(origin, origin_id)
+) STRICT;
+
+INSERT INTO listens_new (id, session, persistent_id, started_at, recorded_at, canonical)
+    SELECT id, session, persistent_id, started_at, recorded_at, canonical
+    FROM listens;
+
+DROP TABLE listens;
+ALTER TABLE listens_new RENAME TO listens;
+CREATE INDEX IF NOT EXISTS listens_by_canonical ON listens(canonical);
+
+-- how far along syncing with each hub is
+CREATE TABLE IF NOT EXISTS sync_cursors (
+    hub     TEXT PRIMARY KEY, -- the url of the hub
+    pushed  INTEGER NOT NULL DEFAULT(0), -- the id of the latest listen of ours that the hub has received
+    pulled  INTEGER NOT NULL DEFAULT(0)  -- the id (on the hub) of the latest listen we've received from it
+) STRICT;
+
+COMMIT;
+PRAGMA foreign_keys = ON;
+PRAGMA foreign_key_check;
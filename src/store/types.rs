@@ -77,88 +77,8 @@ impl From<i64> for MillisecondTimestamp {
     }
 }
 
-/// SQLite doesn't support 8-bit unsigned integers, so use an i64 as an intermediary representation.
-#[derive(Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
-pub struct StoredPersistentId(i64);
-impl StoredPersistentId {
-    pub const fn new(id: u64) -> Self {
-        Self(u64::cast_signed(id))
-    }
-
-    pub fn from_hex(value: &str) -> Result<Self, core::num::ParseIntError> {
-        Ok(Self::new(u64::from_str_radix(value, 16)?))       
-    }
-
-    pub fn to_hex_upper(self) -> String {
-        format!("{:X}", self.0)
-    }
-    pub fn to_hex_lower(self) -> String {
-        format!("{:x}", self.0)
-    }
-
-    pub const fn get(self) -> u64 {
-        i64::cast_unsigned(self.0)
-    }
-
-    pub const fn signed(self) -> i64 {
-        self.0
-    }
-}
-impl From<StoredPersistentId> for u64 {
-    fn from(val: StoredPersistentId) -> Self {
-        val.get()
-    }
-}
-impl From<u64> for StoredPersistentId {
-    fn from(value: u64) -> Self {
-        Self::new(value)
-    }
-}
-impl sqlx::Encode<'_, sqlx::Sqlite> for StoredPersistentId {
-    fn encode_by_ref(
-            &self,
-            buf: &mut <sqlx::Sqlite as sqlx::Database>::ArgumentBuffer<'_>,
-        ) -> Result<sqlx::encode::IsNull, sqlx::error::BoxDynError> {
-        <i64 as sqlx::Encode<sqlx::Sqlite>>::encode_by_ref(&self.0, buf)
-    }
-}
-impl sqlx::Decode<'_, sqlx::Sqlite> for StoredPersistentId {
-    fn decode(value: sqlx::sqlite::SqliteValueRef<'_>) -> Result
-        <Self, sqlx::error::BoxDynError> {
-        let signed: i64 = sqlx::Decode::<sqlx::Sqlite>::decode(value)?;
-        Ok(Self(signed))
-    }
-}
-impl sqlx::Type<sqlx::Sqlite> for StoredPersistentId {
-    fn type_info() -> sqlx::sqlite::SqliteTypeInfo {
-        <i64 as sqlx::Type<sqlx::Sqlite>>::type_info()
-    }
-    fn compatible(ty: &<sqlx::Sqlite as sqlx::Database>::TypeInfo) -> bool {
-        <i64 as sqlx::Type<sqlx::Sqlite>>::compatible(ty)
-    }
-}
-impl core::fmt::Debug for StoredPersistentId {
-    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "StoredPersistentId({})", self.get())
-    }
-}
-impl core::fmt::Display for StoredPersistentId {
-    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "{}", self.get())
-    }
-}
-#[cfg(feature = "musicdb")]
-impl<T> From<StoredPersistentId> for musicdb::PersistentId<T> {
-    fn from(val: StoredPersistentId) -> Self {
-        Self::new(val.get())
-    }
-}
-#[cfg(feature = "musicdb")]
-impl<T> From<musicdb::PersistentId<T>> for StoredPersistentId {
-    fn from(value: musicdb::PersistentId<T>) -> Self {
-        Self::new(value.get_raw())
-    }
-}
+/// SQLite doesn't support 8-bit unsigned integers, so an i64 is used as an intermediary representation.
+pub use player_events::PersistentId as StoredPersistentId;
 
 #[cfg(test)]
 mod tests {
@@ -93,13 +93,20 @@ pub struct Config {
     pub application_id: u64,
     #[serde(default = "DisplayedField::default")]
     pub displayed_field: DisplayedField,
+    /// Whether to name the playlist or radio station being played from when hovering over the artist image.
+    #[serde(default = "Config::default_show_source")]
+    pub show_source: bool,
+}
+impl Config {
+    const fn default_show_source() -> bool { true }
 }
 impl Default for Config {
     fn default() -> Self {
         Self {
             enabled: true,
             application_id: EnumeratedApplicationIdentifier::default_as_u64(),
-            displayed_field: DisplayedField::default()
+            displayed_field: DisplayedField::default(),
+            show_source: Self::default_show_source(),
         }
     }
 }
@@ -216,7 +223,123 @@ impl PendingStatusClear {
 
 
 const CONNECTION_ATTEMPT_TIMEOUT: tokio::time::Duration = tokio::time::Duration::from_secs(3);
-const TRY_AGAIN_DEBOUNCE: tokio::time::Duration = tokio::time::Duration::from_secs(7);
+/// How long to wait before the first attempt to reconnect; each failed attempt after waits twice as long as the last.
+const RECONNECT_BACKOFF_MIN: tokio::time::Duration = tokio::time::Duration::from_secs(2);
+/// The longest that'll be waited between attempts to reconnect, so that Discord being opened is still noticed reasonably soon.
+const RECONNECT_BACKOFF_MAX: tokio::time::Duration = tokio::time::Duration::from_mins(1);
+
+/// The fields of an activity, each kept within the length that Discord will accept for it.
+///
+/// Discord rejects the whole activity if any one field is too long, and `discord-presence` asserts
+/// (taking the thread down with it) if the frame it's sent in is larger than it allows,
+/// so text is truncated and URLs that are too long to be usable are dropped.
+#[derive(Debug, Default, Clone)]
+struct ActivityPayload {
+    details: Option<String>,
+    state: Option<String>,
+    large_text: Option<String>,
+    large_image: Option<String>,
+    small_text: Option<String>,
+    small_image: Option<String>,
+    button: Option<(String, String)>,
+}
+impl ActivityPayload {
+    /// The most characters that any textual field can have.
+    const TEXT_BUDGET: usize = 128;
+    const IMAGE_URL_BUDGET: usize = 256;
+    const BUTTON_LABEL_BUDGET: usize = 32;
+    const BUTTON_URL_BUDGET: usize = 512;
+    /// The `MAX_RPC_FRAME_SIZE` of `discord-presence`, which it doesn't export.
+    const MAX_FRAME_SIZE: usize = 64 * 1024;
+    /// Room left within a frame for its header and the command that the activity is wrapped in.
+    const ENVELOPE_RESERVE: usize = 1024;
+
+    /// Discord, for no good reason, requires that the content of all present status fields be at least two characters long,
+    /// so shorter text is padded out; longer text is cut off with an ellipsis.
+    fn text(mut string: String) -> String {
+        if string.len() < 2 {
+            string += "  "; // two spaces
+        }
+        Self::truncate(string, Self::TEXT_BUDGET)
+    }
+
+    fn truncate(mut string: String, budget: usize) -> String {
+        if string.chars().nth(budget).is_some() {
+            let end = string.char_indices().nth(budget - 1).map_or(string.len(), |(index, _)| index);
+            string.truncate(end);
+            string.push('…');
+        }
+        string
+    }
+
+    /// A truncated URL would lead nowhere, so one that's too long is left out instead.
+    fn url(url: String, budget: usize) -> Option<String> {
+        if url.len() > budget {
+            tracing::debug!(url, budget, "leaving out url that's too long for discord");
+            return None
+        }
+        Some(url)
+    }
+
+    fn details(mut self, details: String) -> Self {
+        self.details = Some(Self::text(details));
+        self
+    }
+    fn state(mut self, state: String) -> Self {
+        self.state = Some(Self::text(state));
+        self
+    }
+    fn large_text(mut self, text: Option<String>) -> Self {
+        self.large_text = text.map(Self::text);
+        self
+    }
+    fn large_image(mut self, url: Option<&str>) -> Self {
+        self.large_image = url.and_then(|url| Self::url(url.to_owned(), Self::IMAGE_URL_BUDGET));
+        self
+    }
+    fn small_text(mut self, text: Option<String>) -> Self {
+        self.small_text = text.map(Self::text);
+        self
+    }
+    fn small_image(mut self, url: Option<&str>) -> Self {
+        self.small_image = url.and_then(|url| Self::url(url.to_owned(), Self::IMAGE_URL_BUDGET));
+        self
+    }
+    fn button(mut self, label: &str, url: String) -> Self {
+        self.button = Self::url(url, Self::BUTTON_URL_BUDGET).map(|url| (Self::truncate(label.to_owned(), Self::BUTTON_LABEL_BUDGET), url));
+        self
+    }
+
+    /// Sets the fields on the given activity.
+    fn apply(self, mut activity: Activity) -> Activity {
+        if let Some(details) = self.details {
+            activity = activity.details(details);
+        }
+        if let Some(state) = self.state {
+            activity = activity.state(state);
+        }
+        activity = activity.assets(|_| ActivityAssets {
+            large_text: self.large_text,
+            large_image: self.large_image,
+            small_image: self.small_image,
+            small_text: self.small_text,
+        });
+        if let Some((label, url)) = self.button {
+            activity = activity.append_buttons(|button| button.label(label).url(url));
+        }
+        activity
+    }
+
+    /// Checks that the activity can be sent without exceeding the frame size, as the library would panic otherwise.
+    fn ensure_fits(activity: &Activity) -> Result<(), DispatchError> {
+        let size = serde_json::to_vec(activity)?.len();
+        if size > Self::MAX_FRAME_SIZE - Self::ENVELOPE_RESERVE {
+            tracing::debug!(size, budget = Self::MAX_FRAME_SIZE - Self::ENVELOPE_RESERVE, "activity is too large");
+            return Err(DispatchError::invalid_data("activity is too large to send"))
+        }
+        Ok(())
+    }
+}
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum DiscordPresenceState {
@@ -370,28 +493,41 @@ impl DiscordPresence {
         };
 
         let auto_reconnect_task_handle = tokio::spawn(async move {
-            // If it's ready, wait for that to change, and then if it disconnects, reconnect. Repeat.
-            // If it's disconnected, see if it stays that way and reconnect if not. Repeat.
-            while let Some(instance) = weak.upgrade() {
-                macro_rules! fetch_state { () => { *instance.lock().await.state.lock().await }; }
-
-                let state = fetch_state!();
-                let state = match state {
-                    DiscordPresenceState::Connected => status_update.recv().await.unwrap(), // wait for disconnect
+            // If it's connected, wait for that to change; the client quitting is seen as a disconnect.
+            // If it's disconnected, try to reconnect, backing off further with each failure. Repeat.
+            // Only a weak reference is held while waiting, so that this doesn't keep the instance alive.
+            let mut backoff = RECONNECT_BACKOFF_MIN;
+            loop {
+                let Some(state) = async { Some(*weak.upgrade()?.lock().await.state.lock().await) }.await else { break };
+                match state {
+                    DiscordPresenceState::Connected => {
+                        backoff = RECONNECT_BACKOFF_MIN;
+                        match status_update.recv().await {
+                            Ok(DiscordPresenceState::Connected) | Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {},
+                            Ok(DiscordPresenceState::Disconnected) => {
+                                tracing::info!("lost connection to discord; it was likely closed");
+                                // The presence goes away along with the client, so there's nothing left to clear.
+                                let Some(instance) = weak.upgrade() else { break };
+                                instance.lock().await.has_content = false;
+                            },
+                            Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                        }
+                    },
                     DiscordPresenceState::Disconnected => {
-                        tracing::debug!("disconnected; polling again in {:.2} seconds", TRY_AGAIN_DEBOUNCE.as_secs_f64());
-                        tokio::time::sleep(TRY_AGAIN_DEBOUNCE).await;
-                        fetch_state!()
-                    }
-                };
-            
-                if state == DiscordPresenceState::Disconnected {
-                    let result = instance.lock().await.connect_in_place(CONNECTION_ATTEMPT_TIMEOUT).await;
-                    if let Err(error) = result  {
-                        tracing::debug!(?error, "couldn't connect");
-                    }
+                        tracing::debug!("disconnected; trying again in {:.2} seconds", backoff.as_secs_f64());
+                        tokio::time::sleep(backoff).await;
+                        let Some(instance) = weak.upgrade() else { break };
+                        let mut instance = instance.lock().await;
+                        if *instance.state.lock().await == DiscordPresenceState::Connected { continue }
+                        // The current activity is republished once connected, since that requests that the start event be redispatched.
+                        if let Err(error) = instance.connect_in_place(CONNECTION_ATTEMPT_TIMEOUT).await {
+                            tracing::debug!(?error, "couldn't connect");
+                            backoff = backoff.saturating_mul(2).min(RECONNECT_BACKOFF_MAX);
+                        }
+                    },
                 }
             }
+            tracing::debug!("discord presence instance was dropped, stopping auto-reconnect task");
         });
 
         instance.lock().await.auto_reconnect_task_handle = Some(auto_reconnect_task_handle);
@@ -440,9 +576,7 @@ impl DiscordPresence {
     #[tracing::instrument(skip(self), level = "debug")]
     async fn send_activity(&mut self) -> Result<(), DispatchError> {
         let activity = self.activity.clone().ok_or_else(|| DispatchError::internal_msg("no activity to dispatch", false))?;
-        let client = self.client.as_mut().ok_or_else(|| DispatchError::internal_msg("cannot dispatch without client", true))?;
-
-        client.set_activity(|_| activity.timestamps(|mut activity| {
+        let activity = activity.timestamps(|mut activity| {
             if let Some(position) = self.position {
                 let now: u64 = chrono::Utc::now().timestamp().try_into().expect("current timestamp should be non-negative");
                 let position: u64 = f32_round_to_u64(position);
@@ -453,7 +587,17 @@ impl DiscordPresence {
                 }
             } 
             activity
-        }))
+        });
+        ActivityPayload::ensure_fits(&activity)?;
+        if super::intercept_dry_run(Self::NAME, "set activity", &activity) { return Ok(()) }
+        if *self.state.lock().await == DiscordPresenceState::Disconnected {
+            // It'll be sent when reconnecting, as that has the start event redispatched.
+            tracing::debug!("not connected to discord; holding on to activity until reconnected");
+            return Ok(())
+        }
+        let client = self.client.as_mut().ok_or_else(|| DispatchError::internal_msg("cannot dispatch without client", true))?;
+
+        client.set_activity(|_| activity)
             .map(|_| { self.has_content = true; })
             .map_err(|err| {
                 use super::error::dispatch::{Recovery, RecoveryAttributes};
@@ -483,40 +627,31 @@ impl DiscordPresence {
         remaining > (STATUS_UPDATE_RATELIMIT_SECONDS / 3. * 2.)
     }
 
-    /// Discord, for no good reason, requires that the content of all present status fields be at least two characters long.
-    /// This function will pad a string to ensure it meets this requirement.
-    fn pad_field(mut string: String) -> String {
-        if string.len() < 2 {
-            string += "  "; // two spaces
+    /// Describes where the track is being played from, unless it's just the library.
+    fn source(player: &osa_apple_music::ApplicationData) -> Option<String> {
+        use osa_apple_music::application::PlaylistKind;
+        let playlist = player.playlist.as_ref()?;
+        match playlist.kind {
+            PlaylistKind::LibraryPlaylist => None,
+            _ if playlist.is_station() => Some(format!("Tuned into {}", playlist.name)),
+            _ => Some(format!("Playing from {}", playlist.name)),
         }
-        string
     }
 
     #[expect(clippy::useless_let_if_seq, reason = "bad with #[cfg]")]
     fn build_activity(config: &Config, context: super::BackendContext<crate::data_fetching::AdditionalTrackData>) -> discord_presence::models::Activity {
-        use osa_apple_music::track::MediaKind;
-        let super::BackendContext { track, listened: _, data: additional_info, .. } = context;
+        use player_events::MediaKind;
+        let super::BackendContext { track, listened: _, data: additional_info, player, .. } = context;
         let image_urls = additional_info.images.urls();
+        let source = if config.show_source { Self::source(&player) } else { None };
 
-        let mut activity = Activity::new()
-            .activity_type(match track.media_kind {
-                MediaKind::MusicVideo => ActivityType::Watching,
-                MediaKind::Song => ActivityType::Listening, 
-                MediaKind::Unknown => {
-                    let persistent_id = track.persistent_id;
-                    tracing::warn!(%persistent_id, "unknown media kind; defaulting to listening");
-                    ActivityType::Listening
-                },
-            })
-            .status_display(config.displayed_field.into())
-            .details(Self::pad_field(track.name.clone()))
-            .state(track.artist.clone().map_or_else(|| "Unknown Artist".to_owned(), Self::pad_field))
-            .assets(|_| ActivityAssets {
-                large_text: track.album.clone().map(Self::pad_field),
-                large_image: image_urls.track.map(str::to_owned).map(Self::pad_field),
-                small_image: image_urls.artist.map(str::to_owned).map(Self::pad_field),
-                small_text: track.artist.clone().map(Self::pad_field),
-            });
+        let mut payload = ActivityPayload::default()
+            .details(track.name.clone())
+            .state(track.artist.clone().unwrap_or_else(|| "Unknown Artist".to_owned()))
+            .large_text(track.album.clone())
+            .large_image(image_urls.track)
+            .small_text(source.or_else(|| track.artist.clone()))
+            .small_image(image_urls.artist);
 
         let mut songlink = None;
         
@@ -528,14 +663,29 @@ impl DiscordPresence {
         }
 
         if songlink.is_none() && let Some(itunes) = &additional_info.itunes {
-            songlink = Some(format!("https://song.link/{url}&app=music", url = itunes.apple_music_url));
+            use itunes_api::SearchResult;
+            songlink = Some(match itunes {
+                SearchResult::Song(_) | SearchResult::MusicVideo(_) => format!("https://song.link/{url}&app=music", url = itunes.url()),
+                // song.link doesn't know of these, so they're linked to directly
+                SearchResult::Podcast(_) | SearchResult::Audiobook(_) => itunes.url().to_owned(),
+            });
         }
 
         if let Some(songlink) = songlink {
-            activity = activity.append_buttons(|button| button.label("Take a listen!").url(songlink));
+            payload = payload.button("Take a listen!", songlink);
         }
 
-        activity
+        payload.apply(Activity::new()
+            .activity_type(match track.media_kind {
+                MediaKind::MusicVideo => ActivityType::Watching,
+                MediaKind::Song | MediaKind::Podcast | MediaKind::Audiobook => ActivityType::Listening,
+                MediaKind::Unknown => {
+                    let persistent_id = track.persistent_id;
+                    tracing::warn!(%persistent_id, "unknown media kind; defaulting to listening");
+                    ActivityType::Listening
+                },
+            })
+            .status_display(config.displayed_field.into()))
     }
 }
 impl Drop for DiscordPresence {
@@ -569,6 +719,14 @@ super::subscribe!(DiscordPresence, TrackStarted, {
         self.send_activity().await
     }
 });
+super::subscribe!(DiscordPresence, DataEnriched, {
+    async fn dispatch(&mut self, context: super::BackendContext<crate::data_fetching::AdditionalTrackData>) -> Result<(), DispatchError> {
+        self.position = context.listened.lock().await.current.as_ref().map(listened::CurrentListened::get_expected_song_position);
+        let activity = Self::build_activity(&self.config, context);
+        self.activity = Some(activity);
+        self.send_activity().await
+    }
+});
 super::subscribe!(DiscordPresence, ProgressJolt, {
     async fn dispatch(&mut self, context: super::BackendContext<()>) -> Result<(), DispatchError> {
         if self.should_dispatch_progress_update(&context).await {
@@ -597,3 +755,30 @@ super::subscribe!(DiscordPresence, ImminentSubscriberTermination, {
         Ok(())
     }
 });
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payload_budgets() {
+        assert_eq!(ActivityPayload::text("a".to_owned()), "a  ");
+        let name = "ö".repeat(200);
+        let truncated = ActivityPayload::text(name);
+        assert_eq!(truncated.chars().count(), ActivityPayload::TEXT_BUDGET);
+        assert!(truncated.ends_with("ö…"));
+
+        let long_url = format!("https://example.com/{}", "a".repeat(ActivityPayload::IMAGE_URL_BUDGET));
+        let payload = ActivityPayload::default()
+            .large_image(Some(&long_url))
+            .small_image(Some("https://example.com/artist.jpg"));
+        assert!(payload.large_image.is_none(), "urls that are too long should be dropped rather than truncated");
+        assert_eq!(payload.small_image.as_deref(), Some("https://example.com/artist.jpg"));
+
+        let activity = payload.details("x".repeat(100_000)).apply(Activity::new());
+        assert!(ActivityPayload::ensure_fits(&activity).is_ok());
+
+        let activity = Activity::new().details("x".repeat(100_000));
+        assert!(ActivityPayload::ensure_fits(&activity).is_err(), "oversized activities should be refused rather than sent");
+    }
+}
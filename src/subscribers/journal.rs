@@ -0,0 +1,115 @@
+//! A write-ahead journal of the listens being dispatched, so that one isn't silently dropped if the program stops partway through.
+//!
+//! Each listen is [appended](append) before the end of its track is dispatched, and [committed](commit) once every backend has accepted it;
+//! it isn't while any backend is holding on to it to replay later.
+//! Those left uncommitted are [replayed](Backends::replay_journal) to the backends which can take listens after the fact on the next startup.
+//! A backend which had already submitted the listen before the program stopped will be given it again; a duplicate is preferable to a lost listen.
+//!
+//! Nothing is journaled or replayed in a [dry run](super::is_dry_run).
+//!
+//! Committed listens are kept for [`RETENTION`] before being pruned.
+use alloc::sync::Arc;
+
+use crate::store::entities::{JournalEntry, Key};
+use super::{Backends, DeferredListen};
+
+/// How long listens are kept after being committed.
+pub const RETENTION: chrono::TimeDelta = chrono::TimeDelta::days(30);
+
+/// Records a listen which is about to be dispatched, returning the entry to [commit] afterwards.
+/// Failing to do so is only logged, since the dispatch can go on without it.
+pub async fn append(listen: &DeferredListen) -> Option<Key<JournalEntry>> {
+    if super::is_dry_run() { return None }
+    let pool = crate::store::DB_POOL.get().await.inspect_err(|error| {
+        tracing::error!(?error, "failed to get database connection to journal listen");
+    }).ok()?;
+    JournalEntry::append(&pool, &listen.track, &listen.player_version, listen.started_at, listen.listened).await.inspect_err(|error| {
+        tracing::error!(?error, "failed to journal listen");
+    }).ok()
+}
+
+/// Marks a listen as having been accepted by every backend.
+pub async fn commit(entry: Key<JournalEntry>) {
+    let pool = match crate::store::DB_POOL.get().await {
+        Ok(pool) => pool,
+        Err(error) => {
+            tracing::error!(?error, "failed to get database connection to commit journaled listen");
+            return
+        },
+    };
+    if let Err(error) = JournalEntry::commit(&pool, entry).await {
+        tracing::error!(?error, ?entry, "failed to commit journaled listen");
+    }
+}
+
+/// Restores the listens of the entries, dropping (and logging) those whose track can't be read.
+pub fn to_listens(entries: &[JournalEntry]) -> Vec<DeferredListen> {
+    entries.iter().filter_map(|entry| match entry.track() {
+        Ok(track) => Some(DeferredListen {
+            track: Arc::new(track),
+            player_version: entry.player_version.as_str().into(),
+            started_at: entry.started_at.0,
+            listened: entry.listened(),
+            journaled: Some(entry.id()),
+        }),
+        Err(error) => {
+            tracing::error!(?error, id = ?entry.id(), "failed to read journaled track; dropping listen");
+            None
+        }
+    }).collect()
+}
+
+impl Backends {
+    /// Gives the listens which were journaled but never committed to the backends which can [replay](super::subscription::DeferredReplay) them,
+    /// then clears out those committed before.
+    pub async fn replay_journal(&self) {
+        if super::is_dry_run() {
+            tracing::info!("dry run; not replaying journal");
+            return
+        }
+        let pool = match crate::store::DB_POOL.get().await {
+            Ok(pool) => pool,
+            Err(error) => {
+                tracing::error!(?error, "failed to get database connection to replay journal");
+                return
+            },
+        };
+        let entries = match JournalEntry::get_uncommitted(&pool).await {
+            Ok(entries) => entries,
+            Err(error) => {
+                tracing::error!(?error, "failed to read journal");
+                return
+            },
+        };
+
+        if !entries.is_empty() {
+            tracing::info!(count = entries.len(), "replaying listens which weren't fully dispatched before the program last stopped");
+            let listens = to_listens(&entries);
+
+            self.replay(&listens).await;
+        }
+
+        match JournalEntry::prune(&pool, chrono::Utc::now() - RETENTION).await {
+            Ok(pruned) => tracing::debug!(pruned, "pruned committed journal entries"),
+            Err(error) => tracing::error!(?error, "failed to prune journal"),
+        }
+    }
+
+    /// Gives the listens to every enabled backend which can [replay](super::subscription::DeferredReplay) them,
+    /// except for those the track is excluded from, then commits those which none are left holding on to.
+    pub async fn replay(&self, listens: &[DeferredListen]) {
+        #[allow(unused_mut, reason = "not mutated when compiled without features")]
+        let mut replaying = Vec::new();
+        for backend in self.all() {
+            #[allow(unused_variables, reason = "not used when compiled without features")]
+            let identity = backend.lock().await.get_identity();
+            if !self.can_replay(identity).await { continue }
+            for listen in listens.iter().filter(|listen| !listen.track.excluded_backends.contains(identity)) {
+                self.defer(identity, listen.clone()).await;
+            }
+            replaying.push(backend);
+        }
+        self.replay_deferred(replaying).await;
+        self.settle(listens).await;
+    }
+}
@@ -4,11 +4,13 @@ use lastfm::{auth::ClientIdentity, scrobble::ScrobbleError};
 use chrono::TimeDelta;
 use maybe_owned_string::MaybeOwnedString;
 
-use super::{error::dispatch::DispatchError, DispatchableTrack, subscribe, subscription};
+use super::{error::dispatch::DispatchError, timestamp::TimestampPolicy, DispatchableTrack, subscribe, subscription};
 use crate::{data_fetching::AdditionalTrackData, listened::TimeDeltaExtension as _};
 
 const FOUR_MINUTES: TimeDelta = TimeDelta::new(4 * 60, 0).unwrap();
 const THIRTY_SECONDS: core::time::Duration = core::time::Duration::new(30, 0);
+/// - <https://www.last.fm/api/show/track.scrobble>
+const MAX_SCROBBLES_PER_REQUEST: usize = 50;
 
 
 pub static DEFAULT_CLIENT_IDENTITY: LazyLock<ClientIdentity> = LazyLock::new(|| {
@@ -38,7 +40,44 @@ pub struct Config {
         skip_serializing_if = "is_default_client_identity"
     )]
     pub identity: ClientIdentity,
-    pub session_key: Option<lastfm::auth::SessionKey>
+    pub session_key: Option<lastfm::auth::SessionKey>,
+    #[serde(default, skip_serializing_if = "TagSubmission::is_default")]
+    pub tags: TagSubmission,
+    /// When scrobbles are said to have happened.
+    #[serde(default, skip_serializing_if = "TimestampPolicy::is_default")]
+    pub timestamp_policy: TimestampPolicy,
+}
+
+/// Tagging scrobbled tracks on Last.fm with their genre.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagSubmission {
+    pub enabled: bool,
+    /// If not empty, only these tags will be submitted.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+    /// Tags which will never be submitted.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deny: Vec<String>,
+}
+impl TagSubmission {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Returns the tags to submit for a genre, lowercased as Last.fm displays them.
+    ///
+    /// Compound genres (e.x. `Hip-Hop/Rap`) are split into a tag for each part.
+    fn tags_for(&self, genre: &str) -> Vec<String> {
+        let listed = |list: &[String], tag: &str| list.iter().any(|listed| listed.trim().eq_ignore_ascii_case(tag));
+        let mut tags = Vec::new();
+        for tag in genre.split('/').map(str::trim).filter(|tag| !tag.is_empty()) {
+            if listed(&self.deny, tag) || (!self.allow.is_empty() && !listed(&self.allow, tag)) { continue }
+            let tag = tag.to_lowercase();
+            if !tags.contains(&tag) { tags.push(tag); }
+        }
+        tags.truncate(lastfm::Client::<lastfm::auth::state::Authorized>::MAX_TAGS_PER_REQUEST);
+        tags
+    }
 }
 
 fn clean_album(mut str: &str) -> &str {
@@ -141,7 +180,7 @@ impl<'a> From<&'a DispatchableTrack> for FirstArtistQuery<'a> {
     fn from(track: &'a DispatchableTrack) -> Self {
         Self {
             name: &track.name,
-            id: musicdb::PersistentId::from(track.persistent_id),
+            id: musicdb::PersistentId::new(track.persistent_id.get()),
             artists: track.artist.as_deref().unwrap_or_else(|| {
                 tracing::error!("missing artist name for track w/ id {}", track.persistent_id);
                 Default::default()
@@ -150,6 +189,113 @@ impl<'a> From<&'a DispatchableTrack> for FirstArtistQuery<'a> {
     }
 }
 
+/// Whether the artist is the one credited first on the track.
+///
+/// An artist resolved from the library or the iTunes store can be that of the album instead of the track;
+/// for a compilation, that'd be "Various Artists", which isn't who performed the track.
+fn is_credited_first(artists: &str, artist: &str) -> bool {
+    artists.strip_prefix(artist).is_some_and(|rest| rest.is_empty() || rest.starts_with(", ") || rest.starts_with(" & "))
+}
+
+/// Strips a trailing featured artist credit from a track title, like "Song (feat. Someone)".
+fn title_without_credits(title: &str) -> &str {
+    let mut buffer = String::with_capacity(11); // fits largest
+    for separator in [" (", " [", " ",] {
+        for featuring in [
+            "feat.",               "ft.",
+            "feat ",  "featuring", "ft "
+        ] {
+            buffer.clear();
+            buffer.push_str(separator);
+            buffer.push_str(featuring);
+            if let Some(index) = title.find(&buffer) {
+                return &title[..index]
+            }
+        }
+    }
+    title
+}
+
+/// Recording search results, which are revalidated rather than fetched anew when searched for again.
+static SEARCHES: LazyLock<brainz::music::request_client::ConditionalCache> = LazyLock::new(|| brainz::music::request_client::ConditionalCache::new(256));
+
+/// Searches MusicBrainz for recordings of the given title by the given artist.
+// TODO: Create a `brainz` abstraction.
+#[allow(clippy::items_after_statements)]
+async fn search_recordings(net: &reqwest::Client, artist: &str, title: &str) -> Option<Vec<brainz::music::entities::Recording>> {
+    use super::listenbrainz::DEFAULT_PROGRAM_INFO;
+    let request = net.get("https://musicbrainz.org/ws/2/recording/?fmt=json")
+        .header("User-Agent", &DEFAULT_PROGRAM_INFO.to_user_agent())
+        .query(&[("query", format!("artist:\"{artist}\" AND recording:\"{title}\""))]);
+
+    // Revalidating still counts against the rate limit, but spares resending the results.
+    let permit = crate::data_fetching::limits::acquire(crate::data_fetching::limits::Service::MusicBrainz).await;
+    let response = SEARCHES.send(request, &crate::net::retry()).await.inspect_err(|err| {
+        tracing::error!(?err, "failed to request recordings from MusicBrainz");
+    }).ok()?;
+    drop(permit);
+
+    let (status, text) = (response.status, response.body);
+    if !status.is_success() {
+        tracing::error!(%status, "MusicBrainz API returned an error");
+        tracing::debug!("could not search recordings: {:?}", text);
+        return None
+    }
+    if response.revalidated { tracing::trace!(artist, title, "recording search results were unchanged") }
+
+    #[derive(serde::Deserialize, Debug)]
+    #[allow(unused)]
+    struct Response {
+        created: String, // ISO 8601
+        count: u32,
+        offset: u32,
+        recordings: Vec<brainz::music::entities::Recording>,
+    }
+
+    let response: Response = serde_json::from_str(&text).inspect_err(|error| {
+        tracing::error!(?error, "failed to parse MusicBrainz recording search response");
+        tracing::debug!("could not deserialize: {:?}", text);
+    }).ok()?;
+
+    Some(response.recordings)
+}
+
+type RecordingId = brainz::music::Id<brainz::music::entities::Recording>;
+
+/// Resolved recording IDs by track, including the tracks which couldn't be resolved, so each is only searched for once.
+static RECORDING_IDS: LazyLock<std::sync::Mutex<std::collections::HashMap<u64, Option<RecordingId>>>> = LazyLock::new(Default::default);
+/// The number of tracks [`RECORDING_IDS`] can hold before it's cleared out.
+const RECORDING_IDS_CAPACITY: usize = 1024;
+
+/// Finds the MusicBrainz recording of the track, for its MBID.
+///
+/// A recording is only accepted if its title and first credited artist match,
+/// and its length (if both are known) is within a few seconds of the track's.
+async fn resolve_recording_id(track: &DispatchableTrack, artist: &str, net: &reqwest::Client) -> Option<RecordingId> {
+    const LENGTH_TOLERANCE_MS: u64 = 5_000;
+    let key = track.persistent_id.get();
+    if let Some(cached) = RECORDING_IDS.lock().expect("poisoned").get(&key) {
+        return *cached
+    }
+
+    let uncredited = title_without_credits(&track.name);
+    let recordings = search_recordings(net, artist, uncredited).await?; // don't cache failed requests
+    let duration = track.duration.map(|duration| u64::try_from(duration.as_millis()).unwrap_or(u64::MAX));
+    let id = recordings.into_iter().find(|recording| {
+        recording.title.eq_ignore_ascii_case(uncredited)
+            && recording.artist_credit.first().is_some_and(|credited| {
+                credited.name.as_deref().unwrap_or(&credited.artist.name).eq_ignore_ascii_case(artist)
+            })
+            && duration.zip(recording.length).is_none_or(|(duration, length)| duration.abs_diff(length.into()) <= LENGTH_TOLERANCE_MS)
+    }).map(|recording| recording.id);
+    tracing::debug!(persistent_id = %track.persistent_id, ?id, "resolved recording");
+
+    let mut cache = RECORDING_IDS.lock().expect("poisoned");
+    if cache.len() >= RECORDING_IDS_CAPACITY { cache.clear() }
+    cache.insert(key, id);
+    id
+}
+
 /// Extracts a plausible "first" artist from a string that may contain multiple artists in the form "Artist1 & Artist2" or "Artist1, Artist2 & Artist3".
 /// Uses external data sources (the iTunes store, ListenBrainz) to resolve conflicts. When this occurs, the result is cached to prevent future lookups.
 // TODO: What if an artist uses a comma within their name?
@@ -165,7 +311,7 @@ async fn extract_first_artist<'a, 'b: 'a>(
 
     if let Some(pool) = &pool {
         use crate::store::entities::CachedFirstArtist;
-        match CachedFirstArtist::get_by_persistent_id(pool, track.id.into(), track.artists).await {
+        match CachedFirstArtist::get_by_persistent_id(pool, track.id.get_raw().into(), track.artists).await {
             Ok(Some(cached)) => {
                 tracing::debug!(?track.id, ?track.artists, artist = ?cached.artist, "using cached first artist");
                 return cached.artist.into()
@@ -175,62 +321,11 @@ async fn extract_first_artist<'a, 'b: 'a>(
         }
     }
 
-    fn title_without_credits(title: &str) -> &str {
-        let mut buffer = String::with_capacity(11); // fits largest
-        for separator in [" (", " [", " ",] {
-            for featuring in [
-                "feat.",               "ft.",
-                "feat ",  "featuring", "ft "
-            ] {
-                buffer.clear();
-                buffer.push_str(separator);
-                buffer.push_str(featuring);
-                if let Some(index) = title.find(&buffer) {
-                    return &title[..index]
-                }
-            }
-        }
-        title
-    }
-
-    // TODO: Create a `brainz` abstraction.
     async fn using_listenbrainz(track: &FirstArtistQuery<'_>, net: &reqwest::Client, left: &str, pool: Option<sqlx::SqlitePool>) -> Option<String> {
-        use super::listenbrainz::DEFAULT_PROGRAM_INFO;
         let uncredited = title_without_credits(track.name);
-        let request = net.get("https://musicbrainz.org/ws/2/recording/?fmt=json")
-            .header("User-Agent", &DEFAULT_PROGRAM_INFO.to_user_agent())
-            .query(&[("query", format!("artist:\"{left}\" AND recording:\"{uncredited}\""))]);
-
-        let response = request.send().await.inspect_err(|err| {
-            tracing::error!(?err, "failed to send request to ListenBrainz");
-        }).ok()?;
-
-        let status = response.status();
-        let text = response.text().await.inspect_err(|err| {
-            tracing::error!(?err, "failed to read response from ListenBrainz");
-        }).ok()?;
-
-        if !status.is_success() {
-            tracing::error!(%status, "ListenBrainz API returned an error");
-            tracing::debug!("could not fetch artist: {:?}", text);
-            return None
-        }
+        let recordings = search_recordings(net, left, uncredited).await?;
 
-        #[derive(serde::Deserialize, Debug)]
-        #[allow(unused)]
-        struct Response {
-            created: String, // ISO 8601
-            count: u32,
-            offset: u32,
-            recordings: Vec<brainz::music::entities::Recording>,
-        }
-
-        let response: Response = serde_json::from_str(&text).inspect_err(|error| {
-            tracing::error!(?error, persistent_id = ?track.id, "failed to parse ListenBrainz response attempting to get first artist");
-            tracing::debug!("could not deserialize: {:?}", text);
-        }).ok()?;
-
-        let recording = response.recordings.into_iter().find(|recording| {
+        let recording = recordings.into_iter().find(|recording| {
             recording.title.eq_ignore_ascii_case(uncredited)
         })?;
 
@@ -239,7 +334,7 @@ async fn extract_first_artist<'a, 'b: 'a>(
 
         if let Some(pool) = pool {
             use crate::store::entities::CachedFirstArtist;
-            match CachedFirstArtist::new(&pool, track.id.into(), track.artists, &artist).await {
+            match CachedFirstArtist::new(&pool, track.id.get_raw().into(), track.artists, &artist).await {
                 Ok(_) => tracing::debug!(?track.id, ?track.artists, ?artist, "cached first artist from ListenBrainz"),
                 Err(err) => tracing::error!(?err, ?track.id, ?track.artists, ?artist, "failed to cache first artist from ListenBrainz")
             }
@@ -274,6 +369,7 @@ async fn extract_first_artist<'a, 'b: 'a>(
         return left.into()
     }
 
+    let track_artists = track.artists;
     if let Some(db) = db && let Some(track) = db.get(track.id) {
         // So, the `cloud_catalog_artist_id` is the actual Apple Music ID for the artist.
         // Multiple client "artists" can map to that singular "real" artist; the real one, or any of the various collaboration artists.
@@ -284,7 +380,8 @@ async fn extract_first_artist<'a, 'b: 'a>(
             // But we can know for certain that it *is* a single artist if we check their name and there isn't an ampersand in it.
             let mut singles = matching_artists.iter()
                 .filter_map(|artist| artist.name)
-                .filter(|name| !name.chars().any(|c| c == '&'));
+                .filter(|name| !name.chars().any(|c| c == '&'))
+                .filter(|name| is_credited_first(track_artists, &name.to_string()));
 
             if let Some(single) = singles.next() {
                 if singles.next().is_none() {
@@ -298,10 +395,10 @@ async fn extract_first_artist<'a, 'b: 'a>(
             // Well, we seemingly didn't have the original artist in the library, but
             // we can leverage the fact that an iTunes lookup will always return the singular
             // primary artist.
-            let client = itunes_api::Client::new(net.clone());
+            let client = itunes_api::Client::new(net.clone()).with_retry(crate::net::retry());
             if let Some(cloud) = client.lookup_artist(cloud_artist_id.into()).await.inspect_err(|err| {
                 tracing::error!(?err, "failed to lookup artist in iTunes API");
-            }).ok().flatten() {
+            }).ok().flatten() && is_credited_first(track_artists, &cloud.name) {
                 return cloud.name.into()
             }
         }
@@ -366,7 +463,9 @@ async fn artist_extraction() {
 } 
 
 subscription::define_subscriber!(pub LastFM, {
-    client: ::lastfm::Client<::lastfm::auth::state::Authorized>
+    client: ::lastfm::Client<::lastfm::auth::state::Authorized>,
+    tags: TagSubmission,
+    timestamp_policy: TimestampPolicy
 });
 subscribe!(LastFM, TrackStarted, {
     async fn dispatch(&mut self, context: super::BackendContext<AdditionalTrackData>) -> Result<(), DispatchError> {
@@ -374,54 +473,111 @@ subscribe!(LastFM, TrackStarted, {
         let pool = crate::store::DB_POOL.get().await.ok();
         let track = context.track.as_ref();
         let artist = extract_first_artist(track, db, pool, &self.client.net).await;
-        let info = Self::track_to_heard(track, &artist);
+        let mbid = resolve_recording_id(track, &artist, &self.client.net).await;
+        let info = Self::track_to_heard(track, &artist, mbid);
+        if super::intercept_dry_run(Self::NAME, "update now playing", &info) { return Ok(()) }
         self.client.set_now_listening(&info).await?;
         Ok(())
     }
 });
 subscribe!(LastFM, TrackEnded, {
     async fn dispatch(&mut self, context: super::BackendContext<()>) -> Result<(), DispatchError> {
-        if !Self::is_eligible(context.track.as_ref(), context.listened).await {
+        let threshold = context.track.duration.map_or(FOUR_MINUTES, Self::threshold);
+        let (total, timestamp) = {
+            let listened = context.listened.lock().await;
+            (listened.total(), self.timestamp_policy.of_ended(&listened, threshold))
+        };
+        if !Self::is_eligible(context.track.as_ref(), total) {
             return Ok(())
         }
+        let timestamp = timestamp.ok_or(DispatchError::missing_required_data("listen start time"))?;
 
         let db = context.musicdb.as_ref().as_ref();
         let pool = crate::store::DB_POOL.get().await.ok();
         let track = context.track.as_ref();
         let artist = extract_first_artist(track, db, pool, &self.client.net).await;
-        let response = self.client.scrobble(&[lastfm::scrobble::Scrobble {
+        let mbid = resolve_recording_id(track, &artist, &self.client.net).await;
+        let scrobbles = [lastfm::scrobble::Scrobble {
             chosen_by_user: None, // TODO: Detect radio stations and such.
-            timestamp: chrono::Utc::now(),
-            info: Self::track_to_heard(track, &artist)
-        }]).await?;
+            stream_id: None,
+            timestamp,
+            info: Self::track_to_heard(track, &artist, mbid)
+        }];
+        if super::intercept_dry_run(Self::NAME, "scrobble", &scrobbles) { return Ok(()) }
+        let response = self.client.scrobble(&scrobbles).await?;
 
         if let Some(outcome) = response.results.into_iter().next() {
             outcome?;
         }
 
+        self.submit_tags(track, &artist).await;
+        Ok(())
+    }
+});
+subscribe!(LastFM, DeferredReplay, {
+    async fn dispatch(&mut self, listens: super::DeferredListens) -> Result<(), DispatchError> {
+        let pool = crate::store::DB_POOL.get().await.ok();
+        let mut artists = Vec::with_capacity(listens.len());
+        for listen in listens.iter().filter(|listen| Self::is_eligible(&listen.track, listen.listened)) {
+            let artist = extract_first_artist(listen.track.as_ref(), None, pool.clone(), &self.client.net).await;
+            let mbid = resolve_recording_id(&listen.track, &artist, &self.client.net).await;
+            artists.push((listen, artist, mbid));
+        }
+        let scrobbles = artists.iter().map(|(listen, artist, mbid)| lastfm::scrobble::Scrobble {
+            chosen_by_user: None,
+            stream_id: None,
+            timestamp: self.timestamp_policy.of_deferred(listen, listen.track.duration.map_or(FOUR_MINUTES, Self::threshold)),
+            info: Self::track_to_heard(&listen.track, artist, *mbid)
+        }).collect::<Vec<_>>();
+        if scrobbles.is_empty() { return Ok(()) }
+        if super::intercept_dry_run(Self::NAME, "scrobble deferred", &scrobbles) { return Ok(()) }
+        for chunk in scrobbles.chunks(MAX_SCROBBLES_PER_REQUEST) {
+            let response = self.client.scrobble(chunk).await?;
+            for error in response.results.into_iter().filter_map(Result::err) {
+                tracing::warn!(?error, "deferred scrobble was ignored");
+            }
+        }
         Ok(())
     }
 });
 
 
 impl LastFM {
-    pub fn new(identity: ClientIdentity, session_key: lastfm::auth::SessionKey) -> Self {
-        let client = lastfm::Client::authorized(identity, session_key);
-        Self { client }
+    pub fn new(identity: ClientIdentity, session_key: lastfm::auth::SessionKey, tags: TagSubmission, timestamp_policy: TimestampPolicy) -> Self {
+        let client = lastfm::Client::authorized(identity, session_key, crate::net::client()).with_retry(crate::net::retry());
+        Self { client, tags, timestamp_policy }
+    }
+
+    /// Tags the track with its genre, if enabled.
+    /// A failure is only logged, since the scrobble itself has already gone through.
+    async fn submit_tags(&self, track: &DispatchableTrack, artist: &str) {
+        if !self.tags.enabled { return }
+        let Some(genre) = track.genre.as_deref() else { return };
+        let tags = self.tags.tags_for(genre);
+        if tags.is_empty() { return }
+        let tags = tags.iter().map(String::as_str).collect::<Vec<_>>();
+
+        if super::intercept_dry_run(Self::NAME, "add tags", &(artist, &track.name, &tags)) { return }
+        if let Err(error) = self.client.add_tags(artist, &track.name, &tags).await {
+            tracing::error!(?error, ?tags, "failed to tag track");
+        }
     }
 
     /// - <https://www.last.fm/api/scrobbling#scrobble-requests>
-    async fn is_eligible(track: &DispatchableTrack, listened: alloc::sync::Arc<tokio::sync::Mutex<crate::Listened>>) -> bool {
+    fn is_eligible(track: &DispatchableTrack, time_listened: TimeDelta) -> bool {
         if let Some(duration) = track.duration {
-            let time_listened = listened.lock().await.total_heard();
             if duration < THIRTY_SECONDS { return false }
-            time_listened >= FOUR_MINUTES ||
-            time_listened.as_secs_f32() >= (duration.as_secs_f32() / 2.)
+            time_listened >= Self::threshold(duration)
         } else { false }
     }
 
+    /// How long a track has to be listened to for before it can be scrobbled; half of it, or four minutes if that's sooner.
+    fn threshold(duration: core::time::Duration) -> TimeDelta {
+        TimeDelta::from_secs_f32(duration.as_secs_f32() / 2.).min(FOUR_MINUTES)
+    }
+
     /// Returns `None` if the track is missing required data (the artist or track name).
-    fn track_to_heard<'a>(track: &'a DispatchableTrack, artist: &'a str) -> lastfm::scrobble::HeardTrackInfo<'a> {
+    fn track_to_heard<'a>(track: &'a DispatchableTrack, artist: &'a str, mbid: Option<RecordingId>) -> lastfm::scrobble::HeardTrackInfo<'a> {
         lastfm::scrobble::HeardTrackInfo {
             artist,
             track: &track.name,
@@ -431,7 +587,8 @@ impl LastFM {
             } else { None },
             duration_in_seconds: track.duration.map(|d| d.as_secs().try_into().inspect_err(|error| tracing::warn!(%error, "track duration exceeds u32::MAX; clamping")).unwrap_or(u32::MAX)),
             track_number: track.track_number.map(|n| n.get().into()),
-            mbid: None
+            mbid,
+            context: None
         }
     }
 }
@@ -440,3 +597,69 @@ impl core::fmt::Debug for LastFM {
         f.debug_struct("LastFM").finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compilation_track() -> DispatchableTrack {
+        DispatchableTrack::from(player_events::Track {
+            name: "Snorkel".to_owned(),
+            album: Some("Now That's What I Call Snorkeling".to_owned()),
+            album_artist: Some("Various Artists".to_owned()),
+            artist: Some("Lumpy & The Snorkelers".to_owned()),
+            persistent_id: crate::store::types::StoredPersistentId::from(1),
+            duration: Some(core::time::Duration::from_secs(200)),
+            media_kind: player_events::MediaKind::Song,
+            track_number: core::num::NonZero::new(7),
+            apple_music_url: None,
+            genre: None,
+            composer: None,
+            work: None,
+            movement: None,
+        })
+    }
+
+    #[test]
+    fn compilation_artists() {
+        let track = compilation_track();
+        let heard = LastFM::track_to_heard(&track, "Lumpy", None);
+        assert_eq!(heard.artist, "Lumpy");
+        assert_eq!(heard.album_artist, Some("Various Artists"));
+
+        let mut single = compilation_track();
+        single.album_artist = Some("Lumpy".to_owned());
+        assert_eq!(LastFM::track_to_heard(&single, "Lumpy", None).album_artist, None);
+
+        assert!(is_credited_first("Lumpy & The Snorkelers", "Lumpy"));
+        assert!(is_credited_first("Lumpy, Dinghy & The Snorkelers", "Lumpy"));
+        assert!(is_credited_first("Lumpy", "Lumpy"));
+        assert!(!is_credited_first("Lumpy & The Snorkelers", "Various Artists"));
+        assert!(!is_credited_first("Lumpyness", "Lumpy"));
+    }
+
+    #[tokio::test]
+    async fn first_artist_from_library() {
+        use musicdb::fixture::{Fixture, COLLABORATION_TRACK};
+        let db = musicdb::MusicDB::from_decoded(Fixture::collaboration().data.into_boxed_slice(), "Library.musicdb").expect("fixture should parse");
+        let query = FirstArtistQuery { name: "Snorkel", id: musicdb::PersistentId::new(COLLABORATION_TRACK), artists: "Lumpy & The Snorkelers" };
+        // Resolved from the library alone, so nothing's looked up over the network.
+        assert_eq!(extract_first_artist(query, Some(&db), None, &reqwest::Client::new()).await, "Lumpy");
+    }
+
+    #[test]
+    fn genre_tags() {
+        let everything = TagSubmission { enabled: true, ..Default::default() };
+        assert_eq!(everything.tags_for("Hip-Hop/Rap"), ["hip-hop", "rap"]);
+        assert_eq!(everything.tags_for("Pop"), ["pop"]);
+        assert!(everything.tags_for(" / ").is_empty());
+
+        let denying = TagSubmission { enabled: true, deny: vec!["rap".to_owned()], ..Default::default() };
+        assert_eq!(denying.tags_for("Hip-Hop/Rap"), ["hip-hop"]);
+
+        let allowing = TagSubmission { enabled: true, allow: vec!["J-Pop".to_owned(), "Rock".to_owned()], deny: vec!["rock".to_owned()] };
+        assert_eq!(allowing.tags_for("J-Pop"), ["j-pop"]);
+        assert!(allowing.tags_for("Rock").is_empty());
+        assert!(allowing.tags_for("Anime").is_empty());
+    }
+}
@@ -1,7 +1,7 @@
 use alloc::sync::Arc;
 use maybe_owned_string::MaybeOwnedStringDeserializeToOwned;
 
-use super::{error::dispatch::DispatchError, DispatchableTrack, subscribe};
+use super::{error::dispatch::DispatchError, timestamp::TimestampPolicy, DispatchableTrack, subscribe};
 use crate::{data_fetching::AdditionalTrackData, listened::TimeDeltaExtension as _};
 
 const FOUR_MINUTES: chrono::TimeDelta = chrono::TimeDelta::new(4 * 60, 0).unwrap();
@@ -35,6 +35,21 @@ pub struct Config {
     )]
     pub program_info: ProgramInfo<S>,
     pub user_token: Option<brainz::listen::v1::UserToken>,
+    /// When listens are said to have happened.
+    #[serde(default, skip_serializing_if = "TimestampPolicy::is_default")]
+    pub timestamp_policy: TimestampPolicy,
+}
+
+use brainz::listen::v1::submit_listens::InvalidListenError;
+impl From<InvalidListenError> for DispatchError {
+    fn from(error: InvalidListenError) -> Self {
+        match error {
+            InvalidListenError::Missing(field) => Self::missing_required_data(field),
+            InvalidListenError::NullCharacter(_) => Self::invalid_data("track metadata contains a null character"),
+            InvalidListenError::DurationTooLong(_) => Self::invalid_data("track duration exceeds the maximum"),
+            InvalidListenError::HistoricDate(_) => Self::invalid_data("date of listen is too far in the past"),
+        }
+    }
 }
 
 use brainz::listen::v1::submit_listens::ListenSubmissionError;
@@ -44,11 +59,8 @@ impl From<ListenSubmissionError> for DispatchError {
             ListenSubmissionError::NetworkFailure(err) => err.into(),
             ListenSubmissionError::HistoricDateError(_) => Self::invalid_data("date of listen is too far in the past"),
             ListenSubmissionError::InvalidToken(_) => Self::unauthorized(Some("invalid token")),
-            ListenSubmissionError::Ratelimited => todo!("ratelimited"),
-            ListenSubmissionError::Other(status, output) => {
-                tracing::error!(%status, ?output, "unexpected listenbrainz submission error");
-                Self::internal_msg("unexpected listenbrainz submission error", false)
-            }
+            ListenSubmissionError::Ratelimited => Self::ratelimited(),
+            ListenSubmissionError::Other(status, output) => from_unexpected_status(status, &output),
         }
     }
 }
@@ -59,17 +71,45 @@ impl From<CurrentlyPlayingSubmissionError> for DispatchError {
         match error {
             CurrentlyPlayingSubmissionError::NetworkFailure(err) => err.into(),
             CurrentlyPlayingSubmissionError::InvalidToken(_) => Self::unauthorized(Some("invalid token")),
-            CurrentlyPlayingSubmissionError::Ratelimited => todo!("ratelimited"),
-            CurrentlyPlayingSubmissionError::Other(status, output) =>  {
-                tracing::error!(%status, ?output, "unexpected listenbrainz submission error");
-                Self::internal_msg("unexpected listenbrainz submission error", false)
-            }
+            CurrentlyPlayingSubmissionError::Ratelimited => Self::ratelimited(),
+            CurrentlyPlayingSubmissionError::Other(status, output) => from_unexpected_status(status, &output),
         }
     }
 }
 
+/// Maps a response with a status that the client doesn't handle itself.
+///
+/// - <https://listenbrainz.readthedocs.io/en/latest/users/api/index.html#api-error-handling>
+fn from_unexpected_status(status: reqwest::StatusCode, output: &str) -> DispatchError {
+    use super::error::dispatch::{cause::{DataError, RequestError}, Cause, Recovery, RecoveryAttributes};
+
+    #[derive(serde::Deserialize)]
+    struct ErrorBody {
+        error: String,
+    }
+
+    if status == reqwest::StatusCode::BAD_REQUEST {
+        // The listen itself was rejected, such as for missing or malformed metadata; sending it again won't change that.
+        let message = serde_json::from_str::<ErrorBody>(output).map_or_else(|_| output.to_owned(), |body| body.error);
+        return Cause::Data(DataError::Invalid(message.into())).with_recovery(Recovery::Continue(RecoveryAttributes {
+            log: Some(tracing::Level::ERROR),
+            defer: false,
+        }))
+    }
+    if status.is_server_error() {
+        tracing::debug!(%status, ?output, "listenbrainz is unavailable");
+        return Cause::Request(RequestError::Unavailable).with_recovery(Recovery::Continue(RecoveryAttributes {
+            log: Some(tracing::Level::WARN),
+            defer: true,
+        }))
+    }
+    tracing::error!(%status, ?output, "unexpected listenbrainz submission error");
+    DispatchError::internal_msg("unexpected listenbrainz submission error", false)
+}
+
 super::subscription::define_subscriber!(pub ListenBrainz, {
     client: Arc<brainz::listen::v1::Client<S>>,
+    timestamp_policy: TimestampPolicy,
 });
 impl core::fmt::Debug for ListenBrainz {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
@@ -77,56 +117,98 @@ impl core::fmt::Debug for ListenBrainz {
     }
 }
 impl ListenBrainz {
-    pub fn new(program_info: ProgramInfo<MaybeOwnedStringDeserializeToOwned<'static>>, token: brainz::listen::v1::UserToken) -> Self {
-        Self { client: Arc::new(brainz::listen::v1::Client::new(program_info, Some(token))) }
+    pub fn new(program_info: ProgramInfo<MaybeOwnedStringDeserializeToOwned<'static>>, token: brainz::listen::v1::UserToken, timestamp_policy: TimestampPolicy) -> Self {
+        Self {
+            client: Arc::new(brainz::listen::v1::Client::new(crate::net::builder(), program_info, Some(token)).with_retry(crate::net::retry())),
+            timestamp_policy,
+        }
     }
 
-    fn basic_track_metadata(track: &DispatchableTrack) -> Result<brainz::listen::v1::submit_listens::BasicTrackMetadata<'_>, DispatchError> {
-        Ok(brainz::listen::v1::submit_listens::BasicTrackMetadata {
-            artist: track.artist.as_deref().ok_or(DispatchError::missing_required_data("artist name"))?,
-            track: &track.name,
-            release: track.album.as_deref()
-        })
+    fn listen<'a>(track: &'a DispatchableTrack, player_version: &'a str, program: &'a brainz::music::request_client::ProgramInfo<S>) -> brainz::listen::v1::submit_listens::ListenBuilder<'a> {
+        use brainz::listen::v1::submit_listens::{additional_info::*, ListenBuilder};
+        ListenBuilder::new()
+            .with_extra(AdditionalInfo {
+                track_number: track.track_number.map(|n| n.get().into()),
+                submission_client: Some(program),
+                origin_url: track.apple_music_url.as_deref(),
+                music_service: Some(MusicService::Domain("music.apple.com")),
+                media_player: Some(MediaPlayer {
+                    name: "Apple Music", // TODO: #30 (iTunes)
+                    version: Some(player_version)
+                }),
+                ..Default::default()
+            })
+            .artist(track.artist.as_deref())
+            .track(track.name.as_str())
+            .release(track.album.as_deref())
+            .duration(track.duration)
     }
 
-    fn additional_info<'a>(track: &'a DispatchableTrack, player: &'a osa_apple_music::application::ApplicationData, program: &'a brainz::music::request_client::ProgramInfo<S>) -> brainz::listen::v1::submit_listens::additional_info::AdditionalInfo<'a> {
-        use brainz::listen::v1::submit_listens::additional_info::*;
-        AdditionalInfo {
-            duration: track.duration,
-            track_number: track.track_number.map(|n| n.get().into()),
-            submission_client: Some(program),
-            origin_url: track.apple_music_url.as_deref(),
-            music_service: Some(MusicService::Domain("music.apple.com")),
-            media_player: Some(MediaPlayer {
-                name: "Apple Music", // TODO: #30 (iTunes)
-                version: Some(&player.version)
-            }),
-            ..Default::default()
-        }
+    /// - <https://listenbrainz.readthedocs.io/en/latest/users/api/core.html#post--1-submit-listens>
+    fn is_eligible_for_submission(track: &DispatchableTrack, time_listened: chrono::TimeDelta) -> bool {
+        track.duration.is_some_and(|duration| time_listened >= Self::threshold(duration))
     }
 
-    /// - <https://listenbrainz.readthedocs.io/en/latest/users/api/core.html#post--1-submit-listens>
-    async fn is_eligible_for_submission<T>(&self, context: &super::BackendContext<T>) -> bool where T: Send + Sync {
-        if let Some(duration) = context.track.duration {
-            let time_listened = context.listened.lock().await.total_heard();
-            time_listened >= FOUR_MINUTES ||
-            time_listened.as_secs_f32() >= (duration.as_secs_f32() / 2.)
-        } else { false }
+    /// How long a track has to be listened to for before it can be submitted; half of it, or four minutes if that's sooner.
+    fn threshold(duration: core::time::Duration) -> chrono::TimeDelta {
+        chrono::TimeDelta::from_secs_f32(duration.as_secs_f32() / 2.).min(FOUR_MINUTES)
     }
 }
 subscribe!(ListenBrainz, TrackStarted, {
     async fn dispatch(&mut self, context: super::BackendContext<AdditionalTrackData>) -> Result<(), DispatchError> {
-        let track_data = Self::basic_track_metadata(&context.track)?;
-        let additional_info = Self::additional_info(&context.track, &context.player, self.client.get_program_info());
+        let (track_data, additional_info) = Self::listen(&context.track, &context.player.version, self.client.get_program_info()).build()?;
+        if super::intercept_dry_run(Self::NAME, "submit playing now", &(&track_data, &additional_info)) { return Ok(()) }
         self.client.submit_playing_now(track_data, Some(additional_info)).await.map_err(Into::into)
     }
 });
 subscribe!(ListenBrainz, TrackEnded, {
     async fn dispatch(&mut self, context: super::BackendContext<()>) -> Result<(), DispatchError> {
-        if !self.is_eligible_for_submission(&context).await { return Ok(()) }
-        let track_data = Self::basic_track_metadata(&context.track)?;
-        let additional_info = Self::additional_info(&context.track, &context.player, self.client.get_program_info());
-        let started_listening_at = context.listened.lock().await.started_at().ok_or(DispatchError::missing_required_data("listen start time"))?;
-        self.client.submit_listen(track_data, started_listening_at, Some(additional_info)).await.map_err(Into::into)
+        let threshold = context.track.duration.map_or(FOUR_MINUTES, Self::threshold);
+        let (total, listened_at) = {
+            let listened = context.listened.lock().await;
+            (listened.total(), self.timestamp_policy.of_ended(&listened, threshold))
+        };
+        if !Self::is_eligible_for_submission(&context.track, total) { return Ok(()) }
+        let listened_at = listened_at.ok_or(DispatchError::missing_required_data("listen start time"))?;
+        let listen = Self::listen(&context.track, &context.player.version, self.client.get_program_info()).build_listen(listened_at)?;
+        if super::intercept_dry_run(Self::NAME, "submit listen", &listen) { return Ok(()) }
+        self.client.submit_listen(listen.track, listen.time, listen.extra).await.map_err(Into::into)
     }
 });
+subscribe!(ListenBrainz, DeferredReplay, {
+    async fn dispatch(&mut self, listens: super::DeferredListens) -> Result<(), DispatchError> {
+        let program = self.client.get_program_info();
+        let listens = listens.iter().filter(|listen| Self::is_eligible_for_submission(&listen.track, listen.listened)).filter_map(|listen| {
+            let time = self.timestamp_policy.of_deferred(listen, listen.track.duration.map_or(FOUR_MINUTES, Self::threshold));
+            Self::listen(&listen.track, &listen.player_version, program).build_listen(time)
+                .inspect_err(|error| tracing::warn!(?error, persistent_id = %listen.track.persistent_id, "dropping deferred listen"))
+                .ok()
+        }).collect::<Vec<_>>();
+        if listens.is_empty() { return Ok(()) }
+        if super::intercept_dry_run(Self::NAME, "import listens", &listens) { return Ok(()) }
+        self.client.import_listens(listens).await.map_err(Into::into)
+    }
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::error::dispatch::{cause::{DataError, RequestError}, Cause};
+
+    #[test]
+    fn submission_errors() {
+        let error = DispatchError::from(ListenSubmissionError::Ratelimited);
+        assert!(matches!(error.cause, Cause::Request(RequestError::Ratelimited)) && error.recovery.defer());
+
+        let error = DispatchError::from(ListenSubmissionError::InvalidToken(brainz::listen::v1::error::InvalidTokenError));
+        assert!(matches!(error.cause, Cause::Request(RequestError::Unauthorized(_))) && error.recovery.defer());
+
+        let output = r#"{"code": 400, "error": "JSON document must contain either artist_mbids or artist_name"}"#;
+        let error = DispatchError::from(ListenSubmissionError::Other(reqwest::StatusCode::BAD_REQUEST, output.to_owned()));
+        assert!(matches!(&error.cause, Cause::Data(DataError::Invalid(message)) if message.starts_with("JSON document")));
+        assert!(!error.recovery.defer(), "a rejected listen would just be rejected again");
+
+        let error = DispatchError::from(CurrentlyPlayingSubmissionError::Other(reqwest::StatusCode::SERVICE_UNAVAILABLE, String::new()));
+        assert!(matches!(error.cause, Cause::Request(RequestError::Unavailable)) && error.recovery.defer());
+    }
+}
@@ -0,0 +1,160 @@
+//! Scrobbling to a self-hosted [Maloja](https://github.com/krateng/maloja) server through its native API.
+//!
+//! ```toml
+//! [backends.maloja]
+//! enabled = true
+//! url = "https://maloja.example.com"
+//! api_key = "..."
+//! ```
+use std::sync::Arc;
+
+use chrono::TimeDelta;
+
+use super::{error::dispatch::DispatchError, timestamp::TimestampPolicy, DispatchableTrack, subscribe};
+use crate::listened::TimeDeltaExtension as _;
+
+const FOUR_MINUTES: TimeDelta = TimeDelta::new(4 * 60, 0).unwrap();
+const THIRTY_SECONDS: core::time::Duration = core::time::Duration::new(30, 0);
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct Config {
+    pub enabled: bool,
+    /// Where the server is hosted, such as `https://maloja.example.com`.
+    pub url: String,
+    pub api_key: Option<maloja::ApiKey>,
+    /// When scrobbles are said to have happened.
+    #[serde(default, skip_serializing_if = "TimestampPolicy::is_default")]
+    pub timestamp_policy: TimestampPolicy,
+}
+
+impl From<maloja::Error> for DispatchError {
+    fn from(error: maloja::Error) -> Self {
+        use super::error::dispatch::{cause::{DataError, RequestError}, Cause, Recovery, RecoveryAttributes};
+        use maloja::Error;
+        match error {
+            Error::Network(error) => error.into(),
+            Error::Deserialization(error) => error.into(),
+            Error::InvalidUrl(_) => Self::internal_msg("invalid maloja server url", true),
+            Error::Unauthorized(reason) => Cause::Request(RequestError::Unauthorized(Some(reason.into()))).with_recovery(Self::unauthorized(None).recovery),
+            // Sending it again won't change the server's mind.
+            Error::Rejected { description, .. } => Cause::Data(DataError::Invalid(description.into())).with_recovery(Recovery::Continue(RecoveryAttributes {
+                log: Some(tracing::Level::ERROR),
+                defer: false,
+            })),
+            Error::Unexpected { status, .. } if status == reqwest::StatusCode::TOO_MANY_REQUESTS => Self::ratelimited(),
+            Error::Unexpected { status, body } if status.is_server_error() => {
+                tracing::debug!(%status, ?body, "maloja is unavailable");
+                Cause::Request(RequestError::Unavailable).with_recovery(Recovery::Continue(RecoveryAttributes {
+                    log: Some(tracing::Level::WARN),
+                    defer: true,
+                }))
+            },
+            Error::Unexpected { status, body } => {
+                tracing::error!(%status, ?body, "unexpected maloja scrobble error");
+                Self::internal_msg("unexpected maloja scrobble error", false)
+            },
+        }
+    }
+}
+
+super::subscription::define_subscriber!(pub Maloja, {
+    client: Arc<maloja::Client>,
+    timestamp_policy: TimestampPolicy,
+});
+impl core::fmt::Debug for Maloja {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct(Self::NAME).finish()
+    }
+}
+impl Maloja {
+    pub fn new(url: &str, key: maloja::ApiKey, timestamp_policy: TimestampPolicy) -> Result<Self, maloja::Error> {
+        Ok(Self {
+            client: Arc::new(maloja::Client::new(url, key, crate::net::client())?.with_retry(crate::net::retry())),
+            timestamp_policy,
+        })
+    }
+
+    fn scrobble(track: &DispatchableTrack, listened: TimeDelta, time: chrono::DateTime<chrono::Utc>) -> Result<maloja::scrobble::Scrobble<'_>, DispatchError> {
+        let seconds = |seconds: u64| u32::try_from(seconds).unwrap_or(u32::MAX);
+        let artist = track.artist.as_deref().ok_or(DispatchError::missing_required_data("artist"))?;
+        Ok(maloja::scrobble::Scrobble {
+            artists: vec![artist],
+            title: &track.name,
+            album: track.album.as_deref(),
+            albumartists: track.album_artist.as_deref().filter(|album_artist| *album_artist != artist).into_iter().collect(),
+            duration: u64::try_from(listened.num_seconds()).ok().map(seconds),
+            length: track.duration.map(|duration| seconds(duration.as_secs())),
+            time,
+        })
+    }
+
+    fn is_eligible(track: &DispatchableTrack, time_listened: TimeDelta) -> bool {
+        if let Some(duration) = track.duration {
+            if duration < THIRTY_SECONDS { return false }
+            time_listened >= Self::threshold(duration)
+        } else { false }
+    }
+
+    /// How long a track has to be listened to for before it can be scrobbled; half of it, or four minutes if that's sooner.
+    fn threshold(duration: core::time::Duration) -> TimeDelta {
+        TimeDelta::from_secs_f32(duration.as_secs_f32() / 2.).min(FOUR_MINUTES)
+    }
+
+    async fn submit(&self, scrobble: &maloja::scrobble::Scrobble<'_>) -> Result<(), DispatchError> {
+        let acknowledgement = self.client.scrobble(scrobble).await?;
+        for warning in acknowledgement.warnings {
+            tracing::warn!(kind = warning.kind, description = ?warning.desc, "maloja accepted scrobble with a warning");
+        }
+        Ok(())
+    }
+}
+subscribe!(Maloja, TrackEnded, {
+    async fn dispatch(&mut self, context: super::BackendContext<()>) -> Result<(), DispatchError> {
+        let threshold = context.track.duration.map_or(FOUR_MINUTES, Self::threshold);
+        let (total, time) = {
+            let listened = context.listened.lock().await;
+            (listened.total(), self.timestamp_policy.of_ended(&listened, threshold))
+        };
+        if !Self::is_eligible(&context.track, total) { return Ok(()) }
+        let time = time.ok_or(DispatchError::missing_required_data("listen start time"))?;
+        let scrobble = Self::scrobble(&context.track, total, time)?;
+        if super::intercept_dry_run(Self::NAME, "scrobble", &scrobble) { return Ok(()) }
+        self.submit(&scrobble).await
+    }
+});
+subscribe!(Maloja, DeferredReplay, {
+    async fn dispatch(&mut self, listens: super::DeferredListens) -> Result<(), DispatchError> {
+        let scrobbles = listens.iter().filter(|listen| Self::is_eligible(&listen.track, listen.listened)).filter_map(|listen| {
+            let time = self.timestamp_policy.of_deferred(listen, listen.track.duration.map_or(FOUR_MINUTES, Self::threshold));
+            Self::scrobble(&listen.track, listen.listened, time)
+                .inspect_err(|error| tracing::warn!(?error, persistent_id = %listen.track.persistent_id, "dropping deferred scrobble"))
+                .ok()
+        }).collect::<Vec<_>>();
+        if scrobbles.is_empty() { return Ok(()) }
+        if super::intercept_dry_run(Self::NAME, "scrobble deferred", &scrobbles) { return Ok(()) }
+        // There's no endpoint for submitting more than one at once.
+        for scrobble in &scrobbles {
+            self.submit(scrobble).await?;
+        }
+        Ok(())
+    }
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::error::dispatch::{cause::{DataError, RequestError}, Cause};
+
+    #[test]
+    fn errors() {
+        let error = DispatchError::from(maloja::Error::Unauthorized("Invalid or missing authentication".to_owned()));
+        assert!(matches!(error.cause, Cause::Request(RequestError::Unauthorized(Some(_)))) && error.recovery.defer());
+
+        let error = DispatchError::from(maloja::Error::Rejected { kind: "missing_scrobble_data".to_owned(), description: "no title".to_owned() });
+        assert!(matches!(&error.cause, Cause::Data(DataError::Invalid(message)) if message.as_ref() == "no title"));
+        assert!(!error.recovery.defer(), "a rejected scrobble would just be rejected again");
+
+        let error = DispatchError::from(maloja::Error::Unexpected { status: reqwest::StatusCode::SERVICE_UNAVAILABLE, body: String::new() });
+        assert!(matches!(error.cause, Cause::Request(RequestError::Unavailable)) && error.recovery.defer());
+    }
+}
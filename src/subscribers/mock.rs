@@ -0,0 +1,342 @@
+//! A backend which sends nothing anywhere, instead keeping a [log](Mock::log) of every event it receives.
+//!
+//! Faults can be [injected](Mock::inject) into its handling of upcoming events, so that what's done about a
+//! backend failing (recovery, deferral, replay) can be tested without any real backend having to fail.
+//!
+//! It's never configured; whatever's testing against it puts it in place on the [backends](super::Backends) itself.
+//! Every clone shares the same log and faults, so a clone can be kept to inspect it afterwards.
+#![cfg_attr(not(test), allow(dead_code, reason = "only put to use by tests"))]
+
+use alloc::sync::Arc;
+
+use super::{error::DispatchError, subscribe, subscription::Identity, BackendContext, DeferredListens, DispatchableTrack, DispatchedApplicationStatus, DispatchedPlayerStatus, DispatchedVolume, SubscriberTerminationCause};
+use crate::data_fetching::AdditionalTrackData;
+
+/// An event the mock received, along with what it was given.
+#[derive(Debug, Clone)]
+pub enum Received {
+    TrackStarted(Arc<DispatchableTrack>),
+    DataEnriched(Arc<DispatchableTrack>),
+    TrackEnded(Arc<DispatchableTrack>),
+    ProgressJolt(Arc<DispatchableTrack>),
+    PlayerStatusUpdate(DispatchedPlayerStatus),
+    VolumeChanged(DispatchedVolume),
+    ApplicationStatusUpdate(DispatchedApplicationStatus),
+    ListeningSessionEnded(player_events::ListeningSession),
+    ImminentSubscriberTermination(SubscriberTerminationCause),
+    DeferredReplay(DeferredListens),
+}
+impl Received {
+    pub const fn event(&self) -> Identity {
+        match self {
+            Self::TrackStarted(_) => Identity::TrackStarted,
+            Self::DataEnriched(_) => Identity::DataEnriched,
+            Self::TrackEnded(_) => Identity::TrackEnded,
+            Self::ProgressJolt(_) => Identity::ProgressJolt,
+            Self::PlayerStatusUpdate(_) => Identity::PlayerStatusUpdate,
+            Self::VolumeChanged(_) => Identity::VolumeChanged,
+            Self::ApplicationStatusUpdate(_) => Identity::ApplicationStatusUpdate,
+            Self::ListeningSessionEnded(_) => Identity::ListeningSessionEnded,
+            Self::ImminentSubscriberTermination(_) => Identity::ImminentSubscriberTermination,
+            Self::DeferredReplay(_) => Identity::DeferredReplay,
+        }
+    }
+
+    /// The track the event was for, if it was for one.
+    pub fn track(&self) -> Option<&DispatchableTrack> {
+        match self {
+            Self::TrackStarted(track) |
+            Self::DataEnriched(track) |
+            Self::TrackEnded(track) |
+            Self::ProgressJolt(track) => Some(track),
+            _ => None
+        }
+    }
+}
+
+/// Something going wrong while handling an event.
+#[derive(Debug, Clone, Copy)]
+pub enum Fault {
+    /// Fails with the error that's made (e.g. [`DispatchError::offline`]).
+    Error(fn() -> DispatchError),
+    /// Takes this long before succeeding.
+    Delay(core::time::Duration),
+    /// Panics, as if the backend had a bug.
+    Panic,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    log: Vec<Received>,
+    /// Each is used up by the next event of its kind.
+    faults: Vec<(Identity, Fault)>,
+}
+
+super::subscription::define_subscriber!(#[derive(Debug, Clone, Default)] pub Mock, {
+    state: Arc<std::sync::Mutex<State>>,
+});
+impl Mock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        // Never held while panicking, so it can't have been poisoned.
+        self.state.lock().expect("mock state was poisoned")
+    }
+
+    /// Makes the next `event` that's received go wrong. Faults for the same event are used up in the order they were injected.
+    pub fn inject(&self, event: Identity, fault: Fault) {
+        self.state().faults.push((event, fault));
+    }
+
+    /// Everything received so far, oldest first; including events which [went wrong](Self::inject).
+    pub fn log(&self) -> Vec<Received> {
+        self.state().log.clone()
+    }
+
+    /// The kind of everything received so far, oldest first.
+    pub fn events(&self) -> Vec<Identity> {
+        self.state().log.iter().map(Received::event).collect()
+    }
+
+    async fn receive(&self, received: Received) -> Result<(), DispatchError> {
+        let event = received.event();
+        let fault = {
+            let mut state = self.state();
+            state.log.push(received);
+            state.faults.iter().position(|(faulty, _)| *faulty == event).map(|index| state.faults.remove(index).1)
+        };
+        match fault {
+            None => Ok(()),
+            Some(Fault::Error(error)) => Err(error()),
+            Some(Fault::Delay(duration)) => {
+                tokio::time::sleep(duration).await;
+                Ok(())
+            },
+            Some(Fault::Panic) => panic!("injected panic while handling {event:?}"),
+        }
+    }
+}
+
+macro_rules! record {
+    ($($event: ident($context: ident: $type: ty) => $received: expr),* $(,)?) => {
+        $(
+            subscribe!(Mock, $event, {
+                async fn dispatch(&mut self, $context: $type) -> Result<(), DispatchError> {
+                    self.receive($received).await
+                }
+            });
+        )*
+    };
+}
+
+record! {
+    TrackStarted(context: BackendContext<AdditionalTrackData>) => Received::TrackStarted(context.track),
+    DataEnriched(context: BackendContext<AdditionalTrackData>) => Received::DataEnriched(context.track),
+    TrackEnded(context: BackendContext<()>) => Received::TrackEnded(context.track),
+    ProgressJolt(context: BackendContext<()>) => Received::ProgressJolt(context.track),
+    PlayerStatusUpdate(status: DispatchedPlayerStatus) => Received::PlayerStatusUpdate(status),
+    VolumeChanged(volume: DispatchedVolume) => Received::VolumeChanged(volume),
+    ApplicationStatusUpdate(status: DispatchedApplicationStatus) => Received::ApplicationStatusUpdate(status),
+    ListeningSessionEnded(session: player_events::ListeningSession) => Received::ListeningSessionEnded(session),
+    ImminentSubscriberTermination(cause: SubscriberTerminationCause) => Received::ImminentSubscriberTermination(cause),
+    DeferredReplay(listens: DeferredListens) => Received::DeferredReplay(listens),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::Mutex;
+    use crate::{listened::Listened, store::{entities::Key, types::StoredPersistentId}, subscribers::{error::dispatch::{Cause, Recovery}, BackendIdentity, Backends, DeferredListen}};
+
+    /// Dispatching publishes events, so this holds on to [exclusivity](crate::service::ipc::events::EXCLUSIVE) too.
+    async fn backends(mock: &Mock) -> (tokio::sync::MutexGuard<'static, ()>, Backends) {
+        use crate::config::{Config, ConfigurableBackends};
+        let exclusive = crate::service::ipc::events::EXCLUSIVE.lock().await;
+        let config = Config {
+            backends: ConfigurableBackends {
+                #[cfg(feature = "discord")]
+                discord: None,
+                #[cfg(feature = "lastfm")]
+                lastfm: None,
+                #[cfg(feature = "listenbrainz")]
+                listenbrainz: None,
+                #[cfg(feature = "notifications")]
+                notifications: None,
+                #[cfg(feature = "mqtt")]
+                mqtt: None,
+                #[cfg(feature = "maloja")]
+                maloja: None,
+            },
+            ..Default::default()
+        };
+        let mut backends = Backends::new(&config, false, tokio::sync::mpsc::channel(1).0).await;
+        backends.mock = Some(Arc::new(Mutex::new(mock.clone())));
+        (exclusive, backends)
+    }
+
+    fn context(name: &str, persistent_id: u64) -> BackendContext<()> {
+        BackendContext {
+            track: Arc::new(DispatchableTrack::from(player_events::Track {
+                name: name.to_owned(),
+                album: None,
+                album_artist: None,
+                artist: Some("Someone".to_owned()),
+                persistent_id: StoredPersistentId::new(persistent_id),
+                duration: Some(core::time::Duration::from_mins(3)),
+                media_kind: player_events::MediaKind::Song,
+                track_number: None,
+                apple_music_url: None,
+                genre: None,
+                composer: None,
+                work: None,
+                movement: None,
+            })),
+            player: Arc::new(crate::player::fake::FakePlayer::application_data("playing", Some(0.))),
+            data: Arc::new(()),
+            listened: Arc::new(Mutex::new(Listened::new_with_current(0.))),
+            #[cfg(feature = "musicdb")]
+            musicdb: Arc::new(None),
+        }
+    }
+
+    fn replayed(received: &Received) -> Vec<String> {
+        match received {
+            Received::DeferredReplay(listens) => listens.iter().map(|listen| listen.track.name.clone()).collect(),
+            other => panic!("expected a replay, got {other:?}")
+        }
+    }
+
+    #[tokio::test]
+    async fn records_every_event() {
+        let mock = Mock::new();
+        let (_exclusive, backends) = backends(&mock).await;
+        backends.dispatch_status(DispatchedPlayerStatus::Playing, None).await;
+        backends.dispatch_volume(DispatchedVolume { level: 40, muted: false }).await;
+        backends.dispatch_application_status(DispatchedApplicationStatus { version: Arc::from("1.6.0.1"), previous_version: Some(Arc::from("1.5.5.3")) }).await;
+        backends.dispatch_current_progress(context("First", 1)).await;
+        backends.dispatch_track_ended(context("First", 1)).await;
+        backends.dispatch_listening_session_ended(player_events::ListeningSession { started: 0, ended: 600_000, tracks: 2, listened: 540. }).await;
+        backends.dispatch_imminent_program_termination(tokio::signal::unix::SignalKind::terminate()).await;
+
+        assert_eq!(mock.events(), [
+            Identity::PlayerStatusUpdate,
+            Identity::VolumeChanged,
+            Identity::ApplicationStatusUpdate,
+            Identity::ProgressJolt,
+            Identity::TrackEnded,
+            Identity::ListeningSessionEnded,
+            Identity::ImminentSubscriberTermination,
+        ]);
+        assert!(matches!(mock.log()[1], Received::VolumeChanged(DispatchedVolume { level: 40, muted: false })));
+        assert!(matches!(&mock.log()[2], Received::ApplicationStatusUpdate(status) if status.previous_version.as_deref() == Some("1.5.5.3")));
+        assert_eq!(mock.log()[4].track().map(|track| track.name.as_str()), Some("First"));
+        assert!(matches!(&mock.log()[5], Received::ListeningSessionEnded(session) if session.tracks == 2));
+        assert!(matches!(&mock.log()[6], Received::ImminentSubscriberTermination(cause) if *cause == tokio::signal::unix::SignalKind::terminate().into()));
+    }
+
+    #[tokio::test]
+    async fn deferred_listens_are_replayed_once_dispatch_succeeds() {
+        let mock = Mock::new();
+        let (_exclusive, backends) = backends(&mock).await;
+        mock.inject(Identity::TrackEnded, Fault::Error(DispatchError::offline));
+        mock.inject(Identity::TrackEnded, Fault::Error(DispatchError::offline));
+        backends.dispatch_track_ended(context("First", 1)).await;
+        backends.dispatch_track_ended(context("Second", 2)).await;
+        backends.dispatch_track_ended(context("Third", 3)).await;
+        backends.dispatch_track_ended(context("Fourth", 4)).await;
+
+        assert_eq!(mock.events(), [
+            Identity::TrackEnded,
+            Identity::TrackEnded,
+            Identity::TrackEnded,
+            Identity::DeferredReplay,
+            Identity::TrackEnded,
+        ]);
+        assert_eq!(replayed(&mock.log()[3]), ["First", "Second"]);
+    }
+
+    #[tokio::test]
+    async fn failed_replays_are_deferred_again() {
+        let mock = Mock::new();
+        let (_exclusive, backends) = backends(&mock).await;
+        mock.inject(Identity::TrackEnded, Fault::Error(DispatchError::offline));
+        mock.inject(Identity::DeferredReplay, Fault::Error(DispatchError::offline));
+        backends.dispatch_track_ended(context("First", 1)).await;
+        backends.dispatch_track_ended(context("Second", 2)).await;
+        backends.dispatch_track_ended(context("Third", 3)).await;
+
+        let log = mock.log();
+        assert_eq!(replayed(&log[2]), ["First"]);
+        assert_eq!(replayed(&log[4]), ["First"]);
+    }
+
+    #[tokio::test]
+    async fn errors_which_should_not_be_deferred_are_not_replayed() {
+        let mock = Mock::new();
+        let (_exclusive, backends) = backends(&mock).await;
+        mock.inject(Identity::TrackEnded, Fault::Error(|| DispatchError::invalid_data("duration")));
+        backends.dispatch_track_ended(context("First", 1)).await;
+        backends.dispatch_track_ended(context("Second", 2)).await;
+
+        assert_eq!(mock.events(), [Identity::TrackEnded, Identity::TrackEnded]);
+    }
+
+    #[tokio::test]
+    async fn journaled_listens_are_held_until_replayed() {
+        let mock = Mock::new();
+        let (_exclusive, backends) = backends(&mock).await;
+        let entry = Key::from(1);
+        backends.defer(BackendIdentity::Mock, DeferredListen {
+            track: context("First", 1).track,
+            player_version: Arc::from("1.6.0.1"),
+            started_at: chrono::Utc::now(),
+            listened: chrono::TimeDelta::minutes(3),
+            journaled: Some(entry),
+        }).await;
+        assert!(backends.holds(entry).await);
+
+        mock.inject(Identity::DeferredReplay, Fault::Error(DispatchError::offline));
+        backends.dispatch_track_ended(context("Second", 2)).await;
+        assert!(backends.holds(entry).await, "a failed replay should keep holding on to it");
+
+        backends.dispatch_track_ended(context("Third", 3)).await;
+        assert!(!backends.holds(entry).await);
+    }
+
+    #[tokio::test]
+    async fn panicking_disables_the_backend() {
+        let mock = Mock::new();
+        let (_exclusive, backends) = backends(&mock).await;
+        mock.inject(Identity::PlayerStatusUpdate, Fault::Panic);
+        backends.dispatch_status(DispatchedPlayerStatus::Playing, None).await;
+        backends.dispatch_status(DispatchedPlayerStatus::Paused, None).await;
+        backends.dispatch_current_progress(context("First", 1)).await;
+
+        assert!(matches!(mock.log()[..], [Received::PlayerStatusUpdate(DispatchedPlayerStatus::Playing)]));
+        assert!(backends.all().is_empty());
+    }
+
+    #[tokio::test]
+    async fn dispatch_waits_for_slow_backends() {
+        let mock = Mock::new();
+        let (_exclusive, backends) = backends(&mock).await;
+        let delay = core::time::Duration::from_millis(50);
+        mock.inject(Identity::ProgressJolt, Fault::Delay(delay));
+        let started = std::time::Instant::now();
+        backends.dispatch_current_progress(context("First", 1)).await;
+
+        assert!(started.elapsed() >= delay);
+        assert_eq!(mock.events(), [Identity::ProgressJolt]);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "dispatch resulted in fatal error")]
+    async fn critical_failures_are_fatal() {
+        let mock = Mock::new();
+        let (_exclusive, backends) = backends(&mock).await;
+        mock.inject(Identity::PlayerStatusUpdate, Fault::Error(|| Cause::internal("broken").with_recovery(Recovery::CriticallyFail)));
+        backends.dispatch_status(DispatchedPlayerStatus::Playing, None).await;
+    }
+}
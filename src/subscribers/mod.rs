@@ -1,9 +1,10 @@
-use alloc::sync::Arc;
+use alloc::{borrow::Cow, sync::Arc};
 use maybe_owned_string::MaybeOwnedString;
 use tokio::sync::Mutex;
 use serde::{Serialize, Deserialize};
 
 use crate::data_fetching::components::ComponentSolicitation;
+use crate::service::ipc::events;
 use crate::store::types::StoredPersistentId;
 
 use error::dispatch::DispatchError;
@@ -98,6 +99,9 @@ pub mod error {
                 /// A response was received, but it indicated that the backend is currently unavailable.
                 #[error("service unavailable")]
                 Unavailable,
+                /// The backend refused the request because too many have been made recently.
+                #[error("ratelimited")]
+                Ratelimited,
                 /// Couldn't connect to the backend; likely because the user's network is offline.
                 #[error("connection failure")]
                 ConnectionFailure,
@@ -312,6 +316,29 @@ pub mod error {
                     }
                 }
             }
+
+            /// The backend is refusing requests until some have stopped being made for a bit.
+            /// It's likely to accept them again soon, so whatever was being submitted is deferred until then.
+            pub const fn ratelimited() -> Self {
+                Self {
+                    cause: Cause::Request(cause::RequestError::Ratelimited),
+                    recovery: Recovery::Continue(RecoveryAttributes {
+                        log: Some(tracing::Level::WARN),
+                        defer: true
+                    })
+                }
+            }
+
+            /// The dispatch wasn't attempted since the network is [offline](crate::connectivity), and so it'd just fail.
+            pub const fn offline() -> Self {
+                Self {
+                    cause: Cause::Request(cause::RequestError::ConnectionFailure),
+                    recovery: Recovery::Continue(RecoveryAttributes {
+                        log: Some(tracing::Level::DEBUG),
+                        defer: true
+                    })
+                }
+            }
         }
         impl From<reqwest::Error> for DispatchError {
             fn from(error: reqwest::Error) -> Self {
@@ -340,31 +367,39 @@ pub mod error {
 
 
 macro_rules! use_backends {
-    ([ $(($name: ident, $ident: ident, $feature: literal, $id: literal)$(,)?)* ]) => {
+    ([ $(($name: ident, $ident: ident, $cfg: meta, $id: literal)$(,)?)* ]) => {
         type BackendIdentityIndex = u8;
 
         pub const MAX_ENABLED_BACKEND_COUNT: BackendIdentityIndex = {
             $(
                 ({
-                    #[cfg(feature = $feature)]
+                    #[cfg($cfg)]
                     { 1 }
-                    #[cfg(not(feature = $feature))]
+                    #[cfg(not($cfg))]
                     { 0 }
                 }) +
             )* 0
         };
+        /// One past the highest [holey index](BackendIdentity::get_holey_index), whether or not that backend is compiled in.
+        const HOLEY_INDEX_BOUND: BackendIdentityIndex = {
+            let mut bound = 0;
+            $(
+                if $id >= bound { bound = $id + 1; }
+            )*
+            bound
+        };
 
         $(
-            #[cfg(feature = $feature)]
+            #[cfg($cfg)]
             pub mod $name;
         )*
 
         #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-        #[cfg_attr(any($(feature = $feature),*), derive(enum_bitset::EnumBitset))]
-        #[cfg_attr(any($(feature = $feature),*), bitset(name = BackendIdentitySet))]
+        #[cfg_attr(any($($cfg),*), derive(enum_bitset::EnumBitset))]
+        #[cfg_attr(any($($cfg),*), bitset(name = BackendIdentitySet))]
         pub enum BackendIdentity {
             $(
-                #[cfg(feature = $feature)]
+                #[cfg($cfg)]
                 $ident,
             )*
         }
@@ -372,15 +407,33 @@ macro_rules! use_backends {
             pub const fn get_name(self) -> &'static str {
                 match self {
                     $(
-                        #[cfg(feature = $feature)]
+                        #[cfg($cfg)]
                         Self::$ident => stringify!($ident),
                     )*
                 }
             }
+            /// The key of the backend in the configuration.
+            pub const fn get_key(self) -> &'static str {
+                match self {
+                    $(
+                        #[cfg($cfg)]
+                        Self::$ident => stringify!($name),
+                    )*
+                }
+            }
+            pub fn from_key(key: &str) -> Option<Self> {
+                match key {
+                    $(
+                        #[cfg($cfg)]
+                        stringify!($name) => Some(Self::$ident),
+                    )*
+                    _ => None
+                }
+            }
             pub const fn get_holey_index(self) -> BackendIdentityIndex {
                 match self {
                     $(
-                        #[cfg(feature = $feature)]
+                        #[cfg($cfg)]
                         Self::$ident => $id,
                     )*
                 }
@@ -388,7 +441,7 @@ macro_rules! use_backends {
             pub const fn from_holey_index(index: BackendIdentityIndex) -> Option<Self> {
                 match index {
                     $(
-                        #[cfg(feature = $feature)]
+                        #[cfg($cfg)]
                         $id => Some(Self::$ident),
                     )*
                     _ => None
@@ -396,13 +449,36 @@ macro_rules! use_backends {
             }
         }
 
-        #[cfg(not(any($(feature = $feature),*)))]
+        #[cfg(not(any($($cfg),*)))]
         crate::util::define_empty_set!(BackendIdentitySet, BackendIdentity);
 
+        impl BackendIdentitySet {
+            /// The [keys](BackendIdentity::get_key) of the backends in the set, separated by spaces.
+            pub fn to_keys(self) -> String {
+                #[allow(unused_mut, reason = "not mutated when compiled without features")]
+                let mut keys: Vec<&'static str> = Vec::new();
+                $(
+                    #[cfg($cfg)]
+                    if self.contains(BackendIdentity::$ident) {
+                        keys.push(stringify!($name));
+                    }
+                )*
+                keys.join(" ")
+            }
+            /// The inverse of [`Self::to_keys`]; keys of backends which aren't compiled in are ignored.
+            pub fn from_keys(keys: &str) -> Self {
+                let mut set = Self::empty();
+                for identity in keys.split_whitespace().filter_map(BackendIdentity::from_key) {
+                    set.insert(identity);
+                }
+                set
+            }
+        }
+
         #[derive(Debug)]
         pub struct BackendMap<T> {
             $(
-                #[cfg(feature = $feature)]
+                #[cfg($cfg)]
                 pub $name: Option<T>,
             )*
 
@@ -417,7 +493,7 @@ macro_rules! use_backends {
             pub const fn new() -> Self {
                 Self {
                     $(
-                        #[cfg(feature = $feature)]
+                        #[cfg($cfg)]
                         $name: None,
                     )*
 
@@ -425,14 +501,14 @@ macro_rules! use_backends {
                 }
             }
 
-            pub fn iter(&'a self) -> iter::BackendMapIterator<'a, T> {
-                self.into_iter()
+            pub const fn iter(&'a self) -> iter::BackendMapIterator<'a, T> {
+                iter::BackendMapIterator { inner: self, index: 0 }
             }
 
             pub const fn take(&mut self, identity: BackendIdentity) -> Option<T> {
                 match identity {
                     $(
-                        #[cfg(feature = $feature)]
+                        #[cfg($cfg)]
                         BackendIdentity::$ident => self.$name.take(),
                     )*
                 }
@@ -443,7 +519,7 @@ macro_rules! use_backends {
             fn index(&self, index: BackendIdentity) -> &Self::Output {
                 match index {
                     $(
-                        #[cfg(feature = $feature)]
+                        #[cfg($cfg)]
                         BackendIdentity::$ident => &self.$name,
                     )*
                 }
@@ -453,7 +529,7 @@ macro_rules! use_backends {
             fn index_mut(&mut self, index: BackendIdentity) -> &mut Self::Output {
                 match index {
                     $(
-                        #[cfg(feature = $feature)]
+                        #[cfg($cfg)]
                         BackendIdentity::$ident => &mut self.$name,
                     )*
                 }
@@ -464,13 +540,13 @@ macro_rules! use_backends {
             use super::*;
 
             pub struct BackendMapIterator<'a, T> {
-                inner: &'a BackendMap<T>,
-                index: BackendIdentityIndex,
+                pub(super) inner: &'a BackendMap<T>,
+                pub(super) index: BackendIdentityIndex,
             }
             impl<'a, T> Iterator for BackendMapIterator<'a, T> {
                 type Item = (BackendIdentity, &'a Option<T>);
                 fn next(&mut self) -> Option<Self::Item> {                
-                    while self.index < MAX_ENABLED_BACKEND_COUNT {
+                    while self.index < HOLEY_INDEX_BOUND {
                         let index = self.index;
                         let identity = BackendIdentity::from_holey_index(index);
                         self.index += 1;
@@ -508,7 +584,7 @@ macro_rules! use_backends {
             impl<T> Iterator for BackendMapIntoIterator<T> {
                 type Item = (BackendIdentity, Option<T>);
                 fn next(&mut self) -> Option<Self::Item> {
-                    while self.index < MAX_ENABLED_BACKEND_COUNT {
+                    while self.index < HOLEY_INDEX_BOUND {
                         let index = self.index;
                         let identity = BackendIdentity::from_holey_index(index);
                         self.index += 1;
@@ -523,18 +599,26 @@ macro_rules! use_backends {
 
         pub struct Backends {
             $(
-                #[cfg(feature = $feature)]
+                #[cfg($cfg)]
                 pub $name: Option<Arc<Mutex<$name::$ident>>>,
             )*
+            normalization: normalize::Config,
+            /// The listens which each backend couldn't submit when they ended, waiting to be [replayed](Self::replay_deferred).
+            deferred: Mutex<BackendMap<Vec<DeferredListen>>>,
+            /// Those which have been [disabled](Self::disable), and so are never dispatched to again.
+            disabled: std::sync::Mutex<BackendIdentitySet>,
         }
         impl Backends {
             pub fn all(&self) -> Vec<Arc<Mutex<dyn Subscriber>>> {
                 #[allow(unused_mut, reason = "not mutated when compiled without features")]
                 let mut backends: Vec<Arc<Mutex<dyn Subscriber>>> = Vec::with_capacity(MAX_ENABLED_BACKEND_COUNT as usize);
         
+                #[allow(unused_variables, reason = "not used when compiled without features")]
+                let disabled = self.disabled();
                 $(
-                    #[cfg(feature = $feature)]
-                    if let Some(backend) = self.$name.as_ref() {
+                    #[cfg($cfg)]
+                    if let Some(backend) = self.$name.as_ref()
+                    && !disabled.contains(BackendIdentity::$ident) {
                         backends.push(backend.clone());
                     }
                 )*
@@ -543,9 +627,10 @@ macro_rules! use_backends {
             }
             #[expect(unused, reason = "may be useful in the future")]
             pub fn get(&self, identity: BackendIdentity) -> Option<Arc<Mutex<dyn Subscriber>>> {
+                if self.disabled().contains(identity) { return None }
                 match identity {
                     $(
-                        #[cfg(feature = $feature)]
+                        #[cfg($cfg)]
                         BackendIdentity::$ident => self.$name.as_ref().map(|b| b.clone() as Arc<Mutex<dyn Subscriber>>),
                     )*
                 }
@@ -553,10 +638,12 @@ macro_rules! use_backends {
             pub fn get_many(&self, identities: BackendIdentitySet) -> Vec<Arc<Mutex<dyn Subscriber>>> {
                 #[allow(unused_mut, reason = "not mutated when compiled without features")]
                 let mut backends: Vec<Arc<Mutex<dyn Subscriber>>> = Vec::with_capacity(identities.len());
+                #[allow(unused_variables, reason = "not used when compiled without features")]
+                let disabled = self.disabled();
         
                 $(
-                    #[cfg(feature = $feature)]
-                    if identities.contains(BackendIdentity::$ident) {
+                    #[cfg($cfg)]
+                    if identities.contains(BackendIdentity::$ident) && !disabled.contains(BackendIdentity::$ident) {
                         if let Some(backend) = self.$name.as_ref() {
                             backends.push(backend.clone());
                         }
@@ -565,13 +652,49 @@ macro_rules! use_backends {
         
                 backends
             }
+            /// Splits the enabled backends which the track may be sent to (optionally, only those `among` a set)
+            /// into those which receive [normalized](normalize) copies of it and those which don't.
+            /// Also returns the backends which would've been dispatched to, but [need the network](BackendIdentity::requires_network) while it's [offline](crate::connectivity).
+            #[allow(clippy::type_complexity, reason = "it's only a tuple of vectors")]
+            pub fn partition_for_track(
+                &self,
+                #[allow(unused_variables, reason = "not used when compiled without features")] track: &DispatchableTrack,
+                #[allow(unused_variables, reason = "not used when compiled without features")] among: Option<BackendIdentitySet>,
+            ) -> (Vec<Arc<Mutex<dyn Subscriber>>>, Vec<Arc<Mutex<dyn Subscriber>>>, Vec<Arc<Mutex<dyn Subscriber>>>) {
+                #[allow(unused_mut, reason = "not mutated when compiled without features")]
+                let (mut normalized, mut verbatim): (Vec<Arc<Mutex<dyn Subscriber>>>, Vec<Arc<Mutex<dyn Subscriber>>>) = (Vec::new(), Vec::new());
+                #[allow(unused_mut, reason = "not mutated when compiled without features")]
+                let mut offline: Vec<Arc<Mutex<dyn Subscriber>>> = Vec::new();
+                #[allow(unused_variables, reason = "not used when compiled without features")]
+                let online = crate::connectivity::is_online();
+                #[allow(unused_variables, reason = "not used when compiled without features")]
+                let disabled = self.disabled();
+
+                $(
+                    #[cfg($cfg)]
+                    if let Some(backend) = self.$name.as_ref()
+                    && among.is_none_or(|among| among.contains(BackendIdentity::$ident))
+                    && !disabled.contains(BackendIdentity::$ident)
+                    && !track.excluded_backends.contains(BackendIdentity::$ident) {
+                        if !online && BackendIdentity::$ident.requires_network() {
+                            offline.push(backend.clone());
+                        } else if self.normalization.applies_to(BackendIdentity::$ident) {
+                            normalized.push(backend.clone());
+                        } else {
+                            verbatim.push(backend.clone());
+                        }
+                    }
+                )*
+
+                (normalized, verbatim, offline)
+            }
         }
         impl core::fmt::Debug for Backends {
             fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
                 #[allow(unused_mut, reason = "not mutated when compiled without features")]
                 let mut set = f.debug_set();
                 $(
-                    #[cfg(feature = $feature)]
+                    #[cfg($cfg)]
                     if let Some(backend) = &self.$name {
                         set.entry(backend);
                     }
@@ -581,37 +704,112 @@ macro_rules! use_backends {
         }
     };
 }
+pub mod journal;
+pub mod normalize;
+pub mod overrides;
+pub mod statistics;
+#[cfg(any(feature = "lastfm", feature = "listenbrainz", feature = "maloja"))]
+pub mod timestamp;
+
 use_backends!([
-    (discord, DiscordPresence, "discord", 0),
-    (lastfm, LastFM, "lastfm", 1),
-    (listenbrainz, ListenBrainz, "listenbrainz", 2)
+    (discord, DiscordPresence, feature = "discord", 0),
+    (lastfm, LastFM, feature = "lastfm", 1),
+    (listenbrainz, ListenBrainz, feature = "listenbrainz", 2),
+    (notifications, Notifications, feature = "notifications", 3),
+    (mqtt, Mqtt, feature = "mqtt", 4),
+    (maloja, Maloja, feature = "maloja", 5),
+    (mock, Mock, any(test, feature = "mock"), 6)
 ]);
 
+impl BackendIdentity {
+    /// Whether the backend has to reach a remote service, and so can't do anything while the network is [offline](crate::connectivity).
+    pub const fn requires_network(self) -> bool {
+        match self {
+            #[cfg(feature = "discord")]
+            Self::DiscordPresence => false,
+            #[cfg(feature = "lastfm")]
+            Self::LastFM => true,
+            #[cfg(feature = "listenbrainz")]
+            Self::ListenBrainz => true,
+            #[cfg(feature = "notifications")]
+            Self::Notifications => false,
+            // It's usually on the local network, and it keeps on retrying by itself anyhow.
+            #[cfg(feature = "mqtt")]
+            Self::Mqtt => false,
+            #[cfg(feature = "maloja")]
+            Self::Maloja => true,
+            #[cfg(any(test, feature = "mock"))]
+            Self::Mock => false,
+        }
+    }
+}
+
+impl<T> BackendMap<T> {
+    /// Moves every present value of the other map into this one.
+    fn merge(&mut self, other: Self) {
+        for (identity, value) in other {
+            if value.is_some() {
+                self[identity] = value;
+            }
+        }
+    }
+}
 impl<T, E> BackendMap<Result<T, E>> {
     fn into_errors_iter(self) -> impl Iterator<Item = (BackendIdentity, E)> {
         self.into_iter().filter_map(|(i, r)| r.and_then(Result::err).map(|e| (i, e)))
     }
 }
 
-/// The minimum data required to dispatch a track to a backend.
+/// The most listens held on to per backend while they can't be submitted; past this, the oldest are dropped.
+const MAX_DEFERRED_LISTENS: usize = 5000;
+
+/// A listen which a backend couldn't submit when it ended, held on to so that it can be [replayed](subscription::DeferredReplay) later.
+///
+/// Whether it's eligible for submission hasn't been checked; that's left up to the backend, like it is when the track ends.
+#[derive(Debug, Clone)]
+pub struct DeferredListen {
+    pub track: Arc<DispatchableTrack>,
+    pub player_version: Arc<str>,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    /// How much of the track was listened to.
+    pub listened: chrono::TimeDelta,
+    /// The [journal](journal) entry of the listen, which is committed once no backend is [holding on](Backends::settle) to it.
+    pub journaled: Option<crate::store::entities::Key<crate::store::entities::JournalEntry>>,
+}
+pub type DeferredListens = Arc<[DeferredListen]>;
+
+/// A [track](player_events::Track), along with what's needed to dispatch it to backends.
 /// This can be serialized and deserialized for bulk dispatches at later dates.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DispatchableTrack {
-    pub name: String,
-    pub album: Option<String>,
-    pub album_artist: Option<String>,
-    pub artist: Option<String>,
-    pub persistent_id: StoredPersistentId,
-    pub duration: Option<core::time::Duration>,
-    pub media_kind: osa_apple_music::track::MediaKind,
-    pub track_number: Option<core::num::NonZero<u16>>,
-    pub apple_music_url: Option<String>,
+    #[serde(flatten)]
+    pub track: player_events::Track,
+    /// The backends which the track should never be sent to, per the user's [overrides](overrides).
+    #[serde(skip)]
+    pub excluded_backends: BackendIdentitySet,
+}
+impl core::ops::Deref for DispatchableTrack {
+    type Target = player_events::Track;
+    fn deref(&self) -> &Self::Target {
+        &self.track
+    }
+}
+impl core::ops::DerefMut for DispatchableTrack {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.track
+    }
+}
+impl From<player_events::Track> for DispatchableTrack {
+    fn from(track: player_events::Track) -> Self {
+        Self { track, excluded_backends: BackendIdentitySet::default() }
+    }
 }
 impl DispatchableTrack {
     pub async fn from_track(
         track: osa_apple_music::track::Track,
         #[cfg(feature = "musicdb")]
         musicdb: Option<&musicdb::MusicDB>,
+        overrides: &[overrides::Override],
     ) -> Self {
         let track = osa_apple_music::track::BasicTrack::from(track);
         let pool = crate::store::DB_POOL.get().await.inspect_err(|error| {
@@ -619,35 +817,58 @@ impl DispatchableTrack {
         }).ok();
         
         let name = match uncensor::track(&track, pool).await {
-            Some(name) => name.into_owned(),
+            Some(name) => name.into_string(),
             None => track.name,
         };
 
         let persistent_id = StoredPersistentId::from_hex(&track.persistent_id).expect("bad track persistent ID");
 
+        #[cfg(feature = "musicdb")]
+        let from_musicdb = musicdb.and_then(|db| db.tracks().get(&musicdb::PersistentId::new(persistent_id.get())));
+
         let apple_music_url = {
             #[cfg(feature = "musicdb")]
-            {
-                musicdb.and_then(|db| {
-                    let id = musicdb::PersistentId::new(persistent_id.get());
-                    db.tracks().get(&id).and_then(|t| t.numerics.apple_music_url())
-                })
-            }
+            { from_musicdb.and_then(|t| t.numerics.apple_music_url()) }
             #[cfg(not(feature = "musicdb"))]
             { None }
         };
 
-        Self {
+        let genre = track.genre.or_else(|| {
+            #[cfg(feature = "musicdb")]
+            { from_musicdb.and_then(|t| t.genre).map(ToString::to_string) }
+            #[cfg(not(feature = "musicdb"))]
+            { None }
+        });
+
+        let mut dispatchable = Self::from(player_events::Track {
             name,
             album: track.album.name,
             album_artist: track.album.artist,
             artist: track.artist,
             persistent_id,
-            media_kind: track.media_kind,
+            media_kind: media_kind(track.media_kind),
             duration: track.duration,
             track_number: track.track_number,
-            apple_music_url
-        }
+            apple_music_url,
+            genre,
+            composer: track.composer,
+            work: track.work,
+            movement: track.movement.map(|movement| player_events::MovementInfo { name: movement.name, index: movement.index }),
+        });
+        dispatchable.exclude_scrobblers_unless_music();
+        overrides::apply(overrides, &mut dispatchable);
+        dispatchable
+    }
+
+    /// Podcasts and audiobooks have where they were left off recorded instead of being scrobbled.
+    fn exclude_scrobblers_unless_music(&mut self) {
+        if self.media_kind.is_music() { return }
+        #[cfg(feature = "lastfm")]
+        self.excluded_backends.insert(BackendIdentity::LastFM);
+        #[cfg(feature = "listenbrainz")]
+        self.excluded_backends.insert(BackendIdentity::ListenBrainz);
+        #[cfg(feature = "maloja")]
+        self.excluded_backends.insert(BackendIdentity::Maloja);
     }
 
     #[cfg(feature = "musicdb")]
@@ -655,10 +876,20 @@ impl DispatchableTrack {
         musicdb.get(musicdb::PersistentId::new(self.persistent_id.get()))
     }
 }
+const fn media_kind(kind: osa_apple_music::track::MediaKind) -> player_events::MediaKind {
+    use osa_apple_music::track::MediaKind;
+    match kind {
+        MediaKind::Song => player_events::MediaKind::Song,
+        MediaKind::MusicVideo => player_events::MediaKind::MusicVideo,
+        MediaKind::Podcast => player_events::MediaKind::Podcast,
+        MediaKind::Audiobook => player_events::MediaKind::Audiobook,
+        MediaKind::Unknown => player_events::MediaKind::Unknown,
+    }
+}
 impl sqlx::FromRow<'_, sqlx::sqlite::SqliteRow> for DispatchableTrack {
     fn from_row(row: &sqlx::sqlite::SqliteRow) -> Result<Self, sqlx::Error> {
         use sqlx::Row;
-        Ok(Self {
+        Ok(Self::from(player_events::Track {
             name: row.try_get("name")?,
             album: row.try_get("album")?,
             album_artist: row.try_get("album_artist")?,
@@ -668,7 +899,11 @@ impl sqlx::FromRow<'_, sqlx::sqlite::SqliteRow> for DispatchableTrack {
             duration: row.try_get::<Option<f32>, _>("duration")?.map(core::time::Duration::from_secs_f32),
             track_number: row.try_get("track_number")?,
             apple_music_url: None,
-        })
+            genre: None,
+            composer: None,
+            work: None,
+            movement: None,
+        }))
     }
 }
 
@@ -716,14 +951,16 @@ pub mod uncensor {
     pub async fn uncensor_track_name_itunes(track: &osa_apple_music::track::BasicTrack) -> Option<String> {
         use crate::data_fetching::services::itunes;
         itunes::find_track(&itunes::Query {
+            entity: itunes_api::Entity::Song,
             title: track.name.as_ref(),
             artist: track.artist.as_deref(),
-            album: track.album.name.as_deref()
+            album: track.album.name.as_deref(),
+            duration: track.duration,
         })
             .await
             .inspect_err(|err| {
                 tracing::error!(error = ?err, "failed to fetch track info from iTunes");
-            }).ok().flatten().map(|track| track.name)
+            }).ok().flatten().map(|track| track.name().to_owned())
     }
 
     #[expect(unused_imports, reason = "may be used in the future with nice verb form `uncensor::with_itunes`")]
@@ -736,26 +973,29 @@ pub mod uncensor {
             return Some(MaybeOwnedString::Borrowed(&track.name));
         }
 
-        if let Some(uncensored) = track.sorting.name.as_ref().and_then(|sorting| heuristically_uncensor_name(&track.name, sorting)) {
-            return Some(uncensored);
-        }
+        let id = StoredPersistentId::from_hex(&track.persistent_id).inspect_err(|error| {
+            tracing::error!(?error, "failed to parse track persistent ID");
+        }).ok();
 
-        let id = match StoredPersistentId::from_hex(&track.persistent_id) {
-            Ok(id) => id,
-            Err(error) => {
-                tracing::error!(?error, "failed to parse track persistent ID");
-                return None;
-            }
+        // What's cached comes first, since it may have been set by hand to correct the heuristic.
+        let cached = match (&pool, id) {
+            (Some(pool), Some(id)) => CachedUncensoredTitle::get_by_persistent_id(pool, id).await.unwrap_or_else(|error| {
+                tracing::error!(?error, "failed to fetch cached uncensored title");
+                None
+            }),
+            _ => None
         };
+        let looked_up = cached.is_some();
+        if let Some(uncensored) = cached.and_then(|cached| cached.uncensored) {
+            return Some(MaybeOwnedString::Owned(uncensored));
+        }
 
-        if let Some(pool) = &pool {
-            match CachedUncensoredTitle::get_by_persistent_id(pool, id).await {
-                Ok(Some(entry)) => return entry.uncensored.map(MaybeOwnedString::Owned),
-                Err(error) => { tracing::error!(?error, "failed to fetch cached uncensored title"); },
-                _ => {}
-            }
+        if let Some(uncensored) = track.sorting.name.as_ref().and_then(|sorting| heuristically_uncensor_name(&track.name, sorting)) {
+            return Some(uncensored);
         }
 
+        if looked_up { return None }
+        let id = id?;
         let uncensored = uncensor_track_name_itunes(track).await;
         
         if let Some(pool) = pool && let Err(error) = CachedUncensoredTitle::new(&pool, id, uncensored.as_deref()).await {
@@ -781,6 +1021,25 @@ pub mod uncensor {
 }
 
 
+static DRY_RUN: core::sync::atomic::AtomicBool = core::sync::atomic::AtomicBool::new(false);
+
+/// Whether backends are in a dry run, in which nothing is submitted.
+pub fn is_dry_run() -> bool {
+    DRY_RUN.load(core::sync::atomic::Ordering::Relaxed)
+}
+
+/// Returns whether backends are in a dry run, logging the payload that would've been submitted if so.
+/// 
+/// Under a dry run, backends are expected to return early instead of submitting anything.
+#[allow(unused, reason = "not utilized when compiled without any backends")]
+pub fn intercept_dry_run(backend: &'static str, action: &'static str, payload: &dyn core::fmt::Debug) -> bool {
+    let dry_run = is_dry_run();
+    if dry_run {
+        tracing::info!(backend, action, ?payload, "dry run; not submitting");
+    }
+    dry_run
+}
+
 #[derive(Debug)]
 pub struct BackendContext<A> {
     pub track: Arc<DispatchableTrack>,
@@ -804,28 +1063,58 @@ impl<A> Clone for BackendContext<A> {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Copy, Clone)]
-pub enum DispatchedPlayerStatus {
-    Playing,
-    /// The music stopped and there is no more music that will start playing soon.
-    // TODO: uhh fact-check this it's been so long
-    Stopped,
-    /// A temporary pause in track playback; the boolean indicating whether this has started or ended.
-    /// This may not be a result of user action— this may also be dispatched when encountering playback buffering issues.
-    Paused,
-    Closed
+pub use player_events::PlayerStatus as DispatchedPlayerStatus;
+pub const fn player_status(state: osa_apple_music::application::PlayerState) -> DispatchedPlayerStatus {
+    use osa_apple_music::application::PlayerState;
+    match state {
+        PlayerState::Playing | PlayerState::FastForwarding | PlayerState::Rewinding => DispatchedPlayerStatus::Playing,
+        PlayerState::Paused => DispatchedPlayerStatus::Paused,
+        PlayerState::Stopped => DispatchedPlayerStatus::Stopped
+    }
 }
-impl From<osa_apple_music::application::PlayerState> for DispatchedPlayerStatus {
-    fn from(value: osa_apple_music::application::PlayerState) -> Self {
-        use osa_apple_music::application::PlayerState;
-        match value {
-            PlayerState::Playing | PlayerState::FastForwarding | PlayerState::Rewinding => Self::Playing,
-            PlayerState::Paused => Self::Paused,
-            PlayerState::Stopped => Self::Stopped
-        }
+
+/// Roughly how many seconds of the track the player scans through each second while fast-forwarding.
+/// It's only an estimate, but the position is re-anchored on the next poll regardless.
+const FAST_FORWARD_RATE: f32 = 3.;
+
+/// Where playback is as the player reported it just now, for the position to be interpolated from until the next poll.
+pub fn playback(application: &osa_apple_music::ApplicationData) -> Option<player_events::Playback> {
+    use osa_apple_music::application::PlayerState;
+    Some(player_events::Playback {
+        position: application.position?,
+        at: chrono::Utc::now().timestamp_millis(),
+        rate: match application.state {
+            PlayerState::Playing => 1.,
+            PlayerState::FastForwarding => FAST_FORWARD_RATE,
+            // The position is never interpolated backwards, so it's held in place until the next poll says where it went.
+            PlayerState::Rewinding | PlayerState::Paused | PlayerState::Stopped => 0.,
+        },
+    })
+}
+
+/// The volume of the player, dispatched whenever it's changed.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Serialize)]
+pub struct DispatchedVolume {
+    /// An integer from 0 to 100, inclusive on both ends; kept as it was while muted.
+    pub level: u8,
+    pub muted: bool,
+}
+impl From<&osa_apple_music::ApplicationData> for DispatchedVolume {
+    fn from(value: &osa_apple_music::ApplicationData) -> Self {
+        Self { level: value.volume, muted: value.mute }
     }
 }
 
+/// What changed about the player application itself, dispatched whenever its version differs from the last one seen.
+///
+/// This happens when it's been updated (and relaunched) while running, or when it's first opened if it wasn't at startup.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct DispatchedApplicationStatus {
+    pub version: Arc<str>,
+    /// The version it had beforehand, if it was known.
+    pub previous_version: Option<Arc<str>>,
+}
+
 /// Why the subscriber is being terminated.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum SubscriberTerminationCause {
@@ -1042,10 +1331,15 @@ pub mod subscription {
     
     define!($, [
         { TrackStarted<crate::subscribers::BackendContext<crate::data_fetching::AdditionalTrackData>> },
+        { DataEnriched<crate::subscribers::BackendContext<crate::data_fetching::AdditionalTrackData>> },
         { TrackEnded },
         { ProgressJolt },
         { PlayerStatusUpdate<crate::subscribers::DispatchedPlayerStatus> },
-        { ImminentSubscriberTermination<crate::subscribers::SubscriberTerminationCause> }
+        { VolumeChanged<crate::subscribers::DispatchedVolume> },
+        { ApplicationStatusUpdate<crate::subscribers::DispatchedApplicationStatus> },
+        { ListeningSessionEnded<player_events::ListeningSession> },
+        { ImminentSubscriberTermination<crate::subscribers::SubscriberTerminationCause> },
+        { DeferredReplay<crate::subscribers::DeferredListens> }
     ], {
         async fn get_solicitation(&self, event: self::Identity) -> Option<ComponentSolicitation>;
         #[allow(private_interfaces)]
@@ -1070,6 +1364,21 @@ pub mod subscription {
 
 
 impl Backends {
+    fn disabled(&self) -> BackendIdentitySet {
+        // Never held while panicking, so it can't have been poisoned.
+        *self.disabled.lock().expect("disabled backends were poisoned")
+    }
+
+    /// Stops dispatching to the backend for as long as these backends are around, as after panicking it can't be trusted to behave.
+    pub fn disable(&self, identity: BackendIdentity) {
+        let mut disabled = self.disabled.lock().expect("disabled backends were poisoned");
+        if !disabled.contains(identity) {
+            disabled.insert(identity);
+            drop(disabled);
+            tracing::error!(backend = identity.get_name(), "disabling backend after it panicked");
+        }
+    }
+
     #[tracing::instrument(level = "debug")]
     pub async fn get_solicitations(&self, event: subscription::Identity) -> ComponentSolicitation {
         self.get_solicitations_from(self.all(), event).await
@@ -1080,18 +1389,22 @@ impl Backends {
     pub async fn get_solicitations_from(&self, backends: Vec<Arc<Mutex<dyn Subscriber>>>, event: subscription::Identity) -> ComponentSolicitation {
         let mut solicitation = ComponentSolicitation::default();
         let mut jobs = Vec::with_capacity(backends.len());
-        for backend in backends {
-            jobs.push(tokio::spawn(async move {
-                backend.lock().await.get_solicitation(event).await
-            }));
+        for backend in &backends {
+            let backend = backend.clone();
+            jobs.push(tokio::spawn(crate::debugging::supervisor::supervise(async move {
+                let backend = backend.lock().await;
+                crate::debugging::supervisor::attribute(backend.get_identity());
+                backend.get_solicitation(event).await
+            })));
         }
         for (i, job) in jobs.into_iter().enumerate() {
             match job.await {
                 Ok(Some(got)) => solicitation |= got,
                 Ok(None) => (),
                 Err(error) => {
-                    let backend = self.all()[i].lock().await.get_identity().get_name();
-                    tracing::error!(?error, backend, "error getting solicitation; skipping");
+                    let identity = backends[i].lock().await.get_identity();
+                    tracing::error!(?error, backend = identity.get_name(), "error getting solicitation; skipping");
+                    if error.is_panic() { self.disable(identity); }
                 },
             }
         }
@@ -1110,21 +1423,24 @@ impl Backends {
         let mut outputs  = BackendMap::<Result<<T as subscription::TypeIdentity>::DispatchReturn, DispatchError>>::new();
         let mut jobs = Vec::with_capacity(backends.len());
 
-        for backend in backends {
+        for backend in &backends {
+            let backend = backend.clone();
             let context = context.clone();
             let context = Box::into_raw(Box::new(context));
             let context = TransientSendableUntypedRawBoxPointer(context.cast::<u8>());
-            jobs.push(tokio::spawn(async move {
+            jobs.push(tokio::spawn(crate::debugging::supervisor::supervise(async move {
                 let mut backend = backend.lock().await;
+                crate::debugging::supervisor::attribute(backend.get_identity());
                 unsafe { backend.dispatch_untyped(T::IDENTITY, context).await }
                     .map(|result| (backend.get_identity(), result))
-            }));
+            })));
         }
 
         for (i, job) in jobs.into_iter().enumerate() {
             match job.await {
                 Ok(None) => {},
                 Ok(Some((identity, result))) => {
+                    statistics::record(identity, result.is_err());
                     outputs[identity] = Some(result.map(|ptr| {
                         let ptr = ptr.0.cast::<T::DispatchReturn>();
                         let ptr = unsafe { Box::from_raw(ptr) };
@@ -1132,8 +1448,12 @@ impl Backends {
                     }));
                 },
                 Err(error) => {
-                    let backend = self.all()[i].lock().await.get_identity().get_name();
-                    tracing::error!(?error, backend, "error dispatching track completion");
+                    // The lock was let go of as the task unwound, so it isn't held up by whatever went wrong.
+                    let identity = backends[i].lock().await.get_identity();
+                    statistics::record(identity, true);
+                    let backend = identity.get_name();
+                    tracing::error!(?error, backend, event = ?T::IDENTITY, "error dispatching");
+                    if error.is_panic() { self.disable(identity); }
                 }
             }
         };
@@ -1141,49 +1461,245 @@ impl Backends {
         outputs
     }
 
+    /// Dispatches an event concerning a track to the backends it isn't [excluded](overrides) from (optionally, only those `among` a set),
+    /// giving those that normalization applies to a [normalized](normalize) copy of it.
+    pub async fn dispatch_for_track<T, A>(&self, among: Option<BackendIdentitySet>, context: BackendContext<A>) -> BackendMap<Result<T::DispatchReturn, DispatchError>>
+    where T: subscription::TypeIdentity<DispatchContext = BackendContext<A>>, A: Send + Sync {
+        let (normalized, mut verbatim, offline) = self.partition_for_track(&context.track, among);
+        let track = if normalized.is_empty() { None } else { self.normalization.apply(&context.track) };
+        let mut outputs = if let Some(track) = track {
+            tracing::debug!(?track, "normalized track");
+            let normalized_context = BackendContext { track: Arc::new(track), ..context.clone() };
+            let (mut outputs, verbatim_outputs) = tokio::join!(
+                self.dispatch_to::<T>(normalized, normalized_context),
+                self.dispatch_to::<T>(verbatim, context)
+            );
+            outputs.merge(verbatim_outputs);
+            outputs
+        } else {
+            verbatim.extend(normalized);
+            self.dispatch_to::<T>(verbatim, context).await
+        };
+
+        // Checked before those skipped for being offline are given errors of the same kind, which would otherwise have it probed again every dispatch.
+        if outputs.iter().any(|(_, output)| matches!(output, Some(Err(DispatchError { cause: error::dispatch::Cause::Request(error::dispatch::cause::RequestError::ConnectionFailure), .. })))) {
+            crate::connectivity::suspect();
+        }
+        for backend in offline {
+            let backend = backend.lock().await;
+            // Only those subscribed to the event would've been dispatched it.
+            if backend.get_solicitation(T::IDENTITY).await.is_some() {
+                outputs[backend.get_identity()] = Some(Err(DispatchError::offline()));
+            }
+        }
+        outputs
+    }
+
     #[tracing::instrument(skip(context), level = "debug", fields(track = ?&context.track.persistent_id))]
     pub async fn dispatch_track_started(&self, context: BackendContext<crate::data_fetching::AdditionalTrackData>) {
         type Variant = subscription::type_identity::TrackStarted;
-        for (identity, error) in self.dispatch::<Variant>(context).await.into_errors_iter() {
+        events::publish_track_started(&context.track.track, context.data.cached_artwork(&context.track));
+        for (identity, error) in self.dispatch_for_track::<Variant, _>(None, context).await.into_errors_iter() {
             error.handle(identity.get_name(), &Variant {});
         }
     }
 
+    /// Dispatches additional data about the current track which didn't arrive in time to be sent along with its start.
+    #[tracing::instrument(skip(context), level = "debug", fields(track = ?&context.track.persistent_id))]
+    pub async fn dispatch_data_enriched(&self, context: BackendContext<crate::data_fetching::AdditionalTrackData>) {
+        type Variant = subscription::type_identity::DataEnriched;
+        if let Some(artwork) = context.data.cached_artwork(&context.track) {
+            events::publish_artwork_resolved(&context.track.track, artwork);
+        }
+        for (identity, error) in self.dispatch_for_track::<Variant, _>(None, context).await.into_errors_iter() {
+            error.handle(identity.get_name(), &Variant {});
+        }
+    }
+
+    /// Dispatches the end of a track; backends which can [replay](subscription::DeferredReplay) listens hold on to it if
+    /// it should be [deferred](error::dispatch::Recovery::defer), and are given those they've held on to once they succeed again.
+    ///
+    /// The listen is [journaled](journal) until every backend has accepted it.
     #[tracing::instrument(skip(context), level = "debug", fields(track = ?&context.track.persistent_id))]
     pub async fn dispatch_track_ended(&self, context: BackendContext<()>) {
         type Variant = subscription::type_identity::TrackEnded;
-        for (identity, error) in self.dispatch::<Variant>(context).await.into_errors_iter() {
-            error.handle(identity.get_name(), &Variant {});
+        let (started_at, listened, summary) = {
+            let listened = context.listened.lock().await;
+            (listened.started_at(), listened.total(), listened.summarize(context.track.duration))
+        };
+        events::publish(events::Event::TrackEnded { track: Cow::Borrowed(&context.track.track), listened: summary });
+        let mut listen = started_at.map(|started_at| DeferredListen {
+            track: context.track.clone(),
+            player_version: context.player.version.as_str().into(),
+            started_at,
+            listened,
+            journaled: None,
+        });
+        if let Some(listen) = &mut listen {
+            listen.journaled = journal::append(listen).await;
+        }
+
+        #[allow(unused_mut, reason = "not mutated when compiled without features")]
+        let mut succeeded = BackendIdentitySet::empty();
+        for (identity, output) in self.dispatch_for_track::<Variant, _>(None, context).await {
+            match output {
+                Some(Ok(())) => { succeeded.insert(identity); },
+                Some(Err(error)) => {
+                    error.handle(identity.get_name(), &Variant {});
+                    if error.recovery.defer() && let Some(listen) = &listen && self.can_replay(identity).await {
+                        self.defer(identity, listen.clone()).await;
+                    }
+                },
+                None => {},
+            }
+        }
+        if let Some(listen) = &listen {
+            self.settle(core::slice::from_ref(listen)).await;
+        }
+        self.replay_deferred(self.get_many(succeeded)).await;
+    }
+
+    /// Whether the backend is enabled and can be given listens which it couldn't submit earlier.
+    pub async fn can_replay(&self, identity: BackendIdentity) -> bool {
+        let mut identities = BackendIdentitySet::empty();
+        identities.insert(identity);
+        let Some(backend) = self.get_many(identities).into_iter().next() else { return false };
+        let solicitation = backend.lock().await.get_solicitation(subscription::Identity::DeferredReplay).await;
+        solicitation.is_some()
+    }
+
+    /// How many listens the backend is holding on to, waiting for them to be [replayed](Self::replay_deferred).
+    pub async fn deferred_count(&self, identity: BackendIdentity) -> usize {
+        self.deferred.lock().await[identity].as_ref().map_or(0, Vec::len)
+    }
+
+    async fn defer(&self, identity: BackendIdentity, listen: DeferredListen) {
+        let mut deferred = self.deferred.lock().await;
+        let listens = deferred[identity].get_or_insert_default();
+        if listens.len() >= MAX_DEFERRED_LISTENS {
+            tracing::warn!(backend = identity.get_name(), "too many listens are waiting to be replayed; dropping the oldest");
+            listens.remove(0);
+        }
+        listens.push(listen);
+        let count = listens.len();
+        drop(deferred);
+        tracing::debug!(backend = identity.get_name(), count, "deferred listen for replay");
+    }
+
+    /// Whether any backend is holding on to the listen of the journal entry, to replay it later.
+    async fn holds(&self, entry: crate::store::entities::Key<crate::store::entities::JournalEntry>) -> bool {
+        self.deferred.lock().await.iter()
+            .filter_map(|(_, listens)| listens.as_ref())
+            .flatten()
+            .any(|listen| listen.journaled.is_some_and(|held| held.get() == entry.get()))
+    }
+
+    /// [Commits](journal::commit) the journal entries of the listens which no backend is holding on to anymore.
+    async fn settle(&self, listens: &[DeferredListen]) {
+        for entry in listens.iter().filter_map(|listen| listen.journaled) {
+            if !self.holds(entry).await {
+                journal::commit(entry).await;
+            }
+        }
+    }
+
+    /// Gives the backends the listens they've had [deferred](Self::defer), all at once.
+    /// Those which still can't be submitted, and should be deferred again, are held on to for next time.
+    async fn replay_deferred(&self, backends: Vec<Arc<Mutex<dyn Subscriber>>>) {
+        type Variant = subscription::type_identity::DeferredReplay;
+        for backend in backends {
+            #[allow(unused_variables, reason = "not used when compiled without features")]
+            let identity = backend.lock().await.get_identity();
+            let Some(listens) = self.deferred.lock().await.take(identity) else { continue };
+            let listens = if self.normalization.applies_to(identity) {
+                listens.into_iter().map(|listen| DeferredListen {
+                    track: self.normalization.apply(&listen.track).map_or_else(|| listen.track.clone(), Arc::new),
+                    ..listen
+                }).collect()
+            } else { listens };
+
+            tracing::info!(backend = identity.get_name(), count = listens.len(), "replaying deferred listens");
+            let listens: DeferredListens = listens.into();
+            let outputs = self.dispatch_to::<Variant>(vec![backend], listens.clone()).await;
+            for (identity, error) in outputs.into_errors_iter() {
+                error.handle(identity.get_name(), &Variant {});
+                if error.recovery.defer() {
+                    for listen in listens.iter() {
+                        self.defer(identity, listen.clone()).await;
+                    }
+                }
+            }
+            self.settle(&listens).await;
         }
     }
 
     #[tracing::instrument(skip(context), level = "debug", fields(track = ?&context.track.persistent_id))]
     pub async fn dispatch_current_progress(&self, context: BackendContext<()>) {
         type Variant = subscription::type_identity::ProgressJolt;
-        for (identity, error) in self.dispatch::<Variant>(context).await.into_errors_iter() {
+        let position = context.listened.lock().await.current.as_ref().map(crate::listened::CurrentListened::get_expected_song_position);
+        events::publish(events::Event::ProgressJolt { track: Cow::Borrowed(&context.track.track), position });
+        for (identity, error) in self.dispatch_for_track::<Variant, _>(None, context).await.into_errors_iter() {
             error.handle(identity.get_name(), &Variant {});
         }
     }
 
     #[tracing::instrument(level = "debug")]
-    pub async fn dispatch_status(&self, status: DispatchedPlayerStatus) {
+    pub async fn dispatch_status(&self, status: DispatchedPlayerStatus, playback: Option<player_events::Playback>) {
         type Variant = subscription::type_identity::PlayerStatusUpdate;
+        events::publish(events::Event::PlayerStatus { status, playback });
+        for (identity, error) in self.dispatch::<Variant>(status).await.into_errors_iter() {
+            error.handle(identity.get_name(), &Variant {});
+        }
+    }
+
+    #[tracing::instrument(level = "debug")]
+    pub async fn dispatch_volume(&self, volume: DispatchedVolume) {
+        type Variant = subscription::type_identity::VolumeChanged;
+        events::publish(events::Event::VolumeChanged { volume: volume.level, muted: volume.muted });
+        for (identity, error) in self.dispatch::<Variant>(volume).await.into_errors_iter() {
+            error.handle(identity.get_name(), &Variant {});
+        }
+    }
+
+    #[tracing::instrument(level = "debug")]
+    pub async fn dispatch_application_status(&self, status: DispatchedApplicationStatus) {
+        type Variant = subscription::type_identity::ApplicationStatusUpdate;
         for (identity, error) in self.dispatch::<Variant>(status).await.into_errors_iter() {
             error.handle(identity.get_name(), &Variant {});
         }
     }
 
+    #[tracing::instrument(level = "debug")]
+    pub async fn dispatch_listening_session_ended(&self, session: player_events::ListeningSession) {
+        type Variant = subscription::type_identity::ListeningSessionEnded;
+        events::publish(events::Event::ListeningSessionEnded { session });
+        for (identity, error) in self.dispatch::<Variant>(session).await.into_errors_iter() {
+            error.handle(identity.get_name(), &Variant {});
+        }
+    }
+
     #[tracing::instrument(level = "debug")]
     pub async fn dispatch_imminent_program_termination(&self, signal: tokio::signal::unix::SignalKind) {
         type Variant = subscription::type_identity::ImminentSubscriberTermination;
+        events::publish(events::Event::Terminating);
         let cause = SubscriberTerminationCause::from(signal);
         for (identity, error) in self.dispatch::<Variant>(cause).await.into_errors_iter() {
             error.handle(identity.get_name(), &Variant {});
         }
     }
 
+    pub async fn new(config: &crate::config::Config, dry_run: bool, redispatch_start_request_tx: tokio::sync::mpsc::Sender<crate::subscribers::BackendIdentity>) -> Self {
+        Self::new_among(config, dry_run, redispatch_start_request_tx, None).await
+    }
+
+    /// Like [`Self::new`], but only enabling the backends `among` a set, if given.
     #[allow(unused, reason = "not utilized when compiled without any backends")]
-    pub async fn new(config: &crate::config::Config, redispatch_start_request_tx: tokio::sync::mpsc::Sender<crate::subscribers::BackendIdentity>) -> Self {        
+    pub async fn new_among(
+        config: &crate::config::Config,
+        dry_run: bool,
+        redispatch_start_request_tx: tokio::sync::mpsc::Sender<crate::subscribers::BackendIdentity>,
+        among: Option<BackendIdentitySet>,
+    ) -> Self {
         #[cfg(feature = "lastfm")]
         use crate::subscribers::lastfm::*;
 
@@ -1193,29 +1709,63 @@ impl Backends {
         #[cfg(feature = "listenbrainz")]
         use crate::subscribers::listenbrainz::*;
 
+        #[cfg(feature = "notifications")]
+        use crate::subscribers::notifications::*;
+
+        let dry_run = dry_run || config.dry_run;
+        DRY_RUN.store(dry_run, core::sync::atomic::Ordering::Relaxed);
+        if dry_run { tracing::warn!("performing a dry run; backends will log what they would submit instead of submitting it"); }
+
         #[cfg(feature = "lastfm")]
         let lastfm = config.backends.lastfm.as_ref().and_then(|config| {
-            if config.enabled {
+            if config.enabled && among.is_none_or(|among| among.contains(BackendIdentity::LastFM)) {
                 Some(Arc::new(Mutex::new(LastFM::new(
                     config.identity.clone(),
-                    config.session_key.clone().expect("no session keys")
+                    config.session_key.clone().expect("no session keys"),
+                    config.tags.clone(),
+                    config.timestamp_policy
                 ))))
             } else { None }
         });
         
         #[cfg(feature = "listenbrainz")]
         let listenbrainz = config.backends.listenbrainz.as_ref().and_then(|config| {
-            if config.enabled {
+            if config.enabled && among.is_none_or(|among| among.contains(BackendIdentity::ListenBrainz)) {
                 Some(Arc::new(Mutex::new(ListenBrainz::new(
                     config.program_info.clone(),
-                    config.user_token.clone().expect("no token")
+                    config.user_token.clone().expect("no token"),
+                    config.timestamp_policy
                 ))))
             } else { None }
         });
 
+        #[cfg(feature = "notifications")]
+        let notifications = config.backends.notifications.as_ref().copied().and_then(|config| {
+            if config.enabled && among.is_none_or(|among| among.contains(BackendIdentity::Notifications)) {
+                Some(Arc::new(Mutex::new(Notifications::new(config))))
+            } else { None }
+        });
+
+        #[cfg(feature = "mqtt")]
+        let mqtt = config.backends.mqtt.as_ref().and_then(|config| {
+            if config.enabled && among.is_none_or(|among| among.contains(BackendIdentity::Mqtt)) {
+                mqtt::Mqtt::new(config.clone()).map(|mqtt| Arc::new(Mutex::new(mqtt)))
+            } else { None }
+        });
+
+        #[cfg(feature = "maloja")]
+        let maloja = config.backends.maloja.as_ref().and_then(|config| {
+            if config.enabled && among.is_none_or(|among| among.contains(BackendIdentity::Maloja)) {
+                let key = config.api_key.clone().expect("no api key");
+                maloja::Maloja::new(&config.url, key, config.timestamp_policy)
+                    .inspect_err(|error| tracing::error!(%error, "failed to set up maloja"))
+                    .ok().map(|maloja| Arc::new(Mutex::new(maloja)))
+            } else { None }
+        });
+
         #[cfg(feature = "discord")]
         let discord = match config.backends.discord.as_ref().copied() {
-            Some(config) if config.enabled => Some(DiscordPresence::new(config, redispatch_start_request_tx).await),
+            Some(config) if config.enabled && among.is_none_or(|among| among.contains(BackendIdentity::DiscordPresence)) => Some(DiscordPresence::new(config, redispatch_start_request_tx).await),
             _ => None
         };
 
@@ -1224,7 +1774,80 @@ impl Backends {
         Self {
             #[cfg(feature = "lastfm")] lastfm,
             #[cfg(feature = "discord")] discord,
-            #[cfg(feature = "listenbrainz")] listenbrainz
+            #[cfg(feature = "listenbrainz")] listenbrainz,
+            #[cfg(feature = "notifications")] notifications,
+            #[cfg(feature = "mqtt")] mqtt,
+            #[cfg(feature = "maloja")] maloja,
+            // Only ever put in place by whatever's testing against it.
+            #[cfg(any(test, feature = "mock"))] mock: None,
+            normalization: config.normalization.clone(),
+            deferred: Mutex::new(BackendMap::new()),
+            disabled: std::sync::Mutex::new(BackendIdentitySet::empty()),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[expect(clippy::float_cmp, reason = "the rates are exact constants")]
+    fn playback_rate_follows_player_state() {
+        let rate = |state| playback(&crate::player::fake::FakePlayer::application_data(state, Some(30.))).expect("position was given").rate;
+        assert_eq!(rate("playing"), 1.);
+        assert_eq!(rate("fast forwarding"), FAST_FORWARD_RATE);
+        assert_eq!(rate("rewinding"), 0.);
+        assert_eq!(rate("paused"), 0.);
+        assert_eq!(rate("stopped"), 0.);
+    }
+
+    /// Whichever backends are compiled in, including when holes are left by those which aren't, every one of them is iterated over.
+    #[test]
+    fn backend_maps_visit_every_backend() {
+        let mut map = BackendMap::new();
+        for identity in BackendIdentitySet::all().iter() {
+            assert!(identity.get_holey_index() < HOLEY_INDEX_BOUND);
+            assert_eq!(BackendIdentity::from_holey_index(identity.get_holey_index()), Some(identity));
+            map[identity] = Some(identity);
+        }
+
+        let visited = map.iter().filter_map(|(_, value)| *value).collect::<Vec<_>>();
+        assert_eq!(visited, BackendIdentitySet::all().iter().collect::<Vec<_>>());
+        let taken = map.into_iter().filter_map(|(identity, value)| value.filter(|value| *value == identity)).collect::<Vec<_>>();
+        assert_eq!(visited, taken);
+    }
+
+    #[test]
+    fn only_music_is_scrobbled() {
+        let track = |media_kind| {
+            let mut track = DispatchableTrack::from(player_events::Track {
+                name: "Episode".to_owned(),
+                album: None,
+                album_artist: None,
+                artist: None,
+                persistent_id: StoredPersistentId::new(1),
+                media_kind,
+                duration: None,
+                track_number: None,
+                apple_music_url: None,
+                genre: None,
+                composer: None,
+                work: None,
+                movement: None,
+            });
+            track.exclude_scrobblers_unless_music();
+            track.excluded_backends
+        };
+
+        assert!(track(player_events::MediaKind::Song).is_empty());
+        let podcast = track(player_events::MediaKind::Podcast);
+        #[cfg(feature = "lastfm")]
+        assert!(podcast.contains(BackendIdentity::LastFM));
+        #[cfg(feature = "listenbrainz")]
+        assert!(podcast.contains(BackendIdentity::ListenBrainz));
+        #[cfg(feature = "maloja")]
+        assert!(podcast.contains(BackendIdentity::Maloja));
+        assert!(!podcast.contains(BackendIdentity::Mock), "only scrobblers are excluded");
+    }
+}
@@ -0,0 +1,218 @@
+//! Publishes what's playing to an MQTT broker, for home automation (such as with Home Assistant) to act upon.
+//!
+//! The state is published as a retained JSON message to a single topic, so that whatever subscribes to it later
+//! still gets told what's playing right away:
+//! ```json
+//! { "status": "playing", "track": { "name": "...", "artist": "...", ... }, "artwork": "https://..." }
+//! ```
+//! The broker is left a will of the `closed` status, which it publishes on our behalf if we drop off without saying goodbye.
+use std::sync::Arc;
+
+use rumqttc::{AsyncClient, EventLoop, LastWill, MqttOptions, QoS};
+
+use super::{error::dispatch::DispatchError, subscribe, DispatchableTrack, DispatchedPlayerStatus};
+use crate::data_fetching::components::{Component, ComponentSolicitation};
+
+/// How long to wait before trying to reconnect after losing the connection to the broker.
+const RECONNECT_DELAY: core::time::Duration = core::time::Duration::from_secs(5);
+/// How long to wait for the goodbye to be sent when the program is exiting.
+const DISCONNECT_TIMEOUT: core::time::Duration = core::time::Duration::from_secs(2);
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Credentials {
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct Config {
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16,
+    /// The topic that the state is published to.
+    pub topic: String,
+    pub client_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credentials: Option<Credentials>,
+}
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            host: "localhost".to_owned(),
+            port: 1883,
+            topic: "am-osx-status/now_playing".to_owned(),
+            client_id: "am-osx-status".to_owned(),
+            credentials: None,
+        }
+    }
+}
+
+/// What gets published to the topic.
+#[derive(Debug, serde::Serialize)]
+struct NowPlaying<'a> {
+    status: DispatchedPlayerStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    track: Option<&'a player_events::Track>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    artwork: Option<&'a str>,
+}
+impl NowPlaying<'_> {
+    fn to_payload(&self) -> Vec<u8> {
+        serde_json::to_vec(self).expect("state is always serializable")
+    }
+}
+
+super::subscription::define_subscriber!(#[derive(Debug)] pub Mqtt, {
+    client: AsyncClient,
+    topic: String,
+    /// The last payload published, which is published again whenever the connection is re-established,
+    /// since the broker will have published our will in the meantime.
+    published: tokio::sync::watch::Sender<Vec<u8>>,
+    connection: Option<tokio::task::JoinHandle<()>>,
+    status: DispatchedPlayerStatus,
+    track: Option<Arc<DispatchableTrack>>,
+    artwork: Option<String>,
+});
+impl Mqtt {
+    /// Starts connecting to the broker in the background, unless this is a dry run; returns [`None`] if the topic can't be published to.
+    pub fn new(config: Config) -> Option<Self> {
+        if config.topic.is_empty() || !rumqttc::valid_topic(&config.topic) {
+            tracing::error!(topic = config.topic, "mqtt topic is invalid; it can't be empty or contain wildcards");
+            return None
+        }
+
+        let closed = NowPlaying { status: DispatchedPlayerStatus::Closed, track: None, artwork: None }.to_payload();
+        let mut options = MqttOptions::new(config.client_id, config.host, config.port);
+        options.set_last_will(LastWill::new(&config.topic, closed.clone(), QoS::AtLeastOnce, true));
+        if let Some(credentials) = config.credentials {
+            options.set_credentials(credentials.username, credentials.password);
+        }
+
+        let broker = options.broker_address();
+        let (client, events) = AsyncClient::new(options, 10);
+        let (published, last) = tokio::sync::watch::channel(closed);
+        // Connecting would leave the broker our will, and publish the state upon being acknowledged.
+        let connection = (!super::intercept_dry_run(Self::NAME, "connect", &broker))
+            .then(|| tokio::spawn(Self::drive(events, client.clone(), config.topic.clone(), last)));
+
+        Some(Self {
+            client,
+            topic: config.topic,
+            published,
+            connection,
+            status: DispatchedPlayerStatus::Closed,
+            track: None,
+            artwork: None,
+        })
+    }
+
+    /// Polls the connection until it's disconnected from, reconnecting whenever it's lost.
+    async fn drive(mut events: EventLoop, client: AsyncClient, topic: String, last: tokio::sync::watch::Receiver<Vec<u8>>) {
+        use rumqttc::{Event, Outgoing, Packet};
+        loop {
+            match events.poll().await {
+                Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                    tracing::debug!("connected to mqtt broker");
+                    let payload = last.borrow().clone();
+                    if let Err(error) = client.try_publish(&topic, QoS::AtLeastOnce, true, payload) {
+                        tracing::warn!(?error, "failed to republish state after connecting to mqtt broker");
+                    }
+                },
+                Ok(Event::Outgoing(Outgoing::Disconnect)) => break,
+                Ok(_) => {},
+                Err(error) => {
+                    tracing::warn!(?error, "lost connection to mqtt broker; retrying in {} seconds", RECONNECT_DELAY.as_secs());
+                    tokio::time::sleep(RECONNECT_DELAY).await;
+                }
+            }
+        }
+    }
+
+    fn publish(&self) {
+        let state = NowPlaying {
+            status: self.status,
+            track: self.track.as_deref().map(|track| &track.track),
+            artwork: self.artwork.as_deref(),
+        };
+        if super::intercept_dry_run(Self::NAME, "publish state", &state) { return }
+        let payload = state.to_payload();
+        self.published.send_replace(payload.clone());
+        // The queue only fills up while the broker can't be reached, and the latest state is republished upon reconnecting anyhow.
+        if let Err(error) = self.client.try_publish(&self.topic, QoS::AtLeastOnce, true, payload) {
+            tracing::debug!(?error, "couldn't queue mqtt publish");
+        }
+    }
+
+    fn publish_track(&mut self, context: &super::BackendContext<crate::data_fetching::AdditionalTrackData>) {
+        self.track = Some(context.track.clone());
+        self.artwork = context.data.images.track.as_ref().and_then(|artwork| artwork.as_url()).map(ToOwned::to_owned);
+        self.publish();
+    }
+}
+
+subscribe!(Mqtt, TrackStarted, {
+    async fn get_solicitation(&self) -> ComponentSolicitation {
+        let mut solicitation = ComponentSolicitation::default();
+        solicitation.insert(Component::AlbumImage);
+        solicitation
+    }
+
+    async fn dispatch(&mut self, context: super::BackendContext<crate::data_fetching::AdditionalTrackData>) -> Result<(), DispatchError> {
+        self.status = DispatchedPlayerStatus::Playing;
+        self.publish_track(&context);
+        Ok(())
+    }
+});
+subscribe!(Mqtt, DataEnriched, {
+    async fn dispatch(&mut self, context: super::BackendContext<crate::data_fetching::AdditionalTrackData>) -> Result<(), DispatchError> {
+        self.publish_track(&context);
+        Ok(())
+    }
+});
+subscribe!(Mqtt, PlayerStatusUpdate, {
+    async fn dispatch(&mut self, status: DispatchedPlayerStatus) -> Result<(), DispatchError> {
+        // This is dispatched every time the player is polled, not only when it changes.
+        if status == self.status { return Ok(()) }
+        self.status = status;
+        if matches!(status, DispatchedPlayerStatus::Stopped | DispatchedPlayerStatus::Closed) {
+            self.track = None;
+            self.artwork = None;
+        }
+        self.publish();
+        Ok(())
+    }
+});
+subscribe!(Mqtt, ImminentSubscriberTermination, {
+    async fn dispatch(&mut self, _: super::SubscriberTerminationCause) -> Result<(), DispatchError> {
+        self.status = DispatchedPlayerStatus::Closed;
+        self.track = None;
+        self.artwork = None;
+        self.publish();
+        // There's no connection to a broker under a dry run.
+        let Some(connection) = self.connection.take() else { return Ok(()) };
+        if let Err(error) = self.client.try_disconnect() {
+            tracing::warn!(?error, "failed to disconnect from mqtt broker");
+        }
+        if tokio::time::timeout(DISCONNECT_TIMEOUT, connection).await.is_err() {
+            tracing::warn!("timed out disconnecting from mqtt broker");
+        }
+        Ok(())
+    }
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payload() {
+        let closed = NowPlaying { status: DispatchedPlayerStatus::Closed, track: None, artwork: None };
+        assert_eq!(closed.to_payload(), br#"{"status":"closed"}"#);
+
+        let config: Config = toml::from_str("enabled = true\nhost = \"homeassistant.local\"").unwrap();
+        assert_eq!(config.topic, "am-osx-status/now_playing");
+        assert_eq!(config.port, 1883);
+    }
+}
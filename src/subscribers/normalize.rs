@@ -0,0 +1,268 @@
+//! Cleaning up track metadata before it's dispatched, since what's in the library is often noisier than what
+//! one would want on their profile (e.x. `Song (Remastered 2011)` instead of `Song`).
+//!
+//! Backends can opt out of this, in which case they receive the track exactly as it was read.
+use super::DispatchableTrack;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+#[allow(clippy::struct_excessive_bools, reason = "they're independent passes")]
+pub struct Config {
+    pub enabled: bool,
+    /// Strip remaster annotations from titles and albums, as well as release-type suffixes (e.x. ` - Single`) from albums.
+    pub strip_suffixes: bool,
+    /// Move featured artists from the title (e.x. `Song (feat. B)`) into the artist.
+    pub move_featuring: bool,
+    /// Trim and collapse runs of whitespace.
+    pub collapse_whitespace: bool,
+    /// Title the movements of classical works as `Composer: Work — Movement` instead of by their track name.
+    pub classical_titles: bool,
+    /// The backends which should receive tracks as-is, by their configuration key (e.x. `lastfm`).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub opted_out: Vec<String>,
+}
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            strip_suffixes: true,
+            move_featuring: true,
+            collapse_whitespace: true,
+            classical_titles: false,
+            opted_out: Vec::new(),
+        }
+    }
+}
+impl Config {
+    pub fn applies_to(&self, backend: super::BackendIdentity) -> bool {
+        self.enabled && !self.opted_out.iter().any(|key| key.eq_ignore_ascii_case(backend.get_key()))
+    }
+
+    /// Returns a normalized copy of the track, or `None` if normalization wouldn't change anything.
+    pub fn apply(&self, track: &DispatchableTrack) -> Option<DispatchableTrack> {
+        let mut name = track.name.clone();
+        let mut artist = track.artist.clone();
+        let mut album = track.album.clone();
+        let mut album_artist = track.album_artist.clone();
+
+        if self.classical_titles && let Some(title) = classical_title(track) {
+            name = title;
+        }
+        if self.collapse_whitespace {
+            for field in [Some(&mut name), artist.as_mut(), album.as_mut(), album_artist.as_mut()].into_iter().flatten() {
+                *field = collapse_whitespace(field);
+            }
+        }
+        if self.strip_suffixes {
+            // Suffixes are stripped from the end, so what's left is always a prefix.
+            let stripped = strip_title_suffixes(&name).len();
+            name.truncate(stripped);
+            album = album.map(|album| strip_album_suffixes(&album).to_owned());
+        }
+        if self.move_featuring && let Some((title, featured)) = split_featuring(&name) {
+            artist = Some(artist.map_or_else(|| featured.to_owned(), |artist| add_featured_artist(&artist, featured)));
+            let title = title.len();
+            name.truncate(title);
+        }
+
+        let changed = name != track.name || artist != track.artist || album != track.album || album_artist != track.album_artist;
+        changed.then(|| DispatchableTrack {
+            track: player_events::Track { name, artist, album, album_artist, ..track.track.clone() },
+            excluded_backends: track.excluded_backends,
+        })
+    }
+}
+
+/// Formats the title of a classical track as `Composer: Work — Movement`, or returns `None` if it isn't part of a work.
+pub fn classical_title(track: &DispatchableTrack) -> Option<String> {
+    let work = track.work.as_deref()?;
+    let mut title = track.composer.as_deref().map_or_else(|| work.to_owned(), |composer| format!("{composer}: {work}"));
+    if let Some(movement) = &track.movement {
+        title.push_str(" — ");
+        title.push_str(&movement.name);
+    }
+    Some(title)
+}
+
+pub fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Whether a parenthetical or dash-separated annotation only denotes a remaster (e.x. `Remastered 2011`, `2009 Remaster`).
+fn is_remaster_annotation(annotation: &str) -> bool {
+    annotation.split_whitespace().all(|word| {
+        let word = word.to_ascii_lowercase();
+        word.starts_with("remaster") || word == "digital" || word == "version" || word.chars().all(|char| char.is_ascii_digit())
+    }) && annotation.to_ascii_lowercase().contains("remaster")
+}
+
+/// Removes a trailing `(...)`, `[...]`, or ` - ...` annotation if the predicate accepts what's within it.
+fn strip_annotation(text: &str, predicate: impl Fn(&str) -> bool) -> Option<&str> {
+    let trimmed = text.trim_end();
+    for (open, close) in [('(', ')'), ('[', ']')] {
+        if let Some(inner) = trimmed.strip_suffix(close)
+            && let Some(start) = inner.rfind(open)
+            && predicate(inner[start + open.len_utf8()..].trim()) {
+            return Some(inner[..start].trim_end())
+        }
+    }
+    trimmed.rfind(" - ")
+        .filter(|&start| predicate(trimmed[start + " - ".len()..].trim()))
+        .map(|start| trimmed[..start].trim_end())
+}
+
+pub fn strip_title_suffixes(title: &str) -> &str {
+    let mut title = title;
+    while let Some(stripped) = strip_annotation(title, is_remaster_annotation) {
+        if stripped.is_empty() { break }
+        title = stripped;
+    }
+    title
+}
+
+pub fn strip_album_suffixes(album: &str) -> &str {
+    const RELEASE_TYPES: &[&str] = &["Single", "EP"];
+    let mut album = album;
+    while let Some(stripped) = strip_annotation(album, |annotation| is_remaster_annotation(annotation) || RELEASE_TYPES.contains(&annotation)) {
+        if stripped.is_empty() { break }
+        album = stripped;
+    }
+    album
+}
+
+/// Splits a title into the title itself and the artist(s) it credits as featured, if there are any.
+///
+/// Only parenthesized (or bracketed) credits are recognized, since a bare `feat.` can't be reliably told apart from the title.
+pub fn split_featuring(title: &str) -> Option<(&str, &str)> {
+    const MARKERS: &[&str] = &["feat. ", "ft. ", "featuring ", "with "];
+    let trimmed = title.trim_end();
+    for (open, close) in [('(', ')'), ('[', ']')] {
+        let Some(inner) = trimmed.strip_suffix(close) else { continue };
+        let Some(start) = inner.rfind(open) else { continue };
+        let annotation = &inner[start + open.len_utf8()..];
+        for marker in MARKERS {
+            if annotation.len() > marker.len() && annotation.is_char_boundary(marker.len()) && annotation[..marker.len()].eq_ignore_ascii_case(marker) {
+                let featured = annotation[marker.len()..].trim();
+                let title = inner[..start].trim_end();
+                if featured.is_empty() || title.is_empty() { return None }
+                return Some((title, featured))
+            }
+        }
+    }
+    None
+}
+
+/// Adds a featured artist to the artist list, unless they're already credited.
+///
+/// A lone artist is joined with an ampersand (`A & B`); an existing list is extended with a comma (`A & B, C`),
+/// since an ampersand might be part of an artist's name.
+fn add_featured_artist(artist: &str, featured: &str) -> String {
+    if artist.to_lowercase().contains(&featured.to_lowercase()) {
+        artist.to_owned()
+    } else if artist.is_empty() {
+        featured.to_owned()
+    } else if artist.contains(" & ") {
+        format!("{artist}, {featured}")
+    } else {
+        format!("{artist} & {featured}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn title_suffixes() {
+        for (input, expected) in [
+            ("Here Comes the Sun (Remastered 2009)", "Here Comes the Sun"),
+            ("Here Comes the Sun - Remastered 2009", "Here Comes the Sun"),
+            ("Here Comes the Sun [2009 Remaster]", "Here Comes the Sun"),
+            ("Here Comes the Sun (2019 Digital Remaster) - Remastered", "Here Comes the Sun"),
+            ("Here Comes the Sun (Live)", "Here Comes the Sun (Live)"),
+            ("Remastered", "Remastered"),
+            ("(Remastered)", "(Remastered)"),
+            ("Song - Single", "Song - Single"),
+        ] {
+            assert_eq!(strip_title_suffixes(input), expected, "input: {input:?}");
+        }
+    }
+
+    #[test]
+    fn album_suffixes() {
+        for (input, expected) in [
+            ("Mesmerizer - Single", "Mesmerizer"),
+            ("Acoustic Hotel - EP", "Acoustic Hotel"),
+            ("Abbey Road (Remastered)", "Abbey Road"),
+            ("Abbey Road (Super Deluxe Edition)", "Abbey Road (Super Deluxe Edition)"),
+            ("Greatest Hits - Volume 2", "Greatest Hits - Volume 2"),
+        ] {
+            assert_eq!(strip_album_suffixes(input), expected, "input: {input:?}");
+        }
+    }
+
+    #[test]
+    fn featuring() {
+        for (input, expected) in [
+            ("Song (feat. B)", Some(("Song", "B"))),
+            ("Song [ft. B & C]", Some(("Song", "B & C"))),
+            ("Song (Featuring B)", Some(("Song", "B"))),
+            ("Song (with B)", Some(("Song", "B"))),
+            ("Song (Live)", None),
+            ("Song feat. B", None),
+            ("(feat. B)", None),
+            ("Song (feat. )", None),
+        ] {
+            assert_eq!(split_featuring(input), expected, "input: {input:?}");
+        }
+
+        for (artist, featured, expected) in [
+            ("A", "B", "A & B"),
+            ("A & B", "C", "A & B, C"),
+            ("A & B", "b", "A & B"),
+            ("", "B", "B"),
+        ] {
+            assert_eq!(add_featured_artist(artist, featured), expected);
+        }
+    }
+
+    #[test]
+    fn classical() {
+        let track = DispatchableTrack::from(player_events::Track {
+            name: "Symphony No. 5 in C Minor, Op. 67: I. Allegro con brio".to_owned(),
+            album: Some("Beethoven: Symphonies Nos. 5 & 7".to_owned()),
+            album_artist: Some("Wiener Philharmoniker & Carlos Kleiber".to_owned()),
+            artist: Some("Wiener Philharmoniker & Carlos Kleiber".to_owned()),
+            persistent_id: crate::store::types::StoredPersistentId::from(1),
+            duration: None,
+            media_kind: player_events::MediaKind::Song,
+            track_number: None,
+            apple_music_url: None,
+            genre: Some("Classical".to_owned()),
+            composer: Some("Ludwig van Beethoven".to_owned()),
+            work: Some("Symphony No. 5 in C Minor, Op. 67".to_owned()),
+            movement: Some(player_events::MovementInfo { name: "I. Allegro con brio".to_owned(), index: 1 }),
+        });
+        assert_eq!(classical_title(&track).as_deref(), Some("Ludwig van Beethoven: Symphony No. 5 in C Minor, Op. 67 — I. Allegro con brio"));
+
+        let mut untitled = track.clone();
+        untitled.composer = None;
+        untitled.movement = None;
+        assert_eq!(classical_title(&untitled).as_deref(), Some("Symphony No. 5 in C Minor, Op. 67"));
+
+        let mut popular = track;
+        popular.work = None;
+        assert_eq!(classical_title(&popular), None);
+    }
+
+    #[test]
+    fn whitespace() {
+        for (input, expected) in [
+            ("  Song   Title ", "Song Title"),
+            ("Song\tTitle\n", "Song Title"),
+            ("Song", "Song"),
+        ] {
+            assert_eq!(collapse_whitespace(input), expected);
+        }
+    }
+}
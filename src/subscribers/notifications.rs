@@ -0,0 +1,152 @@
+//! Posts a user notification whenever a new track starts.
+//!
+//! Notifications are posted through `osascript`'s `display notification`, since posting through
+//! `UNUserNotificationCenter` requires a signed application bundle. That means there's no way to
+//! attach the artwork as a thumbnail; the notification will carry the icon of whatever posted it.
+use super::{error::dispatch::DispatchError, subscribe, DispatchableTrack};
+
+/// A time of day, in minutes since midnight; (de)serialized as `HH:MM`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct TimeOfDay(u16);
+impl TimeOfDay {
+    pub fn new(hour: u8, minute: u8) -> Option<Self> {
+        if hour < 24 && minute < 60 {
+            Some(Self(u16::from(hour) * 60 + u16::from(minute)))
+        } else { None }
+    }
+
+    pub fn now() -> Self {
+        use chrono::Timelike;
+        let now = chrono::Local::now();
+        #[expect(clippy::cast_possible_truncation, reason = "hours and minutes are always in range")]
+        Self::new(now.hour() as u8, now.minute() as u8).expect("clock gave an out-of-range time")
+    }
+}
+impl core::str::FromStr for TimeOfDay {
+    type Err = &'static str;
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (hour, minute) = value.split_once(':').ok_or("time must be in the form HH:MM")?;
+        let hour = hour.parse().map_err(|_| "hour is not a number")?;
+        let minute = minute.parse().map_err(|_| "minute is not a number")?;
+        Self::new(hour, minute).ok_or("time is out of range")
+    }
+}
+impl TryFrom<String> for TimeOfDay {
+    type Error = &'static str;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+impl From<TimeOfDay> for String {
+    fn from(value: TimeOfDay) -> Self {
+        format!("{:02}:{:02}", value.0 / 60, value.0 % 60)
+    }
+}
+
+/// A span of the day in which no notifications are posted.
+/// The end may be before the start, in which case the span wraps around midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct QuietHours {
+    pub start: TimeOfDay,
+    pub end: TimeOfDay,
+}
+impl QuietHours {
+    pub fn contains(self, time: TimeOfDay) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            self.start <= time || time < self.end
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, Default)]
+pub struct Config {
+    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quiet_hours: Option<QuietHours>,
+    /// Tracks shorter than this many seconds (or of unknown length) won't cause a notification.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_duration: Option<u32>,
+}
+
+super::subscription::define_subscriber!(#[derive(Debug)] pub Notifications, {
+    config: Config,
+});
+impl Notifications {
+    pub const fn new(config: Config) -> Self {
+        Self { config }
+    }
+
+    fn should_notify(&self, track: &DispatchableTrack) -> bool {
+        if let Some(minimum) = self.config.min_duration {
+            let long_enough = track.duration.is_some_and(|duration| duration.as_secs() >= u64::from(minimum));
+            if !long_enough { return false }
+        }
+        !self.config.quiet_hours.is_some_and(|quiet| quiet.contains(TimeOfDay::now()))
+    }
+
+    async fn notify(title: &str, body: &str, subtitle: &str) -> Result<(), DispatchError> {
+        // The text is passed as arguments rather than interpolated into the script, so it needn't be escaped.
+        let output = tokio::process::Command::new("osascript")
+            .args(["-e", "on run argv", "-e", "display notification (item 2 of argv) with title (item 1 of argv) subtitle (item 3 of argv)", "-e", "end run"])
+            .args([title, body, subtitle])
+            .output()
+            .await
+            .map_err(|error| {
+                tracing::error!(?error, "failed to run osascript");
+                DispatchError::internal_msg("failed to run osascript", false)
+            })?;
+
+        if !output.status.success() {
+            tracing::error!(stderr = %String::from_utf8_lossy(&output.stderr), "failed to post notification");
+            return Err(DispatchError::internal_msg("osascript failed to post notification", false));
+        }
+        Ok(())
+    }
+}
+
+subscribe!(Notifications, TrackStarted, {
+    async fn dispatch(&mut self, context: super::BackendContext<crate::data_fetching::AdditionalTrackData>) -> Result<(), DispatchError> {
+        let track = &context.track;
+        if !self.should_notify(track) {
+            return Ok(())
+        }
+        let (title, body, subtitle) = (track.name.as_str(), track.artist.as_deref().unwrap_or_default(), track.album.as_deref().unwrap_or_default());
+        if super::intercept_dry_run(Self::NAME, "post notification", &(title, body, subtitle)) { return Ok(()) }
+        Self::notify(title, body, subtitle).await
+    }
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(value: &str) -> TimeOfDay {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn quiet_hours() {
+        let overnight = QuietHours { start: time("22:00"), end: time("07:30") };
+        assert!(overnight.contains(time("23:59")));
+        assert!(overnight.contains(time("00:00")));
+        assert!(overnight.contains(time("07:29")));
+        assert!(!overnight.contains(time("07:30")));
+        assert!(!overnight.contains(time("12:00")));
+
+        let daytime = QuietHours { start: time("09:00"), end: time("17:00") };
+        assert!(daytime.contains(time("09:00")));
+        assert!(!daytime.contains(time("17:00")));
+        assert!(!daytime.contains(time("08:59")));
+    }
+
+    #[test]
+    fn time_of_day() {
+        assert_eq!(String::from(time("7:05")), "07:05");
+        assert!("24:00".parse::<TimeOfDay>().is_err());
+        assert!("12:60".parse::<TimeOfDay>().is_err());
+        assert!("noon".parse::<TimeOfDay>().is_err());
+    }
+}
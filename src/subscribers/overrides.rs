@@ -0,0 +1,141 @@
+//! User-defined corrections for chronic metadata problems in their library, like an artist who's always
+//! credited under an old name, or an album that should never show up on their Discord profile.
+//!
+//! ```toml
+//! [[overrides]]
+//! match = { artist = "Old Name" }
+//! artist = "New Name"
+//!
+//! [[overrides]]
+//! match = { album = "Embarrassing Album" }
+//! exclude = ["discord"]
+//! ```
+use super::{BackendIdentity, DispatchableTrack};
+
+/// Which tracks an [`Override`] applies to.
+///
+/// Every field that's given must be equal (ignoring case) to that of the track; a matcher without any fields matches nothing.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Matcher {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub artist: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub album: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub album_artist: Option<String>,
+}
+impl Matcher {
+    pub fn matches(&self, track: &DispatchableTrack) -> bool {
+        let field = |expected: &Option<String>, actual: &Option<String>| match (expected, actual) {
+            (None, _) => true,
+            (Some(expected), Some(actual)) => expected.trim().eq_ignore_ascii_case(actual.trim()),
+            (Some(_), None) => false,
+        };
+        *self != Self::default()
+            && field(&self.artist, &track.artist)
+            && field(&self.album, &track.album)
+            && field(&self.album_artist, &track.album_artist)
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Override {
+    #[serde(rename = "match")]
+    pub matcher: Matcher,
+    /// What to replace the artist with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub artist: Option<String>,
+    /// What to replace the album with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub album: Option<String>,
+    /// What to replace the album artist with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub album_artist: Option<String>,
+    /// The backends which matching tracks should never be sent to, by their configuration key (e.x. `discord`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+}
+
+/// Applies every override which matches the track.
+///
+/// Overrides are matched against the track as it was read, so one override's replacements can't cause another to match.
+pub fn apply(overrides: &[Override], track: &mut DispatchableTrack) {
+    let matching = overrides.iter().filter(|rule| rule.matcher.matches(track)).collect::<Vec<_>>();
+    for rule in matching {
+        tracing::debug!(?rule, persistent_id = %track.persistent_id, "applying override");
+        if let Some(artist) = &rule.artist { track.artist = Some(artist.clone()); }
+        if let Some(album) = &rule.album { track.album = Some(album.clone()); }
+        if let Some(album_artist) = &rule.album_artist { track.album_artist = Some(album_artist.clone()); }
+        for key in &rule.exclude {
+            if let Some(identity) = BackendIdentity::from_key(key) {
+                track.excluded_backends.insert(identity);
+            } else {
+                tracing::warn!(key, "override excludes an unknown backend");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track() -> DispatchableTrack {
+        DispatchableTrack::from(player_events::Track {
+            name: "Snorkel".to_owned(),
+            album: Some("Acoustic Hotel".to_owned()),
+            album_artist: Some("Lumpy".to_owned()),
+            artist: Some("Lumpy".to_owned()),
+            persistent_id: crate::store::types::StoredPersistentId::from(1),
+            duration: None,
+            media_kind: player_events::MediaKind::Song,
+            track_number: None,
+            apple_music_url: None,
+            genre: None,
+            composer: None,
+            work: None,
+            movement: None,
+        })
+    }
+
+    #[test]
+    fn matching() {
+        let track = track();
+        for (matcher, expected) in [
+            (Matcher { artist: Some("lumpy".to_owned()), ..Default::default() }, true),
+            (Matcher { artist: Some("Lumpy".to_owned()), album: Some("Acoustic Hotel ".to_owned()), ..Default::default() }, true),
+            (Matcher { artist: Some("Lumpy".to_owned()), album: Some("Other".to_owned()), ..Default::default() }, false),
+            (Matcher::default(), false),
+        ] {
+            assert_eq!(matcher.matches(&track), expected, "matcher: {matcher:?}");
+        }
+    }
+
+    #[test]
+    fn application() {
+        let overrides = [
+            Override {
+                matcher: Matcher { artist: Some("Lumpy".to_owned()), ..Default::default() },
+                artist: Some("Lumpy & The Dumpers".to_owned()),
+                ..Default::default()
+            },
+            // Matched against the original artist, so this still applies.
+            Override {
+                matcher: Matcher { artist: Some("Lumpy".to_owned()), album: Some("Acoustic Hotel".to_owned()), ..Default::default() },
+                album: Some("Acoustic Hotel (Deluxe)".to_owned()),
+                ..Default::default()
+            },
+            Override {
+                matcher: Matcher { artist: Some("Lumpy & The Dumpers".to_owned()), ..Default::default() },
+                album_artist: Some("Nobody".to_owned()),
+                ..Default::default()
+            },
+        ];
+
+        let mut track = track();
+        apply(&overrides, &mut track);
+        assert_eq!(track.artist.as_deref(), Some("Lumpy & The Dumpers"));
+        assert_eq!(track.album.as_deref(), Some("Acoustic Hotel (Deluxe)"));
+        assert_eq!(track.album_artist.as_deref(), Some("Lumpy"));
+    }
+}
@@ -0,0 +1,72 @@
+//! How many dispatches each backend has been given over the lifetime of the process, and how many of them failed.
+//!
+//! These are kept apart from the [backends](super::Backends) themselves so that they survive the backends being recreated when the configuration is reloaded.
+//! They're stored with the [session](crate::store::entities::Session) once it finishes, and can be asked for over IPC while it's running.
+use std::{collections::BTreeMap, sync::Mutex};
+
+use super::{BackendIdentity, BackendMap};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct DispatchCounts {
+    /// How many dispatches the backend handled, successfully or not.
+    pub dispatched: u64,
+    /// How many of those resulted in an error.
+    pub failed: u64,
+}
+
+/// The counts of each backend, keyed by the [configuration key](BackendIdentity::get_key) of the backend.
+pub type Summary = BTreeMap<String, DispatchCounts>;
+
+/// The counts of every backend.
+struct Counts(Mutex<BackendMap<DispatchCounts>>);
+impl Counts {
+    const fn new() -> Self {
+        Self(Mutex::new(BackendMap::new()))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BackendMap<DispatchCounts>> {
+        // the counts are always left consistent, so one panicking while holding the lock is harmless
+        self.0.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn record(&self, identity: BackendIdentity, failed: bool) {
+        let mut all = self.lock();
+        let counts = all[identity].get_or_insert_default();
+        counts.dispatched += 1;
+        counts.failed += u64::from(failed);
+        drop(all);
+    }
+
+    fn summarize(&self) -> Summary {
+        self.lock().iter()
+            .filter_map(|(identity, counts)| counts.map(|counts| (identity.get_key().to_owned(), counts)))
+            .collect()
+    }
+}
+
+static COUNTS: Counts = Counts::new();
+
+/// Counts a dispatch to the backend.
+pub fn record(identity: BackendIdentity, failed: bool) {
+    COUNTS.record(identity, failed);
+}
+
+/// The counts of every backend which has been dispatched to.
+pub fn summarize() -> Summary {
+    COUNTS.summarize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Counted apart from those of the process, which every test dispatching to backends adds to.
+    #[test]
+    fn recording() {
+        let counts = Counts::new();
+        assert!(counts.summarize().is_empty());
+        counts.record(BackendIdentity::Mock, false);
+        counts.record(BackendIdentity::Mock, true);
+        assert_eq!(counts.summarize(), Summary::from([("mock".to_owned(), DispatchCounts { dispatched: 2, failed: 1 })]));
+    }
+}
@@ -0,0 +1,92 @@
+//! When a listen submitted to a scrobbling service is said to have happened.
+//!
+//! This only changes what's submitted; the segments of each listen are recorded in the history as they were regardless.
+use chrono::TimeDelta;
+
+use crate::listened::Listened;
+use super::DeferredListen;
+
+type DateTime = chrono::DateTime<chrono::Utc>;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampPolicy {
+    /// When the track started being listened to.
+    #[default]
+    Start,
+    /// When the track had been listened to for long enough to be submitted.
+    Threshold,
+    /// When the track stopped being listened to.
+    End,
+}
+impl TimestampPolicy {
+    #[allow(clippy::trivially_copy_pass_by_ref, reason = "serde passes it by reference")]
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The timestamp of a listen which has just ended, which had to be listened to for the `threshold` to be submitted.
+    ///
+    /// Returns `None` if nothing was listened to at all.
+    pub fn of_ended(self, listened: &Listened, threshold: TimeDelta) -> Option<DateTime> {
+        match self {
+            Self::Start => listened.started_at(),
+            Self::Threshold => listened.reached_at(threshold).or_else(|| listened.started_at()),
+            Self::End => listened.started_at().map(|_| chrono::Utc::now()),
+        }
+    }
+
+    /// The timestamp of a listen which couldn't be submitted when it ended.
+    ///
+    /// Only when it started and for how long are held on to, so it's assumed to have been listened to without pausing.
+    pub fn of_deferred(self, listen: &DeferredListen, threshold: TimeDelta) -> DateTime {
+        match self {
+            Self::Start => listen.started_at,
+            Self::Threshold => listen.started_at + threshold.min(listen.listened),
+            Self::End => listen.started_at + listen.listened,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::sync::Arc;
+
+    use super::*;
+
+    #[test]
+    fn deferred() {
+        let started_at = DateTime::from_timestamp(1_700_000_000, 0).expect("timestamp in range");
+        let listen = DeferredListen {
+            track: Arc::new(crate::subscribers::DispatchableTrack::from(player_events::Track {
+                name: "Something".to_owned(),
+                album: None,
+                album_artist: None,
+                artist: None,
+                persistent_id: crate::store::types::StoredPersistentId::new(1),
+                duration: Some(core::time::Duration::from_mins(5)),
+                media_kind: player_events::MediaKind::Song,
+                track_number: None,
+                apple_music_url: None,
+                genre: None,
+                composer: None,
+                work: None,
+                movement: None,
+            })),
+            player_version: Arc::from("1.0.0.0"),
+            started_at,
+            listened: TimeDelta::seconds(200),
+            journaled: None,
+        };
+        assert_eq!(TimestampPolicy::Start.of_deferred(&listen, TimeDelta::seconds(120)), started_at);
+        assert_eq!(TimestampPolicy::Threshold.of_deferred(&listen, TimeDelta::seconds(120)), started_at + TimeDelta::seconds(120));
+        assert_eq!(TimestampPolicy::Threshold.of_deferred(&listen, TimeDelta::seconds(240)), started_at + TimeDelta::seconds(200));
+        assert_eq!(TimestampPolicy::End.of_deferred(&listen, TimeDelta::seconds(120)), started_at + TimeDelta::seconds(200));
+    }
+
+    #[test]
+    fn serialization() {
+        assert_eq!(serde_json::to_string(&TimestampPolicy::Threshold).unwrap(), r#""threshold""#);
+        assert_eq!(serde_json::from_str::<TimestampPolicy>(r#""end""#).unwrap(), TimestampPolicy::End);
+    }
+}
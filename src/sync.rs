@@ -0,0 +1,272 @@
+//! Sharing listen history between the daemons running on multiple machines, so that statistics cover all of them.
+//!
+//! One daemon acts as the hub, serving its store over the [HTTP server](crate::http); being bound to the loopback interface,
+//! it has to be exposed to the other machines over HTTPS through something like a reverse proxy. Every other daemon
+//! periodically pushes the listens it recorded to the hub, and pulls those which the hub received from everyone else.
+//!
+//! Listens of the same [canonical track](crate::store::entities::CanonicalTrack) which overlap in time are assumed to be
+//! the same listen recorded by more than one machine; only the longest of them is counted.
+use core::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::store::{MaybeStaticSqlError, entities::{CanonicalTrack, FromKey, Listen, SyncCursor}, types::StoredPersistentId};
+
+/// The most listens sent in a single request.
+pub const BATCH_SIZE: u32 = 250;
+
+/// The header naming the machine that a request to the hub was made by.
+pub const MACHINE_HEADER: &str = "X-Machine";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Config {
+    pub enabled: bool,
+    /// The name that listens recorded on this machine are attributed to; it must be unique among those being synced.
+    #[serde(default = "default_machine")]
+    pub machine: String,
+    /// The base URL of the hub to sync with, e.x. `https://music.example.com`.
+    /// If absent, this machine is the hub.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hub: Option<String>,
+    /// A secret shared by every synced machine, which the hub requires be presented.
+    pub token: String,
+    /// How often to sync with the hub, in seconds.
+    pub interval: u64,
+}
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            machine: default_machine(),
+            hub: None,
+            token: String::new(),
+            interval: 300,
+        }
+    }
+}
+
+fn default_machine() -> String {
+    crate::util::get_hostname().unwrap_or_else(|| "unknown".to_owned())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("store failure: {0}")]
+    Store(#[from] MaybeStaticSqlError),
+    #[error("network failure: {0}")]
+    Network(#[from] reqwest::Error),
+    #[error("hub responded with {0}")]
+    Status(reqwest::StatusCode),
+    #[error("malformed response: {0}")]
+    Malformed(#[from] serde_json::Error),
+}
+impl From<sqlx::Error> for Error {
+    fn from(error: sqlx::Error) -> Self {
+        Self::Store(error.into())
+    }
+}
+
+/// A listen as it's sent between machines.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SyncedListen {
+    /// The name of the machine the listen was recorded on.
+    pub origin: String,
+    /// The ID of the listen on the machine it was recorded on.
+    pub id: i64,
+    pub persistent_id: StoredPersistentId,
+    /// Unix epoch, milliseconds; the start of the earliest segment.
+    pub started_at: i64,
+    /// `None` if the canonical track of the listen wasn't resolved.
+    pub track: Option<SyncedTrack>,
+    pub segments: Vec<SyncedSegment>,
+}
+impl SyncedListen {
+    /// Gathers everything about a stored listen, where `here` is the name of this machine.
+    pub async fn load(pool: &sqlx::SqlitePool, listen: &Listen, here: &str) -> Result<Self, MaybeStaticSqlError> {
+        let track = match listen.canonical {
+            Some(canonical) => Some(CanonicalTrack::get_in_pool(canonical, pool).await?.into()),
+            None => None
+        };
+        let segments = listen.segments(pool).await?.into_iter().map(|segment| SyncedSegment {
+            started_at: segment.started_at.0.timestamp_millis(),
+            duration: segment.duration,
+            started_at_position: segment.started_at_position,
+            expected_end_position: segment.expected_end_position,
+            actual_end_position: segment.actual_end_position,
+        }).collect();
+
+        Ok(Self {
+            origin: listen.origin.clone().unwrap_or_else(|| here.to_owned()),
+            id: listen.origin_id.unwrap_or_else(|| listen.id().get()),
+            persistent_id: listen.persistent_id,
+            started_at: listen.started_at.0.timestamp_millis(),
+            track,
+            segments,
+        })
+    }
+}
+
+/// The identity of a [canonical track](CanonicalTrack), already normalized.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SyncedTrack {
+    pub artist: String,
+    pub title: String,
+    /// In milliseconds.
+    pub duration: Option<i64>,
+}
+impl From<CanonicalTrack> for SyncedTrack {
+    fn from(track: CanonicalTrack) -> Self {
+        Self { artist: track.artist, title: track.title, duration: track.duration }
+    }
+}
+impl From<&SyncedTrack> for crate::store::canonical::CanonicalKey {
+    fn from(track: &SyncedTrack) -> Self {
+        Self {
+            artist: track.artist.clone(),
+            title: track.title.clone(),
+            duration: track.duration.map(|duration| Duration::from_millis(duration.try_into().unwrap_or_default())),
+        }
+    }
+}
+
+/// A [segment](crate::store::entities::ListenSegment) of a listen as it's sent between machines.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SyncedSegment {
+    /// Unix epoch, milliseconds.
+    pub started_at: i64,
+    /// In milliseconds.
+    pub duration: i64,
+    /// In seconds.
+    pub started_at_position: f64,
+    /// In seconds.
+    pub expected_end_position: f64,
+    /// In seconds.
+    pub actual_end_position: Option<f64>,
+}
+
+/// What the hub responds with when listens are pulled from it.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Pulled {
+    pub listens: Vec<SyncedListen>,
+    /// The ID of the last of the listens on the hub, to resume from.
+    pub cursor: i64,
+}
+
+/// Serves a pull of the listens that a machine doesn't have yet; for the hub.
+pub async fn pull(pool: &sqlx::SqlitePool, config: &Config, machine: &str, after: i64) -> Result<Pulled, MaybeStaticSqlError> {
+    let stored = Listen::get_not_from_after(pool, &config.machine, machine, after, BATCH_SIZE).await?;
+    let cursor = stored.last().map_or(after, |listen| listen.id().get());
+    let mut listens = Vec::with_capacity(stored.len());
+    for listen in &stored {
+        listens.push(SyncedListen::load(pool, listen, &config.machine).await?);
+    }
+    Ok(Pulled { listens, cursor })
+}
+
+/// Stores the listens that a machine pushed; for the hub.
+///
+/// Returns the number of listens which weren't already stored.
+pub async fn receive(pool: &sqlx::SqlitePool, config: &Config, listens: &[SyncedListen]) -> sqlx::Result<usize> {
+    let mut stored = 0;
+    for listen in listens {
+        if listen.origin == config.machine {
+            tracing::warn!(origin = %listen.origin, "received a listen attributed to the hub itself; is a machine name reused?");
+            continue
+        }
+        if Listen::insert_synced(pool, listen).await? {
+            stored += 1;
+        }
+    }
+    Ok(stored)
+}
+
+struct Client<'a> {
+    net: reqwest::Client,
+    pool: sqlx::SqlitePool,
+    config: &'a Config,
+    hub: &'a str,
+}
+impl Client<'_> {
+    fn request(&self, method: reqwest::Method) -> reqwest::RequestBuilder {
+        self.net.request(method, format!("{}/sync/listens", self.hub.trim_end_matches('/')))
+            .bearer_auth(&self.config.token)
+            .header(MACHINE_HEADER, &self.config.machine)
+    }
+
+    /// Returns the number of listens pushed.
+    async fn push(&self) -> Result<usize, Error> {
+        let mut pushed = 0;
+        loop {
+            let cursor = SyncCursor::get(&self.pool, self.hub).await?;
+            let stored = Listen::get_recorded_here_after(&self.pool, cursor.pushed, BATCH_SIZE).await?;
+            let Some(last) = stored.last() else { break };
+
+            let mut listens = Vec::with_capacity(stored.len());
+            for listen in &stored {
+                listens.push(SyncedListen::load(&self.pool, listen, &self.config.machine).await?);
+            }
+
+            let response = self.request(reqwest::Method::POST)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(serde_json::to_vec(&listens)?)
+                .send().await?;
+            if !response.status().is_success() { return Err(Error::Status(response.status())) }
+
+            SyncCursor::set_pushed(&self.pool, self.hub, last.id().get()).await?;
+            pushed += listens.len();
+            if stored.len() < BATCH_SIZE as usize { break }
+        }
+        Ok(pushed)
+    }
+
+    /// Returns the number of listens pulled that weren't already stored.
+    async fn pull(&self) -> Result<usize, Error> {
+        let mut pulled = 0;
+        loop {
+            let cursor = SyncCursor::get(&self.pool, self.hub).await?;
+            let response = self.request(reqwest::Method::GET)
+                .query(&[("after", cursor.pulled)])
+                .send().await?;
+            if !response.status().is_success() { return Err(Error::Status(response.status())) }
+            let response = serde_json::from_slice::<Pulled>(&response.bytes().await?)?;
+
+            for listen in &response.listens {
+                if Listen::insert_synced(&self.pool, listen).await? {
+                    pulled += 1;
+                }
+            }
+
+            SyncCursor::set_pulled(&self.pool, self.hub, response.cursor).await?;
+            if response.listens.len() < BATCH_SIZE as usize { break }
+        }
+        Ok(pulled)
+    }
+}
+
+/// Pushes listens to and pulls listens from the hub.
+async fn sync_once(config: &Config, hub: &str, net: reqwest::Client) -> Result<(), Error> {
+    let pool = crate::store::DB_POOL.get().await.map_err(MaybeStaticSqlError::from)?;
+    let client = Client { net, pool, config, hub };
+    let pushed = client.push().await?;
+    let pulled = client.pull().await?;
+    tracing::debug!(pushed, pulled, "synced listens with hub");
+    Ok(())
+}
+
+/// Periodically syncs with the hub in the background, or does nothing if this machine is the hub.
+pub fn spawn(config: Config) -> Option<tokio::task::AbortHandle> {
+    let hub = config.hub.clone()?;
+    tracing::info!(%hub, machine = %config.machine, "syncing listens with hub");
+
+    Some(tokio::spawn(async move {
+        let net = crate::net::client();
+        let mut interval = tokio::time::interval(Duration::from_secs(config.interval.max(1)));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            if let Err(error) = sync_once(&config, &hub, net.clone()).await {
+                tracing::error!(?error, "failed to sync listens with hub");
+            }
+        }
+    }).abort_handle())
+}
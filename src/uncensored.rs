@@ -0,0 +1,112 @@
+//! Inspecting and correcting the [cache](CachedUncensoredTitle) of uncensored track titles,
+//! for when a lookup gets it wrong and the wrong title would otherwise be used forever.
+use std::process::ExitCode;
+
+use crate::cli::UncensorAction;
+use crate::store::{MaybeStaticSqlError, entities::CachedUncensoredTitle};
+
+async fn apply(pool: &sqlx::SqlitePool, action: &UncensorAction) -> Result<(), MaybeStaticSqlError> {
+    match action {
+        UncensorAction::Show { persistent_id } => {
+            let id = persistent_id.to_hex_upper();
+            let cached = CachedUncensoredTitle::get_by_persistent_id(pool, *persistent_id).await?;
+            if crate::console::is_json() {
+                #[derive(serde::Serialize)]
+                struct Entry {
+                    persistent_id: String,
+                    cached: bool,
+                    uncensored: Option<String>,
+                }
+
+                crate::console::json(&Entry {
+                    persistent_id: id,
+                    cached: cached.is_some(),
+                    uncensored: cached.and_then(|cached| cached.uncensored),
+                });
+                return Ok(());
+            }
+            match cached {
+                Some(CachedUncensoredTitle { uncensored: Some(title), .. }) => crate::console::out!("{title}"),
+                Some(CachedUncensoredTitle { uncensored: None, .. }) => crate::console::out!("{}", crate::i18n::uncensored_not_found(&id)),
+                None => crate::console::out!("{}", crate::i18n::uncensored_not_cached(&id)),
+            }
+        },
+        UncensorAction::Set { persistent_id, title } => {
+            CachedUncensoredTitle::set(pool, *persistent_id, Some(title)).await?;
+            crate::console::note!("{}", crate::i18n::uncensored_set(&persistent_id.to_hex_upper(), title));
+        },
+        UncensorAction::Clear { persistent_id } => {
+            let id = persistent_id.to_hex_upper();
+            if CachedUncensoredTitle::clear(pool, *persistent_id).await? {
+                crate::console::note!("{}", crate::i18n::uncensored_cleared(&id));
+            } else {
+                crate::console::note!("{}", crate::i18n::uncensored_not_cached(&id));
+            }
+        },
+    }
+    Ok(())
+}
+
+pub async fn run(action: &UncensorAction) -> ExitCode {
+    crate::store::migrations::migrate().await;
+    let pool = match crate::store::DB_POOL.get().await {
+        Ok(pool) => pool,
+        Err(error) => {
+            crate::console::error!("{}", crate::i18n::database_unavailable(error));
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match apply(&pool, action).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            crate::console::error!("{}", crate::i18n::uncensored_cache_unavailable(error));
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::{debug::*, types::StoredPersistentId};
+
+    async fn cached(pool: &sqlx::SqlitePool, persistent_id: StoredPersistentId) -> Option<CachedUncensoredTitle> {
+        CachedUncensoredTitle::get_by_persistent_id(pool, persistent_id).await.expect("failed to query cache")
+    }
+
+    #[tokio::test]
+    async fn show_set_and_clear() {
+        mk_test_db!("uncensor-show-set-clear", pool);
+        seed_empty(&pool, true).await;
+        let persistent_id = StoredPersistentId::new(0xABCD);
+
+        apply(&pool, &UncensorAction::Show { persistent_id }).await.expect("failed to show uncached title");
+        assert!(cached(&pool, persistent_id).await.is_none(), "showing shouldn't cache anything");
+
+        apply(&pool, &UncensorAction::Set { persistent_id, title: "Shit Happens".to_owned() }).await.expect("failed to set title");
+        apply(&pool, &UncensorAction::Show { persistent_id }).await.expect("failed to show cached title");
+        assert_eq!(cached(&pool, persistent_id).await.and_then(|cached| cached.uncensored).as_deref(), Some("Shit Happens"));
+
+        apply(&pool, &UncensorAction::Set { persistent_id, title: "Stuff Happens".to_owned() }).await.expect("failed to replace title");
+        assert_eq!(cached(&pool, persistent_id).await.and_then(|cached| cached.uncensored).as_deref(), Some("Stuff Happens"));
+
+        apply(&pool, &UncensorAction::Clear { persistent_id }).await.expect("failed to clear title");
+        assert!(cached(&pool, persistent_id).await.is_none());
+        apply(&pool, &UncensorAction::Clear { persistent_id }).await.expect("clearing nothing should be fine");
+    }
+
+    #[tokio::test]
+    async fn clearing_leaves_other_tracks() {
+        mk_test_db!("uncensor-clear-others", pool);
+        seed_empty(&pool, true).await;
+        let (cleared, kept) = (StoredPersistentId::new(1), StoredPersistentId::new(2));
+        for persistent_id in [cleared, kept] {
+            apply(&pool, &UncensorAction::Set { persistent_id, title: "Fuck".to_owned() }).await.expect("failed to set title");
+        }
+
+        apply(&pool, &UncensorAction::Clear { persistent_id: cleared }).await.expect("failed to clear title");
+        assert!(cached(&pool, cleared).await.is_none());
+        assert!(cached(&pool, kept).await.is_some());
+    }
+}
@@ -60,6 +60,19 @@ pub fn get_installed_physical_memory() -> Option<u64> {
     }
 }
 
+/// The host name of this machine, without any `.local` suffix.
+pub fn get_hostname() -> Option<String> {
+    let mut buffer = [0u8; 256];
+    let ret = unsafe { libc::gethostname(buffer.as_mut_ptr().cast(), buffer.len()) };
+    if ret != 0 {
+        let error = std::io::Error::last_os_error();
+        tracing::error!(%error, "failed to get hostname");
+        return None
+    }
+    let hostname = core::ffi::CStr::from_bytes_until_nul(&buffer).ok()?.to_string_lossy();
+    Some(hostname.strip_suffix(".local").unwrap_or(&hostname).to_owned())
+}
+
 #[allow(unused_macros, reason = "used when all members of a feature-gated enum are disabled")]
 macro_rules! define_empty_set {
     ($ident: ident, $contents: ty) => {
@@ -76,6 +89,9 @@ macro_rules! define_empty_set {
                 true
             }
             pub fn insert(&mut self, _: $contents) {}
+            pub const fn contains(self, _: $contents) -> bool {
+                false
+            }
         }
     }
 }
@@ -86,7 +102,7 @@ pub(crate) use define_empty_set;
 macro_rules! ferror {
     ($($t: tt)*) => {
         {
-            eprintln!($($t)*);
+            $crate::console::error!($($t)*);
             std::process::exit(1)
         }
     }